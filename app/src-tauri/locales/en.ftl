@@ -0,0 +1,44 @@
+# Strings shown to the user by the backend. Every locale has the same ids;
+# English is the fallback for anything missing.
+
+## Errors. { $detail } is the underlying cause, usually from the OS or a
+## library and left untranslated.
+
+error-model-missing = Model '{ $detail }' not found. Please download it first.
+error-tesseract-not-found = Tesseract not found: { $detail }. Please ensure Tesseract is correctly installed.
+error-permission-denied = Permission denied: { $detail }
+error-capture = Screen capture failed: { $detail }
+error-ocr = OCR failed: { $detail }
+error-translation = Translation failed: { $detail }
+error-summarization = Summarization failed: { $detail }
+error-tts = Speech error: { $detail }
+error-download = Download failed: { $detail }
+error-invalid-input = Invalid input: { $detail }
+error-unsupported = Not supported: { $detail }
+error-cancelled = Operation cancelled
+error-io = I/O error: { $detail }
+
+## Notifications
+
+notification-copy = Copy
+notification-open-history = Open history
+notification-scheduled-capture = Screen Inu: scheduled capture
+notification-watch-found = Screen Inu: "{ $pattern }" found
+notification-watch-changed = Screen Inu: watched text changed
+
+## Tray menu
+
+tray-capture = 📸 Capture (Ctrl+Shift+X)
+tray-show = 🐕 Show Window
+tray-pause-live-ocr = ⏸ Pause live OCR
+tray-hover-ocr = 🔍 Hover OCR
+tray-languages = 🌐 Languages
+tray-ocr-languages = OCR languages
+tray-translation-pairs = Translation
+tray-no-models = No models installed
+tray-recent-results = 📋 Recent results
+tray-no-recent-results = No results yet
+tray-quit = ❌ Quit Screen Inu
+tray-tooltip = Screen Inu - OCR Tool 🐕
+tray-tooltip-busy = Screen Inu - working…
+tray-tooltip-error = Screen Inu - failed: { $error }
@@ -0,0 +1,40 @@
+## Errors
+
+error-model-missing = モデル「{ $detail }」が見つかりません。先にダウンロードしてください。
+error-tesseract-not-found = Tesseract が見つかりません: { $detail }。Tesseract が正しくインストールされているか確認してください。
+error-permission-denied = 権限がありません: { $detail }
+error-capture = 画面キャプチャに失敗しました: { $detail }
+error-ocr = OCR に失敗しました: { $detail }
+error-translation = 翻訳に失敗しました: { $detail }
+error-summarization = 要約に失敗しました: { $detail }
+error-tts = 音声エラー: { $detail }
+error-download = ダウンロードに失敗しました: { $detail }
+error-invalid-input = 無効な入力です: { $detail }
+error-unsupported = サポートされていません: { $detail }
+error-cancelled = 操作はキャンセルされました
+error-io = 入出力エラー: { $detail }
+
+## Notifications
+
+notification-copy = コピー
+notification-open-history = 履歴を開く
+notification-scheduled-capture = Screen Inu: 定期キャプチャ
+notification-watch-found = Screen Inu: 「{ $pattern }」が見つかりました
+notification-watch-changed = Screen Inu: 監視中のテキストが変わりました
+
+## Tray menu
+
+tray-capture = 📸 キャプチャ (Ctrl+Shift+X)
+tray-show = 🐕 ウィンドウを表示
+tray-pause-live-ocr = ⏸ ライブ OCR を一時停止
+tray-hover-ocr = 🔍 ホバー OCR
+tray-languages = 🌐 言語
+tray-ocr-languages = OCR 言語
+tray-translation-pairs = 翻訳
+tray-no-models = モデルがインストールされていません
+tray-recent-results = 📋 最近の結果
+tray-no-recent-results = まだ結果はありません
+tray-quit = ❌ Screen Inu を終了
+tray-tooltip = Screen Inu - OCR ツール 🐕
+tray-tooltip-busy = Screen Inu - 処理中…
+tray-tooltip-error = Screen Inu - 失敗: { $error }
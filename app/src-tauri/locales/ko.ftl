@@ -0,0 +1,40 @@
+## Errors
+
+error-model-missing = '{ $detail }' 모델을 찾을 수 없습니다. 먼저 다운로드하세요.
+error-tesseract-not-found = Tesseract를 찾을 수 없습니다: { $detail }. Tesseract가 올바르게 설치되었는지 확인하세요.
+error-permission-denied = 권한이 거부되었습니다: { $detail }
+error-capture = 화면 캡처 실패: { $detail }
+error-ocr = OCR 실패: { $detail }
+error-translation = 번역 실패: { $detail }
+error-summarization = 요약 실패: { $detail }
+error-tts = 음성 오류: { $detail }
+error-download = 다운로드 실패: { $detail }
+error-invalid-input = 잘못된 입력: { $detail }
+error-unsupported = 지원되지 않음: { $detail }
+error-cancelled = 작업이 취소되었습니다
+error-io = 입출력 오류: { $detail }
+
+## Notifications
+
+notification-copy = 복사
+notification-open-history = 기록 열기
+notification-scheduled-capture = Screen Inu: 예약 캡처
+notification-watch-found = Screen Inu: "{ $pattern }" 발견
+notification-watch-changed = Screen Inu: 감시 중인 텍스트가 변경됨
+
+## Tray menu
+
+tray-capture = 📸 캡처 (Ctrl+Shift+X)
+tray-show = 🐕 창 표시
+tray-pause-live-ocr = ⏸ 실시간 OCR 일시 중지
+tray-hover-ocr = 🔍 호버 OCR
+tray-languages = 🌐 언어
+tray-ocr-languages = OCR 언어
+tray-translation-pairs = 번역
+tray-no-models = 설치된 모델 없음
+tray-recent-results = 📋 최근 결과
+tray-no-recent-results = 아직 결과 없음
+tray-quit = ❌ Screen Inu 종료
+tray-tooltip = Screen Inu - OCR 도구 🐕
+tray-tooltip-busy = Screen Inu - 처리 중…
+tray-tooltip-error = Screen Inu - 실패: { $error }
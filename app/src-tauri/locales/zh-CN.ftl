@@ -0,0 +1,40 @@
+## Errors
+
+error-model-missing = 未找到模型“{ $detail }”。请先下载。
+error-tesseract-not-found = 未找到 Tesseract：{ $detail }。请确认 Tesseract 已正确安装。
+error-permission-denied = 权限被拒绝：{ $detail }
+error-capture = 屏幕截图失败：{ $detail }
+error-ocr = OCR 失败：{ $detail }
+error-translation = 翻译失败：{ $detail }
+error-summarization = 摘要失败：{ $detail }
+error-tts = 语音错误：{ $detail }
+error-download = 下载失败：{ $detail }
+error-invalid-input = 无效输入：{ $detail }
+error-unsupported = 不支持：{ $detail }
+error-cancelled = 操作已取消
+error-io = 读写错误：{ $detail }
+
+## Notifications
+
+notification-copy = 复制
+notification-open-history = 打开历史记录
+notification-scheduled-capture = Screen Inu：定时截图
+notification-watch-found = Screen Inu：发现“{ $pattern }”
+notification-watch-changed = Screen Inu：监视的文字已变化
+
+## Tray menu
+
+tray-capture = 📸 截图 (Ctrl+Shift+X)
+tray-show = 🐕 显示窗口
+tray-pause-live-ocr = ⏸ 暂停实时 OCR
+tray-hover-ocr = 🔍 悬停 OCR
+tray-languages = 🌐 语言
+tray-ocr-languages = OCR 语言
+tray-translation-pairs = 翻译
+tray-no-models = 未安装模型
+tray-recent-results = 📋 最近结果
+tray-no-recent-results = 暂无结果
+tray-quit = ❌ 退出 Screen Inu
+tray-tooltip = Screen Inu - OCR 工具 🐕
+tray-tooltip-busy = Screen Inu - 处理中…
+tray-tooltip-error = Screen Inu - 失败：{ $error }
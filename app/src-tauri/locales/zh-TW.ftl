@@ -0,0 +1,40 @@
+## Errors
+
+error-model-missing = 找不到模型「{ $detail }」。請先下載。
+error-tesseract-not-found = 找不到 Tesseract：{ $detail }。請確認 Tesseract 已正確安裝。
+error-permission-denied = 權限遭拒：{ $detail }
+error-capture = 螢幕擷取失敗：{ $detail }
+error-ocr = OCR 失敗：{ $detail }
+error-translation = 翻譯失敗：{ $detail }
+error-summarization = 摘要失敗：{ $detail }
+error-tts = 語音錯誤：{ $detail }
+error-download = 下載失敗：{ $detail }
+error-invalid-input = 無效的輸入：{ $detail }
+error-unsupported = 不支援：{ $detail }
+error-cancelled = 操作已取消
+error-io = 讀寫錯誤：{ $detail }
+
+## Notifications
+
+notification-copy = 複製
+notification-open-history = 開啟歷史紀錄
+notification-scheduled-capture = Screen Inu：排程擷取
+notification-watch-found = Screen Inu：找到「{ $pattern }」
+notification-watch-changed = Screen Inu：監看的文字已變更
+
+## Tray menu
+
+tray-capture = 📸 擷取 (Ctrl+Shift+X)
+tray-show = 🐕 顯示視窗
+tray-pause-live-ocr = ⏸ 暫停即時 OCR
+tray-hover-ocr = 🔍 懸停 OCR
+tray-languages = 🌐 語言
+tray-ocr-languages = OCR 語言
+tray-translation-pairs = 翻譯
+tray-no-models = 未安裝模型
+tray-recent-results = 📋 最近結果
+tray-no-recent-results = 尚無結果
+tray-quit = ❌ 結束 Screen Inu
+tray-tooltip = Screen Inu - OCR 工具 🐕
+tray-tooltip-busy = Screen Inu - 處理中…
+tray-tooltip-error = Screen Inu - 失敗：{ $error }
@@ -0,0 +1,127 @@
+// Native half of the mobile-ocr plugin (src/mobile.rs) for Android.
+// Copy into gen/android/app/src/main/java/com/iml1s/screeninu/ after
+// `tauri android init`, with the ML Kit text recognition dependencies and an
+// image/* ACTION_SEND / ACTION_SEND_MULTIPLE intent filter on the activity.
+
+package com.iml1s.screeninu
+
+import android.app.Activity
+import android.content.Intent
+import android.graphics.BitmapFactory
+import android.net.Uri
+import android.util.Base64
+import android.webkit.WebView
+import app.tauri.annotation.Command
+import app.tauri.annotation.InvokeArg
+import app.tauri.annotation.TauriPlugin
+import app.tauri.plugin.Invoke
+import app.tauri.plugin.JSArray
+import app.tauri.plugin.JSObject
+import app.tauri.plugin.Plugin
+import com.google.mlkit.vision.common.InputImage
+import com.google.mlkit.vision.text.TextRecognition
+import com.google.mlkit.vision.text.TextRecognizerOptionsInterface
+import com.google.mlkit.vision.text.chinese.ChineseTextRecognizerOptions
+import com.google.mlkit.vision.text.japanese.JapaneseTextRecognizerOptions
+import com.google.mlkit.vision.text.korean.KoreanTextRecognizerOptions
+import com.google.mlkit.vision.text.latin.TextRecognizerOptions
+
+@InvokeArg
+class RecognizeArgs {
+    lateinit var image: String
+    var languages: Array<String> = arrayOf()
+}
+
+@TauriPlugin
+class MobileOcrPlugin(private val activity: Activity) : Plugin(activity) {
+    // Shared images (base64) until the app takes them
+    private val shared = mutableListOf<String>()
+
+    override fun load(webView: WebView) {
+        collectShared(activity.intent)
+    }
+
+    override fun onNewIntent(intent: Intent) {
+        collectShared(intent)
+    }
+
+    @Suppress("DEPRECATION")
+    private fun collectShared(intent: Intent?) {
+        if (intent == null || !intent.type.orEmpty().startsWith("image/")) return
+        val uris: List<Uri> = when (intent.action) {
+            Intent.ACTION_SEND -> listOfNotNull(intent.getParcelableExtra(Intent.EXTRA_STREAM))
+            Intent.ACTION_SEND_MULTIPLE -> intent.getParcelableArrayListExtra<Uri>(Intent.EXTRA_STREAM).orEmpty()
+            else -> emptyList()
+        }
+        for (uri in uris) {
+            activity.contentResolver.openInputStream(uri)?.use {
+                shared.add(Base64.encodeToString(it.readBytes(), Base64.NO_WRAP))
+            }
+        }
+        if (uris.isNotEmpty()) trigger("shared", JSObject())
+    }
+
+    @Command
+    fun takeSharedImages(invoke: Invoke) {
+        val images = JSArray()
+        for (data in shared) {
+            images.put(JSObject().apply { put("data", data) })
+        }
+        shared.clear()
+        invoke.resolve(JSObject().apply { put("images", images) })
+    }
+
+    @Command
+    fun recognizeText(invoke: Invoke) {
+        val args = invoke.parseArgs(RecognizeArgs::class.java)
+        val bytes = Base64.decode(args.image, Base64.DEFAULT)
+        val bitmap = BitmapFactory.decodeByteArray(bytes, 0, bytes.size)
+        if (bitmap == null) {
+            invoke.reject("Invalid image")
+            return
+        }
+        val recognizer = TextRecognition.getClient(options(args.languages))
+        recognizer.process(InputImage.fromBitmap(bitmap, 0))
+            .addOnSuccessListener { text ->
+                val words = JSArray()
+                var line = 0
+                text.textBlocks.forEachIndexed { block, textBlock ->
+                    for (textLine in textBlock.lines) {
+                        for (element in textLine.elements) {
+                            val box = element.boundingBox ?: continue
+                            words.put(JSObject().apply {
+                                put("text", element.text)
+                                // Reported from ML Kit 16.0.1; 0 before
+                                if (element.confidence > 0f) put("confidence", element.confidence * 100.0)
+                                put("x", box.left)
+                                put("y", box.top)
+                                put("width", box.width())
+                                put("height", box.height())
+                                put("block", block)
+                                put("line", line)
+                            })
+                        }
+                        line++
+                    }
+                }
+                invoke.resolve(JSObject().apply {
+                    put("engine", "mlkit")
+                    put("words", words)
+                })
+            }
+            .addOnFailureListener { e -> invoke.reject(e.message ?: "Text recognition failed") }
+            .addOnCompleteListener { recognizer.close() }
+    }
+
+    // ML Kit has one recognizer per script; the first CJK language picks it
+    private fun options(languages: Array<String>): TextRecognizerOptionsInterface {
+        for (lang in languages) {
+            when {
+                lang.startsWith("ja") -> return JapaneseTextRecognizerOptions.Builder().build()
+                lang.startsWith("zh") -> return ChineseTextRecognizerOptions.Builder().build()
+                lang.startsWith("ko") -> return KoreanTextRecognizerOptions.Builder().build()
+            }
+        }
+        return TextRecognizerOptions.DEFAULT_OPTIONS
+    }
+}
@@ -0,0 +1,91 @@
+// Native half of the mobile-ocr plugin (src/mobile.rs) for iOS.
+// Add to the app target in gen/apple after `tauri ios init`. Shared images
+// come from a share extension that writes them to the app group container's
+// "Shared" folder.
+
+import Foundation
+import Tauri
+import UIKit
+import Vision
+
+let appGroup = "group.com.iml1s.screeninu"
+
+class RecognizeArgs: Decodable {
+    let image: String
+    let languages: [String]
+}
+
+class MobileOcrPlugin: Plugin {
+    @objc public func takeSharedImages(_ invoke: Invoke) throws {
+        var images: [[String: String]] = []
+        let fileManager = FileManager.default
+        if let folder = fileManager.containerURL(forSecurityApplicationGroupIdentifier: appGroup)?
+            .appendingPathComponent("Shared"),
+            let files = try? fileManager.contentsOfDirectory(at: folder, includingPropertiesForKeys: nil)
+        {
+            for file in files.sorted(by: { $0.lastPathComponent < $1.lastPathComponent }) {
+                if let data = try? Data(contentsOf: file) {
+                    images.append(["data": data.base64EncodedString()])
+                }
+                try? fileManager.removeItem(at: file)
+            }
+        }
+        invoke.resolve(["images": images])
+    }
+
+    @objc public func recognizeText(_ invoke: Invoke) throws {
+        let args = try invoke.parseArgs(RecognizeArgs.self)
+        guard let data = Data(base64Encoded: args.image), let image = UIImage(data: data)?.cgImage else {
+            invoke.reject("Invalid image")
+            return
+        }
+        let width = Double(image.width)
+        let height = Double(image.height)
+
+        let request = VNRecognizeTextRequest { request, error in
+            if let error = error {
+                invoke.reject(error.localizedDescription)
+                return
+            }
+            let observations = request.results as? [VNRecognizedTextObservation] ?? []
+            var words: [[String: Any]] = []
+            for (line, observation) in observations.enumerated() {
+                guard let candidate = observation.topCandidates(1).first else { continue }
+                // Normalized, with the origin at the bottom left
+                let box = observation.boundingBox
+                words.append([
+                    "text": candidate.string,
+                    "confidence": Double(candidate.confidence) * 100,
+                    "x": Double(box.minX) * width,
+                    "y": Double(1 - box.maxY) * height,
+                    "width": Double(box.width) * width,
+                    "height": Double(box.height) * height,
+                    "block": 0,
+                    "line": line,
+                ])
+            }
+            invoke.resolve(["engine": "vision", "words": words])
+        }
+        request.recognitionLevel = .accurate
+        request.usesLanguageCorrection = true
+        // Vision wants its own tags ("ja-JP"); match the requested ones by prefix
+        let supported = (try? request.supportedRecognitionLanguages()) ?? []
+        let languages = args.languages.compactMap { lang in supported.first { $0.hasPrefix(lang) } }
+        if !languages.isEmpty {
+            request.recognitionLanguages = languages
+        }
+
+        DispatchQueue.global(qos: .userInitiated).async {
+            do {
+                try VNImageRequestHandler(cgImage: image).perform([request])
+            } catch {
+                invoke.reject(error.localizedDescription)
+            }
+        }
+    }
+}
+
+@_cdecl("init_plugin_mobile_ocr")
+func initPlugin() -> Plugin {
+    return MobileOcrPlugin()
+}
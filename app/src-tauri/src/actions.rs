@@ -0,0 +1,148 @@
+// Post-OCR actions
+// What happens with recognized text after a capture: copy it to the clipboard,
+// type it into the previously focused window, append it to a file, or forward
+// it to a chat target (`chat`). Copied, appended and forwarded text goes
+// through the active output template (`templates`).
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::{AppError, AppResult};
+
+/// A single action run on OCR output
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PostOcrAction {
+    /// Copy the text to the clipboard
+    Copy,
+    /// Type the text into the window that had focus before the capture
+    Paste,
+    /// Append the text (plus a newline) to a file
+    AppendToFile { path: String },
+    /// Post the text, and the capture if the target takes images, to a chat
+    Forward { target: String },
+}
+
+/// Post-OCR action configuration
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActionsConfig {
+    /// Actions run when no binding-specific list exists
+    pub default_actions: Vec<PostOcrAction>,
+    /// Actions per hotkey binding id (e.g. "capture", "capture-and-paste")
+    pub bindings: HashMap<String, Vec<PostOcrAction>>,
+}
+
+impl Default for ActionsConfig {
+    fn default() -> Self {
+        Self {
+            default_actions: vec![PostOcrAction::Copy],
+            bindings: HashMap::new(),
+        }
+    }
+}
+
+impl ActionsConfig {
+    /// Actions for a binding, falling back to the defaults
+    pub fn actions_for(&self, binding: Option<&str>) -> &[PostOcrAction] {
+        binding
+            .and_then(|b| self.bindings.get(b))
+            .unwrap_or(&self.default_actions)
+    }
+}
+
+/// Outcome of one action, reported back to the frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ActionOutcome {
+    pub action: PostOcrAction,
+    pub error: Option<String>,
+}
+
+pub(crate) fn copy_to_clipboard<R: Runtime>(app: &AppHandle<R>, text: &str) -> AppResult<()> {
+    app.clipboard()
+        .write_text(text.to_string())
+        .map_err(|e| AppError::Other(format!("Failed to write clipboard: {}", e)))
+}
+
+fn type_text<R: Runtime>(app: &AppHandle<R>, text: &str) -> AppResult<()> {
+    use enigo::{Enigo, Keyboard, Settings};
+
+    // Give focus back to the window the user was working in
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+            std::thread::sleep(std::time::Duration::from_millis(150));
+        }
+    }
+
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| AppError::PermissionDenied(format!("Cannot send keystrokes: {}", e)))?;
+    enigo
+        .text(text)
+        .map_err(|e| AppError::Other(format!("Failed to type text: {}", e)))
+}
+
+fn append_to_file(path: &str, text: &str) -> AppResult<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", text)?;
+    Ok(())
+}
+
+fn forward(target: &str, text: &str, image: Option<&[u8]>) -> AppResult<()> {
+    let config = crate::config::get()?.chat;
+    crate::chat::forward(config.target(target)?, text, image)
+}
+
+/// Run a list of actions on the text (`image` is the capture it came from).
+/// Every action is attempted even if an earlier one fails.
+pub fn run_actions<R: Runtime>(
+    app: &AppHandle<R>,
+    text: &str,
+    image: Option<&[u8]>,
+    actions: &[PostOcrAction],
+) -> Vec<ActionOutcome> {
+    actions
+        .iter()
+        .map(|action| {
+            let formatted = || crate::templates::apply(text, &Default::default());
+            let result = match action {
+                PostOcrAction::Copy => formatted().and_then(|text| copy_to_clipboard(app, &text)),
+                PostOcrAction::Paste => type_text(app, text),
+                PostOcrAction::AppendToFile { path } => formatted().and_then(|text| append_to_file(path, &text)),
+                PostOcrAction::Forward { target } => formatted().and_then(|text| forward(target, &text, image)),
+            };
+            ActionOutcome {
+                action: action.clone(),
+                error: result.err().map(|e| e.localized_message()),
+            }
+        })
+        .collect()
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Run the configured post-OCR actions for a hotkey binding (or the defaults).
+/// `capture_id` is the capture the text came from, for forwarding.
+#[tauri::command(async)]
+pub fn run_post_ocr_actions(
+    app: AppHandle,
+    text: String,
+    binding: Option<String>,
+    capture_id: Option<String>,
+) -> AppResult<Vec<ActionOutcome>> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let config = crate::config::get()?;
+    let actions = config.actions.actions_for(binding.as_deref());
+    let image = capture_id.and_then(|id| crate::capture_protocol::get(&id)).map(|(_, bytes)| bytes);
+    Ok(run_actions(&app, &text, image.as_deref(), actions))
+}
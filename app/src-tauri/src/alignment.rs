@@ -0,0 +1,169 @@
+// Sentence alignment
+// Translates text sentence by sentence so each source sentence maps to its
+// translation, letting the UI highlight corresponding segments on hover.
+// Offsets count Unicode characters (not bytes) into the source and
+// translated text.
+
+use std::ops::Range;
+
+use crate::error::AppResult;
+
+/// A source sentence and its translation
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignedSegment {
+    pub source: String,
+    pub translation: String,
+    pub source_start: usize,
+    pub source_end: usize,
+    pub target_start: usize,
+    pub target_end: usize,
+}
+
+/// Translated text with its sentence alignment
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignedTranslation {
+    pub text: String,
+    pub segments: Vec<AlignedSegment>,
+}
+
+fn is_terminal(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？')
+}
+
+/// Closing quotes/brackets that belong to the sentence before them
+fn is_closing(c: char) -> bool {
+    matches!(c, '"' | '\'' | ')' | ']' | '”' | '’' | '」' | '』' | '）')
+}
+
+/// Scripts written without spaces between sentences
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3000..=0x30FF | 0x3400..=0x9FFF | 0xAC00..=0xD7AF | 0xFF00..=0xFFEF)
+}
+
+/// Trim whitespace off a char range
+fn trimmed(chars: &[char], range: Range<usize>) -> Option<Range<usize>> {
+    let start = (range.start..range.end).find(|&i| !chars[i].is_whitespace())?;
+    let end = (start..range.end).rev().find(|&i| !chars[i].is_whitespace())? + 1;
+    Some(start..end)
+}
+
+/// Sentence char ranges. Sentences end at terminal punctuation (followed by
+/// whitespace and not a lowercase continuation such as "e.g. this") or at a
+/// blank line. Single line breaks are kept inside sentences, since OCR wraps
+/// lines mid-sentence.
+pub fn split_sentences(text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let mut end = None;
+        if c == '\n' && chars[i + 1..].iter().take_while(|c| c.is_whitespace()).any(|&c| c == '\n') {
+            end = Some(i);
+        } else if is_terminal(c) {
+            let mut j = i + 1;
+            while j < chars.len() && (is_terminal(chars[j]) || is_closing(chars[j])) {
+                j += 1;
+            }
+            let next = chars[j..].iter().find(|c| !c.is_whitespace());
+            let boundary = j == chars.len() || chars[j].is_whitespace() || is_cjk(c);
+            if boundary && !next.is_some_and(|n| n.is_lowercase()) {
+                end = Some(j);
+            }
+            i = j - 1;
+        }
+        if let Some(end) = end {
+            sentences.extend(trimmed(&chars, start..end));
+            start = end;
+        }
+        i += 1;
+    }
+    sentences.extend(trimmed(&chars, start..chars.len()));
+    sentences
+}
+
+/// Translate `text` sentence by sentence with `translate`, recording offsets
+pub fn align(
+    text: &str,
+    mut translate: impl FnMut(&str) -> AppResult<String>,
+) -> AppResult<AlignedTranslation> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = String::new();
+    let mut output_len = 0;
+    let mut segments: Vec<AlignedSegment> = Vec::new();
+
+    for range in split_sentences(text) {
+        let source: String = chars[range.clone()].iter().collect();
+        let translation = translate(&source)?.trim().to_string();
+
+        if let Some(previous) = segments.last() {
+            // Keep the source layout: line breaks stay, spaces only between
+            // sentences of space-separated scripts
+            let gap = &chars[previous.source_end..range.start];
+            let separator = if gap.contains(&'\n') {
+                "\n"
+            } else if translation.chars().next().is_some_and(is_cjk) {
+                ""
+            } else {
+                " "
+            };
+            output.push_str(separator);
+            output_len += separator.chars().count();
+        }
+
+        let target_start = output_len;
+        output.push_str(&translation);
+        output_len += translation.chars().count();
+        segments.push(AlignedSegment {
+            source,
+            translation,
+            source_start: range.start,
+            source_end: range.end,
+            target_start,
+            target_end: output_len,
+        });
+    }
+
+    Ok(AlignedTranslation {
+        text: output,
+        segments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentences(text: &str) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        split_sentences(text).into_iter().map(|r| chars[r].iter().collect()).collect()
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            sentences("Hello there. How are\nyou? Fine, e.g. good!\n\nNew paragraph"),
+            vec!["Hello there.", "How are\nyou?", "Fine, e.g. good!", "New paragraph"]
+        );
+        assert_eq!(sentences("今日は晴れ。「散歩しよう！」行こう"), vec!["今日は晴れ。", "「散歩しよう！」", "行こう"]);
+        assert_eq!(sentences("Version 1.5 is out."), vec!["Version 1.5 is out."]);
+        assert!(sentences("  \n ").is_empty());
+    }
+
+    #[test]
+    fn test_align_offsets() {
+        let text = "Good morning. Bye!";
+        let result = align(text, |s| Ok(if s.starts_with("Good") { "おはよう。".to_string() } else { "またね！".to_string() })).unwrap();
+        assert_eq!(result.text, "おはよう。またね！");
+
+        let second = &result.segments[1];
+        assert_eq!((second.source_start, second.source_end), (14, 18));
+        assert_eq!((second.target_start, second.target_end), (5, 9));
+        let target: String = result.text.chars().skip(second.target_start).take(second.target_end - second.target_start).collect();
+        assert_eq!(target, "またね！");
+    }
+}
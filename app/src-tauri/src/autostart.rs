@@ -0,0 +1,390 @@
+// Start at login
+// Registers the app with the platform's own login mechanism: the HKCU Run key
+// on Windows, a LaunchAgent on macOS and an XDG autostart entry on Linux. The
+// registration itself is the source of truth, so a user removing it from the
+// OS settings shows up as disabled. A Flatpak can't see the host's autostart
+// folder: the Background portal registers it there, and the entry written
+// inside the sandbox only records what was asked for. With "minimized" the app is launched with
+// --minimized and stays in the tray.
+
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppResult};
+
+/// Argument passed by the login entry to start hidden in the tray
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+#[cfg(any(not(windows), test))]
+const IDENTIFIER: &str = "com.iml1s.screeninu";
+
+/// Login registration state
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutostartStatus {
+    pub enabled: bool,
+    /// Start hidden in the tray
+    pub minimized: bool,
+}
+
+/// Whether this process was started by the login entry in minimized mode
+pub fn launched_minimized() -> bool {
+    std::env::args().skip(1).any(|arg| arg == MINIMIZED_ARG)
+}
+
+/// Executable to register. AppImages run from a temporary mount, so the
+/// AppImage file itself is registered instead; snaps run from a
+/// per-revision path, so the snap's launcher is.
+fn executable() -> AppResult<PathBuf> {
+    #[cfg(target_os = "linux")]
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    #[cfg(target_os = "linux")]
+    if crate::sandbox::detect() == Some(crate::sandbox::Sandbox::Snap) {
+        if let Ok(name) = std::env::var("SNAP_NAME") {
+            return Ok(PathBuf::from("/snap/bin").join(name));
+        }
+    }
+    Ok(std::env::current_exe()?)
+}
+
+/// Status from the registered entry (Run key value, plist or .desktop file)
+fn status_from_entry(entry: Option<&str>) -> AutostartStatus {
+    match entry {
+        Some(entry) => AutostartStatus {
+            enabled: true,
+            minimized: entry.contains(MINIMIZED_ARG),
+        },
+        None => AutostartStatus::default(),
+    }
+}
+
+// ========================================
+// Windows: HKCU\...\Run
+// ========================================
+
+#[cfg(windows)]
+mod platform {
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegDeleteValueW, RegOpenKeyExW, RegQueryValueExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER,
+        KEY_QUERY_VALUE, KEY_SET_VALUE, REG_SAM_FLAGS, REG_SZ,
+    };
+
+    use super::*;
+
+    const RUN_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+    const VALUE_NAME: PCWSTR = w!("Screen Inu");
+
+    fn registry_error(action: &str, e: windows::core::Error) -> AppError {
+        AppError::Other(format!("Failed to {} login item: {}", action, e))
+    }
+
+    fn with_run_key<T>(access: REG_SAM_FLAGS, f: impl FnOnce(HKEY) -> AppResult<T>) -> AppResult<T> {
+        let mut key = HKEY::default();
+        unsafe {
+            RegOpenKeyExW(HKEY_CURRENT_USER, RUN_KEY, 0, access, &mut key)
+                .ok()
+                .map_err(|e| registry_error("open", e))?;
+            let result = f(key);
+            let _ = RegCloseKey(key);
+            result
+        }
+    }
+
+    /// Run key value: the quoted executable path followed by the arguments
+    pub fn entry(exe: &std::path::Path, minimized: bool) -> String {
+        let mut command = format!("\"{}\"", exe.display());
+        if minimized {
+            command.push(' ');
+            command.push_str(MINIMIZED_ARG);
+        }
+        command
+    }
+
+    pub fn read() -> AppResult<Option<String>> {
+        with_run_key(KEY_QUERY_VALUE, |key| unsafe {
+            let mut size = 0u32;
+            let found = RegQueryValueExW(key, VALUE_NAME, None, None, None, Some(&mut size));
+            if found == ERROR_FILE_NOT_FOUND {
+                return Ok(None);
+            }
+            found.ok().map_err(|e| registry_error("read", e))?;
+
+            let mut data = vec![0u8; size as usize];
+            RegQueryValueExW(key, VALUE_NAME, None, None, Some(data.as_mut_ptr()), Some(&mut size))
+                .ok()
+                .map_err(|e| registry_error("read", e))?;
+            let wide: Vec<u16> = data
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .take_while(|&c| c != 0)
+                .collect();
+            Ok(Some(String::from_utf16_lossy(&wide)))
+        })
+    }
+
+    pub fn write(entry: &str) -> AppResult<()> {
+        let value = HSTRING::from(entry);
+        // REG_SZ data is the UTF-16 string including its terminator
+        let bytes: Vec<u8> = value
+            .as_wide()
+            .iter()
+            .chain(std::iter::once(&0))
+            .flat_map(|c| c.to_le_bytes())
+            .collect();
+        with_run_key(KEY_SET_VALUE, |key| unsafe {
+            RegSetValueExW(key, VALUE_NAME, 0, REG_SZ, Some(&bytes))
+                .ok()
+                .map_err(|e| registry_error("write", e))
+        })
+    }
+
+    pub fn remove() -> AppResult<()> {
+        with_run_key(KEY_SET_VALUE, |key| unsafe {
+            let result = RegDeleteValueW(key, VALUE_NAME);
+            if result == ERROR_FILE_NOT_FOUND {
+                return Ok(());
+            }
+            result.ok().map_err(|e| registry_error("remove", e))
+        })
+    }
+}
+
+// ========================================
+// macOS: ~/Library/LaunchAgents
+// ========================================
+
+#[cfg(any(target_os = "macos", test))]
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// LaunchAgent that runs `exe` once at login
+#[cfg(any(target_os = "macos", test))]
+fn launch_agent_plist(exe: &std::path::Path, minimized: bool) -> String {
+    let mut arguments = format!("        <string>{}</string>\n", xml_escape(&exe.to_string_lossy()));
+    if minimized {
+        arguments.push_str(&format!("        <string>{}</string>\n", MINIMIZED_ARG));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+{}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        IDENTIFIER, arguments
+    )
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    fn plist_path() -> AppResult<PathBuf> {
+        let home = std::env::var("HOME").map_err(|_| "HOME not found")?;
+        Ok(PathBuf::from(home)
+            .join("Library")
+            .join("LaunchAgents")
+            .join(format!("{}.plist", IDENTIFIER)))
+    }
+
+    /// The LaunchAgent plist
+    pub fn entry(exe: &std::path::Path, minimized: bool) -> String {
+        launch_agent_plist(exe, minimized)
+    }
+
+    pub fn read() -> AppResult<Option<String>> {
+        let path = plist_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(std::fs::read_to_string(path)?))
+    }
+
+    pub fn write(plist: &str) -> AppResult<()> {
+        let path = plist_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, plist)?;
+        Ok(())
+    }
+
+    pub fn remove() -> AppResult<()> {
+        let path = plist_path()?;
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+// ========================================
+// Linux: XDG autostart
+// ========================================
+
+/// Quote an Exec argument per the desktop entry spec
+#[cfg(any(all(unix, not(target_os = "macos")), test))]
+fn desktop_quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            // Escaped for the Exec quoting, then the backslash is escaped
+            // again for the string value
+            '"' | '`' | '$' => quoted.push_str(&format!("\\\\{}", c)),
+            '\\' => quoted.push_str("\\\\\\\\"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Autostart entry that runs `exe` at login
+#[cfg(any(all(unix, not(target_os = "macos")), test))]
+fn desktop_entry(exe: &std::path::Path, minimized: bool) -> String {
+    let mut exec = desktop_quote(&exe.to_string_lossy());
+    if minimized {
+        exec.push(' ');
+        exec.push_str(MINIMIZED_ARG);
+    }
+    format!(
+        "[Desktop Entry]\nType=Application\nName=Screen Inu\nComment=Screenshot OCR and translation\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        exec
+    )
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    fn entry_path() -> AppResult<PathBuf> {
+        let config_dir = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(std::env::var("HOME").map_err(|_| "HOME not found")?).join(".config"),
+        };
+        Ok(config_dir.join("autostart").join(format!("{}.desktop", IDENTIFIER)))
+    }
+
+    /// The .desktop file
+    pub fn entry(exe: &std::path::Path, minimized: bool) -> String {
+        desktop_entry(exe, minimized)
+    }
+
+    pub fn read() -> AppResult<Option<String>> {
+        let path = entry_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let entry = std::fs::read_to_string(path)?;
+        // Desktop environments disable entries in place instead of deleting them
+        let disabled = ["Hidden=true", "X-GNOME-Autostart-enabled=false"];
+        if entry.lines().any(|line| disabled.contains(&line.trim())) {
+            return Ok(None);
+        }
+        Ok(Some(entry))
+    }
+
+    /// Command the Background portal runs inside the sandbox
+    #[cfg(target_os = "linux")]
+    fn flatpak_command(minimized: bool) -> AppResult<Vec<String>> {
+        let exe = std::env::current_exe()?;
+        let name = exe.file_name().ok_or("Executable name not found")?;
+        let mut command = vec![name.to_string_lossy().into_owned()];
+        if minimized {
+            command.push(MINIMIZED_ARG.to_string());
+        }
+        Ok(command)
+    }
+
+    pub fn write(entry: &str) -> AppResult<()> {
+        #[cfg(target_os = "linux")]
+        if crate::sandbox::detect() == Some(crate::sandbox::Sandbox::Flatpak) {
+            crate::sandbox::portal_set_autostart(true, flatpak_command(entry.contains(MINIMIZED_ARG))?)?;
+        }
+        let path = entry_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, entry)?;
+        Ok(())
+    }
+
+    pub fn remove() -> AppResult<()> {
+        #[cfg(target_os = "linux")]
+        if crate::sandbox::detect() == Some(crate::sandbox::Sandbox::Flatpak) {
+            crate::sandbox::portal_set_autostart(false, flatpak_command(false)?)?;
+        }
+        let path = entry_path()?;
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Whether the app is registered to start at login
+#[tauri::command]
+pub fn get_autostart() -> AppResult<AutostartStatus> {
+    Ok(status_from_entry(platform::read()?.as_deref()))
+}
+
+/// Register or unregister the app to start at login, optionally minimized
+/// to the tray
+#[tauri::command]
+pub fn set_autostart(enabled: bool, minimized: bool) -> AppResult<AutostartStatus> {
+    if enabled {
+        let exe = executable()?;
+        if !exe.exists() {
+            return Err(AppError::Other(format!("Executable not found: {}", exe.display())));
+        }
+        platform::write(&platform::entry(&exe, minimized))?;
+    } else {
+        platform::remove()?;
+    }
+    get_autostart()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_status_from_entry() {
+        assert_eq!(status_from_entry(None), AutostartStatus::default());
+        let status = status_from_entry(Some("\"C:\\Screen Inu\\screen-inu.exe\" --minimized"));
+        assert_eq!(status, AutostartStatus { enabled: true, minimized: true });
+        assert!(!status_from_entry(Some("\"/usr/bin/screen-inu\"")).minimized);
+    }
+
+    #[test]
+    fn test_desktop_entry_quotes_exec() {
+        let entry = desktop_entry(Path::new("/opt/Screen Inu/$bin"), true);
+        assert!(entry.contains("Exec=\"/opt/Screen Inu/\\\\$bin\" --minimized\n"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+    }
+
+    #[test]
+    fn test_launch_agent_plist() {
+        let plist = launch_agent_plist(Path::new("/Applications/A&B.app/Contents/MacOS/screen-inu"), false);
+        assert!(plist.contains("<string>/Applications/A&amp;B.app/Contents/MacOS/screen-inu</string>"));
+        assert!(!plist.contains(MINIMIZED_ARG));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+    }
+}
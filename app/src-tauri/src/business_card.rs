@@ -0,0 +1,415 @@
+// Business cards
+// OCR of a business card mapped to contact fields and exported as a vCard.
+// Phone numbers, emails, links and addresses come from the entity patterns,
+// with "Fax"/"Mobile" labels in front of a number deciding its type. The rest
+// is assigned by layout: the name is the largest name-like line, the company
+// has a legal suffix or matches the email domain, and the job title has a
+// title keyword or sits right under the name. Leftover lines become the note.
+
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::calendar::{escape_ics, fold_ics_line};
+use crate::entities::EntityKind;
+use crate::error::{AppError, AppResult};
+use crate::ocr::{self, OcrOptions};
+use crate::ocr_result::OcrLine;
+
+const CONTACTS_DIR: &str = "contacts";
+
+static FAX_LABEL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:fax|\bf\b|傳真|传真|ファックス|팩스)").expect("label regex is valid")
+});
+static CELL_LABEL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:mobile|cell|\bm\b|手機|手机|携帯|휴대폰|핸드폰)").expect("label regex is valid")
+});
+static ADDRESS_LABEL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:address|addr|住所|地址|주소)\.?\s*[:：]?\s*").expect("label regex is valid")
+});
+static COMPANY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:inc|ltd|llc|llp|corp|corporation|co|company|gmbh|ag|plc|pty|limited|group|labs?)\b|株式会社|有限会社|有限公司|股份|公司|集團|集团|주식회사|\(주\)")
+        .expect("company regex is valid")
+});
+static JOB_TITLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:ceo|cto|cfo|coo|founder|president|vp|director|manager|head|lead|engineer|developer|designer|consultant|architect|analyst|officer|partner|specialist|representative|assistant|professor)\b|社長|部長|課長|取締役|主任|經理|经理|總監|总监|工程師|工程师|設計師|设计师|董事|執行長|执行长|대표|부장|과장|팀장|이사|엔지니어")
+        .expect("job title regex is valid")
+});
+
+/// Mail providers whose domain says nothing about the company
+const FREE_MAIL: &[&str] = &["gmail", "googlemail", "yahoo", "outlook", "hotmail", "live", "icloud", "me", "qq", "163", "naver"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PhoneKind {
+    #[default]
+    Work,
+    Cell,
+    Fax,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactPhone {
+    pub kind: PhoneKind,
+    /// As printed on the card
+    pub number: String,
+}
+
+/// Contact fields read from a card; the UI may edit them before export
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Contact {
+    pub name: Option<String>,
+    pub company: Option<String>,
+    /// Job title
+    pub title: Option<String>,
+    pub phones: Vec<ContactPhone>,
+    pub emails: Vec<String>,
+    pub urls: Vec<String>,
+    pub address: Option<String>,
+    /// Lines no field claimed
+    pub note: Option<String>,
+}
+
+/// What `perform_business_card_ocr` returns
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BusinessCard {
+    pub text: String,
+    pub contact: Contact,
+    pub vcard: String,
+}
+
+fn is_cjk_name_char(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF // Hangul syllables
+    )
+}
+
+/// Two to four capitalized words, or two to five CJK characters
+fn looks_like_name(text: &str) -> bool {
+    let text = text.trim();
+    if COMPANY.is_match(text) || JOB_TITLE.is_match(text) {
+        return false;
+    }
+    let cjk: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if cjk.iter().all(|c| is_cjk_name_char(*c)) {
+        return (2..=5).contains(&cjk.len());
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    (2..=4).contains(&words.len())
+        && words.iter().all(|word| {
+            word.chars().next().is_some_and(char::is_uppercase)
+                && word.chars().all(|c| c.is_alphabetic() || matches!(c, '.' | '\'' | '-'))
+        })
+}
+
+fn phone_kind(label: &str) -> PhoneKind {
+    if FAX_LABEL.is_match(label) {
+        PhoneKind::Fax
+    } else if CELL_LABEL.is_match(label) {
+        PhoneKind::Cell
+    } else {
+        PhoneKind::Work
+    }
+}
+
+/// "jane.doe@acme.com" -> "Jane Doe"
+fn name_from_email(email: &str) -> Option<String> {
+    let local = email.split('@').next()?;
+    let parts: Vec<&str> = local.split(['.', '_']).filter(|p| !p.is_empty()).collect();
+    if parts.len() < 2 || parts.iter().any(|p| !p.chars().all(char::is_alphabetic)) {
+        return None;
+    }
+    let capitalized = parts.iter().map(|p| {
+        let mut chars = p.chars();
+        chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+    });
+    Some(capitalized.collect::<Vec<_>>().join(" "))
+}
+
+/// The company part of an email domain ("acme" for "jane@mail.acme.co.jp")
+fn email_company(email: &str) -> Option<String> {
+    let domain = email.split('@').nth(1)?.to_lowercase();
+    let labels: Vec<&str> = domain.split('.').collect();
+    // Skip the public suffix: "com", or "co.jp"
+    let suffix = if labels.len() >= 3 && labels[labels.len() - 2].len() <= 3 { 2 } else { 1 };
+    let label = labels.len().checked_sub(suffix + 1).map(|i| labels[i])?;
+    (!FREE_MAIL.contains(&label) && label.len() >= 3).then(|| label.to_string())
+}
+
+/// Assign the lines of a card to contact fields
+pub fn parse_card(lines: &[OcrLine]) -> Contact {
+    let mut contact = Contact::default();
+    let mut rest: Vec<&OcrLine> = Vec::new();
+    for line in lines {
+        let text = line.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(m) = ADDRESS_LABEL.find(text) {
+            contact.address.get_or_insert_with(|| text[m.end()..].trim().to_string());
+            continue;
+        }
+        let mut claimed = false;
+        let mut cursor = 0;
+        for entity in crate::entities::extract(text) {
+            let Some(at) = text[cursor..].find(&entity.text).map(|i| i + cursor) else { continue };
+            let label = &text[cursor..at];
+            cursor = at + entity.text.len();
+            match entity.kind {
+                EntityKind::Email if !contact.emails.contains(&entity.value) => contact.emails.push(entity.value),
+                EntityKind::Url if !contact.urls.contains(&entity.value) => contact.urls.push(entity.value),
+                EntityKind::Phone => contact.phones.push(ContactPhone {
+                    kind: phone_kind(label),
+                    number: entity.text.trim().to_string(),
+                }),
+                EntityKind::Address => {
+                    contact.address.get_or_insert(entity.text);
+                }
+                _ => {}
+            }
+            claimed |= entity.kind != EntityKind::Date;
+        }
+        if !claimed {
+            rest.push(line);
+        }
+    }
+
+    // Names are printed largest
+    let name = rest
+        .iter()
+        .copied()
+        .filter(|line| looks_like_name(&line.text))
+        .max_by_key(|line| (line.bbox.height, Reverse(line.bbox.y)));
+    rest.retain(|line| !name.is_some_and(|name| std::ptr::eq(*line, name)));
+
+    let domain = contact.emails.iter().find_map(|email| email_company(email));
+    let company = rest
+        .iter()
+        .position(|line| COMPANY.is_match(&line.text))
+        .or_else(|| {
+            let domain = domain.as_ref()?;
+            rest.iter().position(|line| line.text.to_lowercase().replace(' ', "").contains(domain.as_str()))
+        })
+        .map(|i| rest.remove(i));
+
+    // A title keyword, or else the line right under the name
+    let title = rest.iter().position(|line| JOB_TITLE.is_match(&line.text)).or_else(|| {
+        let name = name?;
+        let bottom = name.bbox.y + name.bbox.height;
+        rest.iter().position(|line| {
+            let overlaps = line.bbox.x < name.bbox.x + name.bbox.width && name.bbox.x < line.bbox.x + line.bbox.width;
+            line.bbox.y >= bottom && line.bbox.y - bottom < name.bbox.height * 2 && overlaps
+        })
+    });
+    let title = title.map(|i| rest.remove(i));
+
+    let trimmed = |line: &OcrLine| line.text.trim().to_string();
+    contact.name = name.map(trimmed).or_else(|| contact.emails.iter().find_map(|email| name_from_email(email)));
+    contact.company = company.map(trimmed);
+    contact.title = title.map(trimmed);
+    let note: Vec<String> = rest.into_iter().map(trimmed).collect();
+    contact.note = (!note.is_empty()).then(|| note.join("\n"));
+    contact
+}
+
+/// Family, given and middle names for the vCard N property
+fn name_parts(name: &str) -> (String, String, String) {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    if name.chars().any(is_cjk_name_char) {
+        // Family name first
+        return match words.split_first() {
+            Some((family, given)) if !given.is_empty() => (family.to_string(), given.join(" "), String::new()),
+            _ => (name.trim().to_string(), String::new(), String::new()),
+        };
+    }
+    match words.as_slice() {
+        [] => Default::default(),
+        [single] => (single.to_string(), String::new(), String::new()),
+        [given, middle @ .., family] => (family.to_string(), given.to_string(), middle.join(" ")),
+    }
+}
+
+/// A vCard 3.0 for the contact
+pub fn to_vcard(contact: &Contact) -> String {
+    let full_name = contact
+        .name
+        .clone()
+        .or_else(|| contact.company.clone())
+        .or_else(|| contact.emails.first().cloned())
+        .unwrap_or_default();
+    let (family, given, middle) = contact.name.as_deref().map(name_parts).unwrap_or_default();
+    let mut lines = vec![
+        "BEGIN:VCARD".to_string(),
+        "VERSION:3.0".to_string(),
+        format!("N:{};{};{};;", escape_ics(&family), escape_ics(&given), escape_ics(&middle)),
+        format!("FN:{}", escape_ics(&full_name)),
+    ];
+    if let Some(company) = &contact.company {
+        lines.push(format!("ORG:{}", escape_ics(company)));
+    }
+    if let Some(title) = &contact.title {
+        lines.push(format!("TITLE:{}", escape_ics(title)));
+    }
+    for phone in &contact.phones {
+        let kind = match phone.kind {
+            PhoneKind::Work => "WORK,VOICE",
+            PhoneKind::Cell => "CELL,VOICE",
+            PhoneKind::Fax => "WORK,FAX",
+        };
+        lines.push(format!("TEL;TYPE={}:{}", kind, escape_ics(&phone.number)));
+    }
+    for email in &contact.emails {
+        lines.push(format!("EMAIL;TYPE=INTERNET:{}", escape_ics(email)));
+    }
+    for url in &contact.urls {
+        lines.push(format!("URL:{}", escape_ics(url)));
+    }
+    if let Some(address) = &contact.address {
+        // The whole address as the street; cards don't split it reliably
+        lines.push(format!("ADR;TYPE=WORK:;;{};;;;", escape_ics(address)));
+    }
+    if let Some(note) = &contact.note {
+        lines.push(format!("NOTE:{}", escape_ics(note)));
+    }
+    lines.push("END:VCARD".to_string());
+    lines.iter().map(|line| fold_ics_line(line) + "\r\n").collect()
+}
+
+/// File name for a contact's vCard
+fn vcard_file_name(contact: &Contact) -> String {
+    let name = contact.name.as_deref().or(contact.company.as_deref()).unwrap_or("contact");
+    let slug: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("{}.vcf", slug.trim_matches('-'))
+}
+
+fn contacts_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(CONTACTS_DIR))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// OCR a business card and map it to contact fields
+#[tauri::command]
+pub fn perform_business_card_ocr(
+    base64_image: &str,
+    langs: Option<String>,
+    engine: Option<String>,
+) -> AppResult<BusinessCard> {
+    let bytes = ocr::decode_base64_image(base64_image)?;
+    let options = OcrOptions {
+        langs,
+        engine,
+        ..Default::default()
+    };
+    let result = ocr::run_ocr_detailed(&bytes, &options)?;
+    let contact = parse_card(&result.lines);
+    Ok(BusinessCard {
+        text: result.text,
+        vcard: to_vcard(&contact),
+        contact,
+    })
+}
+
+/// Write the contact as a .vcf file, opened in the contacts app unless
+/// `open` is false; returns the file path
+#[tauri::command]
+pub fn export_vcard(app: tauri::AppHandle, contact: Contact, open: Option<bool>) -> AppResult<String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let dir = contacts_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(vcard_file_name(&contact));
+    std::fs::write(&path, to_vcard(&contact))?;
+    if open.unwrap_or(true) {
+        app.opener()
+            .open_path(path.to_string_lossy(), None::<&str>)
+            .map_err(|e| AppError::Other(format!("Failed to open vCard: {}", e)))?;
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr_result::{BoundingBox, OcrWord};
+
+    fn line(text: &str, y: u32, height: u32) -> OcrLine {
+        let mut x = 20;
+        let words = text
+            .split(' ')
+            .map(|word| {
+                let width = height * word.chars().count() as u32 / 2;
+                let bbox = BoundingBox { x, y, width, height };
+                x += width + height / 2;
+                OcrWord::new(word.to_string(), 90.0, bbox, 1, 1, y)
+            })
+            .collect();
+        OcrLine::from_words(words)
+    }
+
+    #[test]
+    fn test_parse_card() {
+        let contact = parse_card(&[
+            line("ACME WIDGETS", 10, 14),
+            line("Jane Doe", 40, 28),
+            line("Head of Growth", 74, 14),
+            line("T +1 415 555 0134  M +1 415 555 0199", 120, 12),
+            line("jane.doe@acme.com", 138, 12),
+            line("www.acme.com", 156, 12),
+            line("500 Market Street, San Francisco, CA 94105", 174, 12),
+        ]);
+        assert_eq!(contact.name.as_deref(), Some("Jane Doe"));
+        assert_eq!(contact.company.as_deref(), Some("ACME WIDGETS"));
+        assert_eq!(contact.title.as_deref(), Some("Head of Growth"));
+        let phones: Vec<_> = contact.phones.iter().map(|p| (p.kind, p.number.as_str())).collect();
+        assert_eq!(phones, [(PhoneKind::Work, "+1 415 555 0134"), (PhoneKind::Cell, "+1 415 555 0199")]);
+        assert_eq!(contact.emails, ["jane.doe@acme.com"]);
+        assert_eq!(contact.urls, ["https://www.acme.com"]);
+        assert_eq!(contact.address.as_deref(), Some("500 Market Street, San Francisco, CA 94105"));
+        assert_eq!(contact.note, None);
+
+        // No title keyword: the line under the name is the title
+        let contact = parse_card(&[
+            line("株式会社サンプル", 10, 14),
+            line("山田 太郎", 40, 30),
+            line("営業部", 76, 14),
+            line("TEL 03-1234-5678", 120, 12),
+            line("FAX 03-1234-5679", 138, 12),
+        ]);
+        assert_eq!(contact.name.as_deref(), Some("山田太郎"));
+        assert_eq!(contact.company.as_deref(), Some("株式会社サンプル"));
+        assert_eq!(contact.title.as_deref(), Some("営業部"));
+        assert_eq!(contact.phones[1].kind, PhoneKind::Fax);
+    }
+
+    #[test]
+    fn test_to_vcard() {
+        let contact = Contact {
+            name: Some("Jane Q. Doe".to_string()),
+            company: Some("Acme, Inc.".to_string()),
+            phones: vec![ContactPhone { kind: PhoneKind::Cell, number: "+1 415 555 0199".to_string() }],
+            ..Default::default()
+        };
+        let vcard = to_vcard(&contact);
+        assert!(vcard.starts_with("BEGIN:VCARD\r\nVERSION:3.0\r\nN:Doe;Jane;Q.;;\r\nFN:Jane Q. Doe\r\n"));
+        assert!(vcard.contains("ORG:Acme\\, Inc.\r\n"));
+        assert!(vcard.contains("TEL;TYPE=CELL,VOICE:+1 415 555 0199\r\n"));
+        assert!(vcard.ends_with("END:VCARD\r\n"));
+        assert_eq!(vcard_file_name(&contact), "Jane-Q--Doe.vcf");
+        assert_eq!(email_company("taro@mail.sample.co.jp").as_deref(), Some("sample"));
+        assert_eq!(email_company("someone@gmail.com"), None);
+    }
+}
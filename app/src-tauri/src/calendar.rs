@@ -0,0 +1,332 @@
+// Calendar events from captured text
+// `create_event_from_text` reads a date, a start and end time, a title and a
+// location out of a captured meeting invite and writes an .ics file, which is
+// then opened so the OS calendar app imports it. Absolute dates come from the
+// entity patterns (ISO, "Oct 20, 2026", "2026年10月20日", ...), relative ones
+// ("tomorrow", "next friday") from chrono-english; times may be "3pm",
+// "15:30" or "下午3:00". Without a time the event is all-day; without an end
+// time it lasts an hour.
+
+use std::path::PathBuf;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::error::{AppError, AppResult};
+
+const EVENTS_DIR: &str = "events";
+
+/// Longest .ics content line in octets, before folding
+const ICS_LINE_OCTETS: usize = 75;
+
+const DEFAULT_TITLE: &str = "Event";
+
+static TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2})(?:[:.](\d{2}))?\s*(?:([ap])\.?\s?m\b\.?)?").expect("time regex is valid")
+});
+static CJK_TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(上午|早上|中午|下午|晚上|午前|午後|오전|오후)?\s*(\d{1,2})\s*(?:[:：時时点點시])\s*(?:(\d{1,2})\s*分?)?")
+        .expect("time regex is valid")
+});
+static RANGE_SEPARATOR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:-|–|—|~|〜|～|to|until|till|至|到|から)\s*$").expect("range regex is valid")
+});
+static RELATIVE_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:today|tomorrow|(?:next|this)\s+(?:week|month|[a-z]+day)|(?:mon|tues|wednes|thurs|fri|satur|sun)day)\b")
+        .expect("relative date regex is valid")
+});
+static LOCATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?im)^\s*(?:location|where|place|venue|room|地點|地点|場所|会場|會場|장소)\s*[:：]\s*(.+?)\s*$")
+        .expect("location regex is valid")
+});
+static TITLE_LABEL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:subject|title|event|件名|主題|主题|제목)\s*[:：]\s*").expect("title regex is valid")
+});
+/// Lines holding only the when/where of the event
+static DETAIL_LABEL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*(?:location|where|place|venue|room|when|date|time|地點|地点|場所|会場|會場|時間|时间|日時|日期|장소|일시)\s*[:：]")
+        .expect("label regex is valid")
+});
+
+/// An event read from text
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    pub title: String,
+    /// "YYYY-MM-DDTHH:MM" in local time, or "YYYY-MM-DD" when all-day
+    pub start: String,
+    pub end: String,
+    pub all_day: bool,
+    pub location: Option<String>,
+    /// The .ics file written
+    pub ics_path: Option<String>,
+}
+
+/// Times found in the text, with their byte spans, in order
+fn find_times(text: &str) -> Vec<(usize, usize, NaiveTime)> {
+    let mut times = Vec::new();
+    for caps in CJK_TIME.captures_iter(text) {
+        let Ok(mut hour) = caps[2].parse::<u32>() else { continue };
+        let minute = caps.get(3).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        let afternoon = matches!(caps.get(1).map(|m| m.as_str()), Some("下午" | "晚上" | "午後" | "오후"));
+        if afternoon && hour < 12 {
+            hour += 12;
+        }
+        if let (Some(time), Some(m)) = (NaiveTime::from_hms_opt(hour, minute, 0), caps.get(0)) {
+            times.push((m.start(), m.end(), time));
+        }
+    }
+    for caps in TIME.captures_iter(text) {
+        let meridiem = caps.get(3).map(|m| m.as_str().to_lowercase());
+        // A bare number is no time
+        if caps.get(2).is_none() && meridiem.is_none() {
+            continue;
+        }
+        let Ok(mut hour) = caps[1].parse::<u32>() else { continue };
+        let minute = caps.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(0);
+        match meridiem.as_deref() {
+            Some("p") if hour < 12 => hour += 12,
+            Some("a") if hour == 12 => hour = 0,
+            _ => {}
+        }
+        let Some(m) = caps.get(0) else { continue };
+        let overlaps = times.iter().any(|(start, end, _)| m.start() < *end && *start < m.end());
+        if let (Some(time), false) = (NaiveTime::from_hms_opt(hour, minute, 0), overlaps) {
+            times.push((m.start(), m.end(), time));
+        }
+    }
+    times.sort_by_key(|(start, ..)| *start);
+    times
+}
+
+/// The first date in `text`, absolute or relative to `now`
+fn find_date(text: &str, now: chrono::DateTime<chrono::Local>) -> Option<(usize, usize, NaiveDate)> {
+    let absolute = crate::entities::find_dates(text)
+        .into_iter()
+        .filter_map(|(start, end, value)| Some((start, end, NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok()?)))
+        .min_by_key(|(start, ..)| *start);
+    absolute.or_else(|| {
+        let m = RELATIVE_DATE.find(text)?;
+        let parsed = chrono_english::parse_date_string(m.as_str(), now, chrono_english::Dialect::Us).ok()?;
+        Some((m.start(), m.end(), parsed.date_naive()))
+    })
+}
+
+fn find_location(text: &str) -> Option<String> {
+    if let Some(caps) = LOCATION.captures(text) {
+        return Some(caps[1].to_string());
+    }
+    crate::entities::extract(text)
+        .into_iter()
+        .find(|e| e.kind == crate::entities::EntityKind::Address)
+        .map(|e| e.text)
+}
+
+/// The first line that says more than the date and time
+fn find_title(text: &str, spans: &[(usize, usize)]) -> String {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let (line_start, line_end) = (offset, offset + line.len());
+        offset = line_end;
+        if DETAIL_LABEL.is_match(line) {
+            continue;
+        }
+        // The line without the date and times in it
+        let mut rest = String::new();
+        let mut copied = line_start;
+        for &(start, end) in spans.iter().filter(|(start, end)| *start >= line_start && *end <= line_end) {
+            if start < copied {
+                continue;
+            }
+            rest.push_str(&text[copied..start]);
+            copied = end;
+        }
+        rest.push_str(&text[copied..line_end]);
+        let title = TITLE_LABEL.replace(rest.trim(), "");
+        let title = title.trim_matches(|c: char| c.is_whitespace() || matches!(c, ',' | '-' | '–' | '@' | '|' | ':'));
+        if title.chars().filter(|c| c.is_alphabetic()).count() >= 2 {
+            return title.to_string();
+        }
+    }
+    DEFAULT_TITLE.to_string()
+}
+
+/// What `parse_event` read; an all-day event ends at midnight after it
+struct ParsedEvent {
+    title: String,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    all_day: bool,
+    location: Option<String>,
+}
+
+fn parse_event(text: &str, now: chrono::DateTime<chrono::Local>) -> AppResult<ParsedEvent> {
+    let (date_start, date_end, date) =
+        find_date(text, now).ok_or_else(|| AppError::InvalidInput("No date found in the text".to_string()))?;
+    // "10.20.2026" holds no time
+    let times: Vec<_> = find_times(text)
+        .into_iter()
+        .filter(|(start, end, _)| *end <= date_start || *start >= date_end)
+        .collect();
+    let mut spans = vec![(date_start, date_end)];
+    spans.extend(times.iter().map(|(start, end, _)| (*start, *end)));
+    spans.sort();
+
+    let (start, end, all_day) = match times.first() {
+        Some(&(_, first_end, first)) => {
+            let start = date.and_time(first);
+            // "3:00 PM - 4:30 PM"
+            let end = times
+                .get(1)
+                .filter(|(second_start, ..)| RANGE_SEPARATOR.is_match(&text[first_end..*second_start]))
+                .map(|(_, _, second)| date.and_time(*second))
+                .map(|end| if end <= start { end + Duration::days(1) } else { end })
+                .unwrap_or(start + Duration::hours(1));
+            (start, end, false)
+        }
+        None => (date.and_time(NaiveTime::MIN), (date + Duration::days(1)).and_time(NaiveTime::MIN), true),
+    };
+    Ok(ParsedEvent {
+        title: find_title(text, &spans),
+        start,
+        end,
+        all_day,
+        location: find_location(text),
+    })
+}
+
+/// Escape a text value (RFC 5545 3.3.11, which vCard shares)
+pub(crate) fn escape_ics(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets, without splitting a character
+pub(crate) fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / ICS_LINE_OCTETS * 3);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > ICS_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+fn to_ics(event: &ParsedEvent, description: &str, now: chrono::DateTime<chrono::Utc>) -> String {
+    let (start, end) = if event.all_day {
+        (
+            format!("DTSTART;VALUE=DATE:{}", event.start.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", event.end.format("%Y%m%d")),
+        )
+    } else {
+        // Floating local time, as read off the screen
+        (
+            format!("DTSTART:{}", event.start.format("%Y%m%dT%H%M%S")),
+            format!("DTEND:{}", event.end.format("%Y%m%dT%H%M%S")),
+        )
+    };
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Screen Inu//Calendar//EN".to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}-{}@screeninu", now.format("%Y%m%dT%H%M%S%3f"), std::process::id()),
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+        start,
+        end,
+        format!("SUMMARY:{}", escape_ics(&event.title)),
+    ];
+    if let Some(location) = &event.location {
+        lines.push(format!("LOCATION:{}", escape_ics(location)));
+    }
+    lines.push(format!("DESCRIPTION:{}", escape_ics(description.trim())));
+    lines.push("END:VEVENT".to_string());
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold_ics_line(line) + "\r\n").collect()
+}
+
+fn events_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(EVENTS_DIR))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Read an event out of `text` and write it as an .ics file, opened in the
+/// calendar app unless `open` is false
+#[tauri::command]
+pub fn create_event_from_text(app: tauri::AppHandle, text: String, open: Option<bool>) -> AppResult<CalendarEvent> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let event = parse_event(&text, chrono::Local::now())?;
+    let now = chrono::Utc::now();
+    let dir = events_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("event-{}.ics", now.format("%Y%m%d-%H%M%S-%3f")));
+    std::fs::write(&path, to_ics(&event, &text, now))?;
+
+    if open.unwrap_or(true) {
+        app.opener()
+            .open_path(path.to_string_lossy(), None::<&str>)
+            .map_err(|e| AppError::Other(format!("Failed to open calendar event: {}", e)))?;
+    }
+    let format = if event.all_day { "%Y-%m-%d" } else { "%Y-%m-%dT%H:%M" };
+    // The .ics end of an all-day event is exclusive; show the last day
+    let end = if event.all_day { event.end - Duration::days(1) } else { event.end };
+    Ok(CalendarEvent {
+        title: event.title,
+        start: event.start.format(format).to_string(),
+        end: end.format(format).to_string(),
+        all_day: event.all_day,
+        location: event.location,
+        ics_path: Some(path.to_string_lossy().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let now = chrono::Local::now();
+        let text = "Quarterly planning\nOct 20, 2026 3:00 PM - 4:30 PM\nLocation: Room 4, 2nd floor";
+        let event = parse_event(text, now).unwrap();
+        assert_eq!(event.title, "Quarterly planning");
+        assert_eq!((event.start, event.end), (at("2026-10-20", "15:00"), at("2026-10-20", "16:30")));
+        assert_eq!(event.location.as_deref(), Some("Room 4, 2nd floor"));
+        assert!(!event.all_day);
+
+        let event = parse_event("會議 2026年10月20日 下午3:00", now).unwrap();
+        assert_eq!(event.title, "會議");
+        assert_eq!((event.start, event.end), (at("2026-10-20", "15:00"), at("2026-10-20", "16:00")));
+
+        let event = parse_event("Subject: Launch day\n2026-11-02", now).unwrap();
+        assert_eq!(event.title, "Launch day");
+        assert!(event.all_day);
+        assert!(parse_event("No date here", now).is_err());
+    }
+
+    #[test]
+    fn test_ics() {
+        assert_eq!(escape_ics("a;b,c\\d\ne"), "a\\;b\\,c\\\\d\\ne");
+        let long = "DESCRIPTION:".to_string() + &"會".repeat(40);
+        let folded = fold_ics_line(&long);
+        assert!(folded.split("\r\n").all(|line| line.len() <= ICS_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), long);
+    }
+}
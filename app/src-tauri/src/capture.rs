@@ -0,0 +1,593 @@
+// Screen capture backends
+// Supports: xcap (all platforms), DXGI Desktop Duplication (Windows), ScreenCaptureKit (macOS),
+// the xdg-desktop-portal Screenshot interface (Flatpak / Snap on Linux)
+//
+// xcap performs a full capture per call, which is fine for one-shot screenshots but
+// too slow for the live OCR mode at 5-10 fps. The "game mode" backends keep a
+// capture session open and only copy frames when the desktop actually changed.
+
+use image::RgbaImage;
+use xcap::Monitor;
+
+use crate::error::{AppError, AppResult};
+
+/// A source of screen frames
+pub trait CaptureBackend {
+    /// Short identifier reported to the frontend
+    fn name(&self) -> &'static str;
+
+    /// Capture the whole monitor this backend is bound to
+    fn capture_frame(&mut self) -> AppResult<RgbaImage>;
+
+    /// Capture a sub-rectangle of the monitor
+    fn capture_region(&mut self, x: u32, y: u32, width: u32, height: u32) -> AppResult<RgbaImage> {
+        let frame = self.capture_frame()?;
+        if x >= frame.width() || y >= frame.height() {
+            return Err(AppError::InvalidInput("Capture region is outside the monitor bounds".to_string()));
+        }
+        let width = width.min(frame.width() - x);
+        let height = height.min(frame.height() - y);
+        Ok(image::imageops::crop_imm(&frame, x, y, width, height).to_image())
+    }
+}
+
+/// Capture backend types
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum CaptureBackendKind {
+    Xcap,
+    #[cfg(windows)]
+    Dxgi,
+    #[cfg(target_os = "macos")]
+    ScreenCaptureKit,
+    #[cfg(target_os = "linux")]
+    Portal,
+}
+
+impl CaptureBackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptureBackendKind::Xcap => "xcap",
+            #[cfg(windows)]
+            CaptureBackendKind::Dxgi => "dxgi",
+            #[cfg(target_os = "macos")]
+            CaptureBackendKind::ScreenCaptureKit => "screencapturekit",
+            #[cfg(target_os = "linux")]
+            CaptureBackendKind::Portal => "portal",
+        }
+    }
+}
+
+/// Default backend built on xcap, available everywhere. Inside a sandbox
+/// that hides the screen it goes through the Screenshot portal instead.
+pub struct XcapBackend {
+    monitor_index: usize,
+}
+
+impl XcapBackend {
+    pub fn new(monitor_index: usize) -> Self {
+        Self { monitor_index }
+    }
+}
+
+impl CaptureBackend for XcapBackend {
+    fn name(&self) -> &'static str {
+        #[cfg(target_os = "linux")]
+        if crate::sandbox::use_portal_capture() {
+            return CaptureBackendKind::Portal.as_str();
+        }
+        CaptureBackendKind::Xcap.as_str()
+    }
+
+    fn capture_frame(&mut self) -> AppResult<RgbaImage> {
+        crate::perf::time(&crate::perf::capture_metric(self.name()), || {
+            #[cfg(target_os = "linux")]
+            if crate::sandbox::use_portal_capture() {
+                return crate::sandbox::portal_screenshot(self.monitor_index);
+            }
+            let monitors = Monitor::all().map_err(|e| AppError::Capture(e.to_string()))?;
+            let monitor = monitors
+                .get(self.monitor_index)
+                .ok_or_else(|| AppError::Capture("No monitor found".to_string()))?;
+            monitor.capture_image().map_err(|e| AppError::Capture(e.to_string()))
+        })
+    }
+}
+
+/// Convert a BGRA frame whose row pitch may exceed width * 4, as both
+/// Desktop Duplication and CoreVideo hand them out
+#[cfg(any(windows, target_os = "macos", test))]
+fn bgra_to_rgba(src: &[u8], width: u32, height: u32, pitch: usize) -> AppResult<RgbaImage> {
+    let row_bytes = width as usize * 4;
+    if pitch < row_bytes || src.len() < pitch * height as usize {
+        return Err(AppError::Capture("Frame buffer is smaller than the frame".to_string()));
+    }
+    let mut rgba = Vec::with_capacity(row_bytes * height as usize);
+    for line in src.chunks(pitch).take(height as usize) {
+        for px in line[..row_bytes].chunks_exact(4) {
+            rgba.extend_from_slice(&[px[2], px[1], px[0], 255]);
+        }
+    }
+    RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| AppError::Capture("Failed to build image from frame".to_string()))
+}
+
+/// Low-latency backend using DXGI Desktop Duplication (Windows 8+)
+#[cfg(windows)]
+pub struct DxgiBackend {
+    device: windows::Win32::Graphics::Direct3D11::ID3D11Device,
+    context: windows::Win32::Graphics::Direct3D11::ID3D11DeviceContext,
+    duplication: windows::Win32::Graphics::Dxgi::IDXGIOutputDuplication,
+    monitor_index: usize,
+    staging: Option<windows::Win32::Graphics::Direct3D11::ID3D11Texture2D>,
+    last_frame: Option<RgbaImage>,
+}
+
+#[cfg(windows)]
+impl DxgiBackend {
+    pub fn new(monitor_index: usize) -> AppResult<Self> {
+        use windows::Win32::Foundation::HMODULE;
+        use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE;
+        use windows::Win32::Graphics::Direct3D11::{
+            D3D11CreateDevice, D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_SDK_VERSION,
+        };
+
+        unsafe {
+            let mut device = None;
+            let mut context = None;
+            D3D11CreateDevice(
+                None,
+                D3D_DRIVER_TYPE_HARDWARE,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                None,
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                Some(&mut context),
+            )
+            .map_err(|e| AppError::Capture(format!("Failed to create D3D11 device: {}", e)))?;
+
+            let device = device.ok_or_else(|| AppError::Capture("D3D11 device was not created".to_string()))?;
+            let context = context.ok_or_else(|| AppError::Capture("D3D11 context was not created".to_string()))?;
+            let duplication = Self::duplicate_output(&device, monitor_index)?;
+
+            Ok(Self {
+                device,
+                context,
+                duplication,
+                monitor_index,
+                staging: None,
+                last_frame: None,
+            })
+        }
+    }
+
+    /// Open a duplication session for the monitor's output
+    unsafe fn duplicate_output(
+        device: &windows::Win32::Graphics::Direct3D11::ID3D11Device,
+        monitor_index: usize,
+    ) -> AppResult<windows::Win32::Graphics::Dxgi::IDXGIOutputDuplication> {
+        use windows::core::Interface;
+        use windows::Win32::Graphics::Dxgi::{IDXGIDevice, IDXGIOutput1};
+
+        let dxgi_device: IDXGIDevice = device
+            .cast()
+            .map_err(|e| AppError::Capture(format!("Failed to get DXGI device: {}", e)))?;
+        let adapter = dxgi_device
+            .GetAdapter()
+            .map_err(|e| AppError::Capture(format!("Failed to get DXGI adapter: {}", e)))?;
+        let output = adapter
+            .EnumOutputs(monitor_index as u32)
+            .map_err(|e| AppError::Capture(format!("Failed to get output {}: {}", monitor_index, e)))?;
+        let output1: IDXGIOutput1 = output
+            .cast()
+            .map_err(|e| AppError::Capture(format!("Failed to get IDXGIOutput1: {}", e)))?;
+        output1
+            .DuplicateOutput(device)
+            .map_err(|e| AppError::Capture(format!("Failed to duplicate output: {}", e)))
+    }
+
+    /// Acquire the next frame; false when nothing changed within `timeout_ms`.
+    /// A mode change, the secure desktop (UAC) or a full-screen switch loses
+    /// the duplication, which is then recreated once.
+    unsafe fn acquire(
+        &mut self,
+        timeout_ms: u32,
+        frame_info: &mut windows::Win32::Graphics::Dxgi::DXGI_OUTDUPL_FRAME_INFO,
+        resource: &mut Option<windows::Win32::Graphics::Dxgi::IDXGIResource>,
+    ) -> AppResult<bool> {
+        use windows::Win32::Graphics::Dxgi::{DXGI_ERROR_ACCESS_LOST, DXGI_ERROR_WAIT_TIMEOUT};
+
+        let mut recreated = false;
+        loop {
+            match self.duplication.AcquireNextFrame(timeout_ms, frame_info, resource) {
+                Ok(()) => return Ok(true),
+                Err(e) if e.code() == DXGI_ERROR_WAIT_TIMEOUT => return Ok(false),
+                Err(e) if e.code() == DXGI_ERROR_ACCESS_LOST && !recreated => {
+                    tracing::debug!("Desktop duplication lost, recreating it");
+                    self.duplication = Self::duplicate_output(&self.device, self.monitor_index)?;
+                    // The desktop may have changed size
+                    self.staging = None;
+                    self.last_frame = None;
+                    recreated = true;
+                }
+                Err(e) => return Err(AppError::Capture(format!("Failed to acquire frame: {}", e))),
+            }
+        }
+    }
+
+    /// Create (or reuse) a CPU-readable texture matching the desktop texture
+    unsafe fn staging_texture(
+        &mut self,
+        source: &windows::Win32::Graphics::Direct3D11::ID3D11Texture2D,
+    ) -> AppResult<windows::Win32::Graphics::Direct3D11::ID3D11Texture2D> {
+        use windows::Win32::Graphics::Direct3D11::{
+            D3D11_CPU_ACCESS_READ, D3D11_TEXTURE2D_DESC, D3D11_USAGE_STAGING,
+        };
+
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        source.GetDesc(&mut desc);
+
+        if let Some(ref staging) = self.staging {
+            let mut existing = D3D11_TEXTURE2D_DESC::default();
+            staging.GetDesc(&mut existing);
+            if existing.Width == desc.Width && existing.Height == desc.Height {
+                return Ok(staging.clone());
+            }
+        }
+
+        desc.Usage = D3D11_USAGE_STAGING;
+        desc.CPUAccessFlags = D3D11_CPU_ACCESS_READ.0 as u32;
+        desc.BindFlags = 0;
+        desc.MiscFlags = 0;
+        desc.MipLevels = 1;
+        desc.ArraySize = 1;
+
+        let mut texture = None;
+        self.device
+            .CreateTexture2D(&desc, None, Some(&mut texture))
+            .map_err(|e| AppError::Capture(format!("Failed to create staging texture: {}", e)))?;
+        let texture = texture.ok_or_else(|| AppError::Capture("Staging texture was not created".to_string()))?;
+        self.staging = Some(texture.clone());
+        Ok(texture)
+    }
+
+    /// Next desktop frame, or the last one if nothing changed
+    fn duplicate_frame(&mut self) -> AppResult<RgbaImage> {
+        use windows::core::Interface;
+        use windows::Win32::Graphics::Direct3D11::{
+            ID3D11Texture2D, D3D11_MAPPED_SUBRESOURCE, D3D11_MAP_READ, D3D11_TEXTURE2D_DESC,
+        };
+        use windows::Win32::Graphics::Dxgi::DXGI_OUTDUPL_FRAME_INFO;
+
+        unsafe {
+            let mut frame_info = DXGI_OUTDUPL_FRAME_INFO::default();
+            let mut resource = None;
+
+            // Wait at most one 60 Hz frame; if nothing changed, reuse the last frame
+            if !self.acquire(16, &mut frame_info, &mut resource)? {
+                if let Some(ref frame) = self.last_frame {
+                    return Ok(frame.clone());
+                }
+                // First frame: a static desktop may not present one, so wait up to a second
+                if !self.acquire(1000, &mut frame_info, &mut resource)? {
+                    return Err(AppError::Capture("Timed out waiting for a desktop frame".to_string()));
+                }
+            }
+
+            let result = (|| {
+                let texture: ID3D11Texture2D = resource
+                    .as_ref()
+                    .ok_or_else(|| AppError::Capture("No desktop resource".to_string()))?
+                    .cast()
+                    .map_err(|e| AppError::Capture(format!("Failed to get desktop texture: {}", e)))?;
+                let staging = self.staging_texture(&texture)?;
+                self.context.CopyResource(&staging, &texture);
+
+                let mut desc = D3D11_TEXTURE2D_DESC::default();
+                staging.GetDesc(&mut desc);
+
+                let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+                self.context
+                    .Map(&staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))
+                    .map_err(|e| AppError::Capture(format!("Failed to map staging texture: {}", e)))?;
+
+                let pitch = mapped.RowPitch as usize;
+                let src = std::slice::from_raw_parts(mapped.pData as *const u8, pitch * desc.Height as usize);
+                let frame = bgra_to_rgba(src, desc.Width, desc.Height, pitch);
+                self.context.Unmap(&staging, 0);
+                frame
+            })();
+
+            let _ = self.duplication.ReleaseFrame();
+
+            let frame = result?;
+            self.last_frame = Some(frame.clone());
+            Ok(frame)
+        }
+    }
+}
+
+#[cfg(windows)]
+impl CaptureBackend for DxgiBackend {
+    fn name(&self) -> &'static str {
+        CaptureBackendKind::Dxgi.as_str()
+    }
+
+    fn capture_frame(&mut self) -> AppResult<RgbaImage> {
+        crate::perf::time(&crate::perf::capture_metric(self.name()), || self.duplicate_frame())
+    }
+}
+
+/// Frames handed from the ScreenCaptureKit output queue to the capturing thread
+#[cfg(target_os = "macos")]
+#[derive(Default)]
+struct SckFrames {
+    latest: std::sync::Mutex<Option<RgbaImage>>,
+    arrived: std::sync::Condvar,
+    /// Set by the stream's error handler, e.g. when the display went away
+    failed: std::sync::atomic::AtomicBool,
+}
+
+#[cfg(target_os = "macos")]
+struct SckOutput(std::sync::Arc<SckFrames>);
+
+#[cfg(target_os = "macos")]
+impl screencapturekit::sc_output_handler::StreamOutput for SckOutput {
+    fn did_output_sample_buffer(
+        &self,
+        sample: screencapturekit::cm_sample_buffer::CMSampleBuffer,
+        of_type: screencapturekit::sc_output_handler::SCStreamOutputType,
+    ) {
+        use screencapturekit::sc_output_handler::SCStreamOutputType;
+
+        if !matches!(of_type, SCStreamOutputType::Screen) {
+            return;
+        }
+        // Idle updates (nothing changed on the display) carry no image
+        let (Some(image), Some(pixels)) = (sample.image_buf_ref.as_ref(), sample.pixel_buffer.as_ref()) else {
+            return;
+        };
+        if !pixels.lock() {
+            return;
+        }
+        let frame = unsafe {
+            let buffer = &**image as *const _ as *const std::ffi::c_void;
+            let width = CVPixelBufferGetWidth(buffer);
+            let height = CVPixelBufferGetHeight(buffer);
+            let pitch = CVPixelBufferGetBytesPerRow(buffer);
+            let src = std::slice::from_raw_parts(pixels.get_base_adress() as *const u8, pitch * height);
+            bgra_to_rgba(src, width as u32, height as u32, pitch)
+        };
+        pixels.unlock();
+        match frame {
+            Ok(frame) => {
+                *self.0.latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(frame);
+                self.0.arrived.notify_all();
+            }
+            Err(e) => tracing::debug!("ScreenCaptureKit frame dropped: {}", e),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+struct SckErrorHandler(std::sync::Arc<SckFrames>);
+
+#[cfg(target_os = "macos")]
+impl screencapturekit::sc_error_handler::StreamErrorHandler for SckErrorHandler {
+    fn on_error(&self) {
+        self.0.failed.store(true, std::sync::atomic::Ordering::SeqCst);
+        self.0.arrived.notify_all();
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreVideo", kind = "framework")]
+extern "C" {
+    fn CVPixelBufferGetWidth(buffer: *const std::ffi::c_void) -> usize;
+    fn CVPixelBufferGetHeight(buffer: *const std::ffi::c_void) -> usize;
+    fn CVPixelBufferGetBytesPerRow(buffer: *const std::ffi::c_void) -> usize;
+}
+
+/// Low-latency backend using a ScreenCaptureKit stream (macOS 12.3+).
+/// The stream only delivers a frame when the display changed; the newest one
+/// is kept and handed out until the next arrives.
+#[cfg(target_os = "macos")]
+pub struct ScreenCaptureKitBackend {
+    stream: screencapturekit::sc_stream::SCStream,
+    frames: std::sync::Arc<SckFrames>,
+    monitor_index: usize,
+}
+
+#[cfg(target_os = "macos")]
+impl ScreenCaptureKitBackend {
+    pub fn new(monitor_index: usize) -> AppResult<Self> {
+        let frames = std::sync::Arc::new(SckFrames::default());
+        let stream = Self::start_stream(monitor_index, &frames)?;
+        Ok(Self { stream, frames, monitor_index })
+    }
+
+    /// Start a stream of the display xcap knows as `monitor_index`, at its
+    /// native pixel size
+    fn start_stream(
+        monitor_index: usize,
+        frames: &std::sync::Arc<SckFrames>,
+    ) -> AppResult<screencapturekit::sc_stream::SCStream> {
+        use screencapturekit::sc_content_filter::{InitParams, SCContentFilter};
+        use screencapturekit::sc_output_handler::SCStreamOutputType;
+        use screencapturekit::sc_shareable_content::SCShareableContent;
+        use screencapturekit::sc_stream::SCStream;
+        use screencapturekit::sc_stream_configuration::{PixelFormat, SCStreamConfiguration};
+
+        let monitors = Monitor::all().map_err(|e| AppError::Capture(e.to_string()))?;
+        let monitor = monitors
+            .get(monitor_index)
+            .ok_or_else(|| AppError::Capture("No monitor found".to_string()))?;
+        let display_id = monitor.id().map_err(|e| AppError::Capture(e.to_string()))?;
+        let scale = monitor.scale_factor().map_err(|e| AppError::Capture(e.to_string()))?;
+
+        // Fails without the Screen Recording permission
+        let content = SCShareableContent::try_current()
+            .map_err(|e| AppError::Capture(format!("Failed to list shareable displays: {}", e)))?;
+        let display = content
+            .displays
+            .into_iter()
+            .find(|d| d.display_id == display_id)
+            .ok_or_else(|| AppError::Capture(format!("Display {} is not shareable", display_id)))?;
+
+        let config = SCStreamConfiguration {
+            width: (display.width as f32 * scale).round() as u32,
+            height: (display.height as f32 * scale).round() as u32,
+            pixel_format: PixelFormat::ARGB8888,
+            ..Default::default()
+        };
+        frames.failed.store(false, std::sync::atomic::Ordering::SeqCst);
+        let mut stream = SCStream::new(
+            SCContentFilter::new(InitParams::Display(display)),
+            config,
+            SckErrorHandler(frames.clone()),
+        );
+        stream.add_output(SckOutput(frames.clone()), SCStreamOutputType::Screen);
+        stream
+            .start_capture()
+            .map_err(|e| AppError::Capture(format!("Failed to start ScreenCaptureKit stream: {}", e)))?;
+        Ok(stream)
+    }
+
+    /// The newest frame. The first one is waited for up to a second. A
+    /// stream stopped by the system (display reconfigured or removed) is
+    /// restarted once.
+    fn latest_frame(&mut self) -> AppResult<RgbaImage> {
+        use std::sync::atomic::Ordering;
+
+        if self.frames.failed.load(Ordering::SeqCst) {
+            tracing::debug!("ScreenCaptureKit stream stopped, restarting it");
+            let _ = self.stream.stop_capture();
+            // The display may have changed size
+            *self.frames.latest.lock().unwrap_or_else(|e| e.into_inner()) = None;
+            self.stream = Self::start_stream(self.monitor_index, &self.frames)?;
+        }
+
+        let latest = self.frames.latest.lock().unwrap_or_else(|e| e.into_inner());
+        let (latest, _) = self
+            .frames
+            .arrived
+            .wait_timeout_while(latest, std::time::Duration::from_secs(1), |frame| {
+                frame.is_none() && !self.frames.failed.load(Ordering::SeqCst)
+            })
+            .unwrap_or_else(|e| e.into_inner());
+        latest
+            .clone()
+            .ok_or_else(|| AppError::Capture("Timed out waiting for a display frame".to_string()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for ScreenCaptureKitBackend {
+    fn drop(&mut self) {
+        let _ = self.stream.stop_capture();
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl CaptureBackend for ScreenCaptureKitBackend {
+    fn name(&self) -> &'static str {
+        CaptureBackendKind::ScreenCaptureKit.as_str()
+    }
+
+    fn capture_frame(&mut self) -> AppResult<RgbaImage> {
+        crate::perf::time(&crate::perf::capture_metric(self.name()), || self.latest_frame())
+    }
+}
+
+/// Get list of available capture backends for the current platform
+pub fn get_available_backends() -> Vec<CaptureBackendKind> {
+    #[allow(unused_mut)]
+    let mut backends = vec![CaptureBackendKind::Xcap];
+
+    #[cfg(windows)]
+    backends.push(CaptureBackendKind::Dxgi);
+
+    #[cfg(target_os = "macos")]
+    backends.push(CaptureBackendKind::ScreenCaptureKit);
+
+    #[cfg(target_os = "linux")]
+    if crate::sandbox::use_portal_capture() {
+        backends = vec![CaptureBackendKind::Portal];
+    }
+
+    backends
+}
+
+/// Create a capture backend.
+/// With `game_mode` enabled the platform low-latency backend is tried first,
+/// falling back to xcap if it cannot be initialized.
+pub fn create_backend(game_mode: bool, monitor_index: usize) -> Box<dyn CaptureBackend> {
+    if game_mode {
+        #[cfg(windows)]
+        match DxgiBackend::new(monitor_index) {
+            Ok(backend) => return Box::new(backend),
+            Err(e) => tracing::warn!("DXGI capture unavailable: {}, falling back to xcap", e),
+        }
+
+        #[cfg(target_os = "macos")]
+        match ScreenCaptureKitBackend::new(monitor_index) {
+            Ok(backend) => return Box::new(backend),
+            Err(e) => tracing::warn!("ScreenCaptureKit unavailable: {}, falling back to xcap", e),
+        }
+    }
+
+    Box::new(XcapBackend::new(monitor_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Backend returning a fixed frame whose pixels encode their position
+    struct FixedBackend(RgbaImage);
+
+    impl CaptureBackend for FixedBackend {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn capture_frame(&mut self) -> AppResult<RgbaImage> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn fixed(width: u32, height: u32) -> FixedBackend {
+        FixedBackend(RgbaImage::from_fn(width, height, |x, y| image::Rgba([x as u8, y as u8, 0, 255])))
+    }
+
+    #[test]
+    fn test_bgra_to_rgba() {
+        // 2x2 frame with a pitch of 12 bytes (one padding pixel per row)
+        let src = [
+            1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0, //
+            9, 10, 11, 12, 13, 14, 15, 16, 0, 0, 0, 0,
+        ];
+        let image = bgra_to_rgba(&src, 2, 2, 12).unwrap();
+        assert_eq!(image.as_raw(), &[3, 2, 1, 255, 7, 6, 5, 255, 11, 10, 9, 255, 15, 14, 13, 255]);
+
+        assert!(bgra_to_rgba(&src, 2, 2, 4).is_err());
+        assert!(bgra_to_rgba(&src[..20], 2, 2, 12).is_err());
+    }
+
+    #[test]
+    fn test_capture_region_clamps() {
+        let mut backend = fixed(10, 8);
+        let region = backend.capture_region(2, 3, 4, 2).unwrap();
+        assert_eq!(region.dimensions(), (4, 2));
+        assert_eq!(region.get_pixel(0, 0).0, [2, 3, 0, 255]);
+
+        // Regions running past the edge are cut at the monitor bounds
+        let region = backend.capture_region(6, 5, 100, 100).unwrap();
+        assert_eq!(region.dimensions(), (4, 3));
+        assert_eq!(region.get_pixel(3, 2).0, [9, 7, 0, 255]);
+
+        assert!(matches!(backend.capture_region(10, 0, 1, 1), Err(AppError::InvalidInput(_))));
+        assert!(matches!(backend.capture_region(0, 8, 1, 1), Err(AppError::InvalidInput(_))));
+    }
+}
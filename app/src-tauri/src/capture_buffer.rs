@@ -0,0 +1,254 @@
+// Capture buffer ("time travel" capture)
+// Opt-in background loop that keeps the last few seconds of low-rate captures
+// in memory, so something that flashed on screen and disappeared can still be
+// grabbed: the rewind hotkey (or `get_buffered_capture`) serves the frame from
+// a few seconds ago over the capture protocol. Frames are kept as JPEG to
+// bound memory on high-resolution monitors.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::capture_protocol::{self, CaptureHandle};
+use crate::error::{AppError, AppResult};
+use crate::image_encode::{OutputFormat, OutputOptions};
+
+/// Emitted with a `CaptureHandle` when the rewind hotkey grabs a frame
+pub const CAPTURE_REWIND_EVENT: &str = "capture-buffer://rewind";
+
+/// Quality of buffered frames; high enough to OCR
+const FRAME_QUALITY: u8 = 90;
+
+const MIN_INTERVAL_MS: u64 = 250;
+const MAX_SECONDS: u64 = 60;
+
+/// Key of the buffered frames in the memory budget
+const MEMORY_KEY: &str = "capture-buffer";
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static FRAMES: Lazy<Mutex<VecDeque<BufferedFrame>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+/// Hotkey currently registered for rewinding
+static REGISTERED_HOTKEY: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// Capture buffer settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CaptureBufferConfig {
+    /// Nothing is captured in the background unless this is on
+    pub enabled: bool,
+    /// How far back the buffer reaches
+    pub seconds: u64,
+    /// Time between background captures
+    pub interval_ms: u64,
+    /// How far back the hotkey rewinds
+    pub rewind_secs: f64,
+    /// Global shortcut for rewinding, e.g. "CommandOrControl+Shift+Z"
+    pub hotkey: Option<String>,
+}
+
+impl Default for CaptureBufferConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            seconds: 10,
+            interval_ms: 1000,
+            rewind_secs: 5.0,
+            hotkey: None,
+        }
+    }
+}
+
+struct BufferedFrame {
+    captured_ms: i64,
+    width: u32,
+    height: u32,
+    bytes: Vec<u8>,
+}
+
+/// Memory budget eviction: the buffer refills within its window
+fn evict_frames(_key: &str) -> bool {
+    match FRAMES.try_lock() {
+        Ok(mut frames) => {
+            frames.clear();
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Drop frames that fell out of the window
+fn prune(frames: &mut VecDeque<BufferedFrame>, now_ms: i64, seconds: u64) {
+    let oldest = now_ms - (seconds * 1000) as i64;
+    while frames.front().is_some_and(|f| f.captured_ms < oldest) {
+        frames.pop_front();
+    }
+}
+
+/// The newest frame captured at least `age_ms` ago, or the oldest frame when
+/// the buffer doesn't reach back that far
+fn frame_at(frames: &VecDeque<BufferedFrame>, now_ms: i64, age_ms: i64) -> Option<&BufferedFrame> {
+    let target = now_ms - age_ms;
+    frames.iter().rev().find(|f| f.captured_ms <= target).or(frames.front())
+}
+
+fn capture_frame() -> AppResult<BufferedFrame> {
+    crate::permissions::ensure_capture_permission()?;
+    let image = XcapBackend::new(0).capture_frame()?;
+    let (width, height) = image.dimensions();
+    let options = OutputOptions {
+        format: OutputFormat::Jpeg,
+        quality: Some(FRAME_QUALITY),
+        ..Default::default()
+    };
+    Ok(BufferedFrame {
+        captured_ms: chrono::Local::now().timestamp_millis(),
+        width,
+        height,
+        bytes: crate::image_encode::encode(image, &options)?,
+    })
+}
+
+/// Serve the frame from `seconds_ago` over the capture protocol
+fn rewind(seconds_ago: f64) -> AppResult<CaptureHandle> {
+    let frames = FRAMES.lock()?;
+    let frame = frame_at(&frames, chrono::Local::now().timestamp_millis(), (seconds_ago * 1000.0) as i64)
+        .ok_or_else(|| AppError::Capture("The capture buffer is empty".to_string()))?;
+    let mime_type = OutputFormat::Jpeg.mime_type();
+    Ok(CaptureHandle {
+        id: capture_protocol::store(frame.bytes.clone(), mime_type)?,
+        mime_type: mime_type.to_string(),
+        width: frame.width,
+        height: frame.height,
+    })
+}
+
+/// Register the rewind hotkey from the config, replacing the previous one
+fn register_hotkey(app: &AppHandle, config: &CaptureBufferConfig) -> AppResult<()> {
+    let mut registered = REGISTERED_HOTKEY.lock()?;
+    let wanted = config.hotkey.clone().filter(|h| config.enabled && !h.is_empty());
+    if *registered == wanted {
+        return Ok(());
+    }
+    if let Some(previous) = registered.take() {
+        let _ = app.global_shortcut().unregister(previous.as_str());
+    }
+    if let Some(hotkey) = &wanted {
+        app.global_shortcut()
+            .on_shortcut(hotkey.as_str(), |app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+                let seconds_ago = crate::config::get().map(|c| c.capture_buffer.rewind_secs).unwrap_or(5.0);
+                match rewind(seconds_ago) {
+                    Ok(handle) => {
+                        let _ = app.emit(CAPTURE_REWIND_EVENT, handle);
+                    }
+                    Err(e) => tracing::warn!("Capture buffer: {}", e),
+                }
+            })
+            .map_err(|e| AppError::InvalidInput(format!("Invalid hotkey '{}': {}", hotkey, e)))?;
+    }
+    *registered = wanted;
+    Ok(())
+}
+
+/// Start the background capture thread (once) and register the hotkey
+pub fn init(app: &AppHandle) {
+    if let Err(e) = crate::config::get().and_then(|c| register_hotkey(app, &c.capture_buffer)) {
+        tracing::warn!("Capture buffer: {}", e);
+    }
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| loop {
+        let config = crate::config::get().map(|c| c.capture_buffer).unwrap_or_default();
+        if !config.enabled {
+            if let Ok(mut frames) = FRAMES.lock() {
+                frames.clear();
+            }
+            crate::memory::release(MEMORY_KEY);
+            std::thread::sleep(Duration::from_secs(1));
+            continue;
+        }
+
+        match capture_frame() {
+            Ok(frame) => {
+                let buffered_bytes = FRAMES.lock().ok().map(|mut frames| {
+                    let now_ms = frame.captured_ms;
+                    frames.push_back(frame);
+                    prune(&mut frames, now_ms, config.seconds.min(MAX_SECONDS));
+                    frames.iter().map(|f| f.bytes.len() as u64).sum()
+                });
+                if let Some(bytes) = buffered_bytes {
+                    crate::memory::reserve(MEMORY_KEY, "capture-buffer", bytes, evict_frames);
+                }
+            }
+            Err(e) => tracing::warn!("Capture buffer: {}", e),
+        }
+        std::thread::sleep(Duration::from_millis(config.interval_ms.max(MIN_INTERVAL_MS)));
+    });
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// The buffered frame from `seconds_ago` seconds ago, served over the capture
+/// protocol
+#[tauri::command]
+pub fn get_buffered_capture(seconds_ago: f64) -> AppResult<CaptureHandle> {
+    if !seconds_ago.is_finite() || seconds_ago < 0.0 {
+        return Err(AppError::InvalidInput(format!("Invalid capture age: {}", seconds_ago)));
+    }
+    rewind(seconds_ago)
+}
+
+#[tauri::command]
+pub fn get_capture_buffer_config() -> AppResult<CaptureBufferConfig> {
+    Ok(crate::config::get()?.capture_buffer)
+}
+
+/// Save the buffer settings and apply the hotkey right away
+#[tauri::command]
+pub fn set_capture_buffer_config(app: AppHandle, config: CaptureBufferConfig) -> AppResult<()> {
+    if config.seconds == 0 || config.seconds > MAX_SECONDS {
+        return Err(AppError::InvalidInput(format!("Buffer length must be 1-{} seconds", MAX_SECONDS)));
+    }
+    register_hotkey(&app, &config)?;
+    crate::config::update(|c| c.capture_buffer = config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(times: &[i64]) -> VecDeque<BufferedFrame> {
+        times
+            .iter()
+            .map(|&captured_ms| BufferedFrame { captured_ms, width: 1, height: 1, bytes: Vec::new() })
+            .collect()
+    }
+
+    #[test]
+    fn test_prune_keeps_window() {
+        let mut buffer = frames(&[0, 1000, 2000, 3000, 4000]);
+        prune(&mut buffer, 4000, 2);
+        assert_eq!(buffer.iter().map(|f| f.captured_ms).collect::<Vec<_>>(), vec![2000, 3000, 4000]);
+    }
+
+    #[test]
+    fn test_frame_at() {
+        let buffer = frames(&[1000, 2000, 3000, 4000]);
+        assert_eq!(frame_at(&buffer, 4500, 2000).unwrap().captured_ms, 2000);
+        assert_eq!(frame_at(&buffer, 4500, 0).unwrap().captured_ms, 4000);
+        // Older than the buffer reaches: the oldest frame
+        assert_eq!(frame_at(&buffer, 4500, 60_000).unwrap().captured_ms, 1000);
+        assert!(frame_at(&frames(&[]), 4500, 0).is_none());
+    }
+}
@@ -0,0 +1,147 @@
+// Capture protocol
+// Serves encoded captures to the webview over the `screeninu-capture://<id>`
+// custom protocol, so an <img> loads the raw bytes instead of a base64 string
+// that has to be encoded, sent over IPC and decoded again. Only the most
+// recent captures are kept in memory.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tauri::http::{header, Request, Response, StatusCode};
+
+use crate::error::AppResult;
+use crate::events::{self, Pipeline};
+use crate::image_encode::{self, OutputOptions};
+
+/// URI scheme registered with the webview
+pub const CAPTURE_SCHEME: &str = "screeninu-capture";
+
+/// Captures kept before the oldest is dropped
+const MAX_CAPTURES: usize = 4;
+
+static CAPTURES: Lazy<Mutex<VecDeque<StoredCapture>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+struct StoredCapture {
+    id: String,
+    mime_type: &'static str,
+    bytes: Vec<u8>,
+}
+
+/// A capture available over the protocol. The frontend builds its URL with
+/// `convertFileSrc(id, "screeninu-capture")`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureHandle {
+    pub id: String,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Keep an encoded capture for the protocol and return its id
+pub fn store(bytes: Vec<u8>, mime_type: &'static str) -> AppResult<String> {
+    let id = format!("capture-{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    let mut captures = CAPTURES.lock()?;
+    while captures.len() >= MAX_CAPTURES {
+        captures.pop_front();
+    }
+    captures.push_back(StoredCapture {
+        id: id.clone(),
+        mime_type,
+        bytes,
+    });
+    Ok(id)
+}
+
+/// MIME type and bytes of a capture that hasn't been dropped yet
+pub fn get(id: &str) -> Option<(&'static str, Vec<u8>)> {
+    CAPTURES
+        .lock()
+        .ok()
+        .and_then(|captures| captures.iter().find(|c| c.id == id).map(|c| (c.mime_type, c.bytes.clone())))
+}
+
+/// Capture id from a request path ("/capture-3", with or without a query)
+fn capture_id(path: &str) -> &str {
+    path.trim_start_matches('/').split(['?', '#']).next().unwrap_or_default()
+}
+
+/// Protocol handler: the capture's bytes, or 404 once it has been dropped
+pub fn handle(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let found = get(capture_id(request.uri().path()));
+
+    let builder = Response::builder()
+        // Lets the snipping overlay read the pixels back from a canvas
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+    let response = match found {
+        Some((mime_type, bytes)) => builder.header(header::CONTENT_TYPE, mime_type).body(bytes),
+        None => builder.status(StatusCode::NOT_FOUND).body(Vec::new()),
+    };
+    response.unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Capture the primary monitor and serve it over the capture protocol
+#[tauri::command]
+pub async fn capture_full_screen_url(options: Option<OutputOptions>) -> AppResult<CaptureHandle> {
+    use crate::capture::CaptureBackend;
+
+    let run = events::start(Pipeline::Capture, "capture_full_screen_url", None);
+    let result = image_encode::spawn(move || {
+        crate::permissions::ensure_capture_permission()?;
+        let image = crate::capture::XcapBackend::new(0).capture_frame()?;
+        let options = options.unwrap_or_default();
+        let (width, height) = image.dimensions();
+        let bytes = image_encode::encode(image, &options)?;
+        let mime_type = options.format.mime_type();
+        Ok(CaptureHandle {
+            id: store(bytes, mime_type)?,
+            mime_type: mime_type.to_string(),
+            width,
+            height,
+        })
+    })
+    .await;
+    run.finish(result)
+}
+
+/// Drop a capture the frontend no longer shows
+#[tauri::command]
+pub fn release_capture(id: String) -> AppResult<()> {
+    CAPTURES.lock()?.retain(|c| c.id != id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_id() {
+        assert_eq!(capture_id("/capture-3"), "capture-3");
+        assert_eq!(capture_id("/capture-3?t=1"), "capture-3");
+        assert_eq!(capture_id(""), "");
+    }
+
+    #[test]
+    fn test_serves_and_evicts_captures() {
+        let first = store(vec![1, 2, 3], "image/png").unwrap();
+        let request = |id: &str| Request::builder().uri(format!("screeninu-capture://localhost/{}", id)).body(Vec::new()).unwrap();
+
+        let response = handle(&request(&first));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), &vec![1, 2, 3]);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+
+        for _ in 0..MAX_CAPTURES {
+            store(Vec::new(), "image/png").unwrap();
+        }
+        assert_eq!(handle(&request(&first)).status(), StatusCode::NOT_FOUND);
+    }
+}
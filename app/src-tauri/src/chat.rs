@@ -0,0 +1,377 @@
+// Chat forwarding
+// Posts OCR text, and optionally the screenshot, to a Slack or Discord
+// webhook or a Telegram bot chat. Targets are named in the config and used by
+// the `forward` post-OCR action, so each hotkey binding can send to its own
+// channel. Slack incoming webhooks only take text, so images are skipped
+// there; text over a provider's message limit is sent as a file (Discord) or
+// split into several messages (Telegram). A target's webhook URL or bot
+// token is kept in the OS keychain, never in the config file.
+
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::blocking::{RequestBuilder, Response};
+
+use crate::error::{AppError, AppResult};
+
+const USER_AGENT: &str = concat!("ScreenInu/", env!("CARGO_PKG_VERSION"));
+const TELEGRAM_API: &str = "https://api.telegram.org";
+
+/// Message length limits, in characters
+const DISCORD_CONTENT_LIMIT: usize = 2000;
+const TELEGRAM_TEXT_LIMIT: usize = 4096;
+const TELEGRAM_CAPTION_LIMIT: usize = 1024;
+
+/// Chat services
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatProvider {
+    Slack,
+    Discord,
+    Telegram,
+}
+
+impl ChatProvider {
+    fn name(self) -> &'static str {
+        match self {
+            ChatProvider::Slack => "Slack",
+            ChatProvider::Discord => "Discord",
+            ChatProvider::Telegram => "Telegram",
+        }
+    }
+
+    /// What the target's keychain secret holds
+    fn secret_name(self) -> &'static str {
+        match self {
+            ChatProvider::Slack => "Slack webhook URL",
+            ChatProvider::Discord => "Discord webhook URL",
+            ChatProvider::Telegram => "Telegram bot token",
+        }
+    }
+}
+
+/// A channel to forward to
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatTarget {
+    pub id: String,
+    pub provider: ChatProvider,
+    // The webhook URL (Slack and Discord) and bot token (Telegram) are
+    // accepted from the frontend and from configs written before they moved
+    // to the keychain; `move_secrets_to_keychain` moves them there and they
+    // are never written back or returned
+    #[serde(default, skip_serializing)]
+    pub webhook_url: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub bot_token: Option<String>,
+    #[serde(default)]
+    pub chat_id: Option<String>,
+    /// Send the screenshot along with the text
+    #[serde(default)]
+    pub include_image: bool,
+}
+
+/// Chat forwarding settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChatConfig {
+    pub targets: Vec<ChatTarget>,
+}
+
+impl ChatConfig {
+    pub fn target(&self, id: &str) -> AppResult<&ChatTarget> {
+        self.targets
+            .iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown chat target: {}", id)))
+    }
+}
+
+fn require<'a>(value: &'a Option<String>, name: &str) -> AppResult<&'a str> {
+    value
+        .as_deref()
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| AppError::InvalidInput(format!("{} is not configured", name)))
+}
+
+/// Store a target's webhook URL or bot token in the keychain; an empty value
+/// removes it
+fn store_secret(target_id: &str, value: &str) -> AppResult<()> {
+    crate::secrets::store(&format!("chat-secret-{}", target_id), value.trim())
+}
+
+fn load_secret(target_id: &str) -> AppResult<Option<String>> {
+    crate::secrets::load(&format!("chat-secret-{}", target_id))
+}
+
+fn require_secret(target: &ChatTarget) -> AppResult<String> {
+    let value = load_secret(&target.id)?;
+    require(&value, target.provider.secret_name()).map(str::to_string)
+}
+
+/// Hand plaintext secrets found in `config` to `store` and clear them. Only
+/// the field the target's provider uses is kept. Returns whether any were
+/// found.
+fn take_secrets(config: &mut ChatConfig, mut store: impl FnMut(&str, &str) -> AppResult<()>) -> AppResult<bool> {
+    let mut found = false;
+    for target in &mut config.targets {
+        let webhook_url = target.webhook_url.take();
+        let bot_token = target.bot_token.take();
+        let secret = match target.provider {
+            ChatProvider::Slack | ChatProvider::Discord => webhook_url,
+            ChatProvider::Telegram => bot_token,
+        };
+        if let Some(value) = secret {
+            store(&target.id, &value)?;
+            found = true;
+        }
+    }
+    Ok(found)
+}
+
+/// Move plaintext secrets in `config` to the keychain
+pub fn move_secrets_to_keychain(config: &mut ChatConfig) -> AppResult<()> {
+    take_secrets(config, store_secret).map(|_| ())
+}
+
+/// Move secrets left in the config file by older versions to the keychain
+pub fn init() {
+    let result = crate::config::get().and_then(|config| {
+        let mut chat = config.chat;
+        if take_secrets(&mut chat, store_secret)? {
+            crate::config::update(|c| c.chat = chat)?;
+            tracing::info!("Moved chat credentials to the keychain");
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        tracing::warn!("Chat credentials not moved to the keychain: {}", e);
+    }
+}
+
+/// `text` in pieces of at most `limit` characters, split at line breaks
+/// where possible
+fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for line in text.split_inclusive('\n') {
+        if current.chars().count() + line.chars().count() > limit && !current.is_empty() {
+            pieces.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        // A single line over the limit is cut
+        while current.chars().count() > limit {
+            let cut = current.char_indices().nth(limit).map_or(current.len(), |(i, _)| i);
+            pieces.push(current[..cut].to_string());
+            current = current[cut..].to_string();
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+fn image_part(bytes: &[u8], name: &str) -> AppResult<Part> {
+    let (mime_type, extension) = match image::guess_format(bytes) {
+        Ok(image::ImageFormat::Jpeg) => ("image/jpeg", "jpg"),
+        Ok(image::ImageFormat::WebP) => ("image/webp", "webp"),
+        _ => ("image/png", "png"),
+    };
+    Part::bytes(bytes.to_vec())
+        .file_name(format!("{}.{}", name, extension))
+        .mime_str(mime_type)
+        .map_err(|e| AppError::Other(format!("Invalid image type: {}", e)))
+}
+
+fn send(request: RequestBuilder, provider: ChatProvider) -> AppResult<Response> {
+    let response = request
+        .header("User-Agent", USER_AGENT)
+        .send()
+        // The URL holds the webhook secret or bot token
+        .map_err(|e| AppError::Other(format!("{} request failed: {}", provider.name(), e.without_url())))?;
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        Err(AppError::Other(format!("{} post failed: HTTP {} {}", provider.name(), status, body)))
+    }
+}
+
+fn post_slack(url: &str, text: &str) -> AppResult<()> {
+    let request = reqwest::blocking::Client::new()
+        .post(url)
+        .json(&serde_json::json!({ "text": text }));
+    send(request, ChatProvider::Slack)?;
+    Ok(())
+}
+
+fn post_discord(url: &str, text: &str, image: Option<&[u8]>) -> AppResult<()> {
+    let long = text.chars().count() > DISCORD_CONTENT_LIMIT;
+    let mut form = Form::new();
+    let mut files = 0;
+    if long {
+        let part = Part::text(text.to_string())
+            .file_name("capture.txt")
+            .mime_str("text/plain")
+            .map_err(|e| AppError::Other(format!("Invalid text type: {}", e)))?;
+        form = form.part("files[0]", part);
+        files += 1;
+    }
+    if let Some(image) = image {
+        form = form.part(format!("files[{}]", files), image_part(image, "capture")?);
+    }
+    let content = if long { "" } else { text };
+    let form = form.text("payload_json", serde_json::json!({ "content": content }).to_string());
+    send(reqwest::blocking::Client::new().post(url).multipart(form), ChatProvider::Discord)?;
+    Ok(())
+}
+
+fn post_telegram(target: &ChatTarget, token: &str, text: &str, image: Option<&[u8]>) -> AppResult<()> {
+    let chat_id = require(&target.chat_id, "Telegram chat ID")?;
+    let client = reqwest::blocking::Client::new();
+    let method = |name: &str| format!("{}/bot{}/{}", TELEGRAM_API, token, name);
+
+    let mut rest = text;
+    if let Some(image) = image {
+        // Short text goes in the caption, longer text follows the photo
+        let caption = if text.chars().count() <= TELEGRAM_CAPTION_LIMIT { text } else { "" };
+        let form = Form::new()
+            .text("chat_id", chat_id.to_string())
+            .text("caption", caption.to_string())
+            .part("photo", image_part(image, "capture")?);
+        send(client.post(method("sendPhoto")).multipart(form), ChatProvider::Telegram)?;
+        if !caption.is_empty() {
+            rest = "";
+        }
+    }
+    for piece in split_message(rest, TELEGRAM_TEXT_LIMIT) {
+        let request = client
+            .post(method("sendMessage"))
+            .json(&serde_json::json!({ "chat_id": chat_id, "text": piece }));
+        send(request, ChatProvider::Telegram)?;
+    }
+    Ok(())
+}
+
+/// Post `text` (and `image` when the target includes images) to a target
+pub fn forward(target: &ChatTarget, text: &str, image: Option<&[u8]>) -> AppResult<()> {
+    let image = image.filter(|_| target.include_image);
+    let secret = require_secret(target)?;
+    match target.provider {
+        ChatProvider::Slack => post_slack(&secret, text),
+        ChatProvider::Discord => post_discord(&secret, text, image),
+        ChatProvider::Telegram => post_telegram(target, &secret, text, image),
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_chat_config() -> AppResult<ChatConfig> {
+    Ok(crate::config::get()?.chat)
+}
+
+#[tauri::command]
+pub fn set_chat_config(mut config: ChatConfig) -> AppResult<()> {
+    let mut ids = std::collections::HashSet::new();
+    for target in &config.targets {
+        if target.id.trim().is_empty() || !ids.insert(target.id.as_str()) {
+            return Err(AppError::InvalidInput(format!("Chat target ids must be unique: {:?}", target.id)));
+        }
+    }
+    move_secrets_to_keychain(&mut config)?;
+    // Secrets of removed targets would otherwise be picked up by a new
+    // target reusing the id
+    for old in crate::config::get()?.chat.targets {
+        if config.target(&old.id).is_err() {
+            store_secret(&old.id, "")?;
+        }
+    }
+    crate::config::update(|c| c.chat = config)
+}
+
+/// Store a target's webhook URL or bot token in the keychain; `None` removes it
+#[tauri::command]
+pub fn set_chat_secret(target_id: String, value: Option<String>) -> AppResult<()> {
+    store_secret(&target_id, value.as_deref().unwrap_or_default())
+}
+
+/// Whether a webhook URL or bot token is stored for the target
+#[tauri::command]
+pub fn has_chat_secret(target_id: String) -> AppResult<bool> {
+    Ok(load_secret(&target_id)?.is_some())
+}
+
+/// Post text, and the capture when given, to a configured chat target
+#[tauri::command(async)]
+#[tracing::instrument(skip(text, base64_image), err)]
+pub fn forward_to_chat(
+    target_id: String,
+    text: String,
+    capture_id: Option<String>,
+    base64_image: Option<String>,
+) -> AppResult<()> {
+    let config = crate::config::get()?.chat;
+    let image = match (capture_id, base64_image) {
+        (Some(id), _) => crate::capture_protocol::get(&id).map(|(_, bytes)| bytes),
+        (None, Some(base64_image)) => Some(crate::ocr::decode_base64_image(&base64_image)?),
+        (None, None) => None,
+    };
+    forward(config.target(&target_id)?, &text, image.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_message() {
+        assert_eq!(split_message("short", 10), vec!["short"]);
+        assert_eq!(split_message("one\ntwo\nthree", 8), vec!["one\ntwo\n", "three"]);
+        assert_eq!(split_message("あいうえおか", 4), vec!["あいうえ", "おか"]);
+        assert!(split_message("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_config_target() {
+        let config: ChatConfig = serde_json::from_str(
+            r#"{"targets": [{"id": "team", "provider": "discord", "webhookUrl": "https://discord.test/x"}]}"#,
+        )
+        .unwrap();
+        let target = config.target("team").unwrap();
+        assert_eq!(target.provider, ChatProvider::Discord);
+        assert!(!target.include_image);
+        assert!(config.target("other").is_err());
+    }
+
+    #[test]
+    fn test_secrets_stay_out_of_the_config() {
+        let json = r#"{"targets": [
+            {"id": "team", "provider": "slack", "webhookUrl": "https://hooks.slack.test/T0/secret"},
+            {"id": "bot", "provider": "telegram", "botToken": "123:secret", "webhookUrl": "stale", "chatId": "42"}
+        ]}"#;
+        let mut config: ChatConfig = serde_json::from_str(json).unwrap();
+        let saved = serde_json::to_string(&config).unwrap();
+        assert!(!saved.contains("secret") && !saved.contains("stale"));
+
+        let mut stored = Vec::new();
+        let found = take_secrets(&mut config, |id, value| {
+            stored.push((id.to_string(), value.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert!(found);
+        assert_eq!(
+            stored,
+            vec![
+                ("team".to_string(), "https://hooks.slack.test/T0/secret".to_string()),
+                ("bot".to_string(), "123:secret".to_string()),
+            ]
+        );
+        assert!(config.targets.iter().all(|t| t.webhook_url.is_none() && t.bot_token.is_none()));
+        assert_eq!(config.target("bot").unwrap().chat_id.as_deref(), Some("42"));
+        assert!(!take_secrets(&mut config, |_, _| unreachable!()).unwrap());
+    }
+}
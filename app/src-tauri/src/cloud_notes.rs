@@ -0,0 +1,455 @@
+// Notion and OneNote export
+// Pushes a history item (text, translation and screenshot) to a Notion
+// database as a new page, or to a OneNote section through Microsoft Graph.
+// The API tokens (a Notion integration token, a Graph access token with
+// Notes.Create) live in the OS keychain, never in config.json. Both APIs
+// answer 429 when rate limited; requests are retried after Retry-After, or
+// with exponential backoff when it is missing.
+
+use std::time::Duration;
+
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::StatusCode;
+
+use crate::error::{AppError, AppResult};
+use crate::history::HistoryItem;
+
+const USER_AGENT: &str = concat!("ScreenInu/", env!("CARGO_PKG_VERSION"));
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+const GRAPH_API: &str = "https://graph.microsoft.com/v1.0";
+
+/// Notion limits a rich text object to 2000 characters and a request to 100 blocks
+const NOTION_TEXT_LIMIT: usize = 2000;
+const NOTION_BLOCK_LIMIT: usize = 100;
+
+/// Page titles are the first line, cut to this many characters
+const TITLE_CHARS: usize = 80;
+
+/// Longest wait between retries, whatever Retry-After says
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Export destinations
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CloudNotesConfig {
+    pub notion_database_id: Option<String>,
+    pub onenote_section_id: Option<String>,
+    /// Retries of a rate-limited request before giving up
+    pub max_retries: u32,
+}
+
+impl Default for CloudNotesConfig {
+    fn default() -> Self {
+        Self {
+            notion_database_id: None,
+            onenote_section_id: None,
+            max_retries: 3,
+        }
+    }
+}
+
+/// Export providers
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotesProvider {
+    Notion,
+    OneNote,
+}
+
+impl NotesProvider {
+    fn name(self) -> &'static str {
+        match self {
+            NotesProvider::Notion => "Notion",
+            NotesProvider::OneNote => "OneNote",
+        }
+    }
+
+    fn keyring_user(self) -> &'static str {
+        match self {
+            NotesProvider::Notion => "notion-token",
+            NotesProvider::OneNote => "onenote-token",
+        }
+    }
+}
+
+/// A history item with what goes along with it
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesExport {
+    pub item: HistoryItem,
+    #[serde(default)]
+    pub translation: Option<String>,
+    /// The screenshot, by capture id or as base64; text only when unset
+    #[serde(default)]
+    pub capture_id: Option<String>,
+    #[serde(default)]
+    pub base64_image: Option<String>,
+}
+
+/// Result of an export
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NotesExportResult {
+    pub provider: NotesProvider,
+    /// Link to the created page
+    pub url: Option<String>,
+}
+
+struct Image {
+    bytes: Vec<u8>,
+    mime_type: &'static str,
+    file_name: String,
+}
+
+fn load_token(provider: NotesProvider) -> AppResult<String> {
+    crate::secrets::load(provider.keyring_user())?
+        .ok_or_else(|| AppError::InvalidInput(format!("{} token is not set", provider.name())))
+}
+
+fn require<'a>(value: &'a Option<String>, name: &str) -> AppResult<&'a str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| AppError::InvalidInput(format!("{} is not configured", name)))
+}
+
+fn title(item: &HistoryItem) -> String {
+    let line = item.text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("Screen Inu capture");
+    match line.char_indices().nth(TITLE_CHARS) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+/// How long to wait before retry number `attempt` (from 0)
+fn retry_delay(retry_after: Option<&str>, attempt: u32) -> Duration {
+    retry_after
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or_else(|| Duration::from_secs(1 << attempt.min(5)))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Send the request `build` makes, again after a wait while rate limited
+fn send_with_retry(
+    provider: NotesProvider,
+    max_retries: u32,
+    build: impl Fn() -> AppResult<RequestBuilder>,
+) -> AppResult<Response> {
+    let mut attempt = 0;
+    loop {
+        let response = build()?
+            .header("User-Agent", USER_AGENT)
+            .send()
+            .map_err(|e| AppError::Other(format!("{} request failed: {}", provider.name(), e)))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let limited = status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE;
+        if !limited || attempt >= max_retries {
+            let body = response.text().unwrap_or_default();
+            return Err(AppError::Other(format!("{} export failed: HTTP {} {}", provider.name(), status, body)));
+        }
+        let retry_after = response.headers().get("Retry-After").and_then(|v| v.to_str().ok());
+        let delay = retry_delay(retry_after, attempt);
+        tracing::debug!("{} rate limited, retrying in {:?}", provider.name(), delay);
+        std::thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+fn json(response: Response, provider: NotesProvider) -> AppResult<serde_json::Value> {
+    response
+        .json()
+        .map_err(|e| AppError::Other(format!("Unexpected {} response: {}", provider.name(), e)))
+}
+
+fn export_image(export: &NotesExport) -> AppResult<Option<Image>> {
+    let bytes = if let Some(id) = &export.capture_id {
+        crate::capture_protocol::get(id)
+            .map(|(_, bytes)| bytes)
+            .ok_or_else(|| AppError::InvalidInput(format!("Capture {} is no longer available", id)))?
+    } else if let Some(base64_image) = &export.base64_image {
+        crate::ocr::decode_base64_image(base64_image)?
+    } else {
+        return Ok(None);
+    };
+    let (mime_type, extension) = match image::guess_format(&bytes) {
+        Ok(image::ImageFormat::Jpeg) => ("image/jpeg", "jpg"),
+        Ok(image::ImageFormat::Gif) => ("image/gif", "gif"),
+        Ok(image::ImageFormat::WebP) => ("image/webp", "webp"),
+        _ => ("image/png", "png"),
+    };
+    Ok(Some(Image {
+        bytes,
+        mime_type,
+        file_name: format!("capture-{}.{}", export.item.id, extension),
+    }))
+}
+
+// ---- Notion ----
+
+/// Text split into rich text objects under Notion's length limit
+fn notion_rich_text(text: &str) -> Vec<serde_json::Value> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(NOTION_TEXT_LIMIT)
+        .map(|chunk| serde_json::json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect()
+}
+
+fn notion_block(kind: &str, text: &str) -> serde_json::Value {
+    serde_json::json!({ "object": "block", "type": kind, kind: { "rich_text": notion_rich_text(text) } })
+}
+
+/// Page content: a paragraph per paragraph of text, then the translation as a quote
+fn notion_blocks(text: &str, translation: Option<&str>) -> Vec<serde_json::Value> {
+    let mut blocks: Vec<serde_json::Value> = text
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| notion_block("paragraph", p))
+        .collect();
+    if let Some(translation) = translation.map(str::trim).filter(|t| !t.is_empty()) {
+        blocks.push(notion_block("quote", translation));
+    }
+    blocks
+}
+
+/// Name of the database's title property, which differs per database
+fn notion_title_property(token: &str, database_id: &str, max_retries: u32) -> AppResult<String> {
+    let response = send_with_retry(NotesProvider::Notion, max_retries, || {
+        Ok(reqwest::blocking::Client::new()
+            .get(format!("{}/databases/{}", NOTION_API, database_id))
+            .bearer_auth(token)
+            .header("Notion-Version", NOTION_VERSION))
+    })?;
+    let database = json(response, NotesProvider::Notion)?;
+    database["properties"]
+        .as_object()
+        .and_then(|properties| properties.iter().find(|(_, p)| p["type"] == "title"))
+        .map(|(name, _)| name.clone())
+        .ok_or_else(|| AppError::Other("Notion database has no title property".to_string()))
+}
+
+/// Upload an image for use in a block; returns the file upload id
+fn notion_upload(token: &str, image: &Image, max_retries: u32) -> AppResult<String> {
+    let client = reqwest::blocking::Client::new();
+    let response = send_with_retry(NotesProvider::Notion, max_retries, || {
+        Ok(client
+            .post(format!("{}/file_uploads", NOTION_API))
+            .bearer_auth(token)
+            .header("Notion-Version", NOTION_VERSION)
+            .json(&serde_json::json!({ "filename": image.file_name, "content_type": image.mime_type })))
+    })?;
+    let id = json(response, NotesProvider::Notion)?["id"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Other("Notion file upload has no id".to_string()))?;
+
+    send_with_retry(NotesProvider::Notion, max_retries, || {
+        let part = Part::bytes(image.bytes.clone())
+            .file_name(image.file_name.clone())
+            .mime_str(image.mime_type)
+            .map_err(|e| AppError::Other(format!("Invalid image type: {}", e)))?;
+        Ok(client
+            .post(format!("{}/file_uploads/{}/send", NOTION_API, id))
+            .bearer_auth(token)
+            .header("Notion-Version", NOTION_VERSION)
+            .multipart(Form::new().part("file", part)))
+    })?;
+    Ok(id)
+}
+
+fn export_notion(config: &CloudNotesConfig, export: &NotesExport) -> AppResult<Option<String>> {
+    let database_id = require(&config.notion_database_id, "Notion database")?;
+    let token = load_token(NotesProvider::Notion)?;
+    let title_property = notion_title_property(&token, database_id, config.max_retries)?;
+
+    let mut blocks = notion_blocks(&export.item.text, export.translation.as_deref());
+    if let Some(image) = export_image(export)? {
+        let id = notion_upload(&token, &image, config.max_retries)?;
+        blocks.push(serde_json::json!({
+            "object": "block",
+            "type": "image",
+            "image": { "type": "file_upload", "file_upload": { "id": id } },
+        }));
+    }
+    let mut batches = blocks.chunks(NOTION_BLOCK_LIMIT);
+    let first: Vec<_> = batches.next().map(|b| b.to_vec()).unwrap_or_default();
+
+    let client = reqwest::blocking::Client::new();
+    let page = serde_json::json!({
+        "parent": { "database_id": database_id },
+        "properties": { title_property: { "title": notion_rich_text(&title(&export.item)) } },
+        "children": first,
+    });
+    let response = send_with_retry(NotesProvider::Notion, config.max_retries, || {
+        Ok(client
+            .post(format!("{}/pages", NOTION_API))
+            .bearer_auth(&token)
+            .header("Notion-Version", NOTION_VERSION)
+            .json(&page))
+    })?;
+    let page = json(response, NotesProvider::Notion)?;
+
+    // Blocks past the first hundred are appended to the page
+    if let Some(page_id) = page["id"].as_str() {
+        for batch in batches {
+            send_with_retry(NotesProvider::Notion, config.max_retries, || {
+                Ok(client
+                    .patch(format!("{}/blocks/{}/children", NOTION_API, page_id))
+                    .bearer_auth(&token)
+                    .header("Notion-Version", NOTION_VERSION)
+                    .json(&serde_json::json!({ "children": batch })))
+            })?;
+        }
+    }
+    Ok(page["url"].as_str().map(|s| s.to_string()))
+}
+
+// ---- OneNote ----
+
+fn html_text(text: &str) -> String {
+    text.lines()
+        .map(crate::ocr_formats::escape_xml)
+        .collect::<Vec<_>>()
+        .join("<br/>")
+}
+
+/// The page as OneNote's create-page HTML; the image is the "capture" part
+fn onenote_html(export: &NotesExport, with_image: bool) -> String {
+    let created = chrono::DateTime::from_timestamp_millis(export.item.timestamp)
+        .unwrap_or_else(chrono::Utc::now)
+        .to_rfc3339();
+    let mut body = format!("<p>{}</p>", html_text(&export.item.text));
+    if let Some(translation) = export.translation.as_deref().filter(|t| !t.trim().is_empty()) {
+        body.push_str(&format!("<blockquote>{}</blockquote>", html_text(translation)));
+    }
+    if with_image {
+        body.push_str("<img src=\"name:capture\" alt=\"Screenshot\" />");
+    }
+    format!(
+        "<!DOCTYPE html><html><head><title>{}</title><meta name=\"created\" content=\"{}\" /></head><body>{}</body></html>",
+        crate::ocr_formats::escape_xml(&title(&export.item)),
+        created,
+        body
+    )
+}
+
+fn export_onenote(config: &CloudNotesConfig, export: &NotesExport) -> AppResult<Option<String>> {
+    let section_id = require(&config.onenote_section_id, "OneNote section")?;
+    let token = load_token(NotesProvider::OneNote)?;
+    let image = export_image(export)?;
+    let html = onenote_html(export, image.is_some());
+
+    let client = reqwest::blocking::Client::new();
+    let response = send_with_retry(NotesProvider::OneNote, config.max_retries, || {
+        let presentation = Part::text(html.clone())
+            .mime_str("text/html")
+            .map_err(|e| AppError::Other(format!("Invalid page type: {}", e)))?;
+        let mut form = Form::new().part("Presentation", presentation);
+        if let Some(image) = &image {
+            let part = Part::bytes(image.bytes.clone())
+                .file_name(image.file_name.clone())
+                .mime_str(image.mime_type)
+                .map_err(|e| AppError::Other(format!("Invalid image type: {}", e)))?;
+            form = form.part("capture", part);
+        }
+        Ok(client
+            .post(format!("{}/me/onenote/sections/{}/pages", GRAPH_API, section_id))
+            .bearer_auth(&token)
+            .multipart(form))
+    })?;
+    let page = json(response, NotesProvider::OneNote)?;
+    Ok(page["links"]["oneNoteWebUrl"]["href"].as_str().map(|s| s.to_string()))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_cloud_notes_config() -> AppResult<CloudNotesConfig> {
+    Ok(crate::config::get()?.cloud_notes)
+}
+
+#[tauri::command]
+pub fn set_cloud_notes_config(config: CloudNotesConfig) -> AppResult<()> {
+    crate::config::update(|c| c.cloud_notes = config)
+}
+
+/// Store a provider's API token in the keychain; `None` removes it
+#[tauri::command]
+pub fn set_notes_token(provider: NotesProvider, token: Option<String>) -> AppResult<()> {
+    crate::secrets::store(provider.keyring_user(), token.as_deref().map_or("", str::trim))
+}
+
+/// Whether a token is stored for `provider`
+#[tauri::command]
+pub fn has_notes_token(provider: NotesProvider) -> AppResult<bool> {
+    Ok(crate::secrets::load(provider.keyring_user())?.is_some())
+}
+
+/// Create a page for a history item in the configured Notion database or
+/// OneNote section
+#[tauri::command(async)]
+#[tracing::instrument(skip(export), fields(item = %export.item.id), err)]
+pub fn export_to_notes(provider: NotesProvider, export: NotesExport) -> AppResult<NotesExportResult> {
+    let config = crate::config::get()?.cloud_notes;
+    let url = match provider {
+        NotesProvider::Notion => export_notion(&config, &export)?,
+        NotesProvider::OneNote => export_onenote(&config, &export)?,
+    };
+    Ok(NotesExportResult { provider, url })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(text: &str, translation: Option<&str>) -> NotesExport {
+        NotesExport {
+            item: HistoryItem {
+                id: "a1".to_string(),
+                text: text.to_string(),
+                lang: "eng".to_string(),
+                timestamp: 0,
+                source_app: None,
+                summary: None,
+                tags: Vec::new(),
+            },
+            translation: translation.map(|t| t.to_string()),
+            capture_id: None,
+            base64_image: None,
+        }
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(Some("7"), 0), Duration::from_secs(7));
+        assert_eq!(retry_delay(Some("3600"), 0), MAX_RETRY_DELAY);
+        // HTTP dates and missing headers back off exponentially
+        assert_eq!(retry_delay(Some("Wed, 21 Oct 2026 07:28:00 GMT"), 2), Duration::from_secs(4));
+        assert_eq!(retry_delay(None, 0), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_page_content() {
+        let long = "x".repeat(NOTION_TEXT_LIMIT + 1);
+        let blocks = notion_blocks(&format!("First\n\n{}", long), Some("Translated"));
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["paragraph"]["rich_text"][0]["text"]["content"], "First");
+        assert_eq!(blocks[1]["paragraph"]["rich_text"].as_array().unwrap().len(), 2);
+        assert_eq!(blocks[2]["type"], "quote");
+
+        let html = onenote_html(&export("a < b\nc", Some("译")), true);
+        assert!(html.contains("<title>a &lt; b</title>"));
+        assert!(html.contains("<p>a &lt; b<br/>c</p><blockquote>译</blockquote><img src=\"name:capture\""));
+        assert_eq!(title(&export(&"y".repeat(100), None).item).chars().count(), TITLE_CHARS + 1);
+    }
+}
@@ -0,0 +1,100 @@
+// Code mode
+// OCR of source code (video tutorials, VM screenshots) keeping indentation,
+// with an optional guess of the programming language for syntax tagging.
+
+use once_cell::sync::Lazy;
+use syntect::parsing::SyntaxSet;
+
+use crate::error::AppResult;
+use crate::ocr::{self, OcrOptions};
+
+static SYNTAXES: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+
+/// Characteristic tokens per file extension, for snippets without a shebang
+/// or modeline (which is all syntect can detect from content)
+const TOKENS: &[(&str, &[&str])] = &[
+    ("rs", &["fn ", "let mut ", "impl ", "pub fn", "&self", "-> ", "use crate::", "match "]),
+    ("py", &["def ", "import ", "self.", "elif ", "print(", "__init__", "None", "):\n"]),
+    ("ts", &["interface ", ": string", ": number", "export type", "import type", "=> "]),
+    ("js", &["const ", "function ", "=> ", "console.log", "===", "require(", "let "]),
+    ("go", &["func ", "package ", ":= ", "fmt.", "err != nil"]),
+    ("java", &["public class", "System.out", "private ", "@Override", "void "]),
+    ("cs", &["using System", "namespace ", "Console.Write", "public class"]),
+    ("cpp", &["#include", "std::", "int main", "cout <<", "nullptr"]),
+    ("c", &["#include", "printf(", "int main", "malloc(", "NULL"]),
+    ("rb", &["puts ", "require '", "\nend", "do |", "attr_accessor"]),
+    ("sh", &["#!/bin", "echo ", "\nfi", "then\n", "$(", "export "]),
+    ("html", &["<div", "</", "<html", "class=\"", "<span"]),
+    ("sql", &["SELECT ", "FROM ", "WHERE ", "INSERT INTO", "JOIN "]),
+];
+
+/// Code OCR result
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeOcrResult {
+    pub text: String,
+    /// Syntax name (e.g. "Rust", "Python"), if detected
+    pub language: Option<String>,
+}
+
+/// Most likely file extension by counting characteristic tokens
+fn guess_extension(code: &str) -> Option<&'static str> {
+    TOKENS
+        .iter()
+        .map(|(ext, tokens)| (*ext, tokens.iter().filter(|t| code.contains(*t)).count()))
+        // Earlier entries win ties (max_by_key returns the last maximum)
+        .rev()
+        .max_by_key(|(_, score)| *score)
+        .filter(|(_, score)| *score >= 2)
+        .map(|(ext, _)| ext)
+}
+
+/// Detect the language of a code snippet
+pub fn detect_language(code: &str) -> Option<String> {
+    SYNTAXES
+        .find_syntax_by_first_line(code)
+        .or_else(|| guess_extension(code).and_then(|ext| SYNTAXES.find_syntax_by_extension(ext)))
+        .map(|syntax| syntax.name.clone())
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// OCR source code, preserving indentation; optionally tag the language
+#[tauri::command]
+pub fn perform_code_ocr(
+    base64_image: &str,
+    langs: Option<String>,
+    engine: Option<String>,
+    detect_language: Option<bool>,
+) -> AppResult<CodeOcrResult> {
+    let bytes = ocr::decode_base64_image(base64_image)?;
+    let options = OcrOptions {
+        langs,
+        engine,
+        code_mode: true,
+        ..Default::default()
+    }
+    .with_reocr_config();
+    let text = ocr::run_ocr(&bytes, &options)?;
+    let language = if detect_language.unwrap_or(true) {
+        self::detect_language(&text)
+    } else {
+        None
+    };
+    Ok(CodeOcrResult { text, language })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess_extension() {
+        assert_eq!(guess_extension("pub fn main() {\n    let mut x = 1;\n}"), Some("rs"));
+        assert_eq!(guess_extension("def f(self):\n    return None"), Some("py"));
+        assert_eq!(guess_extension("#include <stdio.h>\nint main() { printf(\"hi\"); }"), Some("c"));
+        assert_eq!(guess_extension("hello world"), None);
+    }
+}
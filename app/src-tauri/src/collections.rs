@@ -0,0 +1,277 @@
+// History collections
+// Named collections (a project, a book, a game) that history items can be
+// filed in. They are kept in a yrs document logged per device the same way
+// as history edits (see history_edits.rs), under collections/<device>.json,
+// so collections created and filled on different devices merge. The document
+// has two flat maps: `collections` (id to name) and `members`, one
+// "<collection>/<item>" key per membership. Ids include the device, so two
+// devices never create the same collection, and adding or removing one item
+// never touches another's key. Memberships of a deleted collection are
+// ignored, including ones added concurrently on another device.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Runtime};
+use yrs::{Doc, Map, MapRef, Transact, TransactionMut};
+
+use crate::error::{AppError, AppResult};
+use crate::history_edits::{append_entry, apply, encode, read_entries, EditEntry};
+
+pub(crate) const COLLECTIONS_DIR: &str = "collections";
+const COLLECTIONS_MAP: &str = "collections";
+const MEMBERS_MAP: &str = "members";
+/// v1 encoding of an update with no new blocks and an empty delete set
+const EMPTY_UPDATE_V1: &[u8] = &[0, 0];
+
+/// A collection and the ids of the items filed in it
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    pub item_ids: Vec<String>,
+}
+
+fn collections_dir<R: Runtime>(app: &AppHandle<R>) -> AppResult<PathBuf> {
+    Ok(crate::history_edits::logs_root(app)?.join(COLLECTIONS_DIR))
+}
+
+fn member_key(collection_id: &str, item_id: &str) -> String {
+    format!("{}/{}", collection_id, item_id)
+}
+
+fn load(entries: &[EditEntry], client_id: u64) -> AppResult<Doc> {
+    let doc = Doc::with_client_id(client_id);
+    for entry in entries {
+        apply(&doc, entry)?;
+    }
+    Ok(doc)
+}
+
+/// Run `apply_change` on the collections as `device`; returns the entry to store,
+/// or None when nothing changed
+fn change(
+    entries: &[EditEntry],
+    device: u64,
+    timestamp: i64,
+    apply_change: impl FnOnce(&mut TransactionMut, &MapRef, &MapRef),
+) -> AppResult<Option<EditEntry>> {
+    let doc = load(entries, device)?;
+    let collections = doc.get_or_insert_map(COLLECTIONS_MAP);
+    let members = doc.get_or_insert_map(MEMBERS_MAP);
+    let mut txn = doc.transact_mut();
+    apply_change(&mut txn, &collections, &members);
+    // The state vectors only differ after commit and never for removals, so
+    // look at what the transaction would encode: new blocks or deletions
+    let update = txn.encode_update_v1();
+    if update == EMPTY_UPDATE_V1 {
+        return Ok(None);
+    }
+    Ok(Some(EditEntry {
+        timestamp,
+        device,
+        update: encode(update),
+        seen: None,
+    }))
+}
+
+/// The merged collections, by name
+fn read(entries: &[EditEntry]) -> AppResult<Vec<Collection>> {
+    let doc = load(entries, 1)?;
+    let collections_map = doc.get_or_insert_map(COLLECTIONS_MAP);
+    let members_map = doc.get_or_insert_map(MEMBERS_MAP);
+    let txn = doc.transact();
+
+    let mut collections: BTreeMap<String, Collection> = collections_map
+        .iter(&txn)
+        .map(|(id, name)| {
+            let collection = Collection {
+                id: id.to_string(),
+                name: name.to_string(&txn),
+                item_ids: Vec::new(),
+            };
+            (id.to_string(), collection)
+        })
+        .collect();
+    for (key, _) in members_map.iter(&txn) {
+        if let Some((collection_id, item_id)) = key.split_once('/') {
+            if let Some(collection) = collections.get_mut(collection_id) {
+                collection.item_ids.push(item_id.to_string());
+            }
+        }
+    }
+
+    let mut collections: Vec<Collection> = collections.into_values().collect();
+    for collection in &mut collections {
+        collection.item_ids.sort();
+    }
+    collections.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()).then(a.id.cmp(&b.id)));
+    Ok(collections)
+}
+
+fn validate_name(name: &str) -> AppResult<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput("Collection name is empty".to_string()));
+    }
+    Ok(name)
+}
+
+/// Apply a change to the stored collections and return the result
+fn update<R: Runtime>(
+    app: &AppHandle<R>,
+    apply_change: impl FnOnce(&mut TransactionMut, &MapRef, &MapRef),
+) -> AppResult<Vec<Collection>> {
+    let dir = collections_dir(app)?;
+    let mut entries = read_entries(&dir)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    if let Some(entry) = change(&entries, crate::history_edits::device_id()?, now, apply_change)? {
+        append_entry(&dir, entry.clone())?;
+        entries.push(entry);
+    }
+    read(&entries)
+}
+
+fn find(collections: Vec<Collection>, id: &str) -> AppResult<Collection> {
+    collections
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| AppError::InvalidInput(format!("Collection {} not found", id)))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn sync_get_collections(app: AppHandle) -> AppResult<Vec<Collection>> {
+    read(&read_entries(&collections_dir(&app)?)?)
+}
+
+#[tauri::command]
+pub fn sync_create_collection(app: AppHandle, name: String) -> AppResult<Collection> {
+    let name = validate_name(&name)?;
+    let id = format!(
+        "{:x}-{:x}",
+        crate::history_edits::device_id()?,
+        chrono::Utc::now().timestamp_millis()
+    );
+    let collections = update(&app, |txn, collections, _| {
+        collections.insert(txn, id.as_str(), name);
+    })?;
+    find(collections, &id)
+}
+
+#[tauri::command]
+pub fn sync_rename_collection(app: AppHandle, id: String, name: String) -> AppResult<Collection> {
+    let name = validate_name(&name)?;
+    find(sync_get_collections(app.clone())?, &id)?;
+    let collections = update(&app, |txn, collections, _| {
+        collections.insert(txn, id.as_str(), name);
+    })?;
+    find(collections, &id)
+}
+
+/// Delete a collection; its items stay in history
+#[tauri::command]
+pub fn sync_delete_collection(app: AppHandle, id: String) -> AppResult<()> {
+    let prefix = member_key(&id, "");
+    update(&app, |txn, collections, members| {
+        collections.remove(txn, &id);
+        let keys: Vec<String> = members
+            .keys(&*txn)
+            .filter(|key| key.starts_with(&prefix))
+            .map(str::to_string)
+            .collect();
+        for key in keys {
+            members.remove(txn, &key);
+        }
+    })?;
+    Ok(())
+}
+
+/// File items in a collection, or take them out of it with `remove`
+#[tauri::command]
+pub fn sync_assign_to_collection(
+    app: AppHandle,
+    collection_id: String,
+    item_ids: Vec<String>,
+    remove: Option<bool>,
+) -> AppResult<Collection> {
+    find(sync_get_collections(app.clone())?, &collection_id)?;
+    let remove = remove.unwrap_or(false);
+    let collections = update(&app, |txn, _, members| {
+        for item_id in &item_ids {
+            let key = member_key(&collection_id, item_id);
+            if remove {
+                members.remove(txn, &key);
+            } else if !members.contains_key(&*txn, &key) {
+                members.insert(txn, key, true);
+            }
+        }
+    })?;
+    find(collections, &collection_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(entries: &[EditEntry], device: u64, id: &str, name: &str) -> EditEntry {
+        change(entries, device, 0, |txn, collections, _| {
+            collections.insert(txn, id, name);
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    fn assign(entries: &[EditEntry], device: u64, collection_id: &str, item_id: &str) -> EditEntry {
+        change(entries, device, 1, |txn, _, members| {
+            members.insert(txn, member_key(collection_id, item_id), true);
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_concurrent_assignments_merge() {
+        let book = create(&[], 1, "1-a", "Book");
+        // Both devices file a different page in the same collection
+        let a = assign(&[book.clone()], 1, "1-a", "100");
+        let b = assign(&[book.clone()], 2, "1-a", "200");
+        let game = create(&[book.clone()], 2, "2-b", "game");
+        let entries = vec![book, a, b, game];
+
+        let collections = read(&entries).unwrap();
+        assert_eq!(collections.len(), 2);
+        assert_eq!(collections[0].name, "Book");
+        assert_eq!(collections[0].item_ids, vec!["100", "200"]);
+        assert!(collections[1].item_ids.is_empty());
+    }
+
+    #[test]
+    fn test_deleted_collection_drops_members() {
+        let book = create(&[], 1, "1-a", "Book");
+        let delete = change(&[book.clone()], 1, 1, |txn, collections, _| {
+            collections.remove(txn, "1-a");
+        })
+        .unwrap()
+        .unwrap();
+        // Assigned on another device before it saw the deletion
+        let late = assign(&[book.clone()], 2, "1-a", "100");
+        assert!(read(&[book, delete, late]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unchanged_collections_store_nothing() {
+        let book = create(&[], 1, "1-a", "Book");
+        // Removing a key that isn't there
+        let noop = change(&[book.clone()], 1, 1, |txn, collections, _| {
+            collections.remove(txn, "2-b");
+        })
+        .unwrap();
+        assert!(noop.is_none());
+        assert!(change(&[book], 1, 1, |_, _, _| {}).unwrap().is_none());
+    }
+}
@@ -0,0 +1,148 @@
+// Color picker
+// Pixel inspection and dominant-color palettes from screen captures.
+
+use image::RgbaImage;
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::error::AppResult;
+use crate::frame_diff::Region;
+
+/// Palette size when the caller doesn't ask for one
+const DEFAULT_PALETTE_SIZE: usize = 6;
+
+/// Upper bound on pixels inspected for a palette; larger regions are sampled
+const MAX_PALETTE_SAMPLES: u64 = 250_000;
+
+/// A color in the notations designers use
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ColorInfo {
+    /// "#rrggbb"
+    pub hex: String,
+    pub rgb: [u8; 3],
+    /// Hue in degrees (0-360), saturation and lightness in percent (0-100)
+    pub hsl: [f32; 3],
+}
+
+/// A dominant color and the share of the region it covers
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PaletteColor {
+    pub color: ColorInfo,
+    /// 0.0-1.0
+    pub share: f32,
+}
+
+fn rgb_to_hsl([r, g, b]: [u8; 3]) -> [f32; 3] {
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return [0.0, 0.0, lightness * 100.0];
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    [hue, saturation * 100.0, lightness * 100.0]
+}
+
+impl ColorInfo {
+    pub fn from_rgb(rgb: [u8; 3]) -> Self {
+        Self {
+            hex: format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2]),
+            rgb,
+            hsl: rgb_to_hsl(rgb),
+        }
+    }
+}
+
+/// Dominant colors: pixels are bucketed by their top 4 bits per channel and
+/// each bucket reports its average color, most common first
+pub fn palette(image: &RgbaImage, count: usize) -> Vec<PaletteColor> {
+    let total = image.width() as u64 * image.height() as u64;
+    if total == 0 {
+        return Vec::new();
+    }
+    let step = ((total / MAX_PALETTE_SAMPLES) as f64).sqrt().ceil().max(1.0) as usize;
+
+    // Per bucket: pixel count and channel sums
+    let mut buckets = vec![(0u64, [0u64; 3]); 4096];
+    let mut sampled = 0u64;
+    for y in (0..image.height()).step_by(step) {
+        for x in (0..image.width()).step_by(step) {
+            let [r, g, b, _] = image.get_pixel(x, y).0;
+            let index = (r as usize >> 4) << 8 | (g as usize >> 4) << 4 | b as usize >> 4;
+            let bucket = &mut buckets[index];
+            bucket.0 += 1;
+            bucket.1[0] += r as u64;
+            bucket.1[1] += g as u64;
+            bucket.1[2] += b as u64;
+            sampled += 1;
+        }
+    }
+
+    let mut buckets: Vec<_> = buckets.into_iter().filter(|(n, _)| *n > 0).collect();
+    buckets.sort_by(|a, b| b.0.cmp(&a.0));
+    buckets
+        .into_iter()
+        .take(count)
+        .map(|(n, sums)| PaletteColor {
+            color: ColorInfo::from_rgb(sums.map(|s| (s / n) as u8)),
+            share: n as f32 / sampled as f32,
+        })
+        .collect()
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Color of the screen pixel at (x, y) on the primary monitor
+#[tauri::command]
+pub fn pick_color(x: u32, y: u32) -> AppResult<ColorInfo> {
+    crate::permissions::ensure_capture_permission()?;
+    let pixel = XcapBackend::new(0).capture_region(x, y, 1, 1)?;
+    let [r, g, b, _] = pixel.get_pixel(0, 0).0;
+    Ok(ColorInfo::from_rgb([r, g, b]))
+}
+
+/// Dominant colors of a screen region, most common first
+#[tauri::command]
+pub fn sample_region_palette(rect: Region, count: Option<usize>) -> AppResult<Vec<PaletteColor>> {
+    crate::permissions::ensure_capture_permission()?;
+    let image = XcapBackend::new(0).capture_region(rect.x, rect.y, rect.width, rect.height)?;
+    Ok(palette(&image, count.unwrap_or(DEFAULT_PALETTE_SIZE)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_info() {
+        let red = ColorInfo::from_rgb([255, 0, 0]);
+        assert_eq!(red.hex, "#ff0000");
+        assert_eq!(red.hsl, [0.0, 100.0, 50.0]);
+        assert_eq!(ColorInfo::from_rgb([128, 128, 128]).hsl[1], 0.0);
+        assert_eq!(rgb_to_hsl([0, 0, 255])[0], 240.0);
+    }
+
+    #[test]
+    fn test_palette() {
+        let mut image = RgbaImage::from_pixel(4, 4, image::Rgba([255, 255, 255, 255]));
+        for x in 0..4 {
+            image.put_pixel(x, 0, image::Rgba([10, 20, 30, 255]));
+        }
+        let colors = palette(&image, 5);
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors[0].color.hex, "#ffffff");
+        assert_eq!(colors[0].share, 0.75);
+        assert_eq!(colors[1].color.rgb, [10, 20, 30]);
+    }
+}
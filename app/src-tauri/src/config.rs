@@ -0,0 +1,215 @@
+//! Backend configuration
+//!
+//! Settings that the Rust side needs without a round-trip to the frontend
+//! (post-OCR actions, background features). Persisted as `config.json` in the
+//! app data directory.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+use crate::actions::ActionsConfig;
+use crate::capture_buffer::CaptureBufferConfig;
+use crate::chat::ChatConfig;
+use crate::cloud_notes::CloudNotesConfig;
+use crate::controllers::ControllerConfig;
+use crate::conversion::ConversionConfig;
+use crate::crash_report::CrashReportConfig;
+use crate::email::EmailConfig;
+use crate::encryption::EncryptionConfig;
+use crate::error::AppResult;
+use crate::folder_sync::FolderSyncConfig;
+use crate::glossary::GlossaryConfig;
+use crate::history::HistoryConfig;
+use crate::hover_ocr::HoverOcrConfig;
+use crate::i18n::I18nConfig;
+use crate::ipc::IpcConfig;
+use crate::llm::LlmConfig;
+use crate::memory::MemoryConfig;
+use crate::notes::NotesConfig;
+use crate::model_manager::ModelsConfig;
+use crate::notifications::NotificationsConfig;
+use crate::pairing::PairingConfig;
+use crate::plugins::PluginsConfig;
+use crate::push_to_ocr::PushToOcrConfig;
+use crate::reocr::ReocrConfig;
+use crate::save_targets::SaveTargetsConfig;
+use crate::scheduler::SchedulerConfig;
+use crate::share::ShareConfig;
+use crate::shortcuts::ShortcutsConfig;
+use crate::summarizer::SummarizationConfig;
+use crate::templates::TemplatesConfig;
+use crate::translator::TranslationConfig;
+use crate::watch::WatchConfig;
+use crate::webhooks::WebhookConfig;
+use crate::white_balance::WhiteBalanceConfig;
+
+const CONFIG_FILE: &str = "config.json";
+
+static CONFIG: Lazy<Mutex<AppConfig>> = Lazy::new(|| Mutex::new(load_config()));
+
+/// Backend configuration
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AppConfig {
+    pub actions: ActionsConfig,
+    pub share: ShareConfig,
+    pub ipc: IpcConfig,
+    pub controllers: ControllerConfig,
+    pub encryption: EncryptionConfig,
+    pub history: HistoryConfig,
+    pub models: ModelsConfig,
+    pub scheduler: SchedulerConfig,
+    pub watch: WatchConfig,
+    pub notifications: NotificationsConfig,
+    pub plugins: PluginsConfig,
+    pub glossary: GlossaryConfig,
+    pub translation: TranslationConfig,
+    pub summarization: SummarizationConfig,
+    pub llm: LlmConfig,
+    pub reocr: ReocrConfig,
+    pub white_balance: WhiteBalanceConfig,
+    pub capture_buffer: CaptureBufferConfig,
+    pub crash_reports: CrashReportConfig,
+    pub memory: MemoryConfig,
+    pub i18n: I18nConfig,
+    pub shortcuts: ShortcutsConfig,
+    pub push_to_ocr: PushToOcrConfig,
+    pub hover_ocr: HoverOcrConfig,
+    pub templates: TemplatesConfig,
+    pub conversion: ConversionConfig,
+    pub webhooks: WebhookConfig,
+    pub notes: NotesConfig,
+    pub cloud_notes: CloudNotesConfig,
+    pub email: EmailConfig,
+    pub chat: ChatConfig,
+    pub save_targets: SaveTargetsConfig,
+    pub pairing: PairingConfig,
+    pub folder_sync: FolderSyncConfig,
+}
+
+/// Get the app data directory, following a relocation by `set_data_dir`
+pub fn get_app_data_dir() -> AppResult<PathBuf> {
+    match crate::data_dir::relocated() {
+        Some(path) => Ok(path),
+        None => default_app_data_dir(),
+    }
+}
+
+/// The platform's app data directory (the one Tauri resolves, honoring
+/// XDG_DATA_HOME on Linux), or the folder beside the executable in portable
+/// mode
+pub fn default_app_data_dir() -> AppResult<PathBuf> {
+    if let Some(dir) = crate::portable::portable_dir() {
+        return Ok(dir);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let app_data = std::env::var("APPDATA")
+            .map_err(|_| "APPDATA not found")?;
+        Ok(PathBuf::from(app_data).join("com.iml1s.screeninu"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let home = std::env::var("HOME").map_err(|_| "HOME not found")?;
+        Ok(PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+            .join("com.iml1s.screeninu"))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        // XDG_DATA_HOME only counts when absolute, as in the XDG spec
+        let xdg = std::env::var_os("XDG_DATA_HOME").map(PathBuf::from);
+        let data_home = match xdg.filter(|p| p.is_absolute()) {
+            Some(dir) => dir,
+            None => {
+                let home = std::env::var("HOME").map_err(|_| "HOME not found")?;
+                PathBuf::from(home).join(".local").join("share")
+            }
+        };
+        Ok(data_home.join("com.iml1s.screeninu"))
+    }
+}
+
+/// Where earlier versions kept app data on Linux regardless of
+/// XDG_DATA_HOME, when that differs from the default directory
+pub fn legacy_app_data_dir() -> Option<PathBuf> {
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let home = std::env::var("HOME").ok()?;
+        let legacy = PathBuf::from(home).join(".local").join("share").join("com.iml1s.screeninu");
+        (default_app_data_dir().ok()? != legacy).then_some(legacy)
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    {
+        None
+    }
+}
+
+fn config_path() -> AppResult<PathBuf> {
+    Ok(get_app_data_dir()?.join(CONFIG_FILE))
+}
+
+/// Load the config from disk, falling back to defaults
+fn load_config() -> AppConfig {
+    let path = match config_path() {
+        Ok(path) => path,
+        Err(_) => return AppConfig::default(),
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Invalid config at {:?}: {}, using defaults", path, e);
+            AppConfig::default()
+        }),
+        Err(_) => AppConfig::default(),
+    }
+}
+
+fn save_config(config: &AppConfig) -> AppResult<()> {
+    let path = config_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    std::fs::write(&path, json)?;
+    Ok(())
+}
+
+/// Get a snapshot of the current config
+pub fn get() -> AppResult<AppConfig> {
+    Ok(CONFIG.lock()?.clone())
+}
+
+/// Modify the config and persist it
+pub fn update(f: impl FnOnce(&mut AppConfig)) -> AppResult<()> {
+    let mut guard = CONFIG.lock()?;
+    f(&mut *guard);
+    save_config(&guard)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Get the backend configuration
+#[tauri::command]
+pub fn get_config() -> AppResult<AppConfig> {
+    get()
+}
+
+/// Replace the backend configuration
+#[tauri::command]
+pub fn set_config(mut config: AppConfig) -> AppResult<()> {
+    crate::llm::move_keys_to_keychain(&mut config.llm)?;
+    crate::share::move_secrets_to_keychain(&mut config.share)?;
+    crate::chat::move_secrets_to_keychain(&mut config.chat)?;
+    crate::webhooks::move_secrets_to_keychain(&mut config.webhooks)?;
+    update(|current| *current = config)
+}
@@ -0,0 +1,362 @@
+// Hardware controller integration
+// Maps MIDI notes and Stream Deck key presses to app actions, so a physical button
+// can trigger capture/OCR/translate. Actions reuse the IPC command set.
+//
+// Stream Deck: a plugin (or the generic "WebSocket" action) connects to
+// ws://127.0.0.1:<port> and forwards `keyDown` events, e.g.
+//   {"event":"keyDown","action":"com.iml1s.screeninu.capture"}
+//
+// Browsers let any web page open a WebSocket to localhost, so handshakes that
+// carry a web Origin are refused. The Stream Deck app's Node.js plugins send
+// no Origin and its HTML plugins are loaded from file://.
+
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use midir::{MidiInput, MidiInputConnection};
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{AppError, AppResult};
+use crate::ipc::{self, IpcCommand};
+
+/// Event emitted with the result of a controller-triggered action
+pub const CONTROLLER_EVENT: &str = "controller://triggered";
+
+/// Keep the MIDI connection alive while listening
+static MIDI_CONNECTION: Lazy<Mutex<Option<MidiInputConnection<()>>>> = Lazy::new(|| Mutex::new(None));
+/// The running Stream Deck listener, stopped on restart
+static STREAM_DECK: Lazy<Mutex<Option<StreamDeckListener>>> = Lazy::new(|| Mutex::new(None));
+
+/// How often open Stream Deck connections check for a stop
+const STREAM_DECK_POLL: Duration = Duration::from_secs(1);
+
+/// A MIDI note mapped to an action
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MidiMapping {
+    pub note: u8,
+    /// 0-15; any channel when None
+    pub channel: Option<u8>,
+    pub action: IpcCommand,
+}
+
+/// A Stream Deck action UUID mapped to an app action
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamDeckMapping {
+    pub action_uuid: String,
+    pub action: IpcCommand,
+}
+
+/// Controller settings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ControllerConfig {
+    pub midi_enabled: bool,
+    /// Substring of the MIDI input port name; first port when None
+    pub midi_port: Option<String>,
+    pub midi_mappings: Vec<MidiMapping>,
+    pub stream_deck_enabled: bool,
+    pub stream_deck_port: u16,
+    pub stream_deck_mappings: Vec<StreamDeckMapping>,
+}
+
+impl Default for ControllerConfig {
+    fn default() -> Self {
+        Self {
+            midi_enabled: false,
+            midi_port: None,
+            midi_mappings: Vec::new(),
+            stream_deck_enabled: false,
+            stream_deck_port: 28196,
+            stream_deck_mappings: Vec::new(),
+        }
+    }
+}
+
+/// Run a mapped action and report the outcome to the frontend
+fn trigger(app: &AppHandle, source: &str, action: IpcCommand) {
+    let payload = match ipc::dispatch(app, action) {
+        Ok(result) => {
+            // OCR/translation results; the window is usually hidden
+            if let Some(text) = result.as_str() {
+                if let Err(e) = crate::notifications::notify_result(app, "Screen Inu", text) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            serde_json::json!({ "source": source, "ok": true, "result": result })
+        }
+        Err(e) => serde_json::json!({ "source": source, "ok": false, "error": e }),
+    };
+    let _ = app.emit(CONTROLLER_EVENT, payload);
+}
+
+/// Parse a MIDI message into (channel, note) for Note On events with velocity > 0
+fn note_on(message: &[u8]) -> Option<(u8, u8)> {
+    match message {
+        [status, note, velocity, ..] if status & 0xF0 == 0x90 && *velocity > 0 => {
+            Some((status & 0x0F, *note))
+        }
+        _ => None,
+    }
+}
+
+/// List MIDI input port names
+pub fn midi_ports() -> AppResult<Vec<String>> {
+    let midi_in = MidiInput::new("Screen Inu")
+        .map_err(|e| AppError::Other(format!("Failed to open MIDI: {}", e)))?;
+    Ok(midi_in
+        .ports()
+        .iter()
+        .filter_map(|p| midi_in.port_name(p).ok())
+        .collect())
+}
+
+fn start_midi(app: AppHandle, config: &ControllerConfig) -> AppResult<()> {
+    let midi_in = MidiInput::new("Screen Inu")
+        .map_err(|e| AppError::Other(format!("Failed to open MIDI: {}", e)))?;
+    let ports = midi_in.ports();
+    let port = ports
+        .iter()
+        .find(|p| match config.midi_port.as_deref() {
+            Some(name) => midi_in.port_name(p).is_ok_and(|n| n.contains(name)),
+            None => true,
+        })
+        .ok_or_else(|| AppError::Other("No matching MIDI input port".to_string()))?
+        .clone();
+
+    let mappings = config.midi_mappings.clone();
+    let connection = midi_in
+        .connect(
+            &port,
+            "screen-inu-input",
+            move |_timestamp, message, _| {
+                if let Some((channel, note)) = note_on(message) {
+                    let mapped = mappings
+                        .iter()
+                        .find(|m| m.note == note && m.channel.is_none_or(|c| c == channel));
+                    if let Some(mapping) = mapped {
+                        let app = app.clone();
+                        let action = mapping.action.clone();
+                        // Don't block the MIDI callback thread with capture/OCR
+                        std::thread::spawn(move || trigger(&app, "midi", action));
+                    }
+                }
+            },
+            (),
+        )
+        .map_err(|e| AppError::Other(format!("Failed to connect MIDI port: {}", e)))?;
+
+    *MIDI_CONNECTION.lock()? = Some(connection);
+    Ok(())
+}
+
+/// Whether a handshake with this Origin header may connect: no Origin
+/// (Node.js plugins, scripts) or a Stream Deck HTML plugin
+fn origin_allowed(origin: Option<&str>) -> bool {
+    match origin {
+        None => true,
+        Some(origin) => origin == "file://",
+    }
+}
+
+/// WebSocket handshake that turns away connections from web pages
+fn accept_stream_deck(stream: TcpStream) -> Result<tungstenite::WebSocket<TcpStream>, String> {
+    use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+
+    // The callback's signature is tungstenite's
+    #[allow(clippy::result_large_err)]
+    let check_origin = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        let origin = request.headers().get("Origin").map(|value| value.to_str().unwrap_or_default());
+        if origin_allowed(origin) {
+            return Ok(response);
+        }
+        tracing::warn!("Stream Deck connection from {:?} refused", origin);
+        let mut refused = ErrorResponse::new(Some("Origin not allowed".to_string()));
+        *refused.status_mut() = tungstenite::http::StatusCode::FORBIDDEN;
+        Err(refused)
+    };
+    tungstenite::accept_hdr(stream, check_origin).map_err(|e| e.to_string())
+}
+
+/// Accept thread of the Stream Deck WebSocket server and its stop signal
+struct StreamDeckListener {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl StreamDeckListener {
+    /// Stop accepting and close open connections, freeing the port
+    fn stop(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the blocking accept so it sees the signal
+        let _ = TcpStream::connect(self.addr);
+        let _ = self.thread.join();
+    }
+}
+
+/// Forward a connection's key presses until it closes or `stop` is set
+fn serve_stream_deck(app: AppHandle, stream: TcpStream, stop: Arc<AtomicBool>) {
+    let mut socket = match accept_stream_deck(stream) {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::warn!("Stream Deck handshake failed: {}", e);
+            return;
+        }
+    };
+    let _ = socket.get_ref().set_read_timeout(Some(STREAM_DECK_POLL));
+    while !stop.load(Ordering::SeqCst) {
+        let text = match socket.read() {
+            Ok(tungstenite::Message::Text(text)) => text,
+            Ok(tungstenite::Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+            {
+                continue
+            }
+            Err(_) => break,
+        };
+        let event: serde_json::Value = match serde_json::from_str(&text) {
+            Ok(event) => event,
+            Err(_) => continue,
+        };
+        if event["event"] != "keyDown" {
+            continue;
+        }
+        let uuid = event["action"].as_str().unwrap_or_default().to_string();
+
+        // Mappings are read on every press so edits apply without reconnecting
+        let mappings = crate::config::get()
+            .map(|c| c.controllers.stream_deck_mappings)
+            .unwrap_or_default();
+        if let Some(mapping) = mappings.into_iter().find(|m| m.action_uuid == uuid) {
+            trigger(&app, "stream-deck", mapping.action);
+        }
+    }
+    let _ = socket.close(None);
+}
+
+fn start_stream_deck(app: AppHandle, config: &ControllerConfig) -> AppResult<()> {
+    let mut running = STREAM_DECK.lock()?;
+    if running.is_some() {
+        return Ok(());
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", config.stream_deck_port))?;
+    let addr = listener.local_addr()?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let (app, stop) = (app.clone(), stop.clone());
+                std::thread::spawn(move || serve_stream_deck(app, stream, stop));
+            }
+        })
+    };
+
+    *running = Some(StreamDeckListener { addr, stop, thread });
+    Ok(())
+}
+
+/// Start the controllers enabled in the config
+pub fn init(app: &AppHandle) {
+    let config = match crate::config::get() {
+        Ok(config) => config.controllers,
+        Err(_) => return,
+    };
+    if config.midi_enabled {
+        if let Err(e) = start_midi(app.clone(), &config) {
+            tracing::warn!("MIDI controller not started: {}", e);
+        }
+    }
+    if config.stream_deck_enabled {
+        if let Err(e) = start_stream_deck(app.clone(), &config) {
+            tracing::warn!("Stream Deck listener not started: {}", e);
+        }
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// List MIDI input ports for the mapping UI
+#[tauri::command]
+pub fn list_midi_ports() -> AppResult<Vec<String>> {
+    midi_ports()
+}
+
+/// (Re)start controller listeners after the config changed
+#[tauri::command]
+pub fn restart_controllers(app: AppHandle) -> AppResult<()> {
+    // Dropping the connection closes the MIDI port
+    MIDI_CONNECTION.lock()?.take();
+    // Taken out first so the lock isn't held while the thread winds down
+    let stream_deck = STREAM_DECK.lock()?.take();
+    if let Some(listener) = stream_deck {
+        listener.stop();
+    }
+    init(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_on() {
+        assert_eq!(note_on(&[0x90, 60, 100]), Some((0, 60)));
+        assert_eq!(note_on(&[0x93, 36, 1]), Some((3, 36)));
+        // Note On with velocity 0 is a Note Off
+        assert_eq!(note_on(&[0x90, 60, 0]), None);
+        assert_eq!(note_on(&[0x80, 60, 64]), None);
+        assert_eq!(note_on(&[0x90]), None);
+    }
+
+    #[test]
+    fn test_origin_allowed() {
+        assert!(origin_allowed(None));
+        assert!(origin_allowed(Some("file://")));
+        assert!(!origin_allowed(Some("https://example.com")));
+        assert!(!origin_allowed(Some("null")));
+        assert!(!origin_allowed(Some("")));
+    }
+
+    #[test]
+    fn test_accept_stream_deck_refuses_web_pages() {
+        use tungstenite::client::IntoClientRequest;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut results = Vec::new();
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                results.push(accept_stream_deck(stream).is_ok());
+            }
+            results
+        });
+
+        let connect = |origin: Option<&str>| {
+            let mut request = url.as_str().into_client_request().unwrap();
+            if let Some(origin) = origin {
+                request.headers_mut().insert("Origin", origin.parse().unwrap());
+            }
+            tungstenite::connect(request).is_ok()
+        };
+        assert!(connect(None));
+        assert!(!connect(Some("https://evil.example")));
+        assert_eq!(server.join().unwrap(), vec![true, false]);
+    }
+}
@@ -0,0 +1,565 @@
+// Currency and unit conversion
+// Finds prices ("$20", "€15.50", "1,200 JPY", "100元") and measurements
+// ("5 km", "12 oz", "72°F") in OCR text and converts them to the user's
+// currency and unit system, returning each conversion and the text annotated
+// with them ("5 km (≈ 3.11 mi)"). Exchange rates come from a configurable API
+// returning `{"base": "EUR", "rates": {"USD": 1.08, ...}}` (Frankfurter by
+// default) and are cached in <app data>/exchange_rates.json; offline, the
+// cache is used however old it is, and without one a built-in table of
+// approximate rates.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::error::{AppError, AppResult};
+
+const RATES_FILE: &str = "exchange_rates.json";
+const USER_AGENT: &str = concat!("ScreenInu/", env!("CARGO_PKG_VERSION"));
+
+/// Approximate rates per euro, for when the rates were never fetched
+const BUILTIN_RATES: [(&str, f64); 14] = [
+    ("EUR", 1.0),
+    ("USD", 1.08),
+    ("GBP", 0.85),
+    ("JPY", 160.0),
+    ("CNY", 7.8),
+    ("TWD", 34.5),
+    ("HKD", 8.4),
+    ("KRW", 1450.0),
+    ("INR", 90.0),
+    ("CHF", 0.95),
+    ("CAD", 1.47),
+    ("AUD", 1.63),
+    ("SGD", 1.45),
+    ("THB", 38.5),
+];
+
+/// Currencies shown without decimals
+const NO_DECIMALS: [&str; 3] = ["JPY", "KRW", "TWD"];
+
+static PRICE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?:(US\$|NT\$|HK\$|C\$|A\$|S\$|\$|€|£|¥|￥|₩|₹|\b[A-Z]{3}\b)\s?(\d[\d,]*(?:\.\d+)?)|(\d[\d,]*(?:\.\d+)?)\s?(\b[A-Z]{3}\b|元|円|원|€))",
+    )
+    .expect("price regex is valid")
+});
+static MEASUREMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)(-?\d[\d,]*(?:\.\d+)?)\s?(km/h|mph|°\s?[cf]|fl\.?\s?oz|km|cm|mm|miles?|mi|ft|feet|foot|inch(?:es)?|yards?|yd|kg|lbs?|pounds?|oz|ml|liters?|litres?|gallons?|gal|g|l|m)\b",
+    )
+    .expect("measurement regex is valid")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+/// Conversion settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConversionConfig {
+    /// ISO 4217 code prices are converted to
+    pub currency: String,
+    pub unit_system: UnitSystem,
+    /// Currency a bare "$" stands for
+    pub dollar: String,
+    /// Returns `{"base": ..., "rates": {...}}`
+    pub rates_url: String,
+    /// Cached rates older than this are fetched again
+    pub rates_max_age_hours: i64,
+}
+
+impl Default for ConversionConfig {
+    fn default() -> Self {
+        Self {
+            currency: "USD".to_string(),
+            unit_system: UnitSystem::Metric,
+            dollar: "USD".to_string(),
+            rates_url: "https://api.frankfurter.app/latest".to_string(),
+            rates_max_age_hours: 24,
+        }
+    }
+}
+
+/// Where the rates used came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RatesSource {
+    Live,
+    Cached,
+    Builtin,
+}
+
+/// Exchange rates relative to `base`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRates {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+    /// RFC 3339
+    #[serde(default)]
+    pub fetched_at: String,
+}
+
+impl ExchangeRates {
+    fn builtin() -> Self {
+        Self {
+            base: "EUR".to_string(),
+            rates: BUILTIN_RATES.iter().map(|(code, rate)| (code.to_string(), *rate)).collect(),
+            fetched_at: String::new(),
+        }
+    }
+
+    fn rate(&self, code: &str) -> Option<f64> {
+        if code == self.base {
+            return Some(1.0);
+        }
+        self.rates.get(code).copied().filter(|rate| *rate > 0.0)
+    }
+
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        Some(amount / self.rate(from)? * self.rate(to)?)
+    }
+
+    fn is_stale(&self, max_age_hours: i64) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.fetched_at)
+            .map(|fetched| chrono::Local::now().signed_duration_since(fetched) > chrono::Duration::hours(max_age_hours))
+            .unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversionKind {
+    Currency,
+    Unit,
+}
+
+/// A value found in the text and what it converts to
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversion {
+    pub kind: ConversionKind,
+    /// As it appears in the text
+    pub text: String,
+    /// UTF-16 offsets in the text, as JS strings index it
+    pub start: usize,
+    pub end: usize,
+    pub value: f64,
+    /// ISO currency code or unit symbol
+    pub unit: String,
+    pub converted: f64,
+    pub converted_unit: String,
+    /// e.g. "≈ 3.11 mi"
+    pub display: String,
+}
+
+/// What `convert_values` returns
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionResult {
+    pub conversions: Vec<Conversion>,
+    /// The text with each conversion in parentheses after its value
+    pub annotated: String,
+    /// Set when prices were converted
+    pub rates_source: Option<RatesSource>,
+}
+
+/// "1,234.56" and "12,50" (decimal comma) as numbers
+fn parse_number(text: &str) -> Option<f64> {
+    let normalized = match text.rsplit_once(',') {
+        Some((_, decimals)) if !text.contains('.') && decimals.len() == 2 => text.replace(',', "."),
+        _ => text.replace(',', ""),
+    };
+    normalized.parse().ok()
+}
+
+fn currency_code(symbol: &str, config: &ConversionConfig) -> Option<String> {
+    let code = match symbol {
+        "$" => config.dollar.as_str(),
+        "US$" => "USD",
+        "NT$" => "TWD",
+        "HK$" => "HKD",
+        "C$" => "CAD",
+        "A$" => "AUD",
+        "S$" => "SGD",
+        "€" => "EUR",
+        "£" => "GBP",
+        "¥" | "￥" | "円" => "JPY",
+        "元" => "CNY",
+        "₩" | "원" => "KRW",
+        "₹" => "INR",
+        code if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) => code,
+        _ => return None,
+    };
+    Some(code.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Speed,
+    Temperature,
+}
+
+/// Dimension, size in the dimension's base unit (m, kg, L, km/h) and system
+fn unit_info(symbol: &str) -> Option<(Dimension, f64, UnitSystem)> {
+    use Dimension::*;
+    use UnitSystem::*;
+    Some(match symbol {
+        "mm" => (Length, 0.001, Metric),
+        "cm" => (Length, 0.01, Metric),
+        "m" => (Length, 1.0, Metric),
+        "km" => (Length, 1000.0, Metric),
+        "in" => (Length, 0.0254, Imperial),
+        "ft" => (Length, 0.3048, Imperial),
+        "yd" => (Length, 0.9144, Imperial),
+        "mi" => (Length, 1609.344, Imperial),
+        "g" => (Mass, 0.001, Metric),
+        "kg" => (Mass, 1.0, Metric),
+        "oz" => (Mass, 0.028349523125, Imperial),
+        "lb" => (Mass, 0.45359237, Imperial),
+        "mL" => (Volume, 0.001, Metric),
+        "L" => (Volume, 1.0, Metric),
+        "fl oz" => (Volume, 0.0295735295625, Imperial),
+        "gal" => (Volume, 3.785411784, Imperial),
+        "km/h" => (Speed, 1.0, Metric),
+        "mph" => (Speed, 1.609344, Imperial),
+        "°C" => (Temperature, 1.0, Metric),
+        "°F" => (Temperature, 1.0, Imperial),
+        _ => return None,
+    })
+}
+
+/// Canonical symbol of a unit as written
+fn unit_symbol(written: &str) -> Option<&'static str> {
+    let lower = written.to_lowercase().replace([' ', '.'], "");
+    Some(match lower.as_str() {
+        "mm" => "mm",
+        "cm" => "cm",
+        "m" => "m",
+        "km" => "km",
+        "inch" | "inches" => "in",
+        "ft" | "feet" | "foot" => "ft",
+        "yd" | "yard" | "yards" => "yd",
+        "mi" | "mile" | "miles" => "mi",
+        "g" => "g",
+        "kg" => "kg",
+        "oz" => "oz",
+        "lb" | "lbs" | "pound" | "pounds" => "lb",
+        "ml" => "mL",
+        "l" | "liter" | "liters" | "litre" | "litres" => "L",
+        "floz" => "fl oz",
+        "gal" | "gallon" | "gallons" => "gal",
+        "km/h" => "km/h",
+        "mph" => "mph",
+        "°c" => "°C",
+        "°f" => "°F",
+        _ => return None,
+    })
+}
+
+/// `value` in `from` converted to a unit of `system` that suits its size
+fn convert_unit(value: f64, from: &str, system: UnitSystem) -> Option<(f64, &'static str)> {
+    let (dimension, factor, from_system) = unit_info(from)?;
+    if from_system == system {
+        return None;
+    }
+    if dimension == Dimension::Temperature {
+        return Some(match system {
+            UnitSystem::Metric => ((value - 32.0) * 5.0 / 9.0, "°C"),
+            UnitSystem::Imperial => (value * 9.0 / 5.0 + 32.0, "°F"),
+        });
+    }
+    let base = value * factor;
+    let magnitude = base.abs();
+    let target = match (dimension, system) {
+        (Dimension::Length, UnitSystem::Metric) if magnitude < 1.0 => "cm",
+        (Dimension::Length, UnitSystem::Metric) if magnitude < 1000.0 => "m",
+        (Dimension::Length, UnitSystem::Metric) => "km",
+        (Dimension::Length, UnitSystem::Imperial) if magnitude < 0.3048 => "in",
+        (Dimension::Length, UnitSystem::Imperial) if magnitude < 1609.344 => "ft",
+        (Dimension::Length, UnitSystem::Imperial) => "mi",
+        (Dimension::Mass, UnitSystem::Metric) if magnitude < 1.0 => "g",
+        (Dimension::Mass, UnitSystem::Metric) => "kg",
+        (Dimension::Mass, UnitSystem::Imperial) if magnitude < 0.45359237 => "oz",
+        (Dimension::Mass, UnitSystem::Imperial) => "lb",
+        (Dimension::Volume, UnitSystem::Metric) if magnitude < 1.0 => "mL",
+        (Dimension::Volume, UnitSystem::Metric) => "L",
+        (Dimension::Volume, UnitSystem::Imperial) if magnitude < 3.785411784 => "fl oz",
+        (Dimension::Volume, UnitSystem::Imperial) => "gal",
+        (Dimension::Speed, UnitSystem::Metric) => "km/h",
+        (Dimension::Speed, UnitSystem::Imperial) => "mph",
+        (Dimension::Temperature, _) => return None,
+    };
+    let (_, target_factor, _) = unit_info(target)?;
+    Some((base / target_factor, target))
+}
+
+/// Up to two decimals, without trailing zeros
+fn format_amount(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value);
+    if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        formatted
+    }
+}
+
+fn format_currency(value: f64, code: &str) -> String {
+    let decimals = if NO_DECIMALS.contains(&code) { 0 } else { 2 };
+    format!("{:.*} {}", decimals, value, code)
+}
+
+fn utf16_offset(text: &str, byte: usize) -> usize {
+    text[..byte].encode_utf16().count()
+}
+
+/// A price or measurement found at a byte span, before conversion
+#[derive(Debug, Clone, PartialEq)]
+struct Found {
+    kind: ConversionKind,
+    start: usize,
+    end: usize,
+    value: f64,
+    unit: String,
+}
+
+fn find_prices(text: &str, config: &ConversionConfig) -> Vec<Found> {
+    PRICE
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let (symbol, amount) = match (caps.get(1), caps.get(2)) {
+                (Some(symbol), Some(amount)) => (symbol.as_str(), amount.as_str()),
+                _ => (caps.get(4)?.as_str(), caps.get(3)?.as_str()),
+            };
+            let m = caps.get(0)?;
+            Some(Found {
+                kind: ConversionKind::Currency,
+                start: m.start(),
+                end: m.end(),
+                value: parse_number(amount)?,
+                unit: currency_code(symbol, config)?,
+            })
+        })
+        .collect()
+}
+
+fn find_measurements(text: &str) -> Vec<Found> {
+    MEASUREMENT
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let m = caps.get(0)?;
+            Some(Found {
+                kind: ConversionKind::Unit,
+                start: m.start(),
+                end: m.end(),
+                value: parse_number(&caps[1])?,
+                unit: unit_symbol(&caps[2])?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Convert what's found in `text`; prices need `rates`
+fn convert_text(text: &str, config: &ConversionConfig, rates: Option<&ExchangeRates>) -> (Vec<Conversion>, String) {
+    let mut found = find_prices(text, config);
+    // "ABC 5 km" isn't a price in currency ABC
+    found.retain(|f| f.unit == config.currency || rates.is_some_and(|r| r.rate(&f.unit).is_some()));
+    for measurement in find_measurements(text) {
+        // "$5 m" is a price; "5 EUR" can't also be a length
+        if !found.iter().any(|f| measurement.start < f.end && f.start < measurement.end) {
+            found.push(measurement);
+        }
+    }
+    found.sort_by_key(|f| f.start);
+
+    let mut conversions = Vec::new();
+    let mut annotated = String::with_capacity(text.len());
+    let mut copied = 0;
+    for f in found {
+        let converted = match f.kind {
+            ConversionKind::Currency if f.unit == config.currency => None,
+            ConversionKind::Currency => rates
+                .and_then(|rates| rates.convert(f.value, &f.unit, &config.currency))
+                .map(|value| (value, config.currency.clone(), format_currency(value, &config.currency))),
+            ConversionKind::Unit => convert_unit(f.value, &f.unit, config.unit_system)
+                .map(|(value, unit)| (value, unit.to_string(), format!("{} {}", format_amount(value, 2), unit))),
+        };
+        let Some((converted, converted_unit, formatted)) = converted else {
+            continue;
+        };
+        let display = format!("≈ {}", formatted.replace(" °", "°"));
+        annotated.push_str(&text[copied..f.end]);
+        annotated.push_str(&format!(" ({})", display));
+        copied = f.end;
+        conversions.push(Conversion {
+            kind: f.kind,
+            text: text[f.start..f.end].to_string(),
+            start: utf16_offset(text, f.start),
+            end: utf16_offset(text, f.end),
+            value: f.value,
+            unit: f.unit,
+            converted,
+            converted_unit,
+            display,
+        });
+    }
+    annotated.push_str(&text[copied..]);
+    (conversions, annotated)
+}
+
+fn rates_path() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(RATES_FILE))
+}
+
+fn read_cached_rates() -> Option<ExchangeRates> {
+    let content = std::fs::read_to_string(rates_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+async fn fetch_rates(url: &str) -> AppResult<ExchangeRates> {
+    #[derive(serde::Deserialize)]
+    struct Response {
+        base: String,
+        rates: HashMap<String, f64>,
+    }
+
+    let response = reqwest::Client::new()
+        .get(url)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("Exchange rate request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::Other(format!("Exchange rate request failed: HTTP {}", response.status())));
+    }
+    let body: Response = response
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Invalid exchange rates: {}", e)))?;
+    let rates = ExchangeRates {
+        base: body.base,
+        rates: body.rates,
+        fetched_at: chrono::Local::now().to_rfc3339(),
+    };
+    let json = serde_json::to_vec_pretty(&rates).map_err(|e| AppError::Other(e.to_string()))?;
+    std::fs::write(rates_path()?, json)?;
+    Ok(rates)
+}
+
+/// Cached rates while fresh, else fetched ones, falling back to stale cached
+/// and then built-in rates when offline
+async fn exchange_rates(config: &ConversionConfig, force: bool) -> (ExchangeRates, RatesSource) {
+    let cached = read_cached_rates();
+    if let Some(rates) = cached.as_ref().filter(|r| !force && !r.is_stale(config.rates_max_age_hours)) {
+        return (rates.clone(), RatesSource::Cached);
+    }
+    match fetch_rates(&config.rates_url).await {
+        Ok(rates) => (rates, RatesSource::Live),
+        Err(e) => {
+            tracing::warn!("Using offline exchange rates: {}", e);
+            match cached {
+                Some(rates) => (rates, RatesSource::Cached),
+                None => (ExchangeRates::builtin(), RatesSource::Builtin),
+            }
+        }
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Prices and measurements in `text`, converted to the configured currency
+/// and unit system
+#[tauri::command]
+pub async fn convert_values(text: String) -> AppResult<ConversionResult> {
+    let config = crate::config::get()?.conversion;
+    let needs_rates = find_prices(&text, &config).iter().any(|f| f.unit != config.currency);
+    let (rates, rates_source) = if needs_rates {
+        let (rates, source) = exchange_rates(&config, false).await;
+        (Some(rates), Some(source))
+    } else {
+        (None, None)
+    };
+    let (conversions, annotated) = convert_text(&text, &config, rates.as_ref());
+    Ok(ConversionResult {
+        rates_source: rates_source.filter(|_| conversions.iter().any(|c| c.kind == ConversionKind::Currency)),
+        conversions,
+        annotated,
+    })
+}
+
+/// Fetch exchange rates now
+#[tauri::command]
+pub async fn refresh_exchange_rates() -> AppResult<ExchangeRates> {
+    let config = crate::config::get()?.conversion;
+    fetch_rates(&config.rates_url).await
+}
+
+#[tauri::command]
+pub fn get_conversion_config() -> AppResult<ConversionConfig> {
+    Ok(crate::config::get()?.conversion)
+}
+
+#[tauri::command]
+pub fn set_conversion_config(config: ConversionConfig) -> AppResult<()> {
+    for code in [&config.currency, &config.dollar] {
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(AppError::InvalidInput(format!("Not a currency code: {}", code)));
+        }
+    }
+    crate::config::update(|c| c.conversion = config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("1,234.56"), Some(1234.56));
+        assert_eq!(parse_number("12,50"), Some(12.5));
+        assert_eq!(parse_number("1,200"), Some(1200.0));
+    }
+
+    #[test]
+    fn test_convert_units() {
+        let config = ConversionConfig { unit_system: UnitSystem::Imperial, ..Default::default() };
+        let (conversions, annotated) = convert_text("Run 5 km at 20°C, 12 mi away", &config, None);
+        assert_eq!(annotated, "Run 5 km (≈ 3.11 mi) at 20°C (≈ 68°F), 12 mi away");
+        assert_eq!(conversions.len(), 2);
+        assert_eq!(conversions[0].unit, "km");
+        assert_eq!((conversions[0].start, conversions[0].end), (4, 8));
+
+        let metric = ConversionConfig::default();
+        let (conversions, _) = convert_text("12 oz and 6 ft", &metric, None);
+        let displays: Vec<&str> = conversions.iter().map(|c| c.display.as_str()).collect();
+        assert_eq!(displays, vec!["≈ 340.19 g", "≈ 1.83 m"]);
+    }
+
+    #[test]
+    fn test_convert_prices() {
+        let config = ConversionConfig { currency: "EUR".to_string(), ..Default::default() };
+        let rates = ExchangeRates::builtin();
+        let (conversions, annotated) = convert_text("Only $10.80 or 1,600 JPY (was €12)", &config, Some(&rates));
+        assert_eq!(annotated, "Only $10.80 (≈ 10.00 EUR) or 1,600 JPY (≈ 10.00 EUR) (was €12)");
+        assert_eq!(conversions.len(), 2);
+        assert_eq!(conversions[1].value, 1600.0);
+
+        // Without rates prices are left alone
+        assert!(convert_text("$5", &config, None).0.is_empty());
+        let yen = ConversionConfig { currency: "JPY".to_string(), ..Default::default() };
+        assert_eq!(convert_text("100元", &yen, Some(&rates)).0[0].display, "≈ 2051 JPY");
+    }
+}
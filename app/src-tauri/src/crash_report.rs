@@ -0,0 +1,262 @@
+// Crash reports
+// Panics in background threads (hotkeys, watch folders, the tray) used to
+// vanish with the process. The panic hook always logs them; with crash
+// reports enabled (off by default) it also writes a JSON report to
+// <app data>/crash_reports, which the user can review and submit along with
+// recent logs and system info. Nothing is sent without an explicit submit.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{AppError, AppResult};
+
+const REPORT_DIR: &str = "crash_reports";
+const USER_AGENT: &str = concat!("ScreenInu/", env!("CARGO_PKG_VERSION"));
+
+/// Log entries bundled with a submitted report
+const BUNDLED_LOG_LINES: usize = 500;
+
+/// Mirrors `CrashReportConfig::enabled`, so the panic hook never touches the
+/// config lock (the panic may have happened while it was held)
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Crash report settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CrashReportConfig {
+    /// Write panic reports to disk
+    pub enabled: bool,
+    /// Where submitted reports are POSTed as JSON
+    pub endpoint: Option<String>,
+}
+
+/// A panic, as written to disk
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    /// RFC 3339
+    pub timestamp: String,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: String,
+    pub message: String,
+    /// file:line:column of the panic
+    pub location: Option<String>,
+    pub backtrace: String,
+    /// Set once the report has been submitted
+    #[serde(default)]
+    pub submitted: bool,
+}
+
+/// What `submit_crash_report` sends
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportBundle {
+    report: CrashReport,
+    engines: Vec<&'static str>,
+    logs: Vec<crate::logging::LogEntry>,
+}
+
+fn report_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(REPORT_DIR))
+}
+
+/// Report ids are generated here; anything else could escape the directory
+fn report_path(dir: &Path, id: &str) -> AppResult<PathBuf> {
+    let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        return Err(AppError::InvalidInput(format!("Invalid crash report id: {}", id)));
+    }
+    Ok(dir.join(format!("{}.json", id)))
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string())
+}
+
+fn build_report(info: &std::panic::PanicHookInfo) -> CrashReport {
+    let now = chrono::Local::now();
+    CrashReport {
+        id: format!("panic-{}", now.format("%Y%m%d-%H%M%S-%3f")),
+        timestamp: now.to_rfc3339(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current().name().unwrap_or("<unnamed>").to_string(),
+        message: panic_message(info.payload()),
+        location: info.location().map(|l| l.to_string()),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        submitted: false,
+    }
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> AppResult<()> {
+    std::fs::create_dir_all(dir)?;
+    let json = serde_json::to_vec_pretty(report).map_err(|e| AppError::Other(e.to_string()))?;
+    std::fs::write(report_path(dir, &report.id)?, json)?;
+    Ok(())
+}
+
+fn read_report(path: &Path) -> AppResult<CrashReport> {
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| AppError::Other(format!("Invalid crash report: {}", e)))
+}
+
+/// Reports in `dir`, newest first
+fn list_reports(dir: &Path) -> AppResult<Vec<CrashReport>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut reports: Vec<CrashReport> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| read_report(&path).ok())
+        .collect();
+    reports.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(reports)
+}
+
+/// Install the panic hook. The previous hook (printing to stderr) still runs.
+pub fn init() {
+    ENABLED.store(
+        crate::config::get().map(|c| c.crash_reports.enabled).unwrap_or(false),
+        Ordering::SeqCst,
+    );
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = build_report(info);
+        tracing::error!(
+            thread = %report.thread,
+            location = report.location.as_deref().unwrap_or_default(),
+            "Panic: {}",
+            report.message
+        );
+        if ENABLED.load(Ordering::SeqCst) {
+            if let Err(e) = report_dir().and_then(|dir| write_report(&dir, &report)) {
+                tracing::error!("Failed to write crash report: {}", e);
+            }
+        }
+        previous(info);
+    }));
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_crash_report_config() -> AppResult<CrashReportConfig> {
+    Ok(crate::config::get()?.crash_reports)
+}
+
+#[tauri::command]
+pub fn set_crash_report_config(config: CrashReportConfig) -> AppResult<()> {
+    ENABLED.store(config.enabled, Ordering::SeqCst);
+    crate::config::update(|c| c.crash_reports = config)
+}
+
+/// Crash reports on disk, newest first
+#[tauri::command]
+pub fn list_crash_reports() -> AppResult<Vec<CrashReport>> {
+    list_reports(&report_dir()?)
+}
+
+#[tauri::command]
+pub fn delete_crash_report(id: String) -> AppResult<()> {
+    let path = report_path(&report_dir()?, &id)?;
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Send a report to the configured endpoint, with recent logs and the
+/// available OCR engines, and mark it submitted
+#[tauri::command]
+pub async fn submit_crash_report(id: String) -> AppResult<()> {
+    let config = crate::config::get()?.crash_reports;
+    if !config.enabled {
+        return Err(AppError::InvalidInput("Crash reports are disabled".to_string()));
+    }
+    let endpoint = config
+        .endpoint
+        .filter(|e| !e.trim().is_empty())
+        .ok_or_else(|| AppError::InvalidInput("No crash report endpoint configured".to_string()))?;
+
+    let dir = report_dir()?;
+    let mut report = read_report(&report_path(&dir, &id)?)?;
+    let bundle = ReportBundle {
+        report: report.clone(),
+        engines: crate::ocr::get_available_engines().iter().map(|e| e.as_str()).collect(),
+        logs: crate::logging::get_recent_logs(Some("info".to_string()), Some(BUNDLED_LOG_LINES))?,
+    };
+
+    let response = reqwest::Client::new()
+        .post(&endpoint)
+        .header("User-Agent", USER_AGENT)
+        .json(&bundle)
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("Crash report request failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::Other(format!("Crash report upload failed: HTTP {}", response.status())));
+    }
+
+    report.submitted = true;
+    write_report(&dir, &report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(id: &str) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            timestamp: String::new(),
+            version: "0.0.0".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            thread: "main".to_string(),
+            message: "boom".to_string(),
+            location: Some("src/lib.rs:1:1".to_string()),
+            backtrace: String::new(),
+            submitted: false,
+        }
+    }
+
+    #[test]
+    fn test_report_path_rejects_traversal() {
+        let dir = Path::new("reports");
+        assert_eq!(report_path(dir, "panic-20261016-093000-123").unwrap(), dir.join("panic-20261016-093000-123.json"));
+        assert!(report_path(dir, "../config").is_err());
+        assert!(report_path(dir, "").is_err());
+    }
+
+    #[test]
+    fn test_reports_round_trip_newest_first() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        write_report(dir, &report("panic-20261015-120000-000")).unwrap();
+        write_report(dir, &report("panic-20261016-080000-000")).unwrap();
+
+        let reports = list_reports(dir).unwrap();
+        let ids: Vec<&str> = reports.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["panic-20261016-080000-000", "panic-20261015-120000-000"]);
+        assert_eq!(reports[1], report("panic-20261015-120000-000"));
+    }
+
+    #[test]
+    fn test_panic_message() {
+        assert_eq!(panic_message(&"static"), "static");
+        assert_eq!(panic_message(&"owned".to_string()), "owned");
+        assert_eq!(panic_message(&42), "Box<dyn Any>");
+    }
+}
@@ -0,0 +1,313 @@
+// Data directory location
+// The app data root (config, tessdata tiers, translation and summary models,
+// backups) can be moved to another drive, since models alone can take
+// several GB. Moving copies everything, verifies the copy, then switches by
+// writing a pointer file in the default location; the old files are only
+// deleted when asked to. Paths resolve through `config::get_app_data_dir`, so
+// every module follows the switch.
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{AppError, AppResult};
+
+/// Pointer file in the default data directory naming the relocated root
+const POINTER_FILE: &str = "data_location.json";
+
+/// Emitted with `MigrationProgress` while files are copied
+pub const DATA_DIR_PROGRESS_EVENT: &str = "data-dir://progress";
+
+/// Relocated root, None when data lives in the default directory
+static RELOCATED: Lazy<RwLock<Option<PathBuf>>> = Lazy::new(|| RwLock::new(read_pointer()));
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Pointer {
+    path: PathBuf,
+}
+
+/// Where data lives
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataDirInfo {
+    pub path: String,
+    pub default_path: String,
+    pub relocated: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationProgress {
+    pub copied_files: usize,
+    pub total_files: usize,
+}
+
+fn read_pointer() -> Option<PathBuf> {
+    let default = crate::config::default_app_data_dir().ok()?;
+    let content = std::fs::read_to_string(default.join(POINTER_FILE)).ok()?;
+    let pointer: Pointer = serde_json::from_str(&content).ok()?;
+    Some(pointer.path)
+}
+
+/// The relocated data root, if the data has been moved
+pub fn relocated() -> Option<PathBuf> {
+    RELOCATED.read().ok().and_then(|root| root.clone())
+}
+
+/// Files under `root`, relative to it, skipping the pointer file
+fn list_files(root: &Path) -> AppResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                if relative != Path::new(POINTER_FILE) {
+                    files.push(relative.to_path_buf());
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Reject targets that would copy the data into itself or over other files
+fn validate_target(current: &Path, target: &Path) -> AppResult<()> {
+    if !target.is_absolute() {
+        return Err(AppError::InvalidInput("Data directory must be an absolute path".to_string()));
+    }
+    if target.starts_with(current) || current.starts_with(target) {
+        return Err(AppError::InvalidInput(
+            "Data directory can't be inside the current one or contain it".to_string(),
+        ));
+    }
+    if target.exists() && std::fs::read_dir(target)?.next().is_some() {
+        return Err(AppError::InvalidInput(format!("{} is not empty", target.display())));
+    }
+    Ok(())
+}
+
+/// Copy `files` from `from` to `to`, reporting progress after each file
+fn copy_files(from: &Path, to: &Path, files: &[PathBuf], on_progress: impl Fn(usize)) -> AppResult<()> {
+    for (index, relative) in files.iter().enumerate() {
+        let target = to.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(from.join(relative), &target)?;
+        on_progress(index + 1);
+    }
+    Ok(())
+}
+
+/// Check every file made it with the same size
+fn verify_copy(from: &Path, to: &Path, files: &[PathBuf]) -> AppResult<()> {
+    for relative in files {
+        let expected = std::fs::metadata(from.join(relative))?.len();
+        let copied = std::fs::metadata(to.join(relative)).map(|m| m.len()).ok();
+        if copied != Some(expected) {
+            return Err(AppError::Other(format!("Copy of {} is incomplete", relative.display())));
+        }
+    }
+    Ok(())
+}
+
+/// Point the app at `root` (None for the default directory)
+fn switch_to(default: &Path, root: Option<&Path>) -> AppResult<()> {
+    let pointer_path = default.join(POINTER_FILE);
+    match root {
+        Some(path) => {
+            std::fs::create_dir_all(default)?;
+            let pointer = Pointer { path: path.to_path_buf() };
+            let json = serde_json::to_string_pretty(&pointer).map_err(|e| AppError::Other(e.to_string()))?;
+            std::fs::write(pointer_path, json)?;
+        }
+        None => {
+            if pointer_path.exists() {
+                std::fs::remove_file(pointer_path)?;
+            }
+        }
+    }
+    *RELOCATED.write().map_err(|_| AppError::Other("Data directory lock poisoned".to_string()))? =
+        root.map(Path::to_path_buf);
+    Ok(())
+}
+
+/// Move the files under `from` that `to` doesn't have yet, the pointer file
+/// included, renaming where possible. Returns how many were moved.
+fn merge_into(from: &Path, to: &Path) -> AppResult<usize> {
+    let mut files = list_files(from)?;
+    if from.join(POINTER_FILE).is_file() {
+        files.push(PathBuf::from(POINTER_FILE));
+    }
+    files.retain(|relative| !to.join(relative).exists());
+    for relative in &files {
+        let (source, target) = (from.join(relative), to.join(relative));
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Crossing filesystems: copy, check, then delete the original
+        if std::fs::rename(&source, &target).is_err() {
+            std::fs::copy(&source, &target)?;
+            verify_copy(from, to, std::slice::from_ref(relative))?;
+            std::fs::remove_file(&source)?;
+        }
+    }
+    remove_files(from, &files);
+    let _ = std::fs::remove_dir(from);
+    Ok(files.len())
+}
+
+/// Move data left in the legacy Linux location (`~/.local/share`, whatever
+/// XDG_DATA_HOME said) into the default directory. Runs before anything
+/// reads the data directory, so the pointer file of a relocation is found.
+/// Returns how many files were moved.
+pub fn migrate_legacy_default() -> AppResult<usize> {
+    if crate::portable::portable_dir().is_some() {
+        return Ok(0);
+    }
+    match crate::config::legacy_app_data_dir() {
+        Some(legacy) if legacy.is_dir() => merge_into(&legacy, &crate::config::default_app_data_dir()?),
+        _ => Ok(0),
+    }
+}
+
+/// Delete the copied files from the old root, leaving the pointer file
+fn remove_files(root: &Path, files: &[PathBuf]) {
+    for relative in files {
+        let _ = std::fs::remove_file(root.join(relative));
+    }
+    // Remove emptied directories, deepest first
+    let mut dirs: Vec<PathBuf> = files
+        .iter()
+        .flat_map(|f| f.ancestors().skip(1))
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(|dir| root.join(dir))
+        .collect();
+    dirs.sort_by(|a, b| b.components().count().cmp(&a.components().count()).then(a.cmp(b)));
+    dirs.dedup();
+    for dir in dirs {
+        let _ = std::fs::remove_dir(dir);
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_data_dir() -> AppResult<DataDirInfo> {
+    let default = crate::config::default_app_data_dir()?;
+    let current = crate::config::get_app_data_dir()?;
+    Ok(DataDirInfo {
+        path: current.display().to_string(),
+        default_path: default.display().to_string(),
+        relocated: current != default,
+    })
+}
+
+/// Move the app data to `path`: copy, verify, then switch. Passing the
+/// default directory moves the data back. With `delete_old`, the old copy is
+/// removed once the switch succeeded.
+#[tauri::command(async)]
+pub fn set_data_dir(app: AppHandle, path: String, delete_old: Option<bool>) -> AppResult<DataDirInfo> {
+    let default = crate::config::default_app_data_dir()?;
+    let current = crate::config::get_app_data_dir()?;
+    let target = PathBuf::from(path);
+    if target == current {
+        return get_data_dir();
+    }
+    // The default directory keeps the pointer file, so it's never empty
+    if target != default {
+        validate_target(&current, &target)?;
+    }
+
+    let files = if current.exists() { list_files(&current)? } else { Vec::new() };
+    let total_files = files.len();
+    std::fs::create_dir_all(&target)?;
+    let copied = copy_files(&current, &target, &files, |copied_files| {
+        let _ = app.emit(DATA_DIR_PROGRESS_EVENT, MigrationProgress { copied_files, total_files });
+    })
+    .and_then(|_| verify_copy(&current, &target, &files));
+    if let Err(e) = copied {
+        // Leave the current data in use; drop the partial copy
+        if target != default {
+            let _ = std::fs::remove_dir_all(&target);
+        }
+        return Err(e);
+    }
+
+    switch_to(&default, (target != default).then_some(target.as_path()))?;
+    tracing::info!("Data directory moved from {} to {}", current.display(), target.display());
+
+    if delete_old.unwrap_or(false) {
+        remove_files(&current, &files);
+    }
+    get_data_dir()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_target() {
+        let tmp = tempfile::tempdir().unwrap();
+        let current = Path::new("/data/screen-inu");
+        assert!(validate_target(current, Path::new("relative/dir")).is_err());
+        assert!(validate_target(current, Path::new("/data/screen-inu/models")).is_err());
+        assert!(validate_target(current, Path::new("/data")).is_err());
+        assert!(validate_target(current, &tmp.path().join("missing")).is_ok());
+    }
+
+    #[test]
+    fn test_copy_and_verify() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (from, to) = (tmp.path().join("from"), tmp.path().join("to"));
+        std::fs::create_dir_all(from.join("translation_models/opus-mt-en-de")).unwrap();
+        std::fs::write(from.join("config.json"), "{}").unwrap();
+        std::fs::write(from.join(POINTER_FILE), "{}").unwrap();
+        std::fs::write(from.join("translation_models/opus-mt-en-de/model.onnx"), [0u8; 64]).unwrap();
+
+        let files = list_files(&from).unwrap();
+        assert_eq!(
+            files,
+            vec![PathBuf::from("config.json"), Path::new("translation_models/opus-mt-en-de").join("model.onnx")]
+        );
+        copy_files(&from, &to, &files, |_| {}).unwrap();
+        verify_copy(&from, &to, &files).unwrap();
+
+        std::fs::write(to.join("config.json"), "{\"truncated\"").unwrap();
+        assert!(verify_copy(&from, &to, &files).is_err());
+
+        remove_files(&from, &files);
+        assert!(!from.join("translation_models").exists());
+        assert!(from.join(POINTER_FILE).exists());
+    }
+
+    #[test]
+    fn test_merge_into() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (from, to) = (tmp.path().join("legacy"), tmp.path().join("default"));
+        std::fs::create_dir_all(from.join("translation_models/opus-mt-en-de")).unwrap();
+        std::fs::write(from.join("translation_models/opus-mt-en-de/model.onnx"), [0u8; 64]).unwrap();
+        std::fs::write(from.join("config.json"), "{\"old\": true}").unwrap();
+        std::fs::write(from.join(POINTER_FILE), "{}").unwrap();
+        // Written by Tauri plugins in the default directory already
+        std::fs::create_dir_all(&to).unwrap();
+        std::fs::write(to.join("config.json"), "{}").unwrap();
+
+        assert_eq!(merge_into(&from, &to).unwrap(), 2);
+        assert!(to.join("translation_models/opus-mt-en-de/model.onnx").is_file());
+        assert!(to.join(POINTER_FILE).is_file());
+        assert_eq!(std::fs::read_to_string(to.join("config.json")).unwrap(), "{}");
+        // The conflicting file stays behind, so does the legacy directory
+        assert!(from.join("config.json").is_file());
+        assert!(!from.join("translation_models").exists());
+    }
+}
@@ -0,0 +1,216 @@
+// Translation decoding
+// Beam search over a step function returning next-token logits, with the
+// usual generation controls (length and repetition penalties, n-gram
+// blocking). Decoding is greedy by default: each extra beam runs the decoder
+// once more per step, so wider beams are opt-in for prose that benefits from
+// them.
+
+use std::collections::HashSet;
+
+use crate::error::{AppError, AppResult};
+
+/// Decoding settings for offline translation
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DecodingParams {
+    /// Beams kept per step (1 = greedy, the default)
+    pub beam_size: usize,
+    /// Maximum generated tokens
+    pub max_length: usize,
+    /// Exponent of the length normalization; > 1 favors longer output
+    pub length_penalty: f32,
+    /// > 1 discourages tokens already generated
+    pub repetition_penalty: f32,
+    /// Forbid repeating any n-gram of this size (0 = off)
+    pub no_repeat_ngram_size: usize,
+}
+
+impl Default for DecodingParams {
+    fn default() -> Self {
+        Self {
+            beam_size: 1,
+            max_length: 256,
+            length_penalty: 1.0,
+            repetition_penalty: 1.0,
+            no_repeat_ngram_size: 0,
+        }
+    }
+}
+
+impl DecodingParams {
+    pub fn validate(&self) -> AppResult<()> {
+        if !(1..=8).contains(&self.beam_size) {
+            return Err(AppError::InvalidInput("Beam size must be between 1 and 8".to_string()));
+        }
+        if !(1..=1024).contains(&self.max_length) {
+            return Err(AppError::InvalidInput("Max length must be between 1 and 1024".to_string()));
+        }
+        if !self.length_penalty.is_finite() {
+            return Err(AppError::InvalidInput("Length penalty must be a number".to_string()));
+        }
+        if !(self.repetition_penalty.is_finite() && self.repetition_penalty > 0.0) {
+            return Err(AppError::InvalidInput("Repetition penalty must be positive".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Divide positive / multiply negative logits of tokens already in `tokens`
+fn apply_repetition_penalty(logits: &mut [f32], tokens: &[u32], penalty: f32) {
+    if penalty == 1.0 {
+        return;
+    }
+    for &token in tokens.iter().collect::<HashSet<_>>() {
+        if let Some(logit) = logits.get_mut(token as usize) {
+            *logit = if *logit > 0.0 { *logit / penalty } else { *logit * penalty };
+        }
+    }
+}
+
+/// Tokens that would complete an n-gram already present in `tokens`
+fn banned_tokens(tokens: &[u32], n: usize) -> Vec<u32> {
+    if n == 0 || tokens.len() < n {
+        return Vec::new();
+    }
+    let prefix = &tokens[tokens.len() - (n - 1)..];
+    tokens
+        .windows(n)
+        .filter(|gram| &gram[..n - 1] == prefix)
+        .map(|gram| gram[n - 1])
+        .collect()
+}
+
+fn log_softmax(logits: &[f32]) -> Vec<f32> {
+    let max = logits.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let sum: f32 = logits.iter().map(|l| (l - max).exp()).sum();
+    let log_sum = max + sum.ln();
+    logits.iter().map(|l| l - log_sum).collect()
+}
+
+fn normalized(score: f32, length: usize, length_penalty: f32) -> f32 {
+    score / (length.max(1) as f32).powf(length_penalty)
+}
+
+/// Decode a sequence. `step` returns the next-token logits for a prefix
+/// (starting with `start`). The result excludes `start` and `eos`.
+pub fn beam_search(
+    params: &DecodingParams,
+    start: u32,
+    eos: u32,
+    mut step: impl FnMut(&[u32]) -> AppResult<Vec<f32>>,
+) -> AppResult<Vec<u32>> {
+    let mut beams: Vec<(Vec<u32>, f32)> = vec![(vec![start], 0.0)];
+    let mut finished: Vec<(Vec<u32>, f32)> = Vec::new();
+
+    for _ in 0..params.max_length {
+        crate::operations::check()?;
+        let mut candidates: Vec<(Vec<u32>, f32)> = Vec::new();
+        for (tokens, score) in &beams {
+            let mut logits = step(tokens)?;
+            apply_repetition_penalty(&mut logits, &tokens[1..], params.repetition_penalty);
+            for banned in banned_tokens(&tokens[1..], params.no_repeat_ngram_size) {
+                if let Some(logit) = logits.get_mut(banned as usize) {
+                    *logit = f32::NEG_INFINITY;
+                }
+            }
+            let log_probs = log_softmax(&logits);
+
+            let mut ranked: Vec<(usize, f32)> = log_probs.into_iter().enumerate().filter(|(_, p)| p.is_finite()).collect();
+            ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            for (token, log_prob) in ranked.into_iter().take(params.beam_size * 2) {
+                let mut next = tokens.clone();
+                next.push(token as u32);
+                candidates.push((next, score + log_prob));
+            }
+        }
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        beams.clear();
+        for (tokens, score) in candidates {
+            if tokens.last() == Some(&eos) {
+                let length = tokens.len() - 2;
+                finished.push((tokens, normalized(score, length, params.length_penalty)));
+            } else if beams.len() < params.beam_size {
+                beams.push((tokens, score));
+            }
+            if beams.len() == params.beam_size && finished.len() >= params.beam_size {
+                break;
+            }
+        }
+        if finished.len() >= params.beam_size || beams.is_empty() {
+            break;
+        }
+    }
+
+    // Out of length budget: unfinished beams compete too
+    finished.extend(beams.into_iter().map(|(tokens, score)| {
+        let length = tokens.len() - 1;
+        (tokens, normalized(score, length, params.length_penalty))
+    }));
+    let (best, _) = finished
+        .into_iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .ok_or_else(|| AppError::Translation("Decoding produced no output".to_string()))?;
+    Ok(best.into_iter().skip(1).filter(|&t| t != eos).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EOS: u32 = 0;
+
+    /// Vocabulary of 4 tokens; the model loves to repeat token 1
+    fn repetitive(tokens: &[u32]) -> AppResult<Vec<f32>> {
+        Ok(if tokens.len() > 6 { vec![5.0, 1.0, 0.0, 0.0] } else { vec![0.0, 3.0, 1.0, 0.5] })
+    }
+
+    #[test]
+    fn test_greedy() {
+        let params = DecodingParams::default();
+        assert_eq!(params.beam_size, 1);
+        assert_eq!(beam_search(&params, 3, EOS, repetitive).unwrap(), vec![1; 6]);
+    }
+
+    #[test]
+    fn test_max_length() {
+        // A model that never emits EOS stops at the length budget
+        let endless = |_: &[u32]| -> AppResult<Vec<f32>> { Ok(vec![f32::NEG_INFINITY, 1.0, 2.0, 0.0]) };
+        let params = DecodingParams { beam_size: 2, max_length: 3, ..Default::default() };
+        assert_eq!(beam_search(&params, 3, EOS, endless).unwrap(), vec![2, 2, 2]);
+    }
+
+    #[test]
+    fn test_no_repeat_ngram() {
+        let params = DecodingParams { beam_size: 1, no_repeat_ngram_size: 2, ..Default::default() };
+        let output = beam_search(&params, 3, EOS, repetitive).unwrap();
+        let bigrams: Vec<_> = output.windows(2).collect();
+        let unique: HashSet<_> = bigrams.iter().collect();
+        assert_eq!(bigrams.len(), unique.len());
+    }
+
+    #[test]
+    fn test_beam_finds_better_sequence() {
+        // Greedy takes token 1 (p=0.6) then is stuck with a flat distribution;
+        // token 2 (p=0.4) leads to a confident EOS.
+        let step = |tokens: &[u32]| -> AppResult<Vec<f32>> {
+            Ok(match tokens {
+                [_] => vec![f32::NEG_INFINITY, 0.6f32.ln(), 0.4f32.ln(), f32::NEG_INFINITY],
+                [_, 1] => vec![0.25f32.ln(); 4],
+                [_, 2] => vec![0.99f32.ln(), 0.01f32.ln(), f32::NEG_INFINITY, f32::NEG_INFINITY],
+                _ => vec![0.0, f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY],
+            })
+        };
+        let greedy = DecodingParams { beam_size: 1, ..Default::default() };
+        let beam = DecodingParams { beam_size: 3, ..Default::default() };
+        assert_eq!(beam_search(&greedy, 3, EOS, step).unwrap()[0], 1);
+        assert_eq!(beam_search(&beam, 3, EOS, step).unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn test_repetition_penalty() {
+        let mut logits = vec![2.0, -2.0, 1.0];
+        apply_repetition_penalty(&mut logits, &[0, 1, 1], 2.0);
+        assert_eq!(logits, vec![1.0, -4.0, 1.0]);
+    }
+}
@@ -0,0 +1,231 @@
+// First-run diagnostics
+// Checks everything the first capture depends on, so the UI can show a setup
+// checklist instead of failing with an OCR error.
+
+use std::path::Path;
+
+use crate::permissions::PermissionState;
+
+const TESSERACT_FIX: &str =
+    "Reinstall Screen Inu, install Tesseract OCR, or download it with install_tesseract.";
+
+/// Result of a single check
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+/// A diagnostic check shown as one checklist row
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    /// Stable id ("tesseract", "tessdata", "capture_permission", "models_dir", "ocr_engines")
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix the problem (None when Ok)
+    pub fix: Option<String>,
+}
+
+impl DiagnosticCheck {
+    fn ok(id: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            status: CheckStatus::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        id: &'static str,
+        status: CheckStatus,
+        message: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            id,
+            status,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// All diagnostics
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    pub checks: Vec<DiagnosticCheck>,
+    /// True when no check reported an error
+    pub ready: bool,
+}
+
+fn check_tesseract() -> DiagnosticCheck {
+    let path = match crate::ocr::get_tesseract_path() {
+        Ok(path) => path,
+        Err(e) => {
+            return DiagnosticCheck::problem(
+                "tesseract",
+                CheckStatus::Error,
+                e.to_string(),
+                TESSERACT_FIX,
+            )
+        }
+    };
+
+    match std::process::Command::new(&path).arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            // Older versions print the banner to stderr
+            let banner = if output.stdout.is_empty() {
+                &output.stderr
+            } else {
+                &output.stdout
+            };
+            let version = String::from_utf8_lossy(banner)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            DiagnosticCheck::ok("tesseract", version)
+        }
+        Ok(output) => DiagnosticCheck::problem(
+            "tesseract",
+            CheckStatus::Error,
+            format!(
+                "{} failed: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            TESSERACT_FIX,
+        ),
+        Err(e) => DiagnosticCheck::problem(
+            "tesseract",
+            CheckStatus::Error,
+            format!(
+                "{} could not be started: {} ({})",
+                path.display(),
+                e,
+                crate::sidecar::describe("tesseract")
+            ),
+            TESSERACT_FIX,
+        ),
+    }
+}
+
+fn check_tessdata() -> DiagnosticCheck {
+    match crate::model_manager::list_installed_models() {
+        Ok(models) if models.iter().any(|m| m.code == "eng") => DiagnosticCheck::ok(
+            "tessdata",
+            format!("{} language pack(s) installed", models.len()),
+        ),
+        Ok(models) if !models.is_empty() => DiagnosticCheck::problem(
+            "tessdata",
+            CheckStatus::Warning,
+            format!(
+                "{} language pack(s) installed, English is missing",
+                models.len()
+            ),
+            "Download the English model in the model manager.",
+        ),
+        Ok(_) => DiagnosticCheck::problem(
+            "tessdata",
+            CheckStatus::Error,
+            "No OCR language packs installed",
+            "Download at least one language in the model manager.",
+        ),
+        Err(e) => DiagnosticCheck::problem(
+            "tessdata",
+            CheckStatus::Error,
+            e.to_string(),
+            "Reinstall Screen Inu.",
+        ),
+    }
+}
+
+fn check_capture_permission() -> DiagnosticCheck {
+    let permission = crate::permissions::check_permission();
+    match permission.state {
+        PermissionState::Granted => {
+            DiagnosticCheck::ok("capture_permission", "Screen capture allowed")
+        }
+        PermissionState::Denied | PermissionState::PortalUnavailable => DiagnosticCheck::problem(
+            "capture_permission",
+            CheckStatus::Error,
+            "Screen capture is not available",
+            permission.guidance.unwrap_or_default(),
+        ),
+    }
+}
+
+/// Check that a directory can be created and written to
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".screen-inu-write-test");
+    std::fs::write(&probe, b"ok")?;
+    std::fs::remove_file(&probe)
+}
+
+fn check_models_dir() -> DiagnosticCheck {
+    let dirs = [
+        crate::model_manager::get_tessdata_dir(),
+        crate::translator::get_models_dir(),
+    ];
+    for dir in dirs {
+        let dir = match dir {
+            Ok(dir) => dir,
+            Err(e) => {
+                return DiagnosticCheck::problem(
+                    "models_dir",
+                    CheckStatus::Error,
+                    e.to_string(),
+                    "Check the app data directory.",
+                )
+            }
+        };
+        if let Err(e) = check_writable(&dir) {
+            return DiagnosticCheck::problem(
+                "models_dir",
+                CheckStatus::Warning,
+                format!("{} is not writable: {}", dir.display(), e),
+                "Model downloads will fail. Check the folder permissions.",
+            );
+        }
+    }
+    DiagnosticCheck::ok("models_dir", "Model directories are writable")
+}
+
+fn check_engines() -> DiagnosticCheck {
+    let engines: Vec<&str> = crate::ocr::get_available_engines()
+        .iter()
+        .map(|e| e.as_str())
+        .collect();
+    DiagnosticCheck::ok("ocr_engines", engines.join(", "))
+}
+
+/// Run all checks
+pub fn run() -> Diagnostics {
+    let checks = vec![
+        check_tesseract(),
+        check_tessdata(),
+        check_capture_permission(),
+        check_models_dir(),
+        check_engines(),
+    ];
+    let ready = checks.iter().all(|c| c.status != CheckStatus::Error);
+    Diagnostics { checks, ready }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Setup checklist for first run
+#[tauri::command]
+pub fn run_diagnostics() -> Diagnostics {
+    run()
+}
@@ -0,0 +1,236 @@
+// Document scanning
+// Photos of paper documents (dragged in, or sent from a phone) are shot at an
+// angle and under uneven light. `scan_document` finds the page outline, warps
+// the page flat and evens out the lighting before OCR, so the engines see a
+// scan rather than a photo. The page is the largest four-cornered contour in
+// the edge map; when none is found the whole image is used and only the
+// lighting is corrected.
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage, Luma};
+use imageproc::contours::{find_contours, BorderType};
+use imageproc::distance_transform::Norm;
+use imageproc::geometric_transformations::{warp_into, Interpolation, Projection};
+
+use crate::error::{AppError, AppResult};
+use crate::ocr::{self, OcrOptions};
+
+/// Photos are searched for the page at most this wide
+const ANALYSIS_WIDTH: u32 = 800;
+
+/// The page covers at least this share of the photo
+const MIN_PAGE_SHARE: f64 = 0.2;
+
+/// A contour is a page when its corner quadrilateral covers this much of it
+const MIN_QUAD_FILL: f64 = 0.85;
+
+const CANNY_LOW: f32 = 30.0;
+const CANNY_HIGH: f32 = 90.0;
+
+/// Side of the blocks the paper brightness is estimated over
+const LIGHTING_BLOCK: u32 = 32;
+
+/// Paper is this bright a percentile of its block; the rest is ink
+const PAPER_PERCENTILE: f64 = 0.9;
+
+/// Page corners: top-left, top-right, bottom-right, bottom-left
+pub type Corners = [(f32, f32); 4];
+
+/// What `scan_document` returns
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentScan {
+    pub text: String,
+    /// The corrected page as a PNG data URL
+    pub image: String,
+    /// Page corners found in the photo, in its pixels
+    pub corners: Option<Corners>,
+}
+
+/// Shoelace area of a polygon
+fn polygon_area(points: &[(f64, f64)]) -> f64 {
+    let twice: f64 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+        .sum();
+    twice.abs() / 2.0
+}
+
+/// Corners of a contour as the points furthest along each diagonal
+fn quad_corners(points: &[(f64, f64)]) -> Option<[(f64, f64); 4]> {
+    let by = |key: fn(&(f64, f64)) -> f64| {
+        points.iter().copied().max_by(|a, b| key(a).total_cmp(&key(b)))
+    };
+    Some([
+        by(|(x, y)| -(x + y))?,
+        by(|(x, y)| x - y)?,
+        by(|(x, y)| x + y)?,
+        by(|(x, y)| y - x)?,
+    ])
+}
+
+/// Corners of the page in a photo, in its pixels
+pub fn find_page(image: &DynamicImage) -> Option<Corners> {
+    let (width, height) = (image.width(), image.height());
+    let scale = if width > ANALYSIS_WIDTH { width as f64 / ANALYSIS_WIDTH as f64 } else { 1.0 };
+    let gray = if scale > 1.0 {
+        let analysis_height = (height as f64 / scale).round().max(1.0) as u32;
+        image.resize_exact(ANALYSIS_WIDTH, analysis_height, FilterType::Triangle).to_luma8()
+    } else {
+        image.to_luma8()
+    };
+    // Blur away the text so only the page border has strong edges
+    let blurred = imageproc::filter::gaussian_blur_f32(&gray, 2.0);
+    let edges = imageproc::edges::canny(&blurred, CANNY_LOW, CANNY_HIGH);
+    let edges = imageproc::morphology::dilate(&edges, Norm::LInf, 2);
+
+    let min_area = gray.width() as f64 * gray.height() as f64 * MIN_PAGE_SHARE;
+    let quad = find_contours::<u32>(&edges)
+        .into_iter()
+        .filter(|c| c.border_type == BorderType::Outer)
+        .filter_map(|c| {
+            let points: Vec<(f64, f64)> = c.points.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+            let area = polygon_area(&points);
+            if area < min_area {
+                return None;
+            }
+            let corners = quad_corners(&points)?;
+            let quad_area = polygon_area(&corners);
+            (quad_area >= area * MIN_QUAD_FILL).then_some((quad_area, corners))
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0))?
+        .1;
+    Some(quad.map(|(x, y)| ((x * scale) as f32, (y * scale) as f32)))
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// The page warped to a rectangle as wide and tall as its longer edges
+pub fn flatten(image: &GrayImage, corners: &Corners) -> AppResult<GrayImage> {
+    let [top_left, top_right, bottom_right, bottom_left] = *corners;
+    let width = distance(top_left, top_right).max(distance(bottom_left, bottom_right)).round().max(1.0);
+    let height = distance(top_left, bottom_left).max(distance(top_right, bottom_right)).round().max(1.0);
+    let target = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)];
+    let projection = Projection::from_control_points(*corners, target)
+        .ok_or_else(|| AppError::InvalidInput("Page corners don't form a quadrilateral".to_string()))?;
+    let mut page = GrayImage::new(width as u32, height as u32);
+    warp_into(image, &projection, Interpolation::Bilinear, Luma([255]), &mut page);
+    Ok(page)
+}
+
+/// Divide out uneven lighting: each pixel over the paper brightness around it
+pub fn even_lighting(image: &GrayImage) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let blocks_x = width.div_ceil(LIGHTING_BLOCK).max(1);
+    let blocks_y = height.div_ceil(LIGHTING_BLOCK).max(1);
+    let mut paper = GrayImage::new(blocks_x, blocks_y);
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            let mut values: Vec<u8> = (by * LIGHTING_BLOCK..((by + 1) * LIGHTING_BLOCK).min(height))
+                .flat_map(|y| (bx * LIGHTING_BLOCK..((bx + 1) * LIGHTING_BLOCK).min(width)).map(move |x| (x, y)))
+                .map(|(x, y)| image.get_pixel(x, y)[0])
+                .collect();
+            let nth = ((values.len() - 1) as f64 * PAPER_PERCENTILE) as usize;
+            let (_, level, _) = values.select_nth_unstable(nth);
+            paper.put_pixel(bx, by, Luma([*level]));
+        }
+    }
+    let paper = image::imageops::resize(&paper, width, height, FilterType::Triangle);
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let level = paper.get_pixel(x, y)[0].max(1) as u32;
+        Luma([(image.get_pixel(x, y)[0] as u32 * 255 / level).min(255) as u8])
+    })
+}
+
+/// The photo as a flat, evenly lit page, and the corners it was cut at
+pub fn scan(image: &DynamicImage) -> AppResult<(GrayImage, Option<Corners>)> {
+    let gray = image.to_luma8();
+    let corners = find_page(image);
+    let page = match &corners {
+        Some(corners) => flatten(&gray, corners)?,
+        None => gray,
+    };
+    Ok((even_lighting(&page), corners))
+}
+
+/// `scan` of an encoded photo, as PNG
+pub fn scan_to_png(bytes: &[u8]) -> AppResult<(Vec<u8>, Option<Corners>)> {
+    let photo = crate::image_decode::decode_image_bytes(bytes)?;
+    let (page, corners) = scan(&photo)?;
+    let mut png = Vec::new();
+    DynamicImage::ImageLuma8(page)
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| AppError::Ocr(format!("Failed to encode image: {}", e)))?;
+    Ok((png, corners))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Straighten and relight a photo of a document, then OCR it
+#[tauri::command(async)]
+pub fn scan_document(base64_image: String, langs: Option<String>, engine: Option<String>) -> AppResult<DocumentScan> {
+    use base64::Engine;
+
+    let bytes = ocr::decode_base64_image(&base64_image)?;
+    let (png, corners) = scan_to_png(&bytes)?;
+    let options = OcrOptions {
+        langs,
+        engine,
+        ..Default::default()
+    }
+    .with_reocr_config();
+    let text = ocr::run_ocr(&png, &options)?;
+    Ok(DocumentScan {
+        text,
+        image: format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&png)),
+        corners,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether (x, y) is inside the convex quadrilateral
+    fn inside(corners: &Corners, x: f32, y: f32) -> bool {
+        (0..4).all(|i| {
+            let (ax, ay) = corners[i];
+            let (bx, by) = corners[(i + 1) % 4];
+            (bx - ax) * (y - ay) - (by - ay) * (x - ax) >= 0.0
+        })
+    }
+
+    #[test]
+    fn test_find_page() {
+        let page: Corners = [(60.0, 40.0), (330.0, 60.0), (310.0, 280.0), (40.0, 250.0)];
+        let photo = GrayImage::from_fn(400, 320, |x, y| {
+            Luma([if inside(&page, x as f32, y as f32) { 220 } else { 50 }])
+        });
+        let found = find_page(&DynamicImage::ImageLuma8(photo)).expect("page is found");
+        for (found, expected) in found.iter().zip(page) {
+            assert!(distance(*found, expected) < 6.0, "{:?} vs {:?}", found, expected);
+        }
+
+        let flat = flatten(&GrayImage::from_pixel(400, 320, Luma([200])), &page).unwrap();
+        assert_eq!(flat.dimensions(), (272, 221));
+        assert!(find_page(&DynamicImage::ImageLuma8(GrayImage::from_pixel(400, 320, Luma([128])))).is_none());
+    }
+
+    #[test]
+    fn test_even_lighting() {
+        // Paper darkening from left to right, with a stroke of ink
+        let photo = GrayImage::from_fn(256, 128, |x, y| {
+            let paper = 240 - x as u8 / 4;
+            Luma([if (60..64).contains(&y) && x % 64 < 40 { paper / 4 } else { paper }])
+        });
+        let even = even_lighting(&photo);
+        assert!(even.get_pixel(10, 10)[0] >= 245 && even.get_pixel(250, 10)[0] >= 245);
+        assert!(even.get_pixel(200, 62)[0] < 100);
+    }
+}
@@ -0,0 +1,195 @@
+// Resumable downloads
+// Model files are streamed to `<file>.part` and renamed once complete, so an
+// interrupted download never leaves a truncated model behind. A later download
+// of the same file resumes from the partial file with an HTTP Range request.
+// On exit, `cancel_all` stops in-flight downloads between chunks, keeping
+// their partial files; `cancel_operation` does the same for one download.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+
+use crate::error::{AppError, AppResult};
+use crate::operations::CancelToken;
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a download as in flight until dropped
+struct ActiveDownload;
+
+impl ActiveDownload {
+    fn start() -> AppResult<Self> {
+        if CANCELLED.load(Ordering::SeqCst) {
+            return Err(cancelled());
+        }
+        ACTIVE.fetch_add(1, Ordering::SeqCst);
+        Ok(Self)
+    }
+}
+
+impl Drop for ActiveDownload {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn cancelled() -> AppError {
+    AppError::Download("Download cancelled".to_string())
+}
+
+fn check_cancelled() -> AppResult<()> {
+    if CANCELLED.load(Ordering::SeqCst) {
+        Err(cancelled())
+    } else {
+        Ok(())
+    }
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Bytes already downloaded into the partial file
+fn resume_offset(part: &Path) -> u64 {
+    std::fs::metadata(part).map(|m| m.len()).unwrap_or(0)
+}
+
+/// What to do with the partial file given the response status
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PartAction {
+    /// 206: the server continues where the partial file ends
+    Append,
+    /// 200: the server sent the whole file
+    Restart,
+    /// 416 with a partial file: nothing left to download
+    Complete,
+}
+
+fn part_action(status: StatusCode, offset: u64) -> AppResult<PartAction> {
+    match status {
+        StatusCode::PARTIAL_CONTENT if offset > 0 => Ok(PartAction::Append),
+        StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => Ok(PartAction::Complete),
+        status if status.is_success() => Ok(PartAction::Restart),
+        status => Err(AppError::Download(format!("HTTP {}", status))),
+    }
+}
+
+fn open_part(part: &Path, action: PartAction) -> AppResult<File> {
+    if let Some(parent) = part.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    match action {
+        PartAction::Append | PartAction::Complete => options.create(true).append(true),
+        PartAction::Restart => options.create(true).write(true).truncate(true),
+    };
+    Ok(options.open(part)?)
+}
+
+fn finish(file: File, part: &Path, path: &Path) -> AppResult<()> {
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(part, path)?;
+    Ok(())
+}
+
+fn request_error(url: &str, e: reqwest::Error) -> AppError {
+    AppError::Download(format!("Failed to request {}: {}", url, e))
+}
+
+/// Download `url` to `path`, resuming a partial download (blocking). Stops
+/// if the operation running on this thread is cancelled.
+pub fn fetch_blocking(url: &str, path: &Path) -> AppResult<()> {
+    let _active = ActiveDownload::start()?;
+    let part = part_path(path);
+    let offset = resume_offset(&part);
+
+    let mut request = reqwest::blocking::Client::new().get(url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request.send().map_err(|e| request_error(url, e))?;
+    let action = part_action(response.status(), offset).map_err(|e| AppError::Download(format!("{}: {}", url, e)))?;
+    let mut file = open_part(&part, action)?;
+
+    if action != PartAction::Complete {
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            check_cancelled()?;
+            crate::operations::check()?;
+            let read = response.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            file.write_all(&buffer[..read])?;
+        }
+    }
+    finish(file, &part, path)
+}
+
+/// Download `url` to `path`, resuming a partial download. Stops once
+/// `cancel` is cancelled.
+pub async fn fetch(url: &str, path: &Path, cancel: &CancelToken) -> AppResult<()> {
+    let _active = ActiveDownload::start()?;
+    let part = part_path(path);
+    let offset = resume_offset(&part);
+
+    let mut request = reqwest::Client::new().get(url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request.send().await.map_err(|e| request_error(url, e))?;
+    let action = part_action(response.status(), offset).map_err(|e| AppError::Download(format!("{}: {}", url, e)))?;
+    let mut file = open_part(&part, action)?;
+
+    if action != PartAction::Complete {
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| AppError::Download(format!("Failed to read {}: {}", url, e)))?
+        {
+            check_cancelled()?;
+            cancel.check()?;
+            file.write_all(&chunk)?;
+        }
+    }
+    finish(file, &part, path)
+}
+
+/// Stop all downloads, waiting up to `timeout` for them to reach a chunk
+/// boundary. Partial files are kept for resuming. Later downloads fail.
+pub fn cancel_all(timeout: Duration) {
+    CANCELLED.store(true, Ordering::SeqCst);
+    let started = Instant::now();
+    while ACTIVE.load(Ordering::SeqCst) > 0 && started.elapsed() < timeout {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(part_path(Path::new("/models/eng.traineddata")), PathBuf::from("/models/eng.traineddata.part"));
+    }
+
+    #[test]
+    fn test_part_action() {
+        assert_eq!(part_action(StatusCode::PARTIAL_CONTENT, 100).unwrap(), PartAction::Append);
+        // Servers without range support send everything again
+        assert_eq!(part_action(StatusCode::OK, 100).unwrap(), PartAction::Restart);
+        assert_eq!(part_action(StatusCode::OK, 0).unwrap(), PartAction::Restart);
+        assert_eq!(part_action(StatusCode::RANGE_NOT_SATISFIABLE, 100).unwrap(), PartAction::Complete);
+        assert!(part_action(StatusCode::RANGE_NOT_SATISFIABLE, 0).is_err());
+        assert!(part_action(StatusCode::NOT_FOUND, 0).is_err());
+    }
+}
@@ -0,0 +1,349 @@
+// Email capture results
+// Mails a history item, with its screenshot attached, to yourself or a
+// colleague. With an SMTP server configured the message is sent directly;
+// the password is kept in the OS keychain. Otherwise it is handed to the
+// default mail app: a mailto: link for text alone, or, since mailto: can't
+// carry attachments, an .eml draft marked "X-Unsent: 1", which Outlook,
+// Apple Mail and Thunderbird open as a new message ready to send. Sending
+// over SMTP needs the `smtp` feature (lettre's transport and TLS stack).
+
+use lettre::address::Envelope;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+#[cfg(feature = "smtp")]
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::Message;
+#[cfg(feature = "smtp")]
+use lettre::{SmtpTransport, Transport};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::{AppError, AppResult};
+use crate::history::HistoryItem;
+use crate::templates::TemplateContext;
+
+const KEYRING_USER: &str = "smtp-password";
+const DRAFT_DIR: &str = "drafts";
+
+/// How mail is sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailMethod {
+    /// Hand the message to the default mail app
+    #[default]
+    Mailto,
+    Smtp,
+}
+
+/// SMTP connection security
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    #[default]
+    StartTls,
+    /// Implicit TLS, usually port 465
+    Tls,
+    None,
+}
+
+/// Email settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EmailConfig {
+    pub method: EmailMethod,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_security: SmtpSecurity,
+    pub smtp_username: Option<String>,
+    /// Sender address; the SMTP username when unset
+    pub from: Option<String>,
+    /// Used when no recipient is given
+    pub default_recipient: Option<String>,
+    /// Output template for the subject
+    pub subject_template: String,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            method: EmailMethod::Mailto,
+            smtp_host: None,
+            smtp_port: 587,
+            smtp_security: SmtpSecurity::StartTls,
+            smtp_username: None,
+            from: None,
+            default_recipient: None,
+            subject_template: "Screen Inu capture, {date}".to_string(),
+        }
+    }
+}
+
+/// A screenshot to attach
+struct Screenshot {
+    bytes: Vec<u8>,
+    mime_type: &'static str,
+    file_name: String,
+}
+
+fn mailbox(address: &str) -> AppResult<Mailbox> {
+    address
+        .trim()
+        .parse()
+        .map_err(|_| AppError::InvalidInput(format!("Not an email address: {}", address)))
+}
+
+/// Percent-encoding for mailto: fields
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn mailto_url(recipient: Option<&str>, subject: &str, body: &str) -> String {
+    // mailto: bodies use CRLF line breaks (RFC 6068)
+    let body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+    format!(
+        "mailto:{}?subject={}&body={}",
+        recipient.map(percent_encode).unwrap_or_default(),
+        percent_encode(subject),
+        percent_encode(&body)
+    )
+}
+
+fn subject(config: &EmailConfig, item: &HistoryItem) -> AppResult<String> {
+    let context = TemplateContext {
+        lang: Some(item.lang.clone()),
+        app: item.source_app.clone(),
+        timestamp: Some(item.timestamp),
+        ..Default::default()
+    };
+    let subject = crate::templates::render(&config.subject_template, &item.text, &context)?;
+    // Headers are one line
+    Ok(subject.lines().next().unwrap_or_default().trim().to_string())
+}
+
+fn screenshot(capture_id: Option<&str>, base64_image: Option<&str>) -> AppResult<Option<Screenshot>> {
+    let bytes = match (capture_id, base64_image) {
+        (Some(id), _) => crate::capture_protocol::get(id)
+            .map(|(_, bytes)| bytes)
+            .ok_or_else(|| AppError::InvalidInput(format!("Capture {} is no longer available", id)))?,
+        (None, Some(base64_image)) => crate::ocr::decode_base64_image(base64_image)?,
+        (None, None) => return Ok(None),
+    };
+    let (mime_type, extension) = match image::guess_format(&bytes) {
+        Ok(image::ImageFormat::Jpeg) => ("image/jpeg", "jpg"),
+        Ok(image::ImageFormat::WebP) => ("image/webp", "webp"),
+        _ => ("image/png", "png"),
+    };
+    Ok(Some(Screenshot {
+        bytes,
+        mime_type,
+        file_name: format!("capture.{}", extension),
+    }))
+}
+
+fn build_message(
+    from: Mailbox,
+    to: Option<Mailbox>,
+    subject: &str,
+    item: &HistoryItem,
+    screenshot: Option<Screenshot>,
+) -> AppResult<Message> {
+    let mut builder = Message::builder().from(from.clone()).subject(subject);
+    match to {
+        Some(to) => builder = builder.to(to),
+        // A draft may leave the recipient to be filled in
+        None => {
+            let envelope = Envelope::new(Some(from.email.clone()), vec![from.email])
+                .map_err(|e| AppError::Other(format!("Failed to build email: {}", e)))?;
+            builder = builder.envelope(envelope);
+        }
+    }
+    let text = SinglePart::plain(item.text.clone());
+    let result = match screenshot {
+        Some(screenshot) => {
+            let content_type = ContentType::parse(screenshot.mime_type)
+                .map_err(|e| AppError::Other(format!("Invalid attachment type: {}", e)))?;
+            let attachment = Attachment::new(screenshot.file_name).body(screenshot.bytes, content_type);
+            builder.multipart(MultiPart::mixed().singlepart(text).singlepart(attachment))
+        }
+        None => builder.singlepart(text),
+    };
+    result.map_err(|e| AppError::Other(format!("Failed to build email: {}", e)))
+}
+
+/// Raw .eml draft that mail apps open as an unsent message
+fn draft_bytes(message: &Message) -> Vec<u8> {
+    let mut bytes = b"X-Unsent: 1\r\n".to_vec();
+    bytes.extend_from_slice(&message.formatted());
+    bytes
+}
+
+#[cfg(feature = "smtp")]
+fn send_smtp(config: &EmailConfig, message: &Message) -> AppResult<()> {
+    let host = config
+        .smtp_host
+        .as_deref()
+        .filter(|h| !h.trim().is_empty())
+        .ok_or_else(|| AppError::InvalidInput("SMTP server is not configured".to_string()))?;
+    let smtp_error = |e: lettre::transport::smtp::Error| AppError::Other(format!("SMTP failed: {}", e));
+    let mut transport = match config.smtp_security {
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(host).map_err(smtp_error)?,
+        SmtpSecurity::Tls => SmtpTransport::relay(host).map_err(smtp_error)?,
+        SmtpSecurity::None => SmtpTransport::builder_dangerous(host),
+    }
+    .port(config.smtp_port);
+    if let Some(username) = config.smtp_username.as_deref().filter(|u| !u.is_empty()) {
+        let password = crate::secrets::load(KEYRING_USER)?.unwrap_or_default();
+        transport = transport.credentials(Credentials::new(username.to_string(), password));
+    }
+    transport.build().send(message).map_err(smtp_error)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "smtp"))]
+fn send_smtp(_config: &EmailConfig, _message: &Message) -> AppResult<()> {
+    Err(AppError::Unsupported("SMTP is not supported by this build (smtp feature)".to_string()))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_email_config() -> AppResult<EmailConfig> {
+    Ok(crate::config::get()?.email)
+}
+
+#[tauri::command]
+pub fn set_email_config(config: EmailConfig) -> AppResult<()> {
+    for address in [&config.from, &config.default_recipient].into_iter().flatten() {
+        if !address.trim().is_empty() {
+            mailbox(address)?;
+        }
+    }
+    crate::config::update(|c| c.email = config)
+}
+
+/// Store the SMTP password in the keychain; `None` removes it
+#[tauri::command]
+pub fn set_smtp_password(password: Option<String>) -> AppResult<()> {
+    crate::secrets::store(KEYRING_USER, password.as_deref().unwrap_or_default())
+}
+
+/// Mail a history item to `recipient` (the configured default when unset),
+/// with the screenshot attached when given. The item is read from the
+/// history file; `text` stands in for it when history is in localStorage.
+/// Returns how the mail went out.
+#[tauri::command(async)]
+#[tracing::instrument(skip(app, text, base64_image), err)]
+pub fn email_result(
+    app: AppHandle,
+    item_id: String,
+    recipient: Option<String>,
+    text: Option<String>,
+    capture_id: Option<String>,
+    base64_image: Option<String>,
+) -> AppResult<EmailMethod> {
+    let config = crate::config::get()?.email;
+    let item = match crate::history::find_item(&app, &item_id)? {
+        Some(item) => item,
+        None => HistoryItem {
+            id: item_id.clone(),
+            text: text.ok_or_else(|| AppError::InvalidInput(format!("History item {} not found", item_id)))?,
+            lang: String::new(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            source_app: None,
+            summary: None,
+            tags: Vec::new(),
+        },
+    };
+    let recipient = recipient
+        .or_else(|| config.default_recipient.clone())
+        .filter(|r| !r.trim().is_empty());
+    let to = recipient.as_deref().map(mailbox).transpose()?;
+    let subject = subject(&config, &item)?;
+    let screenshot = screenshot(capture_id.as_deref(), base64_image.as_deref())?;
+
+    if config.method == EmailMethod::Smtp {
+        let to = to.ok_or_else(|| AppError::InvalidInput("No recipient".to_string()))?;
+        let from = config
+            .from
+            .as_deref()
+            .or(config.smtp_username.as_deref())
+            .ok_or_else(|| AppError::InvalidInput("Sender address is not configured".to_string()))?;
+        send_smtp(&config, &build_message(mailbox(from)?, Some(to), &subject, &item, screenshot)?)?;
+        return Ok(EmailMethod::Smtp);
+    }
+
+    match screenshot {
+        None => app
+            .opener()
+            .open_url(mailto_url(recipient.as_deref(), &subject, &item.text), None::<&str>)
+            .map_err(|e| AppError::Other(format!("Failed to open mail app: {}", e)))?,
+        Some(screenshot) => {
+            // A draft needs a sender; mail apps replace it with the account's
+            let from = config.from.as_deref().or(recipient.as_deref()).unwrap_or("me@localhost");
+            let message = build_message(mailbox(from)?, to, &subject, &item, Some(screenshot))?;
+            let dir = crate::config::get_app_data_dir()?.join(DRAFT_DIR);
+            std::fs::create_dir_all(&dir)?;
+            let path = dir.join(format!("capture-{}.eml", item.id));
+            std::fs::write(&path, draft_bytes(&message))?;
+            app.opener()
+                .open_path(path.to_string_lossy(), None::<&str>)
+                .map_err(|e| AppError::Other(format!("Failed to open mail app: {}", e)))?;
+        }
+    }
+    Ok(EmailMethod::Mailto)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> HistoryItem {
+        HistoryItem {
+            id: "a1".to_string(),
+            text: "Total: 12 €\nThanks".to_string(),
+            lang: "eng".to_string(),
+            timestamp: 0,
+            source_app: None,
+            summary: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_mailto_url() {
+        assert_eq!(
+            mailto_url(Some("me@example.org"), "Receipt", &item().text),
+            "mailto:me%40example.org?subject=Receipt&body=Total%3A%2012%20%E2%82%AC%0D%0AThanks"
+        );
+        assert!(mailto_url(None, "a b", "").starts_with("mailto:?subject=a%20b&"));
+    }
+
+    #[test]
+    fn test_draft() {
+        let screenshot = Screenshot {
+            bytes: vec![0x89, b'P', b'N', b'G'],
+            mime_type: "image/png",
+            file_name: "capture.png".to_string(),
+        };
+        let message = build_message(
+            mailbox("me@example.org").unwrap(),
+            Some(mailbox("them@example.org").unwrap()),
+            "Receipt",
+            &item(),
+            Some(screenshot),
+        )
+        .unwrap();
+        let draft = String::from_utf8(draft_bytes(&message)).unwrap();
+        assert!(draft.starts_with("X-Unsent: 1\r\n"));
+        assert!(draft.contains("To: them@example.org"));
+        assert!(draft.contains("Content-Disposition: attachment; filename=\"capture.png\""));
+        assert!(mailbox("not an address").is_err());
+    }
+}
@@ -0,0 +1,223 @@
+// History encryption at rest
+// The file-based history (ocr_history.json) can be stored encrypted with
+// XChaCha20-Poly1305. History kept in localStorage can't be, so encryption is
+// only enabled with a data directory set. The key is derived from a passphrase
+// with Argon2id and kept in the OS keychain, so loading stays transparent. The
+// salt is stored in the file, so the passphrase alone unlocks it on another
+// machine.
+//
+// Format: "SCREENINU-ENC1:" + base64(salt[16] | nonce[24] | ciphertext)
+
+use argon2::Argon2;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+use tauri::AppHandle;
+
+use crate::error::{AppError, AppResult};
+
+/// Prefix marking encrypted content
+pub const ENCRYPTED_PREFIX: &str = "SCREENINU-ENC1:";
+
+const KEYRING_USER: &str = "history-key";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Encryption settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EncryptionConfig {
+    /// Encrypt history when saving
+    pub history_encrypted: bool,
+}
+
+/// Derived key plus the salt it was derived with
+struct StoredKey {
+    salt: [u8; SALT_LEN],
+    key: [u8; 32],
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> AppResult<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Other(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+fn load_key() -> AppResult<Option<StoredKey>> {
+    let Some(secret) = crate::secrets::load(KEYRING_USER)? else {
+        return Ok(None);
+    };
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(secret)
+        .map_err(|_| "Corrupt key in keychain")?;
+    if bytes.len() != SALT_LEN + 32 {
+        return Err("Corrupt key in keychain".into());
+    }
+    let mut stored = StoredKey { salt: [0; SALT_LEN], key: [0; 32] };
+    stored.salt.copy_from_slice(&bytes[..SALT_LEN]);
+    stored.key.copy_from_slice(&bytes[SALT_LEN..]);
+    Ok(Some(stored))
+}
+
+fn store_key(stored: &StoredKey) -> AppResult<()> {
+    let mut bytes = stored.salt.to_vec();
+    bytes.extend_from_slice(&stored.key);
+    crate::secrets::store(KEYRING_USER, &base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+fn encrypt_with(stored: &StoredKey, plaintext: &str) -> AppResult<String> {
+    let cipher = XChaCha20Poly1305::new(&stored.key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| AppError::Other("Encryption failed".to_string()))?;
+
+    let mut payload = stored.salt.to_vec();
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&ciphertext);
+    Ok(format!(
+        "{}{}",
+        ENCRYPTED_PREFIX,
+        base64::engine::general_purpose::STANDARD.encode(payload)
+    ))
+}
+
+fn decrypt_with(key: &[u8; 32], payload: &[u8]) -> AppResult<String> {
+    let nonce = XNonce::from_slice(&payload[SALT_LEN..SALT_LEN + NONCE_LEN]);
+    let plaintext = XChaCha20Poly1305::new(key.into())
+        .decrypt(nonce, &payload[SALT_LEN + NONCE_LEN..])
+        .map_err(|_| AppError::InvalidInput("Wrong passphrase or corrupted history".to_string()))?;
+    String::from_utf8(plaintext).map_err(|_| AppError::InvalidInput("Corrupted history".to_string()))
+}
+
+/// Decrypt content produced by `encrypt`. Plain content is returned unchanged.
+/// Uses the keychain key, or derives one from `passphrase` when given.
+pub fn decrypt(content: &str, passphrase: Option<&str>) -> AppResult<String> {
+    let Some(encoded) = content.trim().strip_prefix(ENCRYPTED_PREFIX) else {
+        return Ok(content.to_string());
+    };
+    let payload = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|_| AppError::InvalidInput("Corrupted history".to_string()))?;
+    if payload.len() < SALT_LEN + NONCE_LEN {
+        return Err(AppError::InvalidInput("Corrupted history".to_string()));
+    }
+    let salt = &payload[..SALT_LEN];
+
+    if let Some(passphrase) = passphrase {
+        let key = derive_key(passphrase, salt)?;
+        let plaintext = decrypt_with(&key, &payload)?;
+        // Remember the key so later loads don't ask again
+        let mut stored = StoredKey { salt: [0; SALT_LEN], key };
+        stored.salt.copy_from_slice(salt);
+        store_key(&stored)?;
+        return Ok(plaintext);
+    }
+
+    match load_key()? {
+        Some(stored) if stored.salt == salt => decrypt_with(&stored.key, &payload),
+        _ => Err(AppError::PermissionDenied(
+            "History is encrypted. Enter the passphrase to unlock it.".to_string(),
+        )),
+    }
+}
+
+/// Encrypt content if history encryption is enabled, otherwise return it unchanged
+pub fn encrypt_if_enabled(plaintext: &str) -> AppResult<String> {
+    if !crate::config::get()?.encryption.history_encrypted {
+        return Ok(plaintext.to_string());
+    }
+    let stored = load_key()?.ok_or_else(|| {
+        AppError::PermissionDenied("Encryption key missing from keychain".to_string())
+    })?;
+    encrypt_with(&stored, plaintext)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Enable history encryption with a new passphrase-derived key. Refused while
+/// history lives in localStorage, which stays plain text.
+#[tauri::command]
+pub fn sync_enable_encryption(app: AppHandle, passphrase: String) -> AppResult<()> {
+    if crate::history::history_file_path(&app)?.is_none() {
+        return Err(AppError::Unsupported(
+            "History is kept in app storage, which can't be encrypted. Choose a data directory first.".to_string(),
+        ));
+    }
+    if crate::config::get()?.folder_sync.enabled {
+        return Err(AppError::Unsupported(
+            "Folder sync writes history unencrypted. Turn it off before enabling encryption.".to_string(),
+        ));
+    }
+    if passphrase.chars().count() < 8 {
+        return Err(AppError::InvalidInput(
+            "Passphrase must be at least 8 characters".to_string(),
+        ));
+    }
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(&passphrase, &salt)?;
+    store_key(&StoredKey { salt, key })?;
+    crate::config::update(|c| c.encryption.history_encrypted = true)?;
+    crate::history_edits::rewrite_logs(&app)
+}
+
+/// Disable history encryption; the next save writes plain JSON
+#[tauri::command]
+pub fn sync_disable_encryption(app: AppHandle) -> AppResult<()> {
+    crate::config::update(|c| c.encryption.history_encrypted = false)?;
+    // Before the key is gone
+    crate::history_edits::rewrite_logs(&app)?;
+    crate::secrets::delete(KEYRING_USER)
+}
+
+/// Whether history encryption is enabled
+#[tauri::command]
+pub fn sync_encryption_enabled() -> AppResult<bool> {
+    Ok(crate::config::get()?.encryption.history_encrypted)
+}
+
+/// Encrypt serialized history before writing it (no-op when disabled)
+#[tauri::command]
+pub fn sync_encrypt_history(content: String) -> AppResult<String> {
+    encrypt_if_enabled(&content)
+}
+
+/// Decrypt history content read from disk (no-op for plain JSON)
+#[tauri::command]
+pub fn sync_decrypt_history(content: String, passphrase: Option<String>) -> AppResult<String> {
+    decrypt(&content, passphrase.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_with_passphrase() {
+        let salt = [7u8; SALT_LEN];
+        let stored = StoredKey { salt, key: derive_key("correct horse", &salt).unwrap() };
+        let encrypted = encrypt_with(&stored, "[{\"text\":\"secret\"}]").unwrap();
+        assert!(encrypted.starts_with(ENCRYPTED_PREFIX));
+        assert!(!encrypted.contains("secret"));
+
+        let payload = base64::engine::general_purpose::STANDARD
+            .decode(encrypted.strip_prefix(ENCRYPTED_PREFIX).unwrap())
+            .unwrap();
+        assert_eq!(decrypt_with(&stored.key, &payload).unwrap(), "[{\"text\":\"secret\"}]");
+
+        let wrong = derive_key("wrong horse", &salt).unwrap();
+        assert!(decrypt_with(&wrong, &payload).is_err());
+    }
+
+    #[test]
+    fn test_plain_content_passthrough() {
+        assert_eq!(decrypt("[]", None).unwrap(), "[]");
+    }
+}
@@ -0,0 +1,300 @@
+// Entity extraction
+// Finds URLs, email addresses, phone numbers, dates and street addresses in
+// OCR text and returns them typed, so the UI can offer "open link", "compose
+// email" or "create calendar event" next to the result. The last extraction is
+// kept for `open_detected_url`, which opens an entity by index (links in the
+// browser, emails and phone numbers through mailto:/tel:). Matching is
+// heuristic: patterns are tried in priority order and later ones never
+// overlap an earlier match, so a date isn't also read as a phone number.
+
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::error::{AppError, AppResult};
+
+/// Entities of the last `extract_entities` call
+static LAST: Lazy<Mutex<Vec<Entity>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+static URL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)\b(?:https?://|www\.)[^\s<>"'`]+"#).expect("URL regex is valid")
+});
+static EMAIL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b").expect("email regex is valid")
+});
+static ISO_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})\b").expect("date regex is valid")
+});
+static NUMERIC_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(\d{1,2})[/.](\d{1,2})[/.](\d{4})\b").expect("date regex is valid")
+});
+static CJK_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d{4})\s*年\s*(\d{1,2})\s*月\s*(\d{1,2})\s*[日号號]").expect("date regex is valid")
+});
+static MONTH_NAME_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:(\d{1,2})(?:st|nd|rd|th)?\s+([a-z]{3,9})\.?,?\s+(\d{4})|([a-z]{3,9})\.?\s+(\d{1,2})(?:st|nd|rd|th)?,?\s+(\d{4}))\b")
+        .expect("date regex is valid")
+});
+static PHONE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{1,4}\)[\s.-]?)?\d{2,4}(?:[\s.-]?\d{2,4}){1,4}").expect("phone regex is valid")
+});
+static STREET_ADDRESS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\b\d{1,5}\s+(?:[A-Z][A-Za-z]*\.?\s+){1,4}(?:Street|St|Avenue|Ave|Road|Rd|Boulevard|Blvd|Lane|Ln|Drive|Dr|Way|Court|Ct|Place|Pl|Square|Sq)\b\.?(?:,?\s+(?:Apt|Suite|Unit)\.?\s*\w+)?(?:,\s*[A-Z][A-Za-z]+(?:\s[A-Z][A-Za-z]+)*)?(?:,\s*[A-Z]{2}\s+\d{5}(?:-\d{4})?)?",
+    )
+    .expect("address regex is valid")
+});
+static CJK_ADDRESS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\p{Han}{1,6}(?:市|縣|县|都|道|府|省)\p{Han}{1,6}(?:區|区|市|町|村|鄉|乡|鎮|镇)[\p{Han}\d\-－]*?(?:\d+\s*[號号]|\d+\s*番地?|\d+丁目[\d\-－]*)")
+        .expect("address regex is valid")
+});
+
+/// Digits a phone number has at least and at most
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 7..=15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    Url,
+    Email,
+    Phone,
+    Date,
+    Address,
+}
+
+/// Something actionable found in the text
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entity {
+    pub kind: EntityKind,
+    /// As it appears in the text
+    pub text: String,
+    /// Normalized form: a URL with its scheme, a phone number as "+" and
+    /// digits, a date as YYYY-MM-DD
+    pub value: String,
+    /// UTF-16 offsets in the text, as JS strings index it
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Entity {
+    /// What `open_detected_url` opens
+    pub fn open_url(&self) -> Option<String> {
+        match self.kind {
+            EntityKind::Url => Some(self.value.clone()),
+            EntityKind::Email => Some(format!("mailto:{}", self.value)),
+            EntityKind::Phone => Some(format!("tel:{}", self.value)),
+            EntityKind::Date | EntityKind::Address => None,
+        }
+    }
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+    let name = name.to_lowercase();
+    let full = [
+        "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november",
+        "december",
+    ];
+    MONTHS
+        .iter()
+        .zip(full)
+        .position(|(short, full)| name == *short || name == full || (name.len() >= 3 && full.starts_with(&name)))
+        .map(|i| i as u32 + 1)
+}
+
+fn ymd(year: &str, month: u32, day: &str) -> Option<String> {
+    let date = NaiveDate::from_ymd_opt(year.parse().ok()?, month, day.parse().ok()?)?;
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+/// Byte span and normalized value of each match
+pub(crate) type Found = Vec<(usize, usize, String)>;
+
+fn find_urls(text: &str) -> Found {
+    URL.find_iter(text)
+        .filter_map(|m| {
+            // Sentence punctuation after a link isn't part of it
+            let trimmed = m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '}', '。', '，', '、']);
+            if trimmed.len() <= "www.".len() {
+                return None;
+            }
+            let value = if trimmed.to_lowercase().starts_with("www.") {
+                format!("https://{}", trimmed)
+            } else {
+                trimmed.to_string()
+            };
+            Some((m.start(), m.start() + trimmed.len(), value))
+        })
+        .collect()
+}
+
+fn find_emails(text: &str) -> Found {
+    EMAIL.find_iter(text).map(|m| (m.start(), m.end(), m.as_str().to_string())).collect()
+}
+
+/// Dates as YYYY-MM-DD, in pattern order rather than text order
+pub(crate) fn find_dates(text: &str) -> Found {
+    let mut found = Found::new();
+    for caps in ISO_DATE.captures_iter(text).chain(CJK_DATE.captures_iter(text)) {
+        if let Some(value) = caps[2].parse().ok().and_then(|month| ymd(&caps[1], month, &caps[3])) {
+            let m = caps.get(0).unwrap();
+            found.push((m.start(), m.end(), value));
+        }
+    }
+    for caps in NUMERIC_DATE.captures_iter(text) {
+        // Month first unless that can't be (13/02/2026)
+        let (a, b): (u32, u32) = match (caps[1].parse(), caps[2].parse()) {
+            (Ok(a), Ok(b)) => (a, b),
+            _ => continue,
+        };
+        let (month, day) = if a > 12 { (b, a) } else { (a, b) };
+        if let Some(value) = ymd(&caps[3], month, &day.to_string()) {
+            let m = caps.get(0).unwrap();
+            found.push((m.start(), m.end(), value));
+        }
+    }
+    for caps in MONTH_NAME_DATE.captures_iter(text) {
+        let value = match (caps.get(1), caps.get(4)) {
+            (Some(day), _) => month_number(&caps[2]).and_then(|month| ymd(&caps[3], month, day.as_str())),
+            (None, Some(month)) => month_number(month.as_str()).and_then(|month| ymd(&caps[6], month, &caps[5])),
+            _ => None,
+        };
+        if let Some(value) = value {
+            let m = caps.get(0).unwrap();
+            found.push((m.start(), m.end(), value));
+        }
+    }
+    found
+}
+
+fn find_phones(text: &str) -> Found {
+    PHONE
+        .find_iter(text)
+        .filter_map(|m| {
+            let digits: String = m.as_str().chars().filter(char::is_ascii_digit).collect();
+            if !PHONE_DIGITS.contains(&digits.len()) {
+                return None;
+            }
+            // Part of a longer number or word, e.g. an order id
+            let before = text[..m.start()].chars().next_back();
+            let after = text[m.end()..].chars().next();
+            if before.is_some_and(|c| c.is_alphanumeric()) || after.is_some_and(|c| c.is_alphanumeric()) {
+                return None;
+            }
+            let plus = if m.as_str().starts_with('+') { "+" } else { "" };
+            Some((m.start(), m.end(), format!("{}{}", plus, digits)))
+        })
+        .collect()
+}
+
+fn find_addresses(text: &str) -> Found {
+    STREET_ADDRESS
+        .find_iter(text)
+        .chain(CJK_ADDRESS.find_iter(text))
+        .map(|m| (m.start(), m.end(), m.as_str().to_string()))
+        .collect()
+}
+
+fn utf16_offset(text: &str, byte: usize) -> usize {
+    text[..byte].encode_utf16().count()
+}
+
+/// Entities in `text`, in order of appearance
+pub fn extract(text: &str) -> Vec<Entity> {
+    let finders: [(EntityKind, fn(&str) -> Found); 5] = [
+        (EntityKind::Url, find_urls),
+        (EntityKind::Email, find_emails),
+        (EntityKind::Date, find_dates),
+        (EntityKind::Phone, find_phones),
+        (EntityKind::Address, find_addresses),
+    ];
+    let mut spans: Vec<(usize, usize, EntityKind, String)> = Vec::new();
+    for (kind, find) in finders {
+        for (start, end, value) in find(text) {
+            if !spans.iter().any(|(s, e, _, _)| start < *e && *s < end) {
+                spans.push((start, end, kind, value));
+            }
+        }
+    }
+    spans.sort_by_key(|(start, ..)| *start);
+    spans
+        .into_iter()
+        .map(|(start, end, kind, value)| Entity {
+            kind,
+            text: text[start..end].to_string(),
+            value,
+            start: utf16_offset(text, start),
+            end: utf16_offset(text, end),
+        })
+        .collect()
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// URLs, emails, phone numbers, dates and addresses in OCR text
+#[tauri::command]
+pub fn extract_entities(text: String) -> AppResult<Vec<Entity>> {
+    let entities = extract(&text);
+    *LAST.lock()? = entities.clone();
+    Ok(entities)
+}
+
+/// Open entity `index` of the last extraction: a link in the browser, an
+/// email or phone number in the mail or phone app
+#[tauri::command]
+pub fn open_detected_url(app: tauri::AppHandle, index: usize) -> AppResult<()> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let entity = LAST
+        .lock()?
+        .get(index)
+        .cloned()
+        .ok_or_else(|| AppError::InvalidInput(format!("No detected entity #{}", index)))?;
+    let url = entity
+        .open_url()
+        .ok_or_else(|| AppError::InvalidInput(format!("Can't open {:?} \"{}\"", entity.kind, entity.text)))?;
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| AppError::Other(format!("Failed to open link: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(text: &str) -> Vec<(EntityKind, String)> {
+        extract(text).into_iter().map(|e| (e.kind, e.value)).collect()
+    }
+
+    #[test]
+    fn test_extract() {
+        let text = "Visit www.example.com/docs. Mail hi@example.org or call +1 (555) 123-4567 before 2026-10-16.";
+        assert_eq!(
+            kinds(text),
+            vec![
+                (EntityKind::Url, "https://www.example.com/docs".to_string()),
+                (EntityKind::Email, "hi@example.org".to_string()),
+                (EntityKind::Phone, "+15551234567".to_string()),
+                (EntityKind::Date, "2026-10-16".to_string()),
+            ]
+        );
+        assert_eq!(kinds("Due Oct 5, 2026 or 13/02/2026")[0].1, "2026-10-05");
+        assert_eq!(kinds("Due Oct 5, 2026 or 13/02/2026")[1].1, "2026-02-13");
+        assert_eq!(kinds("2026年3月1日"), vec![(EntityKind::Date, "2026-03-01".to_string())]);
+        assert_eq!(kinds("Order A12345678 total 42"), vec![]);
+        assert_eq!(kinds("Ship to 221 Baker Street, London")[0].0, EntityKind::Address);
+    }
+
+    #[test]
+    fn test_utf16_offsets() {
+        let text = "連絡 hi@example.org";
+        let entity = &extract(text)[0];
+        assert_eq!((entity.start, entity.end), (3, 17));
+        assert_eq!(entity.open_url().as_deref(), Some("mailto:hi@example.org"));
+    }
+}
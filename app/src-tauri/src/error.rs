@@ -0,0 +1,153 @@
+//! Application error type
+//!
+//! Every Tauri command returns `AppResult<T>`. Errors are serialized to the
+//! frontend as `{ "code": "...", "message": "..." }` so the UI can branch on
+//! the code instead of parsing messages. The message is in the UI language;
+//! `Display` (used for logs) stays in English.
+
+use serde::ser::SerializeStruct;
+
+/// Application-wide error
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("Model '{0}' not found. Please download it first.")]
+    ModelMissing(String),
+
+    #[error("Tesseract not found: {0}. Please ensure Tesseract is correctly installed.")]
+    TesseractNotFound(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Screen capture failed: {0}")]
+    Capture(String),
+
+    #[error("OCR failed: {0}")]
+    Ocr(String),
+
+    #[error("Translation failed: {0}")]
+    Translation(String),
+
+    #[error("Summarization failed: {0}")]
+    Summarization(String),
+
+    #[error("Speech error: {0}")]
+    Tts(String),
+
+    #[error("Download failed: {0}")]
+    Download(String),
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("Not supported: {0}")]
+    Unsupported(String),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AppError {
+    /// Stable machine-readable error code for the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::ModelMissing(_) => "model_missing",
+            AppError::TesseractNotFound(_) => "tesseract_not_found",
+            AppError::PermissionDenied(_) => "permission_denied",
+            AppError::Capture(_) => "capture_failed",
+            AppError::Ocr(_) => "ocr_failed",
+            AppError::Translation(_) => "translation_failed",
+            AppError::Summarization(_) => "summarization_failed",
+            AppError::Tts(_) => "tts_failed",
+            AppError::Download(_) => "download_failed",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::Unsupported(_) => "unsupported",
+            AppError::Cancelled => "cancelled",
+            AppError::Io(e) if e.kind() == std::io::ErrorKind::PermissionDenied => "permission_denied",
+            AppError::Io(_) => "io_error",
+            AppError::Other(_) => "unknown",
+        }
+    }
+
+    /// Message in the UI language. The cause inside is left as is.
+    pub fn localized_message(&self) -> String {
+        let (id, detail) = match self {
+            AppError::ModelMissing(detail) => ("error-model-missing", detail.clone()),
+            AppError::TesseractNotFound(detail) => ("error-tesseract-not-found", detail.clone()),
+            AppError::PermissionDenied(detail) => ("error-permission-denied", detail.clone()),
+            AppError::Capture(detail) => ("error-capture", detail.clone()),
+            AppError::Ocr(detail) => ("error-ocr", detail.clone()),
+            AppError::Translation(detail) => ("error-translation", detail.clone()),
+            AppError::Summarization(detail) => ("error-summarization", detail.clone()),
+            AppError::Tts(detail) => ("error-tts", detail.clone()),
+            AppError::Download(detail) => ("error-download", detail.clone()),
+            AppError::InvalidInput(detail) => ("error-invalid-input", detail.clone()),
+            AppError::Unsupported(detail) => ("error-unsupported", detail.clone()),
+            AppError::Cancelled => ("error-cancelled", String::new()),
+            AppError::Io(e) => ("error-io", e.to_string()),
+            AppError::Other(message) => return message.clone(),
+        };
+        crate::i18n::t_args(id, &[("detail", &detail)])
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other(message.to_string())
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(e: std::sync::PoisonError<T>) -> Self {
+        AppError::Other(e.to_string())
+    }
+}
+
+impl serde::Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.localized_message())?;
+        state.end()
+    }
+}
+
+/// Result type used by all commands
+pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_serializes_code_and_message() {
+        let err = AppError::ModelMissing("opus-mt-en-zh".to_string());
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "model_missing");
+        assert_eq!(
+            json["message"],
+            "Model 'opus-mt-en-zh' not found. Please download it first."
+        );
+    }
+
+    #[test]
+    fn test_io_permission_error_maps_to_permission_code() {
+        let err: AppError = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "nope").into();
+        assert_eq!(err.code(), "permission_denied");
+    }
+}
@@ -0,0 +1,177 @@
+// Pipeline lifecycle events
+// Capture, OCR, translation and model downloads report their stages the same
+// way, wherever they run: `<pipeline>://started`, then `<pipeline>://succeeded`
+// or `<pipeline>://failed`, so the UI, plugins and the tray icon can react to
+// stages instead of waiting on command results. New history items are
+// announced on `sync://item-added`, model changes on `models://changed`.
+// Module-specific events (progress, live OCR, ...) keep their own names.
+
+use std::time::Instant;
+
+use once_cell::sync::OnceCell;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppResult;
+
+/// Emitted with each history item added: captures, which the frontend reports
+/// through `history::sync_item_added`, and imports
+pub const SYNC_ITEM_ADDED_EVENT: &str = "sync://item-added";
+
+/// Emitted when OCR or translation models are deleted or imported; downloads
+/// end with `download://succeeded`
+pub const MODELS_CHANGED_EVENT: &str = "models://changed";
+
+static APP: OnceCell<AppHandle> = OnceCell::new();
+
+/// Pipelines with lifecycle events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pipeline {
+    Capture,
+    Ocr,
+    Translate,
+    Download,
+}
+
+impl Pipeline {
+    pub const ALL: [Pipeline; 4] = [Pipeline::Capture, Pipeline::Ocr, Pipeline::Translate, Pipeline::Download];
+}
+
+/// Lifecycle stages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Started,
+    Succeeded,
+    Failed,
+}
+
+impl Pipeline {
+    /// Event name for a stage, e.g. "ocr://succeeded"
+    pub fn event(self, stage: Stage) -> String {
+        let pipeline = match self {
+            Pipeline::Capture => "capture",
+            Pipeline::Ocr => "ocr",
+            Pipeline::Translate => "translate",
+            Pipeline::Download => "download",
+        };
+        let stage = match stage {
+            Stage::Started => "started",
+            Stage::Succeeded => "succeeded",
+            Stage::Failed => "failed",
+        };
+        format!("{}://{}", pipeline, stage)
+    }
+}
+
+/// Payload of every lifecycle event
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineEvent {
+    /// Operation id (or OCR job id) when the caller gave one
+    pub op_id: Option<String>,
+    /// Command or background task that ran the stage
+    pub source: &'static str,
+    /// Set once finished
+    pub duration_ms: Option<u64>,
+    /// Error code and message on failure; cancellation fails with "cancelled"
+    pub error_code: Option<&'static str>,
+    pub error: Option<String>,
+}
+
+/// A started pipeline stage; `finish` reports how it ended
+#[must_use = "call finish() to report the outcome"]
+pub struct Run {
+    pipeline: Pipeline,
+    source: &'static str,
+    op_id: Option<String>,
+    started: Instant,
+}
+
+impl Run {
+    /// Emit succeeded or failed for `result` and pass it through
+    pub fn finish<T>(self, result: AppResult<T>) -> AppResult<T> {
+        let mut payload = PipelineEvent {
+            op_id: self.op_id,
+            source: self.source,
+            duration_ms: Some(self.started.elapsed().as_millis() as u64),
+            error_code: None,
+            error: None,
+        };
+        let stage = match &result {
+            Ok(_) => Stage::Succeeded,
+            Err(e) => {
+                payload.error_code = Some(e.code());
+                payload.error = Some(e.localized_message());
+                Stage::Failed
+            }
+        };
+        emit(&self.pipeline.event(stage), payload);
+        result
+    }
+}
+
+/// Keep the handle events are emitted through
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Emit to all windows; a no-op before `init` (and in tests)
+pub fn emit<S: serde::Serialize + Clone>(event: &str, payload: S) {
+    if let Some(app) = APP.get() {
+        if let Err(e) = app.emit(event, payload) {
+            tracing::debug!("Failed to emit {}: {}", event, e);
+        }
+    }
+}
+
+/// Emit `<pipeline>://started` and time the stage
+pub fn start(pipeline: Pipeline, source: &'static str, op_id: Option<String>) -> Run {
+    emit(
+        &pipeline.event(Stage::Started),
+        PipelineEvent {
+            op_id: op_id.clone(),
+            source,
+            duration_ms: None,
+            error_code: None,
+            error: None,
+        },
+    );
+    Run {
+        pipeline,
+        source,
+        op_id,
+        started: Instant::now(),
+    }
+}
+
+/// Run `f` as a pipeline stage
+pub fn track<T>(
+    pipeline: Pipeline,
+    source: &'static str,
+    op_id: Option<String>,
+    f: impl FnOnce() -> AppResult<T>,
+) -> AppResult<T> {
+    start(pipeline, source, op_id).finish(f())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_names() {
+        assert_eq!(Pipeline::Capture.event(Stage::Started), "capture://started");
+        assert_eq!(Pipeline::Ocr.event(Stage::Succeeded), "ocr://succeeded");
+        assert_eq!(Pipeline::Translate.event(Stage::Failed), "translate://failed");
+        assert_eq!(Pipeline::Download.event(Stage::Started), "download://started");
+    }
+
+    #[test]
+    fn test_finish_passes_result_through() {
+        let run = start(Pipeline::Ocr, "test", None);
+        assert_eq!(run.finish(Ok(3)).unwrap(), 3);
+        let result = track(Pipeline::Ocr, "test", Some("op".to_string()), || {
+            Err::<(), _>(crate::error::AppError::Cancelled)
+        });
+        assert!(matches!(result, Err(crate::error::AppError::Cancelled)));
+    }
+}
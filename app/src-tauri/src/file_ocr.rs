@@ -0,0 +1,181 @@
+// File OCR
+// OCRs image files directly: photos dragged from a phone onto the window, or
+// scans. Every page of a multi-page TIFF is recognized; formats the OCR
+// engines don't read natively (HEIC, AVIF, WebP, ...) are converted to PNG
+// first. Photos of paper documents can be flattened and relit first
+// (`document_scan`).
+
+use std::path::Path;
+
+use image::{DynamicImage, GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::ColorType;
+
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Pipeline};
+use crate::ocr::{self, OcrOptions};
+
+/// OCR output for one file
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOcrResult {
+    pub path: String,
+    /// Text of all pages, separated by blank lines
+    pub text: String,
+    /// Text per page (one entry except for multi-page TIFFs)
+    pub pages: Vec<String>,
+    /// Set when the file couldn't be read or recognized
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FileKind {
+    /// Passed to the engines as-is
+    Native,
+    /// Decoded and re-encoded as PNG
+    Convert,
+    Tiff,
+}
+
+fn file_kind(path: &Path) -> Option<FileKind> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" | "jpg" | "jpeg" => Some(FileKind::Native),
+        "webp" | "bmp" | "gif" | "heic" | "heif" | "avif" => Some(FileKind::Convert),
+        "tif" | "tiff" => Some(FileKind::Tiff),
+        _ => None,
+    }
+}
+
+fn encode_png(image: DynamicImage) -> AppResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Ocr(format!("Failed to encode image: {}", e)))?;
+    Ok(bytes)
+}
+
+/// Pixels of one TIFF page as an image (8 and 16 bit gray/RGB, with or
+/// without alpha)
+fn tiff_page(width: u32, height: u32, color: ColorType, data: DecodingResult) -> AppResult<DynamicImage> {
+    let samples = match data {
+        DecodingResult::U8(samples) => samples,
+        // Keep the high byte of 16-bit samples
+        DecodingResult::U16(samples) => samples.into_iter().map(|s| (s >> 8) as u8).collect(),
+        _ => return Err(AppError::Unsupported("Unsupported TIFF sample format".to_string())),
+    };
+    let image = match color {
+        ColorType::Gray(_) => GrayImage::from_raw(width, height, samples).map(DynamicImage::ImageLuma8),
+        ColorType::GrayA(_) => GrayAlphaImage::from_raw(width, height, samples).map(DynamicImage::ImageLumaA8),
+        ColorType::RGB(_) => RgbImage::from_raw(width, height, samples).map(DynamicImage::ImageRgb8),
+        ColorType::RGBA(_) => RgbaImage::from_raw(width, height, samples).map(DynamicImage::ImageRgba8),
+        other => return Err(AppError::Unsupported(format!("Unsupported TIFF color type: {:?}", other))),
+    };
+    image.ok_or_else(|| AppError::InvalidInput("TIFF page is truncated".to_string()))
+}
+
+/// Every page of a TIFF as PNG
+fn tiff_pages(bytes: &[u8]) -> AppResult<Vec<Vec<u8>>> {
+    let tiff_error = |e: tiff::TiffError| AppError::InvalidInput(format!("Failed to decode TIFF: {}", e));
+    let mut decoder = Decoder::new(std::io::Cursor::new(bytes)).map_err(tiff_error)?;
+    let mut pages = Vec::new();
+    loop {
+        let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+        let color = decoder.colortype().map_err(tiff_error)?;
+        let data = decoder.read_image().map_err(tiff_error)?;
+        pages.push(encode_png(tiff_page(width, height, color, data)?)?);
+        if !decoder.more_images() {
+            break;
+        }
+        decoder.next_image().map_err(tiff_error)?;
+    }
+    Ok(pages)
+}
+
+/// Encoded images to OCR for a file, one per page
+fn load_pages(path: &Path) -> AppResult<Vec<Vec<u8>>> {
+    let kind = file_kind(path)
+        .ok_or_else(|| AppError::Unsupported(format!("Unsupported image file: {}", path.display())))?;
+    let bytes = std::fs::read(path)?;
+    match kind {
+        FileKind::Native => Ok(vec![bytes]),
+        FileKind::Convert => Ok(vec![encode_png(crate::image_decode::decode_image_bytes(&bytes)?)?]),
+        FileKind::Tiff => tiff_pages(&bytes),
+    }
+}
+
+fn ocr_file(path: &Path, options: &OcrOptions, document: bool) -> AppResult<Vec<String>> {
+    load_pages(path)?
+        .into_iter()
+        .map(|page| {
+            let page = if document { crate::document_scan::scan_to_png(&page)?.0 } else { page };
+            ocr::run_ocr(&page, options).map(|text| text.trim().to_string())
+        })
+        .collect()
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// OCR image files (PNG, JPEG, WebP, BMP, GIF, TIFF, HEIC, AVIF). Files that fail are
+/// reported with an error instead of failing the whole batch. With `document`
+/// each page is treated as a photo of paper and straightened first.
+#[tauri::command(async)]
+pub fn perform_file_ocr(
+    paths: Vec<String>,
+    langs: Option<String>,
+    engine: Option<String>,
+    document: Option<bool>,
+) -> AppResult<Vec<FileOcrResult>> {
+    let document = document.unwrap_or(false);
+    let options = OcrOptions {
+        langs,
+        engine,
+        ..Default::default()
+    }
+    .with_reocr_config();
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let result = events::track(Pipeline::Ocr, "perform_file_ocr", None, || {
+                ocr_file(Path::new(&path), &options, document)
+            });
+            match result {
+                Ok(pages) => FileOcrResult {
+                    text: pages.iter().filter(|p| !p.is_empty()).cloned().collect::<Vec<_>>().join("\n\n"),
+                    pages,
+                    path,
+                    error: None,
+                },
+                Err(e) => FileOcrResult {
+                    path,
+                    text: String::new(),
+                    pages: Vec::new(),
+                    error: Some(e.localized_message()),
+                },
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_kind() {
+        assert_eq!(file_kind(Path::new("scan.TIFF")), Some(FileKind::Tiff));
+        assert_eq!(file_kind(Path::new("photo.jpeg")), Some(FileKind::Native));
+        assert_eq!(file_kind(Path::new("IMG_0001.HEIC")), Some(FileKind::Convert));
+        assert_eq!(file_kind(Path::new("notes.txt")), None);
+        assert_eq!(file_kind(Path::new("README")), None);
+    }
+
+    #[test]
+    fn test_tiff_page_16_bit_gray() {
+        let image = tiff_page(2, 1, ColorType::Gray(16), DecodingResult::U16(vec![0xFFFF, 0x1200])).unwrap();
+        assert_eq!(image.to_luma8().into_raw(), vec![0xFF, 0x12]);
+        assert!(tiff_page(2, 2, ColorType::Gray(8), DecodingResult::U8(vec![0])).is_err());
+    }
+}
@@ -0,0 +1,397 @@
+// History sync through a shared folder
+// Syncs history through a folder that Dropbox, Syncthing or a network drive
+// keeps in step, without a server. Each device only ever writes its own
+// subfolder, <folder>/screen-inu/<device>/: one file of changes per sync
+// (items added or changed, ids deleted), folded into snapshot.json once there
+// are many. Reading merges every device's changes, the latest change to an
+// item winning. Files are written under a temporary name and renamed, the
+// device's folder is locked while it writes, and files that don't parse
+// (half-synced, or mangled by a sync client) are skipped until they do.
+// Conflicted copies a sync client makes are ordinary change files here.
+//
+// Change files are plain JSON, so folder sync is refused while history is
+// encrypted at rest (encryption.rs): the key is per device, and the point of
+// encryption is that screen content doesn't reach a cloud folder in the clear.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::error::{AppError, AppResult};
+use crate::history::HistoryItem;
+
+const SYNC_DIR: &str = "screen-inu";
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const LOCK_FILE: &str = ".lock";
+const STATE_FILE: &str = "folder_sync_state.json";
+
+/// A lock older than this was left by a crashed writer
+const STALE_LOCK: Duration = Duration::from_secs(60);
+
+/// Shared folder sync settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FolderSyncConfig {
+    pub enabled: bool,
+    pub folder: Option<String>,
+    /// Fold this device's change files into its snapshot past this many
+    pub compact_after: usize,
+}
+
+impl Default for FolderSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folder: None,
+            compact_after: 50,
+        }
+    }
+}
+
+/// A change to one history item
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum Change {
+    Put { item: HistoryItem },
+    Delete { id: String },
+}
+
+impl Change {
+    fn id(&self) -> &str {
+        match self {
+            Change::Put { item } => &item.id,
+            Change::Delete { id } => id,
+        }
+    }
+}
+
+/// A change with its ordering: the latest (clock, device) wins
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stamped {
+    /// Unix epoch milliseconds, increasing on each device
+    clock: i64,
+    device: String,
+    #[serde(flatten)]
+    change: Change,
+}
+
+/// Held while this device writes its folder
+struct FolderLock(PathBuf);
+
+impl FolderLock {
+    fn acquire(dir: &Path) -> AppResult<Self> {
+        let path = dir.join(LOCK_FILE);
+        for _ in 0..2 {
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self(path)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let age = std::fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+                    if age.is_some_and(|age| age < STALE_LOCK) {
+                        break;
+                    }
+                    let _ = std::fs::remove_file(&path);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(AppError::Other("The sync folder is busy; try again shortly".to_string()))
+    }
+}
+
+impl Drop for FolderLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Write `value` as JSON under a temporary name, then move it into place
+fn write_atomic<T: serde::Serialize>(path: &Path, value: &T) -> AppResult<()> {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.tmp", name));
+    let content = serde_json::to_string(value).map_err(|e| AppError::Other(e.to_string()))?;
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Change files in a device folder; temporary and hidden files excluded
+fn change_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter(|p| !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Every readable change in the sync folder, and the files that weren't
+fn read_all(root: &Path) -> (Vec<Stamped>, Vec<PathBuf>) {
+    let mut changes = Vec::new();
+    let mut skipped = Vec::new();
+    let Ok(devices) = std::fs::read_dir(root) else {
+        return (changes, skipped);
+    };
+    for device in devices.flatten().filter(|d| d.path().is_dir()) {
+        for file in change_files(&device.path()) {
+            // Removed by its device's compaction since listing: in the snapshot now
+            let Ok(content) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            match serde_json::from_str::<Vec<Stamped>>(&content) {
+                Ok(file_changes) => changes.extend(file_changes),
+                Err(_) => skipped.push(file),
+            }
+        }
+    }
+    (changes, skipped)
+}
+
+/// The latest change to each item
+fn latest(changes: Vec<Stamped>) -> HashMap<String, Stamped> {
+    let mut latest: HashMap<String, Stamped> = HashMap::new();
+    for change in changes {
+        let newer = match latest.get(change.change.id()) {
+            Some(current) => (change.clock, &change.device) > (current.clock, &current.device),
+            None => true,
+        };
+        if newer {
+            latest.insert(change.change.id().to_string(), change);
+        }
+    }
+    latest
+}
+
+/// History from merged changes, newest first
+fn merge(changes: Vec<Stamped>) -> Vec<HistoryItem> {
+    let mut items: Vec<HistoryItem> = latest(changes)
+        .into_values()
+        .filter_map(|stamped| match stamped.change {
+            Change::Put { item } => Some(item),
+            Change::Delete { .. } => None,
+        })
+        .collect();
+    items.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| a.id.cmp(&b.id)));
+    items
+}
+
+/// Changes turning `last` (history as of the previous sync) into `current`
+fn diff(last: &[HistoryItem], current: &[HistoryItem]) -> Vec<Change> {
+    let before: HashMap<&str, &HistoryItem> = last.iter().map(|item| (item.id.as_str(), item)).collect();
+    let mut changes: Vec<Change> = current
+        .iter()
+        .filter(|item| before.get(item.id.as_str()) != Some(item))
+        .map(|item| Change::Put { item: item.clone() })
+        .collect();
+    let now: std::collections::HashSet<&str> = current.iter().map(|item| item.id.as_str()).collect();
+    changes.extend(
+        last.iter()
+            .filter(|item| !now.contains(item.id.as_str()))
+            .map(|item| Change::Delete { id: item.id.clone() }),
+    );
+    changes
+}
+
+/// Fold a device's change files into its snapshot. Only the latest change
+/// to each item is kept; deletions stay as tombstones.
+fn compact(dir: &Path) -> AppResult<()> {
+    let files = change_files(dir);
+    let mut changes = Vec::new();
+    let mut merged = Vec::new();
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        // Unreadable files are left for a later pass
+        if let Ok(file_changes) = serde_json::from_str::<Vec<Stamped>>(&content) {
+            changes.extend(file_changes);
+            merged.push(file);
+        }
+    }
+    let mut snapshot: Vec<Stamped> = latest(changes).into_values().collect();
+    snapshot.sort_by_key(|s| s.clock);
+    let snapshot_path = dir.join(SNAPSHOT_FILE);
+    write_atomic(&snapshot_path, &snapshot)?;
+    for file in merged.into_iter().filter(|f| *f != snapshot_path) {
+        let _ = std::fs::remove_file(file);
+    }
+    Ok(())
+}
+
+/// Record this device's changes since `last` and merge in the other
+/// devices'. Returns the merged history and the files skipped.
+fn sync_folder(
+    root: &Path,
+    device: &str,
+    last: &[HistoryItem],
+    current: &[HistoryItem],
+    compact_after: usize,
+) -> AppResult<(Vec<HistoryItem>, Vec<PathBuf>)> {
+    let dir = root.join(device);
+    std::fs::create_dir_all(&dir)?;
+    let changes = diff(last, current);
+    if !changes.is_empty() {
+        let _lock = FolderLock::acquire(&dir)?;
+        // Later than any change this device made before, even with a skewed clock
+        let (existing, _) = read_all(root);
+        let previous = existing.iter().filter(|s| s.device == device).map(|s| s.clock).max();
+        let now = chrono::Utc::now().timestamp_millis();
+        let clock = previous.map_or(now, |p| now.max(p + 1));
+        let stamped: Vec<Stamped> = changes
+            .into_iter()
+            .map(|change| Stamped {
+                clock,
+                device: device.to_string(),
+                change,
+            })
+            .collect();
+        write_atomic(&dir.join(format!("{}.json", clock)), &stamped)?;
+        if change_files(&dir).len() > compact_after.max(1) {
+            compact(&dir)?;
+        }
+    }
+    let (changes, skipped) = read_all(root);
+    Ok((merge(changes), skipped))
+}
+
+/// Refuse to sync while history encryption is on
+fn ensure_unencrypted(history_encrypted: bool) -> AppResult<()> {
+    if history_encrypted {
+        return Err(AppError::Unsupported(
+            "Folder sync writes history unencrypted. Disable history encryption to use it.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn state_path() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(STATE_FILE))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_folder_sync_config() -> AppResult<FolderSyncConfig> {
+    Ok(crate::config::get()?.folder_sync)
+}
+
+#[tauri::command]
+pub fn set_folder_sync_config(config: FolderSyncConfig) -> AppResult<()> {
+    if config.enabled {
+        ensure_unencrypted(crate::config::get()?.encryption.history_encrypted)?;
+    }
+    if let Some(folder) = config.folder.as_deref().filter(|_| config.enabled) {
+        if !Path::new(folder).is_dir() {
+            return Err(AppError::InvalidInput(format!("Not a folder: {}", folder)));
+        }
+    }
+    // Another folder starts from scratch: everything here is new to it
+    if crate::config::get()?.folder_sync.folder != config.folder {
+        let _ = std::fs::remove_file(state_path()?);
+    }
+    crate::config::update(|c| c.folder_sync = config)
+}
+
+/// Sync `items` (the whole history) through the shared folder; returns the
+/// merged history to store, or None when folder sync is off
+#[tauri::command(async)]
+#[tracing::instrument(skip(items), err)]
+pub fn sync_history_folder(items: Vec<HistoryItem>) -> AppResult<Option<Vec<HistoryItem>>> {
+    let app_config = crate::config::get()?;
+    let config = app_config.folder_sync;
+    let Some(folder) = config.folder.filter(|f| config.enabled && !f.trim().is_empty()) else {
+        return Ok(None);
+    };
+    // Encryption may have been enabled since folder sync was
+    ensure_unencrypted(app_config.encryption.history_encrypted)?;
+    let root = Path::new(&folder).join(SYNC_DIR);
+    let device = format!("{:x}", crate::history_edits::device_id()?);
+    let state = state_path()?;
+    let last: Vec<HistoryItem> = std::fs::read_to_string(&state)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let (merged, skipped) = sync_folder(&root, &device, &last, &items, config.compact_after)?;
+    for file in skipped {
+        tracing::warn!("Skipped unreadable sync file {}", file.display());
+    }
+    write_atomic(&state, &merged)?;
+    Ok(Some(merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, text: &str, timestamp: i64) -> HistoryItem {
+        HistoryItem {
+            id: id.to_string(),
+            text: text.to_string(),
+            lang: "eng".to_string(),
+            timestamp,
+            source_app: None,
+            summary: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_two_devices_merge() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let (a1, b1) = (item("a1", "from laptop", 1), item("b1", "from phone", 2));
+
+        let (laptop, _) = sync_folder(root, "laptop", &[], &[a1.clone()], 50).unwrap();
+        assert_eq!(laptop, vec![a1.clone()]);
+        // The phone had its own item and picks up the laptop's
+        let (phone, _) = sync_folder(root, "phone", &[], &[b1.clone()], 50).unwrap();
+        assert_eq!(phone, vec![b1.clone(), a1.clone()]);
+
+        // The laptop deletes its item; the phone edits its own
+        let (laptop, _) = sync_folder(root, "laptop", &phone, &[b1.clone()], 50).unwrap();
+        assert_eq!(laptop, vec![b1.clone()]);
+        let edited = item("b1", "from the phone", 2);
+        let (phone, _) = sync_folder(root, "phone", &phone, &[edited.clone(), a1], 50).unwrap();
+        // The laptop's deletion wins over the phone's unchanged copy
+        assert_eq!(phone, vec![edited]);
+    }
+
+    #[test]
+    fn test_corrupt_files_and_compaction() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let mut history = Vec::new();
+        let mut last = Vec::new();
+        for n in 0..4 {
+            history.insert(0, item(&format!("i{}", n), "text", n));
+            (last, _) = sync_folder(root, "laptop", &last, &history, 2).unwrap();
+        }
+        let files = change_files(&root.join("laptop"));
+        assert!(files.len() <= 3 && files.contains(&root.join("laptop").join(SNAPSHOT_FILE)));
+        assert_eq!(last, history);
+
+        // A half-synced file from another device is skipped, not fatal
+        std::fs::create_dir_all(root.join("phone")).unwrap();
+        std::fs::write(root.join("phone").join("1.json"), "[{\"clock\": 1, \"dev").unwrap();
+        let (merged, skipped) = sync_folder(root, "laptop", &last, &history, 2).unwrap();
+        assert_eq!(merged, history);
+        assert_eq!(skipped, vec![root.join("phone").join("1.json")]);
+    }
+
+    #[test]
+    fn test_refused_while_encrypted() {
+        assert!(ensure_unencrypted(false).is_ok());
+        assert!(matches!(ensure_unencrypted(true), Err(AppError::Unsupported(_))));
+    }
+}
@@ -0,0 +1,200 @@
+// Frame differencing for watch/live modes
+// Finds the regions that changed between two consecutive captures so only those
+// sub-images need to go through OCR.
+
+use image::RgbaImage;
+
+/// Size of the square tiles the frame is divided into
+const TILE_SIZE: u32 = 32;
+
+/// Per-channel difference below which a pixel is considered unchanged
+/// (absorbs compression noise and cursor blink anti-aliasing)
+const PIXEL_TOLERANCE: u8 = 24;
+
+/// A changed rectangle in frame coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Region {
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// A `width` x `height` rectangle centered on (x, y), moved right or down
+    /// as needed to stay at non-negative coordinates
+    pub fn around(x: f64, y: f64, width: u32, height: u32) -> Region {
+        let start = |center: f64, size: u32| (center - size as f64 / 2.0).max(0.0).round() as u32;
+        Region {
+            x: start(x, width),
+            y: start(y, height),
+            width,
+            height,
+        }
+    }
+}
+
+/// Check whether any pixel inside the tile differs beyond the tolerance
+fn tile_changed(prev: &RgbaImage, next: &RgbaImage, x0: u32, y0: u32, x1: u32, y1: u32) -> bool {
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let a = prev.get_pixel(x, y).0;
+            let b = next.get_pixel(x, y).0;
+            for c in 0..3 {
+                if a[c].abs_diff(b[c]) > PIXEL_TOLERANCE {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Compute the bounding regions that changed between `prev` and `next`.
+///
+/// The frame is split into tiles, changed tiles are grouped into 8-connected
+/// clusters, and one bounding rectangle is returned per cluster. If the frames
+/// differ in size the whole of `next` is reported as changed.
+pub fn changed_regions(prev: &RgbaImage, next: &RgbaImage) -> Vec<Region> {
+    let (width, height) = next.dimensions();
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+    if prev.dimensions() != next.dimensions() {
+        return vec![Region { x: 0, y: 0, width, height }];
+    }
+
+    let cols = width.div_ceil(TILE_SIZE) as usize;
+    let rows = height.div_ceil(TILE_SIZE) as usize;
+
+    let mut changed = vec![false; cols * rows];
+    for row in 0..rows {
+        for col in 0..cols {
+            let x0 = col as u32 * TILE_SIZE;
+            let y0 = row as u32 * TILE_SIZE;
+            let x1 = (x0 + TILE_SIZE).min(width);
+            let y1 = (y0 + TILE_SIZE).min(height);
+            changed[row * cols + col] = tile_changed(prev, next, x0, y0, x1, y1);
+        }
+    }
+
+    // Flood-fill changed tiles into clusters
+    let mut visited = vec![false; cols * rows];
+    let mut regions = Vec::new();
+
+    for start in 0..changed.len() {
+        if !changed[start] || visited[start] {
+            continue;
+        }
+
+        let (mut min_col, mut min_row) = (usize::MAX, usize::MAX);
+        let (mut max_col, mut max_row) = (0, 0);
+        let mut stack = vec![start];
+        visited[start] = true;
+
+        while let Some(idx) = stack.pop() {
+            let (row, col) = (idx / cols, idx % cols);
+            min_col = min_col.min(col);
+            min_row = min_row.min(row);
+            max_col = max_col.max(col);
+            max_row = max_row.max(row);
+
+            for dy in -1i64..=1 {
+                for dx in -1i64..=1 {
+                    let r = row as i64 + dy;
+                    let c = col as i64 + dx;
+                    if r < 0 || c < 0 || r >= rows as i64 || c >= cols as i64 {
+                        continue;
+                    }
+                    let n = r as usize * cols + c as usize;
+                    if changed[n] && !visited[n] {
+                        visited[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+
+        let x = min_col as u32 * TILE_SIZE;
+        let y = min_row as u32 * TILE_SIZE;
+        let x_end = ((max_col as u32 + 1) * TILE_SIZE).min(width);
+        let y_end = ((max_row as u32 + 1) * TILE_SIZE).min(height);
+        regions.push(Region {
+            x,
+            y,
+            width: x_end - x,
+            height: y_end - y,
+        });
+    }
+
+    regions
+}
+
+/// Fraction (0.0-1.0) of the frame covered by the given regions
+pub fn changed_ratio(regions: &[Region], width: u32, height: u32) -> f32 {
+    let total = width as u64 * height as u64;
+    if total == 0 {
+        return 0.0;
+    }
+    let changed: u64 = regions.iter().map(|r| r.area()).sum();
+    (changed as f64 / total as f64).min(1.0) as f32
+}
+
+/// Crop a region out of a frame
+pub fn crop_region(frame: &RgbaImage, region: &Region) -> RgbaImage {
+    image::imageops::crop_imm(frame, region.x, region.y, region.width, region.height).to_image()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn blank(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]))
+    }
+
+    #[test]
+    fn test_identical_frames_have_no_changes() {
+        let frame = blank(100, 80);
+        assert!(changed_regions(&frame, &frame).is_empty());
+    }
+
+    #[test]
+    fn test_separate_changes_yield_separate_regions() {
+        let prev = blank(256, 256);
+        let mut next = prev.clone();
+        next.put_pixel(5, 5, Rgba([0, 0, 0, 255]));
+        next.put_pixel(200, 200, Rgba([0, 0, 0, 255]));
+
+        let regions = changed_regions(&prev, &next);
+        assert_eq!(regions.len(), 2);
+        assert!(regions.contains(&Region { x: 0, y: 0, width: 32, height: 32 }));
+        assert!(regions.contains(&Region { x: 192, y: 192, width: 32, height: 32 }));
+    }
+
+    #[test]
+    fn test_small_noise_is_ignored() {
+        let prev = blank(64, 64);
+        let mut next = prev.clone();
+        next.put_pixel(10, 10, Rgba([250, 250, 250, 255]));
+        assert!(changed_regions(&prev, &next).is_empty());
+    }
+
+    #[test]
+    fn test_region_around() {
+        assert_eq!(Region::around(500.0, 300.0, 200, 100), Region { x: 400, y: 250, width: 200, height: 100 });
+        assert_eq!(Region::around(20.0, 10.0, 200, 100), Region { x: 0, y: 0, width: 200, height: 100 });
+    }
+
+    #[test]
+    fn test_resized_frame_is_fully_changed() {
+        let regions = changed_regions(&blank(10, 10), &blank(20, 20));
+        assert_eq!(regions, vec![Region { x: 0, y: 0, width: 20, height: 20 }]);
+        assert_eq!(changed_ratio(&regions, 20, 20), 1.0);
+    }
+}
@@ -0,0 +1,266 @@
+// Translation glossary
+// Enforces consistent translations of product names and terminology. Before
+// machine translation, glossary terms are swapped for placeholders the model
+// copies through unchanged; afterwards the placeholders (and any source term
+// the model echoed verbatim) are replaced with the glossary translation.
+
+use std::collections::HashMap;
+
+use regex::{Captures, Regex};
+
+use crate::error::{AppError, AppResult};
+
+/// Placeholder stem; an unusual Latin token MarianMT copies instead of translating
+const PLACEHOLDER: &str = "ZXQ";
+
+/// A source term and its required translation
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlossaryTerm {
+    pub source: String,
+    pub target: String,
+    /// "{source}-{target}", as in translation model names (e.g. "en-zh")
+    pub lang_pair: String,
+}
+
+/// Glossary settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GlossaryConfig {
+    pub terms: Vec<GlossaryTerm>,
+}
+
+/// Text prepared for translation, with the terms its placeholders stand for
+#[derive(Debug)]
+pub struct Protected {
+    pub text: String,
+    terms: Vec<GlossaryTerm>,
+}
+
+pub fn lang_pair(source_lang: &str, target_lang: &str) -> String {
+    format!("{}-{}", source_lang, target_lang)
+}
+
+/// Case-insensitive alternation of all source terms, longest first. Word
+/// boundaries are only required at Latin edges; CJK terms match anywhere.
+fn term_regex(terms: &[GlossaryTerm]) -> Option<Regex> {
+    let mut sources: Vec<&str> = terms.iter().map(|t| t.source.as_str()).filter(|s| !s.is_empty()).collect();
+    if sources.is_empty() {
+        return None;
+    }
+    sources.sort_by_key(|s| std::cmp::Reverse(s.chars().count()));
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    let alternatives: Vec<String> = sources
+        .iter()
+        .map(|s| {
+            let start = if is_word(s.chars().next()) { r"\b" } else { "" };
+            let end = if is_word(s.chars().last()) { r"\b" } else { "" };
+            format!("{}{}{}", start, regex::escape(s), end)
+        })
+        .collect();
+    Regex::new(&format!("(?i)(?:{})", alternatives.join("|"))).ok()
+}
+
+fn index_by_source(terms: &[GlossaryTerm]) -> HashMap<String, usize> {
+    terms.iter().enumerate().map(|(i, t)| (t.source.to_lowercase(), i)).collect()
+}
+
+/// Replace glossary terms in `text` with placeholders
+pub fn protect(text: &str, terms: &[GlossaryTerm]) -> Protected {
+    let text = match term_regex(terms) {
+        Some(regex) => {
+            let index = index_by_source(terms);
+            regex
+                .replace_all(text, |caps: &Captures| match index.get(&caps[0].to_lowercase()) {
+                    Some(i) => format!("{}{}", PLACEHOLDER, i),
+                    None => caps[0].to_string(),
+                })
+                .into_owned()
+        }
+        None => text.to_string(),
+    };
+    Protected {
+        text,
+        terms: terms.to_vec(),
+    }
+}
+
+/// Put glossary translations into the translated text
+pub fn restore(translated: &str, protected: &Protected) -> String {
+    // Terms the model copied through untranslated. Done first so a target
+    // containing its own source term isn't replaced twice.
+    let echoed = match term_regex(&protected.terms) {
+        Some(regex) => {
+            let index = index_by_source(&protected.terms);
+            regex
+                .replace_all(translated, |caps: &Captures| match index.get(&caps[0].to_lowercase()) {
+                    Some(&i) => protected.terms[i].target.clone(),
+                    None => caps[0].to_string(),
+                })
+                .into_owned()
+        }
+        None => translated.to_string(),
+    };
+
+    let placeholder = Regex::new(&format!(r"(?i){}\s*(\d+)", PLACEHOLDER)).expect("placeholder regex is valid");
+    placeholder
+        .replace_all(&echoed, |caps: &Captures| {
+            caps[1]
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| protected.terms.get(i))
+                .map_or_else(|| caps[0].to_string(), |t| t.target.clone())
+        })
+        .into_owned()
+}
+
+/// Glossary terms for a language pair
+pub fn terms_for(pair: &str) -> AppResult<Vec<GlossaryTerm>> {
+    Ok(crate::config::get()?
+        .glossary
+        .terms
+        .into_iter()
+        .filter(|t| t.lang_pair == pair)
+        .collect())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Serialize terms as `source,target,langPair` CSV
+fn to_csv(terms: &[GlossaryTerm]) -> String {
+    let mut csv = String::from("source,target,langPair\n");
+    for term in terms {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            csv_field(&term.source),
+            csv_field(&term.target),
+            csv_field(&term.lang_pair)
+        ));
+    }
+    csv
+}
+
+/// Parse a glossary CSV. A header row is skipped; rows without a language
+/// pair column use `default_pair`.
+fn from_csv(content: &str, default_pair: Option<&str>) -> AppResult<Vec<GlossaryTerm>> {
+    let mut terms = Vec::new();
+    for (i, record) in crate::history_import::parse_csv(content.trim_start_matches('\u{feff}'))
+        .into_iter()
+        .enumerate()
+    {
+        if i == 0 && record.first().is_some_and(|f| f.trim().eq_ignore_ascii_case("source")) {
+            continue;
+        }
+        let field = |n: usize| record.get(n).map(|f| f.trim()).filter(|f| !f.is_empty());
+        let (Some(source), Some(target)) = (field(0), field(1)) else {
+            continue;
+        };
+        let lang_pair = field(2).or(default_pair).ok_or_else(|| {
+            AppError::InvalidInput(format!("Glossary row {} has no language pair", i + 1))
+        })?;
+        terms.push(GlossaryTerm {
+            source: source.to_string(),
+            target: target.to_string(),
+            lang_pair: lang_pair.to_string(),
+        });
+    }
+    Ok(terms)
+}
+
+/// Add or replace terms (a term is identified by its source and language pair)
+fn upsert(glossary: &mut GlossaryConfig, terms: Vec<GlossaryTerm>) {
+    for term in terms {
+        glossary
+            .terms
+            .retain(|t| !(t.lang_pair == term.lang_pair && t.source.eq_ignore_ascii_case(&term.source)));
+        glossary.terms.push(term);
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn add_glossary_term(source: String, target: String, lang_pair: String) -> AppResult<()> {
+    let (source, target) = (source.trim().to_string(), target.trim().to_string());
+    if source.is_empty() || target.is_empty() {
+        return Err(AppError::InvalidInput("Glossary terms cannot be empty".to_string()));
+    }
+    crate::config::update(|c| upsert(&mut c.glossary, vec![GlossaryTerm { source, target, lang_pair }]))
+}
+
+#[tauri::command]
+pub fn remove_glossary_term(source: String, lang_pair: String) -> AppResult<()> {
+    crate::config::update(|c| {
+        c.glossary
+            .terms
+            .retain(|t| !(t.lang_pair == lang_pair && t.source.eq_ignore_ascii_case(&source)))
+    })
+}
+
+/// All terms, or those for one language pair
+#[tauri::command]
+pub fn list_glossary_terms(lang_pair: Option<String>) -> AppResult<Vec<GlossaryTerm>> {
+    match lang_pair {
+        Some(pair) => terms_for(&pair),
+        None => Ok(crate::config::get()?.glossary.terms),
+    }
+}
+
+/// Import a CSV glossary (`source,target[,langPair]`). Returns the number of terms imported.
+#[tauri::command]
+pub fn import_glossary_csv(path: String, lang_pair: Option<String>) -> AppResult<usize> {
+    let terms = from_csv(&std::fs::read_to_string(&path)?, lang_pair.as_deref())?;
+    let count = terms.len();
+    crate::config::update(|c| upsert(&mut c.glossary, terms))?;
+    Ok(count)
+}
+
+/// Export the glossary (optionally one language pair) as CSV
+#[tauri::command]
+pub fn export_glossary_csv(path: String, lang_pair: Option<String>) -> AppResult<usize> {
+    let terms = list_glossary_terms(lang_pair)?;
+    std::fs::write(&path, to_csv(&terms))?;
+    Ok(terms.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(source: &str, target: &str) -> GlossaryTerm {
+        GlossaryTerm {
+            source: source.to_string(),
+            target: target.to_string(),
+            lang_pair: "en-zh".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_protect_and_restore() {
+        let terms = vec![term("Screen Inu", "螢幕犬"), term("Inu", "犬"), term("OCR", "文字辨識")];
+        let protected = protect("Screen Inu runs OCR; inuit is not a term.", &terms);
+        assert_eq!(protected.text, "ZXQ0 runs ZXQ2; inuit is not a term.");
+
+        // The model may add spaces or change case; it may also echo a source term
+        let restored = restore("zxq 0 執行 ZXQ2，Inu。", &protected);
+        assert_eq!(restored, "螢幕犬 執行 文字辨識，犬。");
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let terms = vec![term("Hello, \"world\"", "你好"), term("Inu", "犬")];
+        assert_eq!(from_csv(&to_csv(&terms), None).unwrap(), terms);
+
+        let imported = from_csv("HP,生命值\nMP,魔力\n", Some("en-zh")).unwrap();
+        assert_eq!(imported, vec![term("HP", "生命值"), term("MP", "魔力")]);
+        assert!(from_csv("HP,生命值\n", None).is_err());
+    }
+}
@@ -0,0 +1,536 @@
+// OCR history helpers
+// History is stored by the frontend (localStorage or ocr_history.json); these
+// commands work on the items it passes in. Backups cover the file-based history
+// in the custom data directory.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::{AppError, AppResult};
+
+const HISTORY_FILE: &str = "ocr_history.json";
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "ocr_history-";
+
+/// Emitted after a restore so the frontend reloads history
+pub const HISTORY_RESTORED_EVENT: &str = "history://restored";
+
+/// History backup settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HistoryConfig {
+    /// Take a daily backup of the history file
+    pub auto_backup: bool,
+    /// Number of daily backups to keep
+    pub backup_keep: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            auto_backup: true,
+            backup_keep: 7,
+        }
+    }
+}
+
+/// A history entry, mirrors `HistoryItem` in utils/history.ts
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItem {
+    pub id: String,
+    pub text: String,
+    pub lang: String,
+    /// Unix epoch milliseconds
+    pub timestamp: i64,
+    /// Application the capture was taken from, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_app: Option<String>,
+    /// Short digest of long captures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// A labelled count, sorted descending in `HistoryStats`
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct CountEntry {
+    pub key: String,
+    pub count: usize,
+}
+
+/// Aggregated history statistics for charts
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStats {
+    pub total_captures: usize,
+    pub total_characters: usize,
+    /// Captures per day ("YYYY-MM-DD"), oldest first
+    pub per_day: Vec<CountEntry>,
+    /// Captures per OCR language
+    pub languages: Vec<CountEntry>,
+    /// Captures per source application
+    pub source_apps: Vec<CountEntry>,
+    pub first_capture: Option<i64>,
+    pub last_capture: Option<i64>,
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) civil date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's days_from_civil inverse
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Format a millisecond timestamp as a local "YYYY-MM-DD" date
+fn day_key(timestamp_ms: i64, tz_offset_minutes: i32) -> String {
+    let local_ms = timestamp_ms + tz_offset_minutes as i64 * 60_000;
+    let (y, m, d) = civil_from_days(local_ms.div_euclid(86_400_000));
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+fn sorted_counts(counts: HashMap<String, usize>) -> Vec<CountEntry> {
+    let mut entries: Vec<CountEntry> = counts
+        .into_iter()
+        .map(|(key, count)| CountEntry { key, count })
+        .collect();
+    entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    entries
+}
+
+/// Compute statistics over history items
+pub fn compute_stats(items: &[HistoryItem], tz_offset_minutes: i32) -> HistoryStats {
+    let mut per_day: HashMap<String, usize> = HashMap::new();
+    let mut languages: HashMap<String, usize> = HashMap::new();
+    let mut source_apps: HashMap<String, usize> = HashMap::new();
+
+    for item in items {
+        *per_day.entry(day_key(item.timestamp, tz_offset_minutes)).or_default() += 1;
+        // Multi-language captures ("eng+jpn") count towards each language
+        for lang in item.lang.split('+').filter(|l| !l.is_empty()) {
+            *languages.entry(lang.to_string()).or_default() += 1;
+        }
+        if let Some(app) = item.source_app.as_deref().filter(|a| !a.is_empty()) {
+            *source_apps.entry(app.to_string()).or_default() += 1;
+        }
+    }
+
+    let mut per_day: Vec<CountEntry> = per_day
+        .into_iter()
+        .map(|(key, count)| CountEntry { key, count })
+        .collect();
+    per_day.sort_by(|a, b| a.key.cmp(&b.key));
+
+    HistoryStats {
+        total_captures: items.len(),
+        total_characters: items.iter().map(|i| i.text.chars().count()).sum(),
+        per_day,
+        languages: sorted_counts(languages),
+        source_apps: sorted_counts(source_apps),
+        first_capture: items.iter().map(|i| i.timestamp).min(),
+        last_capture: items.iter().map(|i| i.timestamp).max(),
+    }
+}
+
+/// Combine items into one: texts joined oldest first, the earliest
+/// timestamp, id and source app, and every language and tag. The merged item
+/// takes the place of the earliest one.
+pub fn merge_items(items: Vec<HistoryItem>, ids: &[String]) -> AppResult<Vec<HistoryItem>> {
+    if ids.len() < 2 {
+        return Err(AppError::InvalidInput("Select at least two items to merge".to_string()));
+    }
+    if let Some(missing) = ids.iter().find(|id| !items.iter().any(|item| &item.id == *id)) {
+        return Err(AppError::InvalidInput(format!("History item {} not found", missing)));
+    }
+    let mut parts: Vec<&HistoryItem> = items.iter().filter(|item| ids.contains(&item.id)).collect();
+    parts.sort_by_key(|item| item.timestamp);
+    let first = parts[0];
+
+    let mut langs: Vec<&str> = Vec::new();
+    let mut tags: Vec<String> = Vec::new();
+    for part in &parts {
+        for lang in part.lang.split('+').filter(|l| !l.is_empty()) {
+            if !langs.contains(&lang) {
+                langs.push(lang);
+            }
+        }
+        for tag in &part.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+    }
+    let merged = HistoryItem {
+        id: first.id.clone(),
+        text: parts.iter().map(|p| p.text.trim()).collect::<Vec<_>>().join("\n\n"),
+        lang: langs.join("+"),
+        timestamp: first.timestamp,
+        source_app: parts.iter().find_map(|p| p.source_app.clone()),
+        summary: None,
+        tags,
+    };
+
+    let first_id = merged.id.clone();
+    let mut merged = Some(merged);
+    Ok(items
+        .into_iter()
+        .filter_map(|item| match item.id == first_id {
+            true => merged.take(),
+            false => (!ids.contains(&item.id)).then_some(item),
+        })
+        .collect())
+}
+
+/// Cut an item at `offsets` (UTF-16 positions in its text, as the frontend
+/// counts them). The pieces keep the item's metadata and take its place,
+/// in text order; blank pieces are dropped.
+pub fn split_item(items: Vec<HistoryItem>, id: &str, offsets: &[usize]) -> AppResult<Vec<HistoryItem>> {
+    let item = items
+        .iter()
+        .find(|item| item.id == id)
+        .ok_or_else(|| AppError::InvalidInput(format!("History item {} not found", id)))?;
+
+    // UTF-16 offsets to byte offsets at char boundaries
+    let mut cuts = vec![0];
+    let mut units = 0;
+    for (byte, c) in item.text.char_indices() {
+        if offsets.contains(&units) && byte > 0 {
+            cuts.push(byte);
+        }
+        units += c.len_utf16();
+    }
+    cuts.push(item.text.len());
+
+    let pieces: Vec<HistoryItem> = cuts
+        .windows(2)
+        .map(|w| item.text[w[0]..w[1]].trim())
+        .filter(|text| !text.is_empty())
+        .enumerate()
+        .map(|(n, text)| HistoryItem {
+            id: format!("{}-{}", item.id, n + 1),
+            text: text.to_string(),
+            summary: None,
+            ..item.clone()
+        })
+        .collect();
+    if pieces.len() < 2 {
+        return Err(AppError::InvalidInput("Nothing to split there".to_string()));
+    }
+
+    let mut pieces = Some(pieces);
+    Ok(items
+        .into_iter()
+        .flat_map(|item| match item.id == id {
+            true => pieces.take().unwrap_or_default(),
+            false => vec![item],
+        })
+        .collect())
+}
+
+/// Path of the file-based history, None when history lives in localStorage.
+/// In portable mode history defaults to the portable data folder.
+pub fn history_file_path<R: Runtime>(app: &AppHandle<R>) -> AppResult<Option<PathBuf>> {
+    let store = app
+        .store(crate::portable::settings_store_path())
+        .map_err(|e| AppError::Other(format!("Failed to open settings: {}", e)))?;
+    Ok(store
+        .get("dataDirectory")
+        .and_then(|v| v.as_str().map(PathBuf::from))
+        .or_else(crate::portable::portable_dir)
+        .map(|dir| dir.join(HISTORY_FILE)))
+}
+
+fn require_history_file<R: Runtime>(app: &AppHandle<R>) -> AppResult<PathBuf> {
+    history_file_path(app)?.ok_or_else(|| {
+        AppError::Unsupported(
+            "History is kept in app storage. Choose a data directory to enable backups.".to_string(),
+        )
+    })
+}
+
+fn backup_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(BACKUP_DIR))
+}
+
+/// Items of a (possibly encrypted) history file
+fn read_history_file(path: &Path) -> AppResult<Vec<HistoryItem>> {
+    let content = std::fs::read_to_string(path)?;
+    let content = crate::encryption::decrypt(&content, None)?;
+    serde_json::from_str(&content).map_err(|e| AppError::InvalidInput(format!("Not a history file: {}", e)))
+}
+
+/// Check that a file holds valid (possibly encrypted) history
+fn validate_history_file(path: &Path) -> AppResult<usize> {
+    Ok(read_history_file(path)?.len())
+}
+
+/// A history item by id; None when not found or history is in localStorage
+pub fn find_item<R: Runtime>(app: &AppHandle<R>, id: &str) -> AppResult<Option<HistoryItem>> {
+    match history_file_path(app)? {
+        Some(path) if path.exists() => Ok(read_history_file(&path)?.into_iter().find(|item| item.id == id)),
+        _ => Ok(None),
+    }
+}
+
+/// Existing daily backups, oldest first
+fn list_backups(dir: &Path) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(BACKUP_PREFIX) && n.ends_with(".json"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Names embed the date, so lexical order is chronological
+    backups.sort();
+    backups
+}
+
+/// Copy today's history into the backup directory and prune old copies
+fn run_daily_backup<R: Runtime>(app: &AppHandle<R>, keep: usize) -> AppResult<()> {
+    let Some(source) = history_file_path(app)? else {
+        return Ok(());
+    };
+    if !source.exists() {
+        return Ok(());
+    }
+
+    let dir = backup_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    let target = dir.join(format!("{}{}.json", BACKUP_PREFIX, day_key(now_ms, 0)));
+    if !target.exists() {
+        // Don't rotate a corrupted file over the good backups. Encrypted history
+        // that can't be unlocked right now is copied as-is.
+        match validate_history_file(&source) {
+            Ok(_) | Err(AppError::PermissionDenied(_)) => {}
+            Err(e) => return Err(e),
+        }
+        std::fs::copy(&source, &target)?;
+    }
+
+    let backups = list_backups(&dir);
+    if backups.len() > keep.max(1) {
+        for old in &backups[..backups.len() - keep.max(1)] {
+            let _ = std::fs::remove_file(old);
+        }
+    }
+    Ok(())
+}
+
+/// Start the daily backup loop if enabled in the config
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        if let Ok(config) = crate::config::get() {
+            if config.history.auto_backup {
+                if let Err(e) = run_daily_backup(&app, config.history.backup_keep) {
+                    tracing::warn!("History backup failed: {}", e);
+                }
+            }
+        }
+        std::thread::sleep(Duration::from_secs(60 * 60));
+    });
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// History statistics (per-day counts, languages, source apps, characters)
+/// `tz_offset_minutes` is the local offset from UTC, e.g. +540 for JST
+#[tauri::command]
+pub fn sync_get_stats(items: Vec<HistoryItem>, tz_offset_minutes: Option<i32>) -> AppResult<HistoryStats> {
+    Ok(compute_stats(&items, tz_offset_minutes.unwrap_or(0)))
+}
+
+/// Copy the history file to `path`
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+pub fn sync_backup(app: AppHandle, path: String) -> AppResult<()> {
+    let source = require_history_file(&app)?;
+    if !source.exists() {
+        return Err(AppError::InvalidInput("There is no history to back up".to_string()));
+    }
+    std::fs::copy(&source, &path)?;
+    Ok(())
+}
+
+/// Write `content` under a temporary name, then move it into place
+fn write_atomic(path: &Path, content: &str) -> AppResult<()> {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.tmp", name));
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Replace the history file with a backup. The current file is saved to the
+/// backup directory first, under a timestamped name. Returns the number of
+/// restored items.
+#[tauri::command]
+#[tracing::instrument(skip(app), err)]
+pub fn sync_restore(app: AppHandle, path: String) -> AppResult<usize> {
+    let target = require_history_file(&app)?;
+    let count = validate_history_file(Path::new(&path))?;
+
+    if target.exists() {
+        let dir = backup_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f");
+        std::fs::copy(&target, dir.join(format!("pre-restore-{}-{}", stamp, HISTORY_FILE)))?;
+    }
+
+    // A plain backup restored while encryption is on is encrypted like any save
+    let content = std::fs::read_to_string(&path)?;
+    let content = match content.trim_start().starts_with(crate::encryption::ENCRYPTED_PREFIX) {
+        true => content,
+        false => crate::encryption::encrypt_if_enabled(&content)?,
+    };
+    write_atomic(&target, &content)?;
+
+    let _ = app.emit(HISTORY_RESTORED_EVENT, count);
+    Ok(count)
+}
+
+/// A capture was added to history (by hand, scheduled or automated);
+/// announces it on `sync://item-added` and fires the history webhook
+#[tauri::command]
+pub fn sync_item_added(item: HistoryItem) {
+    crate::events::emit(crate::events::SYNC_ITEM_ADDED_EVENT, &item);
+    crate::webhooks::notify_item_added(item);
+}
+
+/// Merge the items with `ids` into one; returns the updated history
+#[tauri::command]
+pub fn sync_merge_items(items: Vec<HistoryItem>, ids: Vec<String>) -> AppResult<Vec<HistoryItem>> {
+    merge_items(items, &ids)
+}
+
+/// Split the item with `id` at UTF-16 `offsets`; returns the updated history
+#[tauri::command]
+pub fn sync_split_item(items: Vec<HistoryItem>, id: String, offsets: Vec<usize>) -> AppResult<Vec<HistoryItem>> {
+    split_item(items, &id, &offsets)
+}
+
+/// Automatic daily backups, newest first
+#[tauri::command]
+pub fn sync_list_backups() -> AppResult<Vec<String>> {
+    let mut backups: Vec<String> = list_backups(&backup_dir()?)
+        .into_iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    backups.reverse();
+    Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(text: &str, lang: &str, timestamp: i64, source_app: Option<&str>) -> HistoryItem {
+        HistoryItem {
+            id: timestamp.to_string(),
+            text: text.to_string(),
+            lang: lang.to_string(),
+            timestamp,
+            source_app: source_app.map(|s| s.to_string()),
+            summary: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_day_key() {
+        assert_eq!(day_key(0, 0), "1970-01-01");
+        // 2024-02-29T23:30:00Z
+        assert_eq!(day_key(1_709_249_400_000, 0), "2024-02-29");
+        assert_eq!(day_key(1_709_249_400_000, 540), "2024-03-01");
+        assert_eq!(day_key(-1, 0), "1969-12-31");
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let day = 86_400_000;
+        let items = vec![
+            item("こんにちは", "jpn", day, Some("Firefox")),
+            item("hello", "eng+jpn", day + 1000, Some("Firefox")),
+            item("world", "eng", 3 * day, None),
+        ];
+        let stats = compute_stats(&items, 0);
+
+        assert_eq!(stats.total_captures, 3);
+        assert_eq!(stats.total_characters, 15);
+        assert_eq!(stats.per_day[0], CountEntry { key: "1970-01-02".into(), count: 2 });
+        assert_eq!(stats.per_day[1], CountEntry { key: "1970-01-04".into(), count: 1 });
+        assert_eq!(stats.languages.len(), 2);
+        assert_eq!(stats.languages[0].count, 2);
+        assert_eq!(stats.source_apps, vec![CountEntry { key: "Firefox".into(), count: 2 }]);
+        assert_eq!(stats.first_capture, Some(day));
+        assert_eq!(stats.last_capture, Some(3 * day));
+    }
+
+    #[test]
+    fn test_merge_items() {
+        let mut page1 = item("Page one", "eng", 1000, None);
+        page1.tags = vec!["invoice".to_string()];
+        let mut page2 = item("Page two", "eng+jpn", 2000, Some("Preview"));
+        page2.tags = vec!["invoice".to_string(), "2024".to_string()];
+        let other = item("other", "eng", 3000, None);
+        let history = vec![other.clone(), page2, page1];
+
+        let merged = merge_items(history.clone(), &["2000".to_string(), "1000".to_string()]).unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0], other);
+        assert_eq!(merged[1].id, "1000");
+        assert_eq!(merged[1].text, "Page one\n\nPage two");
+        assert_eq!(merged[1].lang, "eng+jpn");
+        assert_eq!(merged[1].source_app.as_deref(), Some("Preview"));
+        assert_eq!(merged[1].tags, vec!["invoice", "2024"]);
+        assert!(merge_items(history, &["1000".to_string(), "9".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_split_item() {
+        let history = vec![item("😀 first\nsecond", "eng", 1000, None), item("x", "eng", 500, None)];
+        // The emoji is two UTF-16 units, so "\n" is at 8
+        let split = split_item(history.clone(), "1000", &[8]).unwrap();
+        let texts: Vec<&str> = split.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(texts, vec!["😀 first", "second", "x"]);
+        assert_eq!((split[0].id.as_str(), split[1].id.as_str()), ("1000-1", "1000-2"));
+        assert!(split_item(history, "1000", &[0]).is_err());
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(HISTORY_FILE);
+        std::fs::write(&path, "[]").unwrap();
+        write_atomic(&path, "[{}]").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "[{}]");
+        // Nothing but the history file is left behind
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+}
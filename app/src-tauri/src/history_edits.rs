@@ -0,0 +1,445 @@
+// Versioned history edits
+// Corrections to a history item's text are kept as a Yjs-compatible CRDT
+// text (yrs) rather than overwriting it, so the recognized original is never
+// lost and edits made on different devices merge. Each device appends its
+// updates to its own log file, history_edits/<item>/<device>.json, next to
+// the history file; with the data directory in a synced folder no two
+// devices ever write the same file. The original text is inserted by a fixed
+// client id, so every device creates the identical base and merging it twice
+// is a no-op. Each edit also records how many edits from each device it was
+// made after, so two edits made without seeing each other can be reported as
+// a conflict until a later edit, made on the merged text, settles it.
+//
+// With history encryption on (encryption.rs) the logs are encrypted the same
+// way as the history file; they are rewritten when it is turned on or off.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use tauri::{AppHandle, Runtime};
+use yrs::updates::decoder::Decode;
+use yrs::{Doc, GetString, Text, Transact, Update};
+
+use crate::error::{AppError, AppResult};
+
+const EDITS_DIR: &str = "history_edits";
+const DEVICE_ID_FILE: &str = "device_id";
+const TEXT_NAME: &str = "text";
+
+/// Client id of the update inserting the original text
+const BASE_CLIENT: u64 = 0;
+
+/// Yjs client ids are 53-bit
+const CLIENT_ID_MASK: u64 = (1 << 53) - 1;
+
+/// One CRDT update in a device's log
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EditEntry {
+    /// Unix epoch milliseconds
+    pub timestamp: i64,
+    pub device: u64,
+    /// yrs v1 update, base64
+    pub update: String,
+    /// Number of edits from each device the update was made after; unset
+    /// for the base
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seen: Option<BTreeMap<u64, usize>>,
+}
+
+/// A version of an item's text
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemVersion {
+    /// 0 is the recognized original
+    pub version: usize,
+    pub text: String,
+    pub timestamp: i64,
+    /// Device the edit was made on, None for the original
+    pub device: Option<String>,
+}
+
+/// An item edited on several devices at once
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemConflict {
+    pub id: String,
+    /// Devices with concurrent edits
+    pub devices: Vec<String>,
+    /// Latest of the concurrent edits
+    pub timestamp: i64,
+}
+
+pub(crate) fn crdt_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Other(format!("Corrupt edit history: {}", e))
+}
+
+/// This device's client id, created on first use. Kept in the app data
+/// directory, which is never synced.
+pub(crate) fn device_id() -> AppResult<u64> {
+    let path = crate::config::get_app_data_dir()?.join(DEVICE_ID_FILE);
+    if let Some(id) = std::fs::read_to_string(&path).ok().and_then(|s| s.trim().parse::<u64>().ok()) {
+        return Ok(id);
+    }
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    let id = ((nanos ^ ((std::process::id() as u64) << 32)) & CLIENT_ID_MASK).max(1);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, id.to_string())?;
+    Ok(id)
+}
+
+/// Device logs live beside the history file, or in the app data directory
+/// when history is in localStorage
+pub(crate) fn logs_root<R: Runtime>(app: &AppHandle<R>) -> AppResult<PathBuf> {
+    match crate::history::history_file_path(app)?.and_then(|p| p.parent().map(Path::to_path_buf)) {
+        Some(dir) => Ok(dir),
+        None => crate::config::get_app_data_dir(),
+    }
+}
+
+fn edits_dir<R: Runtime>(app: &AppHandle<R>, item_id: &str) -> AppResult<PathBuf> {
+    if item_id.is_empty() || !item_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(AppError::InvalidInput(format!("Invalid history item id: {}", item_id)));
+    }
+    Ok(logs_root(app)?.join(EDITS_DIR).join(item_id))
+}
+
+pub(crate) fn encode(update: Vec<u8>) -> String {
+    base64::engine::general_purpose::STANDARD.encode(update)
+}
+
+/// Every device's entries for an item, oldest first
+pub(crate) fn read_entries(dir: &Path) -> AppResult<Vec<EditEntry>> {
+    let mut entries = Vec::new();
+    let Ok(files) = std::fs::read_dir(dir) else {
+        return Ok(entries);
+    };
+    for file in files.flatten().filter(|f| f.path().extension().is_some_and(|e| e == "json")) {
+        entries.extend(read_log(&file.path())?);
+    }
+    entries.sort_by_key(|e| (e.timestamp, e.device));
+    Ok(entries)
+}
+
+/// A device's log, encrypted or not
+fn read_log(path: &Path) -> AppResult<Vec<EditEntry>> {
+    let content = crate::encryption::decrypt(&std::fs::read_to_string(path)?, None)?;
+    serde_json::from_str(&content).map_err(crdt_error)
+}
+
+/// Write a device's log, encrypted when history encryption is on
+fn write_log(path: &Path, log: &[EditEntry]) -> AppResult<()> {
+    let content = serde_json::to_string(log).map_err(crdt_error)?;
+    std::fs::write(path, crate::encryption::encrypt_if_enabled(&content)?)?;
+    Ok(())
+}
+
+pub(crate) fn append_entry(dir: &Path, entry: EditEntry) -> AppResult<()> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", entry.device));
+    let mut log = if path.exists() { read_log(&path)? } else { Vec::new() };
+    log.push(entry);
+    write_log(&path, &log)
+}
+
+/// Rewrite every edit and collection log with the current encryption
+/// setting. Called while the key to read them is still in the keychain.
+pub(crate) fn rewrite_logs<R: Runtime>(app: &AppHandle<R>) -> AppResult<()> {
+    let root = logs_root(app)?;
+    let mut dirs = vec![root.join(crate::collections::COLLECTIONS_DIR)];
+    if let Ok(items) = std::fs::read_dir(root.join(EDITS_DIR)) {
+        dirs.extend(items.flatten().map(|item| item.path()).filter(|path| path.is_dir()));
+    }
+    for dir in dirs {
+        let Ok(files) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in files.flatten().map(|f| f.path()).filter(|p| p.extension().is_some_and(|e| e == "json")) {
+            write_log(&path, &read_log(&path)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// The update that inserts `original`; identical on every device
+fn base_update(original: &str) -> Vec<u8> {
+    let doc = Doc::with_client_id(BASE_CLIENT);
+    let text = doc.get_or_insert_text(TEXT_NAME);
+    let mut txn = doc.transact_mut();
+    text.insert(&mut txn, 0, original);
+    txn.encode_update_v1()
+}
+
+pub(crate) fn apply(doc: &Doc, entry: &EditEntry) -> AppResult<()> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(&entry.update).map_err(crdt_error)?;
+    let update = Update::decode_v1(&bytes).map_err(crdt_error)?;
+    doc.transact_mut().apply_update(update).map_err(crdt_error)
+}
+
+fn current_text(doc: &Doc) -> String {
+    let text = doc.get_or_insert_text(TEXT_NAME);
+    let txn = doc.transact();
+    text.get_string(&txn)
+}
+
+/// Byte range of `old` to replace, and its replacement, to turn it into `new`
+fn diff<'a>(old: &str, new: &'a str) -> (usize, usize, &'a str) {
+    let prefix: usize = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    let suffix: usize = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    (prefix, old.len() - suffix, &new[prefix..new.len() - suffix])
+}
+
+/// Apply `new_text` to the item's CRDT as `device`; returns the entry to
+/// store, or None when nothing changed
+fn edit(entries: &[EditEntry], device: u64, new_text: &str, timestamp: i64) -> AppResult<Option<EditEntry>> {
+    let doc = Doc::with_client_id(device);
+    for entry in entries {
+        apply(&doc, entry)?;
+    }
+    let old = current_text(&doc);
+    let (start, end, insert) = diff(&old, new_text);
+    if start == end && insert.is_empty() {
+        return Ok(None);
+    }
+    let text = doc.get_or_insert_text(TEXT_NAME);
+    let mut txn = doc.transact_mut();
+    if end > start {
+        text.remove_range(&mut txn, start as u32, (end - start) as u32);
+    }
+    if !insert.is_empty() {
+        text.insert(&mut txn, start as u32, insert);
+    }
+    Ok(Some(EditEntry {
+        timestamp,
+        device,
+        update: encode(txn.encode_update_v1()),
+        seen: Some(seen(entries)),
+    }))
+}
+
+/// Number of edits from each device in `entries`
+fn seen(entries: &[EditEntry]) -> BTreeMap<u64, usize> {
+    let mut counts = BTreeMap::new();
+    for entry in entries.iter().filter(|e| e.device != BASE_CLIENT) {
+        *counts.entry(entry.device).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Devices and latest timestamp of edits made without seeing each other,
+/// unless a later edit saw them all. Edits from before this was recorded are
+/// assumed to have seen everything.
+fn conflict(entries: &[EditEntry]) -> Option<(Vec<String>, i64)> {
+    // Each edit with its position in its device's log; entries are in order
+    let mut counts: BTreeMap<u64, usize> = BTreeMap::new();
+    let edits: Vec<(&EditEntry, usize)> = entries
+        .iter()
+        .filter(|e| e.device != BASE_CLIENT)
+        .map(|entry| {
+            let count = counts.entry(entry.device).or_insert(0);
+            *count += 1;
+            (entry, *count - 1)
+        })
+        .collect();
+    let saw = |a: &EditEntry, (b, position): (&EditEntry, usize)| match &a.seen {
+        Some(seen) => seen.get(&b.device).is_some_and(|&count| count > position),
+        None => true,
+    };
+    let is_concurrent = |i: usize, j: usize| {
+        let (a, b) = (edits[i], edits[j]);
+        a.0.device != b.0.device && !saw(a.0, b) && !saw(b.0, a)
+    };
+
+    let concurrent: Vec<usize> = (0..edits.len())
+        .filter(|&i| (0..edits.len()).any(|j| is_concurrent(i, j)))
+        .collect();
+    if concurrent.is_empty() {
+        return None;
+    }
+    let settled = edits
+        .iter()
+        .any(|(entry, _)| entry.seen.is_some() && concurrent.iter().all(|&i| saw(entry, edits[i])));
+    if settled {
+        return None;
+    }
+
+    let mut devices: Vec<String> = concurrent.iter().map(|&i| format!("{:x}", edits[i].0.device)).collect();
+    devices.sort();
+    devices.dedup();
+    let timestamp = concurrent.iter().map(|&i| edits[i].0.timestamp).max().unwrap_or_default();
+    Some((devices, timestamp))
+}
+
+/// The text after each entry, replayed in order. Copies of an update (the
+/// base, when two devices created it) count once.
+fn versions(entries: &[EditEntry]) -> AppResult<Vec<ItemVersion>> {
+    let doc = Doc::with_client_id(CLIENT_ID_MASK);
+    let mut seen = std::collections::HashSet::new();
+    entries
+        .iter()
+        .filter(|entry| seen.insert(entry.update.as_str()))
+        .enumerate()
+        .map(|(version, entry)| {
+            apply(&doc, entry)?;
+            Ok(ItemVersion {
+                version,
+                text: current_text(&doc),
+                timestamp: entry.timestamp,
+                device: (entry.device != BASE_CLIENT).then(|| format!("{:x}", entry.device)),
+            })
+        })
+        .collect()
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Change a history item's text, keeping the earlier versions. `original`
+/// is the item's recognized text, needed on its first edit when history is
+/// in localStorage. Returns the new version.
+#[tauri::command]
+#[tracing::instrument(skip(app, new_text, original), err)]
+pub fn sync_edit_item(
+    app: AppHandle,
+    id: String,
+    new_text: String,
+    original: Option<String>,
+) -> AppResult<ItemVersion> {
+    let dir = edits_dir(&app, &id)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut entries = read_entries(&dir)?;
+    if entries.is_empty() {
+        let original = match crate::history::find_item(&app, &id)? {
+            Some(item) => item.text,
+            None => original.ok_or_else(|| AppError::InvalidInput(format!("History item {} not found", id)))?,
+        };
+        let base = EditEntry {
+            timestamp: now,
+            device: BASE_CLIENT,
+            update: encode(base_update(&original)),
+            seen: None,
+        };
+        append_entry(&dir, base.clone())?;
+        entries.push(base);
+    }
+    // Never earlier than what it edits, even with a skewed clock
+    let timestamp = entries.last().map_or(now, |e| now.max(e.timestamp + 1));
+    if let Some(entry) = edit(&entries, device_id()?, &new_text, timestamp)? {
+        append_entry(&dir, entry.clone())?;
+        entries.push(entry);
+    }
+    versions(&entries)?
+        .pop()
+        .ok_or_else(|| AppError::Other("Edit history is empty".to_string()))
+}
+
+/// All versions of a history item's text, the original first; empty when it
+/// was never edited
+#[tauri::command]
+pub fn sync_get_item_history(app: AppHandle, id: String) -> AppResult<Vec<ItemVersion>> {
+    versions(&read_entries(&edits_dir(&app, &id)?)?)
+}
+
+/// Items whose text was edited on several devices at once and not edited
+/// since, for an "edited on two devices" badge
+#[tauri::command]
+pub fn sync_get_conflicts(app: AppHandle) -> AppResult<Vec<ItemConflict>> {
+    let root = logs_root(&app)?.join(EDITS_DIR);
+    let Ok(dirs) = std::fs::read_dir(&root) else {
+        return Ok(Vec::new());
+    };
+    let mut conflicts = Vec::new();
+    for dir in dirs.flatten().filter(|d| d.path().is_dir()) {
+        if let Some((devices, timestamp)) = conflict(&read_entries(&dir.path())?) {
+            conflicts.push(ItemConflict {
+                id: dir.file_name().to_string_lossy().into_owned(),
+                devices,
+                timestamp,
+            });
+        }
+    }
+    conflicts.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base(original: &str) -> EditEntry {
+        EditEntry {
+            timestamp: 0,
+            device: BASE_CLIENT,
+            update: encode(base_update(original)),
+            seen: None,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff("Hel1o wor1d", "Hello world"), (3, 10, "lo worl"));
+        assert_eq!(diff("東京タワー", "東京スカイツリー"), (6, 12, "スカイツリ"));
+        assert_eq!(diff("same", "same"), (4, 4, ""));
+    }
+
+    #[test]
+    fn test_read_plain_logs() {
+        // Logs written before encryption was turned on
+        let tmp = tempfile::tempdir().unwrap();
+        let late = EditEntry { timestamp: 5, device: 2, ..base("x") };
+        std::fs::write(tmp.path().join("2.json"), serde_json::to_string(&[late.clone()]).unwrap()).unwrap();
+        std::fs::write(tmp.path().join("0.json"), serde_json::to_string(&[base("x")]).unwrap()).unwrap();
+        assert_eq!(read_entries(tmp.path()).unwrap(), vec![base("x"), late]);
+    }
+
+    #[test]
+    fn test_concurrent_edits_merge() {
+        // Both devices created the base independently and fixed different typos
+        let a = edit(&[base("Tbe qvick fox")], 1, "The qvick fox", 1).unwrap().unwrap();
+        let b = edit(&[base("Tbe qvick fox")], 2, "Tbe quick fox", 1).unwrap().unwrap();
+        let entries = vec![base("Tbe qvick fox"), base("Tbe qvick fox"), a, b];
+
+        let history = versions(&entries).unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].text, "Tbe qvick fox");
+        assert_eq!(history[0].device, None);
+        assert_eq!(history.last().unwrap().text, "The quick fox");
+        assert!(edit(&entries, 1, "The quick fox", 2).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_conflict() {
+        let a = edit(&[base("Tbe qvick fox")], 1, "The qvick fox", 1).unwrap().unwrap();
+        // Device 2 saw device 1's edit: no conflict
+        let b = edit(&[base("Tbe qvick fox"), a.clone()], 2, "The quick fox", 2).unwrap().unwrap();
+        assert_eq!(conflict(&[base("Tbe qvick fox"), a.clone(), b]), None);
+
+        // Device 2 edited without seeing it, and the deletion alone on device 3 too
+        let b = edit(&[base("Tbe qvick fox")], 2, "Tbe quick fox", 2).unwrap().unwrap();
+        let c = edit(&[base("Tbe qvick fox")], 3, "Tbe qvick", 3).unwrap().unwrap();
+        let mut entries = vec![base("Tbe qvick fox"), a, b, c];
+        assert_eq!(conflict(&entries), Some((vec!["1".to_string(), "2".to_string(), "3".to_string()], 3)));
+
+        // An edit made on the merged text settles it
+        let settle = edit(&entries, 1, "The quick fox", 4).unwrap().unwrap();
+        entries.push(settle);
+        assert_eq!(conflict(&entries), None);
+    }
+}
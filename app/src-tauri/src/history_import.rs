@@ -0,0 +1,308 @@
+// Import history from other OCR tools
+// Text Shot and PowerToys Text Extractor keep no history database of their own;
+// users typically log their output to a text file (one capture per block,
+// separated by blank lines) or a JSON array. Plain-text folders (one capture per
+// .txt file) and CSV exports are supported as well.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::error::{AppError, AppResult};
+use crate::history::HistoryItem;
+
+/// Language recorded for imported items without one
+const UNKNOWN_LANG: &str = "auto";
+
+/// Supported import formats
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalFormat {
+    /// Text Shot log file or JSON export
+    TextShot,
+    /// PowerToys Text Extractor log file or JSON export
+    PowerToys,
+    /// Folder of .txt files, one capture each
+    TextFolder,
+    /// CSV with a header row (text/content, lang/language, timestamp/date)
+    Csv,
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Days since 1970-01-01 for a civil date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Parse an epoch (seconds or milliseconds) or "YYYY-MM-DD[ T]HH:MM[:SS]" (UTC)
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(n) = value.parse::<i64>() {
+        // Anything below year ~2286 in seconds is treated as seconds
+        return Some(if n < 10_000_000_000 { n * 1000 } else { n });
+    }
+
+    let (date, time) = value
+        .split_once(|c| c == 'T' || c == ' ')
+        .unwrap_or((value, ""));
+    let mut parts = date.split(|c| c == '-' || c == '/');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut hms = time
+        .trim_end_matches('Z')
+        .split(':')
+        .map(|p| p.split('.').next().unwrap_or("0").parse::<i64>().unwrap_or(0));
+    let seconds = hms.next().unwrap_or(0) * 3600 + hms.next().unwrap_or(0) * 60 + hms.next().unwrap_or(0);
+
+    Some((days_from_civil(year, month, day) * 86_400 + seconds) * 1000)
+}
+
+/// Stable id so re-importing the same capture dedupes
+fn import_id(text: &str, timestamp: i64) -> String {
+    // FNV-1a
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.as_bytes().iter().chain(timestamp.to_le_bytes().iter()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("import-{:x}", hash)
+}
+
+fn make_item(text: &str, lang: Option<&str>, timestamp: i64) -> HistoryItem {
+    let text = text.trim().to_string();
+    HistoryItem {
+        id: import_id(&text, timestamp),
+        lang: lang.filter(|l| !l.is_empty()).unwrap_or(UNKNOWN_LANG).to_string(),
+        timestamp,
+        text,
+        source_app: None,
+        summary: None,
+        tags: Vec::new(),
+    }
+}
+
+/// Split a CSV document into records (RFC 4180 quoting)
+pub(crate) fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+fn import_csv(content: &str) -> AppResult<Vec<HistoryItem>> {
+    let mut records = parse_csv(content.trim_start_matches('\u{feff}')).into_iter();
+    let header: Vec<String> = records
+        .next()
+        .ok_or_else(|| AppError::InvalidInput("CSV file is empty".to_string()))?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+
+    let text_col = column(&["text", "content", "ocr", "result"])
+        .ok_or_else(|| AppError::InvalidInput("CSV has no text column".to_string()))?;
+    let lang_col = column(&["lang", "language"]);
+    let time_col = column(&["timestamp", "date", "time", "created"]);
+    let fallback = now_ms();
+
+    Ok(records
+        .filter_map(|r| {
+            let text = r.get(text_col)?;
+            let lang = lang_col.and_then(|c| r.get(c)).map(|s| s.as_str());
+            let timestamp = time_col
+                .and_then(|c| r.get(c))
+                .and_then(|t| parse_timestamp(t))
+                .unwrap_or(fallback);
+            Some(make_item(text, lang, timestamp))
+        })
+        .collect())
+}
+
+/// JSON array of strings or objects with text/lang/timestamp fields
+fn import_json(content: &str) -> AppResult<Vec<HistoryItem>> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(content)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid JSON export: {}", e)))?;
+    let fallback = now_ms();
+
+    Ok(values
+        .iter()
+        .filter_map(|v| {
+            if let Some(text) = v.as_str() {
+                return Some(make_item(text, None, fallback));
+            }
+            let text = v.get("text").or_else(|| v.get("content"))?.as_str()?;
+            let lang = v.get("lang").or_else(|| v.get("language")).and_then(|l| l.as_str());
+            let timestamp = v
+                .get("timestamp")
+                .or_else(|| v.get("date"))
+                .and_then(|t| match t {
+                    serde_json::Value::Number(n) => n.as_i64().map(|n| n.to_string()),
+                    serde_json::Value::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .and_then(|t| parse_timestamp(&t))
+                .unwrap_or(fallback);
+            Some(make_item(text, lang, timestamp))
+        })
+        .collect())
+}
+
+/// Plain-text log, one capture per blank-line separated block
+fn import_text_log(content: &str, timestamp: i64) -> Vec<HistoryItem> {
+    content
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .map(|block| make_item(block, None, timestamp))
+        .collect()
+}
+
+fn file_modified_ms(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_else(now_ms)
+}
+
+fn import_text_folder(dir: &Path) -> AppResult<Vec<HistoryItem>> {
+    let mut items = Vec::new();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let is_text = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("txt"));
+        if !is_text {
+            continue;
+        }
+        if let Ok(text) = std::fs::read_to_string(&path) {
+            items.push(make_item(&text, None, file_modified_ms(&path)));
+        }
+    }
+    Ok(items)
+}
+
+/// Read items from an external source
+pub fn import(format: ExternalFormat, path: &Path) -> AppResult<Vec<HistoryItem>> {
+    match format {
+        ExternalFormat::TextFolder => import_text_folder(path),
+        ExternalFormat::Csv => import_csv(&std::fs::read_to_string(path)?),
+        ExternalFormat::TextShot | ExternalFormat::PowerToys => {
+            let content = std::fs::read_to_string(path)?;
+            if content.trim_start().starts_with('[') {
+                import_json(&content)
+            } else {
+                Ok(import_text_log(&content, file_modified_ms(path)))
+            }
+        }
+    }
+}
+
+/// Drop empty items and those whose text already exists, newest first
+pub fn dedupe(items: Vec<HistoryItem>, existing: &[HistoryItem]) -> Vec<HistoryItem> {
+    let mut seen: HashSet<String> = existing.iter().map(|i| i.text.trim().to_string()).collect();
+    let mut items: Vec<HistoryItem> = items
+        .into_iter()
+        .filter(|i| !i.text.is_empty() && seen.insert(i.text.clone()))
+        .collect();
+    items.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    items
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Import history from another tool. Returns the new items (not already in
+/// `existing`) for the frontend to merge; each is also announced on
+/// `sync://item-added`.
+#[tauri::command]
+pub fn sync_import_external(
+    format: ExternalFormat,
+    path: String,
+    existing: Option<Vec<HistoryItem>>,
+) -> AppResult<Vec<HistoryItem>> {
+    let items = import(format, Path::new(&path))?;
+    let items = dedupe(items, &existing.unwrap_or_default());
+    for item in &items {
+        crate::events::emit(crate::events::SYNC_ITEM_ADDED_EVENT, item);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1700000000"), Some(1_700_000_000_000));
+        assert_eq!(parse_timestamp("1700000000123"), Some(1_700_000_000_123));
+        assert_eq!(parse_timestamp("1970-01-02"), Some(86_400_000));
+        assert_eq!(parse_timestamp("2024-02-29T23:30:00Z"), Some(1_709_249_400_000));
+        assert_eq!(parse_timestamp("2024/02/29 23:30"), Some(1_709_249_400_000));
+        assert_eq!(parse_timestamp("not a date"), None);
+    }
+
+    #[test]
+    fn test_import_csv() {
+        let csv = "Text,Language,Date\n\"Hello, \"\"world\"\"\",eng,1970-01-02\n\"multi\nline\",jpn,\n";
+        let items = import_csv(csv).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].text, "Hello, \"world\"");
+        assert_eq!(items[0].lang, "eng");
+        assert_eq!(items[0].timestamp, 86_400_000);
+        assert_eq!(items[1].text, "multi\nline");
+    }
+
+    #[test]
+    fn test_dedupe() {
+        let existing = vec![make_item("old", None, 1)];
+        let items = import_text_log("old\n\nnew\n\n\n\nnew\n", 5);
+        let result = dedupe(items, &existing);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].text, "new");
+        assert_eq!(result[0].lang, UNKNOWN_LANG);
+    }
+}
@@ -0,0 +1,268 @@
+// Hover OCR
+// While toggled on, the small area around the mouse cursor is recognized once
+// the cursor rests, and the line under it is shown in a tooltip window next
+// to the pointer, like a hover dictionary for anything on screen. Captures are
+// throttled to the configured interval and not re-recognized while the cursor
+// and the pixels under it stay the same.
+
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use image::ImageFormat;
+use once_cell::sync::Lazy;
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewUrl, WebviewWindowBuilder,
+};
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::error::{AppError, AppResult};
+use crate::frame_diff::{self, Region};
+use crate::ocr::{self, OcrOptions};
+
+/// Label of the tooltip window (matched by its capability)
+pub const WINDOW_LABEL: &str = "hover-ocr";
+
+/// Emitted with a `HoverText` when the text under the cursor changes
+pub const HOVER_OCR_EVENT: &str = "hover-ocr://text";
+
+/// Emitted with the new state when hover OCR is toggled
+pub const HOVER_OCR_TOGGLED_EVENT: &str = "hover-ocr://toggled";
+
+/// Cursor movement (physical pixels) still counted as resting
+const REST_TOLERANCE: f64 = 4.0;
+
+/// Gap between the recognized area and the tooltip below it, which must stay
+/// out of the capture
+const TOOLTIP_GAP: u32 = 8;
+const TOOLTIP_WIDTH: u32 = 360;
+const TOOLTIP_HEIGHT: u32 = 72;
+
+const MIN_INTERVAL_MS: u64 = 100;
+
+/// Stop flag of the running loop
+static SESSION: Lazy<Mutex<Option<Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(None));
+/// Text shown, for a tooltip window that opens after it was emitted
+static LATEST: Lazy<Mutex<Option<HoverText>>> = Lazy::new(|| Mutex::new(None));
+
+/// Hover OCR settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HoverOcrConfig {
+    /// Area recognized around the cursor, in physical pixels
+    pub width: u32,
+    pub height: u32,
+    /// Time between cursor checks
+    pub interval_ms: u64,
+    /// Tesseract languages; the default OCR languages when unset
+    pub langs: Option<String>,
+}
+
+impl Default for HoverOcrConfig {
+    fn default() -> Self {
+        Self {
+            width: 480,
+            height: 96,
+            interval_ms: 300,
+            langs: None,
+        }
+    }
+}
+
+/// Payload of `hover-ocr://text`; empty text when nothing is under the cursor
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct HoverText {
+    pub text: String,
+    /// Cursor position the text was found at
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Whether the cursor stayed put since the last check
+fn resting(previous: Option<(f64, f64)>, current: (f64, f64)) -> bool {
+    previous.is_some_and(|(x, y)| (current.0 - x).abs() <= REST_TOLERANCE && (current.1 - y).abs() <= REST_TOLERANCE)
+}
+
+/// The line under the cursor in a capture of `region`
+fn recognize(frame: &image::RgbaImage, region: &Region, cursor: (f64, f64), langs: Option<String>) -> AppResult<String> {
+    let mut bytes = Vec::new();
+    frame
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    let result = ocr::run_ocr_detailed(&bytes, &OcrOptions { langs, ..Default::default() })?;
+    let x = (cursor.0 as u32).saturating_sub(region.x);
+    let y = (cursor.1 as u32).saturating_sub(region.y);
+    Ok(crate::text_grab::line_at(&result, x, y).map(|line| line.text.clone()).unwrap_or_default())
+}
+
+/// Show the text under the pointer just below `region`, or hide the tooltip
+/// when there's none
+fn show_tooltip<R: Runtime>(app: &AppHandle<R>, hover: &HoverText, region: &Region) -> AppResult<()> {
+    let window = match app.get_webview_window(WINDOW_LABEL) {
+        Some(window) => window,
+        None if hover.text.is_empty() => return Ok(()),
+        None => WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App("index.html?hover-ocr=1".into()))
+            .title("Screen Inu")
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(false)
+            .visible(false)
+            .build()
+            .map_err(|e| AppError::Other(format!("Failed to open hover OCR tooltip: {}", e)))?,
+    };
+    let result = if hover.text.is_empty() {
+        window.hide()
+    } else {
+        let x = (hover.x as i32 - TOOLTIP_WIDTH as i32 / 2).max(0);
+        let position = PhysicalPosition::new(x, (region.y + region.height + TOOLTIP_GAP) as i32);
+        window
+            .set_position(position)
+            .and_then(|_| window.set_size(PhysicalSize::new(TOOLTIP_WIDTH, TOOLTIP_HEIGHT)))
+            .and_then(|_| window.show())
+    };
+    result.map_err(|e| AppError::Other(format!("Failed to place hover OCR tooltip: {}", e)))
+}
+
+fn run_session<R: Runtime>(app: AppHandle<R>, stop: Arc<AtomicBool>) {
+    let mut backend = XcapBackend::new(0);
+    let mut last_cursor: Option<(f64, f64)> = None;
+    // Region and pixels last recognized, and the text found
+    let mut recognized: Option<(Region, image::RgbaImage)> = None;
+    let mut last_text = String::new();
+
+    while !stop.load(Ordering::SeqCst) {
+        let config = crate::config::get().map(|c| c.hover_ocr).unwrap_or_default();
+        let interval = Duration::from_millis(config.interval_ms.max(MIN_INTERVAL_MS));
+        let started = Instant::now();
+
+        let cursor = match app.cursor_position() {
+            Ok(position) => (position.x, position.y),
+            Err(e) => {
+                tracing::debug!("Hover OCR: cursor position unavailable: {}", e);
+                std::thread::sleep(interval);
+                continue;
+            }
+        };
+        // Wait for the cursor to rest before recognizing
+        let rested = resting(last_cursor, cursor);
+        last_cursor = Some(cursor);
+        if rested {
+            let region = Region::around(cursor.0, cursor.1, config.width, config.height);
+            match backend.capture_region(region.x, region.y, region.width, region.height) {
+                Ok(frame) => {
+                    let unchanged = recognized.as_ref().is_some_and(|(previous_region, previous)| {
+                        *previous_region == region && frame_diff::changed_regions(previous, &frame).is_empty()
+                    });
+                    if !unchanged {
+                        match recognize(&frame, &region, cursor, config.langs.clone()) {
+                            // Same text elsewhere still moves the tooltip
+                            Ok(text) if text != last_text || !text.is_empty() => {
+                                let hover = HoverText { text: text.clone(), x: cursor.0, y: cursor.1 };
+                                if let Ok(mut latest) = LATEST.lock() {
+                                    *latest = Some(hover.clone());
+                                }
+                                if let Err(e) = show_tooltip(&app, &hover, &region) {
+                                    tracing::warn!("Hover OCR: {}", e);
+                                }
+                                let _ = app.emit(HOVER_OCR_EVENT, hover);
+                                last_text = text;
+                            }
+                            Ok(_) => {}
+                            Err(e) => tracing::warn!("Hover OCR failed: {}", e),
+                        }
+                        recognized = Some((region, frame));
+                    }
+                }
+                Err(e) => tracing::warn!("Hover OCR capture failed: {}", e),
+            }
+        }
+
+        if let Some(remaining) = interval.checked_sub(started.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.close();
+    }
+}
+
+pub fn is_running() -> bool {
+    SESSION.lock().map(|session| session.is_some()).unwrap_or(false)
+}
+
+/// Turn hover OCR on or off, keeping the tray and the UI in sync
+pub fn set_enabled<R: Runtime>(app: &AppHandle<R>, enabled: bool) -> AppResult<()> {
+    let mut session = SESSION.lock()?;
+    if session.is_some() == enabled {
+        return Ok(());
+    }
+    if enabled {
+        crate::permissions::ensure_capture_permission()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let session_app = app.clone();
+        std::thread::spawn(move || run_session(session_app, thread_stop));
+        *session = Some(stop);
+    } else if let Some(stop) = session.take() {
+        stop.store(true, Ordering::SeqCst);
+        *LATEST.lock()? = None;
+    }
+    drop(session);
+
+    let _ = app.emit(HOVER_OCR_TOGGLED_EVENT, enabled);
+    if let Err(e) = crate::tray::refresh(app) {
+        tracing::warn!("Tray menu not updated: {}", e);
+    }
+    Ok(())
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Start or stop hover OCR
+#[tauri::command]
+pub fn set_hover_ocr(app: AppHandle, enabled: bool) -> AppResult<()> {
+    set_enabled(&app, enabled)
+}
+
+/// The text under the cursor, if any
+#[tauri::command]
+pub fn get_hover_text() -> AppResult<Option<HoverText>> {
+    Ok(LATEST.lock()?.clone())
+}
+
+#[tauri::command]
+pub fn is_hover_ocr_running() -> AppResult<bool> {
+    Ok(is_running())
+}
+
+#[tauri::command]
+pub fn get_hover_ocr_config() -> AppResult<HoverOcrConfig> {
+    Ok(crate::config::get()?.hover_ocr)
+}
+
+/// Save the settings; a running session picks them up on its next check
+#[tauri::command]
+pub fn set_hover_ocr_config(config: HoverOcrConfig) -> AppResult<()> {
+    if config.width < 16 || config.height < 16 {
+        return Err(AppError::InvalidInput("Hover OCR area is too small".to_string()));
+    }
+    crate::config::update(|c| c.hover_ocr = config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resting() {
+        assert!(!resting(None, (10.0, 10.0)));
+        assert!(resting(Some((10.0, 10.0)), (12.0, 7.0)));
+        assert!(!resting(Some((10.0, 10.0)), (20.0, 10.0)));
+    }
+}
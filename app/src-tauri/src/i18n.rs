@@ -0,0 +1,187 @@
+// Backend localization
+// User-facing strings produced in Rust (error messages sent to the UI,
+// notifications, tray labels) come from the Fluent resources in `locales/`,
+// in the UI language the frontend passes to `set_locale` at startup. The
+// locale is saved so the tray is localized before the UI loads. Logs and
+// `Display` output stay in English.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use once_cell::sync::Lazy;
+use tauri::AppHandle;
+use unic_langid::LanguageIdentifier;
+
+use crate::error::AppResult;
+
+const FALLBACK_LOCALE: &str = "en";
+
+/// Bundled locales, the same as the frontend's
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("ja", include_str!("../locales/ja.ftl")),
+    ("ko", include_str!("../locales/ko.ftl")),
+    ("zh-CN", include_str!("../locales/zh-CN.ftl")),
+    ("zh-TW", include_str!("../locales/zh-TW.ftl")),
+];
+
+static BUNDLES: Lazy<HashMap<&'static str, FluentBundle<FluentResource>>> = Lazy::new(|| {
+    LOCALES
+        .iter()
+        .filter_map(|(locale, source)| bundle(locale, source).map(|bundle| (*locale, bundle)))
+        .collect()
+});
+
+static LOCALE: Lazy<RwLock<&'static str>> = Lazy::new(|| {
+    let saved = crate::config::get().map(|c| c.i18n.locale).unwrap_or_default();
+    RwLock::new(resolve(&saved))
+});
+
+/// Localization settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct I18nConfig {
+    /// Last UI locale reported by the frontend, e.g. "zh-TW"
+    pub locale: String,
+}
+
+fn bundle(locale: &str, source: &str) -> Option<FluentBundle<FluentResource>> {
+    let langid: LanguageIdentifier = locale.parse().ok()?;
+    let resource = match FluentResource::try_new(source.to_string()) {
+        Ok(resource) => resource,
+        Err((_, errors)) => {
+            tracing::warn!("Invalid {} messages: {:?}", locale, errors);
+            return None;
+        }
+    };
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // No Unicode isolation marks around arguments; notifications and the
+    // tray would show them as boxes
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource).ok()?;
+    Some(bundle)
+}
+
+/// Bundled locale for a UI locale: Chinese by script or region, others by
+/// language, English if unsupported
+fn resolve(locale: &str) -> &'static str {
+    let Ok(id) = locale.parse::<LanguageIdentifier>() else {
+        return FALLBACK_LOCALE;
+    };
+    let language = id.language.as_str();
+    if language == "zh" {
+        let traditional = id.script.is_some_and(|s| s.as_str() == "Hant")
+            || id.region.is_some_and(|r| matches!(r.as_str(), "TW" | "HK" | "MO"));
+        return if traditional { "zh-TW" } else { "zh-CN" };
+    }
+    LOCALES
+        .iter()
+        .map(|(bundled, _)| *bundled)
+        .find(|bundled| *bundled == language)
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+fn format(locale: &str, id: &str, args: &FluentArgs) -> Option<String> {
+    let bundle = BUNDLES.get(locale)?;
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(args), &mut errors);
+    if !errors.is_empty() {
+        tracing::debug!("Formatting {} ({}): {:?}", id, locale, errors);
+    }
+    Some(text.into_owned())
+}
+
+/// The current backend locale
+pub fn current() -> &'static str {
+    LOCALE.read().map(|locale| *locale).unwrap_or(FALLBACK_LOCALE)
+}
+
+/// Message `id` in the current locale with `$name` arguments, falling back
+/// to English, then to the id itself
+pub fn t_args(id: &str, args: &[(&str, &str)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, *value);
+    }
+    format(current(), id, &fluent_args)
+        .or_else(|| format(FALLBACK_LOCALE, id, &fluent_args))
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Message `id` in the current locale
+pub fn t(id: &str) -> String {
+    t_args(id, &[])
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Switch backend messages to the UI locale. Returns the bundled locale used.
+#[tauri::command]
+pub fn set_locale(app: AppHandle, locale: String) -> AppResult<String> {
+    let resolved = resolve(&locale);
+    let changed = {
+        let mut current = LOCALE.write()?;
+        let changed = *current != resolved;
+        *current = resolved;
+        changed
+    };
+    if crate::config::get()?.i18n.locale != locale {
+        crate::config::update(|c| c.i18n.locale = locale)?;
+    }
+    if changed {
+        if let Err(e) = crate::tray::refresh(&app) {
+            tracing::warn!("Tray labels not updated: {}", e);
+        }
+    }
+    Ok(resolved.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve("en-US"), "en");
+        assert_eq!(resolve("ja"), "ja");
+        assert_eq!(resolve("ko-KR"), "ko");
+        assert_eq!(resolve("zh-TW"), "zh-TW");
+        assert_eq!(resolve("zh-HK"), "zh-TW");
+        assert_eq!(resolve("zh-Hant"), "zh-TW");
+        assert_eq!(resolve("zh"), "zh-CN");
+        assert_eq!(resolve("zh-CN"), "zh-CN");
+        assert_eq!(resolve("fr-FR"), "en");
+        assert_eq!(resolve(""), "en");
+    }
+
+    #[test]
+    fn test_locales_complete() {
+        let english: Vec<&str> = LOCALES[0]
+            .1
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(" = ").map(|(id, _)| id))
+            .collect();
+        assert!(english.contains(&"error-ocr"));
+        for (locale, _) in LOCALES {
+            let bundle = BUNDLES.get(locale).unwrap_or_else(|| panic!("{} failed to load", locale));
+            for id in &english {
+                assert!(bundle.has_message(id), "{} is missing {}", locale, id);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        let mut args = FluentArgs::new();
+        args.set("detail", "timeout");
+        assert_eq!(format("en", "error-ocr", &args).unwrap(), "OCR failed: timeout");
+        assert_eq!(format("ja", "error-ocr", &args).unwrap(), "OCR に失敗しました: timeout");
+        assert!(format("en", "no-such-message", &args).is_none());
+    }
+}
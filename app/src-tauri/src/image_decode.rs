@@ -0,0 +1,169 @@
+// Image decoding
+// Shared decoder for everything the OCR entry points accept. The image crate
+// covers the usual formats; HEIC (iPhone photos and screenshots) and AVIF are
+// ISO-BMFF containers decoded with libheif (`heif` feature), and converted to
+// PNG before they reach the OCR engines, which can't read them. Images are turned upright
+// according to their EXIF orientation (phone photos are often stored
+// sideways), which the engines ignore.
+
+use std::borrow::Cow;
+
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageReader, RgbaImage};
+#[cfg(feature = "heif")]
+use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+use crate::error::{AppError, AppResult};
+
+/// HEIF image brands (HEVC-coded stills and sequences, generic HEIF)
+const HEIF_BRANDS: &[&[u8; 4]] = &[b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1", b"msf1"];
+const AVIF_BRANDS: &[&[u8; 4]] = &[b"avif", b"avis"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Container {
+    Heif,
+    Avif,
+    Other,
+}
+
+/// Identify HEIF/AVIF from the `ftyp` box: the major brand, then the
+/// compatible brands (AVIF files may declare "mif1" as major brand)
+fn sniff(bytes: &[u8]) -> Container {
+    if bytes.len() < 16 || &bytes[4..8] != b"ftyp" {
+        return Container::Other;
+    }
+    let box_size = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+    let end = box_size.clamp(16, bytes.len());
+    // Major brand, minor version, then compatible brands
+    let brands = std::iter::once(&bytes[8..12]).chain(bytes[16..end].chunks_exact(4));
+    let brands: Vec<&[u8]> = brands.collect();
+    let has = |list: &[&[u8; 4]]| brands.iter().any(|b| list.iter().any(|l| &l[..] == *b));
+    if has(AVIF_BRANDS) {
+        Container::Avif
+    } else if has(HEIF_BRANDS) {
+        Container::Heif
+    } else {
+        Container::Other
+    }
+}
+
+#[cfg(feature = "heif")]
+fn heif_error(e: libheif_rs::HeifError) -> AppError {
+    AppError::InvalidInput(format!("Failed to decode HEIF image: {}", e))
+}
+
+/// Primary image of a HEIF/AVIF file
+#[cfg(feature = "heif")]
+fn decode_heif(bytes: &[u8]) -> AppResult<DynamicImage> {
+    let context = HeifContext::read_from_bytes(bytes).map_err(heif_error)?;
+    let handle = context.primary_image_handle().map_err(heif_error)?;
+    let image = LibHeif::new()
+        .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+        .map_err(heif_error)?;
+    let plane = image
+        .planes()
+        .interleaved
+        .ok_or_else(|| AppError::InvalidInput("HEIF image has no RGBA plane".to_string()))?;
+
+    // Rows may be padded past width * 4 bytes
+    let row_len = plane.width as usize * 4;
+    let pixels: Vec<u8> = plane
+        .data
+        .chunks(plane.stride)
+        .take(plane.height as usize)
+        .flat_map(|row| &row[..row_len.min(row.len())])
+        .copied()
+        .collect();
+    RgbaImage::from_raw(plane.width, plane.height, pixels)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| AppError::InvalidInput("HEIF image is truncated".to_string()))
+}
+
+#[cfg(not(feature = "heif"))]
+fn decode_heif(_bytes: &[u8]) -> AppResult<DynamicImage> {
+    Err(AppError::Unsupported(
+        "HEIC/AVIF images are not supported by this build (heif feature)".to_string(),
+    ))
+}
+
+fn decode_error(e: image::ImageError) -> AppError {
+    AppError::InvalidInput(format!("Failed to decode image: {}", e))
+}
+
+/// EXIF orientation of an image the image crate reads (NoTransforms when it
+/// has none or can't be parsed)
+pub fn exif_orientation(bytes: &[u8]) -> Orientation {
+    ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_decoder().ok())
+        .and_then(|mut decoder| decoder.orientation().ok())
+        .unwrap_or(Orientation::NoTransforms)
+}
+
+/// Decode with the image crate and apply the EXIF orientation
+fn decode_upright(bytes: &[u8]) -> AppResult<DynamicImage> {
+    let mut decoder = ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| AppError::InvalidInput(format!("Failed to decode image: {}", e)))?
+        .into_decoder()
+        .map_err(decode_error)?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder).map_err(decode_error)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Decode an image in any supported format, including HEIC and AVIF, upright
+pub fn decode_image_bytes(bytes: &[u8]) -> AppResult<DynamicImage> {
+    match sniff(bytes) {
+        // libheif applies the container's rotation and mirroring itself
+        Container::Heif | Container::Avif => decode_heif(bytes),
+        Container::Other => decode_upright(bytes),
+    }
+}
+
+/// Image bytes the OCR engines can read: HEIC/AVIF, QOI and images with an
+/// EXIF rotation converted to upright PNG, anything else unchanged
+pub fn ocr_input(bytes: &[u8]) -> AppResult<Cow<'_, [u8]>> {
+    let qoi = bytes.starts_with(b"qoif");
+    if sniff(bytes) == Container::Other && !qoi && exif_orientation(bytes) == Orientation::NoTransforms {
+        return Ok(Cow::Borrowed(bytes));
+    }
+    let mut png = Vec::new();
+    decode_image_bytes(bytes)?
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| AppError::Ocr(format!("Failed to encode image: {}", e)))?;
+    Ok(Cow::Owned(png))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ftyp(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+        let size = 16 + 4 * compatible.len() as u32;
+        let mut bytes = size.to_be_bytes().to_vec();
+        bytes.extend_from_slice(b"ftyp");
+        bytes.extend_from_slice(major);
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+        compatible.iter().for_each(|b| bytes.extend_from_slice(*b));
+        bytes.extend_from_slice(b"\0\0\0\x08meta");
+        bytes
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(sniff(&ftyp(b"heic", &[b"mif1", b"heic"])), Container::Heif);
+        assert_eq!(sniff(&ftyp(b"avif", &[b"mif1"])), Container::Avif);
+        assert_eq!(sniff(&ftyp(b"mif1", &[b"avif", b"miaf"])), Container::Avif);
+        assert_eq!(sniff(&ftyp(b"isom", &[b"mp41"])), Container::Other);
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Container::Other);
+    }
+
+    #[test]
+    fn test_ocr_input_passes_through_other_formats() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        assert!(matches!(ocr_input(png).unwrap(), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,240 @@
+// Capture output encoding
+// Encodes captures for the webview in the requested format and size. PNG stays
+// the default since the frontend crops it for OCR; JPEG/WebP at ~80% quality
+// cut the payload for previews by an order of magnitude. Capture commands run
+// capture and encoding on a small rayon pool (`spawn`), since PNG-encoding an
+// 8K multi-monitor capture takes long enough to stall a command thread.
+// QOI and lossless WebP are much faster lossless options for the internal
+// pipeline; the webview can't display QOI. WebP goes through libwebp with the
+// `webp` feature; without it only lossless WebP is available, from the image
+// crate's encoder.
+
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageEncoder, RgbaImage};
+use once_cell::sync::Lazy;
+
+use crate::error::{AppError, AppResult};
+
+/// Quality used by lossy formats when none is given
+const DEFAULT_QUALITY: u8 = 80;
+
+/// AVIF encoder speed (1 slowest - 10 fastest); captures need to be quick
+const AVIF_SPEED: u8 = 8;
+
+/// Encoding threads; a couple is enough to keep captures off command threads
+/// without competing with batch OCR for the global pool
+const ENCODE_THREADS: usize = 2;
+
+static ENCODE_POOL: Lazy<Option<rayon::ThreadPool>> = Lazy::new(|| {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(ENCODE_THREADS)
+        .thread_name(|index| format!("encode-{}", index))
+        .build()
+        .map_err(|e| tracing::warn!("Encode pool not started, using the global pool: {}", e))
+        .ok()
+});
+
+/// Output image format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+    Avif,
+    /// Lossless WebP regardless of quality
+    #[serde(rename = "webp-lossless")]
+    WebpLossless,
+    /// Fast lossless format for the internal pipeline; not viewable in the webview
+    Qoi,
+}
+
+impl OutputFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "image/png",
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::Webp | OutputFormat::WebpLossless => "image/webp",
+            OutputFormat::Avif => "image/avif",
+            OutputFormat::Qoi => "image/qoi",
+        }
+    }
+}
+
+/// How to encode a capture
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    /// 1-100 for JPEG, WebP and AVIF; 100 makes WebP lossless. Ignored for PNG.
+    pub quality: Option<u8>,
+    /// Downscale (keeping the aspect ratio) to fit within these bounds
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+}
+
+impl OutputOptions {
+    fn quality(&self) -> AppResult<u8> {
+        match self.quality {
+            Some(quality) if !(1..=100).contains(&quality) => {
+                Err(AppError::InvalidInput(format!("Invalid image quality: {}", quality)))
+            }
+            quality => Ok(quality.unwrap_or(DEFAULT_QUALITY)),
+        }
+    }
+}
+
+/// Size that fits within the bounds, keeping the aspect ratio; never upscales
+fn fit_within(width: u32, height: u32, max_width: Option<u32>, max_height: Option<u32>) -> (u32, u32) {
+    let scale_x = max_width.map_or(1.0, |m| m as f64 / width.max(1) as f64);
+    let scale_y = max_height.map_or(1.0, |m| m as f64 / height.max(1) as f64);
+    let scale = scale_x.min(scale_y).min(1.0);
+    if scale >= 1.0 {
+        return (width, height);
+    }
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+fn encode_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Capture(format!("Failed to encode image: {}", e))
+}
+
+/// Run `work` (capture, encoding) on the encode pool and await its result
+pub async fn spawn<T: Send + 'static>(work: impl FnOnce() -> AppResult<T> + Send + 'static) -> AppResult<T> {
+    let (sender, mut receiver) = tauri::async_runtime::channel(1);
+    let job = move || {
+        let _ = sender.blocking_send(work());
+    };
+    match ENCODE_POOL.as_ref() {
+        Some(pool) => pool.spawn(job),
+        None => rayon::spawn(job),
+    }
+    receiver
+        .recv()
+        .await
+        .unwrap_or_else(|| Err(AppError::Other("Encoding was interrupted".to_string())))
+}
+
+/// Encode an image with the given options
+pub fn encode(image: RgbaImage, options: &OutputOptions) -> AppResult<Vec<u8>> {
+    crate::perf::time(crate::perf::ENCODE, || encode_image(image, options))
+}
+
+#[cfg(feature = "webp")]
+fn encode_webp_lossless(image: &RgbaImage, bytes: &mut Vec<u8>) -> AppResult<()> {
+    let (width, height) = image.dimensions();
+    bytes.extend_from_slice(&webp::Encoder::from_rgba(image.as_raw(), width, height).encode_lossless());
+    Ok(())
+}
+
+#[cfg(not(feature = "webp"))]
+fn encode_webp_lossless(image: &RgbaImage, bytes: &mut Vec<u8>) -> AppResult<()> {
+    let (width, height) = image.dimensions();
+    image::codecs::webp::WebPEncoder::new_lossless(bytes)
+        .write_image(image.as_raw(), width, height, image::ExtendedColorType::Rgba8)
+        .map_err(encode_error)
+}
+
+fn encode_image(image: RgbaImage, options: &OutputOptions) -> AppResult<Vec<u8>> {
+    let quality = options.quality()?;
+    let (width, height) = fit_within(image.width(), image.height(), options.max_width, options.max_height);
+    let image = if (width, height) == image.dimensions() {
+        image
+    } else {
+        image::imageops::resize(&image, width, height, FilterType::Triangle)
+    };
+
+    let mut bytes: Vec<u8> = Vec::new();
+    match options.format {
+        OutputFormat::Png => DynamicImage::ImageRgba8(image)
+            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .map_err(encode_error)?,
+        // JPEG has no alpha channel
+        OutputFormat::Jpeg => DynamicImage::ImageRgba8(image)
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, quality))
+            .map_err(encode_error)?,
+        #[cfg(feature = "webp")]
+        OutputFormat::Webp => {
+            let encoder = webp::Encoder::from_rgba(image.as_raw(), width, height);
+            let encoded = if quality == 100 {
+                encoder.encode_lossless()
+            } else {
+                encoder.encode(quality as f32)
+            };
+            bytes.extend_from_slice(&encoded);
+        }
+        #[cfg(not(feature = "webp"))]
+        OutputFormat::Webp if quality == 100 => encode_webp_lossless(&image, &mut bytes)?,
+        #[cfg(not(feature = "webp"))]
+        OutputFormat::Webp => {
+            return Err(AppError::Unsupported(
+                "Lossy WebP is not supported by this build (webp feature)".to_string(),
+            ))
+        }
+        OutputFormat::Avif => AvifEncoder::new_with_speed_quality(&mut bytes, AVIF_SPEED, quality)
+            .write_image(image.as_raw(), width, height, image::ExtendedColorType::Rgba8)
+            .map_err(encode_error)?,
+        OutputFormat::WebpLossless => encode_webp_lossless(&image, &mut bytes)?,
+        OutputFormat::Qoi => DynamicImage::ImageRgba8(image)
+            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Qoi)
+            .map_err(encode_error)?,
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_within() {
+        assert_eq!(fit_within(3840, 2160, Some(1920), None), (1920, 1080));
+        assert_eq!(fit_within(3840, 2160, Some(1920), Some(540)), (960, 540));
+        assert_eq!(fit_within(800, 600, Some(1920), Some(1080)), (800, 600));
+        assert_eq!(fit_within(800, 600, None, None), (800, 600));
+    }
+
+    #[test]
+    fn test_encode_formats() {
+        let image = RgbaImage::from_pixel(64, 32, image::Rgba([200, 100, 50, 255]));
+        let png = encode(image.clone(), &OutputOptions::default()).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let options = OutputOptions {
+            format: OutputFormat::Jpeg,
+            max_width: Some(32),
+            ..Default::default()
+        };
+        let jpeg = encode(image.clone(), &options).unwrap();
+        assert!(jpeg.starts_with(&[0xFF, 0xD8]));
+        assert_eq!(image::load_from_memory(&jpeg).unwrap().width(), 32);
+
+        let invalid = OutputOptions {
+            quality: Some(0),
+            ..options
+        };
+        assert!(encode(image.clone(), &invalid).is_err());
+
+        let options = OutputOptions {
+            format: OutputFormat::Qoi,
+            ..Default::default()
+        };
+        let qoi = encode(image.clone(), &options).unwrap();
+        assert!(qoi.starts_with(b"qoif"));
+        assert_eq!(image::load_from_memory(&qoi).unwrap().to_rgba8(), image);
+
+        let options = OutputOptions {
+            format: OutputFormat::WebpLossless,
+            ..Default::default()
+        };
+        let webp = encode(image.clone(), &options).unwrap();
+        assert_eq!(image::load_from_memory(&webp).unwrap().to_rgba8(), image);
+    }
+}
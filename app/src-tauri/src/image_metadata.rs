@@ -0,0 +1,179 @@
+// Image metadata stripping
+// Removes EXIF (camera, GPS location), XMP and text metadata from images before
+// they leave the machine. JPEG and PNG are rewritten segment by segment without
+// re-encoding the pixels; rotated JPEGs and other formats are decoded upright
+// and re-encoded, since dropping the orientation tag would turn them sideways.
+
+use image::metadata::Orientation;
+use image::DynamicImage;
+
+use crate::error::{AppError, AppResult};
+use crate::image_decode;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Quality used when a rotated JPEG has to be re-encoded
+const JPEG_QUALITY: u8 = 92;
+
+/// PNG chunks carrying metadata
+const PNG_METADATA_CHUNKS: &[&[u8; 4]] = &[b"eXIf", b"tEXt", b"iTXt", b"zTXt", b"tIME"];
+
+/// JPEG without APPn segments other than JFIF (APP0), the ICC profile (APP2)
+/// and Adobe (APP14, needed for the color transform), and without comments.
+/// None if it isn't a well-formed JPEG.
+fn strip_jpeg(bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    let mut out = bytes[..2].to_vec();
+    let mut i = 2;
+    while i + 4 <= bytes.len() {
+        if bytes[i] != 0xFF {
+            return None;
+        }
+        let marker = bytes[i + 1];
+        // Fill bytes before a marker
+        if marker == 0xFF {
+            i += 1;
+            continue;
+        }
+        // Start of scan: the rest is entropy-coded data
+        if marker == 0xDA {
+            out.extend_from_slice(&bytes[i..]);
+            return Some(out);
+        }
+        let length = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+        let end = i + 2 + length;
+        if length < 2 || end > bytes.len() {
+            return None;
+        }
+        let metadata = matches!(marker, 0xE1 | 0xE3..=0xED | 0xEF | 0xFE);
+        if !metadata {
+            out.extend_from_slice(&bytes[i..end]);
+        }
+        i = end;
+    }
+    None
+}
+
+/// PNG without metadata chunks. None if it isn't a well-formed PNG.
+fn strip_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    let mut out = PNG_SIGNATURE.to_vec();
+    let mut i = PNG_SIGNATURE.len();
+    while i + 12 <= bytes.len() {
+        let length = u32::from_be_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]) as usize;
+        // Length, type, data, CRC
+        let end = i.checked_add(12 + length).filter(|&end| end <= bytes.len())?;
+        let chunk_type = &bytes[i + 4..i + 8];
+        if !PNG_METADATA_CHUNKS.iter().any(|t| &t[..] == chunk_type) {
+            out.extend_from_slice(&bytes[i..end]);
+        }
+        if chunk_type == b"IEND" {
+            return Some(out);
+        }
+        i = end;
+    }
+    None
+}
+
+fn encode(image: DynamicImage, format: image::ImageFormat) -> AppResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let result = match format {
+        image::ImageFormat::Jpeg => image.to_rgb8().write_with_encoder(
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY),
+        ),
+        _ => image.write_to(&mut std::io::Cursor::new(&mut bytes), format),
+    };
+    result.map_err(|e| AppError::Other(format!("Failed to encode image: {}", e)))?;
+    Ok(bytes)
+}
+
+/// The image without EXIF/GPS, XMP or text metadata, pixels unchanged where
+/// possible
+pub fn strip_metadata(bytes: &[u8]) -> AppResult<Vec<u8>> {
+    let rotated = image_decode::exif_orientation(bytes) != Orientation::NoTransforms;
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        if !rotated {
+            if let Some(stripped) = strip_jpeg(bytes) {
+                return Ok(stripped);
+            }
+        }
+        return encode(image_decode::decode_image_bytes(bytes)?, image::ImageFormat::Jpeg);
+    }
+    if !rotated {
+        if let Some(stripped) = strip_png(bytes) {
+            return Ok(stripped);
+        }
+    }
+    // Other formats: the image crate's encoders don't write metadata
+    encode(image_decode::decode_image_bytes(bytes)?, image::ImageFormat::Png)
+}
+
+/// `strip_metadata` for base64 images (with or without a data URL header)
+pub fn strip_base64(base64_image: &str) -> AppResult<String> {
+    use base64::Engine;
+
+    let bytes = crate::ocr::decode_base64_image(base64_image)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(strip_metadata(&bytes)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0xFF, marker];
+        bytes.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = (data.len() as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(chunk_type);
+        bytes.extend_from_slice(data);
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn test_strip_jpeg_drops_exif_and_comments() {
+        let jfif = segment(0xE0, b"JFIF\0");
+        let quant = segment(0xDB, &[0; 4]);
+        let scan = [0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9];
+        let jpeg = [
+            vec![0xFF, 0xD8],
+            jfif.clone(),
+            segment(0xE1, b"Exif\0\0GPS..."),
+            segment(0xFE, b"comment"),
+            quant.clone(),
+            scan.to_vec(),
+        ]
+        .concat();
+        let expected = [vec![0xFF, 0xD8], jfif, quant, scan.to_vec()].concat();
+        assert_eq!(strip_jpeg(&jpeg), Some(expected));
+        assert_eq!(strip_jpeg(&[0xFF, 0xD8, 0xFF, 0xE1, 0xFF, 0xFF]), None);
+    }
+
+    #[test]
+    fn test_strip_png_drops_text_chunks() {
+        let ihdr = chunk(b"IHDR", &[0; 13]);
+        let idat = chunk(b"IDAT", &[1, 2, 3]);
+        let iend = chunk(b"IEND", &[]);
+        let png = [
+            PNG_SIGNATURE.to_vec(),
+            ihdr.clone(),
+            chunk(b"eXIf", b"MM\0*"),
+            chunk(b"tEXt", b"Author\0me"),
+            idat.clone(),
+            iend.clone(),
+        ]
+        .concat();
+        let expected = [PNG_SIGNATURE.to_vec(), ihdr, idat, iend].concat();
+        assert_eq!(strip_png(&png), Some(expected));
+        assert_eq!(strip_png(b"not a png"), None);
+    }
+}
@@ -0,0 +1,376 @@
+// Local automation channel
+// A Unix domain socket (macOS/Linux) or named pipe (Windows) accepting one JSON
+// command per line, so window managers, AutoHotkey scripts and Stream Deck plugins
+// can drive the app without HTTP. The socket lives in a directory only the user
+// can enter ($XDG_RUNTIME_DIR/screen-inu, or <app data>/ipc) and is itself 0600,
+// so other accounts on the machine can't drive captures. Each request gets one
+// JSON response line:
+//
+//   -> {"action":"capture_region","x":0,"y":0,"width":400,"height":200,"langs":"eng"}
+//   <- {"ok":true,"result":"recognized text"}
+//
+// `find_text`, `click_text` and `find_image` locate text and images on screen
+// for test scripts.
+
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+use interprocess::local_socket::GenericFilePath;
+#[cfg(windows)]
+use interprocess::local_socket::GenericNamespaced;
+use interprocess::local_socket::{prelude::*, Listener, ListenerOptions, Stream};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::capture::CaptureBackend;
+use crate::error::{AppError, AppResult};
+
+/// Socket / pipe name, see `get_ipc_endpoint` for the resolved path
+pub const IPC_NAME: &str = "screen-inu.sock";
+
+/// Automation channel settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct IpcConfig {
+    /// Start the local socket on launch
+    pub enabled: bool,
+}
+
+/// Commands accepted over the channel
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum IpcCommand {
+    Ping,
+    /// Show and focus the main window
+    Show,
+    /// Start the interactive region selection, same as the tray "Capture" item
+    Capture,
+    /// Capture the primary monitor and return the OCR text
+    CaptureFullScreen { langs: Option<String> },
+    /// Capture a region and return the OCR text
+    CaptureRegion {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        langs: Option<String>,
+    },
+    /// Translate text with an offline model
+    Translate {
+        text: String,
+        source_lang: String,
+        target_lang: String,
+    },
+    /// Screen positions of text matching a substring (or a regex)
+    FindText {
+        pattern: String,
+        #[serde(default)]
+        regex: bool,
+        langs: Option<String>,
+        #[serde(default)]
+        monitor_index: usize,
+    },
+    /// Click the `occurrence`th match of the text
+    ClickText {
+        pattern: String,
+        #[serde(default)]
+        regex: bool,
+        langs: Option<String>,
+        #[serde(default)]
+        monitor_index: usize,
+        #[serde(default)]
+        occurrence: usize,
+    },
+    /// Screen positions of a template image (base64 PNG)
+    FindImage {
+        template_png: String,
+        threshold: Option<f32>,
+        #[serde(default)]
+        monitor_index: usize,
+        max_results: Option<usize>,
+    },
+}
+
+fn encode_png(image: image::RgbaImage) -> AppResult<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Execute a command and return its JSON result
+pub fn dispatch(app: &AppHandle, command: IpcCommand) -> AppResult<serde_json::Value> {
+    match command {
+        IpcCommand::Ping => Ok(serde_json::json!("pong")),
+        IpcCommand::Show => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            Ok(serde_json::Value::Null)
+        }
+        IpcCommand::Capture => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.emit("tray-capture", ());
+            }
+            Ok(serde_json::Value::Null)
+        }
+        IpcCommand::CaptureFullScreen { langs } => {
+            crate::permissions::ensure_capture_permission()?;
+            let image = crate::capture::XcapBackend::new(0).capture_frame()?;
+            let text = crate::ocr::run_ocr(
+                &encode_png(image)?,
+                &crate::ocr::OcrOptions { langs, ..Default::default() }.with_reocr_config(),
+            )?;
+            Ok(serde_json::json!(text))
+        }
+        IpcCommand::CaptureRegion {
+            x,
+            y,
+            width,
+            height,
+            langs,
+        } => {
+            crate::permissions::ensure_capture_permission()?;
+            let image = crate::capture::XcapBackend::new(0).capture_region(x, y, width, height)?;
+            let text = crate::ocr::run_ocr(
+                &encode_png(image)?,
+                &crate::ocr::OcrOptions { langs, ..Default::default() }.with_reocr_config(),
+            )?;
+            Ok(serde_json::json!(text))
+        }
+        IpcCommand::Translate {
+            text,
+            source_lang,
+            target_lang,
+        } => {
+            let translated = tauri::async_runtime::block_on(crate::translator::translate_offline(
+                text,
+                source_lang,
+                target_lang,
+                None,
+                None,
+                None,
+            ))?;
+            Ok(serde_json::json!(translated.text))
+        }
+        IpcCommand::FindText {
+            pattern,
+            regex,
+            langs,
+            monitor_index,
+        } => {
+            let matches = crate::screen_find::find_text(&pattern, regex, langs, monitor_index)?;
+            Ok(serde_json::json!(matches))
+        }
+        IpcCommand::ClickText {
+            pattern,
+            regex,
+            langs,
+            monitor_index,
+            occurrence,
+        } => {
+            let clicked = crate::screen_find::click_text(&pattern, regex, langs, monitor_index, occurrence)?;
+            Ok(serde_json::json!(clicked))
+        }
+        IpcCommand::FindImage {
+            template_png,
+            threshold,
+            monitor_index,
+            max_results,
+        } => {
+            let matches = crate::screen_find::find_image_on_screen(template_png, threshold, Some(monitor_index), max_results)?;
+            Ok(serde_json::json!(matches))
+        }
+    }
+}
+
+fn handle_connection(app: &AppHandle, conn: Stream) {
+    let mut reader = BufReader::new(conn);
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<IpcCommand>(&line) {
+            Ok(command) => match dispatch(app, command) {
+                Ok(result) => serde_json::json!({ "ok": true, "result": result }),
+                Err(e) => serde_json::json!({ "ok": false, "error": e }),
+            },
+            Err(e) => serde_json::json!({
+                "ok": false,
+                "error": AppError::InvalidInput(format!("Invalid command: {}", e)),
+            }),
+        };
+
+        let mut out = response.to_string();
+        out.push('\n');
+        if reader.get_mut().write_all(out.as_bytes()).is_err() {
+            break;
+        }
+    }
+}
+
+/// Directory of the socket: the per-user runtime dir when there is one, the
+/// app data dir otherwise
+#[cfg(unix)]
+fn socket_dir_for(runtime_dir: Option<PathBuf>, data_dir: impl FnOnce() -> AppResult<PathBuf>) -> AppResult<PathBuf> {
+    match runtime_dir.filter(|dir| dir.is_absolute()) {
+        Some(dir) => Ok(dir.join("screen-inu")),
+        None => Ok(data_dir()?.join("ipc")),
+    }
+}
+
+#[cfg(unix)]
+fn socket_path() -> AppResult<PathBuf> {
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    Ok(socket_dir_for(runtime_dir, crate::config::get_app_data_dir)?.join(IPC_NAME))
+}
+
+/// Listen on `path`, in a directory only the user can enter. A socket left
+/// behind by a crashed instance is replaced.
+#[cfg(unix)]
+fn bind(path: &Path) -> AppResult<Listener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let dir = path
+        .parent()
+        .ok_or_else(|| AppError::Other(format!("Invalid IPC path: {}", path.display())))?;
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    // An existing directory may have been created with looser permissions
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+
+    let name = path
+        .to_fs_name::<GenericFilePath>()
+        .map_err(|e| AppError::Other(format!("Invalid IPC name: {}", e)))?;
+    let listener = ListenerOptions::new()
+        .name(name)
+        .try_overwrite(true)
+        .create_sync()
+        .map_err(|e| AppError::Other(format!("Failed to open IPC channel: {}", e)))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// The named pipe's default DACL only lets the user (and administrators)
+/// write to it
+#[cfg(windows)]
+fn bind_pipe() -> AppResult<Listener> {
+    let name = IPC_NAME
+        .to_ns_name::<GenericNamespaced>()
+        .map_err(|e| AppError::Other(format!("Invalid IPC name: {}", e)))?;
+    ListenerOptions::new()
+        .name(name)
+        .create_sync()
+        .map_err(|e| AppError::Other(format!("Failed to open IPC channel: {}", e)))
+}
+
+/// Start listening on the local socket in a background thread
+pub fn start_server(app: AppHandle) -> AppResult<()> {
+    #[cfg(unix)]
+    let listener = bind(&socket_path()?)?;
+    #[cfg(windows)]
+    let listener = bind_pipe()?;
+
+    std::thread::spawn(move || {
+        for conn in listener.incoming() {
+            match conn {
+                Ok(conn) => {
+                    let app = app.clone();
+                    std::thread::spawn(move || handle_connection(&app, conn));
+                }
+                Err(e) => tracing::warn!("IPC connection failed: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Start the server if enabled in the config
+pub fn init(app: &AppHandle) {
+    let enabled = crate::config::get().map(|c| c.ipc.enabled).unwrap_or(false);
+    if enabled {
+        if let Err(e) = start_server(app.clone()) {
+            tracing::warn!("IPC server not started: {}", e);
+        }
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Path of the automation socket / pipe for scripts
+#[tauri::command]
+pub fn get_ipc_endpoint() -> AppResult<String> {
+    #[cfg(unix)]
+    {
+        Ok(socket_path()?.display().to_string())
+    }
+    #[cfg(windows)]
+    {
+        Ok(format!(r"\\.\pipe\{}", IPC_NAME))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    #[test]
+    fn test_socket_dir_for() {
+        let data_dir = || Ok(PathBuf::from("/home/u/.local/share/screen-inu"));
+        assert_eq!(
+            socket_dir_for(Some(PathBuf::from("/run/user/1000")), data_dir).unwrap(),
+            Path::new("/run/user/1000/screen-inu")
+        );
+        // A relative runtime dir is ignored, as the XDG spec asks
+        assert_eq!(
+            socket_dir_for(Some(PathBuf::from("run")), data_dir).unwrap(),
+            Path::new("/home/u/.local/share/screen-inu/ipc")
+        );
+        assert_eq!(socket_dir_for(None, data_dir).unwrap(), Path::new("/home/u/.local/share/screen-inu/ipc"));
+    }
+
+    #[test]
+    fn test_bind_is_private_and_replaces_stale_socket() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("ipc");
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let path = dir.join(IPC_NAME);
+
+        let listener = bind(&path).unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&path), 0o600);
+
+        let name = path.as_path().to_fs_name::<GenericFilePath>().unwrap();
+        let mut client = Stream::connect(name).unwrap();
+        let mut conn = listener.accept().unwrap();
+        client.write_all(b"ping\n").unwrap();
+        let mut line = String::new();
+        BufReader::new(&mut conn).read_line(&mut line).unwrap();
+        assert_eq!(line, "ping\n");
+
+        // A socket file left by a crashed instance doesn't block the next one
+        drop((client, conn, listener));
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        bind(&path).unwrap();
+    }
+}
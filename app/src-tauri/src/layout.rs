@@ -0,0 +1,275 @@
+// Layout analysis
+// Groups OCR lines into paragraphs/blocks from their bounding boxes and puts
+// the blocks in reading order, so multi-column pages and chat bubbles aren't
+// interleaved line by line.
+//
+// Lines that span a wide horizontal gap (two columns read as one line) are
+// split first. Lines then join the block above them when the vertical gap is
+// small and they overlap or align horizontally. Blocks are ordered by
+// recursive XY-cut: split into horizontal bands at empty rows, bands into
+// columns at empty columns, read top to bottom and left to right (right to
+// left on pages of mostly RTL text).
+
+use crate::error::AppResult;
+use crate::ocr::{self, OcrOptions};
+use crate::ocr_result::{BoundingBox, OcrLine, OcrResult, OcrTextDirection, OcrWord};
+
+/// Words further apart than this many line heights belong to different columns
+const COLUMN_GAP: f32 = 2.0;
+
+/// Lines further apart vertically than this many line heights start a new block
+const PARAGRAPH_GAP: f32 = 0.8;
+
+/// A paragraph or block of lines
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextBlock {
+    pub text: String,
+    pub bbox: BoundingBox,
+    pub lines: Vec<OcrLine>,
+    /// Majority direction of the lines
+    pub text_direction: OcrTextDirection,
+}
+
+/// Blocks in reading order
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutResult {
+    /// Block texts separated by blank lines
+    pub text: String,
+    pub blocks: Vec<TextBlock>,
+    pub engine: String,
+}
+
+/// RTL when most of the lines are
+fn majority_direction<'a>(lines: impl IntoIterator<Item = &'a OcrLine>) -> OcrTextDirection {
+    let (rtl, total) = lines.into_iter().fold((0, 0), |(rtl, total), line| {
+        (rtl + (line.text_direction == OcrTextDirection::Rtl) as usize, total + 1)
+    });
+    if rtl * 2 > total {
+        OcrTextDirection::Rtl
+    } else {
+        OcrTextDirection::Ltr
+    }
+}
+
+fn median_line_height(lines: &[OcrLine]) -> f32 {
+    let mut heights: Vec<u32> = lines.iter().map(|l| l.bbox.height).filter(|h| *h > 0).collect();
+    heights.sort_unstable();
+    heights.get(heights.len() / 2).copied().unwrap_or(1) as f32
+}
+
+/// Split a line wherever the gap between words exceeds `max_gap`
+fn split_line(line: OcrLine, max_gap: f32) -> Vec<OcrLine> {
+    let mut words = line.words;
+    words.sort_by_key(|w| w.bbox.x);
+
+    let mut fragments: Vec<Vec<OcrWord>> = Vec::new();
+    let mut right_edge = 0;
+    for word in words {
+        let gap = word.bbox.x as f32 - right_edge as f32;
+        right_edge = right_edge.max(word.bbox.x + word.bbox.width);
+        match fragments.last_mut() {
+            Some(fragment) if gap <= max_gap => fragment.push(word),
+            _ => fragments.push(vec![word]),
+        }
+    }
+    fragments.into_iter().map(OcrLine::from_words).collect()
+}
+
+fn horizontal_overlap(a: &BoundingBox, b: &BoundingBox) -> u32 {
+    (a.x + a.width).min(b.x + b.width).saturating_sub(a.x.max(b.x))
+}
+
+/// Whether `line` continues the paragraph ending with `last`
+fn continues(last: &OcrLine, line: &OcrLine, line_height: f32) -> bool {
+    let last_bottom = last.bbox.y + last.bbox.height;
+    // Must be below the previous line, not beside it
+    if line.bbox.y < last.bbox.y + last.bbox.height / 2 {
+        return false;
+    }
+    let gap = line.bbox.y.saturating_sub(last_bottom) as f32;
+    let overlap = horizontal_overlap(&last.bbox, &line.bbox) as f32;
+    let narrower = last.bbox.width.min(line.bbox.width).max(1) as f32;
+    let aligned = (last.bbox.x as f32 - line.bbox.x as f32).abs() <= line_height;
+    gap <= PARAGRAPH_GAP * line_height && (overlap >= narrower / 2.0 || aligned)
+}
+
+fn group_blocks(mut lines: Vec<OcrLine>, line_height: f32) -> Vec<Vec<OcrLine>> {
+    lines.sort_by_key(|l| (l.bbox.y, l.bbox.x));
+    let mut blocks: Vec<Vec<OcrLine>> = Vec::new();
+    for line in lines {
+        // Closest block whose last line this one continues
+        let target = blocks
+            .iter_mut()
+            .filter(|b| b.last().is_some_and(|last| continues(last, &line, line_height)))
+            .min_by_key(|b| b.last().map_or(u32::MAX, |last| line.bbox.y.saturating_sub(last.bbox.y)));
+        match target {
+            Some(block) => block.push(line),
+            None => blocks.push(vec![line]),
+        }
+    }
+    blocks
+}
+
+fn block_bbox(lines: &[OcrLine]) -> BoundingBox {
+    lines
+        .iter()
+        .skip(1)
+        .fold(lines[0].bbox, |bbox, line| bbox.union(&line.bbox))
+}
+
+/// Split items into groups separated by empty space along one axis
+fn split_at_gaps(mut items: Vec<TextBlock>, span: impl Fn(&BoundingBox) -> (u32, u32)) -> Vec<Vec<TextBlock>> {
+    items.sort_by_key(|b| span(&b.bbox).0);
+    let mut groups: Vec<Vec<TextBlock>> = Vec::new();
+    let mut group_end = 0;
+    for item in items {
+        let (start, end) = span(&item.bbox);
+        match groups.last_mut() {
+            Some(group) if start < group_end => {
+                group_end = group_end.max(end);
+                group.push(item);
+            }
+            _ => {
+                group_end = end;
+                groups.push(vec![item]);
+            }
+        }
+    }
+    groups
+}
+
+/// Recursive XY-cut into reading order
+fn xy_cut(blocks: Vec<TextBlock>, rtl: bool, ordered: &mut Vec<TextBlock>) {
+    if blocks.len() <= 1 {
+        ordered.extend(blocks);
+        return;
+    }
+    let bands = split_at_gaps(blocks, |b| (b.y, b.y + b.height));
+    if bands.len() > 1 {
+        bands.into_iter().for_each(|band| xy_cut(band, rtl, ordered));
+        return;
+    }
+    let blocks = bands.into_iter().flatten().collect();
+    let mut columns = split_at_gaps(blocks, |b| (b.x, b.x + b.width));
+    if columns.len() > 1 {
+        if rtl {
+            columns.reverse();
+        }
+        columns.into_iter().for_each(|column| xy_cut(column, rtl, ordered));
+        return;
+    }
+    let mut blocks: Vec<TextBlock> = columns.into_iter().flatten().collect();
+    blocks.sort_by_key(|b| (b.bbox.y, b.bbox.x));
+    ordered.extend(blocks);
+}
+
+/// Group the lines of an OCR result into reading-ordered blocks
+pub fn analyze(result: &OcrResult) -> LayoutResult {
+    let line_height = median_line_height(&result.lines);
+    let lines: Vec<OcrLine> = result
+        .lines
+        .iter()
+        .cloned()
+        .flat_map(|line| split_line(line, COLUMN_GAP * line_height))
+        .collect();
+
+    let blocks: Vec<TextBlock> = group_blocks(lines, line_height)
+        .into_iter()
+        .map(|lines| TextBlock {
+            text: lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n"),
+            bbox: block_bbox(&lines),
+            text_direction: majority_direction(&lines),
+            lines,
+        })
+        .collect();
+
+    let rtl = majority_direction(blocks.iter().flat_map(|b| &b.lines)) == OcrTextDirection::Rtl;
+    let mut ordered = Vec::with_capacity(blocks.len());
+    xy_cut(blocks, rtl, &mut ordered);
+    LayoutResult {
+        text: ordered.iter().map(|b| b.text.as_str()).collect::<Vec<_>>().join("\n\n"),
+        blocks: ordered,
+        engine: result.engine.clone(),
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// OCR an image and return its text grouped into reading-ordered blocks
+#[tauri::command]
+pub fn perform_ocr_layout(
+    base64_image: &str,
+    langs: Option<String>,
+    engine: Option<String>,
+) -> AppResult<LayoutResult> {
+    let bytes = ocr::decode_base64_image(base64_image)?;
+    let options = OcrOptions {
+        langs,
+        engine,
+        ..Default::default()
+    };
+    let mut layout = analyze(&ocr::run_ocr_detailed(&bytes, &options)?);
+    crate::plugins::apply_to_layout(crate::plugins::PluginStage::Ocr, &mut layout);
+    Ok(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, x: u32, y: u32, line: u32) -> OcrWord {
+        let width = 10 * text.chars().count() as u32;
+        OcrWord::new(text.to_string(), 90.0, BoundingBox { x, y, width, height: 12 }, 1, 1, line)
+    }
+
+    #[test]
+    fn test_two_columns_with_header() {
+        // The engine read each row straight across both columns
+        let result = OcrResult::from_words(
+            vec![
+                word("Title", 0, 0, 1),
+                word("left", 0, 30, 2),
+                word("a", 50, 30, 2),
+                word("right", 150, 30, 2),
+                word("a", 210, 30, 2),
+                word("left", 0, 50, 3),
+                word("b", 50, 50, 3),
+                word("right", 150, 50, 3),
+                word("b", 210, 50, 3),
+            ],
+            "tesseract",
+        );
+        let layout = analyze(&result);
+        assert_eq!(layout.blocks.len(), 3);
+        assert_eq!(layout.text, "Title\n\nleft a\nleft b\n\nright a\nright b");
+    }
+
+    #[test]
+    fn test_chat_bubbles_in_order() {
+        let result = OcrResult::from_words(
+            vec![
+                word("hi", 0, 0, 1),
+                word("there", 0, 16, 2),
+                word("hello!", 200, 50, 3),
+                word("bye", 0, 90, 4),
+            ],
+            "tesseract",
+        );
+        assert_eq!(analyze(&result).text, "hi\nthere\n\nhello!\n\nbye");
+    }
+
+    #[test]
+    fn test_rtl_columns_right_to_left() {
+        let result = OcrResult::from_words(
+            vec![word("שמאל", 0, 0, 1), word("ימין", 150, 0, 2)],
+            "tesseract",
+        );
+        let layout = analyze(&result);
+        assert_eq!(layout.text, "ימין\n\nשמאל");
+        assert_eq!(layout.blocks[0].text_direction, OcrTextDirection::Rtl);
+    }
+}
@@ -1,72 +1,176 @@
 use base64::Engine;
-use image::ImageFormat;
-use std::io::Cursor;
+use capture::CaptureBackend;
+use error::{AppError, AppResult};
 use tauri::Manager;
-use xcap::Monitor;
 
+/// Capture the primary monitor as base64, PNG unless `options` asks for
+/// another format, quality or size
 #[tauri::command]
-fn capture_full_screen() -> Result<String, String> {
-    let monitors = Monitor::all().map_err(|e| e.to_string())?;
-    let monitor = monitors.first().ok_or("No monitor found")?;
-    let image = monitor.capture_image().map_err(|e| e.to_string())?;
-
-    let mut bytes: Vec<u8> = Vec::new();
-    image::DynamicImage::ImageRgba8(image)
-        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
-        .map_err(|e| e.to_string())?;
-
-    let base64_str = base64::engine::general_purpose::STANDARD.encode(&bytes);
-    Ok(base64_str)
+#[tracing::instrument(skip_all, err)]
+async fn capture_full_screen(options: Option<image_encode::OutputOptions>) -> AppResult<String> {
+    let run = events::start(events::Pipeline::Capture, "capture_full_screen", None);
+    let result = image_encode::spawn(move || {
+        permissions::ensure_capture_permission()?;
+        let image = capture::XcapBackend::new(0).capture_frame()?;
+        let bytes = image_encode::encode(image, &options.unwrap_or_default())?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(&bytes))
+    })
+    .await;
+    run.finish(result)
 }
 
 #[tauri::command]
-fn capture_region(x: i32, y: i32, width: u32, height: u32) -> Result<String, String> {
-    let monitors = Monitor::all().map_err(|e| e.to_string())?;
-    let monitor = monitors.first().ok_or("No monitor found")?;
-    let image = monitor.capture_image().map_err(|e| e.to_string())?;
-
-    let sub_image = image::imageops::crop_imm(&image, x as u32, y as u32, width, height);
-
-    let mut bytes: Vec<u8> = Vec::new();
-    image::DynamicImage::ImageRgba8(sub_image.to_image())
-        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
-        .map_err(|e| e.to_string())?;
-
-    let base64_str = base64::engine::general_purpose::STANDARD.encode(&bytes);
-    Ok(base64_str)
+#[tracing::instrument(err)]
+async fn capture_region(x: i32, y: i32, width: u32, height: u32) -> AppResult<String> {
+    let run = events::start(events::Pipeline::Capture, "capture_region", None);
+    let result = image_encode::spawn(move || {
+        permissions::ensure_capture_permission()?;
+        let sub_image = capture::XcapBackend::new(0).capture_region(x as u32, y as u32, width, height)?;
+        let bytes = image_encode::encode(sub_image, &Default::default())?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(&bytes))
+    })
+    .await;
+    run.finish(result)
 }
 
+mod actions;
+mod alignment;
+mod autostart;
+mod business_card;
+mod calendar;
+mod capture;
+mod capture_buffer;
+mod capture_protocol;
+mod chat;
+mod cloud_notes;
+mod code_mode;
+mod collections;
+mod color;
+mod config;
+mod controllers;
+mod conversion;
+mod crash_report;
+mod data_dir;
+mod decoding;
+mod diagnostics;
+mod document_scan;
+mod downloads;
+mod email;
+mod encryption;
+mod entities;
+mod error;
+mod events;
+mod file_ocr;
+mod folder_sync;
+mod frame_diff;
+mod glossary;
+mod history;
+mod history_edits;
+mod history_import;
+mod hover_ocr;
+mod i18n;
+mod image_decode;
+mod image_encode;
+mod image_metadata;
+mod ipc;
+mod layout;
+mod lifecycle;
+mod live_ocr;
+mod llm;
+mod logging;
+mod measure;
+mod memory;
+mod mobile;
+mod notes;
+mod notifications;
 mod ocr;
+mod ocr_formats;
+mod ocr_jobs;
+mod ocr_result;
+mod operations;
+mod overlay;
+mod pairing;
 mod model_manager;
+mod perf;
+mod permissions;
+mod pin;
+mod plugins;
+mod portable;
+mod print;
+mod push_to_ocr;
+mod region_suggest;
+mod recent_results;
+mod reocr;
+mod romanize;
+mod sandbox;
+mod save_targets;
+mod scheduler;
+mod screen_find;
+mod searchable_pdf;
+mod secrets;
+mod session;
+mod share;
+mod shortcuts;
+mod sidecar;
+mod storage;
+mod summarizer;
+#[cfg(feature = "tesseract-ffi")]
+mod tesseract_pool;
+mod tesseract_manager;
+mod templates;
+mod text_grab;
+mod translation_worker;
 mod translator;
+mod watch;
+mod webhooks;
+mod white_balance;
+
+/// OCR options sent by the frontend, with the configured re-OCR settings.
+/// Every OCR command builds its options through here.
+fn command_ocr_options(options: Option<ocr::OcrOptions>) -> ocr::OcrOptions {
+    options.unwrap_or_default().with_reocr_config()
+}
 
-#[tauri::command]
-fn perform_ocr(base64_image: &str, langs: Option<String>, engine: Option<String>) -> Result<String, String> {
-    // Remove header if present
-    let base64_data = base64_image.split(",").last().unwrap_or(base64_image);
-
-    let bytes = base64::engine::general_purpose::STANDARD
-        .decode(base64_data)
-        .map_err(|e| e.to_string())?;
+/// Perform OCR. With an `op_id` it can be stopped through `cancel_operation`.
+/// `format` selects plain text (default), hOCR, ALTO XML or TSV output.
+#[tauri::command(async)]
+fn perform_ocr(
+    base64_image: String,
+    options: Option<ocr::OcrOptions>,
+    op_id: Option<String>,
+    format: Option<ocr_formats::OcrFormat>,
+) -> AppResult<String> {
+    events::track(events::Pipeline::Ocr, "perform_ocr", op_id.clone(), || {
+        let operation = operations::start(op_id)?;
+        let bytes = ocr::decode_base64_image(&base64_image)?;
+        let options = command_ocr_options(options);
+        let format = format.unwrap_or_default();
+        if format == ocr_formats::OcrFormat::Plain {
+            let text = operation.scope(|| ocr::run_ocr(&bytes, &options))?;
+            recent_results::push(recent_results::RecentKind::Ocr, &text);
+            return Ok(text);
+        }
+        let result = operation.scope(|| ocr::run_ocr_detailed(&bytes, &options))?;
+        recent_results::push(recent_results::RecentKind::Ocr, &result.text);
+        // Coordinates are in the upright image
+        let image = image_decode::decode_image_bytes(&bytes)?;
+        Ok(ocr_formats::render(&result, format, image.width(), image.height()))
+    })
+}
 
-    let lang = langs.unwrap_or("eng".to_string());
-    
-    // Parse engine selection
-    let ocr_engine = match engine.as_deref() {
-        Some("tesseract") => ocr::OcrEngine::Tesseract,
-        #[cfg(windows)]
-        Some("windows") => ocr::OcrEngine::WindowsOcr,
-        #[cfg(target_os = "macos")]
-        Some("apple") => ocr::OcrEngine::AppleVision,
-        _ => ocr::OcrEngine::Auto,
-    };
-    
-    // Handle auto-detection
-    if lang == "auto" {
-        ocr::perform_auto_ocr(&bytes, ocr_engine)
-    } else {
-        ocr::perform_ocr_with_engine(&bytes, &lang, ocr_engine)
-    }
+/// Perform OCR and return per-line/word confidence and bounding boxes
+#[tauri::command(async)]
+fn perform_ocr_detailed(
+    base64_image: String,
+    options: Option<ocr::OcrOptions>,
+    op_id: Option<String>,
+) -> AppResult<ocr_result::OcrResult> {
+    events::track(events::Pipeline::Ocr, "perform_ocr_detailed", op_id.clone(), || {
+        let operation = operations::start(op_id)?;
+        let bytes = ocr::decode_base64_image(&base64_image)?;
+        let options = command_ocr_options(options);
+        operation.scope(|| ocr::run_ocr_detailed(&bytes, &options))
+    })
 }
 
 /// Result of a single image in a batch OCR operation
@@ -79,45 +183,25 @@ struct BatchOcrResult {
 
 /// Perform OCR on multiple images in parallel using Rayon
 #[tauri::command]
-fn perform_batch_ocr(
-    images: Vec<String>,
-    langs: Option<String>,
-    engine: Option<String>,
-) -> Vec<BatchOcrResult> {
+fn perform_batch_ocr(images: Vec<String>, options: Option<ocr::OcrOptions>) -> Vec<BatchOcrResult> {
     use rayon::prelude::*;
 
-    let lang = langs.unwrap_or_else(|| "eng".to_string());
-    let ocr_engine = match engine.as_deref() {
-        Some("tesseract") => ocr::OcrEngine::Tesseract,
-        #[cfg(windows)]
-        Some("windows") => ocr::OcrEngine::WindowsOcr,
-        #[cfg(target_os = "macos")]
-        Some("apple") => ocr::OcrEngine::AppleVision,
-        _ => ocr::OcrEngine::Auto,
-    };
+    let options = command_ocr_options(options);
 
     images
         .par_iter()
         .enumerate()
         .map(|(index, base64_image)| {
-            let base64_data = base64_image.split(',').last().unwrap_or(base64_image);
-            match base64::engine::general_purpose::STANDARD.decode(base64_data) {
-                Ok(bytes) => match ocr::perform_ocr_with_engine(&bytes, &lang, ocr_engine.clone()) {
-                    Ok(text) => BatchOcrResult {
-                        index,
-                        text: Some(text),
-                        error: None,
-                    },
-                    Err(e) => BatchOcrResult {
-                        index,
-                        text: None,
-                        error: Some(e),
-                    },
+            match ocr::decode_base64_image(base64_image).and_then(|bytes| ocr::run_ocr(&bytes, &options)) {
+                Ok(text) => BatchOcrResult {
+                    index,
+                    text: Some(text),
+                    error: None,
                 },
                 Err(e) => BatchOcrResult {
                     index,
                     text: None,
-                    error: Some(format!("Base64 decode error: {}", e)),
+                    error: Some(e.localized_message()),
                 },
             }
         })
@@ -129,14 +213,36 @@ fn perform_batch_ocr(
 fn get_ocr_engines() -> Vec<String> {
     ocr::get_available_engines()
         .iter()
-        .map(|e| match e {
-            ocr::OcrEngine::Tesseract => "tesseract".to_string(),
-            #[cfg(windows)]
-            ocr::OcrEngine::WindowsOcr => "windows".to_string(),
-            #[cfg(target_os = "macos")]
-            ocr::OcrEngine::AppleVision => "apple".to_string(),
-            ocr::OcrEngine::Auto => "auto".to_string(),
-        })
+        .map(|e| e.as_str().to_string())
+        .collect()
+}
+
+/// OCR languages installed in Windows (BCP-47 tags), empty on other platforms
+#[tauri::command]
+fn get_windows_ocr_languages() -> AppResult<Vec<String>> {
+    #[cfg(windows)]
+    return ocr::windows_available_languages();
+
+    #[cfg(not(windows))]
+    Ok(Vec::new())
+}
+
+/// Open the system page for installing Windows OCR language packs
+#[tauri::command]
+fn open_windows_language_settings() -> AppResult<()> {
+    #[cfg(windows)]
+    return ocr::open_windows_language_settings();
+
+    #[cfg(not(windows))]
+    Err(AppError::Unsupported("Windows OCR is only available on Windows".to_string()))
+}
+
+/// Get available capture backends for the current platform
+#[tauri::command]
+fn get_capture_backends() -> Vec<String> {
+    capture::get_available_backends()
+        .iter()
+        .map(|b| b.as_str().to_string())
         .collect()
 }
 
@@ -147,19 +253,19 @@ use once_cell::sync::Lazy;
 
 static TTS_INSTANCE: Lazy<Mutex<Option<tts::Tts>>> = Lazy::new(|| Mutex::new(None));
 
-fn get_or_init_tts() -> Result<std::sync::MutexGuard<'static, Option<tts::Tts>>, String> {
-    let mut guard = TTS_INSTANCE.lock().map_err(|e| e.to_string())?;
+fn get_or_init_tts() -> AppResult<std::sync::MutexGuard<'static, Option<tts::Tts>>> {
+    let mut guard = TTS_INSTANCE.lock()?;
     if guard.is_none() {
-        let tts = tts::Tts::default().map_err(|e| format!("Failed to init TTS: {}", e))?;
+        let tts = tts::Tts::default().map_err(|e| AppError::Tts(format!("Failed to init TTS: {}", e)))?;
         *guard = Some(tts);
     }
     Ok(guard)
 }
 
 #[tauri::command]
-fn speak_text(text: String, rate: Option<f32>, pitch: Option<f32>, volume: Option<f32>) -> Result<(), String> {
+fn speak_text(text: String, rate: Option<f32>, pitch: Option<f32>, volume: Option<f32>) -> AppResult<()> {
     let mut guard = get_or_init_tts()?;
-    let tts = guard.as_mut().ok_or("TTS not initialized")?;
+    let tts = guard.as_mut().ok_or_else(|| AppError::Tts("TTS not initialized".to_string()))?;
     
     // Set speech parameters if provided
     if let Some(r) = rate {
@@ -172,15 +278,15 @@ fn speak_text(text: String, rate: Option<f32>, pitch: Option<f32>, volume: Optio
         let _ = tts.set_volume(v);
     }
     
-    tts.speak(text, false).map_err(|e| format!("TTS speak error: {}", e))?;
+    tts.speak(text, false).map_err(|e| AppError::Tts(format!("TTS speak error: {}", e)))?;
     Ok(())
 }
 
 #[tauri::command]
-fn stop_speech() -> Result<(), String> {
+fn stop_speech() -> AppResult<()> {
     let mut guard = get_or_init_tts()?;
     if let Some(tts) = guard.as_mut() {
-        tts.stop().map_err(|e| format!("TTS stop error: {}", e))?;
+        tts.stop().map_err(|e| AppError::Tts(format!("TTS stop error: {}", e)))?;
     }
     Ok(())
 }
@@ -192,11 +298,11 @@ struct VoiceInfo {
 }
 
 #[tauri::command]
-fn get_tts_voices() -> Result<Vec<VoiceInfo>, String> {
+fn get_tts_voices() -> AppResult<Vec<VoiceInfo>> {
     let guard = get_or_init_tts()?;
-    let tts = guard.as_ref().ok_or("TTS not initialized")?;
+    let tts = guard.as_ref().ok_or_else(|| AppError::Tts("TTS not initialized".to_string()))?;
     
-    let voices = tts.voices().map_err(|e| format!("Failed to get voices: {}", e))?;
+    let voices = tts.voices().map_err(|e| AppError::Tts(format!("Failed to get voices: {}", e)))?;
     Ok(voices.into_iter().map(|v| VoiceInfo {
         id: v.id().to_string(),
         name: v.name().to_string(),
@@ -204,25 +310,18 @@ fn get_tts_voices() -> Result<Vec<VoiceInfo>, String> {
 }
 
 #[tauri::command]
-fn is_speaking() -> Result<bool, String> {
+fn is_speaking() -> AppResult<bool> {
     let guard = get_or_init_tts()?;
-    let tts = guard.as_ref().ok_or("TTS not initialized")?;
-    tts.is_speaking().map_err(|e| format!("TTS error: {}", e))
+    let tts = guard.as_ref().ok_or_else(|| AppError::Tts("TTS not initialized".to_string()))?;
+    tts.is_speaking().map_err(|e| AppError::Tts(format!("TTS error: {}", e)))
 }
 
 #[tauri::command]
-fn scan_qr(base64_image: &str) -> Result<Option<String>, String> {
-    // Remove header if present
-    let base64_data = base64_image.split(",").last().unwrap_or(base64_image);
-
-    let bytes = base64::engine::general_purpose::STANDARD
-        .decode(base64_data)
-        .map_err(|e| e.to_string())?;
+fn scan_qr(base64_image: &str) -> AppResult<Option<String>> {
+    let bytes = ocr::decode_base64_image(base64_image)?;
 
     // Load image
-    let img = image::load_from_memory(&bytes)
-        .map_err(|e| e.to_string())?
-        .to_luma8();
+    let img = image_decode::decode_image_bytes(&bytes)?.to_luma8();
 
     // Prepare image for rqrr
     let mut prepared = rqrr::PreparedImage::prepare(img);
@@ -245,36 +344,119 @@ fn greet(name: &str) -> String {
 
 // OCR Model Management Commands
 #[tauri::command]
-fn list_ocr_models() -> Result<Vec<model_manager::ModelInfo>, String> {
+fn list_ocr_models() -> AppResult<Vec<model_manager::ModelInfo>> {
     model_manager::list_available_models()
 }
 
+#[tauri::command(async)]
+fn download_ocr_model(
+    lang: String,
+    tier: Option<model_manager::TessdataTier>,
+    op_id: Option<String>,
+) -> AppResult<()> {
+    events::track(events::Pipeline::Download, "download_ocr_model", op_id.clone(), || {
+        operations::start(op_id)?.scope(|| model_manager::download_model(&lang, tier.unwrap_or_default()))
+    })
+}
+
 #[tauri::command]
-fn download_ocr_model(lang: String) -> Result<(), String> {
-    model_manager::download_model(&lang)
+fn delete_ocr_model(lang: String, tier: Option<model_manager::TessdataTier>) -> AppResult<()> {
+    model_manager::delete_model(&lang, tier.unwrap_or_default())?;
+    events::emit(events::MODELS_CHANGED_EVENT, ());
+    Ok(())
+}
+
+/// Download several OCR models in parallel, emitting `models://progress`
+#[tauri::command(async)]
+fn download_ocr_models(
+    app: tauri::AppHandle,
+    langs: Vec<String>,
+    tier: Option<model_manager::TessdataTier>,
+) -> Vec<model_manager::BulkResult> {
+    use tauri::Emitter;
+    let run = events::start(events::Pipeline::Download, "download_ocr_models", None);
+    let results = model_manager::download_models(&langs, tier.unwrap_or_default(), |progress| {
+        let _ = app.emit("models://progress", progress);
+    });
+    // Partial failures are reported per language in the results
+    let _ = run.finish(Ok(()));
+    results
+}
+
+#[tauri::command]
+fn delete_unused_ocr_models(keep_days: u32) -> AppResult<Vec<String>> {
+    model_manager::delete_unused_models(keep_days)
+}
+
+#[tauri::command]
+fn import_custom_traineddata(path: String, code: String, name: String) -> AppResult<()> {
+    model_manager::import_custom_traineddata(std::path::Path::new(&path), &code, &name)?;
+    events::emit(events::MODELS_CHANGED_EVENT, ());
+    Ok(())
 }
 
 #[tauri::command]
-fn delete_ocr_model(lang: String) -> Result<(), String> {
-    model_manager::delete_model(&lang)
+fn set_ocr_model_tier(lang: String, tier: model_manager::TessdataTier) -> AppResult<()> {
+    model_manager::set_active_tier(&lang, tier)
 }
 
 mod tray;
+mod tray_languages;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    portable::init();
+    // Before logging and the relocation pointer read the data directory
+    let legacy_migration = data_dir::migrate_legacy_default();
+    logging::init();
+    crash_report::init();
+    match legacy_migration {
+        Ok(0) => {}
+        Ok(moved) => tracing::info!("Moved {} files from the legacy data directory", moved),
+        Err(e) => tracing::warn!("Failed to move the legacy data directory: {}", e),
+    }
+
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_process::init())
-        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_dialog::init());
+
+    // The updater and global shortcuts are desktop-only
+    #[cfg(desktop)]
+    let builder = builder
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build());
+
+    builder
+        .plugin(mobile::init())
         .setup(|app| {
+            ipc::init(app.handle());
+            controllers::init(app.handle());
+            history::init(app.handle());
+            save_targets::init();
+            scheduler::init(app.handle());
+            watch::init(app.handle());
+            capture_buffer::init(app.handle());
+            shortcuts::init(app.handle());
+            push_to_ocr::init(app.handle());
+            events::init(app.handle());
+            lifecycle::init(app.handle());
+            llm::init();
+            share::init();
+            chat::init();
+            webhooks::init();
+
             #[cfg(desktop)]
             {
                 tray::create_tray(app.handle())?;
 
                 // Intercept window close to minimize to tray instead of quitting
                 let window = app.get_webview_window("main").unwrap();
+                if autostart::launched_minimized() {
+                    let _ = window.hide();
+                }
                 let window_clone = window.clone();
                 window.on_window_event(move |event| {
                     if let tauri::WindowEvent::CloseRequested { api, .. } = event {
@@ -286,20 +468,42 @@ pub fn run() {
             }
             Ok(())
         })
-        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
+        .register_uri_scheme_protocol(capture_protocol::CAPTURE_SCHEME, |_ctx, request| {
+            capture_protocol::handle(&request)
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             capture_full_screen,
+            capture_protocol::capture_full_screen_url,
+            capture_protocol::release_capture,
+            capture_buffer::get_buffered_capture,
+            capture_buffer::get_capture_buffer_config,
+            capture_buffer::set_capture_buffer_config,
+            file_ocr::perform_file_ocr,
+            pin::pin_capture,
+            pin::get_pin,
+            pin::list_pins,
+            pin::set_pin_zoom,
+            pin::set_pin_opacity,
+            pin::close_pin,
             capture_region,
             perform_ocr,
+            perform_ocr_detailed,
             perform_batch_ocr,
             scan_qr,
             get_ocr_engines,
+            get_capture_backends,
+            get_windows_ocr_languages,
+            open_windows_language_settings,
             list_ocr_models,
             download_ocr_model,
             delete_ocr_model,
+            set_ocr_model_tier,
+            import_custom_traineddata,
+            download_ocr_models,
+            delete_unused_ocr_models,
             speak_text,
             stop_speech,
             get_tts_voices,
@@ -308,10 +512,199 @@ pub fn run() {
             translator::list_translation_models,
             translator::get_translation_model_status,
             translator::download_translation_model,
-            translator::delete_translation_model
+            translator::delete_translation_model,
+            summarizer::summarize_text,
+            summarizer::summarize_for_history,
+            summarizer::list_summary_models,
+            summarizer::download_summary_model,
+            summarizer::delete_summary_model,
+            llm::refine_translation,
+            layout::perform_ocr_layout,
+            reocr::perform_ocr_best,
+            code_mode::perform_code_ocr,
+            color::pick_color,
+            color::sample_region_palette,
+            live_ocr::start_live_ocr,
+            live_ocr::stop_live_ocr,
+            live_ocr::is_live_ocr_running,
+            live_ocr::pause_live_ocr,
+            live_ocr::is_live_ocr_paused,
+            measure::measure_region,
+            measure::snap_region_to_edges,
+            overlay::open_text_overlay,
+            overlay::get_overlay_payload,
+            region_suggest::suggest_regions,
+            text_grab::grab_text_at_point,
+            scheduler::schedule_capture,
+            scheduler::list_scheduled_captures,
+            scheduler::remove_scheduled_capture,
+            scheduler::set_scheduled_capture_enabled,
+            watch::watch_region,
+            watch::list_region_watches,
+            watch::remove_region_watch,
+            watch::set_region_watch_enabled,
+            notifications::show_result_notification,
+            plugins::list_plugins,
+            plugins::set_plugin_chain,
+            plugins::install_plugin,
+            plugins::remove_plugin,
+            plugins::test_plugin,
+            glossary::add_glossary_term,
+            glossary::remove_glossary_term,
+            glossary::list_glossary_terms,
+            glossary::import_glossary_csv,
+            glossary::export_glossary_csv,
+            romanize::romanize,
+            permissions::check_capture_permission,
+            permissions::request_capture_permission,
+            ocr_jobs::ocr_submit,
+            ocr_jobs::ocr_cancel,
+            config::get_config,
+            config::set_config,
+            actions::run_post_ocr_actions,
+            share::optimize_for_share,
+            share::share_image,
+            share::share_text,
+            print::print_capture,
+            print::list_printers,
+            ipc::get_ipc_endpoint,
+            controllers::list_midi_ports,
+            controllers::restart_controllers,
+            history::sync_get_stats,
+            history::sync_backup,
+            history::sync_restore,
+            history::sync_list_backups,
+            history_import::sync_import_external,
+            diagnostics::run_diagnostics,
+            logging::get_recent_logs,
+            logging::open_log_folder,
+            crash_report::get_crash_report_config,
+            crash_report::set_crash_report_config,
+            crash_report::list_crash_reports,
+            crash_report::delete_crash_report,
+            crash_report::submit_crash_report,
+            perf::get_perf_metrics,
+            perf::reset_perf_metrics,
+            autostart::get_autostart,
+            autostart::set_autostart,
+            data_dir::get_data_dir,
+            data_dir::set_data_dir,
+            operations::cancel_operation,
+            memory::get_memory_usage,
+            memory::set_memory_budget,
+            i18n::set_locale,
+            tray_languages::set_active_languages,
+            shortcuts::get_shortcuts_config,
+            shortcuts::set_shortcuts_config,
+            shortcuts::check_shortcut,
+            push_to_ocr::get_push_to_ocr_config,
+            push_to_ocr::set_push_to_ocr_config,
+            push_to_ocr::get_push_to_ocr_update,
+            hover_ocr::set_hover_ocr,
+            hover_ocr::is_hover_ocr_running,
+            hover_ocr::get_hover_text,
+            hover_ocr::get_hover_ocr_config,
+            hover_ocr::set_hover_ocr_config,
+            screen_find::find_text_on_screen,
+            screen_find::click_text_on_screen,
+            screen_find::find_image_on_screen,
+            session::save_session,
+            session::restore_last_session,
+            recent_results::list_recent_results,
+            recent_results::pop_recent_result,
+            recent_results::add_recent_result,
+            templates::list_templates,
+            templates::set_active_template,
+            templates::save_template,
+            templates::delete_template,
+            templates::apply_template,
+            entities::extract_entities,
+            entities::open_detected_url,
+            conversion::convert_values,
+            conversion::refresh_exchange_rates,
+            conversion::get_conversion_config,
+            conversion::set_conversion_config,
+            calendar::create_event_from_text,
+            business_card::perform_business_card_ocr,
+            business_card::export_vcard,
+            document_scan::scan_document,
+            searchable_pdf::export_searchable_pdf,
+            history::sync_item_added,
+            webhooks::get_webhook_config,
+            webhooks::set_webhook_config,
+            webhooks::test_webhook,
+            notes::get_notes_config,
+            notes::set_notes_config,
+            notes::append_to_note,
+            cloud_notes::get_cloud_notes_config,
+            cloud_notes::set_cloud_notes_config,
+            cloud_notes::set_notes_token,
+            cloud_notes::has_notes_token,
+            cloud_notes::export_to_notes,
+            email::get_email_config,
+            email::set_email_config,
+            email::set_smtp_password,
+            email::email_result,
+            chat::get_chat_config,
+            chat::set_chat_config,
+            chat::forward_to_chat,
+            save_targets::get_save_targets_config,
+            save_targets::set_save_targets_config,
+            save_targets::set_sftp_password,
+            save_targets::save_capture,
+            save_targets::get_save_queue,
+            save_targets::retry_save_queue,
+            save_targets::clear_save_queue,
+            history_edits::sync_edit_item,
+            history_edits::sync_get_item_history,
+            history::sync_merge_items,
+            history::sync_split_item,
+            collections::sync_get_collections,
+            collections::sync_create_collection,
+            collections::sync_rename_collection,
+            collections::sync_delete_collection,
+            collections::sync_assign_to_collection,
+            history_edits::sync_get_conflicts,
+            pairing::sync_generate_pairing_code,
+            pairing::sync_pair_with_code,
+            pairing::get_paired_peers,
+            pairing::set_device_name,
+            pairing::sync_unpair,
+            folder_sync::get_folder_sync_config,
+            folder_sync::set_folder_sync_config,
+            folder_sync::sync_history_folder,
+            mobile::mobile_take_shared_images,
+            mobile::mobile_ocr_capture,
+            mobile::mobile_ocr_region,
+            mobile::mobile_text_at,
+            sandbox::get_sandbox_info,
+            sandbox::portal_save_file,
+            portable::get_portable_info,
+            tesseract_manager::get_tesseract_status,
+            tesseract_manager::install_tesseract,
+            storage::get_storage_report,
+            storage::clean_storage,
+            encryption::sync_enable_encryption,
+            encryption::sync_disable_encryption,
+            encryption::sync_encryption_enabled,
+            encryption::sync_encrypt_history,
+            encryption::sync_decrypt_history,
+            llm::set_llm_api_key,
+            llm::has_llm_api_key,
+            share::set_share_secret,
+            share::has_share_secret,
+            chat::set_chat_secret,
+            chat::has_chat_secret,
+            webhooks::set_webhook_secret,
+            webhooks::has_webhook_secret
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                lifecycle::shutdown();
+            }
+        });
 }
 
 #[cfg(test)]
@@ -334,7 +727,7 @@ mod tests {
         let b64 = base64::engine::general_purpose::STANDARD.encode(&buffer);
         let data_url = format!("data:image/png;base64,{}", b64);
 
-        let result = perform_ocr(&data_url, Some("eng".to_string()), None);
+        let result = perform_ocr(data_url, Some(ocr::OcrOptions { langs: Some("eng".to_string()), ..Default::default() }), None, None);
         match result {
             Ok(text) => {
                 println!("OCR Output: {}", text);
@@ -345,6 +738,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ocr_detailed_confidence() {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("tests/sample_ocr.png");
+
+        let mut file = std::fs::File::open(path).expect("Failed to open sample_ocr.png");
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)
+            .expect("Failed to read image data");
+
+        let b64 = base64::engine::general_purpose::STANDARD.encode(&buffer);
+        let data_url = format!("data:image/png;base64,{}", b64);
+
+        let options = ocr::OcrOptions {
+            langs: Some("eng".to_string()),
+            engine: Some("tesseract".to_string()),
+            ..Default::default()
+        };
+        let result = perform_ocr_detailed(data_url, Some(options), None)
+            .expect("Detailed OCR returned error");
+
+        assert!(!result.lines.is_empty(), "Detailed OCR returned no lines");
+        assert!(
+            result.confidence > 0.0 && result.confidence <= 100.0,
+            "Confidence out of range: {}",
+            result.confidence
+        );
+        for word in result.lines.iter().flat_map(|l| l.words.iter()) {
+            assert!(word.bbox.width > 0 && word.bbox.height > 0, "Word '{}' has empty bbox", word.text);
+        }
+    }
+
     #[test]
     fn test_qr_functionality() {
         let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -401,7 +826,7 @@ mod tests {
 
         for lang in languages {
             println!("Testing language loading for: {}", lang);
-            let result = perform_ocr(&data_url, Some(lang.to_string()), None);
+            let result = perform_ocr(data_url.clone(), Some(ocr::OcrOptions { langs: Some(lang.to_string()), ..Default::default() }), None, None);
             match result {
                 Ok(_) => println!("Successfully initialized and ran OCR for {}", lang),
                 Err(e) => panic!("Failed to run OCR with language '{}': {}", lang, e),
@@ -426,7 +851,7 @@ mod tests {
         // Create a batch of 3 images
         let images = vec![data_url.clone(), data_url.clone(), data_url.clone()];
 
-        let results = perform_batch_ocr(images, Some("eng".to_string()), None);
+        let results = perform_batch_ocr(images, Some(ocr::OcrOptions { langs: Some("eng".to_string()), ..Default::default() }));
 
         // Should have 3 results
         assert_eq!(results.len(), 3, "Batch OCR should return 3 results");
@@ -0,0 +1,60 @@
+// App lifecycle
+// Teardown for every way the app ends: tray Quit (`app.exit(0)`), the last
+// window closing, and SIGTERM/SIGHUP sent by the session manager at shutdown
+// or logout on Unix. In-flight downloads are stopped with their partial files
+// kept for resuming, models are unloaded and the log file is flushed.
+// Sleep needs no handler: config is written on every change, logs by a
+// background worker, and a download cut off by sleep resumes later.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tauri::{AppHandle, Runtime};
+
+/// How long exit waits for downloads to stop
+const DOWNLOAD_STOP_TIMEOUT: Duration = Duration::from_secs(2);
+
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Exit through the event loop on termination signals, so they get the same
+/// teardown as Quit
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    #[cfg(unix)]
+    {
+        use signal_hook::consts::{SIGHUP, SIGTERM};
+
+        let mut signals = match signal_hook::iterator::Signals::new([SIGTERM, SIGHUP]) {
+            Ok(signals) => signals,
+            Err(e) => {
+                tracing::warn!("Signal handlers not installed: {}", e);
+                return;
+            }
+        };
+        let app = app.clone();
+        std::thread::spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                tracing::info!("Received signal {}, exiting", signal);
+                app.exit(0);
+            }
+        });
+    }
+    #[cfg(not(unix))]
+    let _ = app;
+}
+
+/// Tear down background state. Runs once; later calls do nothing.
+pub fn shutdown() {
+    if SHUT_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tracing::info!("Shutting down");
+
+    crate::downloads::cancel_all(DOWNLOAD_STOP_TIMEOUT);
+    crate::translator::unload();
+    crate::summarizer::unload();
+    #[cfg(feature = "tesseract-ffi")]
+    crate::tesseract_pool::clear();
+
+    // Last, so the messages above make it to the file
+    crate::logging::flush();
+}
@@ -0,0 +1,267 @@
+// Live OCR mode
+// Repeatedly captures a screen region and re-runs OCR only on the parts that changed.
+// The session can be paused (from the tray or the UI) without losing its region.
+
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use image::ImageFormat;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::error::{AppError, AppResult};
+use crate::frame_diff::{self, Region};
+use crate::ocr;
+
+/// Event emitted whenever a changed region has been re-recognized
+pub const LIVE_OCR_EVENT: &str = "live-ocr://updated";
+
+/// Event emitted with the new state when the session is paused or resumed
+pub const LIVE_OCR_PAUSED_EVENT: &str = "live-ocr://paused";
+
+/// If more than this fraction of the frame changed, OCR the whole region once
+/// instead of many small crops (e.g. page scroll or scene change)
+const FULL_FRAME_THRESHOLD: f32 = 0.5;
+
+/// Stop flag of the running live OCR session, if any
+static LIVE_SESSION: Lazy<Mutex<Option<Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(None));
+
+/// The running session skips captures while set; a new session starts unpaused
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Options for a live OCR session
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveOcrOptions {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub lang: Option<String>,
+    pub engine: Option<ocr::OcrEngine>,
+    pub fps: Option<u32>,
+    pub monitor_index: Option<usize>,
+    #[serde(default)]
+    pub game_mode: bool,
+}
+
+/// Payload of `live-ocr://updated`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LiveOcrUpdate {
+    pub region: Region,
+    pub text: String,
+}
+
+fn encode_png(image: &image::RgbaImage) -> AppResult<Vec<u8>> {
+    let mut bytes: Vec<u8> = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    Ok(bytes)
+}
+
+fn full_frame(width: u32, height: u32) -> Region {
+    Region { x: 0, y: 0, width, height }
+}
+
+fn contains(outer: &Region, inner: &Region) -> bool {
+    inner.x >= outer.x
+        && inner.y >= outer.y
+        && inner.x + inner.width <= outer.x + outer.width
+        && inner.y + inner.height <= outer.y + outer.height
+}
+
+/// Regions to OCR for a frame: the changed regions plus the regions that failed
+/// last time, or the whole frame once most of it needs OCR
+fn regions_to_ocr(mut changed: Vec<Region>, failed: &[Region], width: u32, height: u32) -> Vec<Region> {
+    for region in failed {
+        if !changed.iter().any(|c| contains(c, region)) {
+            changed.push(*region);
+        }
+    }
+    if frame_diff::changed_ratio(&changed, width, height) > FULL_FRAME_THRESHOLD {
+        vec![full_frame(width, height)]
+    } else {
+        changed
+    }
+}
+
+#[tracing::instrument(skip_all, fields(lang = ?options.lang, fps = ?options.fps))]
+fn run_session(app: AppHandle, options: LiveOcrOptions, stop: Arc<AtomicBool>) {
+    let fps = options.fps.unwrap_or(5).clamp(1, 10);
+    let interval = Duration::from_millis(1000 / fps as u64);
+    let lang = options.lang.clone().unwrap_or_else(|| "eng".to_string());
+    let engine = options.engine.unwrap_or_default();
+    ocr::warm_tesseract(&lang);
+
+    let mut backend = crate::capture::create_backend(options.game_mode, options.monitor_index.unwrap_or(0));
+    let mut previous: Option<image::RgbaImage> = None;
+    // Regions whose OCR failed; retried with the next frame's changes
+    let mut failed: Vec<Region> = Vec::new();
+
+    while !stop.load(Ordering::SeqCst) {
+        if PAUSED.load(Ordering::SeqCst) {
+            std::thread::sleep(interval);
+            continue;
+        }
+        let started = Instant::now();
+
+        match backend.capture_region(options.x, options.y, options.width, options.height) {
+            Ok(frame) => {
+                let changed = match previous {
+                    Some(ref prev) => frame_diff::changed_regions(prev, &frame),
+                    None => vec![full_frame(frame.width(), frame.height())],
+                };
+                let regions = regions_to_ocr(changed, &failed, frame.width(), frame.height());
+
+                failed.clear();
+                for region in regions {
+                    let crop = frame_diff::crop_region(&frame, &region);
+                    let result = encode_png(&crop)
+                        .and_then(|bytes| ocr::perform_ocr_with_engine(&bytes, &lang, engine, &Default::default()));
+                    match result {
+                        Ok(text) => {
+                            let _ = app.emit(LIVE_OCR_EVENT, LiveOcrUpdate { region, text });
+                        }
+                        Err(e) => {
+                            tracing::warn!("Live OCR failed: {}", e);
+                            failed.push(region);
+                        }
+                    }
+                }
+
+                previous = Some(frame);
+            }
+            Err(e) => tracing::warn!("Live capture failed ({}): {}", backend.name(), e),
+        }
+
+        if let Some(remaining) = interval.checked_sub(started.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+}
+
+pub fn is_running() -> bool {
+    LIVE_SESSION.lock().map(|guard| guard.is_some()).unwrap_or(false)
+}
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+fn sync_tray<R: Runtime>(app: &AppHandle<R>) {
+    if let Err(e) = crate::tray::refresh(app) {
+        tracing::warn!("Tray menu not updated: {}", e);
+    }
+}
+
+/// A session started or stopped: reset the pause and update the tray
+fn session_changed<R: Runtime>(app: &AppHandle<R>) {
+    if PAUSED.swap(false, Ordering::SeqCst) {
+        let _ = app.emit(LIVE_OCR_PAUSED_EVENT, false);
+    }
+    sync_tray(app);
+}
+
+/// Pause or resume the running session, keeping the tray and the UI in sync
+pub fn set_paused<R: Runtime>(app: &AppHandle<R>, paused: bool) {
+    if PAUSED.swap(paused, Ordering::SeqCst) != paused {
+        let _ = app.emit(LIVE_OCR_PAUSED_EVENT, paused);
+        sync_tray(app);
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Start live OCR of a screen region, replacing any running session
+#[tauri::command]
+pub fn start_live_ocr(app: AppHandle, options: LiveOcrOptions) -> AppResult<()> {
+    if options.width == 0 || options.height == 0 {
+        return Err(AppError::InvalidInput("Live OCR region must not be empty".to_string()));
+    }
+
+    let mut guard = LIVE_SESSION.lock()?;
+    if let Some(stop) = guard.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let session_app = app.clone();
+    std::thread::spawn(move || run_session(session_app, options, thread_stop));
+    *guard = Some(stop);
+    drop(guard);
+
+    session_changed(&app);
+    Ok(())
+}
+
+/// Stop the running live OCR session
+#[tauri::command]
+pub fn stop_live_ocr(app: AppHandle) -> AppResult<()> {
+    let mut guard = LIVE_SESSION.lock()?;
+    if let Some(stop) = guard.take() {
+        stop.store(true, Ordering::SeqCst);
+    }
+    drop(guard);
+
+    session_changed(&app);
+    Ok(())
+}
+
+/// Pause or resume the running live OCR session
+#[tauri::command]
+pub fn pause_live_ocr(app: AppHandle, paused: bool) -> AppResult<()> {
+    if paused && !is_running() {
+        return Err(AppError::InvalidInput("No live OCR session is running".to_string()));
+    }
+    set_paused(&app, paused);
+    Ok(())
+}
+
+/// Check whether a live OCR session is running
+#[tauri::command]
+pub fn is_live_ocr_running() -> AppResult<bool> {
+    let guard = LIVE_SESSION.lock()?;
+    Ok(guard.is_some())
+}
+
+/// Check whether the live OCR session is paused
+#[tauri::command]
+pub fn is_live_ocr_paused() -> AppResult<bool> {
+    Ok(is_paused())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: u32, y: u32, width: u32, height: u32) -> Region {
+        Region { x, y, width, height }
+    }
+
+    #[test]
+    fn test_regions_to_ocr_retries_failed() {
+        let changed = vec![region(0, 0, 10, 10)];
+        let failed = [region(2, 2, 4, 4), region(50, 50, 10, 10)];
+        assert_eq!(
+            regions_to_ocr(changed, &failed, 100, 100),
+            vec![region(0, 0, 10, 10), region(50, 50, 10, 10)]
+        );
+        // Nothing changed since the failure: only the failed region is retried
+        assert_eq!(regions_to_ocr(Vec::new(), &failed[1..], 100, 100), vec![region(50, 50, 10, 10)]);
+    }
+
+    #[test]
+    fn test_regions_to_ocr_full_frame() {
+        let changed = vec![region(0, 0, 100, 40)];
+        let failed = [region(0, 50, 100, 20)];
+        assert_eq!(regions_to_ocr(changed, &failed, 100, 100), vec![region(0, 0, 100, 100)]);
+        assert_eq!(regions_to_ocr(Vec::new(), &[], 100, 100), Vec::new());
+    }
+}
@@ -0,0 +1,333 @@
+// LLM post-editing
+// Opt-in fluency pass over machine translation output using a configured LLM:
+// any OpenAI-compatible chat endpoint or a local Ollama server. Tokens are
+// streamed to the frontend as `llm://token` events while the request runs.
+// API keys are kept in the OS keychain, never in the config file.
+
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Pipeline};
+
+/// Emitted with an `LlmToken` for each streamed piece of the refined text
+pub const LLM_TOKEN_EVENT: &str = "llm://token";
+
+const DEFAULT_SYSTEM_PROMPT: &str = "You are a professional translator and editor. Improve the fluency and \
+accuracy of the machine translation using the original text. Keep the meaning, formatting and line breaks. \
+Reply with the improved translation only.";
+
+/// Wire protocol of a provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LlmProviderKind {
+    /// `POST {base_url}/chat/completions` with server-sent events
+    OpenAi,
+    /// `POST {base_url}/api/chat` with newline-delimited JSON
+    Ollama,
+}
+
+/// A configured LLM endpoint
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmProvider {
+    pub id: String,
+    pub kind: LlmProviderKind,
+    /// e.g. "https://api.openai.com/v1" or "http://localhost:11434"
+    pub base_url: String,
+    pub model: String,
+    /// Accepted from the frontend and from configs written before keys moved
+    /// to the keychain; moved there by `move_keys_to_keychain` and never
+    /// written back or returned
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,
+}
+
+/// LLM settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LlmConfig {
+    /// Nothing is sent anywhere unless this is on
+    pub enabled: bool,
+    pub providers: Vec<LlmProvider>,
+    /// Provider id used when a request doesn't name one
+    pub default_provider: Option<String>,
+    /// Overrides the built-in post-editing instructions
+    pub system_prompt: Option<String>,
+}
+
+/// Payload of `llm://token`
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmToken {
+    pub request_id: Option<String>,
+    pub token: String,
+}
+
+/// A parsed line of a streaming response
+#[derive(Debug, PartialEq)]
+enum StreamLine {
+    Token(String),
+    Done,
+    Skip,
+}
+
+/// Parse an OpenAI server-sent event line (`data: {...}` / `data: [DONE]`)
+fn parse_openai_line(line: &str) -> StreamLine {
+    let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+        return StreamLine::Skip;
+    };
+    if data == "[DONE]" {
+        return StreamLine::Done;
+    }
+    serde_json::from_str::<serde_json::Value>(data)
+        .ok()
+        .and_then(|v| v["choices"][0]["delta"]["content"].as_str().map(str::to_string))
+        .map_or(StreamLine::Skip, StreamLine::Token)
+}
+
+/// Parse an Ollama NDJSON line (`{"message":{"content":..},"done":false}`)
+fn parse_ollama_line(line: &str) -> StreamLine {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(line.trim()) else {
+        return StreamLine::Skip;
+    };
+    if value["done"].as_bool() == Some(true) {
+        return StreamLine::Done;
+    }
+    value["message"]["content"]
+        .as_str()
+        .map_or(StreamLine::Skip, |t| StreamLine::Token(t.to_string()))
+}
+
+fn build_messages(system_prompt: &str, text: &str, translation: &str, target_lang: Option<&str>) -> serde_json::Value {
+    let target = target_lang.map(|l| format!(" (target language: {})", l)).unwrap_or_default();
+    serde_json::json!([
+        { "role": "system", "content": system_prompt },
+        {
+            "role": "user",
+            "content": format!("Original text:\n{}\n\nMachine translation{}:\n{}", text, target, translation),
+        },
+    ])
+}
+
+/// Store a provider's API key in the keychain; an empty key removes it
+fn store_api_key(provider_id: &str, api_key: &str) -> AppResult<()> {
+    crate::secrets::store(&format!("llm-api-key-{}", provider_id), api_key.trim())
+}
+
+fn load_api_key(provider_id: &str) -> AppResult<Option<String>> {
+    crate::secrets::load(&format!("llm-api-key-{}", provider_id))
+}
+
+/// Hand plaintext keys found in `config` to `store` and clear them. Returns
+/// whether any were found.
+fn take_api_keys(config: &mut LlmConfig, mut store: impl FnMut(&str, &str) -> AppResult<()>) -> AppResult<bool> {
+    let mut found = false;
+    for provider in &mut config.providers {
+        if let Some(key) = provider.api_key.take() {
+            store(&provider.id, &key)?;
+            found = true;
+        }
+    }
+    Ok(found)
+}
+
+/// Move plaintext keys in `config` to the keychain
+pub fn move_keys_to_keychain(config: &mut LlmConfig) -> AppResult<()> {
+    take_api_keys(config, store_api_key).map(|_| ())
+}
+
+/// Move keys left in the config file by older versions to the keychain
+pub fn init() {
+    let result = crate::config::get().and_then(|config| {
+        let mut llm = config.llm;
+        if take_api_keys(&mut llm, store_api_key)? {
+            crate::config::update(|c| c.llm = llm)?;
+            tracing::info!("Moved LLM API keys to the keychain");
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        tracing::warn!("LLM API keys not moved to the keychain: {}", e);
+    }
+}
+
+fn resolve_provider(config: &LlmConfig, id: Option<&str>) -> AppResult<LlmProvider> {
+    let id = id
+        .or(config.default_provider.as_deref())
+        .or(config.providers.first().map(|p| p.id.as_str()))
+        .ok_or_else(|| AppError::InvalidInput("No LLM provider is configured".to_string()))?;
+    config
+        .providers
+        .iter()
+        .find(|p| p.id == id)
+        .cloned()
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown LLM provider '{}'", id)))
+}
+
+async fn stream_completion(
+    app: &AppHandle,
+    provider: &LlmProvider,
+    messages: serde_json::Value,
+    request_id: Option<String>,
+) -> AppResult<String> {
+    let base_url = provider.base_url.trim_end_matches('/');
+    let (url, body, parse): (String, serde_json::Value, fn(&str) -> StreamLine) = match provider.kind {
+        LlmProviderKind::OpenAi => (
+            format!("{}/chat/completions", base_url),
+            serde_json::json!({ "model": provider.model, "messages": messages, "stream": true }),
+            parse_openai_line,
+        ),
+        LlmProviderKind::Ollama => (
+            format!("{}/api/chat", base_url),
+            serde_json::json!({ "model": provider.model, "messages": messages, "stream": true }),
+            parse_ollama_line,
+        ),
+    };
+
+    let mut request = reqwest::Client::new().post(&url).json(&body);
+    if let Some(key) = load_api_key(&provider.id)?.filter(|k| !k.is_empty()) {
+        request = request.bearer_auth(key);
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| AppError::Translation(format!("LLM request failed: {}", e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(AppError::Translation(format!("LLM request failed: HTTP {} {}", status, body)));
+    }
+
+    let mut output = String::new();
+    let mut pending = Vec::new();
+    'stream: while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Translation(format!("LLM stream failed: {}", e)))?
+    {
+        pending.extend_from_slice(&chunk);
+        // Lines may be split across chunks; only parse complete ones
+        while let Some(newline) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            match parse(&String::from_utf8_lossy(&line)) {
+                StreamLine::Token(token) => {
+                    output.push_str(&token);
+                    let _ = app.emit(LLM_TOKEN_EVENT, LlmToken { request_id: request_id.clone(), token });
+                }
+                StreamLine::Done => break 'stream,
+                StreamLine::Skip => {}
+            }
+        }
+    }
+    Ok(output.trim().to_string())
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Post-edit a machine translation with an LLM. Streams `llm://token` events
+/// (tagged with `request_id`) and returns the full refined text.
+#[tauri::command]
+pub async fn refine_translation(
+    app: AppHandle,
+    text: String,
+    translation: String,
+    provider: Option<String>,
+    target_lang: Option<String>,
+    request_id: Option<String>,
+) -> AppResult<String> {
+    let run = events::start(Pipeline::Translate, "refine_translation", request_id.clone());
+    let result = async {
+        let config = crate::config::get()?.llm;
+        if !config.enabled {
+            return Err(AppError::Unsupported("LLM post-editing is disabled in settings".to_string()));
+        }
+        let provider = resolve_provider(&config, provider.as_deref())?;
+        let system_prompt = config.system_prompt.as_deref().unwrap_or(DEFAULT_SYSTEM_PROMPT);
+        let messages = build_messages(system_prompt, &text, &translation, target_lang.as_deref());
+        stream_completion(&app, &provider, messages, request_id).await
+    }
+    .await;
+    run.finish(result)
+}
+
+/// Store a provider's API key in the keychain; `None` removes it
+#[tauri::command]
+pub fn set_llm_api_key(provider_id: String, api_key: Option<String>) -> AppResult<()> {
+    store_api_key(&provider_id, api_key.as_deref().unwrap_or_default())
+}
+
+/// Whether an API key is stored for the provider
+#[tauri::command]
+pub fn has_llm_api_key(provider_id: String) -> AppResult<bool> {
+    Ok(load_api_key(&provider_id)?.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_openai_line() {
+        assert_eq!(
+            parse_openai_line(r#"data: {"choices":[{"delta":{"content":"Hel"}}]}"#),
+            StreamLine::Token("Hel".to_string())
+        );
+        assert_eq!(parse_openai_line(r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#), StreamLine::Skip);
+        assert_eq!(parse_openai_line("data: [DONE]"), StreamLine::Done);
+        assert_eq!(parse_openai_line(": keep-alive"), StreamLine::Skip);
+    }
+
+    #[test]
+    fn test_parse_ollama_line() {
+        assert_eq!(
+            parse_ollama_line(r#"{"message":{"role":"assistant","content":"lo"},"done":false}"#),
+            StreamLine::Token("lo".to_string())
+        );
+        assert_eq!(parse_ollama_line(r#"{"done":true}"#), StreamLine::Done);
+    }
+
+    #[test]
+    fn test_resolve_provider() {
+        let provider = |id: &str| LlmProvider {
+            id: id.to_string(),
+            kind: LlmProviderKind::Ollama,
+            base_url: "http://localhost:11434".to_string(),
+            model: "llama3".to_string(),
+            api_key: None,
+        };
+        let config = LlmConfig {
+            providers: vec![provider("local"), provider("cloud")],
+            default_provider: Some("cloud".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(resolve_provider(&config, None).unwrap().id, "cloud");
+        assert_eq!(resolve_provider(&config, Some("local")).unwrap().id, "local");
+        assert!(resolve_provider(&config, Some("missing")).is_err());
+        assert!(resolve_provider(&LlmConfig::default(), None).is_err());
+    }
+
+    #[test]
+    fn test_api_keys_stay_out_of_the_config() {
+        let json = r#"{"providers":[
+            {"id":"cloud","kind":"openAi","baseUrl":"https://api.openai.com/v1","model":"gpt-4o","apiKey":"sk-secret"},
+            {"id":"local","kind":"ollama","baseUrl":"http://localhost:11434","model":"llama3"}
+        ]}"#;
+        let mut config: LlmConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.providers[0].api_key.as_deref(), Some("sk-secret"));
+        // Never written to disk or handed back by get_config
+        assert!(!serde_json::to_string(&config).unwrap().contains("sk-secret"));
+
+        let mut stored = Vec::new();
+        let found = take_api_keys(&mut config, |id, key| {
+            stored.push((id.to_string(), key.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert!(found);
+        assert_eq!(stored, vec![("cloud".to_string(), "sk-secret".to_string())]);
+        assert!(config.providers.iter().all(|p| p.api_key.is_none()));
+        assert!(!take_api_keys(&mut config, |_, _| unreachable!()).unwrap());
+    }
+}
@@ -0,0 +1,253 @@
+// Logging
+// Structured logs through `tracing`: human-readable on stderr, and JSON lines
+// in a daily rolling file under <app data>/logs so users can attach them to
+// bug reports. The in-app viewer reads the files back with get_recent_logs.
+// RUST_LOG overrides the default filter.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+use crate::error::{AppError, AppResult};
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "screen-inu";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// Daily files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+const DEFAULT_FILTER: &str = "info,app_lib=debug";
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 5000;
+
+/// Flushes the file writer's buffer when dropped
+static GUARD: Lazy<Mutex<Option<WorkerGuard>>> = Lazy::new(|| Mutex::new(None));
+
+/// A log line as shown by the viewer
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// RFC 3339
+    pub timestamp: String,
+    /// "ERROR", "WARN", "INFO", "DEBUG" or "TRACE"
+    pub level: String,
+    /// Module that logged it
+    pub target: String,
+    pub message: String,
+    /// Other event fields and the enclosing spans, as `key=value` pairs
+    pub fields: Vec<String>,
+}
+
+pub fn get_log_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(LOG_DIR))
+}
+
+/// Install the global subscriber. Called once, before the app starts; file
+/// logging is skipped (stderr only) if the log directory isn't writable.
+pub fn init() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let stderr = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+
+    let appender = get_log_dir().ok().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix(LOG_FILE_SUFFIX)
+            .max_log_files(MAX_LOG_FILES)
+            .build(dir)
+            .map_err(|e| eprintln!("File logging disabled: {}", e))
+            .ok()
+    });
+    let file = appender.map(|appender| {
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        if let Ok(mut slot) = GUARD.lock() {
+            *slot = Some(guard);
+        }
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(false)
+            .with_span_list(true)
+            // Span closings carry how long captures and OCR runs took
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(writer)
+    });
+
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(stderr)
+        .with(file)
+        .try_init();
+}
+
+/// Write out buffered file logs and stop file logging. Called on exit, since
+/// statics are never dropped.
+pub fn flush() {
+    if let Ok(mut slot) = GUARD.lock() {
+        slot.take();
+    }
+}
+
+/// Severity rank, 0 being the most severe. None for unknown levels.
+fn level_rank(level: &str) -> Option<u8> {
+    match level.to_ascii_uppercase().as_str() {
+        "ERROR" => Some(0),
+        "WARN" => Some(1),
+        "INFO" => Some(2),
+        "DEBUG" => Some(3),
+        "TRACE" => Some(4),
+        _ => None,
+    }
+}
+
+fn field_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Parse a JSON line written by the file layer
+fn parse_entry(line: &str) -> Option<LogEntry> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let text = |key: &str| value.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+    let mut message = String::new();
+    let mut fields = Vec::new();
+    if let Some(map) = value.get("fields").and_then(|f| f.as_object()) {
+        for (key, field) in map {
+            if key == "message" {
+                message = field_string(field);
+            } else {
+                fields.push(format!("{}={}", key, field_string(field)));
+            }
+        }
+    }
+    for span in value.get("spans").and_then(|s| s.as_array()).into_iter().flatten() {
+        if let Some(map) = span.as_object() {
+            let name = map.get("name").map(field_string).unwrap_or_default();
+            fields.extend(
+                map.iter()
+                    .filter(|(key, _)| *key != "name")
+                    .map(|(key, field)| format!("{}.{}={}", name, key, field_string(field))),
+            );
+        }
+    }
+
+    Some(LogEntry {
+        timestamp: text("timestamp"),
+        level: text("level"),
+        target: text("target"),
+        message,
+        fields,
+    })
+}
+
+/// Log files in `dir`, newest first (the date suffix sorts chronologically)
+fn log_files(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+/// The last `limit` entries at `max_rank` or more severe, oldest first
+fn recent_entries(dir: &Path, max_rank: u8, limit: usize) -> AppResult<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    for file in log_files(dir)? {
+        let content = std::fs::read_to_string(&file)?;
+        let matching = content
+            .lines()
+            .rev()
+            .filter_map(parse_entry)
+            .filter(|entry| level_rank(&entry.level).is_some_and(|rank| rank <= max_rank));
+        entries.extend(matching.take(limit - entries.len()));
+        if entries.len() >= limit {
+            break;
+        }
+    }
+    entries.reverse();
+    Ok(entries)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Most recent log entries, oldest first. `level` is the least severe level
+/// to include (default "info"); `limit` defaults to 200 (at most 5000).
+#[tauri::command(async)]
+pub fn get_recent_logs(level: Option<String>, limit: Option<usize>) -> AppResult<Vec<LogEntry>> {
+    let level = level.as_deref().unwrap_or("info");
+    let max_rank =
+        level_rank(level).ok_or_else(|| AppError::InvalidInput(format!("Unknown log level: {}", level)))?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    recent_entries(&get_log_dir()?, max_rank, limit)
+}
+
+/// Open the log directory in the file manager
+#[tauri::command]
+pub fn open_log_folder(app: tauri::AppHandle) -> AppResult<()> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let dir = get_log_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| AppError::Other(format!("Failed to open log folder: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = r#"{"timestamp":"2026-10-16T09:30:00.123Z","level":"WARN","fields":{"message":"Live OCR failed","error":"timeout"},"target":"app_lib::live_ocr","spans":[{"engine":"Tesseract","name":"run_ocr"}]}"#;
+
+    #[test]
+    fn test_parse_entry() {
+        let entry = parse_entry(LINE).unwrap();
+        assert_eq!(entry.timestamp, "2026-10-16T09:30:00.123Z");
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.target, "app_lib::live_ocr");
+        assert_eq!(entry.message, "Live OCR failed");
+        assert_eq!(entry.fields, vec!["error=timeout", "run_ocr.engine=Tesseract"]);
+        assert_eq!(parse_entry("not json"), None);
+    }
+
+    #[test]
+    fn test_recent_entries_filters_and_orders() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let line = |level: &str, message: &str| {
+            format!(r#"{{"timestamp":"t","level":"{}","fields":{{"message":"{}"}},"target":"x"}}"#, level, message)
+        };
+        let old = [line("ERROR", "a"), line("INFO", "b")].join("\n");
+        let new = [line("DEBUG", "c"), line("WARN", "d"), "garbage".to_string()].join("\n");
+        std::fs::write(dir.join("screen-inu.2026-10-15.log"), old).unwrap();
+        std::fs::write(dir.join("screen-inu.2026-10-16.log"), new).unwrap();
+
+        let messages = |rank, limit| -> Vec<String> {
+            recent_entries(dir, rank, limit).unwrap().into_iter().map(|e| e.message).collect()
+        };
+        assert_eq!(messages(2, 10), vec!["a", "b", "d"]);
+        assert_eq!(messages(4, 2), vec!["c", "d"]);
+        assert_eq!(messages(1, 10), vec!["a", "d"]);
+    }
+}
@@ -0,0 +1,163 @@
+// Screen ruler
+// Region measurements in physical and logical pixels, and snapping a rough
+// selection to the edges of the UI element under it.
+
+use image::RgbaImage;
+use xcap::Monitor;
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::error::{AppError, AppResult};
+use crate::frame_diff::Region;
+
+/// How far (physical pixels) an edge may move when snapping
+const DEFAULT_SNAP_TOLERANCE: u32 = 12;
+
+/// Mean luma step across an edge for it to count as an element boundary
+const MIN_EDGE_STRENGTH: f32 = 24.0;
+
+/// Size of a region
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Measurement {
+    pub physical_width: u32,
+    pub physical_height: u32,
+    pub logical_width: f64,
+    pub logical_height: f64,
+    pub scale_factor: f64,
+    /// width / height
+    pub aspect_ratio: f64,
+    /// Reduced ratio, e.g. "16:9"
+    pub ratio: String,
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn measure(rect: &Region, scale_factor: f64) -> Measurement {
+    let scale_factor = if scale_factor > 0.0 { scale_factor } else { 1.0 };
+    let divisor = gcd(rect.width, rect.height).max(1);
+    Measurement {
+        physical_width: rect.width,
+        physical_height: rect.height,
+        logical_width: rect.width as f64 / scale_factor,
+        logical_height: rect.height as f64 / scale_factor,
+        scale_factor,
+        aspect_ratio: if rect.height == 0 { 0.0 } else { rect.width as f64 / rect.height as f64 },
+        ratio: format!("{}:{}", rect.width / divisor, rect.height / divisor),
+    }
+}
+
+fn luma(image: &RgbaImage, x: u32, y: u32) -> f32 {
+    let [r, g, b, _] = image.get_pixel(x, y).0;
+    0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32
+}
+
+/// Mean luma difference between column `x - 1` and `x` over rows `y0..y1`
+fn column_edge(image: &RgbaImage, x: u32, y0: u32, y1: u32) -> f32 {
+    let rows = y0..y1.min(image.height());
+    let count = rows.len().max(1) as f32;
+    rows.map(|y| (luma(image, x, y) - luma(image, x - 1, y)).abs()).sum::<f32>() / count
+}
+
+/// Mean luma difference between row `y - 1` and `y` over columns `x0..x1`
+fn row_edge(image: &RgbaImage, y: u32, x0: u32, x1: u32) -> f32 {
+    let columns = x0..x1.min(image.width());
+    let count = columns.len().max(1) as f32;
+    columns.map(|x| (luma(image, x, y) - luma(image, x, y - 1)).abs()).sum::<f32>() / count
+}
+
+/// Strongest edge within `tolerance` of `position`, or `position` if none is strong enough
+fn snap_edge(position: u32, tolerance: u32, limit: u32, strength: impl Fn(u32) -> f32) -> u32 {
+    let lo = position.saturating_sub(tolerance).max(1);
+    let hi = (position + tolerance).min(limit.saturating_sub(1));
+    (lo..=hi)
+        .map(|p| (p, strength(p)))
+        .filter(|(_, s)| *s >= MIN_EDGE_STRENGTH)
+        // Prefer the strongest edge, then the one closest to the selection
+        .max_by(|a, b| {
+            a.1.partial_cmp(&b.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.0.abs_diff(position).cmp(&a.0.abs_diff(position)))
+        })
+        .map_or(position, |(p, _)| p)
+}
+
+/// Move each side of `rect` onto the nearest strong edge in the image
+pub fn snap(image: &RgbaImage, rect: &Region, tolerance: u32) -> Region {
+    let (right, bottom) = (rect.x + rect.width, rect.y + rect.height);
+    let (w, h) = (image.width(), image.height());
+
+    let left = snap_edge(rect.x, tolerance, w, |x| column_edge(image, x, rect.y, bottom));
+    let new_right = snap_edge(right, tolerance, w, |x| column_edge(image, x, rect.y, bottom));
+    let top = snap_edge(rect.y, tolerance, h, |y| row_edge(image, y, rect.x, right));
+    let new_bottom = snap_edge(bottom, tolerance, h, |y| row_edge(image, y, rect.x, right));
+
+    if new_right <= left || new_bottom <= top {
+        return *rect;
+    }
+    Region {
+        x: left,
+        y: top,
+        width: new_right - left,
+        height: new_bottom - top,
+    }
+}
+
+/// Scale factor (physical pixels per logical point) of the primary monitor
+pub fn primary_scale_factor() -> AppResult<f64> {
+    let monitors = Monitor::all().map_err(|e| AppError::Capture(e.to_string()))?;
+    let monitor = monitors
+        .first()
+        .ok_or_else(|| AppError::Capture("No monitor found".to_string()))?;
+    let scale = monitor.scale_factor().map_err(|e| AppError::Capture(e.to_string()))?;
+    Ok(scale as f64)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Measure a region (physical pixels on the primary monitor)
+#[tauri::command]
+pub fn measure_region(rect: Region) -> AppResult<Measurement> {
+    Ok(measure(&rect, primary_scale_factor()?))
+}
+
+/// Snap a rough selection to the bounds of the UI element under it
+#[tauri::command]
+pub fn snap_region_to_edges(rect: Region, tolerance: Option<u32>) -> AppResult<Region> {
+    crate::permissions::ensure_capture_permission()?;
+    let frame = XcapBackend::new(0).capture_frame()?;
+    Ok(snap(&frame, &rect, tolerance.unwrap_or(DEFAULT_SNAP_TOLERANCE)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let m = measure(&Region { x: 0, y: 0, width: 1920, height: 1080 }, 1.5);
+        assert_eq!(m.logical_width, 1280.0);
+        assert_eq!(m.logical_height, 720.0);
+        assert_eq!(m.ratio, "16:9");
+    }
+
+    #[test]
+    fn test_snap_to_button() {
+        // White screen with a dark "button" at x 20..60, y 10..30
+        let mut image = RgbaImage::from_pixel(100, 50, image::Rgba([255, 255, 255, 255]));
+        for y in 10..30 {
+            for x in 20..60 {
+                image.put_pixel(x, y, image::Rgba([40, 40, 40, 255]));
+            }
+        }
+        let rough = Region { x: 24, y: 7, width: 33, height: 25 };
+        assert_eq!(snap(&image, &rough, 8), Region { x: 20, y: 10, width: 40, height: 20 });
+    }
+}
@@ -0,0 +1,203 @@
+// Memory budget
+// Loaded models and in-memory caches report their size here. When a new
+// model would push the total over the configured budget, the least recently
+// used others are evicted first, so several MarianMT models can stay
+// resident without exhausting RAM on smaller machines. Sizes are estimates:
+// the model files on disk, or the bytes a cache holds.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use once_cell::sync::Lazy;
+
+use crate::error::{AppError, AppResult};
+
+const MB: u64 = 1024 * 1024;
+
+static RESIDENT: Lazy<Mutex<HashMap<String, Resident>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Frees a resident by key; returns false if it's in use and was kept
+pub type Evict = fn(&str) -> bool;
+
+/// Memory budget settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MemoryConfig {
+    /// Budget for loaded models and caches
+    pub budget_mb: u64,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self { budget_mb: 1024 }
+    }
+}
+
+struct Resident {
+    kind: &'static str,
+    bytes: u64,
+    last_used: Instant,
+    evict: Evict,
+}
+
+/// A model or cache held in memory
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryEntry {
+    pub key: String,
+    /// "translation", "summary", "tesseract" or "capture-buffer"
+    pub kind: String,
+    pub bytes: u64,
+    pub idle_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryUsage {
+    pub budget_bytes: u64,
+    pub used_bytes: u64,
+    pub entries: Vec<MemoryEntry>,
+}
+
+fn budget_bytes() -> u64 {
+    crate::config::get().map(|c| c.memory).unwrap_or_default().budget_mb * MB
+}
+
+/// Keys to evict, least recently used first, to fit `budget` with `keep`
+/// staying resident
+fn eviction_order(resident: &HashMap<String, Resident>, budget: u64, keep: Option<&str>) -> Vec<String> {
+    let mut used: u64 = resident.values().map(|r| r.bytes).sum();
+    let mut candidates: Vec<(&String, &Resident)> =
+        resident.iter().filter(|(key, _)| Some(key.as_str()) != keep).collect();
+    candidates.sort_by_key(|(_, r)| r.last_used);
+    let mut victims = Vec::new();
+    for (key, r) in candidates {
+        if used <= budget {
+            break;
+        }
+        used -= r.bytes;
+        victims.push(key.clone());
+    }
+    victims
+}
+
+/// Evict until the total fits the budget, sparing `keep`. A model larger
+/// than the whole budget still loads, alone.
+fn enforce(keep: Option<&str>) {
+    let budget = budget_bytes();
+    let victims: Vec<(String, Evict)> = {
+        let Ok(resident) = RESIDENT.lock() else {
+            return;
+        };
+        eviction_order(&resident, budget, keep)
+            .into_iter()
+            .filter_map(|key| resident.get(&key).map(|r| (key, r.evict)))
+            .collect()
+    };
+    // Outside the lock: evicting may release other entries
+    for (key, evict) in victims {
+        if evict(&key) {
+            tracing::debug!("Evicted {} to stay within the memory budget", key);
+            release(&key);
+        }
+    }
+}
+
+/// Record `bytes` held under `key`, evicting others to make room. Call it
+/// before loading, so the old models are freed first.
+pub fn reserve(key: &str, kind: &'static str, bytes: u64, evict: Evict) {
+    if let Ok(mut resident) = RESIDENT.lock() {
+        resident.insert(
+            key.to_string(),
+            Resident {
+                kind,
+                bytes,
+                last_used: Instant::now(),
+                evict,
+            },
+        );
+    }
+    enforce(Some(key));
+}
+
+/// Mark `key` as just used, so it's evicted last
+pub fn touch(key: &str) {
+    if let Ok(mut resident) = RESIDENT.lock() {
+        if let Some(r) = resident.get_mut(key) {
+            r.last_used = Instant::now();
+        }
+    }
+}
+
+/// Forget `key` once it's been freed
+pub fn release(key: &str) {
+    if let Ok(mut resident) = RESIDENT.lock() {
+        resident.remove(key);
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_memory_usage() -> AppResult<MemoryUsage> {
+    let resident = RESIDENT.lock()?;
+    let mut entries: Vec<MemoryEntry> = resident
+        .iter()
+        .map(|(key, r)| MemoryEntry {
+            key: key.clone(),
+            kind: r.kind.to_string(),
+            bytes: r.bytes,
+            idle_secs: r.last_used.elapsed().as_secs(),
+        })
+        .collect();
+    entries.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.key.cmp(&b.key)));
+    Ok(MemoryUsage {
+        budget_bytes: budget_bytes(),
+        used_bytes: entries.iter().map(|e| e.bytes).sum(),
+        entries,
+    })
+}
+
+/// Set the budget and evict right away if usage is over it
+#[tauri::command]
+pub fn set_memory_budget(budget_mb: u64) -> AppResult<MemoryUsage> {
+    if budget_mb < 64 {
+        return Err(AppError::InvalidInput("Memory budget must be at least 64 MB".to_string()));
+    }
+    crate::config::update(|c| c.memory.budget_mb = budget_mb)?;
+    enforce(None);
+    get_memory_usage()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn resident(bytes: u64, age_secs: u64) -> Resident {
+        Resident {
+            kind: "translation",
+            bytes,
+            last_used: Instant::now() - Duration::from_secs(age_secs),
+            evict: |_| true,
+        }
+    }
+
+    #[test]
+    fn test_eviction_order() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), resident(300 * MB, 30));
+        map.insert("b".to_string(), resident(300 * MB, 20));
+        map.insert("c".to_string(), resident(300 * MB, 10));
+        map.insert("new".to_string(), resident(300 * MB, 0));
+
+        assert!(eviction_order(&map, 1200 * MB, Some("new")).is_empty());
+        assert_eq!(eviction_order(&map, 1000 * MB, Some("new")), vec!["a"]);
+        assert_eq!(eviction_order(&map, 500 * MB, Some("new")), vec!["a", "b", "c"]);
+        // The model being loaded stays even if it alone exceeds the budget
+        assert_eq!(eviction_order(&map, 100 * MB, Some("new")).len(), 3);
+    }
+}
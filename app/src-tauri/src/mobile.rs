@@ -0,0 +1,357 @@
+// Phone companion support
+// The same core runs the iOS and Android apps. Phones can't capture other
+// apps' screens, so images come in through the share sheet instead, and OCR
+// runs on the platform recognizer (Vision on iOS, ML Kit on Android) through
+// the native half of this plugin, in mobile/ios and mobile/android. Selection
+// is by touch: a dragged region or a tap, in coordinates relative to the
+// image as shown (0-1), so the UI doesn't need to know its pixel size.
+// On desktop the same commands run the regular OCR engines, which keeps the
+// touch UI usable on tablets and convertibles.
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tauri::plugin::{Builder, TauriPlugin};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::{AppError, AppResult};
+use crate::ocr_result::{BoundingBox, OcrLine, OcrResult};
+
+#[cfg(mobile)]
+use tauri::Manager;
+
+#[cfg(target_os = "android")]
+const ANDROID_PLUGIN_PACKAGE: &str = "com.iml1s.screeninu";
+
+#[cfg(target_os = "ios")]
+tauri::ios_plugin_binding!(init_plugin_mobile_ocr);
+
+/// Extra margin around a dragged region, as a share of its size, since
+/// fingers cover what they select
+const REGION_PADDING: f64 = 0.04;
+
+/// How far from a line a tap still selects it, in pixels
+const TAP_TOLERANCE: u32 = 24;
+
+/// OCR of the last capture and language, for repeated taps on the same image
+static LAST_RESULT: Lazy<Mutex<Option<(String, OcrResult)>>> = Lazy::new(|| Mutex::new(None));
+
+/// The native half of the plugin
+#[cfg(mobile)]
+struct MobileOcr<R: Runtime>(tauri::plugin::PluginHandle<R>);
+
+/// A rectangle relative to the image, each value 0-1
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub struct RelativeRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// An image received through the share sheet
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedImage {
+    /// Served on the capture:// scheme
+    pub capture_id: String,
+    pub mime_type: &'static str,
+}
+
+#[cfg(mobile)]
+#[derive(serde::Serialize)]
+struct RecognizeArgs {
+    /// base64
+    image: String,
+    /// BCP-47 tags; the recognizer's default when empty
+    languages: Vec<String>,
+}
+
+/// A word (ML Kit) or line (Vision) from the native recognizer, in pixels
+#[cfg(mobile)]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NativeWord {
+    text: String,
+    /// 0-100, when the recognizer reports it
+    confidence: Option<f32>,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    block: u32,
+    line: u32,
+}
+
+#[cfg(mobile)]
+#[derive(serde::Deserialize)]
+struct NativeResult {
+    engine: String,
+    words: Vec<NativeWord>,
+}
+
+#[cfg(mobile)]
+#[derive(serde::Deserialize)]
+struct NativeSharedImage {
+    /// base64
+    data: String,
+}
+
+#[cfg(mobile)]
+#[derive(serde::Deserialize)]
+struct NativeSharedImages {
+    images: Vec<NativeSharedImage>,
+}
+
+#[cfg(mobile)]
+fn native_error(e: tauri::plugin::mobile::PluginInvokeError) -> AppError {
+    AppError::Ocr(format!("Native OCR failed: {}", e))
+}
+
+#[cfg(mobile)]
+impl<R: Runtime> MobileOcr<R> {
+    fn recognize(&self, image: &[u8], lang: &str) -> AppResult<OcrResult> {
+        use base64::Engine;
+
+        let languages = lang
+            .split('+')
+            .filter_map(crate::ocr::tesseract_lang_to_bcp47)
+            .map(str::to_string)
+            .collect();
+        let args = RecognizeArgs {
+            image: base64::engine::general_purpose::STANDARD.encode(image),
+            languages,
+        };
+        let result: NativeResult = self.0.run_mobile_plugin("recognizeText", args).map_err(native_error)?;
+        let words = result
+            .words
+            .into_iter()
+            .filter(|w| !w.text.trim().is_empty())
+            .map(|w| {
+                let confidence = w
+                    .confidence
+                    .unwrap_or_else(|| crate::ocr_result::estimate_word_confidence(&w.text));
+                let bbox = BoundingBox {
+                    x: w.x.max(0.0) as u32,
+                    y: w.y.max(0.0) as u32,
+                    width: w.width.max(0.0) as u32,
+                    height: w.height.max(0.0) as u32,
+                };
+                crate::ocr_result::OcrWord::new(w.text, confidence, bbox, w.block, 0, w.line)
+            })
+            .collect();
+        Ok(OcrResult::from_words(words, &result.engine))
+    }
+
+    fn take_shared_images(&self) -> AppResult<Vec<Vec<u8>>> {
+        let shared: NativeSharedImages = self
+            .0
+            .run_mobile_plugin("takeSharedImages", ())
+            .map_err(|e| AppError::Other(format!("Failed to read shared images: {}", e)))?;
+        shared
+            .images
+            .iter()
+            .map(|image| crate::ocr::decode_base64_image(&image.data))
+            .collect()
+    }
+}
+
+/// The plugin; registers the native half on iOS and Android
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::new("mobile-ocr")
+        .setup(|app, api| {
+            #[cfg(target_os = "android")]
+            let handle = api.register_android_plugin(ANDROID_PLUGIN_PACKAGE, "MobileOcrPlugin")?;
+            #[cfg(target_os = "ios")]
+            let handle = api.register_ios_plugin(init_plugin_mobile_ocr)?;
+            #[cfg(mobile)]
+            app.manage(MobileOcr(handle));
+            #[cfg(desktop)]
+            let _ = (app, api);
+            Ok(())
+        })
+        .build()
+}
+
+#[cfg(mobile)]
+fn recognize(app: &AppHandle, image: &[u8], lang: &str) -> AppResult<OcrResult> {
+    let ocr = app
+        .try_state::<MobileOcr<tauri::Wry>>()
+        .ok_or_else(|| AppError::Unsupported("Native OCR is not available".to_string()))?;
+    ocr.recognize(image, lang)
+}
+
+#[cfg(desktop)]
+fn recognize(_app: &AppHandle, image: &[u8], lang: &str) -> AppResult<OcrResult> {
+    let options = crate::ocr::OcrOptions {
+        langs: Some(lang.to_string()),
+        ..Default::default()
+    };
+    crate::ocr::run_ocr_detailed(image, &options)
+}
+
+fn capture_bytes(capture_id: &str) -> AppResult<Vec<u8>> {
+    crate::capture_protocol::get(capture_id)
+        .map(|(_, bytes)| bytes)
+        .ok_or_else(|| AppError::InvalidInput(format!("Capture {} is gone", capture_id)))
+}
+
+/// OCR of a whole capture, reused while the same capture is being tapped on
+fn capture_result(app: &AppHandle, capture_id: &str, lang: &str) -> AppResult<OcrResult> {
+    let key = format!("{}/{}", capture_id, lang);
+    if let Some((cached, result)) = LAST_RESULT.lock()?.as_ref() {
+        if *cached == key {
+            return Ok(result.clone());
+        }
+    }
+    let result = recognize(app, &capture_bytes(capture_id)?, lang)?;
+    *LAST_RESULT.lock()? = Some((key, result.clone()));
+    Ok(result)
+}
+
+/// Pixel box of `rect` in an image, widened by `padding` of its size on each
+/// side and kept inside the image
+fn pixel_rect(image_width: u32, image_height: u32, rect: RelativeRect, padding: f64) -> AppResult<BoundingBox> {
+    let values = [rect.x, rect.y, rect.width, rect.height];
+    if values.iter().any(|v| !v.is_finite()) || rect.width <= 0.0 || rect.height <= 0.0 {
+        return Err(AppError::InvalidInput("Empty selection".to_string()));
+    }
+    let (w, h) = (image_width as f64, image_height as f64);
+    let left = ((rect.x - rect.width * padding) * w).clamp(0.0, w);
+    let top = ((rect.y - rect.height * padding) * h).clamp(0.0, h);
+    let right = ((rect.x + rect.width * (1.0 + padding)) * w).clamp(0.0, w);
+    let bottom = ((rect.y + rect.height * (1.0 + padding)) * h).clamp(0.0, h);
+    if right - left < 1.0 || bottom - top < 1.0 {
+        return Err(AppError::InvalidInput("Selection is outside the image".to_string()));
+    }
+    Ok(BoundingBox {
+        x: left as u32,
+        y: top as u32,
+        width: (right - left) as u32,
+        height: (bottom - top) as u32,
+    })
+}
+
+/// The line under a tap at pixel (x, y), or the nearest one within
+/// `tolerance`
+fn line_at(result: &OcrResult, x: u32, y: u32, tolerance: u32) -> Option<&OcrLine> {
+    let distance = |b: &BoundingBox| {
+        let dx = b.x.saturating_sub(x).max(x.saturating_sub(b.x + b.width));
+        let dy = b.y.saturating_sub(y).max(y.saturating_sub(b.y + b.height));
+        dx.max(dy)
+    };
+    result
+        .lines
+        .iter()
+        .map(|line| (distance(&line.bbox), line))
+        .filter(|(d, _)| *d <= tolerance)
+        .min_by_key(|(d, line)| (*d, line.bbox.area()))
+        .map(|(_, line)| line)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Images shared to the app since the last call, stored as captures. Empty
+/// on desktop.
+#[tauri::command]
+pub fn mobile_take_shared_images(app: AppHandle) -> AppResult<Vec<SharedImage>> {
+    #[cfg(mobile)]
+    let images = match app.try_state::<MobileOcr<tauri::Wry>>() {
+        Some(ocr) => ocr.take_shared_images()?,
+        None => Vec::new(),
+    };
+    #[cfg(desktop)]
+    let images: Vec<Vec<u8>> = {
+        let _ = app;
+        Vec::new()
+    };
+    images
+        .into_iter()
+        .map(|bytes| {
+            let mime_type = match image::guess_format(&bytes) {
+                Ok(image::ImageFormat::Jpeg) => "image/jpeg",
+                Ok(image::ImageFormat::WebP) => "image/webp",
+                Ok(image::ImageFormat::Png) => "image/png",
+                _ => "application/octet-stream",
+            };
+            let capture_id = crate::capture_protocol::store(bytes, mime_type)?;
+            Ok(SharedImage { capture_id, mime_type })
+        })
+        .collect()
+}
+
+/// Recognize a whole capture
+#[tauri::command(async)]
+#[tracing::instrument(skip(app), err)]
+pub fn mobile_ocr_capture(app: AppHandle, capture_id: String, lang: String) -> AppResult<OcrResult> {
+    capture_result(&app, &capture_id, &lang)
+}
+
+/// Recognize the part of a capture a finger dragged over
+#[tauri::command(async)]
+#[tracing::instrument(skip(app), err)]
+pub fn mobile_ocr_region(app: AppHandle, capture_id: String, rect: RelativeRect, lang: String) -> AppResult<OcrResult> {
+    let bytes = capture_bytes(&capture_id)?;
+    let image = crate::image_decode::ocr_input(&bytes)?;
+    let decoded = image::load_from_memory(&image).map_err(|e| AppError::Capture(format!("Invalid image: {}", e)))?;
+    let region = pixel_rect(decoded.width(), decoded.height(), rect, REGION_PADDING)?;
+    let cropped = decoded.crop_imm(region.x, region.y, region.width, region.height);
+    let mut png = Vec::new();
+    cropped
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| AppError::Capture(format!("Failed to encode region: {}", e)))?;
+    recognize(&app, &png, &lang)
+}
+
+/// The line of text under a tap, if any
+#[tauri::command(async)]
+#[tracing::instrument(skip(app), err)]
+pub fn mobile_text_at(app: AppHandle, capture_id: String, x: f64, y: f64, lang: String) -> AppResult<Option<OcrLine>> {
+    let bytes = capture_bytes(&capture_id)?;
+    let image = crate::image_decode::ocr_input(&bytes)?;
+    let (width, height) = image::ImageReader::new(std::io::Cursor::new(&image))
+        .with_guessed_format()?
+        .into_dimensions()
+        .map_err(|e| AppError::Capture(format!("Invalid image: {}", e)))?;
+    let result = capture_result(&app, &capture_id, &lang)?;
+    let px = (x.clamp(0.0, 1.0) * width as f64) as u32;
+    let py = (y.clamp(0.0, 1.0) * height as f64) as u32;
+    Ok(line_at(&result, px, py, TAP_TOLERANCE).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr_result::OcrWord;
+
+    #[test]
+    fn test_pixel_rect() {
+        let rect = RelativeRect { x: 0.25, y: 0.5, width: 0.5, height: 0.25 };
+        assert_eq!(
+            pixel_rect(400, 200, rect, 0.0).unwrap(),
+            BoundingBox { x: 100, y: 100, width: 200, height: 50 }
+        );
+        // Padding stays inside the image
+        let corner = RelativeRect { x: 0.0, y: 0.0, width: 0.5, height: 0.5 };
+        assert_eq!(
+            pixel_rect(100, 100, corner, 0.1).unwrap(),
+            BoundingBox { x: 0, y: 0, width: 55, height: 55 }
+        );
+        let empty = RelativeRect { x: 0.5, y: 0.5, width: 0.0, height: 0.1 };
+        assert!(pixel_rect(100, 100, empty, 0.1).is_err());
+    }
+
+    #[test]
+    fn test_line_at() {
+        let word = |text: &str, y: u32, line: u32| {
+            OcrWord::new(text.to_string(), 90.0, BoundingBox { x: 10, y, width: 100, height: 20 }, 0, 0, line)
+        };
+        let result = OcrResult::from_words(vec![word("first", 10, 0), word("second", 60, 1)], "test");
+        assert_eq!(line_at(&result, 50, 20, 0).unwrap().text, "first");
+        // Just below the second line, within reach of a fingertip
+        assert_eq!(line_at(&result, 50, 95, 24).unwrap().text, "second");
+        assert!(line_at(&result, 50, 200, 24).is_none());
+    }
+}
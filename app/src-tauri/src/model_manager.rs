@@ -1,9 +1,79 @@
 // OCR Model (Language Pack) Management
-// Download/manage Tesseract traineddata files from GitHub tessdata_fast
+// Download/manage Tesseract traineddata files from GitHub tessdata_fast,
+// tessdata (standard) and tessdata_best. Fast models live in the bundled tessdata
+// directory; the other tiers are stored separately in the app data directory.
 
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, AppResult};
+
+/// Tessdata quality tier
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TessdataTier {
+    /// tessdata_fast: small integer models (default, bundled)
+    #[default]
+    Fast,
+    /// tessdata: legacy + LSTM models
+    Standard,
+    /// tessdata_best: float LSTM models, slowest but most accurate (notably for CJK)
+    Best,
+}
+
+impl TessdataTier {
+    pub const ALL: [TessdataTier; 3] = [TessdataTier::Fast, TessdataTier::Standard, TessdataTier::Best];
+
+    /// GitHub repository in the tesseract-ocr organisation
+    fn repository(&self) -> &'static str {
+        match self {
+            TessdataTier::Fast => "tessdata_fast",
+            TessdataTier::Standard => "tessdata",
+            TessdataTier::Best => "tessdata_best",
+        }
+    }
+}
+
+/// Per-language model settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ModelsConfig {
+    /// Active tier per language code; languages not listed use `Fast`
+    pub tiers: HashMap<String, TessdataTier>,
+    /// Display names of imported custom models by code
+    pub custom_names: HashMap<String, String>,
+    /// Last OCR use per language (Unix epoch milliseconds)
+    pub last_used: HashMap<String, i64>,
+    /// Fast models downloaded next to the bundled ones; only these fast
+    /// models are ever cleaned up
+    pub downloaded_fast: BTreeSet<String>,
+}
+
+/// Progress of a bulk download
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BulkProgress {
+    pub lang: String,
+    pub completed: usize,
+    pub total: usize,
+    pub error: Option<String>,
+}
+
+/// Result of one model in a bulk download
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BulkResult {
+    pub lang: String,
+    pub error: Option<String>,
+}
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
 
 /// Model information
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -12,6 +82,15 @@ pub struct ModelInfo {
     pub name: String,
     pub installed: bool,
     pub size_bytes: Option<u64>,
+    /// Tiers downloaded for this language
+    #[serde(default)]
+    pub tiers: Vec<TessdataTier>,
+    /// Tier used for OCR
+    #[serde(default)]
+    pub active_tier: TessdataTier,
+    /// User-imported model
+    #[serde(default)]
+    pub custom: bool,
 }
 
 /// Available languages with their display names
@@ -131,51 +210,162 @@ const AVAILABLE_LANGUAGES: &[(&str, &str)] = &[
     ("yor", "Yoruba"),
 ];
 
-/// Get the tessdata directory path
-fn get_tessdata_dir() -> Result<PathBuf, String> {
+/// Get the tessdata directory path (fast tier)
+pub fn get_tessdata_dir() -> AppResult<PathBuf> {
     crate::ocr::get_resource_dir().map(|p| p.join("tessdata"))
 }
 
-/// List all installed OCR models
-pub fn list_installed_models() -> Result<Vec<ModelInfo>, String> {
-    let tessdata_dir = get_tessdata_dir()?;
-    let mut models = Vec::new();
-    
-    if !tessdata_dir.exists() {
-        return Ok(models);
+/// Storage directory for a tier
+pub fn get_tier_dir(tier: TessdataTier) -> AppResult<PathBuf> {
+    match tier {
+        TessdataTier::Fast => get_tessdata_dir(),
+        TessdataTier::Standard => Ok(crate::config::get_app_data_dir()?.join("tessdata_standard")),
+        TessdataTier::Best => Ok(crate::config::get_app_data_dir()?.join("tessdata_best")),
     }
+}
+
+/// Storage directory for user-imported models
+pub fn get_custom_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join("tessdata_custom"))
+}
+
+fn model_file(tier: TessdataTier, lang: &str) -> AppResult<PathBuf> {
+    Ok(get_tier_dir(tier)?.join(format!("{}.traineddata", lang)))
+}
+
+fn custom_model_file(code: &str) -> AppResult<PathBuf> {
+    Ok(get_custom_dir()?.join(format!("{}.traineddata", code)))
+}
+
+fn is_custom_model(code: &str) -> bool {
+    custom_model_file(code).is_ok_and(|p| p.exists())
+}
+
+/// Whether a model is available in any tier or as a custom model
+pub fn is_model_installed(code: &str) -> bool {
+    !installed_tiers(code).is_empty() || is_custom_model(code)
+}
+
+/// Active tier for a language
+pub fn active_tier(lang: &str) -> TessdataTier {
+    crate::config::get()
+        .ok()
+        .and_then(|c| c.models.tiers.get(lang).copied())
+        .unwrap_or_default()
+}
+
+/// Tiers installed for a language
+fn installed_tiers(lang: &str) -> Vec<TessdataTier> {
+    TessdataTier::ALL
+        .into_iter()
+        .filter(|t| model_file(*t, lang).is_ok_and(|p| p.exists()))
+        .collect()
+}
+
+/// Link (or copy, across file systems) `source` to `target` unless it is already there
+fn link_model(source: &Path, target: &Path) -> AppResult<()> {
+    let source_len = fs::metadata(source)?.len();
+    if fs::metadata(target).is_ok_and(|m| m.len() == source_len) {
+        return Ok(());
+    }
+    let _ = fs::remove_file(target);
+    if fs::hard_link(source, target).is_err() {
+        fs::copy(source, target)?;
+    }
+    Ok(())
+}
+
+/// Tessdata directory to use for a tesseract `-l` argument ("eng+jpn").
+/// With only fast models active this is the bundled directory; otherwise the
+/// requested languages are assembled from their tiers into `tessdata_active`.
+pub fn tessdata_dir_for(langs: &str) -> AppResult<PathBuf> {
+    record_usage(langs);
+    let fast_dir = get_tessdata_dir()?;
+    let selected: Vec<(&str, TessdataTier)> = langs
+        .split('+')
+        .filter(|l| !l.is_empty())
+        .map(|l| (l, active_tier(l)))
+        .collect();
+    if selected
+        .iter()
+        .all(|(lang, tier)| *tier == TessdataTier::Fast && !is_custom_model(lang))
+    {
+        return Ok(fast_dir);
+    }
+
+    let active_dir = crate::config::get_app_data_dir()?.join("tessdata_active");
+    fs::create_dir_all(&active_dir)?;
+    for (lang, tier) in selected.iter().copied().chain(std::iter::once(("osd", TessdataTier::Fast))) {
+        let file = format!("{}.traineddata", lang);
+        // Custom models first, then the selected tier, then the fast model
+        let source = [custom_model_file(lang)?, model_file(tier, lang)?]
+            .into_iter()
+            .find(|p| p.exists())
+            .unwrap_or_else(|| fast_dir.join(&file));
+        if source.exists() {
+            link_model(&source, &active_dir.join(&file))?;
+        }
+    }
+    Ok(active_dir)
+}
+
+/// List all installed OCR models
+pub fn list_installed_models() -> AppResult<Vec<ModelInfo>> {
+    let mut models: Vec<ModelInfo> = Vec::new();
     
-    let entries = fs::read_dir(&tessdata_dir).map_err(|e| e.to_string())?;
-    
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.extension().map_or(false, |ext| ext == "traineddata") {
-            if let Some(stem) = path.file_stem() {
-                let code = stem.to_string_lossy().to_string();
-                let name = AVAILABLE_LANGUAGES
-                    .iter()
-                    .find(|(c, _)| *c == code)
-                    .map(|(_, n)| n.to_string())
-                    .unwrap_or_else(|| code.clone());
-                
-                let size = fs::metadata(&path).map(|m| m.len()).ok();
-                
-                models.push(ModelInfo {
-                    code,
-                    name,
-                    installed: true,
-                    size_bytes: size,
-                });
+    // A language counts as installed if any tier has it
+    for tier in TessdataTier::ALL {
+        let tessdata_dir = get_tier_dir(tier)?;
+        if !tessdata_dir.exists() {
+            continue;
+        }
+        
+        let entries = fs::read_dir(&tessdata_dir)?;
+        
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "traineddata") {
+                if let Some(stem) = path.file_stem() {
+                    let code = stem.to_string_lossy().to_string();
+                    if models.iter().any(|m| m.code == code) {
+                        continue;
+                    }
+                    let name = AVAILABLE_LANGUAGES
+                        .iter()
+                        .find(|(c, _)| *c == code)
+                        .map(|(_, n)| n.to_string())
+                        .unwrap_or_else(|| code.clone());
+                    
+                    // Report the size of the model actually used for OCR
+                    let active = active_tier(&code);
+                    let size = model_file(active, &code)
+                        .ok()
+                        .and_then(|p| fs::metadata(p).ok())
+                        .or_else(|| fs::metadata(&path).ok())
+                        .map(|m| m.len());
+                    
+                    models.push(ModelInfo {
+                        tiers: installed_tiers(&code),
+                        active_tier: active,
+                        code,
+                        name,
+                        installed: true,
+                        size_bytes: size,
+                        custom: false,
+                    });
+                }
             }
         }
     }
     
+    models.extend(list_custom_models()?);
+    
     models.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(models)
 }
 
 /// List all available models (installed + not installed)
-pub fn list_available_models() -> Result<Vec<ModelInfo>, String> {
+pub fn list_available_models() -> AppResult<Vec<ModelInfo>> {
     let installed = list_installed_models()?;
     let installed_codes: std::collections::HashSet<_> = 
         installed.iter().map(|m| m.code.as_str()).collect();
@@ -197,74 +387,323 @@ pub fn list_available_models() -> Result<Vec<ModelInfo>, String> {
                 name: name.to_string(),
                 installed: is_installed,
                 size_bytes: size,
+                tiers: installed_tiers(code),
+                active_tier: active_tier(code),
+                custom: false,
             }
         })
         .collect();
+    models.extend(installed.into_iter().filter(|m| m.custom));
     
     models.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(models)
 }
 
-/// Download a model from GitHub tessdata_fast
-pub fn download_model(lang: &str) -> Result<(), String> {
-    let tessdata_dir = get_tessdata_dir()?;
+/// Download a model of the given tier from GitHub
+pub fn download_model(lang: &str, tier: TessdataTier) -> AppResult<()> {
+    let tessdata_dir = get_tier_dir(tier)?;
     let target_path = tessdata_dir.join(format!("{}.traineddata", lang));
     
     // Check if already exists
     if target_path.exists() {
-        return Err(format!("Model '{}' is already installed", lang));
+        return Err(AppError::InvalidInput(format!("Model '{}' is already installed", lang)));
     }
+    fs::create_dir_all(&tessdata_dir)?;
     
     // Download URL
     let url = format!(
-        "https://raw.githubusercontent.com/tesseract-ocr/tessdata_fast/main/{}.traineddata",
+        "https://raw.githubusercontent.com/tesseract-ocr/{}/main/{}.traineddata",
+        tier.repository(),
         lang
     );
     
-    // Download using reqwest (blocking)
-    let response = reqwest::blocking::get(&url)
-        .map_err(|e| format!("Failed to download: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Err(format!(
-            "Failed to download model '{}': HTTP {}",
-            lang,
-            response.status()
-        ));
+    crate::downloads::fetch_blocking(&url, &target_path)?;
+    if tier == TessdataTier::Fast {
+        crate::config::update(|c| {
+            c.models.downloaded_fast.insert(lang.to_string());
+        })?;
     }
     
-    let bytes = response.bytes().map_err(|e| format!("Failed to read response: {}", e))?;
-    
-    // Write to file
-    let mut file = fs::File::create(&target_path)
-        .map_err(|e| format!("Failed to create file: {}", e))?;
-    file.write_all(&bytes)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+    // Use the new tier right away if there is no fast model to fall back on
+    if tier != TessdataTier::Fast && !model_file(TessdataTier::Fast, lang)?.exists() {
+        set_active_tier(lang, tier)?;
+    }
     
     Ok(())
 }
 
-/// Delete a model
-pub fn delete_model(lang: &str) -> Result<(), String> {
-    let tessdata_dir = get_tessdata_dir()?;
-    let target_path = tessdata_dir.join(format!("{}.traineddata", lang));
+/// Delete a model of the given tier (custom models have no tiers)
+pub fn delete_model(lang: &str, tier: TessdataTier) -> AppResult<()> {
+    if is_custom_model(lang) {
+        fs::remove_file(custom_model_file(lang)?)?;
+        return crate::config::update(|c| {
+            c.models.custom_names.remove(lang);
+        });
+    }
     
-    // Prevent deleting essential models
+    let target_path = model_file(tier, lang)?;
+    
+    // Prevent deleting essential models (other tiers fall back to fast)
     let protected = ["eng", "osd"];
-    if protected.contains(&lang) {
-        return Err(format!("Cannot delete essential model '{}'", lang));
+    if tier == TessdataTier::Fast && protected.contains(&lang) {
+        return Err(AppError::InvalidInput(format!("Cannot delete essential model '{}'", lang)));
     }
     
     if !target_path.exists() {
-        return Err(format!("Model '{}' is not installed", lang));
+        return Err(AppError::ModelMissing(lang.to_string()));
+    }
+    
+    fs::remove_file(&target_path)?;
+    if tier == TessdataTier::Fast {
+        crate::config::update(|c| {
+            c.models.downloaded_fast.remove(lang);
+        })?;
+    }
+    
+    // Switch back to fast when the active tier was removed
+    if tier != TessdataTier::Fast && active_tier(lang) == tier {
+        set_active_tier(lang, TessdataTier::Fast)?;
+    }
+    
+    Ok(())
+}
+
+/// Select the tier used for a language
+pub fn set_active_tier(lang: &str, tier: TessdataTier) -> AppResult<()> {
+    if tier != TessdataTier::Fast && !model_file(tier, lang)?.exists() {
+        return Err(AppError::ModelMissing(format!("{} ({:?})", lang, tier)));
+    }
+    crate::config::update(|c| {
+        if tier == TessdataTier::Fast {
+            c.models.tiers.remove(lang);
+        } else {
+            c.models.tiers.insert(lang.to_string(), tier);
+        }
+    })
+}
+
+/// List user-imported models
+fn list_custom_models() -> AppResult<Vec<ModelInfo>> {
+    let custom_dir = get_custom_dir()?;
+    if !custom_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let names = crate::config::get()?.models.custom_names;
+    
+    let mut models = Vec::new();
+    for entry in fs::read_dir(&custom_dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "traineddata") {
+            if let Some(stem) = path.file_stem() {
+                let code = stem.to_string_lossy().to_string();
+                models.push(ModelInfo {
+                    name: names.get(&code).cloned().unwrap_or_else(|| code.clone()),
+                    code,
+                    installed: true,
+                    size_bytes: fs::metadata(&path).map(|m| m.len()).ok(),
+                    tiers: Vec::new(),
+                    active_tier: TessdataTier::Fast,
+                    custom: true,
+                });
+            }
+        }
     }
+    Ok(models)
+}
+
+/// Check that data looks like a tesseract traineddata archive: an i32 entry
+/// count followed by that many i64 component offsets (-1 = absent), each
+/// pointing inside the file
+fn validate_traineddata(data: &[u8]) -> AppResult<()> {
+    let invalid = |reason: &str| AppError::InvalidInput(format!("Not a valid traineddata file: {}", reason));
     
-    fs::remove_file(&target_path)
-        .map_err(|e| format!("Failed to delete model: {}", e))?;
+    let count_bytes: [u8; 4] = data.get(..4).and_then(|b| b.try_into().ok()).ok_or_else(|| invalid("file too small"))?;
+    let count = i32::from_le_bytes(count_bytes);
+    if !(1..=64).contains(&count) {
+        return Err(invalid("bad header"));
+    }
+    let header_len = 4 + 8 * count as usize;
+    if data.len() <= header_len {
+        return Err(invalid("file too small"));
+    }
     
+    let mut present = 0;
+    for i in 0..count as usize {
+        let start = 4 + 8 * i;
+        let offset = i64::from_le_bytes(data[start..start + 8].try_into().unwrap());
+        if offset == -1 {
+            continue;
+        }
+        if offset < header_len as i64 || offset >= data.len() as i64 {
+            return Err(invalid("component offset out of range"));
+        }
+        present += 1;
+    }
+    if present == 0 {
+        return Err(invalid("no components"));
+    }
     Ok(())
 }
 
+/// Install a user-provided .traineddata file under `code`
+pub fn import_custom_traineddata(path: &Path, code: &str, name: &str) -> AppResult<()> {
+    let valid_code = !code.is_empty()
+        && code.len() <= 64
+        && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid_code {
+        return Err(AppError::InvalidInput(
+            "Model code may only contain letters, digits, '_' and '-'".to_string(),
+        ));
+    }
+    if AVAILABLE_LANGUAGES.iter().any(|(c, _)| *c == code) {
+        return Err(AppError::InvalidInput(format!(
+            "'{}' is an official language code, choose another name",
+            code
+        )));
+    }
+    
+    let data = fs::read(path)?;
+    validate_traineddata(&data)?;
+    
+    let custom_dir = get_custom_dir()?;
+    fs::create_dir_all(&custom_dir)?;
+    fs::write(custom_model_file(code)?, &data)?;
+    
+    let name = if name.trim().is_empty() { code } else { name.trim() };
+    crate::config::update(|c| {
+        c.models.custom_names.insert(code.to_string(), name.to_string());
+    })
+}
+
+/// Remember when languages were last used. Stored at most once per day per
+/// language so OCR calls don't rewrite the config every time.
+fn record_usage(langs: &str) {
+    let now = now_ms();
+    let stale: Vec<String> = match crate::config::get() {
+        Ok(config) => langs
+            .split('+')
+            .filter(|l| !l.is_empty())
+            .filter(|l| config.models.last_used.get(*l).is_none_or(|t| now - t > DAY_MS))
+            .map(|l| l.to_string())
+            .collect(),
+        Err(_) => return,
+    };
+    if !stale.is_empty() {
+        let _ = crate::config::update(|c| {
+            for lang in stale {
+                c.models.last_used.insert(lang, now);
+            }
+        });
+    }
+}
+
+/// Download several models in parallel, reporting progress after each one.
+/// Already installed models count as done.
+pub fn download_models(
+    langs: &[String],
+    tier: TessdataTier,
+    on_progress: impl Fn(BulkProgress) + Sync,
+) -> Vec<BulkResult> {
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    
+    let completed = AtomicUsize::new(0);
+    let total = langs.len();
+    
+    langs
+        .par_iter()
+        .map(|lang| {
+            let error = match download_model(lang, tier) {
+                Ok(()) => None,
+                Err(_) if model_file(tier, lang).is_ok_and(|p| p.exists()) => None,
+                Err(e) => Some(e.to_string()),
+            };
+            on_progress(BulkProgress {
+                lang: lang.clone(),
+                completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                total,
+                error: error.clone(),
+            });
+            BulkResult { lang: lang.clone(), error }
+        })
+        .collect()
+}
+
+/// Downloaded models in `dirs` not used since `cutoff`, as (code, tier).
+/// Models that were never used count from their download time. Fast models
+/// are bundled with the app unless listed in `downloaded_fast`; essential
+/// models are kept.
+fn unused_models(
+    dirs: &[(TessdataTier, PathBuf)],
+    downloaded_fast: &BTreeSet<String>,
+    last_used: &HashMap<String, i64>,
+    cutoff: i64,
+) -> Vec<(String, TessdataTier)> {
+    let mut unused = Vec::new();
+    for (tier, dir) in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "traineddata") {
+                continue;
+            }
+            let Some(code) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            if ["eng", "osd"].contains(&code.as_str()) {
+                continue;
+            }
+            if *tier == TessdataTier::Fast && !downloaded_fast.contains(&code) {
+                continue;
+            }
+
+            let used = last_used.get(&code).copied().or_else(|| {
+                fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as i64)
+            });
+            if used.is_some_and(|t| t < cutoff) {
+                unused.push((code, *tier));
+            }
+        }
+    }
+    unused.sort_by_key(|(code, tier)| (code.clone(), *tier as u8));
+    unused
+}
+
+/// Delete downloaded models not used for OCR in `keep_days` days (see
+/// `unused_models`). Returns the deleted "code (tier)" entries; models that
+/// fail to delete are skipped and logged, and only fail the call when
+/// nothing was deleted.
+pub fn delete_unused_models(keep_days: u32) -> AppResult<Vec<String>> {
+    let cutoff = now_ms() - keep_days as i64 * DAY_MS;
+    let models = crate::config::get()?.models;
+    let dirs = TessdataTier::ALL
+        .into_iter()
+        .map(|tier| get_tier_dir(tier).map(|dir| (tier, dir)))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let mut deleted = Vec::new();
+    let mut errors = Vec::new();
+    for (code, tier) in unused_models(&dirs, &models.downloaded_fast, &models.last_used, cutoff) {
+        match delete_model(&code, tier) {
+            Ok(()) => deleted.push(format!("{} ({:?})", code, tier)),
+            Err(e) => {
+                tracing::warn!("Failed to delete unused model {} ({:?}): {}", code, tier, e);
+                errors.push(format!("{} ({:?}): {}", code, tier, e));
+            }
+        }
+    }
+    if deleted.is_empty() && !errors.is_empty() {
+        return Err(AppError::Other(format!("Failed to delete unused models: {}", errors.join("; "))));
+    }
+    Ok(deleted)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,4 +721,54 @@ mod tests {
         let models = result.unwrap();
         assert!(!models.is_empty());
     }
+    
+    #[test]
+    fn test_unused_models() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (bundled, best) = (tmp.path().join("tessdata"), tmp.path().join("tessdata_best"));
+        fs::create_dir_all(&bundled).unwrap();
+        fs::create_dir_all(&best).unwrap();
+        for file in ["eng", "deu", "fra"] {
+            fs::write(bundled.join(format!("{}.traineddata", file)), b"x").unwrap();
+        }
+        fs::write(best.join("deu.traineddata"), b"x").unwrap();
+        fs::write(best.join("jpn.traineddata"), b"x").unwrap();
+        fs::write(best.join("notes.txt"), b"x").unwrap();
+
+        let dirs = [(TessdataTier::Fast, bundled), (TessdataTier::Best, best)];
+        let downloaded_fast = BTreeSet::from(["fra".to_string()]);
+        let last_used = HashMap::from([("jpn".to_string(), now_ms())]);
+        // Everything on disk is older than a cutoff in the future
+        let unused = unused_models(&dirs, &downloaded_fast, &last_used, now_ms() + DAY_MS);
+        // Bundled deu, essential eng and the text file are never candidates
+        assert_eq!(
+            unused,
+            vec![
+                ("deu".to_string(), TessdataTier::Best),
+                ("fra".to_string(), TessdataTier::Fast),
+                ("jpn".to_string(), TessdataTier::Best),
+            ]
+        );
+        // jpn was just used
+        let unused = unused_models(&dirs, &downloaded_fast, &last_used, now_ms() - DAY_MS);
+        assert!(unused.is_empty());
+    }
+
+    #[test]
+    fn test_validate_traineddata() {
+        // Two components: one present at offset 20, one absent
+        let mut data = 2i32.to_le_bytes().to_vec();
+        data.extend_from_slice(&20i64.to_le_bytes());
+        data.extend_from_slice(&(-1i64).to_le_bytes());
+        data.extend_from_slice(b"unicharset");
+        assert!(validate_traineddata(&data).is_ok());
+        
+        assert!(validate_traineddata(b"\x89PNG\r\n\x1a\n....").is_err());
+        assert!(validate_traineddata(&[]).is_err());
+        
+        let mut out_of_range = 1i32.to_le_bytes().to_vec();
+        out_of_range.extend_from_slice(&999i64.to_le_bytes());
+        out_of_range.extend_from_slice(b"data");
+        assert!(validate_traineddata(&out_of_range).is_err());
+    }
 }
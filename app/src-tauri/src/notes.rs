@@ -0,0 +1,244 @@
+// Send to notes
+// Appends a capture to a note in an Obsidian or Logseq vault: the OCR text
+// and translation through an output template, with the screenshot saved to
+// the vault's attachment folder and embedded below. New notes start with
+// YAML front-matter. The note path takes strftime fields, so the default
+// collects a day's captures in one note. Vaults with a "logseq" folder get
+// relative Markdown image links, others Obsidian's `![[...]]` embeds.
+
+use std::fmt::Write as _;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+use crate::error::{AppError, AppResult};
+use crate::templates::TemplateContext;
+
+/// Notes vault settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NotesConfig {
+    pub vault_path: Option<String>,
+    /// Note to append to, relative to the vault, with strftime fields
+    pub note_path: String,
+    /// Output template for each entry
+    pub note_template: String,
+    /// Screenshots are saved here, relative to the vault
+    pub attachments_dir: String,
+    /// Tags in the front-matter of new notes
+    pub tags: Vec<String>,
+}
+
+impl Default for NotesConfig {
+    fn default() -> Self {
+        Self {
+            vault_path: None,
+            note_path: "Screen Inu/%Y-%m-%d.md".to_string(),
+            note_template: "## {time}\n\n{text}\n\n{translation|quote}".to_string(),
+            attachments_dir: "attachments".to_string(),
+            tags: vec!["screen-inu".to_string()],
+        }
+    }
+}
+
+/// One capture to append
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NoteEntry {
+    pub text: String,
+    pub translation: Option<String>,
+    pub lang: Option<String>,
+    pub source_app: Option<String>,
+    /// The screenshot, by capture id or as base64; none is embedded when unset
+    pub capture_id: Option<String>,
+    pub base64_image: Option<String>,
+}
+
+/// strftime fields of `pattern` filled in with `time`
+fn format_time(pattern: &str, time: &DateTime<Local>) -> AppResult<String> {
+    let mut out = String::new();
+    write!(out, "{}", time.format(pattern))
+        .map_err(|_| AppError::InvalidInput(format!("Invalid date format in {}", pattern)))?;
+    Ok(out)
+}
+
+/// A path inside the vault; absolute paths and ".." are refused
+fn vault_relative(path: &str) -> AppResult<PathBuf> {
+    let relative = PathBuf::from(path.trim());
+    if relative.as_os_str().is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(AppError::InvalidInput(format!("Not a path inside the vault: {}", path)));
+    }
+    Ok(relative)
+}
+
+fn front_matter(time: &DateTime<Local>, tags: &[String]) -> String {
+    let mut yaml = format!("---\ncreated: {}\nsource: Screen Inu\n", time.to_rfc3339());
+    if !tags.is_empty() {
+        yaml.push_str("tags:\n");
+        for tag in tags {
+            yaml.push_str(&format!("  - {}\n", tag));
+        }
+    }
+    yaml.push_str("---\n");
+    yaml
+}
+
+/// Embed of an attachment in a note, both relative to the vault
+fn image_link(note: &Path, attachment: &Path, logseq: bool) -> String {
+    let attachment = attachment.to_string_lossy().replace('\\', "/");
+    if !logseq {
+        return format!("![[{}]]", attachment);
+    }
+    let depth = note.parent().map_or(0, |dir| dir.components().count());
+    format!("![screenshot]({}{})", "../".repeat(depth), attachment)
+}
+
+/// `existing` with `entry` appended, after a blank line
+fn append(existing: &str, entry: &str) -> String {
+    let mut note = existing.to_string();
+    if !note.is_empty() {
+        let newlines = note.len() - note.trim_end_matches('\n').len();
+        note.push_str(&"\n".repeat(2usize.saturating_sub(newlines)));
+    }
+    note.push_str(entry.trim_end());
+    note.push('\n');
+    note
+}
+
+/// Screenshot bytes of an entry, if it has one
+fn entry_image(entry: &NoteEntry) -> AppResult<Option<Vec<u8>>> {
+    if let Some(id) = &entry.capture_id {
+        return crate::capture_protocol::get(id)
+            .map(|(_, bytes)| Some(bytes))
+            .ok_or_else(|| AppError::InvalidInput(format!("Capture {} is no longer available", id)));
+    }
+    entry.base64_image.as_deref().map(crate::ocr::decode_base64_image).transpose()
+}
+
+/// Append `entry` to the note for `time`; returns the note's path
+pub fn append_entry(config: &NotesConfig, vault: &Path, entry: &NoteEntry, time: DateTime<Local>) -> AppResult<PathBuf> {
+    if !vault.is_dir() {
+        return Err(AppError::InvalidInput(format!("Vault not found: {}", vault.display())));
+    }
+    let logseq = vault.join("logseq").is_dir();
+    let note = vault_relative(&format_time(&config.note_path, &time)?)?;
+
+    let context = TemplateContext {
+        translation: entry.translation.clone(),
+        lang: entry.lang.clone(),
+        app: entry.source_app.clone(),
+        timestamp: Some(time.timestamp_millis()),
+    };
+    let mut text = crate::templates::render(&config.note_template, &entry.text, &context)?
+        .trim_end()
+        .to_string();
+
+    if let Some(bytes) = entry_image(entry)? {
+        let extension = image::guess_format(&bytes)
+            .ok()
+            .and_then(|format| format.extensions_str().first().copied())
+            .unwrap_or("png");
+        let name = format!("screen-inu-{}.{}", time.format("%Y%m%d-%H%M%S-%3f"), extension);
+        let attachment = vault_relative(&config.attachments_dir)?.join(name);
+        let path = vault.join(&attachment);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, bytes)?;
+        text.push_str("\n\n");
+        text.push_str(&image_link(&note, &attachment, logseq));
+    }
+
+    let path = vault.join(&note);
+    let existing = match std::fs::read_to_string(&path) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            front_matter(&time, &config.tags)
+        }
+        Err(e) => return Err(e.into()),
+    };
+    std::fs::write(&path, append(&existing, &text))?;
+    Ok(path)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_notes_config() -> AppResult<NotesConfig> {
+    Ok(crate::config::get()?.notes)
+}
+
+#[tauri::command]
+pub fn set_notes_config(config: NotesConfig) -> AppResult<()> {
+    vault_relative(&format_time(&config.note_path, &Local::now())?)?;
+    vault_relative(&config.attachments_dir)?;
+    crate::config::update(|c| c.notes = config)
+}
+
+/// Append a capture to a note in a vault. `vault_path` and `note_template`
+/// override the configured ones. Returns the note's path.
+#[tauri::command(async)]
+#[tracing::instrument(skip(entry), err)]
+pub fn append_to_note(entry: NoteEntry, vault_path: Option<String>, note_template: Option<String>) -> AppResult<String> {
+    let mut config = crate::config::get()?.notes;
+    if let Some(template) = note_template {
+        config.note_template = template;
+    }
+    let vault = vault_path
+        .or_else(|| config.vault_path.clone())
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| AppError::InvalidInput("No notes vault is configured".to_string()))?;
+    let path = append_entry(&config, Path::new(&vault), &entry, Local::now())?;
+    Ok(path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn png() -> String {
+        use base64::Engine;
+        let mut bytes = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(2, 2))
+            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    #[test]
+    fn test_append_entry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let vault = tmp.path();
+        let config = NotesConfig::default();
+        let time = Local.with_ymd_and_hms(2026, 3, 14, 9, 30, 0).unwrap();
+        let entry = NoteEntry {
+            text: "猫".to_string(),
+            translation: Some("cat".to_string()),
+            base64_image: Some(png()),
+            ..Default::default()
+        };
+        let path = append_entry(&config, vault, &entry, time).unwrap();
+        assert_eq!(path, vault.join("Screen Inu/2026-03-14.md"));
+        append_entry(&config, vault, &NoteEntry { text: "犬".to_string(), ..Default::default() }, time).unwrap();
+
+        let note = std::fs::read_to_string(&path).unwrap();
+        assert!(note.starts_with("---\ncreated: 2026-03-14T09:30:00"));
+        assert!(note.contains("tags:\n  - screen-inu\n---\n\n## 09:30\n\n猫\n\n> cat\n\n![[attachments/screen-inu-20260314-093000-000.png]]\n\n## 09:30\n\n犬\n"));
+        assert!(vault.join("attachments/screen-inu-20260314-093000-000.png").is_file());
+    }
+
+    #[test]
+    fn test_paths() {
+        assert!(vault_relative("../outside.md").is_err());
+        assert!(vault_relative("/etc/passwd").is_err());
+        let link = image_link(Path::new("pages/Screen Inu.md"), Path::new("assets/a.png"), true);
+        assert_eq!(link, "![screenshot](../assets/a.png)");
+        assert_eq!(append("x\n", "y"), "x\n\ny\n");
+    }
+}
@@ -0,0 +1,139 @@
+// Native notifications
+// Results of background pipelines (controller hotkeys, scheduled captures) are
+// announced with a notification showing a preview of the text, instead of
+// landing silently in history while the window is hidden.
+//
+// Linux notification servers support action buttons (Copy / Open history). The
+// desktop notification APIs used on Windows and macOS have no buttons; clicking
+// the notification brings the app to the front.
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::error::{AppError, AppResult};
+
+/// Emitted with `{ action }` when a notification action asks the UI to do something
+pub const NOTIFICATION_ACTION_EVENT: &str = "notification://action";
+
+/// Notification settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NotificationsConfig {
+    /// Notify about background results
+    pub enabled: bool,
+    /// Skip the notification when the main window is visible
+    pub only_when_hidden: bool,
+    /// Characters of text shown in the notification
+    pub preview_chars: usize,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            only_when_hidden: true,
+            preview_chars: 120,
+        }
+    }
+}
+
+/// Single-line preview of `text`, at most `max_chars` long
+pub fn preview(text: &str, max_chars: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= max_chars {
+        return collapsed;
+    }
+    let mut truncated: String = collapsed.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn main_window_visible(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false)
+}
+
+/// Run a notification action on the text it was shown for
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn handle_action(app: &AppHandle, action: &str, text: &str) {
+    match action {
+        "copy" => {
+            let outcome = crate::actions::run_actions(app, text, None, &[crate::actions::PostOcrAction::Copy]);
+            if let Some(error) = outcome.into_iter().find_map(|o| o.error) {
+                tracing::warn!("Notification copy failed: {}", error);
+            }
+        }
+        "history" | "default" => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit(NOTIFICATION_ACTION_EVENT, serde_json::json!({ "action": "openHistory" }));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn show_with_actions(app: &AppHandle, title: &str, body: &str, text: &str) -> AppResult<()> {
+    let handle = notify_rust::Notification::new()
+        .appname("Screen Inu")
+        .summary(title)
+        .body(body)
+        .action("copy", &crate::i18n::t("notification-copy"))
+        .action("history", &crate::i18n::t("notification-open-history"))
+        .show()
+        .map_err(|e| AppError::Other(format!("Failed to show notification: {}", e)))?;
+
+    let app = app.clone();
+    let text = text.to_string();
+    std::thread::spawn(move || handle.wait_for_action(|action| handle_action(&app, action, &text)));
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn show_with_actions(app: &AppHandle, title: &str, body: &str, _text: &str) -> AppResult<()> {
+    show(app, title, body)
+}
+
+/// Show a plain notification
+pub fn show(app: &AppHandle, title: &str, body: &str) -> AppResult<()> {
+    app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| AppError::Other(format!("Failed to show notification: {}", e)))
+}
+
+/// Announce a background result, honoring the notification settings
+pub fn notify_result(app: &AppHandle, title: &str, text: &str) -> AppResult<()> {
+    let config = crate::config::get()?.notifications;
+    if !config.enabled || text.trim().is_empty() || (config.only_when_hidden && main_window_visible(app)) {
+        return Ok(());
+    }
+    show_with_actions(app, title, &preview(text, config.preview_chars), text)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Announce a result from the frontend (e.g. a hotkey capture finished while hidden)
+#[tauri::command]
+pub fn show_result_notification(app: AppHandle, title: String, text: String) -> AppResult<()> {
+    notify_result(&app, &title, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview() {
+        assert_eq!(preview("  hello\n\n world ", 20), "hello world");
+        assert_eq!(preview("abcdefghij", 5), "abcd…");
+        assert_eq!(preview("日本語のテキスト", 4), "日本語…");
+    }
+}
@@ -1,8 +1,10 @@
 // OCR Engine abstraction and implementations
 // Supports: Tesseract (all platforms), Windows OCR (Windows), Apple Vision (macOS)
 
-use std::fs::File;
-use std::io::Write;
+use crate::error::{AppError, AppResult};
+use crate::ocr_result::{parse_tesseract_tsv, OcrResult};
+#[cfg(windows)]
+use crate::ocr_result::{BoundingBox, OcrWord};
 
 /// OCR Engine types
 #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -21,6 +23,169 @@ impl Default for OcrEngine {
     }
 }
 
+impl OcrEngine {
+    /// Engine name used by the frontend, see `parse_engine`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OcrEngine::Tesseract => "tesseract",
+            #[cfg(windows)]
+            OcrEngine::WindowsOcr => "windows",
+            #[cfg(target_os = "macos")]
+            OcrEngine::AppleVision => "apple",
+            OcrEngine::Auto => "auto",
+        }
+    }
+}
+
+/// Options shared by all OCR entry points
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrOptions {
+    pub langs: Option<String>,
+    pub engine: Option<String>,
+    /// Vertical CJK text (manga, traditional documents)
+    #[serde(default)]
+    pub vertical: bool,
+    /// Source code: keep indentation and line breaks
+    #[serde(default)]
+    pub code_mode: bool,
+    /// Group lines into reading-ordered blocks (multi-column pages, chats)
+    #[serde(default)]
+    pub layout: bool,
+    /// Tesseract tuning; ignored by the native engines
+    #[serde(default)]
+    pub tesseract: TesseractParams,
+    /// Retry low-confidence results; entry points fill it from the config
+    /// with `with_reocr_config`
+    #[serde(skip)]
+    pub reocr: Option<crate::reocr::ReocrConfig>,
+}
+
+/// Tesseract parameters for a single request
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TesseractParams {
+    /// Page segmentation mode (0-13); defaults to 6, a single uniform block
+    pub psm: Option<u8>,
+    /// OCR engine mode (0-3)
+    pub oem: Option<u8>,
+    /// Only recognize these characters (e.g. "0123456789" for receipts)
+    pub whitelist: Option<String>,
+    /// Never recognize these characters
+    pub blacklist: Option<String>,
+    /// Extra `-c name=value` config variables
+    pub config: std::collections::BTreeMap<String, String>,
+}
+
+impl TesseractParams {
+    /// Command-line arguments for these parameters, using `default_psm` if unset
+    pub fn to_args(&self, default_psm: u8) -> AppResult<Vec<String>> {
+        let psm = self.psm.unwrap_or(default_psm);
+        if psm > 13 {
+            return Err(AppError::InvalidInput(format!("Invalid page segmentation mode: {}", psm)));
+        }
+        let mut args = vec!["--psm".to_string(), psm.to_string()];
+
+        if let Some(oem) = self.oem {
+            if oem > 3 {
+                return Err(AppError::InvalidInput(format!("Invalid OCR engine mode: {}", oem)));
+            }
+            args.extend(["--oem".to_string(), oem.to_string()]);
+        }
+
+        let chars = [
+            ("tessedit_char_whitelist", &self.whitelist),
+            ("tessedit_char_blacklist", &self.blacklist),
+        ];
+        let variables = chars
+            .into_iter()
+            .filter_map(|(name, value)| value.as_deref().map(|v| (name, v)))
+            .chain(self.config.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        for (name, value) in variables {
+            let valid_name = !name.is_empty()
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_name || value.contains(['\n', '\r', '\0']) {
+                return Err(AppError::InvalidInput(format!("Invalid Tesseract variable: {}", name)));
+            }
+            args.extend(["-c".to_string(), format!("{}={}", name, value)]);
+        }
+        Ok(args)
+    }
+}
+
+impl OcrOptions {
+    pub fn lang(&self) -> &str {
+        self.langs.as_deref().unwrap_or("eng")
+    }
+
+    pub fn engine(&self) -> OcrEngine {
+        parse_engine(self.engine.as_deref())
+    }
+
+    /// These options with the configured re-OCR settings
+    pub fn with_reocr_config(self) -> Self {
+        Self {
+            reocr: crate::config::get().ok().map(|c| c.reocr),
+            ..self
+        }
+    }
+}
+
+/// Parse an engine name coming from the frontend
+pub fn parse_engine(name: Option<&str>) -> OcrEngine {
+    match name {
+        Some("tesseract") => OcrEngine::Tesseract,
+        #[cfg(windows)]
+        Some("windows") => OcrEngine::WindowsOcr,
+        #[cfg(target_os = "macos")]
+        Some("apple") => OcrEngine::AppleVision,
+        _ => OcrEngine::Auto,
+    }
+}
+
+/// Decode a base64 image, with or without a `data:` URL header
+pub fn decode_base64_image(base64_image: &str) -> AppResult<Vec<u8>> {
+    use base64::Engine;
+
+    // Remove header if present
+    let base64_data = base64_image.split(',').last().unwrap_or(base64_image);
+
+    base64::engine::general_purpose::STANDARD
+        .decode(base64_data)
+        .map_err(|e| AppError::InvalidInput(format!("Base64 decode error: {}", e)))
+}
+
+/// Run OCR with the given options, handling language auto-detection, then
+/// pass the text through the OCR-stage plugins
+#[tracing::instrument(skip_all, err, fields(langs = options.lang(), engine = ?options.engine(), bytes = image_bytes.len()))]
+pub fn run_ocr(image_bytes: &[u8], options: &OcrOptions) -> AppResult<String> {
+    let image_bytes = &crate::image_decode::ocr_input(image_bytes)?;
+    let balanced = crate::white_balance::normalize_if_enabled(image_bytes);
+    let text = crate::perf::time(&crate::perf::ocr_metric(resolved_engine(options)), || {
+        recognize(balanced.as_deref().unwrap_or(image_bytes), options)
+    })?;
+    Ok(crate::plugins::apply(crate::plugins::PluginStage::Ocr, &text))
+}
+
+fn recognize(image_bytes: &[u8], options: &OcrOptions) -> AppResult<String> {
+    if let Some(reocr) = options.reocr.as_ref().filter(|reocr| reocr.enabled) {
+        let best = crate::reocr::run_best(image_bytes, options, reocr)?.result;
+        let layout = options.layout && !options.vertical && !options.code_mode;
+        return Ok(if layout { crate::layout::analyze(&best).text } else { best.text });
+    }
+    if options.layout && !options.vertical && !options.code_mode {
+        return recognize_detailed(image_bytes, options).map(|r| crate::layout::analyze(&r).text);
+    }
+    if options.vertical || options.code_mode {
+        return recognize_detailed(image_bytes, options).map(|r| r.text);
+    }
+    if options.lang() == "auto" {
+        perform_auto_ocr(image_bytes, options.engine(), &options.tesseract)
+    } else {
+        perform_ocr_with_engine(image_bytes, options.lang(), options.engine(), &options.tesseract)
+    }
+}
+
 /// Get the best OCR engine for a given language
 pub fn get_best_engine_for_language(lang: &str) -> OcrEngine {
     // For CJK languages, prefer native OCR on Windows/macOS
@@ -42,119 +207,257 @@ pub fn get_best_engine_for_language(lang: &str) -> OcrEngine {
     OcrEngine::Tesseract
 }
 
-/// Map Tesseract language code to Windows OCR language tag
-#[cfg(windows)]
-fn tesseract_lang_to_windows(lang: &str) -> Option<&'static str> {
-    // Only take the first language if multiple are specified
-    let primary_lang = lang.split('+').next().unwrap_or(lang);
-    
-    match primary_lang {
-        "eng" => Some("en-US"),
-        "chi_tra" => Some("zh-Hant-TW"),
-        "chi_sim" => Some("zh-Hans-CN"),
-        "jpn" => Some("ja-JP"),
-        "kor" => Some("ko-KR"),
-        "fra" => Some("fr-FR"),
-        "deu" => Some("de-DE"),
-        "spa" => Some("es-ES"),
-        "ita" => Some("it-IT"),
-        "por" => Some("pt-BR"),
-        "rus" => Some("ru-RU"),
-        "vie" => Some("vi-VN"),
-        _ => None,
-    }
+/// Map a Tesseract language code to the BCP-47 prefix the native engines
+/// (Windows OCR, Vision, ML Kit) use. On Windows the full tag (e.g. "en-US"
+/// vs "en-GB") is resolved against the installed recognizer languages at
+/// runtime.
+#[cfg(any(windows, mobile))]
+pub(crate) fn tesseract_lang_to_bcp47(lang: &str) -> Option<&'static str> {
+    Some(match lang {
+        "chi_tra" | "chi_tra_vert" => "zh-Hant",
+        "chi_sim" | "chi_sim_vert" => "zh-Hans",
+        "jpn" | "jpn_vert" => "ja",
+        "kor" | "kor_vert" => "ko",
+        "eng" => "en",
+        "ara" => "ar",
+        "bos" => "bs",
+        "bul" => "bg",
+        "ces" => "cs",
+        "dan" => "da",
+        "deu" => "de",
+        "ell" => "el",
+        "fin" => "fi",
+        "fra" => "fr",
+        "hrv" => "hr",
+        "hun" => "hu",
+        "ita" => "it",
+        "nld" => "nl",
+        "nor" => "nb",
+        "pol" => "pl",
+        "por" => "pt",
+        "ron" => "ro",
+        "rus" => "ru",
+        "slk" => "sk",
+        "slv" => "sl",
+        "spa" => "es",
+        "srp" => "sr",
+        "swe" => "sv",
+        "tur" => "tr",
+        "vie" => "vi",
+        _ => return None,
+    })
 }
 
-/// Perform OCR using Windows OCR API
+/// BCP-47 tags of the OCR languages installed in Windows
 #[cfg(windows)]
-pub fn perform_windows_ocr(image_bytes: &[u8], lang: &str) -> Result<String, String> {
-    use windows::core::HSTRING;
-    use windows::Globalization::Language;
-    use windows::Graphics::Imaging::BitmapDecoder;
+pub fn windows_available_languages() -> AppResult<Vec<String>> {
     use windows::Media::Ocr::OcrEngine as WinOcrEngine;
-    use windows::Storage::Streams::{DataWriter, InMemoryRandomAccessStream};
-
-    // Get the Windows language tag
-    let win_lang = tesseract_lang_to_windows(lang)
-        .ok_or_else(|| format!("Language '{}' not supported by Windows OCR", lang))?;
 
-    // Create language object
-    let language = Language::CreateLanguage(&HSTRING::from(win_lang))
-        .map_err(|e| format!("Failed to create language: {}", e))?;
+    let languages = WinOcrEngine::AvailableRecognizerLanguages()
+        .map_err(|e| AppError::Ocr(format!("Failed to list OCR languages: {}", e)))?;
+    Ok(languages
+        .into_iter()
+        .filter_map(|l| l.LanguageTag().ok().map(|t| t.to_string()))
+        .collect())
+}
 
-    // Check if language is supported
-    if !WinOcrEngine::IsLanguageSupported(&language)
-        .map_err(|e| format!("Failed to check language support: {}", e))? 
-    {
-        return Err(format!(
-            "Windows OCR does not support language '{}'. Please install the language pack.",
-            win_lang
-        ));
-    }
+/// Resolve a Tesseract language code to an installed Windows OCR language tag
+#[cfg(windows)]
+fn resolve_windows_language(lang: &str, installed: &[String]) -> AppResult<String> {
+    let prefix = tesseract_lang_to_bcp47(lang)
+        .ok_or_else(|| AppError::Unsupported(format!("Language '{}' not supported by Windows OCR", lang)))?;
+
+    installed
+        .iter()
+        .find(|tag| {
+            tag.eq_ignore_ascii_case(prefix)
+                || tag.to_ascii_lowercase().starts_with(&format!("{}-", prefix.to_ascii_lowercase()))
+        })
+        .cloned()
+        .ok_or_else(|| AppError::Unsupported(format!(
+            "Windows OCR language pack for '{}' is not installed. Add it under Settings > Time & Language > Language.",
+            prefix
+        )))
+}
 
-    // Create OCR engine for the language
-    // TryCreateFromLanguage returns Result<OcrEngine, Error>, not Result<Option<OcrEngine>>
-    let ocr_engine = WinOcrEngine::TryCreateFromLanguage(&language)
-        .map_err(|e| format!("Failed to create OCR engine: {}", e))?;
+/// Decode image bytes into a bitmap for Windows OCR
+#[cfg(windows)]
+fn decode_windows_bitmap(image_bytes: &[u8]) -> AppResult<windows::Graphics::Imaging::SoftwareBitmap> {
+    use windows::Graphics::Imaging::BitmapDecoder;
+    use windows::Storage::Streams::{DataWriter, InMemoryRandomAccessStream};
 
     // Create in-memory stream from image bytes
     let stream = InMemoryRandomAccessStream::new()
-        .map_err(|e| format!("Failed to create stream: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to create stream: {}", e)))?;
     
     let writer = DataWriter::CreateDataWriter(&stream)
-        .map_err(|e| format!("Failed to create data writer: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to create data writer: {}", e)))?;
     
     writer.WriteBytes(image_bytes)
-        .map_err(|e| format!("Failed to write bytes: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to write bytes: {}", e)))?;
     
     writer.StoreAsync()
-        .map_err(|e| format!("Failed to store async: {}", e))?
+        .map_err(|e| AppError::Ocr(format!("Failed to store async: {}", e)))?
         .get()
-        .map_err(|e| format!("Failed to store: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to store: {}", e)))?;
     
     writer.FlushAsync()
-        .map_err(|e| format!("Failed to flush async: {}", e))?
+        .map_err(|e| AppError::Ocr(format!("Failed to flush async: {}", e)))?
         .get()
-        .map_err(|e| format!("Failed to flush: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to flush: {}", e)))?;
 
     // Reset stream position
     stream.Seek(0)
-        .map_err(|e| format!("Failed to seek: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to seek: {}", e)))?;
 
     // Decode image
     let decoder = BitmapDecoder::CreateAsync(&stream)
-        .map_err(|e| format!("Failed to create decoder async: {}", e))?
+        .map_err(|e| AppError::Ocr(format!("Failed to create decoder async: {}", e)))?
         .get()
-        .map_err(|e| format!("Failed to create decoder: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to create decoder: {}", e)))?;
 
-    let bitmap = decoder.GetSoftwareBitmapAsync()
-        .map_err(|e| format!("Failed to get bitmap async: {}", e))?
+    decoder.GetSoftwareBitmapAsync()
+        .map_err(|e| AppError::Ocr(format!("Failed to get bitmap async: {}", e)))?
         .get()
-        .map_err(|e| format!("Failed to get bitmap: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to get bitmap: {}", e)))
+}
+
+/// Run the Windows OCR API for one language tag and return its raw result
+#[cfg(windows)]
+fn recognize_windows(
+    bitmap: &windows::Graphics::Imaging::SoftwareBitmap,
+    win_lang: &str,
+) -> AppResult<windows::Media::Ocr::OcrResult> {
+    use windows::core::HSTRING;
+    use windows::Globalization::Language;
+    use windows::Media::Ocr::OcrEngine as WinOcrEngine;
+
+    // Create language object
+    let language = Language::CreateLanguage(&HSTRING::from(win_lang))
+        .map_err(|e| AppError::Ocr(format!("Failed to create language: {}", e)))?;
+
+    // Create OCR engine for the language
+    // TryCreateFromLanguage returns Result<OcrEngine, Error>, not Result<Option<OcrEngine>>
+    let ocr_engine = WinOcrEngine::TryCreateFromLanguage(&language)
+        .map_err(|e| AppError::Ocr(format!("Failed to create OCR engine: {}", e)))?;
 
     // Perform OCR
-    let result = ocr_engine.RecognizeAsync(&bitmap)
-        .map_err(|e| format!("Failed to recognize async: {}", e))?
+    let result = ocr_engine.RecognizeAsync(bitmap)
+        .map_err(|e| AppError::Ocr(format!("Failed to recognize async: {}", e)))?
         .get()
-        .map_err(|e| format!("Failed to recognize: {}", e))?;
+        .map_err(|e| AppError::Ocr(format!("Failed to recognize: {}", e)))?;
+
+    Ok(result)
+}
+
+/// Convert a Windows OCR result into words with estimated confidence
+#[cfg(windows)]
+fn windows_result_to_ocr_result(result: &windows::Media::Ocr::OcrResult) -> AppResult<OcrResult> {
+    let lines = result.Lines()
+        .map_err(|e| AppError::Ocr(format!("Failed to get lines: {}", e)))?;
+
+    let mut words = Vec::new();
+    for (line_idx, line) in lines.into_iter().enumerate() {
+        let line_words = line.Words()
+            .map_err(|e| AppError::Ocr(format!("Failed to get words: {}", e)))?;
+        for word in line_words {
+            let text = word.Text()
+                .map_err(|e| AppError::Ocr(format!("Failed to get word text: {}", e)))?
+                .to_string();
+            let rect = word.BoundingRect()
+                .map_err(|e| AppError::Ocr(format!("Failed to get word bounds: {}", e)))?;
+            let confidence = crate::ocr_result::estimate_word_confidence(&text);
+            words.push(OcrWord::new(
+                text,
+                confidence,
+                BoundingBox {
+                    x: rect.X.max(0.0) as u32,
+                    y: rect.Y.max(0.0) as u32,
+                    width: rect.Width.max(0.0) as u32,
+                    height: rect.Height.max(0.0) as u32,
+                },
+                1,
+                1,
+                line_idx as u32 + 1,
+            ));
+        }
+    }
+
+    Ok(OcrResult::from_words(words, "windows"))
+}
+
+/// Recognize with every language in `lang` ("jpn+eng") and merge the results
+#[cfg(windows)]
+fn recognize_windows_multi(image_bytes: &[u8], lang: &str) -> AppResult<Vec<windows::Media::Ocr::OcrResult>> {
+    let installed = windows_available_languages()?;
+    let mut tags: Vec<String> = Vec::new();
+    for code in lang.split('+').filter(|l| !l.is_empty()) {
+        let tag = resolve_windows_language(code, &installed)?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    if tags.is_empty() {
+        return Err(AppError::InvalidInput("No OCR language selected".to_string()));
+    }
+
+    let bitmap = decode_windows_bitmap(image_bytes)?;
+    tags.iter().map(|tag| recognize_windows(&bitmap, tag)).collect()
+}
+
+/// Perform OCR using Windows OCR API
+#[cfg(windows)]
+pub fn perform_windows_ocr(image_bytes: &[u8], lang: &str) -> AppResult<String> {
+    let results = recognize_windows_multi(image_bytes, lang)?;
+
+    if let [result] = results.as_slice() {
+        // Get text
+        let text = result.Text()
+            .map_err(|e| AppError::Ocr(format!("Failed to get text: {}", e)))?
+            .to_string();
+        return Ok(text);
+    }
+
+    let merged = results
+        .iter()
+        .map(windows_result_to_ocr_result)
+        .collect::<AppResult<Vec<_>>>()?;
+    Ok(OcrResult::merge_by_lines(merged, "windows").text)
+}
 
-    // Get text
-    let text = result.Text()
-        .map_err(|e| format!("Failed to get text: {}", e))?
-        .to_string();
+/// Perform OCR using Windows OCR API, with word boxes.
+/// Windows OCR doesn't expose confidence, so it is estimated per word.
+#[cfg(windows)]
+pub fn perform_windows_ocr_detailed(image_bytes: &[u8], lang: &str) -> AppResult<OcrResult> {
+    let mut results = recognize_windows_multi(image_bytes, lang)?
+        .iter()
+        .map(windows_result_to_ocr_result)
+        .collect::<AppResult<Vec<_>>>()?;
+
+    if results.len() == 1 {
+        return Ok(results.remove(0));
+    }
+    Ok(OcrResult::merge_by_lines(results, "windows"))
+}
 
-    Ok(text)
+/// Open the Windows language settings to install OCR language packs
+#[cfg(windows)]
+pub fn open_windows_language_settings() -> AppResult<()> {
+    std::process::Command::new("explorer")
+        .arg("ms-settings:regionlanguage")
+        .spawn()?;
+    Ok(())
 }
 
 /// Placeholder for Apple Vision OCR (macOS)
 #[cfg(target_os = "macos")]
-pub fn perform_apple_vision_ocr(_image_bytes: &[u8], _lang: &str) -> Result<String, String> {
+pub fn perform_apple_vision_ocr(_image_bytes: &[u8], _lang: &str) -> AppResult<String> {
     // TODO: Implement using objc2 and Vision framework
-    Err("Apple Vision OCR not yet implemented".to_string())
+    Err(AppError::Unsupported("Apple Vision OCR not yet implemented".to_string()))
 }
 
 /// Get the resource directory path where bundled files are located
-pub fn get_resource_dir() -> Result<std::path::PathBuf, String> {
+pub fn get_resource_dir() -> AppResult<std::path::PathBuf> {
     // Check for development path FIRST (CARGO_MANIFEST_DIR contains tessdata)
     let dev_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     if dev_path.join("tessdata").exists() {
@@ -162,7 +465,7 @@ pub fn get_resource_dir() -> Result<std::path::PathBuf, String> {
     }
     
     // In production, resources are relative to the executable
-    let exe_path = std::env::current_exe().map_err(|e| e.to_string())?;
+    let exe_path = std::env::current_exe()?;
     let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
     
     // On Windows/macOS/Linux production, tessdata is in the same directory as the exe
@@ -175,8 +478,8 @@ pub fn get_resource_dir() -> Result<std::path::PathBuf, String> {
 }
 
 /// Get the tesseract executable path
-pub fn get_tesseract_path() -> Result<std::path::PathBuf, String> {
-    let exe_path = std::env::current_exe().map_err(|e| e.to_string())?;
+pub fn get_tesseract_path() -> AppResult<std::path::PathBuf> {
+    let exe_path = std::env::current_exe()?;
     let exe_dir = exe_path.parent().ok_or("Failed to get exe directory")?;
     
     let tesseract_name = if cfg!(windows) { "tesseract.exe" } else { "tesseract" };
@@ -193,30 +496,17 @@ pub fn get_tesseract_path() -> Result<std::path::PathBuf, String> {
         return Ok(binaries_path);
     }
     
-    // Development: use the bundled binary with correct target triple suffix
-    let dev_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    
-    #[cfg(all(target_os = "windows", target_arch = "x86_64"))]
-    let sidecar_name = "tesseract-x86_64-pc-windows-msvc.exe";
-    #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-    let sidecar_name = "tesseract-aarch64-apple-darwin";
-    #[cfg(all(target_os = "macos", target_arch = "x86_64"))]
-    let sidecar_name = "tesseract-x86_64-apple-darwin";
-    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-    let sidecar_name = "tesseract-x86_64-unknown-linux-gnu";
-    #[cfg(not(any(
-        all(target_os = "windows", target_arch = "x86_64"),
-        all(target_os = "macos", target_arch = "aarch64"),
-        all(target_os = "macos", target_arch = "x86_64"),
-        all(target_os = "linux", target_arch = "x86_64")
-    )))]
-    let sidecar_name = tesseract_name;
-    
-    let dev_path = dev_dir.join("binaries").join(sidecar_name);
-    if dev_path.exists() {
+    // Development: the bundled binary with a target triple suffix, native first
+    let dev_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("binaries");
+    if let Some(dev_path) = crate::sidecar::find_in(&dev_dir, "tesseract") {
         return Ok(dev_path);
     }
     
+    // Build downloaded at runtime by tesseract_manager
+    if let Some(managed_path) = crate::tesseract_manager::managed_tesseract_path() {
+        return Ok(managed_path);
+    }
+    
     // Final fallback: try system tesseract
     if cfg!(windows) {
         let paths = [
@@ -234,26 +524,36 @@ pub fn get_tesseract_path() -> Result<std::path::PathBuf, String> {
     Ok(std::path::PathBuf::from(tesseract_name))
 }
 
-/// Perform OCR using Tesseract
-pub fn perform_tesseract_ocr(image_bytes: &[u8], lang: &str) -> Result<String, String> {
-    use std::process::Command;
-    
-    let temp_path = std::env::temp_dir().join("ocr_input.png");
-    let mut file = File::create(&temp_path).map_err(|e| e.to_string())?;
-    file.write_all(image_bytes).map_err(|e| e.to_string())?;
-    drop(file);
+/// Classify a failure to launch the tesseract process
+fn tesseract_spawn_error(path: &std::path::Path, e: std::io::Error) -> AppError {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::TesseractNotFound(format!(
+            "{} ({})",
+            path.display(),
+            crate::sidecar::describe("tesseract")
+        )),
+        std::io::ErrorKind::PermissionDenied => {
+            AppError::PermissionDenied(format!("Cannot execute {}", path.display()))
+        }
+        _ => AppError::Ocr(format!("Failed to execute OCR engine: {}", e)),
+    }
+}
 
-    let tesseract_path = get_tesseract_path()?;
+/// Tessdata directory for `langs`, matching the languages' active tiers
+pub(crate) fn tessdata_dir(langs: Option<&str>) -> AppResult<std::path::PathBuf> {
+    match langs {
+        Some(langs) => crate::model_manager::tessdata_dir_for(langs),
+        None => Ok(get_resource_dir()?.join("tessdata")),
+    }
+}
+
+/// Build a tesseract command with TESSDATA_PREFIX and bundled DLLs configured.
+/// `langs` selects the tessdata directory matching the languages' active tiers.
+fn tesseract_command(tesseract_path: &std::path::Path, langs: Option<&str>) -> AppResult<std::process::Command> {
     let resource_dir = get_resource_dir()?;
-    let tessdata_dir = resource_dir.join("tessdata");
+    let tessdata_dir = tessdata_dir(langs)?;
     
-    let mut cmd = Command::new(&tesseract_path);
-    cmd.arg(temp_path.to_str().unwrap())
-       .arg("stdout")
-       .arg("-l")
-       .arg(lang)
-       .arg("--psm")
-       .arg("6");
+    let mut cmd = std::process::Command::new(tesseract_path);
     
     if tessdata_dir.exists() {
         cmd.env("TESSDATA_PREFIX", &tessdata_dir);
@@ -271,32 +571,104 @@ pub fn perform_tesseract_ocr(image_bytes: &[u8], lang: &str) -> Result<String, S
         }
     }
     
-    let output = cmd.output().map_err(|e| {
-        format!(
-            "Failed to execute OCR engine: {}. Please ensure Tesseract is correctly installed.",
-            e
-        )
-    })?;
+    Ok(cmd)
+}
+
+/// Run tesseract on in-memory image bytes.
+/// The image is piped through stdin (`tesseract stdin stdout ...`) so concurrent
+/// calls never share a file and screen contents are never written to disk.
+fn run_tesseract(image_bytes: &[u8], args: &[&str]) -> AppResult<std::process::Output> {
+    use std::io::Write;
+    use std::process::Stdio;
+    
+    let tesseract_path = get_tesseract_path()?;
+    let langs = args.windows(2).find(|w| w[0] == "-l").map(|w| w[1]);
+    let mut cmd = tesseract_command(&tesseract_path, langs)?;
+    cmd.arg("stdin")
+       .arg("stdout")
+       .args(args)
+       .stdin(Stdio::piped())
+       .stdout(Stdio::piped())
+       .stderr(Stdio::piped());
+    
+    let mut child = cmd.spawn().map_err(|e| tesseract_spawn_error(&tesseract_path, e))?;
+    
+    // Feed stdin from a separate thread so a full stdout pipe can't deadlock us
+    let mut stdin = child.stdin.take().ok_or("Failed to open tesseract stdin")?;
+    let input = image_bytes.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    
+    let output = crate::operations::wait_child(child)?;
+    
+    match writer.join() {
+        Ok(Ok(())) => {}
+        // Tesseract may exit before reading everything if the image is invalid;
+        // its stderr explains why, so only report the pipe error on success
+        Ok(Err(e)) if output.status.success() => return Err(e.into()),
+        _ => {}
+    }
+    
+    Ok(output)
+}
+
+/// Output of a tesseract run. With the `tesseract-ffi` feature, runs go
+/// through warm in-process engines; otherwise, or if that fails, the CLI.
+fn tesseract_output(image_bytes: &[u8], args: &[String]) -> AppResult<String> {
+    crate::operations::check()?;
+    #[cfg(feature = "tesseract-ffi")]
+    match crate::tesseract_pool::recognize(image_bytes, args) {
+        Some(Ok(output)) => return Ok(output),
+        Some(Err(e)) => tracing::warn!("In-process Tesseract failed: {}, falling back to the CLI", e),
+        None => {}
+    }
 
-    let result = if !output.status.success() {
+    let output = run_tesseract(image_bytes, &args.iter().map(String::as_str).collect::<Vec<_>>())?;
+    if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Tesseract error: {}", stderr))
-    } else {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    };
+        return Err(AppError::Ocr(format!("Tesseract error: {}", stderr)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Load Tesseract for `lang` ahead of the first recognition with default
+/// parameters (live OCR). Only has an effect with the `tesseract-ffi` feature.
+pub fn warm_tesseract(lang: &str) {
+    #[cfg(feature = "tesseract-ffi")]
+    if let Ok(args) = tesseract_args(lang, &TesseractParams::default(), 6, None) {
+        crate::tesseract_pool::warm(args);
+    }
+    #[cfg(not(feature = "tesseract-ffi"))]
+    let _ = lang;
+}
 
-    // Cleanup temporary file
-    let _ = std::fs::remove_file(&temp_path);
+/// Arguments for a tesseract run: language, tuning parameters and output format
+fn tesseract_args(lang: &str, params: &TesseractParams, default_psm: u8, format: Option<&str>) -> AppResult<Vec<String>> {
+    let mut args = vec!["-l".to_string(), lang.to_string()];
+    args.extend(params.to_args(default_psm)?);
+    args.extend(format.map(str::to_string));
+    Ok(args)
+}
+
+/// Perform OCR using Tesseract
+pub fn perform_tesseract_ocr(image_bytes: &[u8], lang: &str, params: &TesseractParams) -> AppResult<String> {
+    let args = tesseract_args(lang, params, 6, None)?;
+    tesseract_output(image_bytes, &args)
+}
 
-    result
+/// Perform OCR using Tesseract, returning words with confidence from TSV output
+pub fn perform_tesseract_ocr_detailed(image_bytes: &[u8], lang: &str, params: &TesseractParams) -> AppResult<OcrResult> {
+    let args = tesseract_args(lang, params, 6, Some("tsv"))?;
+    let tsv = tesseract_output(image_bytes, &args)?;
+    Ok(OcrResult::from_words(parse_tesseract_tsv(&tsv), "tesseract"))
 }
 
 /// Main OCR function that selects the appropriate engine
 pub fn perform_ocr_with_engine(
     image_bytes: &[u8], 
     lang: &str, 
-    engine: OcrEngine
-) -> Result<String, String> {
+    engine: OcrEngine,
+    params: &TesseractParams,
+) -> AppResult<String> {
     let actual_engine = if engine == OcrEngine::Auto {
         get_best_engine_for_language(lang)
     } else {
@@ -304,7 +676,7 @@ pub fn perform_ocr_with_engine(
     };
     
     match actual_engine {
-        OcrEngine::Tesseract => perform_tesseract_ocr(image_bytes, lang),
+        OcrEngine::Tesseract => perform_tesseract_ocr(image_bytes, lang, params),
         
         #[cfg(windows)]
         OcrEngine::WindowsOcr => {
@@ -312,8 +684,8 @@ pub fn perform_ocr_with_engine(
             match perform_windows_ocr(image_bytes, lang) {
                 Ok(text) => Ok(text),
                 Err(e) => {
-                    eprintln!("Windows OCR failed: {}, falling back to Tesseract", e);
-                    perform_tesseract_ocr(image_bytes, lang)
+                    tracing::warn!("Windows OCR failed: {}, falling back to Tesseract", e);
+                    perform_tesseract_ocr(image_bytes, lang, params)
                 }
             }
         }
@@ -323,14 +695,175 @@ pub fn perform_ocr_with_engine(
             match perform_apple_vision_ocr(image_bytes, lang) {
                 Ok(text) => Ok(text),
                 Err(e) => {
-                    eprintln!("Apple Vision OCR failed: {}, falling back to Tesseract", e);
-                    perform_tesseract_ocr(image_bytes, lang)
+                    tracing::warn!("Apple Vision OCR failed: {}, falling back to Tesseract", e);
+                    perform_tesseract_ocr(image_bytes, lang, params)
                 }
             }
         }
         
-        OcrEngine::Auto => perform_tesseract_ocr(image_bytes, lang),
+        OcrEngine::Auto => perform_tesseract_ocr(image_bytes, lang, params),
+    }
+}
+
+/// Like `perform_ocr_with_engine`, but returns words, lines and confidence scores
+pub fn perform_ocr_detailed_with_engine(
+    image_bytes: &[u8],
+    lang: &str,
+    engine: OcrEngine,
+    params: &TesseractParams,
+) -> AppResult<OcrResult> {
+    let actual_engine = if engine == OcrEngine::Auto {
+        get_best_engine_for_language(lang)
+    } else {
+        engine
+    };
+
+    match actual_engine {
+        #[cfg(windows)]
+        OcrEngine::WindowsOcr => match perform_windows_ocr_detailed(image_bytes, lang) {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                tracing::warn!("Windows OCR failed: {}, falling back to Tesseract", e);
+                perform_tesseract_ocr_detailed(image_bytes, lang, params)
+            }
+        },
+
+        // Apple Vision is not implemented yet; Tesseract is the fallback anyway
+        _ => perform_tesseract_ocr_detailed(image_bytes, lang, params),
+    }
+}
+
+/// Run detailed OCR with the given options, handling language auto-detection
+/// and color casts, then pass the text through the OCR-stage plugins
+#[tracing::instrument(skip_all, err, fields(langs = options.lang(), engine = ?options.engine(), bytes = image_bytes.len()))]
+pub fn run_ocr_detailed(image_bytes: &[u8], options: &OcrOptions) -> AppResult<OcrResult> {
+    let image_bytes = &crate::image_decode::ocr_input(image_bytes)?;
+    let balanced = crate::white_balance::normalize_if_enabled(image_bytes);
+    let mut result = crate::perf::time(&crate::perf::ocr_metric(resolved_engine(options)), || {
+        recognize_detailed(balanced.as_deref().unwrap_or(image_bytes), options)
+    })?;
+    crate::plugins::apply_to_result(crate::plugins::PluginStage::Ocr, &mut result);
+    Ok(result)
+}
+
+/// The engine `options` select, with Auto resolved for the language
+fn resolved_engine(options: &OcrOptions) -> OcrEngine {
+    match options.engine() {
+        OcrEngine::Auto => get_best_engine_for_language(options.lang()),
+        engine => engine,
+    }
+}
+
+/// `run_ocr_detailed` on an image that has already been white balanced
+pub(crate) fn recognize_detailed(image_bytes: &[u8], options: &OcrOptions) -> AppResult<OcrResult> {
+    let lang = if options.lang() == "auto" {
+        detect_script(image_bytes)
+            .map(|script| script_to_language(&script))
+            .unwrap_or_else(|_| "eng".to_string())
+    } else {
+        options.lang().to_string()
+    };
+    if options.vertical {
+        return perform_vertical_ocr_detailed(image_bytes, &lang, options.engine(), &options.tesseract);
+    }
+    if options.code_mode {
+        return perform_code_ocr_detailed(image_bytes, &lang, options.engine(), &options.tesseract);
+    }
+    perform_ocr_detailed_with_engine(image_bytes, &lang, options.engine(), &options.tesseract)
+}
+
+/// OCR source code as a single block without character filtering; the text
+/// keeps indentation and blank lines, rebuilt from word positions
+pub fn perform_code_ocr_detailed(
+    image_bytes: &[u8],
+    lang: &str,
+    engine: OcrEngine,
+    params: &TesseractParams,
+) -> AppResult<OcrResult> {
+    let params = TesseractParams {
+        psm: Some(params.psm.unwrap_or(6)),
+        whitelist: None,
+        blacklist: None,
+        ..params.clone()
+    };
+    let mut result = perform_ocr_detailed_with_engine(image_bytes, lang, engine, &params)?;
+    result.text = result.code_text();
+    Ok(result)
+}
+
+/// Use the `_vert` model for CJK languages when it is installed
+fn vertical_lang(lang: &str) -> String {
+    lang.split('+')
+        .map(|code| {
+            let vert = format!("{}_vert", code);
+            let has_vert = matches!(code, "jpn" | "chi_tra" | "chi_sim" | "kor")
+                && crate::model_manager::is_model_installed(&vert);
+            if has_vert { vert } else { code.to_string() }
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Whether an OSD "Rotate" value should be applied before vertical OCR.
+/// OSD reads vertical CJK columns as sideways horizontal lines and reports
+/// 90 or 270 for an upright page, so only upside-down pages are corrected.
+fn vertical_needs_rotation(rotate: u32) -> bool {
+    rotate % 360 == 180
+}
+
+/// Rotate the image upright if OSD reports it upside down
+fn correct_orientation(image_bytes: &[u8]) -> AppResult<Option<Vec<u8>>> {
+    match detect_orientation(image_bytes) {
+        Ok(rotate) if vertical_needs_rotation(rotate) => {}
+        // OSD fails on small crops; keep the image as-is
+        _ => return Ok(None),
+    }
+
+    let image = image::load_from_memory(image_bytes)
+        .map_err(|e| AppError::InvalidInput(format!("Failed to decode image: {}", e)))?;
+    let rotated = image.rotate180();
+
+    let mut bytes = Vec::new();
+    rotated
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Ocr(format!("Failed to encode image: {}", e)))?;
+    Ok(Some(bytes))
+}
+
+/// OCR vertical text: Tesseract uses the `_vert` models with PSM 5 (vertical
+/// block); native engines' results are reordered into right-to-left columns
+pub fn perform_vertical_ocr_detailed(
+    image_bytes: &[u8],
+    lang: &str,
+    engine: OcrEngine,
+    params: &TesseractParams,
+) -> AppResult<OcrResult> {
+    let corrected = correct_orientation(image_bytes)?;
+    let image_bytes = corrected.as_deref().unwrap_or(image_bytes);
+
+    #[cfg(windows)]
+    {
+        let actual_engine = if engine == OcrEngine::Auto {
+            get_best_engine_for_language(lang)
+        } else {
+            engine
+        };
+        if actual_engine == OcrEngine::WindowsOcr {
+            match perform_windows_ocr_detailed(image_bytes, lang) {
+                Ok(result) => return Ok(result.reorder_vertical()),
+                Err(e) => tracing::warn!("Windows OCR failed: {}, falling back to Tesseract", e),
+            }
+        }
     }
+    #[cfg(not(windows))]
+    let _ = engine;
+
+    let vert_lang = vertical_lang(lang);
+    let args = tesseract_args(&vert_lang, params, 5, Some("tsv"))?;
+    let words = parse_tesseract_tsv(&tesseract_output(image_bytes, &args)?);
+    // The _vert models already emit reading order; plain models need reordering
+    let result = OcrResult::from_words(words, "tesseract");
+    Ok(if vert_lang.contains("_vert") { result } else { result.reorder_vertical() })
 }
 
 /// Get list of available OCR engines for the current platform
@@ -346,65 +879,40 @@ pub fn get_available_engines() -> Vec<OcrEngine> {
     engines
 }
 
-/// Detect the script of an image using Tesseract OSD
-/// Returns the detected script name (e.g., "Latin", "Han", "Japanese")
-pub fn detect_script(image_bytes: &[u8]) -> Result<String, String> {
-    use std::process::Command;
-    
-    let temp_path = std::env::temp_dir().join("osd_input.png");
-    let mut file = File::create(&temp_path).map_err(|e| e.to_string())?;
-    file.write_all(image_bytes).map_err(|e| e.to_string())?;
-    drop(file);
-
-    let tesseract_path = get_tesseract_path()?;
-    let resource_dir = get_resource_dir()?;
-    let tessdata_dir = resource_dir.join("tessdata");
-    
-    let mut cmd = Command::new(&tesseract_path);
-    cmd.arg(temp_path.to_str().unwrap())
-       .arg("stdout")
-       .arg("--psm")
-       .arg("0"); // OSD only mode
-    
-    if tessdata_dir.exists() {
-        cmd.env("TESSDATA_PREFIX", &tessdata_dir);
-    }
-    
-    #[cfg(windows)]
-    {
-        let binaries_dir = resource_dir.join("binaries");
-        if binaries_dir.exists() {
-            if let Ok(current_path) = std::env::var("PATH") {
-                cmd.env("PATH", format!("{};{}", binaries_dir.display(), current_path));
-            } else {
-                cmd.env("PATH", binaries_dir.to_str().unwrap());
-            }
-        }
-    }
-    
-    let output = cmd.output().map_err(|e| {
-        format!("Failed to execute OSD: {}", e)
-    })?;
-
-    // Cleanup
-    let _ = std::fs::remove_file(&temp_path);
+/// Run Tesseract OSD and return a field of its report ("Script", "Rotate", ...)
+fn osd_field(image_bytes: &[u8], field: &str) -> AppResult<String> {
+    // OSD only mode
+    let output = run_tesseract(image_bytes, &["--psm", "0"])?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("OSD error: {}", stderr));
+        return Err(AppError::Ocr(format!("OSD error: {}", stderr)));
     }
     
     let stdout = String::from_utf8_lossy(&output.stdout);
     
-    // Parse "Script: <name>" from output
+    // Parse "<field>: <value>" from output
+    let prefix = format!("{}:", field);
     for line in stdout.lines() {
-        if line.starts_with("Script:") {
-            let script = line.trim_start_matches("Script:").trim();
-            return Ok(script.to_string());
+        if let Some(value) = line.strip_prefix(&prefix) {
+            return Ok(value.trim().to_string());
         }
     }
     
-    Err("Could not detect script".to_string())
+    Err(AppError::Ocr(format!("Could not detect {}", field.to_lowercase())))
+}
+
+/// Detect the script of an image using Tesseract OSD
+/// Returns the detected script name (e.g., "Latin", "Han", "Japanese")
+pub fn detect_script(image_bytes: &[u8]) -> AppResult<String> {
+    osd_field(image_bytes, "Script")
+}
+
+/// Degrees (clockwise) the image must be rotated to be upright, via Tesseract OSD
+pub fn detect_orientation(image_bytes: &[u8]) -> AppResult<u32> {
+    osd_field(image_bytes, "Rotate")?
+        .parse()
+        .map_err(|_| AppError::Ocr("Could not detect orientation".to_string()))
 }
 
 /// Map detected script name to best Tesseract language code
@@ -424,19 +932,58 @@ pub fn script_to_language(script: &str) -> String {
 }
 
 /// Auto-detect language and perform OCR
-pub fn perform_auto_ocr(image_bytes: &[u8], engine: OcrEngine) -> Result<String, String> {
+pub fn perform_auto_ocr(image_bytes: &[u8], engine: OcrEngine, params: &TesseractParams) -> AppResult<String> {
     // Try to detect script
     let lang = match detect_script(image_bytes) {
         Ok(script) => {
             let detected = script_to_language(&script);
-            eprintln!("Auto-detected script: {} -> language: {}", script, detected);
+            tracing::debug!("Auto-detected script: {} -> language: {}", script, detected);
             detected
         }
         Err(e) => {
-            eprintln!("Script detection failed: {}, falling back to English", e);
+            tracing::warn!("Script detection failed: {}, falling back to English", e);
             "eng".to_string()
         }
     };
     
-    perform_ocr_with_engine(image_bytes, &lang, engine)
+    perform_ocr_with_engine(image_bytes, &lang, engine, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tesseract_params_args() {
+        assert_eq!(TesseractParams::default().to_args(6).unwrap(), ["--psm", "6"]);
+
+        let mut params = TesseractParams {
+            psm: Some(7),
+            oem: Some(1),
+            whitelist: Some("0123456789".to_string()),
+            ..Default::default()
+        };
+        params.config.insert("preserve_interword_spaces".to_string(), "1".to_string());
+        assert_eq!(
+            params.to_args(6).unwrap(),
+            [
+                "--psm", "7", "--oem", "1",
+                "-c", "tessedit_char_whitelist=0123456789",
+                "-c", "preserve_interword_spaces=1",
+            ]
+        );
+
+        assert!(TesseractParams { psm: Some(14), ..Default::default() }.to_args(6).is_err());
+        params.config.insert("bad name".to_string(), "1".to_string());
+        assert!(params.to_args(6).is_err());
+    }
+
+    #[test]
+    fn test_vertical_needs_rotation() {
+        // Upright vertical columns are reported as sideways
+        assert!(!vertical_needs_rotation(90));
+        assert!(!vertical_needs_rotation(270));
+        assert!(!vertical_needs_rotation(0));
+        assert!(vertical_needs_rotation(180));
+    }
 }
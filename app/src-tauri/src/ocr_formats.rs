@@ -0,0 +1,265 @@
+// Structured OCR output formats
+// Renders an `OcrResult` as hOCR 1.2, ALTO v4 or Tesseract-style TSV for
+// digitization workflows, alongside plain text. Coordinates are image pixels;
+// blocks and paragraphs come from the engine's word numbering (ALTO has no
+// paragraph level, so each paragraph is a TextBlock there).
+
+use crate::ocr_result::{BoundingBox, OcrLine, OcrResult, OcrTextDirection};
+
+const SOFTWARE: &str = "Screen Inu";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrFormat {
+    #[default]
+    Plain,
+    Hocr,
+    Alto,
+    Tsv,
+}
+
+/// Lines grouped by block, then paragraph
+type Blocks<'a> = Vec<Vec<Vec<&'a OcrLine>>>;
+
+fn blocks(result: &OcrResult) -> Blocks<'_> {
+    let mut blocks: Blocks = Vec::new();
+    let mut last: Option<(u32, u32)> = None;
+    for line in result.lines.iter().filter(|l| !l.words.is_empty()) {
+        let key = (line.words[0].block, line.words[0].paragraph);
+        let same_block = last.is_some_and(|(block, _)| block == key.0);
+        match blocks.last_mut() {
+            Some(block) if last == Some(key) => block.last_mut().expect("blocks hold paragraphs").push(line),
+            Some(block) if same_block => block.push(vec![line]),
+            _ => blocks.push(vec![vec![line]]),
+        }
+        last = Some(key);
+    }
+    blocks
+}
+
+fn union<'a>(boxes: impl Iterator<Item = &'a BoundingBox>) -> BoundingBox {
+    boxes.copied().reduce(|a, b| a.union(&b)).unwrap_or_default()
+}
+
+fn paragraph_box(lines: &[&OcrLine]) -> BoundingBox {
+    union(lines.iter().map(|l| &l.bbox))
+}
+
+fn block_box(paragraphs: &[Vec<&OcrLine>]) -> BoundingBox {
+    union(paragraphs.iter().flatten().map(|l| &l.bbox))
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// hOCR "bbox x0 y0 x1 y1"
+fn hocr_bbox(b: &BoundingBox) -> String {
+    format!("bbox {} {} {} {}", b.x, b.y, b.x + b.width, b.y + b.height)
+}
+
+fn to_hocr(result: &OcrResult, width: u32, height: u32) -> String {
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xml:lang=\"en\" lang=\"en\">\n<head>\n<title></title>\n\
+         <meta http-equiv=\"Content-Type\" content=\"text/html; charset=utf-8\" />\n\
+         <meta name=\"ocr-system\" content=\"{} {} ({})\" />\n\
+         <meta name=\"ocr-capabilities\" content=\"ocr_page ocr_carea ocr_par ocr_line ocrx_word ocrp_wconf\" />\n\
+         </head>\n<body>\n<div class=\"ocr_page\" id=\"page_1\" title=\"{}; ppageno 0\">\n",
+        SOFTWARE,
+        env!("CARGO_PKG_VERSION"),
+        escape_xml(&result.engine),
+        hocr_bbox(&BoundingBox { x: 0, y: 0, width, height }),
+    );
+    let (mut line_id, mut word_id) = (0, 0);
+    for (b, paragraphs) in blocks(result).iter().enumerate() {
+        out.push_str(&format!(
+            "<div class=\"ocr_carea\" id=\"block_1_{}\" title=\"{}\">\n",
+            b + 1,
+            hocr_bbox(&block_box(paragraphs))
+        ));
+        for (p, lines) in paragraphs.iter().enumerate() {
+            out.push_str(&format!(
+                "<p class=\"ocr_par\" id=\"par_1_{}_{}\" title=\"{}\">\n",
+                b + 1,
+                p + 1,
+                hocr_bbox(&paragraph_box(lines))
+            ));
+            for line in lines {
+                line_id += 1;
+                let dir = if line.text_direction == OcrTextDirection::Rtl { " dir=\"rtl\"" } else { "" };
+                out.push_str(&format!(
+                    "<span class=\"ocr_line\" id=\"line_1_{}\"{} title=\"{}\">",
+                    line_id,
+                    dir,
+                    hocr_bbox(&line.bbox)
+                ));
+                let words: Vec<String> = line
+                    .words
+                    .iter()
+                    .map(|w| {
+                        word_id += 1;
+                        format!(
+                            "<span class=\"ocrx_word\" id=\"word_1_{}\" title=\"{}; x_wconf {}\">{}</span>",
+                            word_id,
+                            hocr_bbox(&w.bbox),
+                            w.confidence.round() as u32,
+                            escape_xml(&w.text)
+                        )
+                    })
+                    .collect();
+                out.push_str(&words.join(" "));
+                out.push_str("</span>\n");
+            }
+            out.push_str("</p>\n");
+        }
+        out.push_str("</div>\n");
+    }
+    out.push_str("</div>\n</body>\n</html>\n");
+    out
+}
+
+/// ALTO HPOS/VPOS/WIDTH/HEIGHT attributes
+fn alto_position(b: &BoundingBox) -> String {
+    format!("HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\"", b.x, b.y, b.width, b.height)
+}
+
+fn to_alto(result: &OcrResult, width: u32, height: u32) -> String {
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <alto xmlns=\"http://www.loc.gov/standards/alto/ns-v4#\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"http://www.loc.gov/standards/alto/ns-v4# http://www.loc.gov/alto/v4/alto-4-2.xsd\">\n\
+         <Description>\n<MeasurementUnit>pixel</MeasurementUnit>\n\
+         <OCRProcessing ID=\"OCR_0\">\n<ocrProcessingStep>\n<processingStepSettings>engine: {}</processingStepSettings>\n\
+         <processingSoftware>\n<softwareName>{}</softwareName>\n<softwareVersion>{}</softwareVersion>\n</processingSoftware>\n\
+         </ocrProcessingStep>\n</OCRProcessing>\n</Description>\n\
+         <Layout>\n<Page ID=\"page_1\" PHYSICAL_IMG_NR=\"1\" WIDTH=\"{}\" HEIGHT=\"{}\">\n<PrintSpace {}>\n",
+        escape_xml(&result.engine),
+        SOFTWARE,
+        env!("CARGO_PKG_VERSION"),
+        width,
+        height,
+        alto_position(&BoundingBox { x: 0, y: 0, width, height }),
+    );
+    let (mut block_id, mut line_id, mut word_id) = (0, 0, 0);
+    for lines in blocks(result).iter().flatten() {
+        block_id += 1;
+        out.push_str(&format!(
+            "<TextBlock ID=\"block_{}\" {}>\n",
+            block_id,
+            alto_position(&paragraph_box(lines))
+        ));
+        for line in lines {
+            line_id += 1;
+            out.push_str(&format!("<TextLine ID=\"line_{}\" {}>\n", line_id, alto_position(&line.bbox)));
+            for (i, w) in line.words.iter().enumerate() {
+                if i > 0 {
+                    out.push_str("<SP/>\n");
+                }
+                word_id += 1;
+                out.push_str(&format!(
+                    "<String ID=\"string_{}\" CONTENT=\"{}\" {} WC=\"{:.2}\"/>\n",
+                    word_id,
+                    escape_xml(&w.text),
+                    alto_position(&w.bbox),
+                    w.confidence / 100.0
+                ));
+            }
+            out.push_str("</TextLine>\n");
+        }
+        out.push_str("</TextBlock>\n");
+    }
+    out.push_str("</PrintSpace>\n</Page>\n</Layout>\n</alto>\n");
+    out
+}
+
+/// Tesseract's TSV: one row per page, block, paragraph, line and word
+fn to_tsv(result: &OcrResult, width: u32, height: u32) -> String {
+    let mut rows = vec![
+        "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext".to_string(),
+    ];
+    let mut row = |level: u8, ids: [usize; 4], b: &BoundingBox, conf: f32, text: &str| {
+        rows.push(format!(
+            "{}\t1\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            level, ids[0], ids[1], ids[2], ids[3], b.x, b.y, b.width, b.height, conf, text
+        ));
+    };
+    row(1, [0; 4], &BoundingBox { x: 0, y: 0, width, height }, -1.0, "");
+    for (b, paragraphs) in blocks(result).iter().enumerate() {
+        row(2, [b + 1, 0, 0, 0], &block_box(paragraphs), -1.0, "");
+        for (p, lines) in paragraphs.iter().enumerate() {
+            row(3, [b + 1, p + 1, 0, 0], &paragraph_box(lines), -1.0, "");
+            for (l, line) in lines.iter().enumerate() {
+                row(4, [b + 1, p + 1, l + 1, 0], &line.bbox, -1.0, "");
+                for (w, word) in line.words.iter().enumerate() {
+                    // Tabs and newlines would break the columns
+                    let text = word.text.replace(['\t', '\n'], " ");
+                    row(5, [b + 1, p + 1, l + 1, w + 1], &word.bbox, word.confidence, &text);
+                }
+            }
+        }
+    }
+    rows.join("\n") + "\n"
+}
+
+/// `result` in `format`, for an image of `width` x `height` pixels
+pub fn render(result: &OcrResult, format: OcrFormat, width: u32, height: u32) -> String {
+    match format {
+        OcrFormat::Plain => result.text.clone(),
+        OcrFormat::Hocr => to_hocr(result, width, height),
+        OcrFormat::Alto => to_alto(result, width, height),
+        OcrFormat::Tsv => to_tsv(result, width, height),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr_result::{parse_tesseract_tsv, OcrWord};
+
+    fn sample() -> OcrResult {
+        let word = |text: &str, x, y, paragraph, line| {
+            OcrWord::new(text.to_string(), 90.0, BoundingBox { x, y, width: 40, height: 12 }, 1, paragraph, line)
+        };
+        OcrResult::from_words(
+            vec![
+                word("Fish", 10, 10, 1, 1),
+                word("&", 55, 10, 1, 1),
+                word("Chips", 10, 30, 1, 2),
+                word("\"Open\"", 10, 60, 2, 1),
+            ],
+            "tesseract",
+        )
+    }
+
+    #[test]
+    fn test_tsv_round_trip() {
+        let result = sample();
+        let tsv = render(&result, OcrFormat::Tsv, 200, 100);
+        assert!(tsv.starts_with("level\tpage_num"));
+        assert!(tsv.contains("\n3\t1\t1\t2\t0\t0\t10\t60\t40\t12\t-1\t\n"));
+        let words = parse_tesseract_tsv(&tsv);
+        assert_eq!(words.len(), 4);
+        assert_eq!(OcrResult::from_words(words, "tesseract").text, result.text);
+    }
+
+    #[test]
+    fn test_hocr_and_alto() {
+        let result = sample();
+        let hocr = render(&result, OcrFormat::Hocr, 200, 100);
+        assert!(hocr.contains("<div class=\"ocr_page\" id=\"page_1\" title=\"bbox 0 0 200 100; ppageno 0\">"));
+        assert!(hocr.contains("<span class=\"ocrx_word\" id=\"word_1_2\" title=\"bbox 55 10 95 22; x_wconf 90\">&amp;</span>"));
+        assert_eq!(hocr.matches("class=\"ocr_par\"").count(), 2);
+
+        let alto = render(&result, OcrFormat::Alto, 200, 100);
+        assert!(alto.contains("<Page ID=\"page_1\" PHYSICAL_IMG_NR=\"1\" WIDTH=\"200\" HEIGHT=\"100\">"));
+        assert!(alto.contains("CONTENT=\"&quot;Open&quot;\" HPOS=\"10\" VPOS=\"60\" WIDTH=\"40\" HEIGHT=\"12\" WC=\"0.90\""));
+        assert_eq!(alto.matches("<TextBlock ").count(), 2);
+        assert_eq!(render(&result, OcrFormat::Plain, 200, 100), result.text);
+    }
+}
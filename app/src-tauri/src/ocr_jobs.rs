@@ -0,0 +1,235 @@
+// OCR job queue
+// A bounded pool of worker threads processes OCR jobs in the background so batch and
+// live modes don't block Tauri command threads. Results are delivered as events.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use once_cell::sync::OnceCell;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Pipeline};
+use crate::ocr::{self, OcrOptions};
+use crate::operations::CancelToken;
+
+/// Event emitted when a job finishes, fails, or is cancelled
+pub const OCR_COMPLETED_EVENT: &str = "ocr://completed";
+
+/// Maximum number of jobs waiting in the queue
+const MAX_QUEUED_JOBS: usize = 64;
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+static POOL: OnceCell<Arc<JobPool>> = OnceCell::new();
+
+struct Job {
+    id: u64,
+    image_bytes: Vec<u8>,
+    options: OcrOptions,
+}
+
+struct JobPool {
+    queue: Mutex<VecDeque<Job>>,
+    available: Condvar,
+    /// Cancellation tokens of jobs currently being processed
+    running: Mutex<HashMap<u64, CancelToken>>,
+}
+
+impl JobPool {
+    fn new() -> Self {
+        Self {
+            queue: Mutex::new(VecDeque::new()),
+            available: Condvar::new(),
+            running: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Queue a job and return its id
+    fn submit(&self, image_bytes: Vec<u8>, options: OcrOptions) -> AppResult<u64> {
+        let mut queue = self.queue.lock()?;
+        if queue.len() >= MAX_QUEUED_JOBS {
+            return Err(AppError::Ocr(format!(
+                "Too many pending OCR jobs (limit {})",
+                MAX_QUEUED_JOBS
+            )));
+        }
+
+        let id = NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst);
+        queue.push_back(Job { id, image_bytes, options });
+        self.available.notify_one();
+        Ok(id)
+    }
+
+    /// Wait for the next job and register it as running
+    fn next_job(&self) -> (Job, CancelToken) {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        let job = loop {
+            if let Some(job) = queue.pop_front() {
+                break job;
+            }
+            queue = self.available.wait(queue).unwrap_or_else(|e| e.into_inner());
+        };
+        // Register as running before releasing the queue so a concurrent
+        // cancel always finds the job in one of the two places
+        let token = CancelToken::default();
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(job.id, token.clone());
+        (job, token)
+    }
+
+    fn finish(&self, job_id: u64) {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&job_id);
+    }
+
+    /// Cancel a job: drop it from the queue (true) or cancel it while it
+    /// runs, killing its Tesseract process (false)
+    fn cancel(&self, job_id: u64) -> AppResult<bool> {
+        {
+            let mut queue = self.queue.lock()?;
+            if let Some(pos) = queue.iter().position(|job| job.id == job_id) {
+                queue.remove(pos);
+                return Ok(true);
+            }
+        }
+
+        match self.running.lock()?.get(&job_id) {
+            Some(token) => {
+                token.cancel();
+                Ok(false)
+            }
+            None => Err(AppError::InvalidInput(format!("Unknown OCR job {}", job_id))),
+        }
+    }
+}
+
+/// Payload of `ocr://completed`
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrJobResult {
+    pub job_id: u64,
+    pub text: Option<String>,
+    pub error: Option<String>,
+    pub cancelled: bool,
+}
+
+impl OcrJobResult {
+    fn cancelled(job_id: u64) -> Self {
+        Self {
+            job_id,
+            text: None,
+            error: None,
+            cancelled: true,
+        }
+    }
+}
+
+fn worker_loop(app: AppHandle, pool: Arc<JobPool>) {
+    loop {
+        let (job, token) = pool.next_job();
+        // In the token's scope a cancel kills the Tesseract process
+        let result = events::track(Pipeline::Ocr, "ocr_job", Some(job.id.to_string()), || {
+            token.scope(|| ocr::run_ocr(&job.image_bytes, &job.options))
+        });
+        pool.finish(job.id);
+
+        let payload = if token.is_cancelled() {
+            OcrJobResult::cancelled(job.id)
+        } else {
+            match result {
+                Ok(text) => OcrJobResult {
+                    job_id: job.id,
+                    text: Some(text),
+                    error: None,
+                    cancelled: false,
+                },
+                Err(e) => OcrJobResult {
+                    job_id: job.id,
+                    text: None,
+                    error: Some(e.localized_message()),
+                    cancelled: false,
+                },
+            }
+        };
+        let _ = app.emit(OCR_COMPLETED_EVENT, payload);
+    }
+}
+
+/// Get the pool, starting the workers on first use
+fn get_or_init_pool(app: &AppHandle) -> Arc<JobPool> {
+    POOL.get_or_init(|| {
+        let pool = Arc::new(JobPool::new());
+
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(2)
+            .clamp(1, 4);
+        for _ in 0..workers {
+            let app = app.clone();
+            let pool = pool.clone();
+            std::thread::spawn(move || worker_loop(app, pool));
+        }
+
+        pool
+    })
+    .clone()
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Queue an image for OCR and return its job id.
+/// The result arrives later as an `ocr://completed` event.
+#[tauri::command]
+pub fn ocr_submit(app: AppHandle, base64_image: String, options: Option<OcrOptions>) -> AppResult<u64> {
+    let image_bytes = ocr::decode_base64_image(&base64_image)?;
+    get_or_init_pool(&app).submit(image_bytes, options.unwrap_or_default().with_reocr_config())
+}
+
+/// Cancel a queued or running OCR job
+#[tauri::command]
+pub fn ocr_cancel(app: AppHandle, job_id: u64) -> AppResult<()> {
+    if get_or_init_pool(&app).cancel(job_id)? {
+        let _ = app.emit(OCR_COMPLETED_EVENT, OcrJobResult::cancelled(job_id));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_and_cancel() {
+        let pool = JobPool::new();
+        let first = pool.submit(vec![1], OcrOptions::default()).unwrap();
+        let second = pool.submit(vec![2], OcrOptions::default()).unwrap();
+
+        // Queued: removed before a worker sees it
+        assert!(pool.cancel(second).unwrap());
+        let (job, token) = pool.next_job();
+        assert_eq!((job.id, job.image_bytes), (first, vec![1]));
+        assert!(pool.queue.lock().unwrap().is_empty());
+
+        // Running: its token is cancelled, which kills a Tesseract child
+        assert!(!pool.cancel(first).unwrap());
+        assert!(token.is_cancelled());
+        pool.finish(first);
+        assert!(pool.cancel(first).is_err());
+    }
+
+    #[test]
+    fn test_queue_limit() {
+        let pool = JobPool::new();
+        for _ in 0..MAX_QUEUED_JOBS {
+            pool.submit(Vec::new(), OcrOptions::default()).unwrap();
+        }
+        assert!(pool.submit(Vec::new(), OcrOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,668 @@
+// Structured OCR results
+// Words with bounding boxes and confidence scores, grouped into lines.
+
+/// Words/lines below this confidence (0-100) are flagged for highlighting
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 60.0;
+
+/// Axis-aligned box in image pixel coordinates
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BoundingBox {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BoundingBox {
+    /// Smallest box containing both boxes
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        BoundingBox {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// Area shared with another box
+    pub fn intersection_area(&self, other: &BoundingBox) -> u64 {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        if right <= left || bottom <= top {
+            0
+        } else {
+            (right - left) as u64 * (bottom - top) as u64
+        }
+    }
+}
+
+/// A single recognized word
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrWord {
+    pub text: String,
+    /// 0-100, estimated for engines that don't report confidence
+    pub confidence: f32,
+    pub bbox: BoundingBox,
+    pub block: u32,
+    pub paragraph: u32,
+    pub line: u32,
+    pub low_confidence: bool,
+}
+
+/// Base direction of a line or block, for display (`dir` attribute)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrTextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+/// A line of words, in logical (reading) order
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrLine {
+    pub text: String,
+    pub confidence: f32,
+    pub bbox: BoundingBox,
+    pub words: Vec<OcrWord>,
+    pub low_confidence: bool,
+    #[serde(default)]
+    pub text_direction: OcrTextDirection,
+}
+
+/// Full OCR result with per-line and per-word confidence
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrResult {
+    pub text: String,
+    /// Mean word confidence (0-100)
+    pub confidence: f32,
+    pub lines: Vec<OcrLine>,
+    /// Text with low-confidence lines dropped, suitable for translation
+    pub confident_text: String,
+    pub engine: String,
+}
+
+impl OcrWord {
+    pub fn new(text: String, confidence: f32, bbox: BoundingBox, block: u32, paragraph: u32, line: u32) -> Self {
+        let confidence = confidence.clamp(0.0, 100.0);
+        Self {
+            text,
+            confidence,
+            bbox,
+            block,
+            paragraph,
+            line,
+            low_confidence: confidence < LOW_CONFIDENCE_THRESHOLD,
+        }
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF // Hangul syllables
+        | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+        | 0xFF00..=0xFFEF // Fullwidth forms
+        | 0x3000..=0x303F // CJK punctuation
+    )
+}
+
+/// Join words of a line, without spaces between CJK characters
+pub fn join_words<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    let mut text = String::new();
+    for word in words {
+        let needs_space = match (text.chars().last(), word.chars().next()) {
+            (Some(prev), Some(next)) => !(is_cjk(prev) && is_cjk(next)),
+            _ => false,
+        };
+        if needs_space {
+            text.push(' ');
+        }
+        text.push_str(word);
+    }
+    text
+}
+
+fn is_rtl(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF   // Hebrew, Arabic, Syriac, Thaana, NKo, Arabic Extended
+        | 0xFB1D..=0xFDFF // Hebrew and Arabic presentation forms A
+        | 0xFE70..=0xFEFF // Arabic presentation forms B
+    )
+}
+
+/// Direction of a word from its first strong character; digits count as LTR
+/// (numbers read left to right inside RTL text), punctuation is neutral
+fn word_direction(text: &str) -> Option<OcrTextDirection> {
+    text.chars().find_map(|c| {
+        if is_rtl(c) {
+            Some(OcrTextDirection::Rtl)
+        } else if c.is_alphanumeric() {
+            Some(OcrTextDirection::Ltr)
+        } else {
+            None
+        }
+    })
+}
+
+/// Put the words of a line into logical order. Engines report RTL words in
+/// inconsistent orders, so lines containing RTL text are re-derived from the
+/// visual (left to right) positions: the base direction is the majority of
+/// strong characters, neutral words take the direction of matching
+/// neighbours, and runs against the base direction keep their own order.
+/// Lines without RTL text are returned unchanged.
+fn logical_order(mut words: Vec<OcrWord>) -> (Vec<OcrWord>, OcrTextDirection) {
+    if !words.iter().any(|w| w.text.chars().any(is_rtl)) {
+        return (words, OcrTextDirection::Ltr);
+    }
+    let (rtl, ltr) = words.iter().flat_map(|w| w.text.chars()).fold((0, 0), |(r, l), c| {
+        if is_rtl(c) {
+            (r + 1, l)
+        } else if c.is_alphanumeric() {
+            (r, l + 1)
+        } else {
+            (r, l)
+        }
+    });
+    let base = if rtl >= ltr { OcrTextDirection::Rtl } else { OcrTextDirection::Ltr };
+
+    words.sort_by_key(|w| w.bbox.x);
+    let strong: Vec<Option<OcrTextDirection>> = words.iter().map(|w| word_direction(&w.text)).collect();
+    let resolved: Vec<OcrTextDirection> = (0..words.len())
+        .map(|i| {
+            strong[i].unwrap_or_else(|| {
+                let before = strong[..i].iter().rev().find_map(|d| *d);
+                let after = strong[i + 1..].iter().find_map(|d| *d);
+                match (before, after) {
+                    (Some(b), Some(a)) if a == b => a,
+                    _ => base,
+                }
+            })
+        })
+        .collect();
+
+    // Reverse runs running against the base direction; for an RTL base the
+    // whole line is reversed first, which flips those runs back
+    let mut indices: Vec<usize> = (0..words.len()).collect();
+    if base == OcrTextDirection::Rtl {
+        indices.reverse();
+    }
+    let against = if base == OcrTextDirection::Rtl { OcrTextDirection::Ltr } else { OcrTextDirection::Rtl };
+    let mut start = 0;
+    while start < indices.len() {
+        let mut end = start;
+        while end < indices.len() && resolved[indices[end]] == against {
+            end += 1;
+        }
+        if end > start {
+            indices[start..end].reverse();
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+
+    let mut slots: Vec<Option<OcrWord>> = words.into_iter().map(Some).collect();
+    let ordered = indices.into_iter().filter_map(|i| slots[i].take()).collect();
+    (ordered, base)
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = values.fold((0.0, 0usize), |(s, c), v| (s + v, c + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f32
+    }
+}
+
+fn median(values: &mut [f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Some(values[values.len() / 2])
+}
+
+impl OcrLine {
+    /// A line made of `words`, in the given order (RTL lines in logical order)
+    pub fn from_words(words: Vec<OcrWord>) -> Self {
+        let (words, text_direction) = logical_order(words);
+        let bbox = words
+            .iter()
+            .skip(1)
+            .fold(words.first().map(|w| w.bbox).unwrap_or_default(), |bbox, w| bbox.union(&w.bbox));
+        let confidence = mean(words.iter().map(|w| w.confidence));
+        OcrLine {
+            text: join_words(words.iter().map(|w| w.text.as_str())),
+            confidence,
+            bbox,
+            words,
+            low_confidence: confidence < LOW_CONFIDENCE_THRESHOLD,
+            text_direction,
+        }
+    }
+}
+
+impl OcrResult {
+    /// Group words (in reading order) into lines and assemble the text.
+    /// Paragraph and block changes are separated by a blank line.
+    pub fn from_words(words: Vec<OcrWord>, engine: &str) -> Self {
+        let mut lines: Vec<OcrLine> = Vec::new();
+        let mut keys: Vec<(u32, u32)> = Vec::new();
+
+        for word in words {
+            let key = (word.block, word.paragraph);
+            match lines.last_mut() {
+                Some(line)
+                    if line.words[0].block == word.block
+                        && line.words[0].paragraph == word.paragraph
+                        && line.words[0].line == word.line =>
+                {
+                    line.bbox = line.bbox.union(&word.bbox);
+                    line.words.push(word);
+                }
+                _ => {
+                    keys.push(key);
+                    lines.push(OcrLine {
+                        text: String::new(),
+                        confidence: 0.0,
+                        bbox: word.bbox,
+                        words: vec![word],
+                        low_confidence: false,
+                        text_direction: OcrTextDirection::Ltr,
+                    });
+                }
+            }
+        }
+
+        for line in lines.iter_mut() {
+            let (words, direction) = logical_order(std::mem::take(&mut line.words));
+            line.words = words;
+            line.text_direction = direction;
+            line.text = join_words(line.words.iter().map(|w| w.text.as_str()));
+            line.confidence = mean(line.words.iter().map(|w| w.confidence));
+            line.low_confidence = line.confidence < LOW_CONFIDENCE_THRESHOLD;
+        }
+
+        let assemble = |include: &dyn Fn(&OcrLine) -> bool| {
+            let mut text = String::new();
+            let mut prev_key: Option<(u32, u32)> = None;
+            for (line, key) in lines.iter().zip(keys.iter()) {
+                if !include(line) {
+                    continue;
+                }
+                if let Some(prev) = prev_key {
+                    text.push('\n');
+                    if prev != *key {
+                        text.push('\n');
+                    }
+                }
+                text.push_str(&line.text);
+                prev_key = Some(*key);
+            }
+            text
+        };
+
+        let text = assemble(&|_| true);
+        let confident_text = assemble(&|line| !line.low_confidence);
+        let confidence = mean(lines.iter().flat_map(|l| l.words.iter()).map(|w| w.confidence));
+
+        OcrResult {
+            text,
+            confidence,
+            lines,
+            confident_text,
+            engine: engine.to_string(),
+        }
+    }
+
+    /// Iterate over all words in reading order
+    pub fn words(&self) -> impl Iterator<Item = &OcrWord> {
+        self.lines.iter().flat_map(|l| l.words.iter())
+    }
+
+    /// Reorder words for vertical CJK text: columns right to left, each read
+    /// top to bottom. Engines that assume horizontal lines return vertical text
+    /// as scattered fragments; this rebuilds the columns from the word boxes.
+    pub fn reorder_vertical(self) -> Self {
+        let engine = self.engine.clone();
+        let mut words: Vec<OcrWord> = self.lines.into_iter().flat_map(|l| l.words).collect();
+        words.sort_by_key(|w| std::cmp::Reverse(w.bbox.x + w.bbox.width / 2));
+
+        // Group words whose horizontal centers fall inside an existing column
+        let mut columns: Vec<(BoundingBox, Vec<OcrWord>)> = Vec::new();
+        for word in words {
+            let center = word.bbox.x + word.bbox.width / 2;
+            match columns
+                .iter_mut()
+                .find(|(bbox, _)| center >= bbox.x && center <= bbox.x + bbox.width)
+            {
+                Some((bbox, column)) => {
+                    *bbox = bbox.union(&word.bbox);
+                    column.push(word);
+                }
+                None => columns.push((word.bbox, vec![word])),
+            }
+        }
+        columns.sort_by_key(|(bbox, _)| std::cmp::Reverse(bbox.x + bbox.width));
+
+        let words = columns
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, (_, mut column))| {
+                column.sort_by_key(|w| w.bbox.y);
+                column.into_iter().map(move |mut w| {
+                    w.block = 1;
+                    w.paragraph = 1;
+                    w.line = i as u32 + 1;
+                    w
+                })
+            })
+            .collect();
+        OcrResult::from_words(words, &engine)
+    }
+
+    /// Text laid out as source code: indentation, spacing between words and
+    /// blank lines are rebuilt from word positions, since OCR engines collapse
+    /// whitespace. Assumes a monospaced font.
+    pub fn code_text(&self) -> String {
+        let mut char_widths: Vec<f32> = self
+            .words()
+            .filter(|w| !w.text.is_empty())
+            .map(|w| w.bbox.width as f32 / w.text.chars().count() as f32)
+            .collect();
+        let Some(char_width) = median(&mut char_widths).filter(|w| *w > 0.0) else {
+            return self.text.clone();
+        };
+
+        let mut pitches: Vec<f32> = self
+            .lines
+            .windows(2)
+            .map(|pair| pair[1].bbox.y.saturating_sub(pair[0].bbox.y) as f32)
+            .filter(|d| *d > 0.0)
+            .collect();
+        let pitch = median(&mut pitches);
+        let left = self.lines.iter().map(|l| l.bbox.x).min().unwrap_or(0);
+
+        let mut text = String::new();
+        let mut prev_y: Option<u32> = None;
+        for line in &self.lines {
+            if let Some(prev_y) = prev_y {
+                text.push('\n');
+                // A gap of several line pitches means blank lines in between
+                if let Some(pitch) = pitch {
+                    let gap = (line.bbox.y.saturating_sub(prev_y) as f32 / pitch).round() as usize;
+                    text.push_str(&"\n".repeat(gap.saturating_sub(1)));
+                }
+            }
+            prev_y = Some(line.bbox.y);
+
+            let mut column = 0usize;
+            for (i, word) in line.words.iter().enumerate() {
+                let target = ((word.bbox.x as f32 - left as f32) / char_width).round() as usize;
+                // Words on a line are at least one space apart
+                let spaces = if i == 0 { target } else { target.saturating_sub(column).max(1) };
+                text.push_str(&" ".repeat(spaces));
+                text.push_str(&word.text);
+                column += spaces + word.text.chars().count();
+            }
+        }
+        text
+    }
+
+    /// Merge results of the same image recognized with different languages.
+    /// Where lines overlap, the one with the higher confidence wins; lines
+    /// found by only one language are kept.
+    pub fn merge_by_lines(results: Vec<OcrResult>, engine: &str) -> Self {
+        let mut candidates: Vec<OcrLine> = results.into_iter().flat_map(|r| r.lines).collect();
+        candidates.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut kept: Vec<OcrLine> = Vec::new();
+        for line in candidates {
+            let overlaps = kept.iter().any(|k| {
+                let smaller = k.bbox.area().min(line.bbox.area()).max(1);
+                k.bbox.intersection_area(&line.bbox) * 2 > smaller
+            });
+            if !overlaps {
+                kept.push(line);
+            }
+        }
+        kept.sort_by_key(|l| (l.bbox.y, l.bbox.x));
+
+        let words = kept
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                line.words.into_iter().map(move |mut w| {
+                    w.block = 1;
+                    w.paragraph = 1;
+                    w.line = i as u32 + 1;
+                    w
+                })
+            })
+            .collect();
+        OcrResult::from_words(words, engine)
+    }
+}
+
+/// Parse Tesseract TSV output (`tesseract ... tsv`) into words.
+/// Columns: level page_num block_num par_num line_num word_num left top width height conf text
+pub fn parse_tesseract_tsv(tsv: &str) -> Vec<OcrWord> {
+    let mut words = Vec::new();
+
+    for line in tsv.lines().skip(1) {
+        let cols: Vec<&str> = line.splitn(12, '\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+        let text = cols[11].trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let num = |i: usize| cols[i].trim().parse::<u32>().unwrap_or(0);
+        let confidence = cols[10].trim().parse::<f32>().unwrap_or(0.0);
+        if confidence < 0.0 {
+            continue;
+        }
+
+        words.push(OcrWord::new(
+            text.to_string(),
+            confidence,
+            BoundingBox {
+                x: num(6),
+                y: num(7),
+                width: num(8),
+                height: num(9),
+            },
+            num(2),
+            num(3),
+            num(4),
+        ));
+    }
+
+    words
+}
+
+/// Small set of very frequent English words used for dictionary-based estimation
+const COMMON_WORDS: &[&str] = &[
+    "the", "be", "to", "of", "and", "a", "in", "that", "have", "i", "it", "for", "not", "on", "with",
+    "he", "as", "you", "do", "at", "this", "but", "his", "by", "from", "they", "we", "say", "her",
+    "she", "or", "an", "will", "my", "one", "all", "would", "there", "their", "what", "so", "up",
+    "out", "if", "about", "who", "get", "which", "go", "me", "when", "make", "can", "like", "time",
+    "no", "just", "him", "know", "take", "is", "are", "was", "were", "file", "edit", "view", "help",
+    "open", "save", "close", "new", "ok", "cancel", "yes", "settings", "error", "warning",
+];
+
+/// Estimate a confidence (0-100) for a word from an engine that doesn't report one.
+/// Dictionary hits score highest, plausible alphanumeric tokens medium, and
+/// tokens dominated by stray symbols (typical OCR garbage) low.
+pub fn estimate_word_confidence(word: &str) -> f32 {
+    let trimmed = word.trim_matches(|c: char| c.is_ascii_punctuation());
+    if trimmed.is_empty() {
+        return if word.chars().count() <= 2 { 70.0 } else { 30.0 };
+    }
+
+    let lower = trimmed.to_lowercase();
+    if COMMON_WORDS.contains(&lower.as_str()) {
+        return 95.0;
+    }
+
+    let total = trimmed.chars().count() as f32;
+    if trimmed.chars().all(is_cjk) {
+        return 85.0;
+    }
+    if trimmed.chars().all(|c| c.is_ascii_digit() || c == '.' || c == ',') {
+        return 85.0;
+    }
+
+    let alnum = trimmed.chars().filter(|c| c.is_alphanumeric()).count() as f32;
+    let ratio = alnum / total;
+
+    // Mixed letters and digits inside one token ("he1lo", "0CR") are a common misread
+    let has_alpha = trimmed.chars().any(|c| c.is_alphabetic());
+    let has_digit = trimmed.chars().any(|c| c.is_ascii_digit());
+    let mixed_penalty = if has_alpha && has_digit { 15.0 } else { 0.0 };
+
+    // Unusual case changes mid-word ("tHe", "wOrD")
+    let case_switches = trimmed
+        .chars()
+        .zip(trimmed.chars().skip(1))
+        .filter(|(a, b)| a.is_lowercase() && b.is_uppercase())
+        .count() as f32;
+
+    (40.0 + 45.0 * ratio - mixed_penalty - 10.0 * case_switches).clamp(10.0, 90.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_TSV: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t200\t100\t-1\t
+5\t1\t1\t1\t1\t1\t10\t10\t40\t12\t96.5\tHello
+5\t1\t1\t1\t1\t2\t55\t10\t50\t12\t91.0\tworld
+5\t1\t1\t1\t2\t1\t10\t30\t30\t12\t20.0\t~#%
+5\t1\t2\t1\t1\t1\t10\t60\t60\t12\t88.0\tSecond";
+
+    #[test]
+    fn test_parse_tsv_words() {
+        let words = parse_tesseract_tsv(SAMPLE_TSV);
+        assert_eq!(words.len(), 4);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!(words[0].bbox, BoundingBox { x: 10, y: 10, width: 40, height: 12 });
+        assert!(words[2].low_confidence);
+    }
+
+    #[test]
+    fn test_result_groups_lines_and_filters_low_confidence() {
+        let result = OcrResult::from_words(parse_tesseract_tsv(SAMPLE_TSV), "tesseract");
+        assert_eq!(result.lines.len(), 3);
+        assert_eq!(result.text, "Hello world\n~#%\n\nSecond");
+        assert_eq!(result.confident_text, "Hello world\n\nSecond");
+        assert_eq!(result.lines[0].bbox, BoundingBox { x: 10, y: 10, width: 95, height: 12 });
+    }
+
+    #[test]
+    fn test_logical_order_bidi() {
+        // Words given in visual order, left to right
+        let line = |words: &[&str]| {
+            let words = words
+                .iter()
+                .enumerate()
+                .map(|(i, t)| OcrWord::new(t.to_string(), 90.0, BoundingBox { x: i as u32 * 100, y: 0, width: 80, height: 12 }, 1, 1, 1))
+                .collect();
+            OcrLine::from_words(words)
+        };
+
+        let hebrew = line(&["עולם", "שלום"]);
+        assert_eq!(hebrew.text, "שלום עולם");
+        assert_eq!(hebrew.text_direction, OcrTextDirection::Rtl);
+
+        // Arabic sentence with an embedded LTR run
+        let arabic = line(&["جديد", "Windows", "11", "نظام", "هذا"]);
+        assert_eq!(arabic.text, "هذا نظام Windows 11 جديد");
+
+        // English sentence with an embedded Hebrew name
+        let mixed = line(&["Hello", "עולם", "שלום", "friend"]);
+        assert_eq!(mixed.text, "Hello שלום עולם friend");
+        assert_eq!(mixed.text_direction, OcrTextDirection::Ltr);
+
+        assert_eq!(line(&["plain", "text"]).text_direction, OcrTextDirection::Ltr);
+    }
+
+    #[test]
+    fn test_join_words_cjk() {
+        assert_eq!(join_words(["你", "好", "world"]), "你好 world");
+    }
+
+    #[test]
+    fn test_estimate_word_confidence() {
+        assert!(estimate_word_confidence("the") > 90.0);
+        assert!(estimate_word_confidence("Screenshot") > LOW_CONFIDENCE_THRESHOLD);
+        assert!(estimate_word_confidence("~#%&") < LOW_CONFIDENCE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_merge_by_lines() {
+        let word = |text: &str, conf: f32, y: u32, line: u32| {
+            OcrWord::new(text.to_string(), conf, BoundingBox { x: 0, y, width: 100, height: 20 }, 1, 1, line)
+        };
+        let english = OcrResult::from_words(vec![word("Hello", 95.0, 0, 1), word("???", 20.0, 30, 2)], "windows");
+        let japanese = OcrResult::from_words(vec![word("Ha11o", 40.0, 0, 1), word("こんにちは", 90.0, 30, 2)], "windows");
+
+        let merged = OcrResult::merge_by_lines(vec![english, japanese], "windows");
+        assert_eq!(merged.text, "Hello\nこんにちは");
+        assert_eq!(merged.lines.len(), 2);
+    }
+
+    #[test]
+    fn test_reorder_vertical() {
+        let word = |text: &str, x: u32, y: u32| {
+            OcrWord::new(text.to_string(), 90.0, BoundingBox { x, y, width: 20, height: 20 }, 1, 1, 1)
+        };
+        // Two columns: right one reads 日本, left one reads 語です
+        let result = OcrResult::from_words(
+            vec![word("語", 10, 0), word("日", 50, 0), word("で", 12, 22), word("本", 51, 21), word("す", 10, 44)],
+            "windows",
+        );
+        assert_eq!(result.reorder_vertical().text, "日本\n語です");
+    }
+
+    #[test]
+    fn test_code_text_preserves_indentation() {
+        let word = |text: &str, x: u32, y: u32| {
+            let width = 10 * text.chars().count() as u32;
+            OcrWord::new(text.to_string(), 90.0, BoundingBox { x, y, width, height: 16 }, 1, 1, y / 20 + 1)
+        };
+        let result = OcrResult::from_words(
+            vec![
+                word("fn", 0, 0),
+                word("main()", 30, 0),
+                word("{", 100, 0),
+                word("let", 40, 20),
+                word("x", 80, 20),
+                word("}", 0, 60),
+                word("//", 0, 80),
+            ],
+            "tesseract",
+        );
+        assert_eq!(result.code_text(), "fn main() {\n    let x\n\n}\n//");
+    }
+}
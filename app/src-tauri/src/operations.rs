@@ -0,0 +1,200 @@
+// Cancellable operations
+// Long-running commands accept an optional `op_id` chosen by the frontend, and
+// `cancel_operation(op_id)` flags it. Work stops at its next check: a running
+// Tesseract process is killed, translation decoding stops between steps and
+// downloads between chunks. Synchronous work finds its token through a
+// thread-local set by `scope`; async downloads are handed the token.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Child, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::error::{AppError, AppResult};
+
+/// How often a child process is polled for exit or cancellation
+const CHILD_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Operations that can currently be cancelled, by id
+static OPERATIONS: Lazy<Mutex<HashMap<String, CancelToken>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// Cancellation flag shared between an operation and its canceller
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Err(Cancelled) once cancelled
+    pub fn check(&self) -> AppResult<()> {
+        if self.is_cancelled() {
+            Err(AppError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Run `f` with this token as the current thread's token
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let result = f();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+        result
+    }
+}
+
+/// A registered operation, cancellable by id until dropped
+pub struct Operation {
+    id: Option<String>,
+    token: CancelToken,
+}
+
+impl Operation {
+    pub fn token(&self) -> CancelToken {
+        self.token.clone()
+    }
+
+    pub fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.token.scope(f)
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        if let Some(id) = &self.id {
+            if let Ok(mut operations) = OPERATIONS.lock() {
+                operations.remove(id);
+            }
+        }
+    }
+}
+
+/// Register an operation. Without an id it can't be cancelled, but code
+/// checking the token works the same.
+pub fn start(op_id: Option<String>) -> AppResult<Operation> {
+    let token = CancelToken::default();
+    if let Some(id) = &op_id {
+        let mut operations = OPERATIONS.lock()?;
+        if operations.contains_key(id) {
+            return Err(AppError::InvalidInput(format!("Operation '{}' is already running", id)));
+        }
+        operations.insert(id.clone(), token.clone());
+    }
+    Ok(Operation { id: op_id, token })
+}
+
+/// The token of the operation running on this thread, if any
+pub fn current() -> Option<CancelToken> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Err(Cancelled) if the operation running on this thread was cancelled
+pub fn check() -> AppResult<()> {
+    match current() {
+        Some(token) => token.check(),
+        None => Ok(()),
+    }
+}
+
+/// Like `Child::wait_with_output`, but kills the child if the current
+/// operation is cancelled
+pub fn wait_child(mut child: Child) -> AppResult<Output> {
+    let Some(token) = current() else {
+        return Ok(child.wait_with_output()?);
+    };
+
+    // Drain the pipes while polling so a full pipe can't stall the child
+    fn drain(pipe: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<Vec<u8>> {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    }
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if token.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(AppError::Cancelled);
+        }
+        std::thread::sleep(CHILD_POLL_INTERVAL);
+    };
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Cancel a running operation. Returns false if it already finished.
+#[tauri::command]
+pub fn cancel_operation(op_id: String) -> AppResult<bool> {
+    match OPERATIONS.lock()?.get(&op_id) {
+        Some(token) => {
+            token.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_by_id() {
+        let operation = start(Some("test-cancel".to_string())).unwrap();
+        assert!(start(Some("test-cancel".to_string())).is_err());
+        operation.scope(|| assert!(check().is_ok()));
+
+        assert!(cancel_operation("test-cancel".to_string()).unwrap());
+        operation.scope(|| assert!(matches!(check(), Err(AppError::Cancelled))));
+        assert!(check().is_ok());
+
+        drop(operation);
+        assert!(!cancel_operation("test-cancel".to_string()).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_child_kills_on_cancel() {
+        use std::process::{Command, Stdio};
+
+        let token = CancelToken::default();
+        token.cancel();
+        let child = Command::new("sleep").arg("10").stdout(Stdio::piped()).spawn().unwrap();
+        assert!(matches!(token.scope(|| wait_child(child)), Err(AppError::Cancelled)));
+
+        let child = Command::new("echo").arg("done").stdout(Stdio::piped()).spawn().unwrap();
+        let output = CancelToken::default().scope(|| wait_child(child)).unwrap();
+        assert_eq!(output.stdout, b"done\n");
+    }
+}
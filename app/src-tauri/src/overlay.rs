@@ -0,0 +1,105 @@
+// Copy-text-in-place overlay
+// A frameless window placed exactly over a captured region. It shows the frozen
+// screenshot with an invisible, selectable text layer built from the OCR word
+// boxes, so text can be selected and copied where it appeared on screen.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::error::{AppError, AppResult};
+use crate::frame_diff::Region;
+use crate::ocr::{self, OcrOptions};
+use crate::ocr_result::OcrResult;
+
+/// Window labels start with this (matched by the overlay capability)
+const LABEL_PREFIX: &str = "overlay-";
+
+/// What an overlay window renders
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayPayload {
+    /// PNG screenshot of the region (base64)
+    pub image: String,
+    pub result: OcrResult,
+}
+
+/// Payloads of open overlay windows, by label
+static PAYLOADS: Lazy<Mutex<HashMap<String, OverlayPayload>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// OCR a captured region and open an overlay window over it.
+/// `rect` is the region's position on screen in physical pixels.
+#[tauri::command(async)]
+pub fn open_text_overlay(
+    app: AppHandle,
+    base64_image: String,
+    rect: Region,
+    langs: Option<String>,
+    engine: Option<String>,
+) -> AppResult<String> {
+    let bytes = ocr::decode_base64_image(&base64_image)?;
+    let result = ocr::run_ocr_detailed(&bytes, &OcrOptions { langs, engine, ..Default::default() })?;
+
+    let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let label = format!("{}{}", LABEL_PREFIX, id);
+    let image = base64_image
+        .split_once(',')
+        .map_or(base64_image.as_str(), |(_, data)| data)
+        .to_string();
+    PAYLOADS.lock()?.insert(label.clone(), OverlayPayload { image, result });
+
+    let url = WebviewUrl::App(format!("index.html?overlay={}", label).into());
+    let window = WebviewWindowBuilder::new(&app, &label, url)
+        .title("Screen Inu")
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .shadow(false)
+        .visible(false)
+        .build();
+    let window = match window {
+        Ok(window) => window,
+        Err(e) => {
+            PAYLOADS.lock()?.remove(&label);
+            return Err(AppError::Other(format!("Failed to open overlay: {}", e)));
+        }
+    };
+
+    let closed_label = label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            if let Ok(mut payloads) = PAYLOADS.lock() {
+                payloads.remove(&closed_label);
+            }
+        }
+    });
+
+    // Builder positions are logical; the region is in physical pixels
+    window
+        .set_position(PhysicalPosition::new(rect.x as i32, rect.y as i32))
+        .and_then(|_| window.set_size(PhysicalSize::new(rect.width, rect.height)))
+        .and_then(|_| window.show())
+        .and_then(|_| window.set_focus())
+        .map_err(|e| AppError::Other(format!("Failed to place overlay: {}", e)))?;
+
+    Ok(label)
+}
+
+/// Screenshot and OCR result for an overlay window
+#[tauri::command]
+pub fn get_overlay_payload(label: String) -> AppResult<OverlayPayload> {
+    PAYLOADS
+        .lock()?
+        .get(&label)
+        .cloned()
+        .ok_or_else(|| AppError::InvalidInput(format!("No overlay named {}", label)))
+}
@@ -0,0 +1,429 @@
+// Device pairing
+// Pairs two devices for sync by scanning a QR code instead of typing
+// addresses and keys. The device showing the code listens on a one-off TCP
+// port; the code carries its LAN address, that port, its device id and an
+// ephemeral 256-bit key. The scanning device connects and both prove they
+// hold the key (HMAC-SHA256 over fresh nonces) before deriving a long-term
+// secret from it and the nonces. Each side keeps the other as a paired peer,
+// with the secret in the keychain. The listener closes after one pairing or
+// five minutes, and a new code replaces the previous one.
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use hmac::{Hmac, Mac};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use sha2::Sha256;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{AppError, AppResult};
+
+const CODE_PREFIX: &str = "screeninu-pair:";
+const CODE_VERSION: u32 = 1;
+const CODE_LIFETIME: Duration = Duration::from_secs(300);
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_MESSAGE_LEN: usize = 4096;
+
+/// Emitted with the new PairedPeer when a device pairs with this one
+pub const PAIRED_EVENT: &str = "sync://paired";
+
+/// Bumped by every new code; older listeners stop when they see it change
+static SESSION: AtomicU64 = AtomicU64::new(0);
+
+/// A device paired for sync
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedPeer {
+    pub device: String,
+    pub name: String,
+    /// LAN address the peer was last seen at
+    pub host: String,
+    /// Unix epoch milliseconds
+    pub paired_at: i64,
+}
+
+/// Pairing settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PairingConfig {
+    /// Name shown on other devices; the host name when unset
+    pub device_name: Option<String>,
+    pub peers: Vec<PairedPeer>,
+}
+
+/// What the QR code carries
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct CodePayload {
+    v: u32,
+    host: String,
+    port: u16,
+    device: String,
+    name: String,
+    /// Ephemeral key, base64
+    key: String,
+}
+
+/// A pairing code to show, as text and as an SVG QR code
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingCode {
+    pub code: String,
+    pub svg: String,
+    pub expires_at: i64,
+}
+
+/// Handshake message, one JSON line each way
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct Hello {
+    device: String,
+    name: String,
+    /// base64
+    nonce: String,
+    /// base64 HMAC proving the sender holds the key
+    mac: String,
+}
+
+/// One side of a pairing
+#[derive(Debug, Clone, PartialEq)]
+struct Identity {
+    device: String,
+    name: String,
+}
+
+fn b64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+fn random_bytes() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn pairing_error(message: impl std::fmt::Display) -> AppError {
+    AppError::Other(format!("Pairing failed: {}", message))
+}
+
+fn hmac(key: &[u8], parts: &[&str]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(parts.join("\n").as_bytes());
+    mac
+}
+
+fn sign(key: &[u8], parts: &[&str]) -> String {
+    b64().encode(hmac(key, parts).finalize().into_bytes())
+}
+
+fn verify(key: &[u8], parts: &[&str], mac: &str) -> AppResult<()> {
+    let mac = b64().decode(mac).map_err(pairing_error)?;
+    hmac(key, parts)
+        .verify_slice(&mac)
+        .map_err(|_| pairing_error("the other device has a different code"))
+}
+
+/// Long-term secret both sides derive once the key is proven
+fn derive_secret(key: &[u8], host_nonce: &str, join_nonce: &str) -> Vec<u8> {
+    hmac(key, &["secret", host_nonce, join_nonce]).finalize().into_bytes().to_vec()
+}
+
+fn encode_code(payload: &CodePayload) -> String {
+    let json = serde_json::to_vec(payload).expect("payload serializes");
+    format!("{}{}", CODE_PREFIX, base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json))
+}
+
+fn decode_code(code: &str) -> AppResult<CodePayload> {
+    let invalid = || AppError::InvalidInput("Not a Screen Inu pairing code".to_string());
+    let data = code.trim().strip_prefix(CODE_PREFIX).ok_or_else(invalid)?;
+    let json = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(data)
+        .map_err(|_| invalid())?;
+    let payload: CodePayload = serde_json::from_slice(&json).map_err(|_| invalid())?;
+    if payload.v != CODE_VERSION {
+        return Err(AppError::Unsupported(format!("Pairing code version {}", payload.v)));
+    }
+    Ok(payload)
+}
+
+fn write_message<T: serde::Serialize>(stream: &mut impl Write, message: &T) -> AppResult<()> {
+    let mut line = serde_json::to_vec(message).map_err(pairing_error)?;
+    line.push(b'\n');
+    stream.write_all(&line)?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn read_message<T: serde::de::DeserializeOwned>(stream: &mut impl Read) -> AppResult<T> {
+    let mut line = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        if stream.read(&mut byte)? == 0 {
+            return Err(pairing_error("connection closed"));
+        }
+        if byte[0] == b'\n' {
+            break;
+        }
+        if line.len() >= MAX_MESSAGE_LEN {
+            return Err(pairing_error("message too long"));
+        }
+        line.push(byte[0]);
+    }
+    serde_json::from_slice(&line).map_err(pairing_error)
+}
+
+/// The code's side: check the joining device's proof, answer with ours
+fn host_handshake(stream: &mut (impl Read + Write), key: &[u8], local: &Identity) -> AppResult<(Identity, Vec<u8>)> {
+    let hello: Hello = read_message(stream)?;
+    verify(key, &["join", &hello.device, &hello.nonce], &hello.mac)?;
+
+    let nonce = b64().encode(random_bytes());
+    let reply = Hello {
+        device: local.device.clone(),
+        name: local.name.clone(),
+        mac: sign(key, &["host", &local.device, &nonce, &hello.nonce]),
+        nonce,
+    };
+    write_message(stream, &reply)?;
+
+    let secret = derive_secret(key, &reply.nonce, &hello.nonce);
+    Ok((Identity { device: hello.device, name: hello.name }, secret))
+}
+
+/// The scanning side
+fn join_handshake(
+    stream: &mut (impl Read + Write),
+    payload: &CodePayload,
+    local: &Identity,
+) -> AppResult<(Identity, Vec<u8>)> {
+    let key = b64().decode(&payload.key).map_err(pairing_error)?;
+    let nonce = b64().encode(random_bytes());
+    let hello = Hello {
+        device: local.device.clone(),
+        name: local.name.clone(),
+        mac: sign(&key, &["join", &local.device, &nonce]),
+        nonce,
+    };
+    write_message(stream, &hello)?;
+
+    let reply: Hello = read_message(stream)?;
+    if reply.device != payload.device {
+        return Err(pairing_error("answered by a different device"));
+    }
+    verify(&key, &["host", &reply.device, &reply.nonce, &hello.nonce], &reply.mac)?;
+
+    let secret = derive_secret(&key, &reply.nonce, &hello.nonce);
+    Ok((Identity { device: reply.device, name: reply.name }, secret))
+}
+
+/// Address other devices on the LAN reach this one at. Connecting a UDP
+/// socket sends nothing; it only picks the outgoing interface.
+fn local_ip() -> AppResult<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+    socket
+        .connect(("192.0.2.1", 9))
+        .map_err(|e| AppError::Other(format!("No network connection: {}", e)))?;
+    Ok(socket.local_addr()?.ip())
+}
+
+fn local_identity(config: &PairingConfig) -> AppResult<Identity> {
+    let name = config
+        .device_name
+        .clone()
+        .filter(|n| !n.trim().is_empty())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_else(|| "Screen Inu".to_string());
+    Ok(Identity {
+        device: format!("{:x}", crate::history_edits::device_id()?),
+        name,
+    })
+}
+
+fn keyring_user(device: &str) -> String {
+    format!("sync-peer-{}", device)
+}
+
+/// Keep a peer and its secret, replacing an earlier pairing with it
+fn store_peer(peer: &Identity, host: IpAddr, secret: &[u8]) -> AppResult<PairedPeer> {
+    crate::secrets::store(&keyring_user(&peer.device), &b64().encode(secret))?;
+    let peer = PairedPeer {
+        device: peer.device.clone(),
+        name: peer.name.clone(),
+        host: host.to_string(),
+        paired_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let stored = peer.clone();
+    crate::config::update(|c| {
+        c.pairing.peers.retain(|p| p.device != stored.device);
+        c.pairing.peers.push(stored);
+    })?;
+    Ok(peer)
+}
+
+fn accept(stream: &mut TcpStream, key: &[u8], local: &Identity) -> AppResult<PairedPeer> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let (peer, secret) = host_handshake(stream, key, local)?;
+    store_peer(&peer, stream.peer_addr()?.ip(), &secret)
+}
+
+/// Wait for the device that scans the code, until it pairs, the code expires
+/// or a newer code replaces it
+fn listen(app: AppHandle, listener: TcpListener, session: u64, key: [u8; 32], local: Identity) {
+    let deadline = Instant::now() + CODE_LIFETIME;
+    while Instant::now() < deadline && SESSION.load(Ordering::SeqCst) == session {
+        match listener.accept() {
+            Ok((mut stream, address)) => match accept(&mut stream, &key, &local) {
+                Ok(peer) => {
+                    tracing::info!("Paired with {} ({})", peer.name, peer.device);
+                    let _ = app.emit(PAIRED_EVENT, peer);
+                    return;
+                }
+                Err(e) => tracing::warn!("Pairing attempt from {} failed: {}", address, e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            Err(e) => {
+                tracing::warn!("Pairing listener failed: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Start listening for a device to pair with; returns the code to show it
+#[tauri::command]
+pub fn sync_generate_pairing_code(app: AppHandle) -> AppResult<PairingCode> {
+    let local = local_identity(&crate::config::get()?.pairing)?;
+    let host = local_ip()?;
+    let listener = TcpListener::bind((host, 0))?;
+    listener.set_nonblocking(true)?;
+    let key = random_bytes();
+
+    let code = encode_code(&CodePayload {
+        v: CODE_VERSION,
+        host: host.to_string(),
+        port: listener.local_addr()?.port(),
+        device: local.device.clone(),
+        name: local.name.clone(),
+        key: b64().encode(key),
+    });
+    let svg = QrCode::new(code.as_bytes())
+        .map_err(|e| AppError::Other(format!("Failed to make the QR code: {}", e)))?
+        .render::<svg::Color>()
+        .min_dimensions(256, 256)
+        .build();
+
+    let session = SESSION.fetch_add(1, Ordering::SeqCst) + 1;
+    std::thread::spawn(move || listen(app, listener, session, key, local));
+    Ok(PairingCode {
+        code,
+        svg,
+        expires_at: chrono::Utc::now().timestamp_millis() + CODE_LIFETIME.as_millis() as i64,
+    })
+}
+
+/// Pair with the device showing `code` (scanned from its QR code)
+#[tauri::command(async)]
+#[tracing::instrument(skip(code), err)]
+pub fn sync_pair_with_code(code: String) -> AppResult<PairedPeer> {
+    let payload = decode_code(&code)?;
+    let local = local_identity(&crate::config::get()?.pairing)?;
+    if payload.device == local.device {
+        return Err(AppError::InvalidInput("That is this device's own pairing code".to_string()));
+    }
+    let host: IpAddr = payload
+        .host
+        .parse()
+        .map_err(|_| AppError::InvalidInput(format!("Invalid address in pairing code: {}", payload.host)))?;
+
+    let mut stream = TcpStream::connect_timeout(&(host, payload.port).into(), IO_TIMEOUT)
+        .map_err(|e| pairing_error(format!("could not reach {} ({})", payload.name, e)))?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let (peer, secret) = join_handshake(&mut stream, &payload, &local)?;
+    store_peer(&peer, host, &secret)
+}
+
+#[tauri::command]
+pub fn get_paired_peers() -> AppResult<Vec<PairedPeer>> {
+    Ok(crate::config::get()?.pairing.peers)
+}
+
+#[tauri::command]
+pub fn set_device_name(name: Option<String>) -> AppResult<()> {
+    crate::config::update(|c| c.pairing.device_name = name.filter(|n| !n.trim().is_empty()))
+}
+
+/// Forget a paired device and its secret
+#[tauri::command]
+pub fn sync_unpair(device: String) -> AppResult<()> {
+    crate::secrets::delete(&keyring_user(&device))?;
+    crate::config::update(|c| c.pairing.peers.retain(|p| p.device != device))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(key: &[u8]) -> CodePayload {
+        CodePayload {
+            v: CODE_VERSION,
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            device: "a1".to_string(),
+            name: "Laptop".to_string(),
+            key: b64().encode(key),
+        }
+    }
+
+    #[test]
+    fn test_code_roundtrip() {
+        let payload = payload(&[7; 32]);
+        let code = encode_code(&payload);
+        assert!(code.starts_with(CODE_PREFIX));
+        assert_eq!(decode_code(&format!(" {}\n", code)).unwrap(), payload);
+        assert!(decode_code("https://example.com").is_err());
+        assert!(decode_code("screeninu-pair:???").is_err());
+    }
+
+    #[test]
+    fn test_handshake() {
+        let host = Identity { device: "a1".to_string(), name: "Laptop".to_string() };
+        let phone = Identity { device: "b2".to_string(), name: "Phone".to_string() };
+        let pair = |host_key: [u8; 32], join_key: [u8; 32]| {
+            let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let host = host.clone();
+            let server = std::thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                host_handshake(&mut stream, &host_key, &host)
+            });
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            let joined = join_handshake(&mut stream, &payload(&join_key), &phone);
+            drop(stream);
+            (server.join().unwrap(), joined)
+        };
+
+        let (hosted, joined) = pair([1; 32], [1; 32]);
+        let (hosted, joined) = (hosted.unwrap(), joined.unwrap());
+        assert_eq!(hosted.0, phone);
+        assert_eq!(joined.0, host);
+        assert_eq!(hosted.1, joined.1);
+
+        let (hosted, joined) = pair([1; 32], [2; 32]);
+        assert!(hosted.is_err());
+        assert!(joined.is_err());
+    }
+}
@@ -0,0 +1,165 @@
+// Performance metrics
+// Timings of the pipeline stages (capture, encode, OCR per engine,
+// translation) kept in memory as a rolling window per metric, so users can
+// see where a slow capture goes and compare engines. Samples can carry a
+// unit count (generated tokens) to report throughput.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use once_cell::sync::Lazy;
+
+use crate::error::AppResult;
+
+/// Samples kept per metric
+const WINDOW: usize = 200;
+
+pub const ENCODE: &str = "encode";
+pub const TRANSLATION: &str = "translation";
+
+static METRICS: Lazy<Mutex<HashMap<String, VecDeque<Sample>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Sample {
+    ms: f64,
+    /// Work done in this sample (tokens), if the metric counts any
+    units: Option<u64>,
+}
+
+/// Statistics over a metric's recent samples
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricSummary {
+    /// "capture:<backend>", "encode", "ocr:<engine>" or "translation"
+    pub name: String,
+    pub count: usize,
+    pub last_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    /// Units per second over the samples that counted units (tokens/sec)
+    pub units_per_sec: Option<f64>,
+}
+
+/// Metric name for captures with the backend named `backend`
+pub fn capture_metric(backend: &str) -> String {
+    format!("capture:{}", backend)
+}
+
+/// Metric name for OCR with `engine`
+pub fn ocr_metric(engine: crate::ocr::OcrEngine) -> String {
+    format!("ocr:{}", engine.as_str())
+}
+
+fn push(name: &str, sample: Sample) {
+    if let Ok(mut metrics) = METRICS.lock() {
+        let samples = metrics.entry(name.to_string()).or_default();
+        samples.push_back(sample);
+        if samples.len() > WINDOW {
+            samples.pop_front();
+        }
+    }
+}
+
+/// Record a duration measured from `started`
+pub fn record(name: &str, started: Instant) {
+    push(name, Sample { ms: started.elapsed().as_secs_f64() * 1000.0, units: None });
+}
+
+/// Record a duration that produced `units` units of work
+pub fn record_units(name: &str, started: Instant, units: u64) {
+    push(name, Sample { ms: started.elapsed().as_secs_f64() * 1000.0, units: Some(units) });
+}
+
+/// Run `f` and record how long it took, whether or not it succeeded
+pub fn time<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = f();
+    record(name, started);
+    result
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn summarize(name: &str, samples: &VecDeque<Sample>) -> MetricSummary {
+    let mut sorted: Vec<f64> = samples.iter().map(|s| s.ms).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let count = sorted.len();
+
+    let (units, unit_ms) = samples
+        .iter()
+        .filter_map(|s| s.units.map(|units| (units, s.ms)))
+        .fold((0u64, 0.0), |(units, ms), (u, m)| (units + u, ms + m));
+
+    MetricSummary {
+        name: name.to_string(),
+        count,
+        last_ms: samples.back().map(|s| s.ms).unwrap_or(0.0),
+        mean_ms: if count == 0 { 0.0 } else { sorted.iter().sum::<f64>() / count as f64 },
+        p50_ms: percentile(&sorted, 50.0),
+        p95_ms: percentile(&sorted, 95.0),
+        max_ms: sorted.last().copied().unwrap_or(0.0),
+        units_per_sec: (unit_ms > 0.0).then(|| units as f64 / (unit_ms / 1000.0)),
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Summaries of every metric recorded since startup (or the last reset),
+/// sorted by name
+#[tauri::command]
+pub fn get_perf_metrics() -> AppResult<Vec<MetricSummary>> {
+    let metrics = METRICS.lock()?;
+    let mut summaries: Vec<MetricSummary> = metrics.iter().map(|(name, samples)| summarize(name, samples)).collect();
+    summaries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(summaries)
+}
+
+#[tauri::command]
+pub fn reset_perf_metrics() -> AppResult<()> {
+    METRICS.lock()?.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(values: &[(f64, Option<u64>)]) -> VecDeque<Sample> {
+        values.iter().map(|&(ms, units)| Sample { ms, units }).collect()
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert_eq!(percentile(&sorted, 50.0), 10.0);
+        assert_eq!(percentile(&sorted, 95.0), 19.0);
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&[], 95.0), 0.0);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let summary = summarize("translation", &samples(&[(400.0, Some(20)), (100.0, None), (600.0, Some(40))]));
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.last_ms, 600.0);
+        assert!((summary.mean_ms - 1100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(summary.p50_ms, 400.0);
+        assert_eq!(summary.max_ms, 600.0);
+        // 60 tokens in one second
+        assert_eq!(summary.units_per_sec, Some(60.0));
+
+        assert_eq!(summarize("capture", &samples(&[(5.0, None)])).units_per_sec, None);
+    }
+}
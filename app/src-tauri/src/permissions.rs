@@ -0,0 +1,183 @@
+// Screen capture permission detection
+// macOS requires the Screen Recording permission (otherwise captures come back black
+// or wallpaper-only), Linux Wayland sessions and Flatpak / Snap sandboxes need the
+// xdg-desktop-portal.
+
+#[cfg(target_os = "linux")]
+use once_cell::sync::Lazy;
+
+use crate::error::{AppError, AppResult};
+
+/// Permission state reported to the frontend
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// Wayland session without a usable screenshot portal
+    PortalUnavailable,
+}
+
+/// Capture permission status with guidance for the user
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CapturePermission {
+    pub state: PermissionState,
+    pub platform: String,
+    /// Human-readable hint on how to fix the problem (None when granted)
+    pub guidance: Option<String>,
+    /// Deep link to the relevant system settings page, if any
+    pub settings_url: Option<String>,
+}
+
+#[cfg(target_os = "macos")]
+const MACOS_SCREEN_RECORDING_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture";
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGPreflightScreenCaptureAccess() -> bool;
+    fn CGRequestScreenCaptureAccess() -> bool;
+}
+
+#[cfg(target_os = "macos")]
+fn macos_status(granted: bool) -> CapturePermission {
+    if granted {
+        CapturePermission {
+            state: PermissionState::Granted,
+            platform: "macos".to_string(),
+            guidance: None,
+            settings_url: None,
+        }
+    } else {
+        CapturePermission {
+            state: PermissionState::Denied,
+            platform: "macos".to_string(),
+            guidance: Some(
+                "Enable Screen Inu under System Settings > Privacy & Security > Screen Recording, then restart the app."
+                    .to_string(),
+            ),
+            settings_url: Some(MACOS_SCREEN_RECORDING_URL.to_string()),
+        }
+    }
+}
+
+/// Check whether the session is Wayland
+#[cfg(target_os = "linux")]
+pub fn is_wayland_session() -> bool {
+    std::env::var("WAYLAND_DISPLAY").is_ok()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t == "wayland")
+}
+
+/// Whether xdg-desktop-portal exposes the Screenshot interface on the session
+/// bus. Asked once: captures check permission every time.
+#[cfg(target_os = "linux")]
+static SCREENSHOT_PORTAL: Lazy<bool> = Lazy::new(|| {
+    async fn query() -> ashpd::zbus::Result<u32> {
+        let connection = ashpd::zbus::Connection::session().await?;
+        let proxy = ashpd::zbus::Proxy::new(
+            &connection,
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Screenshot",
+        )
+        .await?;
+        proxy.get_property::<u32>("version").await
+    }
+    // Its own thread: the first check may come from the async runtime
+    std::thread::spawn(|| tauri::async_runtime::block_on(query()))
+        .join()
+        .is_ok_and(|version| version.is_ok())
+});
+
+#[cfg(target_os = "linux")]
+fn screenshot_portal_available() -> bool {
+    *SCREENSHOT_PORTAL
+}
+
+/// Detect the current screen capture permission state
+pub fn check_permission() -> CapturePermission {
+    #[cfg(target_os = "macos")]
+    {
+        let granted = unsafe { CGPreflightScreenCaptureAccess() };
+        macos_status(granted)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let sandboxed = crate::sandbox::use_portal_capture();
+        if (is_wayland_session() || sandboxed) && !screenshot_portal_available() {
+            return CapturePermission {
+                state: PermissionState::PortalUnavailable,
+                platform: if sandboxed { "linux-sandbox" } else { "linux-wayland" }.to_string(),
+                guidance: Some(
+                    "Screen capture on Wayland needs xdg-desktop-portal with a backend for your desktop (e.g. xdg-desktop-portal-gnome or -kde)."
+                        .to_string(),
+                ),
+                settings_url: None,
+            };
+        }
+        CapturePermission {
+            state: PermissionState::Granted,
+            platform: if is_wayland_session() { "linux-wayland" } else { "linux-x11" }.to_string(),
+            guidance: None,
+            settings_url: None,
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        CapturePermission {
+            state: PermissionState::Granted,
+            platform: std::env::consts::OS.to_string(),
+            guidance: None,
+            settings_url: None,
+        }
+    }
+}
+
+/// Fail early with a permission error instead of returning a black capture
+pub fn ensure_capture_permission() -> AppResult<()> {
+    let status = check_permission();
+    if status.state == PermissionState::Granted {
+        Ok(())
+    } else {
+        Err(AppError::PermissionDenied(
+            status
+                .guidance
+                .unwrap_or_else(|| "Screen capture is not allowed".to_string()),
+        ))
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Report whether screen capture is currently allowed
+#[tauri::command]
+pub fn check_capture_permission() -> CapturePermission {
+    check_permission()
+}
+
+/// Ask the OS for screen capture permission.
+/// On macOS this shows the system prompt the first time and opens the
+/// Screen Recording settings page if the permission was previously denied.
+#[tauri::command]
+pub fn request_capture_permission() -> CapturePermission {
+    #[cfg(target_os = "macos")]
+    {
+        let granted = unsafe { CGRequestScreenCaptureAccess() };
+        if !granted {
+            let _ = std::process::Command::new("open")
+                .arg(MACOS_SCREEN_RECORDING_URL)
+                .spawn();
+        }
+        macos_status(granted)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        check_permission()
+    }
+}
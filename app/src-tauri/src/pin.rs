@@ -0,0 +1,205 @@
+// Pinned captures
+// Borderless always-on-top windows showing a capture, like Snipaste's pins, for
+// keeping a reference on screen while working. Pins are owned by the backend:
+// their image, zoom and opacity live here, so they survive main window reloads.
+// Windows load the same page with ?pin=<label> and follow `pin://updated`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::error::{AppError, AppResult};
+use crate::ocr;
+
+/// Window labels start with this (matched by the pin capability)
+const LABEL_PREFIX: &str = "pin-";
+
+/// Emitted with the `PinState` when a pin's zoom or opacity changes
+pub const PIN_UPDATED_EVENT: &str = "pin://updated";
+
+const MIN_ZOOM: f64 = 0.1;
+const MAX_ZOOM: f64 = 8.0;
+const MIN_OPACITY: f64 = 0.1;
+
+static PINS: Lazy<Mutex<HashMap<String, PinState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Screen position of a pin's top-left corner in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PinPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// What a pin window shows
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinState {
+    pub label: String,
+    /// Base64 image, without a data URL prefix
+    pub image: String,
+    /// Image size in pixels
+    pub width: u32,
+    pub height: u32,
+    pub zoom: f64,
+    /// 0.1-1.0; applied by the page (windows are transparent except on macOS)
+    pub opacity: f64,
+}
+
+/// Window size for an image at `zoom`
+fn pin_size(width: u32, height: u32, zoom: f64) -> (u32, u32) {
+    (
+        ((width as f64 * zoom).round() as u32).max(1),
+        ((height as f64 * zoom).round() as u32).max(1),
+    )
+}
+
+fn update_pin(app: &AppHandle, label: &str, change: impl FnOnce(&mut PinState)) -> AppResult<PinState> {
+    let state = {
+        let mut pins = PINS.lock()?;
+        let pin = pins
+            .get_mut(label)
+            .ok_or_else(|| AppError::InvalidInput(format!("No pin named {}", label)))?;
+        change(pin);
+        pin.clone()
+    };
+    let _ = app.emit_to(label, PIN_UPDATED_EVENT, state.clone());
+    Ok(state)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Open a capture (base64 or data URL) in an always-on-top pin window.
+/// Without a position the pin opens centered. Returns the window label.
+#[tauri::command]
+pub fn pin_capture(app: AppHandle, image: String, position: Option<PinPosition>) -> AppResult<String> {
+    use base64::Engine;
+
+    let bytes = ocr::decode_base64_image(&image)?;
+    let decoded = crate::image_decode::decode_image_bytes(&bytes)?;
+    let (width, height) = (decoded.width(), decoded.height());
+    // The page shows PNG data; HEIC/AVIF pins are converted
+    let image = base64::engine::general_purpose::STANDARD.encode(crate::image_decode::ocr_input(&bytes)?);
+
+    let label = format!("{}{}", LABEL_PREFIX, NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    PINS.lock()?.insert(
+        label.clone(),
+        PinState {
+            label: label.clone(),
+            image,
+            width,
+            height,
+            zoom: 1.0,
+            opacity: 1.0,
+        },
+    );
+
+    let url = WebviewUrl::App(format!("index.html?pin={}", label).into());
+    let builder = WebviewWindowBuilder::new(&app, &label, url)
+        .title("Screen Inu Pin")
+        .decorations(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false);
+    // Transparent windows need the private API on macOS; opacity then
+    // blends with the window background there
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.transparent(true);
+    let window = match builder.build() {
+        Ok(window) => window,
+        Err(e) => {
+            PINS.lock()?.remove(&label);
+            return Err(AppError::Other(format!("Failed to open pin: {}", e)));
+        }
+    };
+
+    let closed_label = label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            if let Ok(mut pins) = PINS.lock() {
+                pins.remove(&closed_label);
+            }
+        }
+    });
+
+    // Sizes are physical so the capture shows 1:1 on its own monitor
+    window
+        .set_size(PhysicalSize::new(width, height))
+        .and_then(|_| match position {
+            Some(p) => window.set_position(PhysicalPosition::new(p.x, p.y)),
+            None => window.center(),
+        })
+        .and_then(|_| window.show())
+        .map_err(|e| AppError::Other(format!("Failed to place pin: {}", e)))?;
+
+    Ok(label)
+}
+
+/// Image and display settings for a pin window
+#[tauri::command]
+pub fn get_pin(label: String) -> AppResult<PinState> {
+    PINS.lock()?
+        .get(&label)
+        .cloned()
+        .ok_or_else(|| AppError::InvalidInput(format!("No pin named {}", label)))
+}
+
+#[tauri::command]
+pub fn list_pins() -> AppResult<Vec<PinState>> {
+    let mut pins: Vec<PinState> = PINS.lock()?.values().cloned().collect();
+    pins.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(pins)
+}
+
+/// Zoom a pin, resizing its window around the image
+#[tauri::command]
+pub fn set_pin_zoom(app: AppHandle, label: String, zoom: f64) -> AppResult<PinState> {
+    if !zoom.is_finite() {
+        return Err(AppError::InvalidInput(format!("Invalid zoom: {}", zoom)));
+    }
+    let state = update_pin(&app, &label, |pin| pin.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM))?;
+    if let Some(window) = app.get_webview_window(&label) {
+        let (width, height) = pin_size(state.width, state.height, state.zoom);
+        window
+            .set_size(PhysicalSize::new(width, height))
+            .map_err(|e| AppError::Other(format!("Failed to resize pin: {}", e)))?;
+    }
+    Ok(state)
+}
+
+#[tauri::command]
+pub fn set_pin_opacity(app: AppHandle, label: String, opacity: f64) -> AppResult<PinState> {
+    if !opacity.is_finite() {
+        return Err(AppError::InvalidInput(format!("Invalid opacity: {}", opacity)));
+    }
+    update_pin(&app, &label, |pin| pin.opacity = opacity.clamp(MIN_OPACITY, 1.0))
+}
+
+#[tauri::command]
+pub fn close_pin(app: AppHandle, label: String) -> AppResult<()> {
+    if let Some(window) = app.get_webview_window(&label) {
+        window
+            .close()
+            .map_err(|e| AppError::Other(format!("Failed to close pin: {}", e)))?;
+    }
+    PINS.lock()?.remove(&label);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_size() {
+        assert_eq!(pin_size(800, 600, 1.0), (800, 600));
+        assert_eq!(pin_size(800, 600, 0.5), (400, 300));
+        assert_eq!(pin_size(3, 3, 0.1), (1, 1));
+    }
+}
@@ -0,0 +1,404 @@
+// Post-processing plugins
+// User-provided WebAssembly modules chained after OCR and/or translation
+// (glossaries, profanity filters, formatting). Plugins are sandboxed: no
+// imports are provided, memory is capped and execution is fuel-limited.
+//
+// A plugin is a `.wasm` file in `<app data>/plugins` exporting:
+//   memory                                 linear memory
+//   alloc(len: i32) -> i32                 buffer for `len` bytes of input
+//   process(ptr: i32, len: i32) -> i64     UTF-8 in, returns (out_ptr << 32) | out_len
+//
+// The runtime (wasmtime) is only built with the `wasm-plugins` feature;
+// without it the chain is skipped and installing a plugin fails.
+
+use std::path::{Path, PathBuf};
+#[cfg(feature = "wasm-plugins")]
+use std::{collections::HashMap, sync::Mutex, time::SystemTime};
+
+#[cfg(feature = "wasm-plugins")]
+use once_cell::sync::Lazy;
+#[cfg(feature = "wasm-plugins")]
+use wasmtime::{Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::error::{AppError, AppResult};
+use crate::layout::LayoutResult;
+use crate::ocr_result::OcrResult;
+
+/// Instructions a plugin may execute per call (roughly a second of work)
+#[cfg(feature = "wasm-plugins")]
+const FUEL_PER_CALL: u64 = 2_000_000_000;
+
+/// Linear memory a plugin may grow to
+#[cfg(feature = "wasm-plugins")]
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+#[cfg(feature = "wasm-plugins")]
+static ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut config = wasmtime::Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).expect("wasmtime engine configuration is valid")
+});
+
+/// Compiled modules by file name, with the modification time they were compiled at
+#[cfg(feature = "wasm-plugins")]
+static MODULES: Lazy<Mutex<HashMap<String, (SystemTime, Module)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Where a plugin runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginStage {
+    Ocr,
+    Translation,
+}
+
+/// A plugin in the chain
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginEntry {
+    /// File name in the plugins directory
+    pub name: String,
+    pub enabled: bool,
+    pub stages: Vec<PluginStage>,
+}
+
+/// Plugin chain, in execution order
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PluginsConfig {
+    pub chain: Vec<PluginEntry>,
+}
+
+pub fn get_plugins_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join("plugins"))
+}
+
+/// Path of an installed plugin. Names come from the frontend, so anything
+/// that could leave the plugins directory is rejected.
+fn plugin_path(dir: &Path, name: &str) -> AppResult<PathBuf> {
+    let valid = name.ends_with(".wasm")
+        && !name.starts_with('.')
+        && !name.contains("..")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(AppError::InvalidInput(format!("Invalid plugin name: {}", name)));
+    }
+    Ok(dir.join(name))
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn plugin_error(name: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::Other(format!("Plugin {}: {}", name, e))
+}
+
+/// Split the packed `process` return value into (pointer, length)
+#[cfg(feature = "wasm-plugins")]
+fn unpack(packed: i64) -> (usize, usize) {
+    let packed = packed as u64;
+    ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize)
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn load_module(path: &Path) -> AppResult<Module> {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+    let modified = std::fs::metadata(path)?.modified()?;
+
+    let mut modules = MODULES.lock()?;
+    if let Some((compiled_at, module)) = modules.get(&name) {
+        if *compiled_at == modified {
+            return Ok(module.clone());
+        }
+    }
+    let module = Module::from_file(&ENGINE, path).map_err(|e| plugin_error(&name, e))?;
+    modules.insert(name, (modified, module.clone()));
+    Ok(module)
+}
+
+/// Run a compiled plugin on `text`
+#[cfg(feature = "wasm-plugins")]
+fn run_module(name: &str, module: &Module, text: &str) -> AppResult<String> {
+    let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).build();
+    let mut store: Store<StoreLimits> = Store::new(&ENGINE, limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(FUEL_PER_CALL).map_err(|e| plugin_error(name, e))?;
+
+    let instance = Instance::new(&mut store, module, &[]).map_err(|e| plugin_error(name, e))?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| plugin_error(name, "does not export memory"))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&mut store, "alloc")
+        .map_err(|e| plugin_error(name, e))?;
+    let process = instance
+        .get_typed_func::<(i32, i32), i64>(&mut store, "process")
+        .map_err(|e| plugin_error(name, e))?;
+
+    let input = text.as_bytes();
+    let len = i32::try_from(input.len()).map_err(|_| plugin_error(name, "input too large"))?;
+    let ptr = alloc.call(&mut store, len).map_err(|e| plugin_error(name, e))?;
+    memory
+        .write(&mut store, ptr as u32 as usize, input)
+        .map_err(|e| plugin_error(name, e))?;
+
+    let packed = process.call(&mut store, (ptr, len)).map_err(|e| plugin_error(name, e))?;
+    let (out_ptr, out_len) = unpack(packed);
+    let mut output = vec![0u8; out_len];
+    memory
+        .read(&store, out_ptr, &mut output)
+        .map_err(|e| plugin_error(name, e))?;
+    String::from_utf8(output).map_err(|_| plugin_error(name, "returned invalid UTF-8"))
+}
+
+/// Run one installed plugin
+#[cfg(feature = "wasm-plugins")]
+pub fn run_plugin(name: &str, text: &str) -> AppResult<String> {
+    let module = load_module(&plugin_path(&get_plugins_dir()?, name)?)?;
+    run_module(name, &module, text)
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+pub fn run_plugin(_name: &str, _text: &str) -> AppResult<String> {
+    Err(not_built())
+}
+
+/// Compile a module to be installed and check it has the plugin exports
+#[cfg(feature = "wasm-plugins")]
+fn check_exports(path: &Path, name: &str) -> AppResult<()> {
+    let module = Module::from_file(&ENGINE, path).map_err(|e| plugin_error(name, e))?;
+    for export in ["memory", "alloc", "process"] {
+        if module.get_export(export).is_none() {
+            return Err(plugin_error(name, format!("missing export `{}`", export)));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn check_exports(_path: &Path, _name: &str) -> AppResult<()> {
+    Err(not_built())
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn not_built() -> AppError {
+    AppError::Unsupported("Plugins are not supported by this build (wasm-plugins feature)".to_string())
+}
+
+/// Enabled plugins for a stage, in order; none when plugins can't run
+fn stage_chain(stage: PluginStage) -> Vec<PluginEntry> {
+    if cfg!(not(feature = "wasm-plugins")) {
+        return Vec::new();
+    }
+    match crate::config::get() {
+        Ok(config) => config
+            .plugins
+            .chain
+            .into_iter()
+            .filter(|p| p.enabled && p.stages.contains(&stage))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Pass text through a chain. A failing plugin is skipped so a broken module
+/// can't swallow OCR results.
+fn run_chain(chain: &[PluginEntry], text: &str) -> String {
+    chain.iter().fold(text.to_string(), |text, plugin| match run_plugin(&plugin.name, &text) {
+        Ok(processed) => processed,
+        Err(e) => {
+            tracing::warn!("{}", e);
+            text
+        }
+    })
+}
+
+/// Pass text through the enabled plugins for a stage
+pub fn apply(stage: PluginStage, text: &str) -> String {
+    run_chain(&stage_chain(stage), text)
+}
+
+/// Pass a detailed result through the enabled plugins: the whole text (so
+/// plugins see the context) and each line, for overlays. Word boxes keep the
+/// recognized text.
+pub fn apply_to_result(stage: PluginStage, result: &mut OcrResult) {
+    let chain = stage_chain(stage);
+    if chain.is_empty() {
+        return;
+    }
+    result.text = run_chain(&chain, &result.text);
+    result.confident_text = run_chain(&chain, &result.confident_text);
+    for line in &mut result.lines {
+        line.text = run_chain(&chain, &line.text);
+    }
+}
+
+/// Pass a layout through the enabled plugins line by line and reassemble
+/// the block texts. Layout analysis rebuilds lines from the recognized
+/// words, so this runs on the analyzed layout.
+pub fn apply_to_layout(stage: PluginStage, layout: &mut LayoutResult) {
+    let chain = stage_chain(stage);
+    if chain.is_empty() {
+        return;
+    }
+    for block in &mut layout.blocks {
+        for line in &mut block.lines {
+            line.text = run_chain(&chain, &line.text);
+        }
+        block.text = block.lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n");
+    }
+    layout.text = layout.blocks.iter().map(|b| b.text.as_str()).collect::<Vec<_>>().join("\n\n");
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Installed plugins in chain order (plugins not yet in the chain are appended, disabled)
+#[tauri::command]
+pub fn list_plugins() -> AppResult<Vec<PluginEntry>> {
+    let mut chain = crate::config::get()?.plugins.chain;
+    let dir = get_plugins_dir()?;
+    let mut installed: Vec<String> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|e| e.file_name().to_str().map(str::to_string))
+            .filter(|n| n.ends_with(".wasm"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    installed.sort();
+
+    chain.retain(|p| installed.contains(&p.name));
+    for name in installed {
+        if !chain.iter().any(|p| p.name == name) {
+            chain.push(PluginEntry {
+                name,
+                enabled: false,
+                stages: vec![PluginStage::Ocr],
+            });
+        }
+    }
+    Ok(chain)
+}
+
+/// Replace the plugin chain (order, enabled state and stages)
+#[tauri::command]
+pub fn set_plugin_chain(chain: Vec<PluginEntry>) -> AppResult<()> {
+    crate::config::update(|c| c.plugins.chain = chain)
+}
+
+/// Copy a .wasm file into the plugins directory after checking its exports
+#[tauri::command]
+pub fn install_plugin(path: String) -> AppResult<String> {
+    let source = Path::new(&path);
+    let name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|n| n.ends_with(".wasm"))
+        .ok_or_else(|| AppError::InvalidInput("Plugins must be .wasm files".to_string()))?
+        .to_string();
+    check_exports(source, &name)?;
+
+    let dir = get_plugins_dir()?;
+    let target = plugin_path(&dir, &name)?;
+    std::fs::create_dir_all(&dir)?;
+    std::fs::copy(source, target)?;
+    Ok(name)
+}
+
+#[tauri::command]
+pub fn remove_plugin(name: String) -> AppResult<()> {
+    let path = plugin_path(&get_plugins_dir()?, &name)?;
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    #[cfg(feature = "wasm-plugins")]
+    MODULES.lock()?.remove(&name);
+    crate::config::update(|c| c.plugins.chain.retain(|p| p.name != name))
+}
+
+/// Run a single plugin on sample text (for trying it out in settings)
+#[tauri::command]
+pub fn test_plugin(name: String, text: String) -> AppResult<String> {
+    run_plugin(&name, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Uppercases ASCII in place
+    #[cfg(feature = "wasm-plugins")]
+    const UPPERCASE_WAT: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $heap (mut i32) (i32.const 1024))
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $heap))
+            (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+            (local.get $ptr))
+          (func (export "process") (param $ptr i32) (param $len i32) (result i64)
+            (local $i i32)
+            (local $c i32)
+            (block $done
+              (loop $next
+                (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+                (if (i32.and (i32.ge_u (local.get $c) (i32.const 97)) (i32.le_u (local.get $c) (i32.const 122)))
+                  (then (i32.store8 (i32.add (local.get $ptr) (local.get $i)) (i32.sub (local.get $c) (i32.const 32)))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br $next)))
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+              (i64.extend_i32_u (local.get $len)))))
+    "#;
+
+    #[test]
+    #[cfg(feature = "wasm-plugins")]
+    fn test_unpack() {
+        assert_eq!(unpack((1024i64 << 32) | 5), (1024, 5));
+    }
+
+    #[test]
+    #[cfg(feature = "wasm-plugins")]
+    fn test_run_module() {
+        let module = Module::new(&ENGINE, UPPERCASE_WAT).unwrap();
+        assert_eq!(run_module("upper", &module, "Hello, 世界").unwrap(), "HELLO, 世界");
+    }
+
+    /// `process` runs `body`; `alloc` hands out the start of memory
+    #[cfg(feature = "wasm-plugins")]
+    fn plugin(body: &str) -> Module {
+        let wat = format!(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "process") (param i32 i32) (result i64) {} (i64.const 0)))"#,
+            body
+        );
+        Module::new(&ENGINE, wat).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "wasm-plugins")]
+    fn test_run_module_limits() {
+        // Runs out of fuel instead of hanging OCR
+        let spin = plugin("(loop $forever (br $forever))");
+        assert!(run_module("spin", &spin, "text").is_err());
+
+        // Growing past the memory cap fails; the plugin traps when it does
+        let pages = MAX_MEMORY_BYTES / 65536;
+        let greedy = plugin(&format!("(if (i32.lt_s (memory.grow (i32.const {})) (i32.const 0)) (then unreachable))", pages));
+        assert!(run_module("greedy", &greedy, "text").is_err());
+        let modest = plugin("(if (i32.lt_s (memory.grow (i32.const 1)) (i32.const 0)) (then unreachable))");
+        assert_eq!(run_module("modest", &modest, "text").unwrap(), "");
+    }
+
+    #[test]
+    fn test_plugin_path() {
+        let dir = Path::new("plugins");
+        assert_eq!(plugin_path(dir, "glossary-v2.wasm").unwrap(), dir.join("glossary-v2.wasm"));
+        for name in ["../config.json", "../x.wasm", "a/b.wasm", "a\\b.wasm", ".wasm", "x.txt", ""] {
+            assert!(plugin_path(dir, name).is_err(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+// Portable mode
+// With a `portable.flag` file next to the executable, everything the app
+// writes (config, tessdata tiers, models, logs, the settings store and the
+// history file) goes to a `data` folder beside it instead of the user profile,
+// for running from a USB stick or on machines without a writable profile.
+// On Windows the WebView2 profile moves there too.
+
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+
+const FLAG_FILE: &str = "portable.flag";
+const DATA_DIR: &str = "data";
+const SETTINGS_STORE: &str = "settings.json";
+
+static PORTABLE_DIR: Lazy<Option<PathBuf>> =
+    Lazy::new(|| std::env::current_exe().ok().and_then(|exe| portable_dir_for(&exe)));
+
+/// Portable mode state for the frontend
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortableInfo {
+    pub portable: bool,
+    /// Data folder beside the executable (portable mode only)
+    pub data_dir: Option<String>,
+    /// Path to load the settings store from
+    pub settings_path: String,
+}
+
+fn portable_dir_for(exe: &Path) -> Option<PathBuf> {
+    let dir = exe.parent()?;
+    dir.join(FLAG_FILE).is_file().then(|| dir.join(DATA_DIR))
+}
+
+/// Data folder beside the executable, when running portable
+pub fn portable_dir() -> Option<PathBuf> {
+    PORTABLE_DIR.clone()
+}
+
+/// Settings store path: absolute in portable mode, otherwise relative to
+/// the store plugin's app data directory
+pub fn settings_store_path() -> PathBuf {
+    match portable_dir() {
+        Some(dir) => dir.join(SETTINGS_STORE),
+        None => PathBuf::from(SETTINGS_STORE),
+    }
+}
+
+/// Point the webview profile at the portable folder. Must run before any
+/// webview is created.
+pub fn init() {
+    #[cfg(windows)]
+    if let Some(dir) = portable_dir() {
+        std::env::set_var("WEBVIEW2_USER_DATA_FOLDER", dir.join("webview"));
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_portable_info() -> PortableInfo {
+    let data_dir = portable_dir();
+    PortableInfo {
+        portable: data_dir.is_some(),
+        data_dir: data_dir.map(|d| d.display().to_string()),
+        settings_path: settings_store_path().display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_dir_for() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let exe = dir.join("screen-inu.exe");
+        assert_eq!(portable_dir_for(&exe), None);
+
+        std::fs::write(dir.join(FLAG_FILE), "").unwrap();
+        assert_eq!(portable_dir_for(&exe), Some(dir.join(DATA_DIR)));
+    }
+}
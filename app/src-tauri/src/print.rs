@@ -0,0 +1,199 @@
+// Print support for captures and OCR text
+// Uses the system print spooler: CUPS `lp` on macOS/Linux, the shell "Print" verb on Windows.
+
+#[cfg(not(windows))]
+use std::ffi::OsString;
+use std::io::Write;
+#[cfg(not(windows))]
+use std::path::Path;
+use std::process::Command;
+
+use tempfile::TempPath;
+
+use crate::error::{AppError, AppResult};
+
+/// Print options
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrintOptions {
+    /// Target printer; the system default is used when empty
+    pub printer: Option<String>,
+    pub copies: Option<u32>,
+    /// Scale the image to fill the page
+    pub fit_to_page: bool,
+    /// Also print this OCR text as a separate page
+    pub text: Option<String>,
+}
+
+/// Write data to a temp file that is removed when the returned path is dropped
+fn write_temp_file(extension: &str, data: &[u8]) -> AppResult<TempPath> {
+    let mut file = tempfile::Builder::new()
+        .prefix("screen-inu-print-")
+        .suffix(&format!(".{}", extension))
+        .tempfile()?;
+    file.write_all(data)?;
+    Ok(file.into_temp_path())
+}
+
+/// Build the `lp` arguments for printing `path`
+#[cfg(not(windows))]
+fn lp_args(path: &Path, options: &PrintOptions) -> AppResult<Vec<OsString>> {
+    let mut args: Vec<OsString> = Vec::new();
+    if let Some(printer) = options.printer.as_deref().filter(|p| !p.is_empty()) {
+        // lp would read a leading dash as another option
+        if printer.starts_with('-') {
+            return Err(AppError::InvalidInput(format!("Invalid printer name: {}", printer)));
+        }
+        args.extend(["-d".into(), printer.into()]);
+    }
+    if let Some(copies) = options.copies {
+        args.extend(["-n".into(), copies.max(1).to_string().into()]);
+    }
+    if options.fit_to_page {
+        args.extend(["-o".into(), "fit-to-page".into()]);
+    }
+    args.extend(["--".into(), path.into()]);
+    Ok(args)
+}
+
+/// Send a file to the printer; the file is removed once it has been spooled
+#[cfg(not(windows))]
+fn print_file(path: TempPath, options: &PrintOptions) -> AppResult<()> {
+    let output = Command::new("lp")
+        .args(lp_args(&path, options)?)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                AppError::Unsupported("The 'lp' command was not found. Please install CUPS.".to_string())
+            }
+            _ => AppError::Io(e),
+        })?;
+
+    if !output.status.success() {
+        return Err(AppError::Other(format!(
+            "Print failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Send a file to the printer; the file is removed once the print handler had time to read it
+#[cfg(windows)]
+fn print_file(path: TempPath, options: &PrintOptions) -> AppResult<()> {
+    let file = path.display().to_string().replace('\'', "''");
+    let script = match options.printer.as_deref().filter(|p| !p.is_empty()) {
+        Some(printer) => format!(
+            "Start-Process -FilePath '{}' -Verb PrintTo -ArgumentList '\"{}\"' -WindowStyle Hidden",
+            file,
+            printer.replace('\'', "''")
+        ),
+        None => format!("Start-Process -FilePath '{}' -Verb Print -WindowStyle Hidden", file),
+    };
+
+    for _ in 0..options.copies.unwrap_or(1).max(1) {
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .output()?;
+        if !output.status.success() {
+            return Err(AppError::Other(format!(
+                "Print failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+
+    // The print verb returns before the handler has read the file
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_secs(60));
+        drop(path);
+    });
+    Ok(())
+}
+
+/// List installed printers
+pub fn get_printers() -> AppResult<Vec<String>> {
+    #[cfg(windows)]
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Get-Printer | Select-Object -ExpandProperty Name",
+        ])
+        .output()?;
+
+    #[cfg(not(windows))]
+    let output = Command::new("lpstat").arg("-e").output()?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Print a capture (base64 image) and optionally its OCR text
+#[tauri::command]
+pub fn print_capture(base64_image: String, options: Option<PrintOptions>) -> AppResult<()> {
+    let options = options.unwrap_or_default();
+    let bytes = crate::ocr::decode_base64_image(&base64_image)?;
+
+    print_file(write_temp_file("png", &bytes)?, &options)?;
+
+    if let Some(text) = options.text.as_deref().filter(|t| !t.trim().is_empty()) {
+        print_file(write_temp_file("txt", text.as_bytes())?, &options)?;
+    }
+
+    Ok(())
+}
+
+/// List printers available to the system print queue
+#[tauri::command]
+pub fn list_printers() -> AppResult<Vec<String>> {
+    get_printers()
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lp_args() {
+        let path = Path::new("/tmp/capture.png");
+        assert_eq!(lp_args(path, &PrintOptions::default()).unwrap(), ["--", "/tmp/capture.png"]);
+
+        let options = PrintOptions {
+            printer: Some("Office".to_string()),
+            copies: Some(0),
+            fit_to_page: true,
+            text: None,
+        };
+        assert_eq!(
+            lp_args(path, &options).unwrap(),
+            ["-d", "Office", "-n", "1", "-o", "fit-to-page", "--", "/tmp/capture.png"]
+        );
+    }
+
+    #[test]
+    fn test_lp_args_rejects_option_printer() {
+        let options = PrintOptions {
+            printer: Some("-h evil".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(lp_args(Path::new("a.png"), &options), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_temp_file_removed_on_drop() {
+        let path = write_temp_file("txt", b"hello").unwrap();
+        let copy = path.to_path_buf();
+        assert_eq!(std::fs::read(&copy).unwrap(), b"hello");
+        drop(path);
+        assert!(!copy.exists());
+    }
+}
@@ -0,0 +1,273 @@
+// Push-to-OCR
+// While the hotkey is held, the region around the cursor is captured, OCR'd
+// and translated in a loop, and shown in a small always-on-top window below
+// it; releasing the hotkey stops the loop and closes the window. Press and
+// release come from the global shortcut on the Rust side, so it works while
+// the main window is hidden. Frames that didn't change are not re-recognized.
+
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use image::ImageFormat;
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Pipeline};
+use crate::frame_diff::{self, Region};
+use crate::ocr::{self, OcrOptions};
+
+/// Label of the result window (matched by its capability)
+pub const WINDOW_LABEL: &str = "push-to-ocr";
+
+/// Emitted to the result window with a `PushToOcrUpdate`
+pub const PUSH_TO_OCR_EVENT: &str = "push-to-ocr://updated";
+
+/// Height of the result window below the region
+const WINDOW_HEIGHT: u32 = 160;
+
+const MIN_INTERVAL_MS: u64 = 200;
+
+/// Stop flag of the running loop, while the hotkey is held
+static SESSION: Lazy<Mutex<Option<Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(None));
+/// Last update, for a window that opens after it was emitted
+static LATEST: Lazy<Mutex<Option<PushToOcrUpdate>>> = Lazy::new(|| Mutex::new(None));
+/// Hotkey currently registered
+static REGISTERED_HOTKEY: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// Push-to-OCR settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PushToOcrConfig {
+    pub enabled: bool,
+    /// Global shortcut to hold, e.g. "Alt+Q"
+    pub hotkey: Option<String>,
+    /// Size of the region around the cursor, in physical pixels
+    pub width: u32,
+    pub height: u32,
+    /// Tesseract languages; the default OCR languages when unset
+    pub langs: Option<String>,
+    /// Offline translation pair; the text is shown untranslated without one
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+    /// Time between captures while held
+    pub interval_ms: u64,
+}
+
+impl Default for PushToOcrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hotkey: None,
+            width: 600,
+            height: 200,
+            langs: None,
+            source_lang: None,
+            target_lang: None,
+            interval_ms: 500,
+        }
+    }
+}
+
+/// Payload of `push-to-ocr://updated`
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushToOcrUpdate {
+    pub text: String,
+    pub translation: Option<String>,
+    pub error: Option<String>,
+}
+
+fn recognize(frame: &image::RgbaImage, config: &PushToOcrConfig) -> AppResult<PushToOcrUpdate> {
+    let mut bytes = Vec::new();
+    frame
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    let options = OcrOptions { langs: config.langs.clone(), ..Default::default() };
+    let result = events::track(Pipeline::Ocr, "push_to_ocr", None, || ocr::run_ocr_detailed(&bytes, &options))?;
+
+    // Low-confidence lines are shown but not translated
+    let translation = match (&config.source_lang, &config.target_lang) {
+        (Some(source), Some(target)) if !result.confident_text.trim().is_empty() => {
+            let params = crate::config::get()?.translation.decoding;
+            let run = events::start(Pipeline::Translate, "push_to_ocr", None);
+            let translated = tauri::async_runtime::block_on(crate::translation_worker::translate(
+                result.confident_text.clone(),
+                source.clone(),
+                target.clone(),
+                params,
+                None,
+                false,
+            ));
+            Some(run.finish(translated)?.text)
+        }
+        _ => None,
+    };
+    Ok(PushToOcrUpdate { text: result.text, translation, error: None })
+}
+
+fn run_session(app: AppHandle, config: PushToOcrConfig, region: Region, stop: Arc<AtomicBool>) {
+    let interval = Duration::from_millis(config.interval_ms.max(MIN_INTERVAL_MS));
+    let mut backend = XcapBackend::new(0);
+    let mut previous: Option<image::RgbaImage> = None;
+
+    while !stop.load(Ordering::SeqCst) {
+        let started = Instant::now();
+        match backend.capture_region(region.x, region.y, region.width, region.height) {
+            Ok(frame) => {
+                let unchanged = previous
+                    .as_ref()
+                    .is_some_and(|prev| frame_diff::changed_regions(prev, &frame).is_empty());
+                if !unchanged {
+                    let update = recognize(&frame, &config).unwrap_or_else(|e| PushToOcrUpdate {
+                        text: String::new(),
+                        translation: None,
+                        error: Some(e.localized_message()),
+                    });
+                    // Released while recognizing
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(mut latest) = LATEST.lock() {
+                        *latest = Some(update.clone());
+                    }
+                    let _ = app.emit_to(WINDOW_LABEL, PUSH_TO_OCR_EVENT, update);
+                    previous = Some(frame);
+                }
+            }
+            Err(e) => tracing::warn!("Push-to-OCR capture failed: {}", e),
+        }
+        if let Some(remaining) = interval.checked_sub(started.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+}
+
+/// Open the result window just below the region
+fn open_window(app: &AppHandle, region: &Region) -> AppResult<()> {
+    let window = match app.get_webview_window(WINDOW_LABEL) {
+        Some(window) => window,
+        None => WebviewWindowBuilder::new(app, WINDOW_LABEL, WebviewUrl::App("index.html?push-to-ocr=1".into()))
+            .title("Screen Inu")
+            .decorations(false)
+            .resizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(false)
+            .visible(false)
+            .build()
+            .map_err(|e| AppError::Other(format!("Failed to open push-to-OCR window: {}", e)))?,
+    };
+    // Builder positions are logical; the region is in physical pixels
+    window
+        .set_position(PhysicalPosition::new(region.x as i32, (region.y + region.height) as i32))
+        .and_then(|_| window.set_size(PhysicalSize::new(region.width, WINDOW_HEIGHT)))
+        .and_then(|_| window.show())
+        .map_err(|e| AppError::Other(format!("Failed to place push-to-OCR window: {}", e)))
+}
+
+/// Hotkey pressed: start recognizing around the cursor
+fn begin(app: &AppHandle) -> AppResult<()> {
+    let mut session = SESSION.lock()?;
+    // Key repeat while held
+    if session.is_some() {
+        return Ok(());
+    }
+    crate::permissions::ensure_capture_permission()?;
+    let config = crate::config::get()?.push_to_ocr;
+    let cursor = app
+        .cursor_position()
+        .map_err(|e| AppError::Other(format!("Cursor position unavailable: {}", e)))?;
+    let region = Region::around(cursor.x, cursor.y, config.width, config.height);
+    *LATEST.lock()? = None;
+    open_window(app, &region)?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let session_app = app.clone();
+    std::thread::spawn(move || run_session(session_app, config, region, thread_stop));
+    *session = Some(stop);
+    Ok(())
+}
+
+/// Hotkey released: stop and hide everything
+fn end(app: &AppHandle) {
+    if let Some(stop) = SESSION.lock().ok().and_then(|mut session| session.take()) {
+        stop.store(true, Ordering::SeqCst);
+    }
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.close();
+    }
+}
+
+/// Register the hotkey from the config, replacing the previous one
+fn register_hotkey(app: &AppHandle, config: &PushToOcrConfig) -> AppResult<()> {
+    let mut registered = REGISTERED_HOTKEY.lock()?;
+    let wanted = config.hotkey.clone().filter(|h| config.enabled && !h.is_empty());
+    if *registered == wanted {
+        return Ok(());
+    }
+    if let Some(previous) = registered.take() {
+        let _ = app.global_shortcut().unregister(previous.as_str());
+        end(app);
+    }
+    if let Some(hotkey) = &wanted {
+        app.global_shortcut()
+            .on_shortcut(hotkey.as_str(), |app, _shortcut, event| match event.state {
+                ShortcutState::Pressed => {
+                    if let Err(e) = begin(app) {
+                        tracing::warn!("Push-to-OCR: {}", e);
+                        end(app);
+                    }
+                }
+                ShortcutState::Released => end(app),
+            })
+            .map_err(|e| AppError::InvalidInput(format!("Invalid hotkey '{}': {}", hotkey, e)))?;
+    }
+    *registered = wanted;
+    Ok(())
+}
+
+/// The registered hotkey, for conflict checks
+pub fn hotkey() -> Option<String> {
+    REGISTERED_HOTKEY.lock().ok().and_then(|hotkey| hotkey.clone())
+}
+
+/// Register the hotkey if push-to-OCR is enabled
+pub fn init(app: &AppHandle) {
+    if let Err(e) = crate::config::get().and_then(|c| register_hotkey(app, &c.push_to_ocr)) {
+        tracing::warn!("Push-to-OCR: {}", e);
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_push_to_ocr_config() -> AppResult<PushToOcrConfig> {
+    Ok(crate::config::get()?.push_to_ocr)
+}
+
+/// The latest result while the hotkey is held
+#[tauri::command]
+pub fn get_push_to_ocr_update() -> AppResult<Option<PushToOcrUpdate>> {
+    Ok(LATEST.lock()?.clone())
+}
+
+/// Save the settings and apply the hotkey right away
+#[tauri::command]
+pub fn set_push_to_ocr_config(app: AppHandle, config: PushToOcrConfig) -> AppResult<()> {
+    if config.width < 16 || config.height < 16 {
+        return Err(AppError::InvalidInput("Push-to-OCR region is too small".to_string()));
+    }
+    if config.source_lang.is_some() != config.target_lang.is_some() {
+        return Err(AppError::InvalidInput("Set both translation languages or neither".to_string()));
+    }
+    register_hotkey(&app, &config)?;
+    crate::config::update(|c| c.push_to_ocr = config)
+}
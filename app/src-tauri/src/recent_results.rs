@@ -0,0 +1,180 @@
+// Recent results
+// A clipboard-history-like stack of the last OCR and translation results, kept
+// in memory only. `pop_recent_result(1)` copies the second most recent one
+// back to the clipboard without opening history; the tray lists the stack in
+// a "Recent results" submenu. OCR results are pushed by `perform_ocr`,
+// translations by the frontend (online translation runs in the webview).
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tauri::{
+    menu::{MenuItem, Submenu},
+    AppHandle, Runtime,
+};
+
+use crate::error::{AppError, AppResult};
+use crate::i18n::t;
+
+/// Emitted when the stack changes, for the tray to rebuild its submenu
+pub const RECENT_RESULTS_CHANGED_EVENT: &str = "recent://changed";
+
+/// Results kept
+const MAX_RESULTS: usize = 10;
+
+/// Characters of a result shown in the tray
+const MENU_LABEL_CHARS: usize = 48;
+
+const MENU_ID_PREFIX: &str = "recent:";
+
+/// Newest first
+static RECENT: Lazy<Mutex<VecDeque<RecentResult>>> = Lazy::new(|| Mutex::new(VecDeque::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecentKind {
+    Ocr,
+    Translation,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentResult {
+    pub kind: RecentKind,
+    pub text: String,
+    /// RFC 3339
+    pub timestamp: String,
+}
+
+/// Put `result` on top, dropping an older copy of the same text and the
+/// oldest results beyond the limit
+fn push_front(stack: &mut VecDeque<RecentResult>, result: RecentResult) {
+    stack.retain(|r| r.text != result.text);
+    stack.push_front(result);
+    stack.truncate(MAX_RESULTS);
+}
+
+/// Move the result at `index` back on top
+fn take(stack: &mut VecDeque<RecentResult>, index: usize) -> AppResult<RecentResult> {
+    let result = stack
+        .remove(index)
+        .ok_or_else(|| AppError::InvalidInput(format!("No recent result #{}", index)))?;
+    stack.push_front(result.clone());
+    Ok(result)
+}
+
+/// Remember a result; blank text is ignored
+pub fn push(kind: RecentKind, text: &str) {
+    if text.trim().is_empty() {
+        return;
+    }
+    let result = RecentResult {
+        kind,
+        text: text.to_string(),
+        timestamp: chrono::Local::now().to_rfc3339(),
+    };
+    if let Ok(mut stack) = RECENT.lock() {
+        push_front(&mut stack, result);
+    }
+    crate::events::emit(RECENT_RESULTS_CHANGED_EVENT, ());
+}
+
+fn list() -> Vec<RecentResult> {
+    RECENT.lock().map(|stack| stack.iter().cloned().collect()).unwrap_or_default()
+}
+
+/// Copy the result at `index` (0 = most recent) to the clipboard
+fn pop<R: Runtime>(app: &AppHandle<R>, index: usize) -> AppResult<String> {
+    let result = take(&mut *RECENT.lock()?, index)?;
+    crate::actions::copy_to_clipboard(app, &result.text)?;
+    crate::events::emit(RECENT_RESULTS_CHANGED_EVENT, ());
+    Ok(result.text)
+}
+
+/// The "Recent results" submenu, most recent first
+pub fn build_submenu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Submenu<R>> {
+    let submenu = Submenu::with_id(app, "recent-results", t("tray-recent-results"), true)?;
+    let results = list();
+    if results.is_empty() {
+        submenu.append(&MenuItem::with_id(app, "recent-none", t("tray-no-recent-results"), false, None::<&str>)?)?;
+    }
+    for (index, result) in results.iter().enumerate() {
+        let prefix = match result.kind {
+            RecentKind::Ocr => "",
+            RecentKind::Translation => "→ ",
+        };
+        let label = format!("{}{}", prefix, crate::notifications::preview(&result.text, MENU_LABEL_CHARS));
+        let id = format!("{}{}", MENU_ID_PREFIX, index);
+        submenu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+    }
+    Ok(submenu)
+}
+
+/// Copy a result picked in the submenu. Returns false for other menu ids.
+pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, id: &str) -> bool {
+    let Some(index) = id.strip_prefix(MENU_ID_PREFIX).and_then(|i| i.parse().ok()) else {
+        return false;
+    };
+    if let Err(e) = pop(app, index) {
+        tracing::warn!("Recent result not copied: {}", e);
+    }
+    true
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Recent results, most recent first
+#[tauri::command]
+pub fn list_recent_results() -> AppResult<Vec<RecentResult>> {
+    Ok(list())
+}
+
+/// Copy the result at `index` (0 = most recent) to the clipboard and move it
+/// back on top; returns its text
+#[tauri::command]
+pub fn pop_recent_result(app: AppHandle, index: usize) -> AppResult<String> {
+    pop(&app, index)
+}
+
+/// Remember a result produced in the frontend, e.g. a translation
+#[tauri::command]
+pub fn add_recent_result(kind: RecentKind, text: String) -> AppResult<()> {
+    push(kind, &text);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(text: &str) -> RecentResult {
+        RecentResult {
+            kind: RecentKind::Ocr,
+            text: text.to_string(),
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_stack() {
+        let mut stack = VecDeque::new();
+        for i in 0..MAX_RESULTS + 2 {
+            push_front(&mut stack, result(&i.to_string()));
+        }
+        assert_eq!(stack.len(), MAX_RESULTS);
+        assert_eq!(stack[0].text, (MAX_RESULTS + 1).to_string());
+
+        // Same text again moves to the top instead of duplicating
+        push_front(&mut stack, result("5"));
+        assert_eq!(stack.len(), MAX_RESULTS);
+        assert_eq!(stack.iter().filter(|r| r.text == "5").count(), 1);
+
+        let second = stack[1].text.clone();
+        assert_eq!(take(&mut stack, 1).unwrap().text, second);
+        assert_eq!(stack[0].text, second);
+        assert!(take(&mut stack, MAX_RESULTS).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+// Smart region suggestions
+// Proposes snap-to rectangles for region selection: UI elements (windows,
+// panels, buttons) from edge contours, and text blocks from edges dilated
+// until neighbouring characters merge.
+
+use image::imageops::FilterType;
+use image::GrayImage;
+use imageproc::contours::{find_contours, BorderType};
+use imageproc::distance_transform::Norm;
+
+use crate::error::AppResult;
+use crate::frame_diff::Region;
+
+/// Screenshots are analysed at most this wide, then regions scaled back
+const ANALYSIS_WIDTH: u32 = 1280;
+
+/// Regions smaller than this (either side, image pixels) aren't worth snapping to
+const MIN_SIDE: u32 = 16;
+
+const MAX_SUGGESTIONS: usize = 50;
+
+/// Canny thresholds; UI chrome has crisp, high-contrast edges
+const CANNY_LOW: f32 = 20.0;
+const CANNY_HIGH: f32 = 60.0;
+
+/// Dilation radius that merges glyphs into words and lines into blocks
+const TEXT_BLOCK_RADIUS: u8 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RegionKind {
+    Element,
+    TextBlock,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct SuggestedRegion {
+    #[serde(flatten)]
+    pub region: Region,
+    pub kind: RegionKind,
+}
+
+fn intersection_over_union(a: &Region, b: &Region) -> f64 {
+    let left = a.x.max(b.x);
+    let top = a.y.max(b.y);
+    let right = (a.x + a.width).min(b.x + b.width);
+    let bottom = (a.y + a.height).min(b.y + b.height);
+    if right <= left || bottom <= top {
+        return 0.0;
+    }
+    let intersection = (right - left) as f64 * (bottom - top) as f64;
+    intersection / ((a.area() + b.area()) as f64 - intersection)
+}
+
+/// Bounding boxes of the outer contours in a binary edge map
+fn contour_boxes(edges: &GrayImage) -> Vec<Region> {
+    find_contours::<u32>(edges)
+        .into_iter()
+        .filter(|c| c.border_type == BorderType::Outer)
+        .filter_map(|c| {
+            let min_x = c.points.iter().map(|p| p.x).min()?;
+            let min_y = c.points.iter().map(|p| p.y).min()?;
+            let max_x = c.points.iter().map(|p| p.x).max()?;
+            let max_y = c.points.iter().map(|p| p.y).max()?;
+            Some(Region {
+                x: min_x,
+                y: min_y,
+                width: max_x - min_x + 1,
+                height: max_y - min_y + 1,
+            })
+        })
+        .collect()
+}
+
+/// Drop tiny, full-screen and near-duplicate regions; largest first
+fn filter_regions(mut regions: Vec<SuggestedRegion>, width: u32, height: u32) -> Vec<SuggestedRegion> {
+    let screen = width as u64 * height as u64;
+    regions.retain(|r| {
+        r.region.width >= MIN_SIDE && r.region.height >= MIN_SIDE && r.region.area() * 100 < screen * 95
+    });
+    regions.sort_by(|a, b| b.region.area().cmp(&a.region.area()));
+
+    let mut kept: Vec<SuggestedRegion> = Vec::new();
+    for region in regions {
+        if kept.iter().all(|k| intersection_over_union(&k.region, &region.region) < 0.85) {
+            kept.push(region);
+        }
+        if kept.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    kept
+}
+
+/// Suggest regions in image pixel coordinates
+pub fn suggest(image: &image::DynamicImage) -> Vec<SuggestedRegion> {
+    let (width, height) = (image.width(), image.height());
+    let scale = if width > ANALYSIS_WIDTH { width as f64 / ANALYSIS_WIDTH as f64 } else { 1.0 };
+    let gray = if scale > 1.0 {
+        let analysis_height = (height as f64 / scale).round().max(1.0) as u32;
+        image.resize_exact(ANALYSIS_WIDTH, analysis_height, FilterType::Triangle).to_luma8()
+    } else {
+        image.to_luma8()
+    };
+
+    let edges = imageproc::edges::canny(&gray, CANNY_LOW, CANNY_HIGH);
+    // Close 1px gaps so element borders form closed contours
+    let elements = imageproc::morphology::dilate(&edges, Norm::LInf, 1);
+    let text_blocks = imageproc::morphology::dilate(&edges, Norm::LInf, TEXT_BLOCK_RADIUS);
+
+    let rescale = |r: Region| Region {
+        x: (r.x as f64 * scale) as u32,
+        y: (r.y as f64 * scale) as u32,
+        width: ((r.width as f64 * scale).round() as u32).min(width),
+        height: ((r.height as f64 * scale).round() as u32).min(height),
+    };
+    let regions = contour_boxes(&elements)
+        .into_iter()
+        .map(|r| SuggestedRegion { region: rescale(r), kind: RegionKind::Element })
+        .chain(
+            contour_boxes(&text_blocks)
+                .into_iter()
+                .map(|r| SuggestedRegion { region: rescale(r), kind: RegionKind::TextBlock }),
+        )
+        .collect();
+    filter_regions(regions, width, height)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Propose snap-to rectangles (panels, buttons, text blocks) for a screenshot
+#[tauri::command(async)]
+pub fn suggest_regions(base64_image: String) -> AppResult<Vec<SuggestedRegion>> {
+    let bytes = crate::ocr::decode_base64_image(&base64_image)?;
+    let image = crate::image_decode::decode_image_bytes(&bytes)?;
+    Ok(suggest(&image))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: u32, y: u32, width: u32, height: u32) -> SuggestedRegion {
+        SuggestedRegion { region: Region { x, y, width, height }, kind: RegionKind::Element }
+    }
+
+    #[test]
+    fn test_filter_regions() {
+        let regions = vec![
+            region(0, 0, 100, 100), // whole screen
+            region(10, 10, 4, 30),  // too thin
+            region(10, 10, 40, 30),
+            region(11, 10, 39, 30), // duplicate of the previous
+            region(60, 60, 30, 20),
+        ];
+        let kept = filter_regions(regions, 100, 100);
+        assert_eq!(kept, vec![region(10, 10, 40, 30), region(60, 60, 30, 20)]);
+    }
+
+    #[test]
+    fn test_suggest_finds_panel() {
+        let mut image = image::RgbImage::from_pixel(200, 120, image::Rgb([255, 255, 255]));
+        for y in 30..90 {
+            for x in 40..160 {
+                image.put_pixel(x, y, image::Rgb([30, 30, 30]));
+            }
+        }
+        let suggestions = suggest(&image::DynamicImage::ImageRgb8(image));
+        assert!(suggestions.iter().any(|s| {
+            s.kind == RegionKind::Element
+                && s.region.x.abs_diff(40) <= 2
+                && s.region.y.abs_diff(30) <= 2
+                && s.region.width.abs_diff(120) <= 4
+                && s.region.height.abs_diff(60) <= 4
+        }));
+    }
+}
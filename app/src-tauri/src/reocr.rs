@@ -0,0 +1,340 @@
+// Automatic re-OCR
+// When the mean confidence of a result is below a threshold, retries with the
+// other engines available on this platform and with a preprocessed image
+// (upscaled and binarized), keeping the most confident result and noting
+// which attempt won.
+
+use image::{imageops::FilterType, GrayImage, Luma};
+
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Pipeline};
+use crate::ocr::{self, OcrEngine, OcrOptions};
+use crate::ocr_result::{BoundingBox, OcrResult};
+
+/// Images are upscaled by this factor before binarizing
+const UPSCALE_FACTOR: u32 = 2;
+
+/// Upscaling stops at this many pixels on the longer side
+const MAX_UPSCALED_SIDE: u32 = 4096;
+
+/// Re-OCR settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReocrConfig {
+    /// Retry low-confidence results in the regular OCR pipeline
+    pub enabled: bool,
+    /// Mean confidence (0-100) below which OCR is retried
+    pub threshold: f32,
+    /// Try the other engines available on this platform
+    pub try_other_engines: bool,
+    /// Try again on an upscaled, binarized image
+    pub try_preprocessing: bool,
+}
+
+impl Default for ReocrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 60.0,
+            try_other_engines: true,
+            try_preprocessing: true,
+        }
+    }
+}
+
+/// One OCR attempt: "original", "engine:<name>" or "preprocessed"
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReocrAttempt {
+    pub label: String,
+    pub engine: String,
+    pub confidence: f32,
+    /// Set when the attempt failed instead of producing a result
+    pub error: Option<String>,
+}
+
+/// The most confident result and how it was obtained
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReocrResult {
+    pub result: OcrResult,
+    /// Label of the attempt that produced `result`
+    pub winner: String,
+    pub attempts: Vec<ReocrAttempt>,
+}
+
+/// Otsu's threshold: the gray level that best separates the histogram into
+/// foreground and background
+fn otsu_threshold(histogram: &[u64; 256]) -> u8 {
+    let total: u64 = histogram.iter().sum();
+    if total == 0 {
+        return 128;
+    }
+    let weighted_total: f64 = histogram.iter().enumerate().map(|(i, &n)| i as f64 * n as f64).sum();
+
+    let (mut best, mut best_variance) = (0u8, -1.0f64);
+    let (mut background, mut weighted_background) = (0u64, 0.0f64);
+    for (level, &count) in histogram.iter().enumerate() {
+        background += count;
+        weighted_background += level as f64 * count as f64;
+        let foreground = total - background;
+        if background == 0 || foreground == 0 {
+            continue;
+        }
+        let mean_background = weighted_background / background as f64;
+        let mean_foreground = (weighted_total - weighted_background) / foreground as f64;
+        let variance = background as f64 * foreground as f64 * (mean_background - mean_foreground).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best = level as u8;
+        }
+    }
+    best
+}
+
+/// Black and white image: pixels above the Otsu threshold become white
+fn binarize(gray: &GrayImage) -> GrayImage {
+    let mut histogram = [0u64; 256];
+    gray.pixels().for_each(|p| histogram[p[0] as usize] += 1);
+    let threshold = otsu_threshold(&histogram);
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        Luma([if gray.get_pixel(x, y)[0] > threshold { 255 } else { 0 }])
+    })
+}
+
+/// Upscale (small text is the usual cause of poor confidence) and binarize.
+/// Returns the PNG and the scale factor applied.
+fn preprocess(image_bytes: &[u8]) -> AppResult<(Vec<u8>, u32)> {
+    let image = crate::image_decode::decode_image_bytes(image_bytes)?;
+    let longest = image.width().max(image.height()).max(1);
+    let factor = (MAX_UPSCALED_SIDE / longest).clamp(1, UPSCALE_FACTOR);
+    let gray = image
+        .resize(image.width() * factor, image.height() * factor, FilterType::CatmullRom)
+        .to_luma8();
+
+    let mut bytes = Vec::new();
+    image::DynamicImage::ImageLuma8(binarize(&gray))
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Ocr(format!("Failed to encode image: {}", e)))?;
+    Ok((bytes, factor))
+}
+
+/// Map boxes from an upscaled image back to the original coordinates
+fn unscale(mut result: OcrResult, factor: u32) -> OcrResult {
+    let scale = |b: &mut BoundingBox| {
+        *b = BoundingBox {
+            x: b.x / factor,
+            y: b.y / factor,
+            width: b.width / factor,
+            height: b.height / factor,
+        }
+    };
+    for line in &mut result.lines {
+        scale(&mut line.bbox);
+        line.words.iter_mut().for_each(|w| scale(&mut w.bbox));
+    }
+    result
+}
+
+/// Index of the most confident result; earlier attempts win ties
+fn best_index(results: &[(String, OcrResult)]) -> Option<usize> {
+    results
+        .iter()
+        .enumerate()
+        .fold(None, |best: Option<(usize, f32)>, (i, (_, result))| match best {
+            Some((_, confidence)) if confidence >= result.confidence => best,
+            _ => Some((i, result.confidence)),
+        })
+        .map(|(i, _)| i)
+}
+
+/// OCR with `options`, retrying per `config` while the best result so far is
+/// below the threshold. The image is expected to be white balanced already.
+pub fn run_best(image_bytes: &[u8], options: &OcrOptions, config: &ReocrConfig) -> AppResult<ReocrResult> {
+    let engines: Vec<&str> = ocr::get_available_engines()
+        .into_iter()
+        .filter(|e| *e != OcrEngine::Auto)
+        .map(|e| e.as_str())
+        .collect();
+    run_best_with(image_bytes, options, config, &engines, ocr::recognize_detailed)
+}
+
+/// `run_best` with the engines to retry with and the recognizer passed in
+fn run_best_with(
+    image_bytes: &[u8],
+    options: &OcrOptions,
+    config: &ReocrConfig,
+    engines: &[&str],
+    recognize: impl Fn(&[u8], &OcrOptions) -> AppResult<OcrResult>,
+) -> AppResult<ReocrResult> {
+    let original = recognize(image_bytes, options)?;
+    let mut attempts = vec![ReocrAttempt {
+        label: "original".to_string(),
+        engine: original.engine.clone(),
+        confidence: original.confidence,
+        error: None,
+    }];
+    let mut results = vec![("original".to_string(), original)];
+
+    let mut retries: Vec<(String, Option<String>)> = Vec::new();
+    if config.try_other_engines {
+        let used = results[0].1.engine.clone();
+        retries.extend(
+            engines
+                .iter()
+                .filter(|e| **e != used)
+                .map(|e| (format!("engine:{}", e), Some(e.to_string()))),
+        );
+    }
+    if config.try_preprocessing {
+        retries.push(("preprocessed".to_string(), None));
+    }
+
+    for (label, engine) in retries {
+        let best = best_index(&results).map_or(0.0, |i| results[i].1.confidence);
+        if best >= config.threshold {
+            break;
+        }
+        let outcome = match engine {
+            Some(engine) => recognize(
+                image_bytes,
+                &OcrOptions {
+                    engine: Some(engine),
+                    ..options.clone()
+                },
+            ),
+            None => preprocess(image_bytes)
+                .and_then(|(bytes, factor)| recognize(&bytes, options).map(|result| unscale(result, factor))),
+        };
+        match outcome {
+            Ok(result) => {
+                attempts.push(ReocrAttempt {
+                    label: label.clone(),
+                    engine: result.engine.clone(),
+                    confidence: result.confidence,
+                    error: None,
+                });
+                results.push((label, result));
+            }
+            // A failed retry doesn't lose the results we already have
+            Err(e) => attempts.push(ReocrAttempt {
+                label,
+                engine: String::new(),
+                confidence: 0.0,
+                error: Some(e.localized_message()),
+            }),
+        }
+    }
+
+    let best = best_index(&results).unwrap_or(0);
+    let (winner, result) = results.swap_remove(best);
+    Ok(ReocrResult {
+        result,
+        winner,
+        attempts,
+    })
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// OCR an image, retrying with other engines or preprocessing when the mean
+/// confidence is below `threshold` (the configured one by default)
+#[tauri::command(async)]
+pub fn perform_ocr_best(
+    base64_image: &str,
+    langs: Option<String>,
+    engine: Option<String>,
+    threshold: Option<f32>,
+) -> AppResult<ReocrResult> {
+    events::track(Pipeline::Ocr, "perform_ocr_best", None, || {
+        let bytes = crate::image_decode::ocr_input(&ocr::decode_base64_image(base64_image)?)?.into_owned();
+        let bytes = crate::white_balance::normalize_if_enabled(&bytes).unwrap_or(bytes);
+        let mut config = crate::config::get()?.reocr;
+        if let Some(threshold) = threshold {
+            config.threshold = threshold;
+        }
+        let options = OcrOptions {
+            langs,
+            engine,
+            ..Default::default()
+        };
+        let mut best = run_best(&bytes, &options, &config)?;
+        crate::plugins::apply_to_result(crate::plugins::PluginStage::Ocr, &mut best.result);
+        Ok(best)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr_result::OcrWord;
+
+    fn result(confidence: f32) -> OcrResult {
+        let bbox = BoundingBox { x: 0, y: 0, width: 10, height: 10 };
+        OcrResult::from_words(vec![OcrWord::new("a".to_string(), confidence, bbox, 1, 1, 1)], "tesseract")
+    }
+
+    #[test]
+    fn test_otsu_threshold_separates_modes() {
+        let mut histogram = [0u64; 256];
+        histogram[30] = 500;
+        histogram[40] = 300;
+        histogram[220] = 900;
+        let threshold = otsu_threshold(&histogram);
+        assert!((40..220).contains(&threshold));
+    }
+
+    #[test]
+    fn test_binarize() {
+        let gray = GrayImage::from_fn(4, 1, |x, _| Luma([[20, 60, 180, 240][x as usize]]));
+        let binary = binarize(&gray);
+        assert_eq!(binary.pixels().map(|p| p[0]).collect::<Vec<_>>(), vec![0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_unscale() {
+        let unscaled = unscale(result(50.0), 2);
+        assert_eq!(unscaled.lines[0].bbox, BoundingBox { x: 0, y: 0, width: 5, height: 5 });
+        assert_eq!(unscaled.lines[0].words[0].bbox.width, 5);
+    }
+
+    #[test]
+    fn test_best_index_prefers_confidence_then_order() {
+        let results = vec![
+            ("original".to_string(), result(40.0)),
+            ("engine:windows".to_string(), result(75.0)),
+            ("preprocessed".to_string(), result(75.0)),
+        ];
+        assert_eq!(best_index(&results), Some(1));
+        assert_eq!(best_index(&[]), None);
+    }
+
+    #[test]
+    fn test_run_best_records_failed_retries() {
+        let config = ReocrConfig { enabled: true, ..Default::default() };
+        let recognize = |_: &[u8], options: &OcrOptions| match options.engine.as_deref() {
+            Some("windows") => Err(AppError::Ocr("engine unavailable".to_string())),
+            _ => Ok(result(30.0)),
+        };
+        // Not an image, so preprocessing fails too
+        let best = run_best_with(b"raw", &OcrOptions::default(), &config, &["tesseract", "windows"], recognize).unwrap();
+        assert_eq!(best.winner, "original");
+        let labels: Vec<_> = best.attempts.iter().map(|a| (a.label.as_str(), a.error.is_some())).collect();
+        assert_eq!(labels, vec![("original", false), ("engine:windows", true), ("preprocessed", true)]);
+    }
+
+    #[test]
+    fn test_run_best_stops_at_threshold() {
+        let config = ReocrConfig { enabled: true, threshold: 50.0, ..Default::default() };
+        let recognize = |_: &[u8], options: &OcrOptions| {
+            Ok(result(if options.engine.as_deref() == Some("apple") { 80.0 } else { 30.0 }))
+        };
+        let engines = ["tesseract", "apple", "windows"];
+        let best = run_best_with(b"raw", &OcrOptions::default(), &config, &engines, recognize).unwrap();
+        assert_eq!(best.winner, "engine:apple");
+        assert_eq!(best.attempts.len(), 2);
+        assert_eq!(best.result.confidence, 80.0);
+    }
+}
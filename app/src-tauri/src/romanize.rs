@@ -0,0 +1,187 @@
+// Romanization
+// Pronunciation-only fast path that skips translation: Hepburn for Japanese
+// (kakasi, `romaji` feature), tone-marked pinyin for Chinese and Revised
+// Romanization for Korean (computed from Hangul syllable structure).
+
+use pinyin::ToPinyin;
+
+use crate::error::{AppError, AppResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Japanese,
+    Chinese,
+    Korean,
+}
+
+const HANGUL_BASE: u32 = 0xAC00;
+const HANGUL_LAST: u32 = 0xD7A3;
+
+/// Initial consonants (ㅇ is silent)
+const RR_INITIALS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p", "h",
+];
+const SILENT_INITIAL: u32 = 11;
+
+const RR_VOWELS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we", "wi", "yu", "eu",
+    "ui", "i",
+];
+
+/// Final consonants before a consonant or at the end of a word
+const RR_FINALS: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p", "t", "t", "ng", "t",
+    "t", "k", "t", "p", "t",
+];
+
+/// Final consonants carried over to a following vowel (liaison)
+const RR_FINALS_LIAISON: [&str; 28] = [
+    "", "g", "kk", "gs", "n", "nj", "n", "d", "r", "lg", "lm", "lb", "ls", "lt", "lp", "r", "m", "b", "bs", "s", "ss",
+    "ng", "j", "ch", "k", "t", "p", "",
+];
+
+fn script_for_lang(lang: &str) -> Option<Script> {
+    match lang {
+        l if l.starts_with("jpn") || l.starts_with("ja") => Some(Script::Japanese),
+        l if l.starts_with("chi") || l.starts_with("zh") => Some(Script::Chinese),
+        l if l.starts_with("kor") || l.starts_with("ko") => Some(Script::Korean),
+        _ => None,
+    }
+}
+
+/// Guess the script from the text: kana means Japanese even with kanji
+fn detect_script(text: &str) -> Option<Script> {
+    let has = |range: &[std::ops::RangeInclusive<u32>]| text.chars().any(|c| range.iter().any(|r| r.contains(&(c as u32))));
+    if has(&[0x3040..=0x30FF, 0x31F0..=0x31FF, 0xFF66..=0xFF9F]) {
+        Some(Script::Japanese)
+    } else if has(&[HANGUL_BASE..=HANGUL_LAST, 0x1100..=0x11FF, 0x3130..=0x318F]) {
+        Some(Script::Korean)
+    } else if has(&[0x4E00..=0x9FFF, 0x3400..=0x4DBF]) {
+        Some(Script::Chinese)
+    } else {
+        None
+    }
+}
+
+/// Split a Hangul syllable into (initial, vowel, final) indices
+fn hangul_parts(c: char) -> Option<(u32, u32, u32)> {
+    let code = c as u32;
+    if !(HANGUL_BASE..=HANGUL_LAST).contains(&code) {
+        return None;
+    }
+    let index = code - HANGUL_BASE;
+    Some((index / (21 * 28), (index % (21 * 28)) / 28, index % 28))
+}
+
+/// Revised Romanization, with liaison of final consonants before a vowel
+fn romanize_korean(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        let Some((initial, vowel, last)) = hangul_parts(c) else {
+            out.push(c);
+            continue;
+        };
+        out.push_str(RR_INITIALS[initial as usize]);
+        out.push_str(RR_VOWELS[vowel as usize]);
+        let before_vowel = chars
+            .get(i + 1)
+            .and_then(|&next| hangul_parts(next))
+            .is_some_and(|(next_initial, _, _)| next_initial == SILENT_INITIAL);
+        let finals = if before_vowel { &RR_FINALS_LIAISON } else { &RR_FINALS };
+        out.push_str(finals[last as usize]);
+    }
+    out
+}
+
+/// Tone-marked pinyin, one syllable per character, separated by spaces
+fn romanize_chinese(text: &str) -> String {
+    let mut out = String::new();
+    let mut after_syllable = false;
+    for (c, pinyin) in text.chars().zip(text.to_pinyin()) {
+        match pinyin {
+            Some(pinyin) => {
+                if !out.is_empty() && !out.ends_with(char::is_whitespace) {
+                    out.push(' ');
+                }
+                out.push_str(pinyin.with_tone());
+                after_syllable = true;
+            }
+            None => {
+                if after_syllable && c.is_alphanumeric() {
+                    out.push(' ');
+                }
+                out.push(c);
+                after_syllable = false;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(feature = "romaji")]
+fn romanize_japanese(text: &str) -> AppResult<String> {
+    Ok(kakasi::convert(text).romaji)
+}
+
+#[cfg(not(feature = "romaji"))]
+fn romanize_japanese(_text: &str) -> AppResult<String> {
+    Err(AppError::Unsupported(
+        "Japanese romanization is not supported by this build (romaji feature)".to_string(),
+    ))
+}
+
+/// Romanize `text`. `lang` is a Tesseract or ISO code; the script is detected
+/// from the text when it's "auto" or missing.
+pub fn romanize_text(text: &str, lang: Option<&str>) -> AppResult<String> {
+    let script = match lang.filter(|l| *l != "auto") {
+        Some(lang) => script_for_lang(lang)
+            .ok_or_else(|| AppError::Unsupported(format!("Romanization is not available for '{}'", lang)))?,
+        None => match detect_script(text) {
+            Some(script) => script,
+            // Nothing to romanize
+            None => return Ok(text.to_string()),
+        },
+    };
+    Ok(match script {
+        Script::Japanese => romanize_japanese(text)?,
+        Script::Chinese => romanize_chinese(text),
+        Script::Korean => romanize_korean(text),
+    })
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Romanize OCR text without translating it
+#[tauri::command]
+pub fn romanize(text: String, lang: Option<String>) -> AppResult<String> {
+    romanize_text(&text, lang.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_romanize_korean() {
+        assert_eq!(romanize_korean("한국어"), "hangugeo");
+        assert_eq!(romanize_korean("서울 가요!"), "seoul gayo!");
+        assert_eq!(romanize_korean("읽다"), "ikda");
+    }
+
+    #[test]
+    fn test_detect_script() {
+        assert_eq!(detect_script("日本語を読む"), Some(Script::Japanese));
+        assert_eq!(detect_script("中文"), Some(Script::Chinese));
+        assert_eq!(detect_script("한국어"), Some(Script::Korean));
+        assert_eq!(detect_script("plain"), None);
+        assert_eq!(script_for_lang("chi_tra"), Some(Script::Chinese));
+    }
+
+    #[test]
+    fn test_romanize_chinese() {
+        assert_eq!(romanize_chinese("中文OCR"), "zhōng wén OCR");
+    }
+}
@@ -0,0 +1,282 @@
+// Flatpak / Snap sandbox
+// Inside a Flatpak (and a Wayland session under Snap) xcap can't read the
+// screen, GTK file dialogs only see the sandbox and the host's autostart
+// folder is out of reach. There the xdg-desktop-portal does the work instead:
+// Screenshot for captures, FileChooser for "Save as" and Background for
+// start at login.
+
+use crate::error::{AppError, AppResult};
+
+/// Sandbox the app is running in
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sandbox {
+    Flatpak,
+    Snap,
+}
+
+/// Sandbox state reported to the frontend
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxInfo {
+    pub kind: Option<Sandbox>,
+    /// Flatpak app id or snap name
+    pub app_id: Option<String>,
+}
+
+/// File type filter of a save dialog, as the dialog plugin takes them
+#[derive(Debug, Clone, serde::Deserialize)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct DialogFilter {
+    pub name: String,
+    pub extensions: Vec<String>,
+}
+
+fn detect_from(flatpak_info: bool, var: impl Fn(&str) -> Option<String>) -> Option<Sandbox> {
+    if flatpak_info || var("FLATPAK_ID").is_some() {
+        Some(Sandbox::Flatpak)
+    } else if var("SNAP").is_some() && var("SNAP_NAME").is_some() {
+        Some(Sandbox::Snap)
+    } else {
+        None
+    }
+}
+
+static SANDBOX: once_cell::sync::Lazy<Option<Sandbox>> = once_cell::sync::Lazy::new(|| {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    detect_from(std::path::Path::new("/.flatpak-info").exists(), |name| {
+        std::env::var(name).ok().filter(|value| !value.is_empty())
+    })
+});
+
+/// The sandbox this process runs in, if any
+pub fn detect() -> Option<Sandbox> {
+    *SANDBOX
+}
+
+/// Whether captures go through the Screenshot portal. Snaps can still use
+/// X11 directly.
+#[cfg(target_os = "linux")]
+pub fn use_portal_capture() -> bool {
+    match detect() {
+        Some(Sandbox::Flatpak) => true,
+        Some(Sandbox::Snap) => crate::permissions::is_wayland_session(),
+        None => false,
+    }
+}
+
+/// Glob patterns for the FileChooser portal ("json" -> "*.json")
+#[cfg(any(target_os = "linux", test))]
+fn globs(filter: &DialogFilter) -> Vec<String> {
+    filter
+        .extensions
+        .iter()
+        .map(|ext| format!("*.{}", ext.trim_start_matches('.')))
+        .collect()
+}
+
+/// Rectangle of monitor `index` inside a screenshot of the whole desktop.
+/// None when the screenshot doesn't match the monitor layout (scaled
+/// desktops), in which case the whole screenshot is used.
+#[cfg(any(target_os = "linux", test))]
+fn monitor_crop(size: (u32, u32), monitors: &[(i32, i32, u32, u32)], index: usize) -> Option<(u32, u32, u32, u32)> {
+    if monitors.len() < 2 {
+        return None;
+    }
+    let left = monitors.iter().map(|m| m.0).min()?;
+    let top = monitors.iter().map(|m| m.1).min()?;
+    let right = monitors.iter().map(|m| m.0 + m.2 as i32).max()?;
+    let bottom = monitors.iter().map(|m| m.1 + m.3 as i32).max()?;
+    if size != ((right - left) as u32, (bottom - top) as u32) {
+        return None;
+    }
+    let (x, y, width, height) = *monitors.get(index)?;
+    Some(((x - left) as u32, (y - top) as u32, width, height))
+}
+
+#[cfg(target_os = "linux")]
+mod portal {
+    use ashpd::desktop::background::Background;
+    use ashpd::desktop::file_chooser::{FileFilter, SelectedFiles};
+    use ashpd::desktop::screenshot::Screenshot;
+    use ashpd::desktop::ResponseError;
+    use image::RgbaImage;
+    use xcap::Monitor;
+
+    use super::*;
+
+    fn portal_error(e: ashpd::Error) -> AppError {
+        match e {
+            ashpd::Error::Response(ResponseError::Cancelled) => AppError::Cancelled,
+            e => AppError::Other(format!("Portal request failed: {}", e)),
+        }
+    }
+
+    /// Run a portal request to completion. Captures are taken from sync code
+    /// that may itself be on the async runtime, so a thread of its own is used.
+    fn block_on<T: Send + 'static>(
+        request: impl std::future::Future<Output = Result<T, ashpd::Error>> + Send + 'static,
+    ) -> AppResult<T> {
+        std::thread::spawn(move || tauri::async_runtime::block_on(request))
+            .join()
+            .map_err(|_| AppError::Other("Portal request panicked".to_string()))?
+            .map_err(portal_error)
+    }
+
+    fn monitor_rects() -> Option<Vec<(i32, i32, u32, u32)>> {
+        Monitor::all()
+            .ok()?
+            .iter()
+            .map(|m| Some((m.x().ok()?, m.y().ok()?, m.width().ok()?, m.height().ok()?)))
+            .collect()
+    }
+
+    /// Screenshot of monitor `monitor_index` through the Screenshot portal
+    pub fn screenshot(monitor_index: usize) -> AppResult<RgbaImage> {
+        let uri = block_on(async {
+            let response = Screenshot::request().interactive(false).modal(false).send().await?.response()?;
+            Ok::<_, ashpd::Error>(response.uri().clone())
+        })
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+        let path = uri
+            .to_file_path()
+            .map_err(|_| AppError::Capture(format!("Unexpected screenshot location: {}", uri)))?;
+        let image = image::open(&path)
+            .map_err(|e| AppError::Capture(e.to_string()))?
+            .to_rgba8();
+        // Every request writes a new file; don't leave them piling up
+        let _ = std::fs::remove_file(&path);
+
+        let rects = monitor_rects().unwrap_or_default();
+        Ok(match monitor_crop(image.dimensions(), &rects, monitor_index) {
+            Some((x, y, width, height)) => image::imageops::crop_imm(&image, x, y, width, height).to_image(),
+            None => image,
+        })
+    }
+
+    /// Ask where to save through the FileChooser portal
+    pub fn save_file(
+        title: Option<String>,
+        default_name: Option<String>,
+        filters: Vec<DialogFilter>,
+    ) -> AppResult<Option<String>> {
+        let selected = block_on(async move {
+            let mut request = SelectedFiles::save_file()
+                .title(title.as_deref().unwrap_or("Save"))
+                .modal(true)
+                .current_name(default_name.as_deref());
+            for filter in &filters {
+                let mut portal_filter = FileFilter::new(&filter.name);
+                for glob in globs(filter) {
+                    portal_filter = portal_filter.glob(&glob);
+                }
+                request = request.filter(portal_filter);
+            }
+            request.send().await?.response()
+        });
+        let selected = match selected {
+            Ok(selected) => selected,
+            Err(AppError::Cancelled) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Ok(selected
+            .uris()
+            .first()
+            .and_then(|uri| uri.to_file_path().ok())
+            .map(|path| path.to_string_lossy().into_owned()))
+    }
+
+    /// Register or unregister the login entry through the Background portal.
+    /// `command` runs inside the sandbox.
+    pub fn set_autostart(enabled: bool, command: Vec<String>) -> AppResult<()> {
+        let granted = block_on(async move {
+            let response = Background::request()
+                .reason("Start Screen Inu at login")
+                .auto_start(enabled)
+                .command(command)
+                .dbus_activatable(false)
+                .send()
+                .await?
+                .response()?;
+            Ok::<_, ashpd::Error>(response.auto_start())
+        })?;
+        if enabled && !granted {
+            return Err(AppError::PermissionDenied(
+                "Starting at login was not allowed. Check the app's permissions in your desktop settings.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub use portal::{screenshot as portal_screenshot, set_autostart as portal_set_autostart};
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Which sandbox the app runs in, so the frontend can route file saves
+#[tauri::command]
+pub fn get_sandbox_info() -> SandboxInfo {
+    let kind = detect();
+    let app_id = match kind {
+        Some(Sandbox::Flatpak) => std::env::var("FLATPAK_ID").ok(),
+        Some(Sandbox::Snap) => std::env::var("SNAP_NAME").ok(),
+        None => None,
+    };
+    SandboxInfo { kind, app_id }
+}
+
+/// "Save as" through the FileChooser portal; None when cancelled. The path
+/// is a document portal path the app is allowed to write.
+#[tauri::command]
+pub async fn portal_save_file(
+    title: Option<String>,
+    default_name: Option<String>,
+    filters: Option<Vec<DialogFilter>>,
+) -> AppResult<Option<String>> {
+    #[cfg(target_os = "linux")]
+    {
+        tauri::async_runtime::spawn_blocking(move || portal::save_file(title, default_name, filters.unwrap_or_default()))
+            .await
+            .map_err(|e| AppError::Other(e.to_string()))?
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (title, default_name, filters);
+        Err(AppError::Unsupported("The file chooser portal is only available on Linux".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from() {
+        let none = |_: &str| None;
+        assert_eq!(detect_from(false, none), None);
+        assert_eq!(detect_from(true, none), Some(Sandbox::Flatpak));
+        let snap = |name: &str| matches!(name, "SNAP" | "SNAP_NAME").then(|| "screen-inu".to_string());
+        assert_eq!(detect_from(false, snap), Some(Sandbox::Snap));
+        // SNAP alone is sometimes left behind by a parent snap
+        let partial = |name: &str| (name == "SNAP").then(|| "/snap/x".to_string());
+        assert_eq!(detect_from(false, partial), None);
+    }
+
+    #[test]
+    fn test_globs_and_monitor_crop() {
+        let filter = DialogFilter { name: "JSON".to_string(), extensions: vec!["json".to_string(), ".txt".to_string()] };
+        assert_eq!(globs(&filter), vec!["*.json", "*.txt"]);
+
+        let monitors = [(0, 0, 1920, 1080), (1920, -200, 1280, 1024)];
+        assert_eq!(monitor_crop((3200, 1280), &monitors, 1), Some((1920, 0, 1280, 1024)));
+        assert_eq!(monitor_crop((3200, 1280), &monitors, 0), Some((0, 200, 1920, 1080)));
+        // Scaled desktop or a single monitor: use the whole screenshot
+        assert_eq!(monitor_crop((6400, 2560), &monitors, 0), None);
+        assert_eq!(monitor_crop((1920, 1080), &monitors[..1], 0), None);
+    }
+}
@@ -0,0 +1,458 @@
+// Save targets
+// Captures can be saved to a local folder and to network destinations: SFTP
+// servers (through libssh2) and SMB shares, given as a UNC path on Windows or
+// the share's mount point elsewhere. The OCR text is saved next to the image.
+// A network save that fails is queued on disk and retried in the background,
+// so a scan taken while the VPN is down still lands on the shared drive.
+// SFTP passwords are kept in the OS keychain; the server's host key is pinned
+// on first connection. SFTP needs the `sftp` feature.
+
+#[cfg(feature = "sftp")]
+use std::io::Write;
+#[cfg(feature = "sftp")]
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::error::{AppError, AppResult};
+
+const QUEUE_DIR: &str = "save_queue";
+const QUEUE_FILE: &str = "queue.json";
+#[cfg(feature = "sftp")]
+const SFTP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Held while the queue is read or written
+static QUEUE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Network destination types
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SaveTargetKind {
+    #[serde(rename_all = "camelCase")]
+    Sftp {
+        host: String,
+        #[serde(default = "default_sftp_port")]
+        port: u16,
+        username: String,
+        /// Directory on the server; the login directory when empty
+        #[serde(default)]
+        remote_dir: String,
+        /// Private key to log in with instead of the stored password
+        #[serde(default)]
+        key_file: Option<String>,
+        /// Base64 SHA-256 of the server's host key, pinned on first connection
+        #[serde(default)]
+        host_key: Option<String>,
+    },
+    /// A share path: "\\server\share\scans", or where the share is mounted
+    Smb { path: String },
+}
+
+fn default_sftp_port() -> u16 {
+    22
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// A network destination
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveTarget {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: SaveTargetKind,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Save destinations
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SaveTargetsConfig {
+    /// Local folder captures are saved to; not saved locally when unset
+    pub local_dir: Option<String>,
+    pub targets: Vec<SaveTarget>,
+    /// Save the OCR text as a .txt next to the image
+    pub include_text: bool,
+    /// Seconds between retries of queued saves
+    pub retry_interval_secs: u64,
+}
+
+impl Default for SaveTargetsConfig {
+    fn default() -> Self {
+        Self {
+            local_dir: None,
+            targets: Vec::new(),
+            include_text: true,
+            retry_interval_secs: 60,
+        }
+    }
+}
+
+/// A save waiting for its destination to come back
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedSave {
+    pub id: String,
+    pub target_id: String,
+    pub files: Vec<String>,
+    /// Unix epoch milliseconds
+    pub queued_at: i64,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+/// What `save_capture` did
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveReport {
+    pub local_path: Option<String>,
+    /// Targets written to
+    pub saved: Vec<String>,
+    /// Targets that failed; the save is retried later
+    pub queued: Vec<String>,
+}
+
+/// A file to save
+pub struct SaveFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+fn keyring_user(target_id: &str) -> String {
+    format!("sftp-{}", target_id)
+}
+
+#[cfg(feature = "sftp")]
+fn sftp_error(e: ssh2::Error) -> AppError {
+    AppError::Other(format!("SFTP failed: {}", e))
+}
+
+fn write_dir(dir: &Path, files: &[SaveFile]) -> AppResult<()> {
+    std::fs::create_dir_all(dir)?;
+    for file in files {
+        std::fs::write(dir.join(&file.name), &file.bytes)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sftp")]
+fn upload_sftp(target: &SaveTarget, files: &[SaveFile]) -> AppResult<()> {
+    use base64::Engine;
+
+    let SaveTargetKind::Sftp { host, port, username, remote_dir, key_file, host_key } = &target.kind else {
+        return Err(AppError::InvalidInput(format!("{} is not an SFTP target", target.id)));
+    };
+    let address = std::net::ToSocketAddrs::to_socket_addrs(&(host.as_str(), *port))?
+        .next()
+        .ok_or_else(|| AppError::Other(format!("Cannot resolve {}", host)))?;
+    let tcp = TcpStream::connect_timeout(&address, SFTP_TIMEOUT)?;
+    let mut session = ssh2::Session::new().map_err(sftp_error)?;
+    session.set_timeout(SFTP_TIMEOUT.as_millis() as u32);
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(sftp_error)?;
+
+    let fingerprint = session
+        .host_key_hash(ssh2::HashType::Sha256)
+        .map(|hash| base64::engine::general_purpose::STANDARD.encode(hash))
+        .ok_or_else(|| AppError::Other("SFTP server sent no host key".to_string()))?;
+    match host_key {
+        Some(pinned) if *pinned != fingerprint => {
+            return Err(AppError::PermissionDenied(format!(
+                "Host key of {} changed (now SHA256:{}); check the server before trusting it",
+                host, fingerprint
+            )));
+        }
+        Some(_) => {}
+        None => {
+            let id = target.id.clone();
+            let pinned = fingerprint.clone();
+            crate::config::update(move |c| {
+                for t in c.save_targets.targets.iter_mut().filter(|t| t.id == id) {
+                    if let SaveTargetKind::Sftp { host_key, .. } = &mut t.kind {
+                        *host_key = Some(pinned.clone());
+                    }
+                }
+            })?;
+        }
+    }
+
+    match key_file.as_deref().filter(|k| !k.is_empty()) {
+        Some(key_file) => session
+            .userauth_pubkey_file(username, None, Path::new(key_file), None)
+            .map_err(sftp_error)?,
+        None => {
+            let password = crate::secrets::load(&keyring_user(&target.id))?
+                .ok_or_else(|| AppError::InvalidInput(format!("No SFTP password for {}", target.id)))?;
+            session.userauth_password(username, &password).map_err(sftp_error)?;
+        }
+    }
+
+    let sftp = session.sftp().map_err(sftp_error)?;
+    let dir = PathBuf::from(remote_dir.trim());
+    // mkdir -p; existing directories fail harmlessly
+    let mut partial = PathBuf::new();
+    for component in dir.components() {
+        partial.push(component);
+        let _ = sftp.mkdir(&partial, 0o755);
+    }
+    for file in files {
+        let mut remote = sftp.create(&dir.join(&file.name)).map_err(sftp_error)?;
+        remote.write_all(&file.bytes)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "sftp"))]
+fn upload_sftp(_target: &SaveTarget, _files: &[SaveFile]) -> AppResult<()> {
+    Err(AppError::Unsupported("SFTP is not supported by this build (sftp feature)".to_string()))
+}
+
+/// Write files to a network target
+pub fn upload(target: &SaveTarget, files: &[SaveFile]) -> AppResult<()> {
+    match &target.kind {
+        SaveTargetKind::Sftp { .. } => upload_sftp(target, files),
+        SaveTargetKind::Smb { path } => write_dir(Path::new(path), files),
+    }
+}
+
+fn queue_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(QUEUE_DIR))
+}
+
+fn read_queue(dir: &Path) -> Vec<QueuedSave> {
+    std::fs::read_to_string(dir.join(QUEUE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_queue(dir: &Path, queue: &[QueuedSave]) -> AppResult<()> {
+    std::fs::create_dir_all(dir)?;
+    let content = serde_json::to_string_pretty(queue).map_err(|e| AppError::Other(e.to_string()))?;
+    std::fs::write(dir.join(QUEUE_FILE), content)?;
+    Ok(())
+}
+
+/// Keep a copy of `files` for a later retry to `target_id`
+fn enqueue(dir: &Path, target_id: &str, files: &[SaveFile], error: &AppError) -> AppResult<()> {
+    let _lock = QUEUE_LOCK.lock().map_err(|_| "Save queue lock poisoned")?;
+    let now = chrono::Utc::now();
+    let id = format!("{}-{}", now.timestamp_millis(), target_id);
+    write_dir(&dir.join(&id), files)?;
+    let mut queue = read_queue(dir);
+    queue.push(QueuedSave {
+        id,
+        target_id: target_id.to_string(),
+        files: files.iter().map(|f| f.name.clone()).collect(),
+        queued_at: now.timestamp_millis(),
+        attempts: 1,
+        last_error: error.to_string(),
+    });
+    write_queue(dir, &queue)
+}
+
+/// Retry every queued save; returns how many are still waiting. Saves to
+/// targets that were removed from the config are dropped.
+fn process_queue(dir: &Path, config: &SaveTargetsConfig) -> AppResult<usize> {
+    let _lock = QUEUE_LOCK.lock().map_err(|_| "Save queue lock poisoned")?;
+    let mut remaining = Vec::new();
+    for mut entry in read_queue(dir) {
+        let entry_dir = dir.join(&entry.id);
+        let Some(target) = config.targets.iter().find(|t| t.id == entry.target_id) else {
+            let _ = std::fs::remove_dir_all(&entry_dir);
+            continue;
+        };
+        if !target.enabled {
+            remaining.push(entry);
+            continue;
+        }
+        let result = entry
+            .files
+            .iter()
+            .map(|name| Ok(SaveFile { name: name.clone(), bytes: std::fs::read(entry_dir.join(name))? }))
+            .collect::<AppResult<Vec<_>>>()
+            .and_then(|files| upload(target, &files));
+        match result {
+            Ok(()) => {
+                let _ = std::fs::remove_dir_all(&entry_dir);
+            }
+            Err(e) => {
+                entry.attempts += 1;
+                entry.last_error = e.to_string();
+                remaining.push(entry);
+            }
+        }
+    }
+    write_queue(dir, &remaining)?;
+    Ok(remaining.len())
+}
+
+/// Save files to the local folder and every enabled target, queueing the
+/// targets that fail
+pub fn save_files(config: &SaveTargetsConfig, files: &[SaveFile]) -> AppResult<SaveReport> {
+    let mut report = SaveReport::default();
+    if let Some(local_dir) = config.local_dir.as_deref().filter(|d| !d.trim().is_empty()) {
+        write_dir(Path::new(local_dir), files)?;
+        report.local_path = Some(Path::new(local_dir).join(&files[0].name).display().to_string());
+    }
+    for target in config.targets.iter().filter(|t| t.enabled) {
+        match upload(target, files) {
+            Ok(()) => report.saved.push(target.id.clone()),
+            Err(e) => {
+                tracing::warn!("Save to {} failed, queued for retry: {}", target.id, e);
+                enqueue(&queue_dir()?, &target.id, files, &e)?;
+                report.queued.push(target.id.clone());
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Start retrying queued saves in the background
+pub fn init() {
+    std::thread::spawn(|| loop {
+        let interval = crate::config::get().map_or(60, |c| c.save_targets.retry_interval_secs);
+        std::thread::sleep(Duration::from_secs(interval.max(5)));
+        let result = crate::config::get().and_then(|c| process_queue(&queue_dir()?, &c.save_targets));
+        if let Err(e) = result {
+            tracing::warn!("Retrying queued saves failed: {}", e);
+        }
+    });
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_save_targets_config() -> AppResult<SaveTargetsConfig> {
+    Ok(crate::config::get()?.save_targets)
+}
+
+#[tauri::command]
+pub fn set_save_targets_config(config: SaveTargetsConfig) -> AppResult<()> {
+    let mut ids = std::collections::HashSet::new();
+    for target in &config.targets {
+        if target.id.trim().is_empty() || !ids.insert(target.id.as_str()) {
+            return Err(AppError::InvalidInput(format!("Save target ids must be unique: {:?}", target.id)));
+        }
+        // Saves to it would only pile up in the retry queue
+        if cfg!(not(feature = "sftp")) && target.enabled && matches!(target.kind, SaveTargetKind::Sftp { .. }) {
+            return Err(AppError::Unsupported("SFTP is not supported by this build (sftp feature)".to_string()));
+        }
+    }
+    crate::config::update(|c| c.save_targets = config)
+}
+
+/// Store an SFTP target's password in the keychain; `None` removes it
+#[tauri::command]
+pub fn set_sftp_password(target_id: String, password: Option<String>) -> AppResult<()> {
+    crate::secrets::store(&keyring_user(&target_id), password.as_deref().unwrap_or_default())
+}
+
+/// Save a capture, and its OCR text when given, to the configured destinations
+#[tauri::command(async)]
+#[tracing::instrument(skip(base64_image, text), err)]
+pub fn save_capture(
+    capture_id: Option<String>,
+    base64_image: Option<String>,
+    text: Option<String>,
+) -> AppResult<SaveReport> {
+    let config = crate::config::get()?.save_targets;
+    let bytes = match (capture_id, base64_image) {
+        (Some(id), _) => crate::capture_protocol::get(&id)
+            .map(|(_, bytes)| bytes)
+            .ok_or_else(|| AppError::InvalidInput(format!("Capture {} is no longer available", id)))?,
+        (None, Some(base64_image)) => crate::ocr::decode_base64_image(&base64_image)?,
+        (None, None) => return Err(AppError::InvalidInput("Nothing to save".to_string())),
+    };
+    let extension = image::guess_format(&bytes)
+        .ok()
+        .and_then(|format| format.extensions_str().first().copied())
+        .unwrap_or("png");
+    let stem = format!("capture-{}", chrono::Local::now().format("%Y%m%d-%H%M%S-%3f"));
+    let mut files = vec![SaveFile { name: format!("{}.{}", stem, extension), bytes }];
+    if let Some(text) = text.filter(|t| config.include_text && !t.trim().is_empty()) {
+        files.push(SaveFile { name: format!("{}.txt", stem), bytes: text.into_bytes() });
+    }
+    save_files(&config, &files)
+}
+
+/// Saves waiting for their destination
+#[tauri::command]
+pub fn get_save_queue() -> AppResult<Vec<QueuedSave>> {
+    Ok(read_queue(&queue_dir()?))
+}
+
+/// Retry queued saves now; returns how many are still waiting
+#[tauri::command(async)]
+pub fn retry_save_queue() -> AppResult<usize> {
+    process_queue(&queue_dir()?, &crate::config::get()?.save_targets)
+}
+
+/// Drop all queued saves
+#[tauri::command]
+pub fn clear_save_queue() -> AppResult<()> {
+    let _lock = QUEUE_LOCK.lock().map_err(|_| "Save queue lock poisoned")?;
+    let dir = queue_dir()?;
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_targets() {
+        let config: SaveTargetsConfig = serde_json::from_str(
+            r#"{"targets": [
+                {"id": "nas", "type": "sftp", "host": "nas.local", "username": "scan", "remoteDir": "scans"},
+                {"id": "office", "type": "smb", "path": "\\\\server\\share", "enabled": false}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(config.include_text);
+        let SaveTargetKind::Sftp { port, remote_dir, host_key, .. } = &config.targets[0].kind else {
+            panic!("not an SFTP target");
+        };
+        assert_eq!((*port, remote_dir.as_str(), host_key), (22, "scans", &None));
+        assert_eq!(config.targets[1].kind, SaveTargetKind::Smb { path: r"\\server\share".to_string() });
+        assert!(!config.targets[1].enabled);
+    }
+
+    #[test]
+    fn test_queue_retry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let queue = root.join("queue");
+        let share = root.join("share");
+        // The share is unreachable while a file blocks its path
+        std::fs::write(&share, b"").unwrap();
+        let config = SaveTargetsConfig {
+            targets: vec![SaveTarget {
+                id: "office".to_string(),
+                kind: SaveTargetKind::Smb { path: share.display().to_string() },
+                enabled: true,
+            }],
+            ..Default::default()
+        };
+        let files = [SaveFile { name: "capture.txt".to_string(), bytes: b"hello".to_vec() }];
+        let error = upload(&config.targets[0], &files).unwrap_err();
+        enqueue(&queue, "office", &files, &error).unwrap();
+        assert_eq!(process_queue(&queue, &config).unwrap(), 1);
+        assert_eq!(read_queue(&queue)[0].attempts, 2);
+
+        std::fs::remove_file(&share).unwrap();
+        assert_eq!(process_queue(&queue, &config).unwrap(), 0);
+        assert_eq!(std::fs::read(share.join("capture.txt")).unwrap(), b"hello");
+    }
+}
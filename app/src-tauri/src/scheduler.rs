@@ -0,0 +1,207 @@
+// Scheduled captures
+// Captures and OCRs a screen region on a cron schedule, also while the window
+// is hidden in the tray (monitoring dashboards, auction pages). Results are
+// emitted to the frontend, which stores them in history.
+//
+// Cron expressions use the standard 5 fields (minute hour day month weekday);
+// a leading seconds field is accepted too.
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use cron::Schedule;
+use tauri::{AppHandle, Emitter};
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::error::{AppError, AppResult};
+use crate::frame_diff::Region;
+use crate::ocr::{self, OcrOptions};
+
+/// Emitted with a `ScheduledResult` after each scheduled capture
+pub const SCHEDULED_CAPTURE_EVENT: &str = "scheduler://captured";
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// A recurring capture
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledCapture {
+    pub id: String,
+    pub cron: String,
+    /// Whole primary monitor when None
+    pub region: Option<Region>,
+    /// OCR profile
+    pub langs: Option<String>,
+    pub engine: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Scheduler settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SchedulerConfig {
+    pub captures: Vec<ScheduledCapture>,
+}
+
+/// Outcome of a scheduled capture
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledResult {
+    pub id: String,
+    pub text: Option<String>,
+    pub lang: String,
+    pub error: Option<String>,
+}
+
+/// Parse a cron expression, accepting 5 fields (no seconds)
+pub fn parse_cron(expr: &str) -> AppResult<Schedule> {
+    let expr = expr.trim();
+    let normalized = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+    Schedule::from_str(&normalized)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid cron expression '{}': {}", expr, e)))
+}
+
+/// Whether the schedule fires in (after, until]
+fn is_due(schedule: &Schedule, after: &DateTime<Local>, until: &DateTime<Local>) -> bool {
+    schedule.after(after).next().is_some_and(|next| next <= *until)
+}
+
+fn capture_and_ocr(capture: &ScheduledCapture) -> AppResult<String> {
+    crate::permissions::ensure_capture_permission()?;
+    let mut backend = XcapBackend::new(0);
+    let image = match capture.region {
+        Some(r) => backend.capture_region(r.x, r.y, r.width, r.height)?,
+        None => backend.capture_frame()?,
+    };
+
+    let mut bytes = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    let options = OcrOptions {
+        langs: capture.langs.clone(),
+        engine: capture.engine.clone(),
+        ..Default::default()
+    }
+    .with_reocr_config();
+    ocr::run_ocr(&bytes, &options)
+}
+
+fn run(app: &AppHandle, capture: ScheduledCapture) {
+    let result = capture_and_ocr(&capture);
+    let payload = ScheduledResult {
+        lang: capture.langs.clone().unwrap_or_else(|| "eng".to_string()),
+        text: result.as_ref().ok().map(|t| t.trim().to_string()),
+        error: result.err().map(|e| e.localized_message()),
+        id: capture.id,
+    };
+    if let Some(text) = &payload.text {
+        if let Err(e) = crate::notifications::notify_result(app, &crate::i18n::t("notification-scheduled-capture"), text) {
+            tracing::warn!("{}", e);
+        }
+    }
+    let _ = app.emit(SCHEDULED_CAPTURE_EVENT, payload);
+}
+
+/// Start the scheduler thread (once)
+pub fn init(app: &AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut last_tick = Local::now();
+        loop {
+            std::thread::sleep(Duration::from_secs(1));
+            let now = Local::now();
+            let captures = crate::config::get().map(|c| c.scheduler.captures).unwrap_or_default();
+            for capture in captures.into_iter().filter(|c| c.enabled) {
+                let due = parse_cron(&capture.cron).is_ok_and(|s| is_due(&s, &last_tick, &now));
+                if due {
+                    // OCR can take longer than a tick; don't hold up other schedules
+                    let app = app.clone();
+                    std::thread::spawn(move || run(&app, capture));
+                }
+            }
+            last_tick = now;
+        }
+    });
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Add a scheduled capture. Returns its id.
+#[tauri::command]
+pub fn schedule_capture(
+    cron: String,
+    region: Option<Region>,
+    langs: Option<String>,
+    engine: Option<String>,
+) -> AppResult<String> {
+    parse_cron(&cron)?;
+    let id = format!("{:x}", Local::now().timestamp_millis());
+    let capture = ScheduledCapture {
+        id: id.clone(),
+        cron,
+        region,
+        langs,
+        engine,
+        enabled: true,
+    };
+    crate::config::update(|c| c.scheduler.captures.push(capture))?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn list_scheduled_captures() -> AppResult<Vec<ScheduledCapture>> {
+    Ok(crate::config::get()?.scheduler.captures)
+}
+
+#[tauri::command]
+pub fn remove_scheduled_capture(id: String) -> AppResult<()> {
+    crate::config::update(|c| c.scheduler.captures.retain(|s| s.id != id))
+}
+
+#[tauri::command]
+pub fn set_scheduled_capture_enabled(id: String, enabled: bool) -> AppResult<()> {
+    crate::config::update(|c| {
+        for capture in c.scheduler.captures.iter_mut().filter(|s| s.id == id) {
+            capture.enabled = enabled;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_cron() {
+        assert!(parse_cron("*/5 * * * *").is_ok());
+        assert!(parse_cron("30 0 9 * * Mon-Fri").is_ok());
+        assert!(parse_cron("every minute").is_err());
+    }
+
+    #[test]
+    fn test_is_due() {
+        let schedule = parse_cron("*/5 * * * *").unwrap();
+        let at = |m, s| Local.with_ymd_and_hms(2024, 5, 1, 12, m, s).unwrap();
+        assert!(is_due(&schedule, &at(4, 59), &at(5, 0)));
+        assert!(!is_due(&schedule, &at(5, 0), &at(5, 1)));
+        assert!(!is_due(&schedule, &at(6, 0), &at(9, 59)));
+    }
+}
@@ -0,0 +1,456 @@
+// Finding things on screen for automation
+// OCRs a monitor and maps the words matching a pattern back to screen
+// coordinates, so test scripts and bots driving the app over IPC can locate
+// text and click it (enigo). Images are found by template matching with
+// zero-mean normalized cross-correlation, searched on downscaled copies first
+// and refined at full size. Coordinates are physical pixels in the virtual
+// desktop, i.e. offset by the monitor's position.
+
+use image::GrayImage;
+use rayon::prelude::*;
+use regex::Regex;
+use xcap::Monitor;
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Pipeline};
+use crate::ocr::{self, OcrOptions};
+use crate::ocr_result::{BoundingBox, OcrLine, OcrResult};
+
+/// Text found on screen
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextMatch {
+    /// The matched part of the line
+    pub text: String,
+    /// The whole recognized line
+    pub line: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub confidence: f32,
+}
+
+impl TextMatch {
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.width as i32 / 2, self.y + self.height as i32 / 2)
+    }
+}
+
+/// Where a template image was found
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMatch {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Normalized cross-correlation, 1.0 for a pixel-exact match
+    pub score: f32,
+}
+
+const DEFAULT_IMAGE_THRESHOLD: f32 = 0.9;
+const DEFAULT_MAX_IMAGE_MATCHES: usize = 10;
+
+/// The coarse search keeps the template's short side at least this long
+const MIN_COARSE_SIDE: u32 = 12;
+const MAX_COARSE_SCALE: u32 = 4;
+
+/// Coarse scores run lower than full-size ones (fine detail is blurred and
+/// shifted by up to a pixel), so the best coarse peaks are refined whatever
+/// their score: this many per wanted match
+const PEAKS_PER_MATCH: usize = 4;
+
+/// What to look for: a case-insensitive substring, or a regular expression
+fn matcher(pattern: &str, regex: bool) -> AppResult<Regex> {
+    if pattern.is_empty() {
+        return Err(AppError::InvalidInput("Empty search pattern".to_string()));
+    }
+    let source = if regex { pattern.to_string() } else { format!("(?i){}", regex::escape(pattern)) };
+    Regex::new(&source).map_err(|e| AppError::InvalidInput(format!("Invalid pattern: {}", e)))
+}
+
+/// Box of the words covering bytes `start..end` of the line text; the line's
+/// box when it has no word boxes
+fn span_box(line: &OcrLine, start: usize, end: usize) -> BoundingBox {
+    let mut offset = 0;
+    let mut bbox: Option<BoundingBox> = None;
+    for word in &line.words {
+        // The line text is its words joined, with or without spaces
+        let Some(found) = line.text[offset..].find(&word.text) else {
+            continue;
+        };
+        let range = offset + found..offset + found + word.text.len();
+        offset = range.end;
+        if range.start < end && start < range.end {
+            bbox = Some(bbox.map_or(word.bbox, |b| b.union(&word.bbox)));
+        }
+    }
+    bbox.unwrap_or(line.bbox)
+}
+
+/// Matches in a result, in image coordinates
+fn find_in_result(result: &OcrResult, pattern: &Regex) -> Vec<(String, String, BoundingBox, f32)> {
+    let mut matches = Vec::new();
+    for line in &result.lines {
+        for found in pattern.find_iter(&line.text) {
+            let bbox = span_box(line, found.start(), found.end());
+            matches.push((found.as_str().to_string(), line.text.clone(), bbox, line.confidence));
+        }
+    }
+    matches
+}
+
+/// A template with its mean removed, ready for correlation
+struct Template {
+    width: u32,
+    height: u32,
+    centered: Vec<f32>,
+    /// Sum of squares of `centered`
+    energy: f32,
+}
+
+impl Template {
+    fn new(image: &GrayImage) -> AppResult<Self> {
+        let n = image.pixels().len() as f32;
+        let mean = image.pixels().map(|p| p.0[0] as f32).sum::<f32>() / n;
+        let centered: Vec<f32> = image.pixels().map(|p| p.0[0] as f32 - mean).collect();
+        let energy = centered.iter().map(|v| v * v).sum::<f32>();
+        if energy < 1.0 {
+            return Err(AppError::InvalidInput("Template is a flat color and can't be matched".to_string()));
+        }
+        Ok(Self { width: image.width(), height: image.height(), centered, energy })
+    }
+
+    /// Correlation with the window of `image` at (x, y)
+    fn score(&self, image: &GrayImage, x: u32, y: u32) -> f32 {
+        let n = self.centered.len() as f32;
+        let (mut sum, mut sum_sq, mut cross) = (0.0f32, 0.0f32, 0.0f32);
+        for ty in 0..self.height {
+            for tx in 0..self.width {
+                let v = image.get_pixel(x + tx, y + ty).0[0] as f32;
+                sum += v;
+                sum_sq += v * v;
+                cross += v * self.centered[(ty * self.width + tx) as usize];
+            }
+        }
+        let variance = sum_sq - sum * sum / n;
+        if variance < 1.0 {
+            return 0.0;
+        }
+        cross / (variance * self.energy).sqrt()
+    }
+
+    /// Scores of every window of `image`, row by row
+    fn score_map(&self, image: &GrayImage) -> Vec<(u32, u32, f32)> {
+        if image.width() < self.width || image.height() < self.height {
+            return Vec::new();
+        }
+        (0..=image.height() - self.height)
+            .into_par_iter()
+            .flat_map_iter(|y| (0..=image.width() - self.width).map(move |x| (x, y, self.score(image, x, y))))
+            .collect()
+    }
+}
+
+/// Average `scale`x`scale` blocks of `image`, starting at (`ox`, `oy`)
+fn downscale(image: &GrayImage, scale: u32, ox: u32, oy: u32) -> GrayImage {
+    let width = (image.width() - ox) / scale;
+    let height = (image.height() - oy) / scale;
+    GrayImage::from_fn(width, height, |x, y| {
+        let mut sum = 0u32;
+        for dy in 0..scale {
+            for dx in 0..scale {
+                sum += image.get_pixel(ox + x * scale + dx, oy + y * scale + dy).0[0] as u32;
+            }
+        }
+        image::Luma([(sum / (scale * scale)) as u8])
+    })
+}
+
+/// Matches of `template` in `image` scoring at least `threshold`, best first,
+/// without overlapping matches
+fn match_template(image: &GrayImage, template: &GrayImage, threshold: f32, max: usize) -> AppResult<Vec<ImageMatch>> {
+    let full = Template::new(template)?;
+    let scale = (template.width().min(template.height()) / MIN_COARSE_SIDE).clamp(1, MAX_COARSE_SCALE);
+
+    let mut candidates = if scale > 1 {
+        // One coarse pass per block offset, so a match lines up with the
+        // downscaled template in one of them whatever its position
+        let coarse = Template::new(&downscale(template, scale, 0, 0))?;
+        let mut candidates = Vec::new();
+        for oy in 0..scale {
+            for ox in 0..scale {
+                let map = coarse.score_map(&downscale(image, scale, ox, oy));
+                candidates.extend(map.into_iter().map(|(x, y, score)| (ox + x * scale, oy + y * scale, score)));
+            }
+        }
+        candidates
+    } else {
+        full.score_map(image).into_iter().filter(|(_, _, score)| *score >= threshold).collect()
+    };
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let overlaps = |found: &[(u32, u32, f32)], x: u32, y: u32| {
+        found.iter().any(|(fx, fy, _)| fx.abs_diff(x) < full.width && fy.abs_diff(y) < full.height)
+    };
+    let mut peaks: Vec<(u32, u32, f32)> = Vec::new();
+    for (x, y, score) in candidates {
+        if peaks.len() >= max * PEAKS_PER_MATCH {
+            break;
+        }
+        if !overlaps(&peaks, x, y) {
+            peaks.push((x, y, score));
+        }
+    }
+
+    // Best full-size position around each peak
+    let (max_x, max_y) = (image.width() - full.width, image.height() - full.height);
+    let mut refined: Vec<(u32, u32, f32)> = peaks
+        .par_iter()
+        .map(|&(cx, cy, _)| {
+            let mut best = (cx.min(max_x), cy.min(max_y), f32::MIN);
+            for y in cy.saturating_sub(1)..=(cy + 1).min(max_y) {
+                for x in cx.saturating_sub(1)..=(cx + 1).min(max_x) {
+                    let score = full.score(image, x, y);
+                    if score > best.2 {
+                        best = (x, y, score);
+                    }
+                }
+            }
+            best
+        })
+        .filter(|(_, _, score)| *score >= threshold)
+        .collect();
+    refined.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    let mut matches: Vec<(u32, u32, f32)> = Vec::new();
+    for (x, y, score) in refined {
+        if matches.len() < max && !overlaps(&matches, x, y) {
+            matches.push((x, y, score));
+        }
+    }
+    Ok(matches
+        .into_iter()
+        .map(|(x, y, score)| ImageMatch { x: x as i32, y: y as i32, width: full.width, height: full.height, score })
+        .collect())
+}
+
+/// Find a template image (PNG, JPEG, ... bytes) on a monitor
+pub fn find_image(template: &[u8], threshold: f32, monitor_index: usize, max: usize) -> AppResult<Vec<ImageMatch>> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(AppError::InvalidInput(format!("Threshold must be 0-1, got {}", threshold)));
+    }
+    let template = crate::image_decode::decode_image_bytes(template)?.to_luma8();
+    crate::permissions::ensure_capture_permission()?;
+    let frame = image::DynamicImage::ImageRgba8(XcapBackend::new(monitor_index).capture_frame()?).to_luma8();
+    if template.width() > frame.width() || template.height() > frame.height() {
+        return Err(AppError::InvalidInput("Template is larger than the screen".to_string()));
+    }
+
+    let (origin_x, origin_y) = monitor_origin(monitor_index)?;
+    Ok(match_template(&frame, &template, threshold, max)?
+        .into_iter()
+        .map(|m| ImageMatch { x: origin_x + m.x, y: origin_y + m.y, ..m })
+        .collect())
+}
+
+/// Position of a monitor in the virtual desktop
+pub(crate) fn monitor_origin(monitor_index: usize) -> AppResult<(i32, i32)> {
+    let monitors = Monitor::all().map_err(|e| AppError::Capture(e.to_string()))?;
+    let monitor = monitors
+        .get(monitor_index)
+        .ok_or_else(|| AppError::Capture("No monitor found".to_string()))?;
+    let x = monitor.x().map_err(|e| AppError::Capture(e.to_string()))?;
+    let y = monitor.y().map_err(|e| AppError::Capture(e.to_string()))?;
+    Ok((x, y))
+}
+
+/// Find text on a monitor
+pub fn find_text(
+    pattern: &str,
+    regex: bool,
+    langs: Option<String>,
+    monitor_index: usize,
+) -> AppResult<Vec<TextMatch>> {
+    let pattern = matcher(pattern, regex)?;
+    crate::permissions::ensure_capture_permission()?;
+    let frame = XcapBackend::new(monitor_index).capture_frame()?;
+    let mut bytes = Vec::new();
+    frame
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    let result = events::track(Pipeline::Ocr, "find_text_on_screen", None, || {
+        ocr::run_ocr_detailed(&bytes, &OcrOptions { langs, ..Default::default() })
+    })?;
+
+    let (origin_x, origin_y) = monitor_origin(monitor_index)?;
+    Ok(find_in_result(&result, &pattern)
+        .into_iter()
+        .map(|(text, line, bbox, confidence)| TextMatch {
+            text,
+            line,
+            x: origin_x + bbox.x as i32,
+            y: origin_y + bbox.y as i32,
+            width: bbox.width,
+            height: bbox.height,
+            confidence,
+        })
+        .collect())
+}
+
+/// Left-click a point given in physical pixels
+pub(crate) fn click_at(x: i32, y: i32) -> AppResult<()> {
+    use enigo::{Button, Coordinate, Direction, Enigo, Mouse, Settings};
+
+    // enigo works in points on macOS
+    #[cfg(target_os = "macos")]
+    let (x, y) = {
+        let scale = crate::measure::primary_scale_factor()?;
+        ((x as f64 / scale).round() as i32, (y as f64 / scale).round() as i32)
+    };
+    let mut enigo = Enigo::new(&Settings::default())
+        .map_err(|e| AppError::PermissionDenied(format!("Cannot control the mouse: {}", e)))?;
+    enigo
+        .move_mouse(x, y, Coordinate::Abs)
+        .and_then(|_| enigo.button(Button::Left, Direction::Click))
+        .map_err(|e| AppError::Other(format!("Failed to click: {}", e)))
+}
+
+/// Click the center of the `occurrence`th (0-based) match of `pattern`
+pub fn click_text(
+    pattern: &str,
+    regex: bool,
+    langs: Option<String>,
+    monitor_index: usize,
+    occurrence: usize,
+) -> AppResult<TextMatch> {
+    let found = find_text(pattern, regex, langs, monitor_index)?
+        .into_iter()
+        .nth(occurrence)
+        .ok_or_else(|| AppError::Ocr(format!("'{}' not found on screen", pattern)))?;
+    let (x, y) = found.center();
+    click_at(x, y)?;
+    Ok(found)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Screen positions of the text matching `pattern` (a case-insensitive
+/// substring, or a regular expression with `regex`) on a monitor
+#[tauri::command(async)]
+pub fn find_text_on_screen(
+    pattern: String,
+    regex: Option<bool>,
+    langs: Option<String>,
+    monitor_index: Option<usize>,
+) -> AppResult<Vec<TextMatch>> {
+    find_text(&pattern, regex.unwrap_or(false), langs, monitor_index.unwrap_or(0))
+}
+
+/// Click the text matching `pattern`; `occurrence` picks among several
+/// matches (in reading order). Returns what was clicked.
+#[tauri::command(async)]
+pub fn click_text_on_screen(
+    pattern: String,
+    regex: Option<bool>,
+    langs: Option<String>,
+    monitor_index: Option<usize>,
+    occurrence: Option<usize>,
+) -> AppResult<TextMatch> {
+    click_text(
+        &pattern,
+        regex.unwrap_or(false),
+        langs,
+        monitor_index.unwrap_or(0),
+        occurrence.unwrap_or(0),
+    )
+}
+
+/// Screen positions of a template image (base64 or data URL) scoring at
+/// least `threshold` (0-1, default 0.9), best first
+#[tauri::command(async)]
+pub fn find_image_on_screen(
+    template_png: String,
+    threshold: Option<f32>,
+    monitor_index: Option<usize>,
+    max_results: Option<usize>,
+) -> AppResult<Vec<ImageMatch>> {
+    let template = ocr::decode_base64_image(&template_png)?;
+    find_image(
+        &template,
+        threshold.unwrap_or(DEFAULT_IMAGE_THRESHOLD),
+        monitor_index.unwrap_or(0),
+        max_results.unwrap_or(DEFAULT_MAX_IMAGE_MATCHES),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr_result::OcrWord;
+
+    fn result() -> OcrResult {
+        let word = |text: &str, x: u32, y: u32, line: u32| {
+            OcrWord::new(text.to_string(), 90.0, BoundingBox { x, y, width: 40, height: 20 }, 1, 1, line)
+        };
+        OcrResult::from_words(
+            vec![
+                word("Please", 0, 0, 1),
+                word("Sign", 50, 0, 1),
+                word("in", 100, 0, 1),
+                word("Cancel", 0, 40, 2),
+                word("sign-up", 50, 40, 2),
+            ],
+            "tesseract",
+        )
+    }
+
+    #[test]
+    fn test_find_substring() {
+        let matches = find_in_result(&result(), &matcher("sign in", false).unwrap());
+        assert_eq!(matches.len(), 1);
+        let (text, line, bbox, _) = &matches[0];
+        assert_eq!(text, "Sign in");
+        assert_eq!(line, "Please Sign in");
+        assert_eq!(*bbox, BoundingBox { x: 50, y: 0, width: 90, height: 20 });
+    }
+
+    #[test]
+    fn test_find_regex() {
+        let matches = find_in_result(&result(), &matcher(r"(?i)^sign|sign-\w+", true).unwrap());
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].0, "sign-up");
+        assert_eq!(matches[0].2, BoundingBox { x: 50, y: 40, width: 40, height: 20 });
+        assert!(matcher("(", true).is_err());
+        assert!(matcher("", false).is_err());
+    }
+
+    /// Noise-like background with a distinct patch pasted at the given spots
+    fn scene(spots: &[(u32, u32)]) -> (GrayImage, GrayImage) {
+        let template = GrayImage::from_fn(32, 24, |x, y| image::Luma([((x * 37 + y * 91) % 251) as u8]));
+        let mut image = GrayImage::from_fn(300, 200, |x, y| image::Luma([((x * 7 + y * 13) % 64 + 96) as u8]));
+        for &(sx, sy) in spots {
+            image::imageops::replace(&mut image, &template, sx as i64, sy as i64);
+        }
+        (image, template)
+    }
+
+    #[test]
+    fn test_match_template() {
+        let (image, template) = scene(&[(40, 30), (201, 151)]);
+        let matches = match_template(&image, &template, 0.9, 10).unwrap();
+        let spots: Vec<(i32, i32)> = matches.iter().map(|m| (m.x, m.y)).collect();
+        assert_eq!(spots.len(), 2);
+        assert!(spots.contains(&(40, 30)) && spots.contains(&(201, 151)));
+        assert!(matches.iter().all(|m| m.score > 0.99 && m.width == 32 && m.height == 24));
+
+        assert_eq!(match_template(&image, &template, 0.9, 1).unwrap().len(), 1);
+        let (empty, _) = scene(&[]);
+        assert!(match_template(&empty, &template, 0.9, 10).unwrap().is_empty());
+        assert!(match_template(&image, &GrayImage::new(8, 8), 0.9, 10).is_err());
+    }
+}
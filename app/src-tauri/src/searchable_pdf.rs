@@ -0,0 +1,275 @@
+// Searchable PDF export
+// Captures are written as PDF pages holding the image with the OCR text laid
+// over it invisibly (text render mode 3), each word placed and stretched to
+// its bounding box, so the PDF can be searched and copied from like a
+// scanned document from an OCR suite. The text uses a non-embedded
+// "glyphless" Type0 font: codes are UTF-16 units, mapped back to Unicode by
+// an identity ToUnicode CMap, and nothing is ever drawn with it.
+
+use lopdf::{dictionary, Document, Object, Stream};
+
+use crate::error::{AppError, AppResult};
+use crate::ocr::{self, OcrOptions};
+use crate::ocr_result::OcrResult;
+
+/// Captures are laid out at screen resolution
+const DPI: f32 = 96.0;
+
+/// Advance of every glyph in the font, in 1/1000 em
+const GLYPH_WIDTH: f32 = 500.0;
+
+/// Baseline above the bottom of a word box, as a share of its height
+const DESCENT: f32 = 0.2;
+
+const FONT_NAME: &str = "GlyphLessFont";
+
+/// One page: a capture, by id or as base64, with its OCR result if known
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PdfPage {
+    pub capture_id: Option<String>,
+    pub base64_image: Option<String>,
+    /// Recognized again when unset
+    pub ocr: Option<OcrResult>,
+}
+
+fn pdf_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Other(format!("Failed to write PDF: {}", e))
+}
+
+/// UTF-16 units of `text` as a PDF hex string
+fn hex_string(text: &str) -> String {
+    let hex: String = text.encode_utf16().map(|unit| format!("{:04X}", unit)).collect();
+    format!("<{}>", hex)
+}
+
+/// Maps every two-byte code to the same UTF-16 unit; surrogate pairs come
+/// out as two units, which viewers join back
+fn to_unicode_cmap() -> String {
+    let ranges: Vec<String> = (0..=0xFFu32).map(|high| format!("<{0:02X}00> <{0:02X}FF> <{0:02X}00>", high)).collect();
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    // At most 100 entries per block
+    for block in ranges.chunks(100) {
+        cmap.push_str(&format!("{} beginbfrange\n{}\nendbfrange\n", block.len(), block.join("\n")));
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap
+}
+
+/// Content stream: the image over the whole page, then the invisible words
+fn page_content(result: &OcrResult, width: u32, height: u32) -> String {
+    let scale = 72.0 / DPI;
+    let (page_width, page_height) = (width as f32 * scale, height as f32 * scale);
+    let mut content = format!("q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q\nBT 3 Tr\n", page_width, page_height);
+    for word in result.lines.iter().flat_map(|line| &line.words) {
+        let text = word.text.trim();
+        let units = text.encode_utf16().count();
+        if units == 0 || word.bbox.width == 0 || word.bbox.height == 0 {
+            continue;
+        }
+        let size = word.bbox.height as f32 * scale;
+        let natural_width = units as f32 * GLYPH_WIDTH / 1000.0 * size;
+        let stretch = word.bbox.width as f32 * scale / natural_width * 100.0;
+        let x = word.bbox.x as f32 * scale;
+        let baseline = page_height - (word.bbox.y + word.bbox.height) as f32 * scale + size * DESCENT;
+        content.push_str(&format!(
+            "/F1 {:.2} Tf {:.2} Tz 1 0 0 1 {:.2} {:.2} Tm {} Tj\n",
+            size,
+            stretch,
+            x,
+            baseline,
+            hex_string(text)
+        ));
+    }
+    content.push_str("ET\n");
+    content
+}
+
+/// The Type0 font the text layer uses
+fn add_font(doc: &mut Document) -> lopdf::ObjectId {
+    let descriptor = doc.add_object(dictionary! {
+        "Type" => "FontDescriptor",
+        "FontName" => FONT_NAME,
+        "Flags" => 5,
+        "FontBBox" => vec![Object::Integer(0), Object::Integer(0), Object::Integer(GLYPH_WIDTH as i64), Object::Integer(1000)],
+        "ItalicAngle" => 0,
+        "Ascent" => 1000,
+        "Descent" => 0,
+        "CapHeight" => 1000,
+        "StemV" => 80,
+    });
+    let cid_font = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "CIDFontType2",
+        "BaseFont" => FONT_NAME,
+        "CIDSystemInfo" => dictionary! {
+            "Registry" => Object::string_literal("Adobe"),
+            "Ordering" => Object::string_literal("Identity"),
+            "Supplement" => 0,
+        },
+        "FontDescriptor" => descriptor,
+        "DW" => GLYPH_WIDTH as i64,
+        "CIDToGIDMap" => "Identity",
+    });
+    let to_unicode = doc.add_object(Stream::new(dictionary! {}, to_unicode_cmap().into_bytes()));
+    doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type0",
+        "BaseFont" => FONT_NAME,
+        "Encoding" => "Identity-H",
+        "DescendantFonts" => vec![Object::Reference(cid_font)],
+        "ToUnicode" => to_unicode,
+    })
+}
+
+/// A searchable PDF of images and their OCR results
+pub fn build(pages: &[(image::DynamicImage, OcrResult)]) -> AppResult<Vec<u8>> {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font = add_font(&mut doc);
+
+    let mut kids: Vec<Object> = Vec::new();
+    for (image, result) in pages {
+        let rgb = image.to_rgb8();
+        let (width, height) = rgb.dimensions();
+        let xobject = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => width as i64,
+                "Height" => height as i64,
+                "ColorSpace" => "DeviceRGB",
+                "BitsPerComponent" => 8,
+            },
+            rgb.into_raw(),
+        ));
+        let content = doc.add_object(Stream::new(dictionary! {}, page_content(result, width, height).into_bytes()));
+        let scale = 72.0 / DPI;
+        let media_box = vec![
+            Object::Integer(0),
+            Object::Integer(0),
+            Object::Integer((width as f32 * scale).round() as i64),
+            Object::Integer((height as f32 * scale).round() as i64),
+        ];
+        let page = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => media_box,
+            "Contents" => content,
+            "Resources" => dictionary! {
+                "XObject" => dictionary! { "Im0" => xobject },
+                "Font" => dictionary! { "F1" => font },
+            },
+        });
+        kids.push(page.into());
+    }
+
+    let count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+        }),
+    );
+    let catalog = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog);
+    doc.compress();
+
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).map_err(pdf_error)?;
+    Ok(bytes)
+}
+
+/// Image bytes of a page
+fn page_image(page: &PdfPage) -> AppResult<Vec<u8>> {
+    if let Some(id) = &page.capture_id {
+        return crate::capture_protocol::get(id)
+            .map(|(_, bytes)| bytes)
+            .ok_or_else(|| AppError::InvalidInput(format!("Capture {} is no longer available", id)));
+    }
+    match &page.base64_image {
+        Some(base64_image) => ocr::decode_base64_image(base64_image),
+        None => Err(AppError::InvalidInput("PDF page without an image".to_string())),
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Write captures to `path` as a searchable PDF, one page each; pages
+/// without an OCR result are recognized with `langs` and `engine`
+#[tauri::command(async)]
+pub fn export_searchable_pdf(
+    pages: Vec<PdfPage>,
+    path: String,
+    langs: Option<String>,
+    engine: Option<String>,
+) -> AppResult<String> {
+    if pages.is_empty() {
+        return Err(AppError::InvalidInput("Nothing to export".to_string()));
+    }
+    let options = OcrOptions {
+        langs,
+        engine,
+        ..Default::default()
+    };
+    let pages = pages
+        .iter()
+        .map(|page| {
+            let bytes = page_image(page)?;
+            let result = match &page.ocr {
+                Some(result) => result.clone(),
+                None => ocr::run_ocr_detailed(&bytes, &options)?,
+            };
+            Ok((crate::image_decode::decode_image_bytes(&bytes)?, result))
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    std::fs::write(&path, build(&pages)?)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr_result::{BoundingBox, OcrWord};
+
+    fn result() -> OcrResult {
+        OcrResult::from_words(
+            vec![
+                OcrWord::new("Hi".to_string(), 90.0, BoundingBox { x: 8, y: 8, width: 24, height: 16 }, 1, 1, 1),
+                OcrWord::new("世界".to_string(), 90.0, BoundingBox { x: 40, y: 8, width: 32, height: 16 }, 1, 1, 1),
+            ],
+            "tesseract",
+        )
+    }
+
+    #[test]
+    fn test_page_content() {
+        let content = page_content(&result(), 96, 48);
+        assert!(content.starts_with("q 72.00 0 0 36.00 0 0 cm /Im0 Do Q\nBT 3 Tr\n"));
+        // 24px at 96 dpi is 18pt; two glyphs of half an em at 12pt are 12pt
+        assert!(content.contains("/F1 12.00 Tf 150.00 Tz 1 0 0 1 6.00 20.40 Tm <00480069> Tj"));
+        assert!(content.contains("<4E16754C> Tj"));
+        assert!(to_unicode_cmap().contains("<4E00> <4EFF> <4E00>"));
+    }
+
+    #[test]
+    fn test_build() {
+        let image = image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(96, 48, image::Rgb([255, 255, 255])));
+        let pdf = build(&[(image.clone(), result()), (image, result())]).unwrap();
+        assert!(pdf.starts_with(b"%PDF-1.5"));
+        let doc = Document::load_mem(&pdf).unwrap();
+        assert_eq!(doc.get_pages().len(), 2);
+    }
+}
@@ -0,0 +1,39 @@
+// Secrets in the OS keychain
+// API keys, passwords, tokens and keys are kept out of config.json. Every
+// secret lives under the app's service name, keyed by a per-feature user name
+// such as "llm-api-key-<provider>" or "history-key".
+
+use crate::error::{AppError, AppResult};
+
+const SERVICE: &str = "com.iml1s.screeninu";
+
+fn entry(user: &str) -> AppResult<keyring::Entry> {
+    keyring::Entry::new(SERVICE, user).map_err(|e| AppError::Other(format!("Keychain unavailable: {}", e)))
+}
+
+/// Read a secret; `None` when nothing is stored for `user`
+pub fn load(user: &str) -> AppResult<Option<String>> {
+    match entry(user)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(AppError::Other(format!("Keychain read failed: {}", e))),
+    }
+}
+
+/// Store a secret; an empty value removes it
+pub fn store(user: &str, value: &str) -> AppResult<()> {
+    if value.is_empty() {
+        return delete(user);
+    }
+    entry(user)?
+        .set_password(value)
+        .map_err(|e| AppError::Other(format!("Keychain write failed: {}", e)))
+}
+
+/// Remove a secret; removing one that isn't stored succeeds
+pub fn delete(user: &str) -> AppResult<()> {
+    match entry(user)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(AppError::Other(format!("Keychain delete failed: {}", e))),
+    }
+}
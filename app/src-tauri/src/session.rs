@@ -0,0 +1,221 @@
+// Session restore
+// The capture being worked on, the selection made on it, its OCR result and
+// translation are written to <app data>/session as they change, so after a
+// crash or restart `restore_last_session` brings the user back to where they
+// were instead of losing a capture that was never saved to history. Saving
+// an empty state (the result was cleared) removes the files.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::capture_protocol::{self, CaptureHandle};
+use crate::error::{AppError, AppResult};
+
+const SESSION_DIR: &str = "session";
+const STATE_FILE: &str = "session.json";
+const IMAGE_FILE: &str = "capture";
+
+/// Capture id whose image is on disk, so it's written once per capture
+static SAVED_CAPTURE: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// Selected area of the capture, in image pixels
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SelectionRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// What the frontend reports as it changes
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SessionState {
+    /// Capture protocol id of the capture being worked on
+    pub capture_id: Option<String>,
+    pub selection: Option<SelectionRect>,
+    pub ocr_text: String,
+    pub translation: Option<String>,
+}
+
+impl SessionState {
+    fn is_empty(&self) -> bool {
+        self.capture_id.is_none() && self.ocr_text.is_empty() && self.translation.is_none()
+    }
+}
+
+/// `SessionState` as written to disk, without the capture id
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedState {
+    selection: Option<SelectionRect>,
+    ocr_text: String,
+    translation: Option<String>,
+    /// RFC 3339
+    saved_at: String,
+}
+
+/// A session brought back after a restart
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSession {
+    /// The capture, served over the capture protocol again
+    pub capture: Option<CaptureHandle>,
+    pub selection: Option<SelectionRect>,
+    pub ocr_text: String,
+    pub translation: Option<String>,
+    pub saved_at: String,
+}
+
+fn session_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join(SESSION_DIR))
+}
+
+/// Write through a temporary file, so a crash mid-write keeps the old file
+fn write_atomic(path: &Path, bytes: &[u8]) -> AppResult<()> {
+    let temp = path.with_extension("tmp");
+    std::fs::write(&temp, bytes)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> AppResult<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Write the state, with the capture's bytes when they changed. `None` keeps
+/// the image on disk, an empty slice removes it.
+fn write_session(dir: &Path, state: &SessionState, image: Option<&[u8]>) -> AppResult<()> {
+    if state.is_empty() {
+        remove_if_exists(&dir.join(STATE_FILE))?;
+        return remove_if_exists(&dir.join(IMAGE_FILE));
+    }
+    std::fs::create_dir_all(dir)?;
+    match image {
+        Some([]) => remove_if_exists(&dir.join(IMAGE_FILE))?,
+        Some(bytes) => write_atomic(&dir.join(IMAGE_FILE), bytes)?,
+        None => {}
+    }
+    let saved = SavedState {
+        selection: state.selection,
+        ocr_text: state.ocr_text.clone(),
+        translation: state.translation.clone(),
+        saved_at: chrono::Local::now().to_rfc3339(),
+    };
+    let json = serde_json::to_vec_pretty(&saved).map_err(|e| AppError::Other(e.to_string()))?;
+    write_atomic(&dir.join(STATE_FILE), &json)
+}
+
+/// The saved state and capture bytes, if a session was saved
+fn read_session(dir: &Path) -> AppResult<Option<(SavedState, Option<Vec<u8>>)>> {
+    let content = match std::fs::read_to_string(dir.join(STATE_FILE)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let saved: SavedState =
+        serde_json::from_str(&content).map_err(|e| AppError::Other(format!("Invalid session file: {}", e)))?;
+    let image = std::fs::read(dir.join(IMAGE_FILE)).ok();
+    Ok(Some((saved, image)))
+}
+
+/// Serve restored capture bytes over the capture protocol again
+fn restore_capture(bytes: Vec<u8>) -> AppResult<CaptureHandle> {
+    let reader = image::ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    let format = reader
+        .format()
+        .ok_or_else(|| AppError::Capture("Unknown image format of the saved capture".to_string()))?;
+    let (width, height) = reader.into_dimensions().map_err(|e| AppError::Capture(e.to_string()))?;
+    let mime_type = format.to_mime_type();
+    Ok(CaptureHandle {
+        id: capture_protocol::store(bytes, mime_type)?,
+        mime_type: mime_type.to_string(),
+        width,
+        height,
+    })
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Persist the work in progress; called by the frontend as it changes
+#[tauri::command(async)]
+pub fn save_session(state: SessionState) -> AppResult<()> {
+    let mut saved_capture = SAVED_CAPTURE.lock()?;
+    let image = match &state.capture_id {
+        Some(id) if saved_capture.as_ref() == Some(id) => None,
+        // Already dropped from memory: keep what's on disk
+        Some(id) => capture_protocol::get(id).map(|(_, bytes)| bytes),
+        None => Some(Vec::new()),
+    };
+    write_session(&session_dir()?, &state, image.as_deref())?;
+    if image.is_some() || state.is_empty() {
+        *saved_capture = state.capture_id.clone();
+    }
+    Ok(())
+}
+
+/// The session saved before the last exit, if any
+#[tauri::command(async)]
+pub fn restore_last_session() -> AppResult<Option<RestoredSession>> {
+    let Some((saved, image)) = read_session(&session_dir()?)? else {
+        return Ok(None);
+    };
+    let capture = match image.map(restore_capture).transpose() {
+        Ok(capture) => capture,
+        Err(e) => {
+            tracing::warn!("Saved capture not restored: {}", e);
+            None
+        }
+    };
+    // The image on disk now belongs to the restored capture
+    *SAVED_CAPTURE.lock()? = capture.as_ref().map(|c| c.id.clone());
+    Ok(Some(RestoredSession {
+        capture,
+        selection: saved.selection,
+        ocr_text: saved.ocr_text,
+        translation: saved.translation,
+        saved_at: saved.saved_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        assert!(read_session(dir).unwrap().is_none());
+
+        let state = SessionState {
+            capture_id: Some("capture-1".to_string()),
+            selection: Some(SelectionRect { x: 10, y: 20, width: 300, height: 40 }),
+            ocr_text: "Hello".to_string(),
+            translation: Some("Hola".to_string()),
+        };
+        write_session(dir, &state, Some(&[1, 2, 3])).unwrap();
+        // Text changed, same capture
+        let edited = SessionState { ocr_text: "Hello!".to_string(), ..state.clone() };
+        write_session(dir, &edited, None).unwrap();
+
+        let (saved, image) = read_session(dir).unwrap().unwrap();
+        assert_eq!(saved.ocr_text, "Hello!");
+        assert_eq!(saved.selection, state.selection);
+        assert_eq!(saved.translation.as_deref(), Some("Hola"));
+        assert_eq!(image, Some(vec![1, 2, 3]));
+
+        write_session(dir, &SessionState::default(), Some(&[])).unwrap();
+        assert!(read_session(dir).unwrap().is_none());
+    }
+}
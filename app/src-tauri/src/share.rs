@@ -0,0 +1,519 @@
+// Share/export to external services
+// Providers: Imgur (images), GitHub Gist and Pastebin (text), generic webhook (both).
+// The resulting URL is copied to the clipboard. `optimize_for_share` shrinks
+// a screenshot to fit a chat app's upload limit first. The GitHub token,
+// Pastebin key and webhook URL (which usually embeds a secret) are kept in the
+// OS keychain, never in the config file.
+
+use image::imageops::FilterType;
+use image::RgbaImage;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::{AppError, AppResult};
+use crate::image_encode::{OutputFormat, OutputOptions};
+
+const USER_AGENT: &str = concat!("ScreenInu/", env!("CARGO_PKG_VERSION"));
+
+/// Quality range searched when optimizing for size; below the minimum the
+/// image is downscaled instead
+const MIN_SHARE_QUALITY: u8 = 40;
+const MAX_SHARE_QUALITY: u8 = 90;
+
+/// Optimizing stops downscaling before either side gets this small
+const MIN_SHARE_SIDE: u32 = 64;
+
+/// Share provider credentials and endpoints
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ShareConfig {
+    pub imgur_client_id: Option<String>,
+    // The secrets below are accepted from the frontend and from configs
+    // written before they moved to the keychain; `move_secrets_to_keychain`
+    // moves them there and they are never written back or returned
+    #[serde(skip_serializing)]
+    pub github_token: Option<String>,
+    #[serde(skip_serializing)]
+    pub pastebin_api_key: Option<String>,
+    #[serde(skip_serializing)]
+    pub webhook_url: Option<String>,
+}
+
+/// Share settings kept in the keychain
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareSecret {
+    GithubToken,
+    PastebinApiKey,
+    WebhookUrl,
+}
+
+impl ShareSecret {
+    const ALL: [ShareSecret; 3] = [ShareSecret::GithubToken, ShareSecret::PastebinApiKey, ShareSecret::WebhookUrl];
+
+    fn keyring_user(self) -> &'static str {
+        match self {
+            ShareSecret::GithubToken => "share-github-token",
+            ShareSecret::PastebinApiKey => "share-pastebin-api-key",
+            ShareSecret::WebhookUrl => "share-webhook-url",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ShareSecret::GithubToken => "GitHub token",
+            ShareSecret::PastebinApiKey => "Pastebin API key",
+            ShareSecret::WebhookUrl => "Webhook URL",
+        }
+    }
+
+    /// The plaintext field of an older config
+    fn field(self, config: &mut ShareConfig) -> &mut Option<String> {
+        match self {
+            ShareSecret::GithubToken => &mut config.github_token,
+            ShareSecret::PastebinApiKey => &mut config.pastebin_api_key,
+            ShareSecret::WebhookUrl => &mut config.webhook_url,
+        }
+    }
+}
+
+/// Share providers
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareProvider {
+    Imgur,
+    Gist,
+    Pastebin,
+    Webhook,
+}
+
+/// Result of a share operation
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ShareResult {
+    pub provider: ShareProvider,
+    /// Public URL of the shared item (webhooks may not return one)
+    pub url: Option<String>,
+}
+
+/// An image recompressed for an upload size limit
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizedImage {
+    pub base64: String,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    pub quality: u8,
+    pub size_bytes: usize,
+    /// False if even the smallest attempt is over the target
+    pub fits: bool,
+}
+
+struct Encoded {
+    bytes: Vec<u8>,
+    width: u32,
+    height: u32,
+    quality: u8,
+}
+
+fn require<'a>(value: &'a Option<String>, name: &str) -> AppResult<&'a str> {
+    value
+        .as_deref()
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| AppError::InvalidInput(format!("{} is not configured", name)))
+}
+
+/// Store a secret in the keychain; an empty value removes it
+fn store_secret(secret: ShareSecret, value: &str) -> AppResult<()> {
+    crate::secrets::store(secret.keyring_user(), value.trim())
+}
+
+fn load_secret(secret: ShareSecret) -> AppResult<Option<String>> {
+    crate::secrets::load(secret.keyring_user())
+}
+
+fn require_secret(secret: ShareSecret) -> AppResult<String> {
+    let value = load_secret(secret)?;
+    require(&value, secret.name()).map(str::to_string)
+}
+
+/// Hand plaintext secrets found in `config` to `store` and clear them.
+/// Returns whether any were found.
+fn take_secrets(
+    config: &mut ShareConfig,
+    mut store: impl FnMut(ShareSecret, &str) -> AppResult<()>,
+) -> AppResult<bool> {
+    let mut found = false;
+    for secret in ShareSecret::ALL {
+        if let Some(value) = secret.field(config).take() {
+            store(secret, &value)?;
+            found = true;
+        }
+    }
+    Ok(found)
+}
+
+/// Move plaintext secrets in `config` to the keychain
+pub fn move_secrets_to_keychain(config: &mut ShareConfig) -> AppResult<()> {
+    take_secrets(config, store_secret).map(|_| ())
+}
+
+/// Move secrets left in the config file by older versions to the keychain
+pub fn init() {
+    let result = crate::config::get().and_then(|config| {
+        let mut share = config.share;
+        if take_secrets(&mut share, store_secret)? {
+            crate::config::update(|c| c.share = share)?;
+            tracing::info!("Moved share credentials to the keychain");
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        tracing::warn!("Share credentials not moved to the keychain: {}", e);
+    }
+}
+
+async fn check_status(response: reqwest::Response, provider: &str) -> AppResult<reqwest::Response> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Err(AppError::Other(format!("{} upload failed: HTTP {} {}", provider, status, body)))
+    }
+}
+
+async fn upload_imgur(config: &ShareConfig, base64_image: &str) -> AppResult<String> {
+    let client_id = require(&config.imgur_client_id, "Imgur client ID")?;
+
+    let response = reqwest::Client::new()
+        .post("https://api.imgur.com/3/image")
+        .header("Authorization", format!("Client-ID {}", client_id))
+        .header("User-Agent", USER_AGENT)
+        .form(&[("image", base64_image), ("type", "base64")])
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("Imgur request failed: {}", e.without_url())))?;
+
+    let json: serde_json::Value = check_status(response, "Imgur")
+        .await?
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Invalid Imgur response: {}", e.without_url())))?;
+
+    json["data"]["link"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Other("Imgur response has no link".to_string()))
+}
+
+async fn create_gist(text: &str) -> AppResult<String> {
+    let token = require_secret(ShareSecret::GithubToken)?;
+
+    let body = serde_json::json!({
+        "description": "Shared from Screen Inu",
+        "public": false,
+        "files": { "screen-inu.txt": { "content": text } },
+    });
+
+    let response = reqwest::Client::new()
+        .post("https://api.github.com/gists")
+        .bearer_auth(token)
+        .header("User-Agent", USER_AGENT)
+        .header("Accept", "application/vnd.github+json")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("Gist request failed: {}", e.without_url())))?;
+
+    let json: serde_json::Value = check_status(response, "Gist")
+        .await?
+        .json()
+        .await
+        .map_err(|e| AppError::Other(format!("Invalid Gist response: {}", e.without_url())))?;
+
+    json["html_url"]
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Other("Gist response has no URL".to_string()))
+}
+
+async fn create_paste(text: &str) -> AppResult<String> {
+    let api_key = require_secret(ShareSecret::PastebinApiKey)?;
+
+    let response = reqwest::Client::new()
+        .post("https://pastebin.com/api/api_post.php")
+        .header("User-Agent", USER_AGENT)
+        .form(&[
+            ("api_dev_key", api_key.as_str()),
+            ("api_option", "paste"),
+            ("api_paste_code", text),
+            ("api_paste_private", "1"),
+        ])
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("Pastebin request failed: {}", e.without_url())))?;
+
+    // Pastebin answers with the paste URL as plain text, or "Bad API request, ..."
+    let body = check_status(response, "Pastebin")
+        .await?
+        .text()
+        .await
+        .map_err(|e| AppError::Other(format!("Invalid Pastebin response: {}", e.without_url())))?;
+    if body.starts_with("http") {
+        Ok(body.trim().to_string())
+    } else {
+        Err(AppError::Other(format!("Pastebin upload failed: {}", body)))
+    }
+}
+
+async fn post_webhook(text: Option<&str>, base64_image: Option<&str>) -> AppResult<Option<String>> {
+    let url = require_secret(ShareSecret::WebhookUrl)?;
+
+    let body = serde_json::json!({
+        "source": "screen-inu",
+        "text": text,
+        "image": base64_image,
+    });
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header("User-Agent", USER_AGENT)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| AppError::Other(format!("Webhook request failed: {}", e.without_url())))?;
+
+    let response = check_status(response, "Webhook").await?;
+
+    // Use a Location header or a {"url": "..."} body if the endpoint provides one
+    if let Some(location) = response
+        .headers()
+        .get("Location")
+        .and_then(|v| v.to_str().ok())
+    {
+        return Ok(Some(location.to_string()));
+    }
+    let json: Option<serde_json::Value> = response.json().await.ok();
+    Ok(json.and_then(|j| j["url"].as_str().map(|s| s.to_string())))
+}
+
+fn encode_at(image: &RgbaImage, format: OutputFormat, quality: u8) -> AppResult<Vec<u8>> {
+    let options = OutputOptions {
+        format,
+        quality: Some(quality),
+        ..Default::default()
+    };
+    crate::image_encode::encode(image.clone(), &options)
+}
+
+/// Encode at the highest quality that fits `target` bytes. If the minimum
+/// quality is still too big, downscale by the estimated ratio and retry.
+fn optimize(mut image: RgbaImage, format: OutputFormat, target: usize) -> AppResult<Encoded> {
+    loop {
+        let smallest = encode_at(&image, format, MIN_SHARE_QUALITY)?;
+        if smallest.len() > target {
+            // Size scales roughly with the pixel count; aim a little under
+            let scale = ((target as f64 / smallest.len() as f64).sqrt() * 0.9).clamp(0.25, 0.9);
+            let width = (image.width() as f64 * scale) as u32;
+            let height = (image.height() as f64 * scale) as u32;
+            if width.min(height) < MIN_SHARE_SIDE {
+                return Ok(Encoded {
+                    bytes: smallest,
+                    width: image.width(),
+                    height: image.height(),
+                    quality: MIN_SHARE_QUALITY,
+                });
+            }
+            image = image::imageops::resize(&image, width, height, FilterType::Lanczos3);
+            continue;
+        }
+
+        let (mut quality, mut bytes) = (MIN_SHARE_QUALITY, smallest);
+        let (mut low, mut high) = (MIN_SHARE_QUALITY + 1, MAX_SHARE_QUALITY);
+        while low <= high {
+            let candidate = low + (high - low) / 2;
+            let encoded = encode_at(&image, format, candidate)?;
+            if encoded.len() <= target {
+                (quality, bytes) = (candidate, encoded);
+                low = candidate + 1;
+            } else {
+                high = candidate - 1;
+            }
+        }
+        return Ok(Encoded {
+            bytes,
+            width: image.width(),
+            height: image.height(),
+            quality,
+        });
+    }
+}
+
+fn finish(app: &AppHandle, provider: ShareProvider, url: Option<String>) -> ShareResult {
+    if let Some(ref url) = url {
+        let _ = app.clipboard().write_text(url.clone());
+    }
+    ShareResult { provider, url }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Upload an image (base64) and copy the share URL to the clipboard
+#[tauri::command]
+pub async fn share_image(app: AppHandle, base64_image: String, provider: ShareProvider) -> AppResult<ShareResult> {
+    let config = crate::config::get()?.share;
+    // Never upload EXIF/GPS metadata
+    let stripped = crate::image_metadata::strip_base64(&base64_image)?;
+    let base64_data = stripped.as_str();
+
+    let url = match provider {
+        ShareProvider::Imgur => Some(upload_imgur(&config, base64_data).await?),
+        ShareProvider::Webhook => post_webhook(None, Some(base64_data)).await?,
+        _ => {
+            return Err(AppError::Unsupported(format!(
+                "{:?} does not accept images",
+                provider
+            )))
+        }
+    };
+
+    Ok(finish(&app, provider, url))
+}
+
+/// Recompress an image (base64) to at most `target_kb` for chat apps with
+/// upload limits: the highest quality that fits, downscaling only when the
+/// lowest quality is still too big. JPEG unless `format` is WebP.
+#[tauri::command]
+pub async fn optimize_for_share(
+    base64_image: String,
+    target_kb: u32,
+    format: Option<OutputFormat>,
+) -> AppResult<OptimizedImage> {
+    let format = format.unwrap_or(OutputFormat::Jpeg);
+    if !matches!(format, OutputFormat::Jpeg | OutputFormat::Webp) {
+        return Err(AppError::InvalidInput(format!("{:?} can't be optimized for size", format)));
+    }
+    if target_kb == 0 {
+        return Err(AppError::InvalidInput("Target size must be above 0 KB".to_string()));
+    }
+    let target = target_kb as usize * 1024;
+
+    crate::image_encode::spawn(move || {
+        use base64::Engine;
+
+        let bytes = crate::ocr::decode_base64_image(&base64_image)?;
+        let image = crate::image_decode::decode_image_bytes(&bytes)?.to_rgba8();
+        let encoded = optimize(image, format, target)?;
+        Ok(OptimizedImage {
+            base64: base64::engine::general_purpose::STANDARD.encode(&encoded.bytes),
+            mime_type: format.mime_type().to_string(),
+            width: encoded.width,
+            height: encoded.height,
+            quality: encoded.quality,
+            size_bytes: encoded.bytes.len(),
+            fits: encoded.bytes.len() <= target,
+        })
+    })
+    .await
+}
+
+/// Share text and copy the share URL to the clipboard
+#[tauri::command]
+pub async fn share_text(app: AppHandle, text: String, provider: ShareProvider) -> AppResult<ShareResult> {
+    if text.trim().is_empty() {
+        return Err(AppError::InvalidInput("Nothing to share".to_string()));
+    }
+
+    let url = match provider {
+        ShareProvider::Gist => Some(create_gist(&text).await?),
+        ShareProvider::Pastebin => Some(create_paste(&text).await?),
+        ShareProvider::Webhook => post_webhook(Some(&text), None).await?,
+        ShareProvider::Imgur => {
+            return Err(AppError::Unsupported("Imgur does not accept text".to_string()))
+        }
+    };
+
+    Ok(finish(&app, provider, url))
+}
+
+/// Store a share credential in the keychain; `None` removes it
+#[tauri::command]
+pub fn set_share_secret(secret: ShareSecret, value: Option<String>) -> AppResult<()> {
+    store_secret(secret, value.as_deref().unwrap_or_default())
+}
+
+/// Whether a share credential is stored
+#[tauri::command]
+pub fn has_share_secret(secret: ShareSecret) -> AppResult<bool> {
+    Ok(load_secret(secret)?.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(width: u32, height: u32) -> RgbaImage {
+        let mut seed: u32 = 1;
+        RgbaImage::from_fn(width, height, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let [r, g, b, _] = seed.to_be_bytes();
+            image::Rgba([r, g, b, 255])
+        })
+    }
+
+    #[test]
+    fn test_optimize_fits_target() {
+        let image = noise(800, 600);
+        let full = encode_at(&image, OutputFormat::Jpeg, MAX_SHARE_QUALITY).unwrap();
+
+        // Already small enough: full size at the best quality
+        let encoded = optimize(image.clone(), OutputFormat::Jpeg, full.len()).unwrap();
+        assert_eq!((encoded.width, encoded.height, encoded.quality), (800, 600, MAX_SHARE_QUALITY));
+
+        let target = full.len() / 8;
+        let encoded = optimize(image.clone(), OutputFormat::Jpeg, target).unwrap();
+        assert!(encoded.bytes.len() <= target);
+        assert!(encoded.width < 800);
+        assert!((encoded.width as f64 / encoded.height as f64 - 4.0 / 3.0).abs() < 0.05);
+
+        // Impossible targets return the smallest attempt
+        let encoded = optimize(image, OutputFormat::Jpeg, 10).unwrap();
+        assert!(encoded.bytes.len() > 10);
+        assert!(encoded.width.min(encoded.height) >= MIN_SHARE_SIDE);
+    }
+
+    #[test]
+    fn test_secrets_stay_out_of_the_config() {
+        let json = r#"{"imgurClientId":"abc","githubToken":"ghp_secret","webhookUrl":"https://hooks.example/T0/secret"}"#;
+        let mut config: ShareConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.github_token.as_deref(), Some("ghp_secret"));
+        // Never written to disk or handed back by get_config
+        assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"imgurClientId":"abc"}"#);
+
+        let mut stored = Vec::new();
+        let found = take_secrets(&mut config, |secret, value| {
+            stored.push((secret, value.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert!(found);
+        assert_eq!(
+            stored,
+            vec![
+                (ShareSecret::GithubToken, "ghp_secret".to_string()),
+                (ShareSecret::WebhookUrl, "https://hooks.example/T0/secret".to_string()),
+            ]
+        );
+        assert_eq!(config, ShareConfig { imgur_client_id: Some("abc".to_string()), ..Default::default() });
+        assert!(!take_secrets(&mut config, |_, _| unreachable!()).unwrap());
+    }
+
+    #[test]
+    fn test_secret_keyring_users_are_distinct() {
+        let users: std::collections::HashSet<_> = ShareSecret::ALL.iter().map(|s| s.keyring_user()).collect();
+        assert_eq!(users.len(), ShareSecret::ALL.len());
+    }
+}
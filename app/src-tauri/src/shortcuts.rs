@@ -0,0 +1,750 @@
+// Global shortcuts
+// Bindings map a trigger to an action name, emitted on `shortcuts://triggered`
+// for the frontend to run. A trigger is one accelerator ("Ctrl+Shift+X"), a
+// chord of several pressed one after another ("Ctrl+Shift+S R"), or a double
+// press of a bare modifier ("Shift Shift"). Only a chord's first step is
+// registered with the OS; the next steps are registered while the chord is
+// pending. Double presses need a low-level keyboard listener (X11, Windows,
+// macOS with Accessibility access). Conflicts with other bindings, app hotkeys
+// and well-known system shortcuts are reported back instead of registered.
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::error::{AppError, AppResult};
+
+/// Emitted with the `ShortcutBinding` that fired
+pub const SHORTCUT_TRIGGERED_EVENT: &str = "shortcuts://triggered";
+
+/// Emitted with the keys pressed so far while a chord waits for its next step
+pub const CHORD_PENDING_EVENT: &str = "shortcuts://chord-pending";
+
+/// Emitted with the conflicts found when the bindings are registered
+pub const SHORTCUT_CONFLICTS_EVENT: &str = "shortcuts://conflicts";
+
+/// Registered first steps of the bindings
+static REGISTERED: Lazy<Mutex<Vec<Shortcut>>> = Lazy::new(|| Mutex::new(Vec::new()));
+/// Bindings as registered, read by the shortcut and keyboard handlers
+static ACTIVE: Lazy<Mutex<Vec<(ShortcutBinding, Trigger)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static PENDING: Lazy<Mutex<Option<PendingChord>>> = Lazy::new(|| Mutex::new(None));
+/// Bumped for every chord step, so a stale timeout doesn't cancel a newer chord
+static CHORD_GENERATION: AtomicU64 = AtomicU64::new(0);
+static LISTENER_STARTED: AtomicBool = AtomicBool::new(false);
+static DOUBLE_PRESS: Lazy<Mutex<DoublePressDetector>> =
+    Lazy::new(|| Mutex::new(DoublePressDetector::new(Duration::from_millis(400))));
+
+/// Shortcut settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ShortcutsConfig {
+    pub bindings: Vec<ShortcutBinding>,
+    /// How long a chord waits for its next step
+    pub chord_timeout_ms: u64,
+    /// Longest gap between the two presses of a double press
+    pub double_press_ms: u64,
+}
+
+impl Default for ShortcutsConfig {
+    fn default() -> Self {
+        Self {
+            bindings: Vec::new(),
+            chord_timeout_ms: 1500,
+            double_press_ms: 400,
+        }
+    }
+}
+
+/// An action and the keys that run it
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    /// Action name the frontend handles, e.g. "capture" or "capture-region"
+    pub action: String,
+    /// e.g. "CommandOrControl+Shift+X", "Ctrl+Shift+S R" or "Shift Shift"
+    pub trigger: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictKind {
+    /// The trigger doesn't parse
+    Invalid,
+    /// Another binding or app hotkey uses it, or is a prefix of it
+    App,
+    /// A system shortcut, or taken by another application
+    System,
+    /// Can't work in this session (double presses on Wayland)
+    Unsupported,
+}
+
+/// A binding that was not registered, and why
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutConflict {
+    pub action: String,
+    pub trigger: String,
+    pub kind: ConflictKind,
+    /// The other action, app feature or system shortcut, or the error
+    pub with: String,
+}
+
+/// Modifiers that can be double-pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Ctrl,
+    Shift,
+    Alt,
+    Meta,
+}
+
+impl FromStr for Modifier {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => Ok(Modifier::Ctrl),
+            "shift" => Ok(Modifier::Shift),
+            "alt" | "option" => Ok(Modifier::Alt),
+            "super" | "meta" | "cmd" | "command" => Ok(Modifier::Meta),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Trigger {
+    /// One accelerator, or a chord of several
+    Keys(Vec<Shortcut>),
+    DoublePress(Modifier),
+}
+
+fn parse_trigger(trigger: &str) -> AppResult<Trigger> {
+    let steps: Vec<&str> = trigger.split_whitespace().collect();
+    if let [first, second] = steps[..] {
+        if let (Ok(a), Ok(b)) = (first.parse::<Modifier>(), second.parse::<Modifier>()) {
+            if a == b {
+                return Ok(Trigger::DoublePress(a));
+            }
+        }
+    }
+    if steps.is_empty() {
+        return Err(AppError::InvalidInput("Empty shortcut".to_string()));
+    }
+    steps
+        .iter()
+        .map(|step| {
+            Shortcut::from_str(step)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid shortcut '{}': {}", step, e)))
+        })
+        .collect::<AppResult<Vec<_>>>()
+        .map(Trigger::Keys)
+}
+
+/// Well-known shortcuts the OS or desktop keeps for itself
+fn system_shortcuts() -> Vec<(Shortcut, &'static str)> {
+    #[cfg(target_os = "macos")]
+    let reserved: &[(&str, &str)] = &[
+        ("Super+Shift+3", "Screenshot"),
+        ("Super+Shift+4", "Screenshot of a selection"),
+        ("Super+Shift+5", "Screenshot toolbar"),
+        ("Super+Space", "Spotlight"),
+        ("Super+Tab", "App switcher"),
+        ("Super+Q", "Quit app"),
+        ("Super+H", "Hide app"),
+        ("Super+M", "Minimize window"),
+        ("Ctrl+Super+Q", "Lock screen"),
+        ("Super+Alt+Escape", "Force quit"),
+    ];
+    #[cfg(target_os = "windows")]
+    let reserved: &[(&str, &str)] = &[
+        ("Super+Shift+S", "Snipping Tool"),
+        ("PrintScreen", "Snipping Tool"),
+        ("Alt+Tab", "App switcher"),
+        ("Alt+F4", "Close window"),
+        ("Super+L", "Lock screen"),
+        ("Super+D", "Show desktop"),
+        ("Super+E", "File Explorer"),
+        ("Super+R", "Run"),
+        ("Super+Tab", "Task view"),
+        ("Ctrl+Shift+Escape", "Task Manager"),
+    ];
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let reserved: &[(&str, &str)] = &[
+        ("PrintScreen", "Screenshot"),
+        ("Shift+PrintScreen", "Screenshot of a selection"),
+        ("Alt+PrintScreen", "Screenshot of a window"),
+        ("Alt+Tab", "App switcher"),
+        ("Alt+F2", "Run command"),
+        ("Alt+F4", "Close window"),
+        ("Super+L", "Lock screen"),
+        ("Ctrl+Alt+T", "Terminal"),
+    ];
+    reserved
+        .iter()
+        .filter_map(|(keys, name)| Shortcut::from_str(keys).ok().map(|shortcut| (shortcut, *name)))
+        .collect()
+}
+
+/// Whether one key sequence starts with the other (or they're equal)
+fn overlaps(a: &[Shortcut], b: &[Shortcut]) -> bool {
+    let len = a.len().min(b.len());
+    a[..len] == b[..len]
+}
+
+/// Conflicts of `bindings` among themselves, with `app_hotkeys` (other
+/// features' hotkeys, by name) and with system shortcuts
+fn find_conflicts(bindings: &[ShortcutBinding], app_hotkeys: &[(String, String)]) -> Vec<ShortcutConflict> {
+    let system = system_shortcuts();
+    let app: Vec<(Shortcut, &str)> = app_hotkeys
+        .iter()
+        .filter_map(|(name, keys)| Shortcut::from_str(keys).ok().map(|shortcut| (shortcut, name.as_str())))
+        .collect();
+    let parsed: Vec<AppResult<Trigger>> = bindings.iter().map(|b| parse_trigger(&b.trigger)).collect();
+
+    let mut conflicts = Vec::new();
+    for (i, (binding, trigger)) in bindings.iter().zip(&parsed).enumerate() {
+        let conflict = |kind, with: String| ShortcutConflict {
+            action: binding.action.clone(),
+            trigger: binding.trigger.clone(),
+            kind,
+            with,
+        };
+        let trigger = match trigger {
+            Ok(trigger) => trigger,
+            Err(e) => {
+                conflicts.push(conflict(ConflictKind::Invalid, e.to_string()));
+                continue;
+            }
+        };
+        let clash = bindings.iter().zip(&parsed).enumerate().find(|(j, (_, other))| {
+            *j != i
+                && match (trigger, other) {
+                    (Trigger::Keys(a), Ok(Trigger::Keys(b))) => overlaps(a, b),
+                    (Trigger::DoublePress(a), Ok(Trigger::DoublePress(b))) => a == b,
+                    _ => false,
+                }
+        });
+        if let Some((_, (other, _))) = clash {
+            conflicts.push(conflict(ConflictKind::App, other.action.clone()));
+            continue;
+        }
+        let Trigger::Keys(keys) = trigger else {
+            continue;
+        };
+        if let Some((_, name)) = app.iter().find(|(shortcut, _)| *shortcut == keys[0]) {
+            conflicts.push(conflict(ConflictKind::App, name.to_string()));
+        } else if let Some((_, name)) = system.iter().find(|(shortcut, _)| *shortcut == keys[0]) {
+            conflicts.push(conflict(ConflictKind::System, name.to_string()));
+        }
+    }
+    conflicts
+}
+
+/// Hotkeys of other features that bindings must not take
+fn app_hotkeys() -> Vec<(String, String)> {
+    let config = crate::config::get().map(|c| c.capture_buffer).unwrap_or_default();
+    let capture_buffer = config.hotkey.filter(|hotkey| config.enabled && !hotkey.is_empty());
+    [("capture-buffer", capture_buffer), ("push-to-ocr", crate::push_to_ocr::hotkey())]
+        .into_iter()
+        .filter_map(|(name, hotkey)| hotkey.map(|hotkey| (name.to_string(), hotkey)))
+        .collect()
+}
+
+/// What pressing a key sequence does
+#[derive(Debug, Clone, PartialEq)]
+enum ChordStep {
+    Fire(ShortcutBinding),
+    /// Wait for one of these next steps
+    Pending(Vec<Shortcut>),
+    None,
+}
+
+fn advance(bindings: &[(ShortcutBinding, Trigger)], pressed: &[Shortcut]) -> ChordStep {
+    let mut next = Vec::new();
+    for (binding, trigger) in bindings {
+        let Trigger::Keys(keys) = trigger else {
+            continue;
+        };
+        if !keys.starts_with(pressed) {
+            continue;
+        }
+        match keys.get(pressed.len()) {
+            None => return ChordStep::Fire(binding.clone()),
+            Some(step) if !next.contains(step) => next.push(*step),
+            Some(_) => {}
+        }
+    }
+    if next.is_empty() {
+        ChordStep::None
+    } else {
+        ChordStep::Pending(next)
+    }
+}
+
+/// A chord waiting for its next step
+struct PendingChord {
+    pressed: Vec<Shortcut>,
+    /// Next steps registered just for this chord
+    temporary: Vec<Shortcut>,
+}
+
+fn describe(pressed: &[Shortcut]) -> Vec<String> {
+    pressed.iter().map(|shortcut| shortcut.into_string()).collect()
+}
+
+fn fire(app: &AppHandle, binding: ShortcutBinding) {
+    tracing::debug!("Shortcut {} ({})", binding.action, binding.trigger);
+    let _ = app.emit(SHORTCUT_TRIGGERED_EVENT, binding);
+}
+
+/// Unregister a finished or abandoned chord's temporary steps
+fn end_chord(app: &AppHandle) {
+    let pending = PENDING.lock().ok().and_then(|mut pending| pending.take());
+    if let Some(pending) = pending {
+        for shortcut in pending.temporary {
+            let _ = app.global_shortcut().unregister(shortcut);
+        }
+    }
+}
+
+/// Handle a press of any registered shortcut
+fn on_pressed(app: &AppHandle, shortcut: Shortcut) {
+    let mut pressed = PENDING
+        .lock()
+        .ok()
+        .and_then(|pending| pending.as_ref().map(|p| p.pressed.clone()))
+        .unwrap_or_default();
+    pressed.push(shortcut);
+    let step = ACTIVE.lock().map(|active| advance(&active, &pressed)).unwrap_or(ChordStep::None);
+    // Registering from within a shortcut handler can deadlock the event loop
+    let app = app.clone();
+    std::thread::spawn(move || {
+        end_chord(&app);
+        match step {
+            ChordStep::Fire(binding) => fire(&app, binding),
+            ChordStep::Pending(next) => begin_chord(&app, pressed, next),
+            // Not a step of the pending chord; maybe the start of another
+            ChordStep::None if pressed.len() > 1 => {
+                let restart = ACTIVE.lock().map(|active| advance(&active, &[shortcut])).unwrap_or(ChordStep::None);
+                match restart {
+                    ChordStep::Fire(binding) => fire(&app, binding),
+                    ChordStep::Pending(next) => begin_chord(&app, vec![shortcut], next),
+                    ChordStep::None => {}
+                }
+            }
+            ChordStep::None => {}
+        }
+    });
+}
+
+/// Register the next steps of a chord until it completes or times out
+fn begin_chord(app: &AppHandle, pressed: Vec<Shortcut>, next: Vec<Shortcut>) {
+    let registered = REGISTERED.lock().map(|r| r.clone()).unwrap_or_default();
+    let temporary: Vec<Shortcut> = next
+        .into_iter()
+        .filter(|step| !registered.contains(step))
+        .filter(|step| match app.global_shortcut().register(*step) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::debug!("Chord step {} not registered: {}", step.into_string(), e);
+                false
+            }
+        })
+        .collect();
+    let _ = app.emit(CHORD_PENDING_EVENT, describe(&pressed));
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = Some(PendingChord { pressed, temporary });
+    }
+
+    let generation = CHORD_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let timeout = crate::config::get().map(|c| c.shortcuts.chord_timeout_ms).unwrap_or(1500);
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(timeout));
+        if CHORD_GENERATION.load(Ordering::SeqCst) == generation {
+            end_chord(&app);
+            let _ = app.emit(CHORD_PENDING_EVENT, Vec::<String>::new());
+        }
+    });
+}
+
+/// Tracks bare modifier taps; a tap is a press and release with no other key
+/// in between
+struct DoublePressDetector {
+    window: Duration,
+    /// Modifier held down, when it was pressed, and whether it's still a tap
+    held: Option<(Modifier, Instant, bool)>,
+    last_tap: Option<(Modifier, Instant)>,
+}
+
+impl DoublePressDetector {
+    fn new(window: Duration) -> Self {
+        Self { window, held: None, last_tap: None }
+    }
+
+    /// A key went down; `modifier` is None for other keys
+    fn key_down(&mut self, modifier: Option<Modifier>, now: Instant) {
+        let Some(m) = modifier else {
+            if let Some((_, _, clean)) = &mut self.held {
+                *clean = false;
+            }
+            self.last_tap = None;
+            return;
+        };
+        match &mut self.held {
+            // Auto-repeat
+            Some((held, _, _)) if *held == m => {}
+            Some((_, _, clean)) => *clean = false,
+            None => self.held = Some((m, now, true)),
+        }
+    }
+
+    /// A key went up; returns the modifier on its second tap
+    fn key_up(&mut self, modifier: Option<Modifier>, now: Instant) -> Option<Modifier> {
+        let m = modifier?;
+        let (held, pressed_at, clean) = self.held.filter(|(held, _, _)| *held == m)?;
+        self.held = None;
+        if !clean || now.duration_since(pressed_at) > self.window {
+            self.last_tap = None;
+            return None;
+        }
+        match self.last_tap.take() {
+            Some((last, at)) if last == held && now.duration_since(at) <= self.window => Some(held),
+            _ => {
+                self.last_tap = Some((held, now));
+                None
+            }
+        }
+    }
+}
+
+fn modifier_of(key: rdev::Key) -> Option<Modifier> {
+    match key {
+        rdev::Key::ControlLeft | rdev::Key::ControlRight => Some(Modifier::Ctrl),
+        rdev::Key::ShiftLeft | rdev::Key::ShiftRight => Some(Modifier::Shift),
+        rdev::Key::Alt | rdev::Key::AltGr => Some(Modifier::Alt),
+        rdev::Key::MetaLeft | rdev::Key::MetaRight => Some(Modifier::Meta),
+        _ => None,
+    }
+}
+
+/// Start the keyboard listener for double presses (once; it can't be stopped,
+/// and ignores keys while no double-press binding is active)
+fn start_double_press_listener(app: &AppHandle) {
+    if LISTENER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let result = rdev::listen(move |event| {
+            let now = Instant::now();
+            let Ok(mut detector) = DOUBLE_PRESS.lock() else {
+                return;
+            };
+            let tapped = match event.event_type {
+                rdev::EventType::KeyPress(key) => {
+                    detector.key_down(modifier_of(key), now);
+                    None
+                }
+                rdev::EventType::KeyRelease(key) => detector.key_up(modifier_of(key), now),
+                _ => None,
+            };
+            drop(detector);
+            let Some(modifier) = tapped else {
+                return;
+            };
+            let binding = ACTIVE.lock().ok().and_then(|active| {
+                active
+                    .iter()
+                    .find(|(_, trigger)| *trigger == Trigger::DoublePress(modifier))
+                    .map(|(binding, _)| binding.clone())
+            });
+            if let Some(binding) = binding {
+                fire(&app, binding);
+            }
+        });
+        if let Err(e) = result {
+            tracing::warn!("Double-press shortcuts unavailable: {:?}", e);
+            LISTENER_STARTED.store(false, Ordering::SeqCst);
+        }
+    });
+}
+
+fn double_press_supported() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        !crate::permissions::is_wayland_session()
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        true
+    }
+}
+
+/// Register `config`'s bindings in place of the previous ones. Bindings with a
+/// conflict are left out and returned. Every trigger is parsed before anything
+/// changes, and first steps kept from the previous set stay registered; if
+/// releasing the old ones fails, the previous set is put back.
+fn register_bindings(app: &AppHandle, config: &ShortcutsConfig) -> AppResult<Vec<ShortcutConflict>> {
+    let mut conflicts = find_conflicts(&config.bindings, &app_hotkeys());
+    let parsed = config
+        .bindings
+        .iter()
+        .filter(|b| !conflicts.iter().any(|c| c.action == b.action && c.trigger == b.trigger))
+        .map(|b| parse_trigger(&b.trigger).map(|trigger| (b.clone(), trigger)))
+        .collect::<AppResult<Vec<_>>>()?;
+    let mut registered = REGISTERED.lock()?;
+    end_chord(app);
+
+    let previous = registered.clone();
+    let mut added = Vec::new();
+    let mut active = Vec::new();
+    for (binding, trigger) in parsed {
+        let conflict = |kind, with: String| ShortcutConflict {
+            action: binding.action.clone(),
+            trigger: binding.trigger.clone(),
+            kind,
+            with,
+        };
+        match &trigger {
+            Trigger::Keys(keys) => {
+                let first = keys[0];
+                if !previous.contains(&first) && !added.contains(&first) {
+                    // Usually another application holding the hotkey
+                    if let Err(e) = register_first_step(app, first) {
+                        conflicts.push(conflict(ConflictKind::System, e.to_string()));
+                        continue;
+                    }
+                    added.push(first);
+                }
+            }
+            Trigger::DoublePress(_) => {
+                if !double_press_supported() {
+                    conflicts.push(conflict(
+                        ConflictKind::Unsupported,
+                        "Double presses need X11 on Linux".to_string(),
+                    ));
+                    continue;
+                }
+            }
+        }
+        active.push((binding, trigger));
+    }
+
+    let kept: Vec<Shortcut> = active
+        .iter()
+        .filter_map(|(_, trigger)| match trigger {
+            Trigger::Keys(keys) => Some(keys[0]),
+            Trigger::DoublePress(_) => None,
+        })
+        .collect();
+    let mut released = Vec::new();
+    for shortcut in previous.iter().filter(|s| !kept.contains(s)) {
+        if let Err(e) = app.global_shortcut().unregister(*shortcut) {
+            // Back to the previous set, which ACTIVE still describes
+            for shortcut in &added {
+                let _ = app.global_shortcut().unregister(*shortcut);
+            }
+            for shortcut in released {
+                let _ = register_first_step(app, shortcut);
+            }
+            return Err(AppError::Other(format!("Failed to release shortcut {}: {}", shortcut.into_string(), e)));
+        }
+        released.push(*shortcut);
+    }
+    *registered = previous.into_iter().filter(|s| kept.contains(s)).chain(added).collect();
+
+    if let Ok(mut detector) = DOUBLE_PRESS.lock() {
+        *detector = DoublePressDetector::new(Duration::from_millis(config.double_press_ms));
+    }
+    if active.iter().any(|(_, trigger)| matches!(trigger, Trigger::DoublePress(_))) {
+        start_double_press_listener(app);
+    }
+    // Not held above: the shortcut handlers lock it on the event loop
+    *ACTIVE.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = active;
+    Ok(conflicts)
+}
+
+fn register_first_step(app: &AppHandle, shortcut: Shortcut) -> Result<(), tauri_plugin_global_shortcut::Error> {
+    app.global_shortcut().on_shortcut(shortcut, |app, shortcut, event| {
+        if event.state == ShortcutState::Pressed {
+            on_pressed(app, *shortcut);
+        }
+    })
+}
+
+/// Register the saved bindings, reporting conflicts to the UI
+pub fn init(app: &AppHandle) {
+    match crate::config::get().and_then(|c| register_bindings(app, &c.shortcuts)) {
+        Ok(conflicts) if !conflicts.is_empty() => {
+            tracing::warn!("{} shortcut(s) not registered", conflicts.len());
+            let _ = app.emit(SHORTCUT_CONFLICTS_EVENT, conflicts);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Shortcuts: {}", e),
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_shortcuts_config() -> AppResult<ShortcutsConfig> {
+    Ok(crate::config::get()?.shortcuts)
+}
+
+/// Save and register the bindings; returns the ones left out and why
+#[tauri::command]
+pub fn set_shortcuts_config(app: AppHandle, config: ShortcutsConfig) -> AppResult<Vec<ShortcutConflict>> {
+    if config.chord_timeout_ms == 0 || config.double_press_ms == 0 {
+        return Err(AppError::InvalidInput("Timeouts must be positive".to_string()));
+    }
+    let conflicts = register_bindings(&app, &config)?;
+    crate::config::update(|c| c.shortcuts = config)?;
+    Ok(conflicts)
+}
+
+/// Conflicts `trigger` would have as `action`'s shortcut, e.g. while the user
+/// records one. The action's own binding doesn't count.
+#[tauri::command]
+pub fn check_shortcut(app: AppHandle, action: String, trigger: String) -> AppResult<Vec<ShortcutConflict>> {
+    let mut bindings: Vec<ShortcutBinding> = crate::config::get()?
+        .shortcuts
+        .bindings
+        .into_iter()
+        .filter(|b| b.action != action)
+        .collect();
+    bindings.push(ShortcutBinding { action: action.clone(), trigger: trigger.clone() });
+    let mut conflicts: Vec<ShortcutConflict> = find_conflicts(&bindings, &app_hotkeys())
+        .into_iter()
+        .filter(|c| c.action == action)
+        .collect();
+    // Registered elsewhere in the app (or by us, for a binding being changed)
+    if conflicts.is_empty() {
+        if let Ok(Trigger::Keys(keys)) = parse_trigger(&trigger) {
+            let own = REGISTERED.lock().map(|r| r.contains(&keys[0])).unwrap_or(false);
+            if !own && app.global_shortcut().is_registered(keys[0]) {
+                conflicts.push(ShortcutConflict {
+                    action,
+                    trigger,
+                    kind: ConflictKind::App,
+                    with: "registered".to_string(),
+                });
+            }
+        }
+    }
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(action: &str, trigger: &str) -> ShortcutBinding {
+        ShortcutBinding { action: action.to_string(), trigger: trigger.to_string() }
+    }
+
+    fn keys(trigger: &str) -> Vec<Shortcut> {
+        match parse_trigger(trigger).unwrap() {
+            Trigger::Keys(keys) => keys,
+            other => panic!("not keys: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_trigger() {
+        assert_eq!(keys("Ctrl+Shift+X").len(), 1);
+        assert_eq!(keys("Ctrl+Shift+S R"), vec![
+            Shortcut::from_str("Ctrl+Shift+S").unwrap(),
+            Shortcut::from_str("R").unwrap(),
+        ]);
+        assert_eq!(parse_trigger("Shift Shift").unwrap(), Trigger::DoublePress(Modifier::Shift));
+        assert_eq!(parse_trigger("cmd command").unwrap(), Trigger::DoublePress(Modifier::Meta));
+        assert!(parse_trigger("").is_err());
+        assert!(parse_trigger("Ctrl+Nope").is_err());
+    }
+
+    #[test]
+    fn test_advance_chords() {
+        let bindings: Vec<(ShortcutBinding, Trigger)> = [
+            binding("region", "Ctrl+Shift+S R"),
+            binding("window", "Ctrl+Shift+S W"),
+            binding("capture", "Ctrl+Shift+X"),
+        ]
+        .into_iter()
+        .map(|b| {
+            let trigger = parse_trigger(&b.trigger).unwrap();
+            (b, trigger)
+        })
+        .collect();
+        let fired = |step: ChordStep| match step {
+            ChordStep::Fire(binding) => binding.action,
+            other => panic!("nothing fired: {:?}", other),
+        };
+        assert_eq!(advance(&bindings, &keys("Ctrl+Shift+S")), ChordStep::Pending(keys("R W")));
+        assert_eq!(fired(advance(&bindings, &keys("Ctrl+Shift+S W"))), "window");
+        assert_eq!(advance(&bindings, &keys("Ctrl+Shift+S X")), ChordStep::None);
+        assert_eq!(fired(advance(&bindings, &keys("Ctrl+Shift+X"))), "capture");
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let bindings = vec![
+            binding("capture", "Ctrl+Shift+S"),
+            binding("region", "Ctrl+Shift+S R"),
+            binding("translate", "Ctrl+Nope"),
+            binding("ocr", "Ctrl+Shift+O"),
+            binding("rewind", "Ctrl+Shift+Z"),
+            binding("pin", "Alt Alt"),
+            binding("copy", "Alt Alt"),
+        ];
+        let app = vec![("capture-buffer".to_string(), "Ctrl+Shift+Z".to_string())];
+        let conflicts = find_conflicts(&bindings, &app);
+        let kinds: Vec<(&str, ConflictKind, &str)> =
+            conflicts.iter().map(|c| (c.action.as_str(), c.kind, c.with.as_str())).collect();
+        assert!(kinds.contains(&("capture", ConflictKind::App, "region")));
+        assert!(kinds.contains(&("region", ConflictKind::App, "capture")));
+        assert!(kinds.iter().any(|(action, kind, _)| *action == "translate" && *kind == ConflictKind::Invalid));
+        assert!(kinds.contains(&("rewind", ConflictKind::App, "capture-buffer")));
+        assert!(kinds.contains(&("pin", ConflictKind::App, "copy")));
+        assert!(!kinds.iter().any(|(action, _, _)| *action == "ocr"));
+
+        let system = system_shortcuts();
+        assert!(!system.is_empty());
+        let keys = system[0].0.into_string();
+        let conflicts = find_conflicts(&[binding("capture", &keys)], &[]);
+        assert_eq!(conflicts[0].kind, ConflictKind::System);
+    }
+
+    #[test]
+    fn test_double_press() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut detector = DoublePressDetector::new(Duration::from_millis(400));
+        detector.key_down(Some(Modifier::Shift), at(0));
+        assert_eq!(detector.key_up(Some(Modifier::Shift), at(50)), None);
+        detector.key_down(Some(Modifier::Shift), at(150));
+        detector.key_down(Some(Modifier::Shift), at(180));
+        assert_eq!(detector.key_up(Some(Modifier::Shift), at(200)), Some(Modifier::Shift));
+
+        // Shift+A then Shift isn't a double press
+        detector.key_down(Some(Modifier::Shift), at(1000));
+        detector.key_down(None, at(1020));
+        assert_eq!(detector.key_up(Some(Modifier::Shift), at(1050)), None);
+        detector.key_down(Some(Modifier::Shift), at(1100));
+        assert_eq!(detector.key_up(Some(Modifier::Shift), at(1150)), None);
+
+        // Too slow
+        detector.key_down(Some(Modifier::Ctrl), at(2000));
+        detector.key_up(Some(Modifier::Ctrl), at(2050));
+        detector.key_down(Some(Modifier::Ctrl), at(2600));
+        assert_eq!(detector.key_up(Some(Modifier::Ctrl), at(2650)), None);
+    }
+}
@@ -0,0 +1,138 @@
+// Sidecar resolution
+// Sidecars are named with a target triple in development (binaries/
+// tesseract-x86_64-pc-windows-msvc.exe) and for the managed downloads. The
+// triple is worked out at runtime from the machine rather than the build, so
+// an x64 build on an ARM Windows laptop or under Rosetta still finds the
+// native binary first, then falls back to one the OS can emulate.
+
+use std::path::PathBuf;
+
+/// Target triple for an OS and architecture, as `std::env::consts` names them
+pub fn triple(os: &str, arch: &str) -> Option<String> {
+    let triple = match (os, arch) {
+        ("windows", "x86_64" | "aarch64") => format!("{}-pc-windows-msvc", arch),
+        ("macos", "x86_64" | "aarch64") => format!("{}-apple-darwin", arch),
+        ("linux", "x86_64" | "aarch64") => format!("{}-unknown-linux-gnu", arch),
+        ("linux", "arm") => "armv7-unknown-linux-gnueabihf".to_string(),
+        _ => return None,
+    };
+    Some(triple)
+}
+
+/// Triples to try, best first: the machine's, the build's, then x86_64
+/// where ARM machines emulate it (Windows, macOS with Rosetta)
+fn triples_for(os: &str, native_arch: &str, build_arch: &str) -> Vec<String> {
+    let mut arches = vec![native_arch, build_arch];
+    if native_arch == "aarch64" && matches!(os, "windows" | "macos") {
+        arches.push("x86_64");
+    }
+    let mut triples: Vec<String> = Vec::new();
+    for triple in arches.into_iter().filter_map(|arch| triple(os, arch)) {
+        if !triples.contains(&triple) {
+            triples.push(triple);
+        }
+    }
+    triples
+}
+
+/// Architecture of the machine, which differs from the build's under
+/// emulation
+fn native_arch() -> &'static str {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::SystemInformation::{
+            IMAGE_FILE_MACHINE, IMAGE_FILE_MACHINE_AMD64, IMAGE_FILE_MACHINE_ARM64,
+        };
+        use windows::Win32::System::Threading::{GetCurrentProcess, IsWow64Process2};
+
+        let mut process = IMAGE_FILE_MACHINE::default();
+        let mut native = IMAGE_FILE_MACHINE::default();
+        if unsafe { IsWow64Process2(GetCurrentProcess(), &mut process, Some(&mut native)) }.is_ok() {
+            if native == IMAGE_FILE_MACHINE_ARM64 {
+                return "aarch64";
+            }
+            if native == IMAGE_FILE_MACHINE_AMD64 {
+                return "x86_64";
+            }
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        // Reported by Apple Silicon Macs even to processes under Rosetta
+        let arm64 = std::process::Command::new("sysctl")
+            .args(["-in", "hw.optional.arm64"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "1")
+            .unwrap_or(false);
+        if arm64 {
+            return "aarch64";
+        }
+    }
+    std::env::consts::ARCH
+}
+
+/// Triples to try on this machine, best first; empty on platforms without
+/// known sidecar builds
+pub fn candidate_triples() -> Vec<String> {
+    triples_for(std::env::consts::OS, native_arch(), std::env::consts::ARCH)
+}
+
+/// File names of a sidecar for each candidate triple
+pub fn sidecar_names(base: &str) -> Vec<String> {
+    candidate_triples()
+        .iter()
+        .map(|triple| format!("{}-{}{}", base, triple, std::env::consts::EXE_SUFFIX))
+        .collect()
+}
+
+/// First existing sidecar in `dir`
+pub fn find_in(dir: &std::path::Path, base: &str) -> Option<PathBuf> {
+    sidecar_names(base)
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Where a sidecar was looked for, for diagnostics
+pub fn describe(base: &str) -> String {
+    let names = sidecar_names(base);
+    if names.is_empty() {
+        format!(
+            "no bundled {} for {} on {}",
+            base,
+            std::env::consts::OS,
+            native_arch()
+        )
+    } else {
+        format!("looked for {}", names.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triple() {
+        assert_eq!(triple("windows", "aarch64").as_deref(), Some("aarch64-pc-windows-msvc"));
+        assert_eq!(triple("linux", "arm").as_deref(), Some("armv7-unknown-linux-gnueabihf"));
+        assert_eq!(triple("linux", "aarch64").as_deref(), Some("aarch64-unknown-linux-gnu"));
+        assert_eq!(triple("freebsd", "x86_64"), None);
+    }
+
+    #[test]
+    fn test_triples_for() {
+        // x64 build on an ARM Windows laptop
+        assert_eq!(
+            triples_for("windows", "aarch64", "x86_64"),
+            vec!["aarch64-pc-windows-msvc", "x86_64-pc-windows-msvc"]
+        );
+        assert_eq!(
+            triples_for("macos", "aarch64", "aarch64"),
+            vec!["aarch64-apple-darwin", "x86_64-apple-darwin"]
+        );
+        // No emulation on ARM Linux
+        assert_eq!(triples_for("linux", "arm", "arm"), vec!["armv7-unknown-linux-gnueabihf"]);
+        assert!(triples_for("freebsd", "x86_64", "x86_64").is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+// Disk usage report
+// Breaks the app data folder down by category so users can see what takes
+// space, with a cleanup action where one is safe to offer.
+
+use std::path::PathBuf;
+
+use tauri::AppHandle;
+
+use crate::error::{AppError, AppResult};
+use crate::model_manager::TessdataTier;
+use crate::translator::calculate_dir_size;
+
+/// Days without use after which tessdata cleanup removes a model
+const UNUSED_MODEL_DAYS: u32 = 30;
+
+/// Storage categories
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageCategory {
+    /// OCR language packs (all tiers and custom models)
+    Tessdata,
+    TranslationModels,
+    /// File-based OCR history
+    History,
+    /// Assembled tessdata directory and other regenerated files
+    Cache,
+    /// Automatic and pre-restore history backups
+    Backups,
+}
+
+/// Size of one category
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageEntry {
+    pub category: StorageCategory,
+    pub size_bytes: u64,
+    pub paths: Vec<String>,
+    /// Description of what `clean_storage` does for this category, if supported
+    pub cleanup: Option<String>,
+}
+
+/// Disk usage report
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageReport {
+    pub entries: Vec<StorageEntry>,
+    pub total_bytes: u64,
+}
+
+fn path_size(path: &PathBuf) -> u64 {
+    match std::fs::metadata(path) {
+        Ok(m) if m.is_dir() => calculate_dir_size(path).unwrap_or(0),
+        Ok(m) => m.len(),
+        Err(_) => 0,
+    }
+}
+
+fn entry(category: StorageCategory, paths: Vec<PathBuf>, cleanup: Option<&str>) -> StorageEntry {
+    StorageEntry {
+        category,
+        size_bytes: paths.iter().map(path_size).sum(),
+        paths: paths.iter().map(|p| p.display().to_string()).collect(),
+        cleanup: cleanup.map(|c| c.to_string()),
+    }
+}
+
+fn tessdata_paths() -> AppResult<Vec<PathBuf>> {
+    let mut paths = TessdataTier::ALL
+        .into_iter()
+        .map(crate::model_manager::get_tier_dir)
+        .collect::<AppResult<Vec<_>>>()?;
+    paths.push(crate::model_manager::get_custom_dir()?);
+    Ok(paths)
+}
+
+fn cache_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join("tessdata_active"))
+}
+
+fn backups_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join("backups"))
+}
+
+/// Build the report
+pub fn report(app: &AppHandle) -> AppResult<StorageReport> {
+    let unused_cleanup = format!(
+        "Delete language packs unused for {} days",
+        UNUSED_MODEL_DAYS
+    );
+    let entries = vec![
+        entry(
+            StorageCategory::Tessdata,
+            tessdata_paths()?,
+            Some(&unused_cleanup),
+        ),
+        entry(
+            StorageCategory::TranslationModels,
+            vec![crate::translator::get_models_dir()?],
+            None,
+        ),
+        entry(
+            StorageCategory::History,
+            crate::history::history_file_path(app)?
+                .into_iter()
+                .collect(),
+            None,
+        ),
+        entry(
+            StorageCategory::Cache,
+            vec![cache_dir()?],
+            Some("Clear regenerated files"),
+        ),
+        entry(
+            StorageCategory::Backups,
+            vec![backups_dir()?],
+            Some("Delete all history backups"),
+        ),
+    ];
+    let total_bytes = entries.iter().map(|e| e.size_bytes).sum();
+    Ok(StorageReport {
+        entries,
+        total_bytes,
+    })
+}
+
+/// Run the cleanup action of a category. Returns the bytes freed.
+pub fn clean(category: StorageCategory) -> AppResult<u64> {
+    match category {
+        StorageCategory::Tessdata => {
+            let paths = tessdata_paths()?;
+            let before: u64 = paths.iter().map(path_size).sum();
+            crate::model_manager::delete_unused_models(UNUSED_MODEL_DAYS)?;
+            let after: u64 = paths.iter().map(path_size).sum();
+            Ok(before.saturating_sub(after))
+        }
+        StorageCategory::Cache | StorageCategory::Backups => {
+            let dir = if category == StorageCategory::Cache {
+                cache_dir()?
+            } else {
+                backups_dir()?
+            };
+            let freed = path_size(&dir);
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir)?;
+            }
+            Ok(freed)
+        }
+        StorageCategory::TranslationModels | StorageCategory::History => Err(
+            AppError::Unsupported(format!("{:?} has no automatic cleanup", category)),
+        ),
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Disk usage by category
+#[tauri::command]
+pub fn get_storage_report(app: AppHandle) -> AppResult<StorageReport> {
+    report(&app)
+}
+
+/// Run a category's cleanup action, returns the bytes freed
+#[tauri::command]
+pub fn clean_storage(category: StorageCategory) -> AppResult<u64> {
+    clean(category)
+}
@@ -0,0 +1,303 @@
+// On-device summarization
+// Condenses long OCR results (full articles) into a short digest with a small
+// T5 model, managed like the translation models: downloaded on demand into
+// the app data directory and run with tract.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tokenizers::Tokenizer;
+
+use crate::decoding::DecodingParams;
+use crate::error::{AppError, AppResult};
+use crate::translator::{self, RunnableModel};
+
+/// Downloadable models: (name, base URL, task prefix)
+const SUMMARY_MODELS: &[(&str, &str, &str)] = &[
+    ("t5-small", "https://huggingface.co/Xenova/t5-small/resolve/main", "summarize: "),
+];
+
+/// T5 attends to at most this many input tokens
+const MAX_INPUT_TOKENS: usize = 512;
+
+static SUMMARIZER: Lazy<Mutex<Option<Summarizer>>> = Lazy::new(|| Mutex::new(None));
+
+/// Summarization settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SummarizationConfig {
+    /// Summarize long captures when they're added to history
+    pub auto_summarize: bool,
+    pub model: String,
+    /// Captures shorter than this aren't summarized automatically
+    pub min_chars: usize,
+    /// Maximum summary length in tokens
+    pub max_len: usize,
+}
+
+impl Default for SummarizationConfig {
+    fn default() -> Self {
+        Self {
+            auto_summarize: false,
+            model: "t5-small".to_string(),
+            min_chars: 1000,
+            max_len: 120,
+        }
+    }
+}
+
+/// Summarization model information
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct SummaryModelInfo {
+    pub name: String,
+    pub size_bytes: u64,
+    pub installed: bool,
+    pub download_url: Option<String>,
+}
+
+struct Summarizer {
+    name: String,
+    encoder: RunnableModel,
+    decoder: RunnableModel,
+    tokenizer: Tokenizer,
+    prefix: &'static str,
+}
+
+pub fn get_summary_models_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join("summary_models"))
+}
+
+fn model_entry(name: &str) -> AppResult<&'static (&'static str, &'static str, &'static str)> {
+    SUMMARY_MODELS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown summarization model '{}'", name)))
+}
+
+fn is_installed(model_dir: &std::path::Path) -> bool {
+    ["encoder_model.onnx", "decoder_model.onnx", "tokenizer.json"]
+        .iter()
+        .all(|f| model_dir.join(f).exists())
+}
+
+impl Summarizer {
+    fn load(name: &str) -> AppResult<Self> {
+        let (_, _, prefix) = model_entry(name)?;
+        let model_dir = get_summary_models_dir()?.join(name);
+        if !is_installed(&model_dir) {
+            return Err(AppError::ModelMissing(name.to_string()));
+        }
+        let tokenizer = Tokenizer::from_file(model_dir.join("tokenizer.json"))
+            .map_err(|e| AppError::Summarization(format!("Failed to load tokenizer: {}", e)))?;
+        Ok(Self {
+            name: name.to_string(),
+            encoder: translator::load_graph(&model_dir.join("encoder_model.onnx"))?,
+            decoder: translator::load_graph(&model_dir.join("decoder_model.onnx"))?,
+            tokenizer,
+            prefix,
+        })
+    }
+
+    fn token_id(&self, token: &str) -> AppResult<u32> {
+        self.tokenizer
+            .token_to_id(token)
+            .ok_or_else(|| AppError::Summarization(format!("Tokenizer has no {} token", token)))
+    }
+
+    fn summarize(&self, text: &str, max_len: usize) -> AppResult<String> {
+        let encoding = self
+            .tokenizer
+            .encode(format!("{}{}", self.prefix, text), true)
+            .map_err(|e| AppError::Summarization(format!("Tokenization failed: {}", e)))?;
+        let mut input_ids: Vec<i64> = encoding.get_ids().iter().map(|&id| id as i64).collect();
+        // Keep the beginning of long articles, and the end-of-sequence token
+        if input_ids.len() > MAX_INPUT_TOKENS {
+            let eos = input_ids[input_ids.len() - 1];
+            input_ids.truncate(MAX_INPUT_TOKENS - 1);
+            input_ids.push(eos);
+        }
+        let attention_mask = vec![1i64; input_ids.len()];
+
+        let params = DecodingParams {
+            beam_size: 2,
+            max_length: max_len,
+            no_repeat_ngram_size: 3,
+            ..Default::default()
+        };
+        params.validate()?;
+        // T5 starts decoding from the pad token
+        let output_ids = translator::generate(
+            &self.encoder,
+            &self.decoder,
+            &input_ids,
+            &attention_mask,
+            self.token_id("<pad>")?,
+            self.token_id("</s>")?,
+            &params,
+        )?;
+        let summary = self
+            .tokenizer
+            .decode(&output_ids, true)
+            .map_err(|e| AppError::Summarization(format!("Decoding failed: {}", e)))?;
+        Ok(summary.trim().to_string())
+    }
+}
+
+fn memory_key(name: &str) -> String {
+    format!("summary:{}", name)
+}
+
+/// Memory budget eviction
+fn evict(_key: &str) -> bool {
+    unload()
+}
+
+/// Summarize with the configured model, loading it on first use
+pub fn summarize(text: &str, max_len: usize) -> AppResult<String> {
+    let model = crate::config::get()?.summarization.model;
+    let mut guard = SUMMARIZER.lock()?;
+    if guard.as_ref().is_none_or(|s| s.name != model) {
+        if let Some(previous) = guard.take() {
+            crate::memory::release(&memory_key(&previous.name));
+        }
+        let bytes = translator::calculate_dir_size(&get_summary_models_dir()?.join(&model)).unwrap_or(0);
+        crate::memory::reserve(&memory_key(&model), "summary", bytes, evict);
+        match Summarizer::load(&model) {
+            Ok(summarizer) => *guard = Some(summarizer),
+            Err(e) => {
+                crate::memory::release(&memory_key(&model));
+                return Err(e);
+            }
+        }
+    }
+    let summarizer = guard
+        .as_ref()
+        .ok_or_else(|| AppError::Summarization("Summarizer not initialized".to_string()))?;
+    crate::memory::touch(&memory_key(&summarizer.name));
+    summarizer.summarize(text, max_len)
+}
+
+/// Drop the loaded model. Skipped if a summary is running; returns whether
+/// the model is gone.
+pub fn unload() -> bool {
+    match SUMMARIZER.try_lock() {
+        Ok(mut guard) => {
+            if let Some(previous) = guard.take() {
+                crate::memory::release(&memory_key(&previous.name));
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Summarize text into at most `max_len` tokens
+#[tauri::command(async)]
+pub fn summarize_text(text: String, max_len: Option<usize>) -> AppResult<String> {
+    if text.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let max_len = match max_len {
+        Some(max_len) => max_len,
+        None => crate::config::get()?.summarization.max_len,
+    };
+    summarize(&text, max_len)
+}
+
+/// Summary to store with a new history item: None when auto-summarize is
+/// off, the text is short or the model isn't installed
+#[tauri::command(async)]
+pub fn summarize_for_history(text: String) -> AppResult<Option<String>> {
+    let config = crate::config::get()?.summarization;
+    if !config.auto_summarize || text.chars().count() < config.min_chars {
+        return Ok(None);
+    }
+    match summarize(&text, config.max_len) {
+        Ok(summary) => Ok(Some(summary).filter(|s| !s.is_empty())),
+        Err(AppError::ModelMissing(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+pub fn list_summary_models() -> AppResult<Vec<SummaryModelInfo>> {
+    let models_dir = get_summary_models_dir()?;
+    Ok(SUMMARY_MODELS
+        .iter()
+        .map(|(name, url, _)| {
+            let model_path = models_dir.join(name);
+            let installed = is_installed(&model_path);
+            SummaryModelInfo {
+                name: name.to_string(),
+                size_bytes: if installed { translator::calculate_dir_size(&model_path).unwrap_or(0) } else { 0 },
+                installed,
+                download_url: Some(url.to_string()),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn download_summary_model(model_name: String, op_id: Option<String>) -> AppResult<()> {
+    let run = crate::events::start(crate::events::Pipeline::Download, "download_summary_model", op_id.clone());
+    let result = async {
+        let operation = crate::operations::start(op_id)?;
+        let cancel = operation.token();
+        let (name, base_url, _) = model_entry(&model_name)?;
+        let model_path = get_summary_models_dir()?.join(name);
+        if is_installed(&model_path) {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&model_path)?;
+
+        translator::download_file(&format!("{}/tokenizer.json", base_url), &model_path.join("tokenizer.json"), &cancel).await?;
+        for part in ["encoder_model", "decoder_model"] {
+            let target = model_path.join(format!("{}.onnx", part));
+            if translator::download_file(&format!("{}/onnx/{}.onnx", base_url, part), &target, &cancel).await.is_err() {
+                translator::download_file(&format!("{}/onnx/{}_quantized.onnx", base_url, part), &target, &cancel).await?;
+            }
+        }
+        Ok::<_, AppError>(())
+    }
+    .await;
+    run.finish(result)
+}
+
+#[tauri::command]
+pub fn delete_summary_model(model_name: String) -> AppResult<()> {
+    // Only known models, so the name can't point outside the models directory
+    let (name, _, _) = model_entry(&model_name)?;
+    let mut guard = SUMMARIZER.lock()?;
+    if guard.as_ref().is_some_and(|s| s.name == *name) {
+        *guard = None;
+        crate::memory::release(&memory_key(name));
+    }
+    let model_path = get_summary_models_dir()?.join(name);
+    if model_path.exists() {
+        std::fs::remove_dir_all(&model_path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_entry() {
+        assert_eq!(model_entry("t5-small").unwrap().2, "summarize: ");
+        for name in ["../config.json", "t5-small/..", "", "t5-base"] {
+            assert!(matches!(model_entry(name), Err(AppError::InvalidInput(_))), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_delete_summary_model_rejects_unknown_names() {
+        assert!(matches!(delete_summary_model("..".to_string()), Err(AppError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,237 @@
+// Output templates
+// Copied and exported results go through the active template, e.g.
+// "{text}\n\n— captured {date} from {app}". Placeholders are text,
+// translation, lang, app, date and time; `{text|quote}` prefixes every line
+// with "> " and `{text|json}` writes a JSON string. `{{` and `}}` are literal
+// braces. A few templates are built in; users add their own. Without an
+// active template the text is copied as is.
+
+use crate::error::{AppError, AppResult};
+
+/// Template settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TemplatesConfig {
+    /// Id of the template applied; plain text when unset
+    pub active: Option<String>,
+    /// User templates, after the built-in ones
+    pub custom: Vec<OutputTemplate>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputTemplate {
+    pub id: String,
+    pub name: String,
+    pub template: String,
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+/// What `list_templates` returns
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateList {
+    pub active: Option<String>,
+    pub templates: Vec<OutputTemplate>,
+}
+
+/// Values a template can use
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TemplateContext {
+    pub translation: Option<String>,
+    pub lang: Option<String>,
+    /// Application the capture was taken from
+    pub app: Option<String>,
+    /// Unix epoch milliseconds of the capture; now when unset
+    pub timestamp: Option<i64>,
+}
+
+const BUILTIN: [(&str, &str, &str); 4] = [
+    ("plain", "Plain text", "{text}"),
+    ("attribution", "With source", "{text}\n\n— captured {date} from {app}"),
+    ("markdown-quote", "Markdown quote", "{text|quote}\n>\n> — {date}"),
+    (
+        "json",
+        "JSON",
+        "{{\"text\": {text|json}, \"translation\": {translation|json}, \"lang\": {lang|json}, \"app\": {app|json}, \"date\": {date|json}}}",
+    ),
+];
+
+fn builtin() -> impl Iterator<Item = OutputTemplate> {
+    BUILTIN.iter().map(|(id, name, template)| OutputTemplate {
+        id: id.to_string(),
+        name: name.to_string(),
+        template: template.to_string(),
+        builtin: true,
+    })
+}
+
+fn all_templates(config: &TemplatesConfig) -> Vec<OutputTemplate> {
+    builtin().chain(config.custom.iter().cloned()).collect()
+}
+
+fn quote(text: &str) -> String {
+    text.lines().map(|line| format!("> {}", line).trim_end().to_string()).collect::<Vec<_>>().join("\n")
+}
+
+/// Value of one placeholder; `None` when the name or filter is unknown
+fn placeholder(expr: &str, text: &str, context: &TemplateContext) -> Option<String> {
+    let (name, filter) = match expr.split_once('|') {
+        Some((name, filter)) => (name.trim(), Some(filter.trim())),
+        None => (expr.trim(), None),
+    };
+    let time = context
+        .timestamp
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|t| t.with_timezone(&chrono::Local))
+        .unwrap_or_else(chrono::Local::now);
+    let value = match name {
+        "text" => Some(text.to_string()),
+        "translation" => context.translation.clone(),
+        "lang" => context.lang.clone(),
+        "app" => context.app.clone(),
+        "date" => Some(time.format("%Y-%m-%d %H:%M").to_string()),
+        "time" => Some(time.format("%H:%M").to_string()),
+        _ => return None,
+    };
+    match filter {
+        None => Some(value.unwrap_or_default()),
+        Some("quote") => Some(quote(&value.unwrap_or_default())),
+        // Missing values are null, not ""
+        Some("json") => Some(serde_json::json!(value).to_string()),
+        Some(_) => None,
+    }
+}
+
+/// Fill in `template`
+pub fn render(template: &str, text: &str, context: &TemplateContext) -> AppResult<String> {
+    let mut out = String::with_capacity(template.len() + text.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        let end = match tail.find('}') {
+            Some(end) if tail.starts_with('{') => end,
+            _ => return Err(AppError::InvalidInput(format!("Unmatched brace in template: {}", template))),
+        };
+        let expr = &tail[1..end];
+        let value = placeholder(expr, text, context)
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown placeholder {{{}}}", expr)))?;
+        out.push_str(&value);
+        rest = &tail[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// `text` through the active template, as is when none is set
+pub fn apply(text: &str, context: &TemplateContext) -> AppResult<String> {
+    let config = crate::config::get()?.templates;
+    let Some(active) = &config.active else {
+        return Ok(text.to_string());
+    };
+    match all_templates(&config).into_iter().find(|t| &t.id == active) {
+        Some(template) => render(&template.template, text, context),
+        None => Ok(text.to_string()),
+    }
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Built-in and user templates, and which one is active
+#[tauri::command]
+pub fn list_templates() -> AppResult<TemplateList> {
+    let config = crate::config::get()?.templates;
+    Ok(TemplateList {
+        active: config.active.clone(),
+        templates: all_templates(&config),
+    })
+}
+
+/// Apply `id` when copying and exporting; `None` copies plain text
+#[tauri::command]
+pub fn set_active_template(id: Option<String>) -> AppResult<()> {
+    let config = crate::config::get()?.templates;
+    if let Some(id) = &id {
+        if !all_templates(&config).iter().any(|t| &t.id == id) {
+            return Err(AppError::InvalidInput(format!("Unknown template: {}", id)));
+        }
+    }
+    crate::config::update(|c| c.templates.active = id)
+}
+
+/// Add a user template, or replace the one with the same id
+#[tauri::command]
+pub fn save_template(template: OutputTemplate) -> AppResult<()> {
+    if template.id.trim().is_empty() || builtin().any(|t| t.id == template.id) {
+        return Err(AppError::InvalidInput(format!("Invalid template id: {}", template.id)));
+    }
+    // Rejects unknown placeholders before they fail a copy
+    render(&template.template, "", &TemplateContext::default())?;
+    let template = OutputTemplate { builtin: false, ..template };
+    crate::config::update(|c| match c.templates.custom.iter_mut().find(|t| t.id == template.id) {
+        Some(existing) => *existing = template,
+        None => c.templates.custom.push(template),
+    })
+}
+
+#[tauri::command]
+pub fn delete_template(id: String) -> AppResult<()> {
+    crate::config::update(|c| {
+        c.templates.custom.retain(|t| t.id != id);
+        if c.templates.active.as_deref() == Some(id.as_str()) {
+            c.templates.active = None;
+        }
+    })
+}
+
+/// `text` as it would be copied with the active template
+#[tauri::command]
+pub fn apply_template(text: String, context: Option<TemplateContext>) -> AppResult<String> {
+    apply(&text, &context.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let context = TemplateContext {
+            app: Some("Firefox".to_string()),
+            lang: Some("eng".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(render("{text} ({app}, {lang})", "Hi", &context).unwrap(), "Hi (Firefox, eng)");
+        assert_eq!(render("{text|quote}", "a\n\nb", &context).unwrap(), "> a\n>\n> b");
+        assert_eq!(
+            render("{{\"t\": {text|json}, \"tr\": {translation|json}}}", "say \"hi\"", &context).unwrap(),
+            r#"{"t": "say \"hi\"", "tr": null}"#
+        );
+        assert!(render("{nope}", "x", &context).is_err());
+        assert!(render("{text|upper}", "x", &context).is_err());
+        assert!(render("{text", "x", &context).is_err());
+    }
+
+    #[test]
+    fn test_builtin_templates_render() {
+        let context = TemplateContext { timestamp: Some(0), ..Default::default() };
+        for template in builtin() {
+            let rendered = render(&template.template, "Hello", &context).unwrap();
+            assert!(rendered.contains("Hello"), "{}: {}", template.id, rendered);
+        }
+        let json = builtin().find(|t| t.id == "json").unwrap();
+        let rendered = render(&json.template, "Hello", &context).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(value["text"], "Hello");
+    }
+}
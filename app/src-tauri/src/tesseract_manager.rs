@@ -0,0 +1,346 @@
+// Managed Tesseract binary
+// Downloads a platform-appropriate tesseract build into the app data directory
+// when neither a bundled sidecar nor a system install is available. Builds are
+// listed in a JSON manifest with SHA-256 checksums:
+//
+//   { "x86_64-pc-windows-msvc": { "url": "...zip", "sha256": "...", "version": "5.5.0" }, ... }
+//
+// The manifest is signed with the release minisign key (manifest.json.minisig,
+// the key the updater trusts), so the checksums can't be swapped along with the
+// binaries. Archives (.zip) are extracted as-is, so Windows builds can ship
+// their DLLs. A build is unpacked and run in a staging directory before it
+// replaces the installed one.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{AppError, AppResult};
+
+/// Release manifest of prebuilt tesseract binaries
+const MANIFEST_URL: &str =
+    "https://github.com/ImL1s/screen_inu/releases/download/tesseract-binaries/manifest.json";
+
+/// Release signing key (minisign), the updater's `pubkey`
+const MANIFEST_PUBLIC_KEY: &str = "RWRmL78igHeIFEg5wUqLN4M1Ix8wKYof/BzATlFjRxZm7hbY4ZFuGBOw";
+
+/// Emitted with `{ downloaded, total }` while downloading
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "tesseract://download-progress";
+
+/// A downloadable build from the manifest
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TesseractBuild {
+    pub url: String,
+    pub sha256: String,
+    pub version: Option<String>,
+}
+
+/// Where tesseract was found
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TesseractStatus {
+    /// Resolved executable path (may be a bare name resolved via PATH)
+    pub path: String,
+    /// The executable could be started
+    pub found: bool,
+    /// The executable is the managed download
+    pub managed: bool,
+}
+
+fn executable_name() -> &'static str {
+    if cfg!(windows) {
+        "tesseract.exe"
+    } else {
+        "tesseract"
+    }
+}
+
+/// Directory for the managed tesseract install
+pub fn managed_dir() -> AppResult<PathBuf> {
+    Ok(crate::config::get_app_data_dir()?.join("binaries"))
+}
+
+/// Path of the managed tesseract executable, if installed
+pub fn managed_tesseract_path() -> Option<PathBuf> {
+    let path = managed_dir().ok()?.join(executable_name());
+    path.exists().then_some(path)
+}
+
+/// Check that tesseract at `path` can be started
+fn is_runnable(path: &Path) -> bool {
+    std::process::Command::new(path)
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Parse the manifest after checking its minisign signature against `public_key`
+fn verify_manifest(
+    public_key: &str,
+    manifest: &[u8],
+    signature: &str,
+) -> AppResult<std::collections::HashMap<String, TesseractBuild>> {
+    let invalid =
+        |e: minisign_verify::Error| AppError::Download(format!("Tesseract manifest signature is invalid: {}", e));
+    let key = minisign_verify::PublicKey::from_base64(public_key).map_err(invalid)?;
+    let signature = minisign_verify::Signature::decode(signature).map_err(invalid)?;
+    key.verify(manifest, &signature, false).map_err(invalid)?;
+    serde_json::from_slice(manifest).map_err(|e| AppError::Download(format!("Invalid tesseract manifest: {}", e)))
+}
+
+async fn fetch_bytes(url: &str) -> AppResult<Vec<u8>> {
+    let response = reqwest::get(url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::Download(format!("Failed to fetch {}: {}", url, e)))?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::Download(format!("Failed to fetch {}: {}", url, e)))?;
+    Ok(bytes.to_vec())
+}
+
+async fn fetch_build() -> AppResult<TesseractBuild> {
+    let triples = crate::sidecar::candidate_triples();
+    if triples.is_empty() {
+        return Err(AppError::Unsupported("No prebuilt tesseract for this platform".to_string()));
+    }
+
+    let manifest = fetch_bytes(MANIFEST_URL).await?;
+    let signature = fetch_bytes(&format!("{}.minisig", MANIFEST_URL)).await?;
+    let manifest = verify_manifest(MANIFEST_PUBLIC_KEY, &manifest, &String::from_utf8_lossy(&signature))?;
+
+    // The machine's own build first, then one it can emulate
+    triples
+        .iter()
+        .find_map(|triple| manifest.get(triple).cloned())
+        .ok_or_else(|| AppError::Unsupported(format!("No prebuilt tesseract for {}", triples.join(" or "))))
+}
+
+async fn download(app: &AppHandle, url: &str) -> AppResult<Vec<u8>> {
+    let mut response = reqwest::get(url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::Download(format!("{}: {}", url, e)))?;
+
+    let total = response.content_length();
+    let mut data = Vec::with_capacity(total.unwrap_or(0) as usize);
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Download(format!("{}: {}", url, e)))?
+    {
+        data.extend_from_slice(&chunk);
+        let _ = app.emit(
+            DOWNLOAD_PROGRESS_EVENT,
+            serde_json::json!({ "downloaded": data.len(), "total": total }),
+        );
+    }
+    Ok(data)
+}
+
+/// Extract a zip archive into `dir` (the executable is expected at the archive root)
+fn extract_zip(data: &[u8], dir: &Path) -> AppResult<()> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data))
+        .map_err(|e| AppError::Download(format!("Invalid archive: {}", e)))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| AppError::Download(format!("Invalid archive: {}", e)))?;
+        // enclosed_name rejects absolute paths and `..`
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let target = dir.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        std::fs::write(&target, contents)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> AppResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> AppResult<()> {
+    Ok(())
+}
+
+/// Unpack a build into `staging` and check it runs
+fn stage(data: &[u8], zip: bool, staging: &Path, runs: impl Fn(&Path) -> bool) -> AppResult<()> {
+    if staging.exists() {
+        std::fs::remove_dir_all(staging)?;
+    }
+    std::fs::create_dir_all(staging)?;
+
+    let executable = staging.join(executable_name());
+    if zip {
+        extract_zip(data, staging)?;
+    } else {
+        std::fs::write(&executable, data)?;
+    }
+    if !executable.exists() {
+        return Err(AppError::Download(format!(
+            "Archive does not contain {}",
+            executable_name()
+        )));
+    }
+    make_executable(&executable)?;
+
+    if !runs(&executable) {
+        return Err(AppError::TesseractNotFound(format!(
+            "{} was downloaded but cannot run",
+            executable_name()
+        )));
+    }
+    Ok(())
+}
+
+/// Stage the build next to `dir` and swap it in, so a failed download or a
+/// build that doesn't run leaves the installed one untouched
+fn install_into(dir: &Path, data: &[u8], zip: bool, runs: impl Fn(&Path) -> bool) -> AppResult<PathBuf> {
+    let staging = dir.with_extension("staging");
+    if let Err(e) = stage(data, zip, &staging, runs) {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    let previous = dir.with_extension("old");
+    if previous.exists() {
+        std::fs::remove_dir_all(&previous)?;
+    }
+    if dir.exists() {
+        std::fs::rename(dir, &previous)?;
+    }
+    if let Err(e) = std::fs::rename(&staging, dir) {
+        if previous.exists() {
+            let _ = std::fs::rename(&previous, dir);
+        }
+        return Err(e.into());
+    }
+    let _ = std::fs::remove_dir_all(&previous);
+    Ok(dir.join(executable_name()))
+}
+
+/// Download, verify and install the managed tesseract build
+pub async fn install(app: &AppHandle) -> AppResult<PathBuf> {
+    let build = fetch_build().await?;
+    let data = download(app, &build.url).await?;
+
+    let actual = sha256_hex(&data);
+    if !actual.eq_ignore_ascii_case(build.sha256.trim()) {
+        return Err(AppError::Download(format!(
+            "Checksum mismatch for tesseract (expected {}, got {})",
+            build.sha256, actual
+        )));
+    }
+
+    let executable = install_into(&managed_dir()?, &data, build.url.ends_with(".zip"), is_runnable)?;
+    tracing::info!(
+        "Installed tesseract {} at {}",
+        build.version.as_deref().unwrap_or("(unknown version)"),
+        executable.display()
+    );
+    Ok(executable)
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Report which tesseract binary is used and whether it runs
+#[tauri::command]
+pub fn get_tesseract_status() -> AppResult<TesseractStatus> {
+    let path = crate::ocr::get_tesseract_path()?;
+    Ok(TesseractStatus {
+        found: is_runnable(&path),
+        managed: managed_tesseract_path().as_deref() == Some(path.as_path()),
+        path: path.display().to_string(),
+    })
+}
+
+/// Download tesseract if no working binary is found. Returns the executable path.
+#[tauri::command]
+pub async fn install_tesseract(app: AppHandle, force: Option<bool>) -> AppResult<String> {
+    if !force.unwrap_or(false) {
+        let current = crate::ocr::get_tesseract_path()?;
+        if is_runnable(&current) {
+            return Ok(current.display().to_string());
+        }
+    }
+    Ok(install(&app).await?.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    const TEST_KEY: &str = "RWRvHl+Pe2eqeS0N2+eg1gLZe6sdDJ6ivBg34EvkoMmivi2hCf/8r+kd";
+    const TEST_MANIFEST: &str = r#"{"x86_64-unknown-linux-gnu":{"url":"https://example.com/tesseract","sha256":"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad","version":"5.5.0"}}"#;
+    const TEST_SIGNATURE: &str = "untrusted comment: signature from rsign secret key
+RURvHl+Pe2eqeThP0maSwhQ+n8GyUjdwN4x/rBgq1t8cjVCLr/l5Q5xNAVfH2Knx4mEE/uiCGOxC+rexc+KsaZ9nlM7n3tusNA4=
+trusted comment: test manifest
+zkczZGWPeSJHHhYwqRIyzZmkBxzOpVf+KJWUrY6Wi1pLFacctYR5Ilue/BxaEy79GigtbzeHm82mBTNZxC+sAQ==
+";
+
+    #[test]
+    fn test_verify_manifest() {
+        let manifest = verify_manifest(TEST_KEY, TEST_MANIFEST.as_bytes(), TEST_SIGNATURE).unwrap();
+        assert_eq!(manifest["x86_64-unknown-linux-gnu"].version.as_deref(), Some("5.5.0"));
+
+        // A checksum swapped along with the binary breaks the signature
+        let tampered = TEST_MANIFEST.replace("ba78", "0000");
+        assert!(verify_manifest(TEST_KEY, tampered.as_bytes(), TEST_SIGNATURE).is_err());
+        // Signed by another key
+        assert!(verify_manifest(MANIFEST_PUBLIC_KEY, TEST_MANIFEST.as_bytes(), TEST_SIGNATURE).is_err());
+    }
+
+    #[test]
+    fn test_install_into_keeps_old_build_on_failure() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("binaries");
+        let executable = install_into(&dir, b"v1", false, |_| true).unwrap();
+        assert_eq!(std::fs::read(&executable).unwrap(), b"v1");
+
+        // A build that doesn't run, or an archive without the executable
+        assert!(install_into(&dir, b"v2", false, |_| false).is_err());
+        assert!(install_into(&dir, b"not a zip", true, |_| true).is_err());
+        assert_eq!(std::fs::read(&executable).unwrap(), b"v1");
+        assert!(!dir.with_extension("staging").exists());
+
+        install_into(&dir, b"v2", false, |path| path.starts_with(dir.with_extension("staging"))).unwrap();
+        assert_eq!(std::fs::read(&executable).unwrap(), b"v2");
+        assert!(!dir.with_extension("old").exists());
+    }
+}
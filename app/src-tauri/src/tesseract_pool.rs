@@ -0,0 +1,253 @@
+// Tesseract instance pool
+// Spawning the tesseract CLI reloads the traineddata on every call, which
+// costs 300-800 ms and dominates live OCR. With the `tesseract-ffi` feature,
+// libtesseract is linked directly and initialized engines are kept warm here,
+// keyed by their language and settings. OSD and anything the pool can't
+// express fall back to the CLI.
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tesseract::{OcrEngineMode, Tesseract};
+
+use crate::error::{AppError, AppResult};
+
+/// Idle engines kept across all settings; each holds its traineddata in memory
+const MAX_IDLE: usize = 4;
+
+/// Key of the idle engines in the memory budget
+const MEMORY_KEY: &str = "tesseract";
+
+/// Header of the CLI's TSV output, which the API leaves out
+const TSV_HEADER: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext";
+
+/// Idle engines, least recently used first
+static IDLE: Lazy<Mutex<Vec<(EngineKey, Tesseract)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Settings an engine was initialized with. Variables persist on an engine,
+/// so only engines with identical settings are reused.
+#[derive(Debug, Clone, PartialEq)]
+struct EngineKey {
+    lang: String,
+    psm: String,
+    oem: Option<String>,
+    variables: Vec<(String, String)>,
+}
+
+/// A tesseract run as the pool sees it
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    key: EngineKey,
+    tsv: bool,
+}
+
+/// Parse arguments built for the CLI (`-l`, `--psm`, `--oem`, `-c`, `tsv`).
+/// None for runs the pool doesn't handle: OSD, or unknown arguments.
+fn parse_args(args: &[String]) -> Option<Request> {
+    let mut lang = None;
+    let mut psm = None;
+    let mut oem = None;
+    let mut variables = Vec::new();
+    let mut tsv = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-l" => lang = Some(iter.next()?.clone()),
+            "--psm" => psm = Some(iter.next()?.clone()),
+            "--oem" => oem = Some(iter.next()?.clone()),
+            "-c" => {
+                let (name, value) = iter.next()?.split_once('=')?;
+                variables.push((name.to_string(), value.to_string()));
+            }
+            "tsv" => tsv = true,
+            _ => return None,
+        }
+    }
+
+    let psm = psm.unwrap_or_else(|| "3".to_string());
+    // OSD-only mode prints a report the API doesn't produce
+    if psm == "0" {
+        return None;
+    }
+    Some(Request {
+        key: EngineKey {
+            lang: lang.unwrap_or_else(|| "eng".to_string()),
+            psm,
+            oem,
+            variables,
+        },
+        tsv,
+    })
+}
+
+fn engine_mode(oem: &str) -> AppResult<OcrEngineMode> {
+    match oem {
+        "0" => Ok(OcrEngineMode::TesseractOnly),
+        "1" => Ok(OcrEngineMode::LstmOnly),
+        "2" => Ok(OcrEngineMode::TesseractLstmCombined),
+        "3" => Ok(OcrEngineMode::Default),
+        _ => Err(AppError::InvalidInput(format!("Invalid OCR engine mode: {}", oem))),
+    }
+}
+
+fn tess_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Ocr(format!("Tesseract error: {}", e))
+}
+
+/// Initialize an engine: load the traineddata and apply the settings
+fn create(key: &EngineKey) -> AppResult<Tesseract> {
+    let tessdata_dir = crate::ocr::tessdata_dir(Some(&key.lang))?;
+    let datapath = tessdata_dir.exists().then(|| tessdata_dir.to_string_lossy().into_owned());
+    let mut engine = match &key.oem {
+        Some(oem) => Tesseract::new_with_oem(datapath.as_deref(), Some(&key.lang), engine_mode(oem)?),
+        None => Tesseract::new(datapath.as_deref(), Some(&key.lang)),
+    }
+    .map_err(tess_error)?;
+
+    engine = engine.set_variable("tessedit_pageseg_mode", &key.psm).map_err(tess_error)?;
+    for (name, value) in &key.variables {
+        engine = engine.set_variable(name, value).map_err(tess_error)?;
+    }
+    Ok(engine)
+}
+
+/// A warm engine for `key`, or a new one
+fn checkout(key: &EngineKey) -> AppResult<Tesseract> {
+    let idle = {
+        let mut idle = IDLE.lock()?;
+        idle.iter()
+            .rposition(|(k, _)| k == key)
+            .map(|index| idle.remove(index).1)
+    };
+    match idle {
+        Some(engine) => Ok(engine),
+        None => create(key),
+    }
+}
+
+fn checkin(key: EngineKey, engine: Tesseract) {
+    if let Ok(mut idle) = IDLE.lock() {
+        idle.push((key, engine));
+        if idle.len() > MAX_IDLE {
+            idle.remove(0);
+        }
+    }
+    report_memory();
+}
+
+/// Size of the traineddata an engine holds
+fn engine_bytes(key: &EngineKey) -> u64 {
+    let Ok(tessdata_dir) = crate::ocr::tessdata_dir(Some(&key.lang)) else {
+        return 0;
+    };
+    key.lang
+        .split('+')
+        .filter_map(|lang| std::fs::metadata(tessdata_dir.join(format!("{}.traineddata", lang))).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Update the idle engines' share of the memory budget
+fn report_memory() {
+    let bytes: u64 = match IDLE.lock() {
+        Ok(idle) => idle.iter().map(|(key, _)| engine_bytes(key)).sum(),
+        Err(_) => return,
+    };
+    if bytes == 0 {
+        crate::memory::release(MEMORY_KEY);
+    } else {
+        crate::memory::reserve(MEMORY_KEY, "tesseract", bytes, |_| {
+            clear();
+            true
+        });
+    }
+}
+
+fn run(image_bytes: &[u8], request: Request) -> AppResult<String> {
+    let engine = checkout(&request.key)?;
+    // An engine that fails mid-run is dropped rather than returned
+    let mut engine = engine
+        .set_image_from_mem(image_bytes)
+        .map_err(tess_error)?
+        .recognize()
+        .map_err(tess_error)?;
+    let output = if request.tsv {
+        engine
+            .get_tsv_text(1)
+            .map(|tsv| format!("{}\n{}", TSV_HEADER, tsv))
+            .map_err(tess_error)?
+    } else {
+        engine.get_text().map_err(tess_error)?
+    };
+    checkin(request.key, engine);
+    Ok(output)
+}
+
+/// Run tesseract in-process with the CLI arguments `args`, returning what the
+/// CLI would print. None if the pool can't handle the arguments.
+pub fn recognize(image_bytes: &[u8], args: &[String]) -> Option<AppResult<String>> {
+    parse_args(args).map(|request| run(image_bytes, request))
+}
+
+/// Drop all idle engines
+pub fn clear() {
+    if let Ok(mut idle) = IDLE.lock() {
+        idle.clear();
+    }
+    crate::memory::release(MEMORY_KEY);
+}
+
+/// Load an engine for `args` in the background so the first recognition
+/// doesn't pay for initialization
+pub fn warm(args: Vec<String>) {
+    std::thread::spawn(move || {
+        let Some(request) = parse_args(&args) else {
+            return;
+        };
+        if let Ok(idle) = IDLE.lock() {
+            if idle.iter().any(|(k, _)| *k == request.key) {
+                return;
+            }
+        }
+        match create(&request.key) {
+            Ok(engine) => checkin(request.key, engine),
+            Err(e) => tracing::warn!("Failed to warm up Tesseract: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let request = parse_args(&args(&[
+            "-l", "eng+jpn", "--psm", "6", "--oem", "1", "-c", "tessedit_char_whitelist=0123=", "tsv",
+        ]))
+        .unwrap();
+        assert!(request.tsv);
+        assert_eq!(request.key.lang, "eng+jpn");
+        assert_eq!(request.key.psm, "6");
+        assert_eq!(request.key.oem.as_deref(), Some("1"));
+        assert_eq!(
+            request.key.variables,
+            vec![("tessedit_char_whitelist".to_string(), "0123=".to_string())]
+        );
+
+        assert!(!parse_args(&args(&["-l", "eng", "--psm", "6"])).unwrap().tsv);
+    }
+
+    #[test]
+    fn test_parse_args_falls_back_to_cli() {
+        assert_eq!(parse_args(&args(&["--psm", "0"])), None);
+        assert_eq!(parse_args(&args(&["-l", "eng", "hocr"])), None);
+        assert_eq!(parse_args(&args(&["-l"])), None);
+        assert_eq!(parse_args(&args(&["-c", "novalue"])), None);
+    }
+}
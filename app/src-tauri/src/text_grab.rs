@@ -0,0 +1,357 @@
+// Accessibility text grab
+// Reads the real text under a screen point through the platform accessibility
+// API (UI Automation on Windows, AXUIElement on macOS, AT-SPI on Linux). Exact
+// and much faster than OCR when the app exposes its text; otherwise falls back
+// to OCR of the area around the point.
+//
+// AT-SPI has no desktop-wide hit test: the showing top-level windows are asked
+// whether they contain the point (the active one first), then hit-tested down
+// to the deepest child. Wayland gives AT-SPI no global coordinates, so it
+// always uses the OCR fallback.
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Pipeline};
+use crate::ocr::{self, OcrOptions};
+use crate::ocr_result::{OcrLine, OcrResult};
+
+/// Area captured around the point for the OCR fallback (physical pixels)
+const FALLBACK_WIDTH: u32 = 800;
+const FALLBACK_HEIGHT: u32 = 160;
+
+/// Text found under a point
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GrabbedText {
+    pub text: String,
+    /// "accessibility" or "ocr"
+    pub source: String,
+}
+
+#[cfg(windows)]
+fn accessible_text_at(x: i32, y: i32) -> AppResult<Option<String>> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED};
+    use windows::Win32::UI::Accessibility::{
+        CUIAutomation, IUIAutomation, IUIAutomationTextPattern, IUIAutomationValuePattern, TextUnit_Line,
+        UIA_TextPatternId, UIA_ValuePatternId,
+    };
+
+    let non_empty = |text: String| (!text.trim().is_empty()).then_some(text);
+
+    unsafe {
+        // Already-initialized COM (S_FALSE / RPC_E_CHANGED_MODE) is fine
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| AppError::Unsupported(format!("UI Automation unavailable: {}", e)))?;
+
+        let point = POINT { x, y };
+        let Ok(element) = automation.ElementFromPoint(point) else {
+            return Ok(None);
+        };
+
+        // Documents and text boxes: the line under the point
+        if let Ok(pattern) = element.GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId) {
+            if let Ok(range) = pattern.RangeFromPoint(point) {
+                if range.ExpandToEnclosingUnit(TextUnit_Line).is_ok() {
+                    if let Some(text) = range.GetText(-1).ok().and_then(|t| non_empty(t.to_string())) {
+                        return Ok(Some(text));
+                    }
+                }
+            }
+        }
+        if let Ok(pattern) = element.GetCurrentPatternAs::<IUIAutomationValuePattern>(UIA_ValuePatternId) {
+            if let Some(text) = pattern.CurrentValue().ok().and_then(|t| non_empty(t.to_string())) {
+                return Ok(Some(text));
+            }
+        }
+        // Labels, buttons and menu items expose their text as the name
+        Ok(element.CurrentName().ok().and_then(|t| non_empty(t.to_string())))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod ax {
+    use std::ffi::{c_char, c_void, CStr, CString};
+
+    pub type CFTypeRef = *const c_void;
+    const K_AX_ERROR_SUCCESS: i32 = 0;
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+        fn AXUIElementCreateSystemWide() -> CFTypeRef;
+        fn AXUIElementCopyElementAtPosition(application: CFTypeRef, x: f32, y: f32, element: *mut CFTypeRef) -> i32;
+        fn AXUIElementCopyAttributeValue(element: CFTypeRef, attribute: CFTypeRef, value: *mut CFTypeRef) -> i32;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: CFTypeRef);
+        fn CFGetTypeID(cf: CFTypeRef) -> usize;
+        fn CFStringGetTypeID() -> usize;
+        fn CFStringCreateWithCString(alloc: CFTypeRef, c_str: *const c_char, encoding: u32) -> CFTypeRef;
+        fn CFStringGetLength(string: CFTypeRef) -> isize;
+        fn CFStringGetMaximumSizeForEncoding(length: isize, encoding: u32) -> isize;
+        fn CFStringGetCString(string: CFTypeRef, buffer: *mut c_char, size: isize, encoding: u32) -> bool;
+    }
+
+    /// Owned CoreFoundation object, released on drop
+    struct Owned(CFTypeRef);
+
+    impl Drop for Owned {
+        fn drop(&mut self) {
+            if !self.0.is_null() {
+                unsafe { CFRelease(self.0) };
+            }
+        }
+    }
+
+    unsafe fn to_string(value: CFTypeRef) -> Option<String> {
+        if CFGetTypeID(value) != CFStringGetTypeID() {
+            return None;
+        }
+        let size = CFStringGetMaximumSizeForEncoding(CFStringGetLength(value), K_CF_STRING_ENCODING_UTF8) + 1;
+        let mut buffer = vec![0 as c_char; size as usize];
+        if !CFStringGetCString(value, buffer.as_mut_ptr(), size, K_CF_STRING_ENCODING_UTF8) {
+            return None;
+        }
+        Some(CStr::from_ptr(buffer.as_ptr()).to_string_lossy().into_owned())
+    }
+
+    /// Text of the element at (x, y) in global display points
+    pub fn text_at(x: f32, y: f32) -> Option<String> {
+        unsafe {
+            // Without the Accessibility permission every query fails
+            if !AXIsProcessTrusted() {
+                return None;
+            }
+            let system = Owned(AXUIElementCreateSystemWide());
+            let mut element: CFTypeRef = std::ptr::null();
+            if AXUIElementCopyElementAtPosition(system.0, x, y, &mut element) != K_AX_ERROR_SUCCESS {
+                return None;
+            }
+            let element = Owned(element);
+
+            for attribute in ["AXSelectedText", "AXValue", "AXTitle", "AXDescription"] {
+                let name = CString::new(attribute).ok()?;
+                let name = Owned(CFStringCreateWithCString(std::ptr::null(), name.as_ptr(), K_CF_STRING_ENCODING_UTF8));
+                let mut value: CFTypeRef = std::ptr::null();
+                if AXUIElementCopyAttributeValue(element.0, name.0, &mut value) != K_AX_ERROR_SUCCESS {
+                    continue;
+                }
+                let value = Owned(value);
+                if let Some(text) = to_string(value.0).filter(|t| !t.trim().is_empty()) {
+                    return Some(text);
+                }
+            }
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn accessible_text_at(x: i32, y: i32) -> AppResult<Option<String>> {
+    // AX works in points; the coordinates are physical pixels
+    let scale = crate::measure::primary_scale_factor().unwrap_or(1.0);
+    Ok(ax::text_at((x as f64 / scale) as f32, (y as f64 / scale) as f32))
+}
+
+#[cfg(target_os = "linux")]
+mod atspi_text {
+    use atspi::proxy::accessible::{AccessibleProxy, ObjectRefExt};
+    use atspi::proxy::proxy_ext::ProxyExt;
+    use atspi::zbus;
+    use atspi::{AccessibilityConnection, CoordType, Granularity, State};
+
+    use crate::error::{AppError, AppResult};
+
+    /// Nesting depth after which the hit test gives up
+    const MAX_DEPTH: usize = 32;
+    /// Path of the null object returned when no child is at the point
+    const NULL_PATH: &str = "/org/a11y/atspi/null";
+
+    fn atspi_error(e: impl std::fmt::Display) -> AppError {
+        AppError::Other(format!("AT-SPI request failed: {}", e))
+    }
+
+    fn non_empty(text: String) -> Option<String> {
+        (!text.trim().is_empty()).then_some(text)
+    }
+
+    /// The deepest accessible under (x, y) in `window`, None when the window
+    /// doesn't contain the point
+    async fn hit_test<'a>(
+        conn: &'a zbus::Connection,
+        window: AccessibleProxy<'a>,
+        x: i32,
+        y: i32,
+    ) -> Option<AccessibleProxy<'a>> {
+        let mut proxies = window.proxies().await.ok()?;
+        if !proxies.component().ok()?.contains(x, y, CoordType::Screen).await.ok()? {
+            return None;
+        }
+        let mut current = window;
+        for _ in 0..MAX_DEPTH {
+            let mut proxies = current.proxies().await.ok()?;
+            let Ok(component) = proxies.component() else {
+                break;
+            };
+            let child = match component.get_accessible_at_point(x, y, CoordType::Screen).await {
+                Ok(child) if ![NULL_PATH, current.inner().path().as_str()].contains(&child.path.as_str()) => child,
+                _ => break,
+            };
+            current = child.into_accessible_proxy(conn).await.ok()?;
+        }
+        Some(current)
+    }
+
+    /// The line of text under the point, or the accessible's name (labels,
+    /// buttons and menu items) or description
+    async fn text_of(accessible: &AccessibleProxy<'_>, x: i32, y: i32) -> Option<String> {
+        if let Ok(mut proxies) = accessible.proxies().await {
+            if let Ok(text) = proxies.text() {
+                if let Ok(offset) = text.get_offset_at_point(x, y, CoordType::Screen).await {
+                    if offset >= 0 {
+                        if let Ok((line, _, _)) = text.get_string_at_offset(offset, Granularity::Line).await {
+                            if let Some(line) = non_empty(line) {
+                                return Some(line);
+                            }
+                        }
+                    }
+                }
+                if let Some(content) = text.get_text(0, -1).await.ok().and_then(non_empty) {
+                    return Some(content);
+                }
+            }
+        }
+        if let Some(name) = accessible.name().await.ok().and_then(non_empty) {
+            return Some(name);
+        }
+        accessible.description().await.ok().and_then(non_empty)
+    }
+
+    /// Text under (x, y) in screen coordinates. Apps that don't answer are
+    /// skipped.
+    pub async fn text_at(x: i32, y: i32) -> AppResult<Option<String>> {
+        let a11y = AccessibilityConnection::new().await.map_err(atspi_error)?;
+        let conn = a11y.connection();
+        let desktop = AccessibleProxy::builder(conn)
+            .destination("org.a11y.atspi.Registry")
+            .map_err(atspi_error)?
+            .path("/org/a11y/atspi/accessible/root")
+            .map_err(atspi_error)?
+            .cache_properties(zbus::proxy::CacheProperties::No)
+            .build()
+            .await
+            .map_err(atspi_error)?;
+
+        let mut windows = Vec::new();
+        for app in desktop.get_children().await.map_err(atspi_error)? {
+            let Ok(app) = app.into_accessible_proxy(conn).await else {
+                continue;
+            };
+            for window in app.get_children().await.unwrap_or_default() {
+                let Ok(window) = window.into_accessible_proxy(conn).await else {
+                    continue;
+                };
+                let Ok(state) = window.get_state().await else {
+                    continue;
+                };
+                if state.contains(State::Showing) {
+                    windows.push((state.contains(State::Active), window));
+                }
+            }
+        }
+        // Overlapping windows: the active one is most likely on top
+        windows.sort_by_key(|(active, _)| !*active);
+
+        for (_, window) in windows {
+            if let Some(accessible) = hit_test(conn, window, x, y).await {
+                return Ok(text_of(&accessible, x, y).await);
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn accessible_text_at(x: i32, y: i32) -> AppResult<Option<String>> {
+    if crate::permissions::is_wayland_session() {
+        return Ok(None);
+    }
+    // Called from sync code that may itself be on the async runtime
+    std::thread::spawn(move || tauri::async_runtime::block_on(atspi_text::text_at(x, y)))
+        .join()
+        .map_err(|_| AppError::Other("AT-SPI request panicked".to_string()))?
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn accessible_text_at(_x: i32, _y: i32) -> AppResult<Option<String>> {
+    Ok(None)
+}
+
+/// The OCR line containing (x, y), or the nearest one
+pub(crate) fn line_at(result: &OcrResult, x: u32, y: u32) -> Option<&OcrLine> {
+    let distance = |line: &OcrLine| {
+        let b = &line.bbox;
+        let dx = if x < b.x { b.x - x } else { x.saturating_sub(b.x + b.width) };
+        let dy = if y < b.y { b.y - y } else { y.saturating_sub(b.y + b.height) };
+        dx as u64 * dx as u64 + dy as u64 * dy as u64
+    };
+    result.lines.iter().min_by_key(|line| distance(line))
+}
+
+/// OCR the area around the point and return the line under it
+fn ocr_text_at(x: u32, y: u32, langs: Option<String>) -> AppResult<Option<String>> {
+    crate::permissions::ensure_capture_permission()?;
+    let left = x.saturating_sub(FALLBACK_WIDTH / 2);
+    let top = y.saturating_sub(FALLBACK_HEIGHT / 2);
+    let image = XcapBackend::new(0).capture_region(left, top, FALLBACK_WIDTH, FALLBACK_HEIGHT)?;
+
+    let mut bytes = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    let result = ocr::run_ocr_detailed(&bytes, &OcrOptions { langs, ..Default::default() })?;
+    Ok(line_at(&result, x - left, y - top).map(|line| line.text.clone()))
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Text under a screen point (physical pixels, primary monitor): from the
+/// accessibility API when available, otherwise by OCR
+#[tauri::command(async)]
+pub fn grab_text_at_point(x: u32, y: u32, langs: Option<String>) -> AppResult<GrabbedText> {
+    match accessible_text_at(x as i32, y as i32) {
+        Ok(Some(text)) => {
+            return Ok(GrabbedText { text, source: "accessibility".to_string() });
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Accessibility text grab failed: {}, falling back to OCR", e),
+    }
+
+    let text = events::track(Pipeline::Ocr, "grab_text_at_point", None, || {
+        ocr_text_at(x, y, langs)?.ok_or_else(|| AppError::Ocr("No text found at this point".to_string()))
+    })?;
+    Ok(GrabbedText { text, source: "ocr".to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr_result::{BoundingBox, OcrWord};
+
+    #[test]
+    fn test_line_at() {
+        let word = |text: &str, y: u32, line: u32| {
+            OcrWord::new(text.to_string(), 90.0, BoundingBox { x: 10, y, width: 100, height: 20 }, 1, 1, line)
+        };
+        let result = OcrResult::from_words(vec![word("first", 0, 1), word("second", 40, 2)], "tesseract");
+        assert_eq!(line_at(&result, 50, 45).unwrap().text, "second");
+        assert_eq!(line_at(&result, 50, 25).unwrap().text, "first");
+        assert_eq!(line_at(&result, 500, 100).unwrap().text, "second");
+    }
+}
@@ -0,0 +1,177 @@
+// Translation worker
+// Offline translation requests go over a channel to a worker task that loads
+// models one at a time on the blocking pool. Each inference then runs as its
+// own blocking task with a shared handle to its model, so no lock is held
+// while translating and requests run side by side. Loaded models stay
+// resident within the memory budget. Every request gets its own future and
+// can be cancelled by operation id.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use tauri::async_runtime::{self, Receiver, Sender};
+
+use crate::alignment::AlignedTranslation;
+use crate::decoding::DecodingParams;
+use crate::error::{AppError, AppResult};
+use crate::operations::CancelToken;
+use crate::translator::{self, TranslatorService};
+
+/// Requests waiting for the worker before senders wait too
+const QUEUE_SIZE: usize = 64;
+
+/// Memory budget keys are this prefix and the model name
+const MEMORY_KEY_PREFIX: &str = "translation:";
+
+static WORKER: Lazy<Sender<Request>> = Lazy::new(|| {
+    let (sender, receiver) = async_runtime::channel(QUEUE_SIZE);
+    async_runtime::spawn(run(receiver));
+    sender
+});
+
+/// Loaded models by name. Requests hold their own handle, so evicting a
+/// model frees it once the translations using it finish.
+static LOADED: Lazy<Mutex<HashMap<String, Arc<TranslatorService>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+struct Request {
+    text: String,
+    source_lang: String,
+    target_lang: String,
+    params: DecodingParams,
+    align: bool,
+    cancel: CancelToken,
+    reply: Sender<AppResult<AlignedTranslation>>,
+}
+
+fn worker_stopped() -> AppError {
+    AppError::Translation("Translation worker stopped".to_string())
+}
+
+/// Model naming: opus-mt-{src}-{tgt}
+fn model_name(source_lang: &str, target_lang: &str) -> String {
+    format!("opus-mt-{}-{}", source_lang, target_lang)
+}
+
+fn memory_key(name: &str) -> String {
+    format!("{}{}", MEMORY_KEY_PREFIX, name)
+}
+
+/// Memory budget eviction
+fn evict(key: &str) -> bool {
+    let Some(name) = key.strip_prefix(MEMORY_KEY_PREFIX) else {
+        return false;
+    };
+    match LOADED.lock() {
+        Ok(mut loaded) => {
+            loaded.remove(name);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn cached(name: &str) -> Option<Arc<TranslatorService>> {
+    let service = LOADED.lock().ok()?.get(name).cloned();
+    if service.is_some() {
+        crate::memory::touch(&memory_key(name));
+    }
+    service
+}
+
+/// Load a model, making room for it in the memory budget first
+fn load(name: &str) -> AppResult<Arc<TranslatorService>> {
+    let key = memory_key(name);
+    let bytes = translator::calculate_dir_size(&translator::get_models_dir()?.join(name)).unwrap_or(0);
+    crate::memory::reserve(&key, "translation", bytes, evict);
+    match translator::load_service(name) {
+        Ok(service) => {
+            let service = Arc::new(service);
+            LOADED.lock()?.insert(name.to_string(), service.clone());
+            Ok(service)
+        }
+        Err(e) => {
+            crate::memory::release(&key);
+            Err(e)
+        }
+    }
+}
+
+async fn run(mut receiver: Receiver<Request>) {
+    while let Some(request) = receiver.recv().await {
+        if let Err(e) = request.cancel.check() {
+            let _ = request.reply.send(Err(e)).await;
+            continue;
+        }
+
+        let name = model_name(&request.source_lang, &request.target_lang);
+        let service = match cached(&name) {
+            Some(service) => service,
+            None => {
+                let service = async_runtime::spawn_blocking(move || load(&name))
+                    .await
+                    .map_err(|e| AppError::Translation(format!("Model loading failed: {}", e)))
+                    .and_then(|result| result);
+                match service {
+                    Ok(service) => service,
+                    Err(e) => {
+                        let _ = request.reply.send(Err(e)).await;
+                        continue;
+                    }
+                }
+            }
+        };
+
+        async_runtime::spawn_blocking(move || {
+            let result = request.cancel.scope(|| {
+                translator::translate_text(
+                    &service,
+                    &request.text,
+                    &request.source_lang,
+                    &request.target_lang,
+                    &request.params,
+                    request.align,
+                )
+            });
+            let _ = request.reply.blocking_send(result);
+        });
+    }
+}
+
+/// Translate on the worker. With an `op_id`, the request can be cancelled
+/// through `cancel_operation` until it completes: queued requests fail right
+/// away, running ones at the next decoding step.
+pub async fn translate(
+    text: String,
+    source_lang: String,
+    target_lang: String,
+    params: DecodingParams,
+    op_id: Option<String>,
+    align: bool,
+) -> AppResult<AlignedTranslation> {
+    let operation = crate::operations::start(op_id)?;
+    let (reply, mut response) = async_runtime::channel(1);
+    let request = Request {
+        text,
+        source_lang,
+        target_lang,
+        params,
+        align,
+        cancel: operation.token(),
+        reply,
+    };
+    match WORKER.send(request).await {
+        Ok(()) => response.recv().await.unwrap_or_else(|| Err(worker_stopped())),
+        Err(_) => Err(worker_stopped()),
+    }
+}
+
+/// Drop all loaded models
+pub fn unload() {
+    if let Ok(mut loaded) = LOADED.lock() {
+        for name in loaded.keys() {
+            crate::memory::release(&memory_key(name));
+        }
+        loaded.clear();
+    }
+}
@@ -3,14 +3,21 @@
 //! Provides privacy-preserving local neural machine translation
 //! using MarianMT OPUS models via the tract-onnx crate.
 
-use std::path::PathBuf;
-use std::sync::Mutex;
-use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tract_onnx::prelude::*;
 use tokenizers::Tokenizer;
 
-/// Thread-safe singleton for the translator instance
-static TRANSLATOR_INSTANCE: Lazy<Mutex<Option<TranslatorService>>> = Lazy::new(|| Mutex::new(None));
+use crate::alignment::{self, AlignedTranslation};
+use crate::decoding::{self, DecodingParams};
+use crate::error::{AppError, AppResult};
+use crate::events::{self, Pipeline};
+use crate::operations::CancelToken;
+
+/// Environment variable overriding the models directory
+pub const MODELS_DIR_ENV: &str = "SCREEN_INU_MODELS_DIR";
+
+const MODELS_SUBDIR: &str = "translation_models";
 
 /// Available translation model information
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -23,162 +30,297 @@ pub struct TranslationModelInfo {
     pub download_url: Option<String>,
 }
 
-/// Manages ONNX model lifecycle
+pub(crate) type RunnableModel = SimplePlan<TypedFact, Box<dyn TypedOp>, Graph<TypedFact, Box<dyn TypedOp>>>;
+
+/// Loaded model graphs
+enum TranslationModel {
+    /// Single graph that generates output ids (decoding fixed at export time)
+    Generate(RunnableModel),
+    /// Separate encoder and decoder, decoded here with beam search
+    Seq2Seq {
+        encoder: RunnableModel,
+        decoder: RunnableModel,
+    },
+}
+
+/// Translation settings
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TranslationConfig {
+    /// Default decoding settings, overridable per request
+    pub decoding: DecodingParams,
+    /// Where models are stored, instead of the app data directory.
+    /// `SCREEN_INU_MODELS_DIR` takes precedence.
+    pub models_dir: Option<String>,
+}
+
+/// A loaded translation model, shared by the requests that use it
 pub struct TranslatorService {
-    model: SimplePlan<TypedFact, Box<dyn TypedOp>, Graph<TypedFact, Box<dyn TypedOp>>>,
+    model: TranslationModel,
     tokenizer: Tokenizer,
-    current_model: String,
+}
+
+pub(crate) fn load_graph(path: &Path) -> AppResult<RunnableModel> {
+    tract_onnx::onnx()
+        .model_for_path(path)
+        .map_err(|e| AppError::Translation(format!("Failed to load ONNX model: {}", e)))?
+        .into_optimized()
+        .map_err(|e| AppError::Translation(format!("Failed to optimize model: {}", e)))?
+        .into_runnable()
+        .map_err(|e| AppError::Translation(format!("Failed to create runnable model: {}", e)))
+}
+
+/// Run a graph with inputs given by name (input order differs between exports)
+fn run_named(plan: &RunnableModel, mut inputs: HashMap<&str, TValue>) -> AppResult<TVec<TValue>> {
+    let model = plan.model();
+    let outlets = model
+        .input_outlets()
+        .map_err(|e| AppError::Translation(format!("Invalid model inputs: {}", e)))?;
+    let ordered = outlets
+        .iter()
+        .map(|outlet| {
+            let name = &model.node(outlet.node).name;
+            inputs
+                .remove(name.as_str())
+                .ok_or_else(|| AppError::Translation(format!("Unsupported model input '{}'", name)))
+        })
+        .collect::<AppResult<TVec<TValue>>>()?;
+    plan.run(ordered)
+        .map_err(|e| AppError::Translation(format!("Inference failed: {}", e)))
+}
+
+fn ids_tensor(ids: &[i64]) -> AppResult<TValue> {
+    let tensor: Tensor = tract_ndarray::Array2::from_shape_vec((1, ids.len()), ids.to_vec())
+        .map_err(|e| AppError::Translation(format!("Failed to create input tensor: {}", e)))?
+        .into();
+    Ok(tensor.into())
+}
+
+/// Whether a directory holds a usable model
+fn has_model_files(model_dir: &Path) -> bool {
+    model_dir.join("model.onnx").exists()
+        || (model_dir.join("encoder_model.onnx").exists() && model_dir.join("decoder_model.onnx").exists())
 }
 
 impl TranslatorService {
     /// Create a new translator service with the specified model
-    pub fn new(model_path: &str) -> Result<Self, String> {
+    pub fn new(model_path: &str) -> AppResult<Self> {
         let model_dir = PathBuf::from(model_path);
         
-        // Load ONNX model
-        let model_file = model_dir.join("model.onnx");
-        let model = tract_onnx::onnx()
-            .model_for_path(&model_file)
-            .map_err(|e| format!("Failed to load ONNX model: {}", e))?
-            .into_optimized()
-            .map_err(|e| format!("Failed to optimize model: {}", e))?
-            .into_runnable()
-            .map_err(|e| format!("Failed to create runnable model: {}", e))?;
+        // Prefer the encoder/decoder pair, which supports decoding settings
+        let encoder_file = model_dir.join("encoder_model.onnx");
+        let decoder_file = model_dir.join("decoder_model.onnx");
+        let model = if encoder_file.exists() && decoder_file.exists() {
+            TranslationModel::Seq2Seq {
+                encoder: load_graph(&encoder_file)?,
+                decoder: load_graph(&decoder_file)?,
+            }
+        } else {
+            TranslationModel::Generate(load_graph(&model_dir.join("model.onnx"))?)
+        };
         
         // Load tokenizer
         let tokenizer_path = model_dir.join("tokenizer.json");
         let tokenizer = Tokenizer::from_file(&tokenizer_path)
-            .map_err(|e| format!("Failed to load tokenizer: {}", e))?;
+            .map_err(|e| AppError::Translation(format!("Failed to load tokenizer: {}", e)))?;
         
         Ok(Self {
             model,
             tokenizer,
-            current_model: model_path.to_string(),
         })
     }
     
+    fn token_id(&self, token: &str) -> AppResult<u32> {
+        self.tokenizer
+            .token_to_id(token)
+            .ok_or_else(|| AppError::Translation(format!("Tokenizer has no {} token", token)))
+    }
+    
     /// Translate text
-    pub fn translate(&self, text: &str) -> Result<String, String> {
+    pub fn translate(&self, text: &str, params: &DecodingParams) -> AppResult<String> {
         if text.trim().is_empty() {
             return Ok(String::new());
         }
+        let started = std::time::Instant::now();
         
         // Tokenize input
         let encoding = self.tokenizer.encode(text, true)
-            .map_err(|e| format!("Tokenization failed: {}", e))?;
+            .map_err(|e| AppError::Translation(format!("Tokenization failed: {}", e)))?;
         
         let input_ids: Vec<i64> = encoding.get_ids().iter().map(|&id| id as i64).collect();
         let attention_mask: Vec<i64> = encoding.get_attention_mask().iter().map(|&m| m as i64).collect();
         
-        // Prepare tensors
-        let seq_len = input_ids.len();
-        let input_tensor: Tensor = tract_ndarray::Array2::from_shape_vec(
-            (1, seq_len),
-            input_ids,
-        ).map_err(|e| format!("Failed to create input tensor: {}", e))?.into();
-        
-        let attention_tensor: Tensor = tract_ndarray::Array2::from_shape_vec(
-            (1, seq_len),
-            attention_mask,
-        ).map_err(|e| format!("Failed to create attention tensor: {}", e))?.into();
-        
-        // Run inference
-        let outputs = self.model.run(tvec!(input_tensor.into(), attention_tensor.into()))
-            .map_err(|e| format!("Inference failed: {}", e))?;
-        
-        // Extract output tokens
-        let output = outputs[0].to_array_view::<i64>()
-            .map_err(|e| format!("Failed to extract output: {}", e))?;
-        
-        let output_ids: Vec<u32> = output.iter().map(|&id| id as u32).collect();
+        let output_ids = match &self.model {
+            TranslationModel::Generate(model) => {
+                let outputs = model.run(tvec!(ids_tensor(&input_ids)?, ids_tensor(&attention_mask)?))
+                    .map_err(|e| AppError::Translation(format!("Inference failed: {}", e)))?;
+                let output = outputs[0].to_array_view::<i64>()
+                    .map_err(|e| AppError::Translation(format!("Failed to extract output: {}", e)))?;
+                output.iter().take(params.max_length).map(|&id| id as u32).collect()
+            }
+            TranslationModel::Seq2Seq { encoder, decoder } => {
+                self.decode(encoder, decoder, &input_ids, &attention_mask, params)?
+            }
+        };
+        crate::perf::record_units(crate::perf::TRANSLATION, started, output_ids.len() as u64);
         
         // Decode tokens back to text
         let decoded = self.tokenizer.decode(&output_ids, true)
-            .map_err(|e| format!("Decoding failed: {}", e))?;
+            .map_err(|e| AppError::Translation(format!("Decoding failed: {}", e)))?;
         
         Ok(decoded)
     }
-}
-
-/// Get the models directory path
-pub fn get_models_dir() -> Result<PathBuf, String> {
-    #[cfg(target_os = "windows")]
-    {
-        let app_data = std::env::var("APPDATA")
-            .map_err(|_| "APPDATA not found")?;
-        Ok(PathBuf::from(app_data).join("com.iml1s.screeninu").join("translation_models"))
-    }
-    
-    #[cfg(target_os = "macos")]
-    {
-        let home = std::env::var("HOME").map_err(|_| "HOME not found")?;
-        Ok(PathBuf::from(home)
-            .join("Library")
-            .join("Application Support")
-            .join("com.iml1s.screeninu")
-            .join("translation_models"))
-    }
     
-    #[cfg(target_os = "linux")]
-    {
-        let home = std::env::var("HOME").map_err(|_| "HOME not found")?;
-        Ok(PathBuf::from(home)
-            .join(".local")
-            .join("share")
-            .join("com.iml1s.screeninu")
-            .join("translation_models"))
+    /// Marian starts decoding from the pad token
+    fn decode(
+        &self,
+        encoder: &RunnableModel,
+        decoder: &RunnableModel,
+        input_ids: &[i64],
+        attention_mask: &[i64],
+        params: &DecodingParams,
+    ) -> AppResult<Vec<u32>> {
+        let pad = self.token_id("<pad>")?;
+        let eos = self.token_id("</s>")?;
+        generate(encoder, decoder, input_ids, attention_mask, pad, eos, params)
     }
 }
 
-/// Initialize or get the translator service
-fn get_or_init_translator(model_name: &str) -> Result<(), String> {
-    let mut guard = TRANSLATOR_INSTANCE.lock().map_err(|e| e.to_string())?;
-    
-    // Check if we need to reload
-    if let Some(ref service) = *guard {
-        if service.current_model.contains(model_name) {
-            return Ok(());
+/// Encode once, then beam search with the decoder. The start token is never
+/// generated. Shared by translation and summarization models.
+pub(crate) fn generate(
+    encoder: &RunnableModel,
+    decoder: &RunnableModel,
+    input_ids: &[i64],
+    attention_mask: &[i64],
+    start: u32,
+    eos: u32,
+    params: &DecodingParams,
+) -> AppResult<Vec<u32>> {
+    let encoded = run_named(encoder, HashMap::from([
+        ("input_ids", ids_tensor(input_ids)?),
+        ("attention_mask", ids_tensor(attention_mask)?),
+    ]))?;
+    let hidden_states = encoded[0].clone();
+    let encoder_mask = ids_tensor(attention_mask)?;
+    
+    decoding::beam_search(params, start, eos, |prefix| {
+        let ids: Vec<i64> = prefix.iter().map(|&id| id as i64).collect();
+        let outputs = run_named(decoder, HashMap::from([
+            ("input_ids", ids_tensor(&ids)?),
+            ("encoder_attention_mask", encoder_mask.clone()),
+            ("encoder_hidden_states", hidden_states.clone()),
+        ]))?;
+        // logits: [1, prefix length, vocab]; keep the last position
+        let logits = outputs[0].to_array_view::<f32>()
+            .map_err(|e| AppError::Translation(format!("Failed to extract logits: {}", e)))?;
+        let mut last: Vec<f32> = logits
+            .index_axis(tract_ndarray::Axis(0), 0)
+            .index_axis(tract_ndarray::Axis(0), prefix.len() - 1)
+            .iter()
+            .copied()
+            .collect();
+        if let Some(logit) = last.get_mut(start as usize) {
+            *logit = f32::NEG_INFINITY;
         }
+        Ok(last)
+    })
+}
+
+/// Get the models directory path: the env or config override, otherwise
+/// `translation_models` in the app data directory
+pub fn get_models_dir() -> AppResult<PathBuf> {
+    let env_dir = std::env::var_os(MODELS_DIR_ENV).map(PathBuf::from);
+    let config_dir = crate::config::get()?.translation.models_dir.map(PathBuf::from);
+    if let Some(dir) = env_dir.into_iter().chain(config_dir).find(|d| !d.as_os_str().is_empty()) {
+        return Ok(dir);
     }
-    
+    Ok(crate::config::get_app_data_dir()?.join(MODELS_SUBDIR))
+}
+
+/// Load an installed model by name
+pub(crate) fn load_service(model_name: &str) -> AppResult<TranslatorService> {
     let models_dir = get_models_dir()?;
     let model_path = models_dir.join(model_name);
     
     if !model_path.exists() {
-        return Err(format!("Model '{}' not found. Please download it first.", model_name));
+        return Err(AppError::ModelMissing(model_name.to_string()));
     }
     
-    let service = TranslatorService::new(model_path.to_str().unwrap())?;
-    *guard = Some(service);
-    
-    Ok(())
+    TranslatorService::new(&model_path.to_string_lossy())
+}
+
+/// Translate with the glossary and plugins applied: in one pass, or sentence
+/// by sentence with `align` so each sentence maps to its translation. Stops
+/// if the operation running on this thread is cancelled.
+pub(crate) fn translate_text(
+    service: &TranslatorService,
+    text: &str,
+    source_lang: &str,
+    target_lang: &str,
+    params: &DecodingParams,
+    align: bool,
+) -> AppResult<AlignedTranslation> {
+    let terms = crate::glossary::terms_for(&crate::glossary::lang_pair(source_lang, target_lang))?;
+    let translate = |text: &str| {
+        crate::operations::check()?;
+        let protected = crate::glossary::protect(text, &terms);
+        let translated = crate::glossary::restore(&service.translate(&protected.text, params)?, &protected);
+        Ok(crate::plugins::apply(crate::plugins::PluginStage::Translation, &translated))
+    };
+    if align {
+        return alignment::align(text, translate);
+    }
+    Ok(AlignedTranslation {
+        text: translate(text)?.trim().to_string(),
+        segments: Vec::new(),
+    })
+}
+
+/// Drop the loaded model. Running translations keep their copy until done.
+pub fn unload() {
+    crate::translation_worker::unload();
 }
 
 // ========================================
 // Tauri Commands
 // ========================================
 
-/// Translate text using offline model
+/// Translate text using offline model. With `align`, the text is translated
+/// sentence by sentence and the sentence alignment is returned along with it
+/// (slower, and the model sees less context); otherwise `segments` is empty.
+///
+/// `options` overrides the configured decoding settings for this request.
+/// Requests run on the translation worker, several at a time; pass an
+/// `op_id` to be able to cancel one with `cancel_operation`.
 #[tauri::command]
-pub fn translate_offline(
+#[tracing::instrument(skip(text, options), err, fields(chars = text.len()))]
+pub async fn translate_offline(
     text: String,
     source_lang: String,
     target_lang: String,
-) -> Result<String, String> {
-    // Model naming: opus-mt-{src}-{tgt}
-    let model_name = format!("opus-mt-{}-{}", source_lang, target_lang);
-    
-    get_or_init_translator(&model_name)?;
-    
-    let guard = TRANSLATOR_INSTANCE.lock().map_err(|e| e.to_string())?;
-    let service = guard.as_ref().ok_or("Translator not initialized")?;
-    
-    service.translate(&text)
+    options: Option<DecodingParams>,
+    op_id: Option<String>,
+    align: Option<bool>,
+) -> AppResult<AlignedTranslation> {
+    let run = events::start(Pipeline::Translate, "translate_offline", op_id.clone());
+    let result = async {
+        let params = match options {
+            Some(options) => options,
+            None => crate::config::get()?.translation.decoding,
+        };
+        params.validate()?;
+
+        let align = align.unwrap_or(false);
+        crate::translation_worker::translate(text, source_lang, target_lang, params, op_id, align).await
+    }
+    .await;
+    run.finish(result)
 }
 
 /// List available translation models
 #[tauri::command]
-pub fn list_translation_models() -> Result<Vec<TranslationModelInfo>, String> {
+pub fn list_translation_models() -> AppResult<Vec<TranslationModelInfo>> {
     let models_dir = get_models_dir()?;
     
     // Available models (can be downloaded)
@@ -196,7 +338,7 @@ pub fn list_translation_models() -> Result<Vec<TranslationModelInfo>, String> {
     
     for (name, src, tgt, url) in available_models {
         let model_path = models_dir.join(name);
-        let installed = model_path.exists() && model_path.join("model.onnx").exists();
+        let installed = has_model_files(&model_path);
         let size = if installed {
             calculate_dir_size(&model_path).unwrap_or(0)
         } else {
@@ -218,11 +360,11 @@ pub fn list_translation_models() -> Result<Vec<TranslationModelInfo>, String> {
 
 /// Get status of a specific model
 #[tauri::command]
-pub fn get_translation_model_status(model_name: String) -> Result<TranslationModelInfo, String> {
+pub fn get_translation_model_status(model_name: String) -> AppResult<TranslationModelInfo> {
     let models_dir = get_models_dir()?;
     let model_path = models_dir.join(&model_name);
     
-    let installed = model_path.exists() && model_path.join("model.onnx").exists();
+    let installed = has_model_files(&model_path);
     let size = if installed {
         calculate_dir_size(&model_path).unwrap_or(0)
     } else {
@@ -249,86 +391,88 @@ pub fn get_translation_model_status(model_name: String) -> Result<TranslationMod
 
 /// Delete a translation model
 #[tauri::command]
-pub fn delete_translation_model(model_name: String) -> Result<(), String> {
+pub fn delete_translation_model(model_name: String) -> AppResult<()> {
     let models_dir = get_models_dir()?;
     let model_path = models_dir.join(&model_name);
     
     if model_path.exists() {
-        std::fs::remove_dir_all(&model_path)
-            .map_err(|e| format!("Failed to delete model: {}", e))?;
+        std::fs::remove_dir_all(&model_path)?;
+        events::emit(events::MODELS_CHANGED_EVENT, ());
     }
     
     Ok(())
 }
 
-/// Download a translation model
+/// Download a translation model. With an `op_id` it can be cancelled through
+/// `cancel_operation`; partial files are kept for resuming.
 #[tauri::command]
-pub async fn download_translation_model(model_name: String) -> Result<(), String> {
-    let models_dir = get_models_dir()?;
-    let model_path = models_dir.join(&model_name);
-    
-    if model_path.exists() {
-        return Ok(());
-    }
-    
-    std::fs::create_dir_all(&model_path)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
-    
-    let parts: Vec<&str> = model_name.split('-').collect();
-    if parts.len() < 4 {
-        return Err("Invalid model name".to_string());
-    }
-    let src = parts[2];
-    let tgt = parts[3];
-    
-    // Xenova models base URL
-    let base_url = format!("https://huggingface.co/Xenova/opus-mt-{}-{}/resolve/main", src, tgt);
-    
-    // Download tokenizer.json
-    download_file(&format!("{}/tokenizer.json", base_url), &model_path.join("tokenizer.json")).await?;
-    
-    // Download model.onnx (try standard first, then quantized)
-    let model_res = download_file(&format!("{}/onnx/model.onnx", base_url), &model_path.join("model.onnx")).await;
-    
-    if model_res.is_err() {
-        // Try quantized
-         download_file(&format!("{}/onnx/model_quantized.onnx", base_url), &model_path.join("model.onnx")).await?;
+pub async fn download_translation_model(model_name: String, op_id: Option<String>) -> AppResult<()> {
+    let run = events::start(Pipeline::Download, "download_translation_model", op_id.clone());
+    let result = async {
+        let operation = crate::operations::start(op_id)?;
+        let cancel = operation.token();
+        let models_dir = get_models_dir()?;
+        let model_path = models_dir.join(&model_name);
+
+        if model_path.exists() {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&model_path)?;
+
+        let parts: Vec<&str> = model_name.split('-').collect();
+        if parts.len() < 4 {
+            return Err(AppError::InvalidInput(format!("Invalid model name '{}'", model_name)));
+        }
+        let src = parts[2];
+        let tgt = parts[3];
+
+        // Xenova models base URL
+        let base_url = format!("https://huggingface.co/Xenova/opus-mt-{}-{}/resolve/main", src, tgt);
+
+        // Download tokenizer.json
+        download_file(&format!("{}/tokenizer.json", base_url), &model_path.join("tokenizer.json"), &cancel).await?;
+
+        // Encoder/decoder pair (supports decoding settings); quantized as fallback
+        let mut seq2seq = true;
+        for part in ["encoder_model", "decoder_model"] {
+            let target = model_path.join(format!("{}.onnx", part));
+            if download_file(&format!("{}/onnx/{}.onnx", base_url, part), &target, &cancel).await.is_err()
+                && download_file(&format!("{}/onnx/{}_quantized.onnx", base_url, part), &target, &cancel).await.is_err()
+            {
+                seq2seq = false;
+                break;
+            }
+        }
+        if seq2seq {
+            return Ok(());
+        }
+
+        // Download model.onnx (try standard first, then quantized)
+        let model_res = download_file(&format!("{}/onnx/model.onnx", base_url), &model_path.join("model.onnx"), &cancel).await;
+
+        if model_res.is_err() {
+            // Try quantized
+            download_file(&format!("{}/onnx/model_quantized.onnx", base_url), &model_path.join("model.onnx"), &cancel).await?;
+        }
+
+        Ok(())
     }
-    
-    Ok(())
+    .await;
+    run.finish(result)
 }
 
 // ========================================
 // Helper Functions
 // ========================================
 
-async fn download_file(url: &str, path: &PathBuf) -> Result<(), String> {
-    use std::io::Write;
-    
-    let response = reqwest::get(url)
-        .await
-        .map_err(|e| format!("Failed to request {}: {}", url, e))?;
-        
-    if !response.status().is_success() {
-        return Err(format!("Failed to download {}: Status {}", url, response.status()));
-    }
-    
-    let content = response.bytes()
-        .await
-        .map_err(|e| format!("Failed to get bytes {}: {}", url, e))?;
-        
-    let mut file = std::fs::File::create(path)
-        .map_err(|e| format!("Failed to create file {:?}: {}", path, e))?;
-        
-    file.write_all(&content)
-        .map_err(|e| format!("Failed to write file {:?}: {}", path, e))?;
-        
-    Ok(())
+pub(crate) async fn download_file(url: &str, path: &PathBuf, cancel: &CancelToken) -> AppResult<()> {
+    crate::downloads::fetch(url, path, cancel).await
 }
 
 
 /// Calculate total size of a directory
-fn calculate_dir_size(path: &PathBuf) -> Result<u64, std::io::Error> {
+pub fn calculate_dir_size(path: &PathBuf) -> Result<u64, std::io::Error> {
     let mut size = 0;
     
     if path.is_dir() {
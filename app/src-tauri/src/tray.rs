@@ -1,21 +1,225 @@
+// System tray
+// The icon follows the pipeline events: an amber badge while a capture, OCR,
+// translation or model download is running, a red one when the last of them
+// failed (until the next starts or the window is opened), with the tooltip
+// saying which. The menu has a "Pause live OCR" toggle, enabled while a live
+// OCR session runs, a hover OCR toggle, the language switcher
+// (`tray_languages`), rebuilt when models change, and the recent results
+// (`recent_results`), rebuilt when a result comes in.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    image::Image,
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager, Runtime,
+    AppHandle, Emitter, Listener, Manager, Runtime,
 };
 
-pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
-    // Menu Items
-    let capture_i = MenuItem::with_id(app, "capture", "📸 Capture (Ctrl+Shift+X)", true, None::<&str>)?;
-    let show_i = MenuItem::with_id(app, "show", "🐕 Show Window", true, None::<&str>)?;
+use crate::events::{Pipeline, Stage, MODELS_CHANGED_EVENT};
+use crate::recent_results::RECENT_RESULTS_CHANGED_EVENT;
+use crate::i18n::{t, t_args};
+
+const TRAY_ID: &str = "tray";
+
+/// Badge colors (RGBA) for the busy and error icons
+const BUSY_BADGE: [u8; 4] = [245, 158, 11, 255];
+const ERROR_BADGE: [u8; 4] = [220, 38, 38, 255];
+
+/// Badge radius as a fraction of the icon size
+const BADGE_RADIUS: f32 = 0.22;
+
+/// Error characters shown in the tooltip (Windows cuts tooltips at 128)
+const TOOLTIP_ERROR_CHARS: usize = 90;
+
+/// Pipeline stages currently running
+static BUSY: AtomicUsize = AtomicUsize::new(0);
+
+/// Error of the last failed stage, shown until the next one starts
+static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrayState {
+    Idle,
+    Busy,
+    Error,
+}
+
+fn state() -> TrayState {
+    if BUSY.load(Ordering::SeqCst) > 0 {
+        TrayState::Busy
+    } else if last_error().is_some() {
+        TrayState::Error
+    } else {
+        TrayState::Idle
+    }
+}
+
+fn last_error() -> Option<String> {
+    LAST_ERROR.lock().ok().and_then(|error| error.clone())
+}
+
+fn set_last_error(error: Option<String>) {
+    if let Ok(mut last) = LAST_ERROR.lock() {
+        *last = error;
+    }
+}
+
+/// Error message of a failed stage's payload; cancelling isn't an error
+fn failure(payload: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(payload).ok()?;
+    if event["errorCode"] == "cancelled" {
+        return None;
+    }
+    event["error"].as_str().map(str::to_string)
+}
+
+/// Fill a circle in the bottom-right corner of an RGBA image
+fn draw_badge(rgba: &mut [u8], width: u32, height: u32, color: [u8; 4]) {
+    let radius = width.min(height) as f32 * BADGE_RADIUS;
+    let (center_x, center_y) = (width as f32 - radius, height as f32 - radius);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - center_x, y as f32 + 0.5 - center_y);
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+fn icon<R: Runtime>(app: &AppHandle<R>, state: TrayState) -> Option<Image<'static>> {
+    let base = app.default_window_icon()?;
+    let color = match state {
+        TrayState::Idle => return Some(base.clone().to_owned()),
+        TrayState::Busy => BUSY_BADGE,
+        TrayState::Error => ERROR_BADGE,
+    };
+    let mut rgba = base.rgba().to_vec();
+    draw_badge(&mut rgba, base.width(), base.height(), color);
+    Some(Image::new_owned(rgba, base.width(), base.height()))
+}
+
+fn tooltip(state: TrayState) -> String {
+    match state {
+        TrayState::Idle => t("tray-tooltip"),
+        TrayState::Busy => t("tray-tooltip-busy"),
+        TrayState::Error => {
+            let error = crate::notifications::preview(&last_error().unwrap_or_default(), TOOLTIP_ERROR_CHARS);
+            t_args("tray-tooltip-error", &[("error", &error)])
+        }
+    }
+}
+
+/// Show the current state in the icon and tooltip
+fn apply_state<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let state = state();
+    if let Err(e) = tray.set_icon(icon(app, state)).and_then(|_| tray.set_tooltip(Some(tooltip(state)))) {
+        tracing::debug!("Tray state not updated: {}", e);
+    }
+}
+
+/// Track running stages through the pipeline events
+fn listen_pipelines<R: Runtime>(app: &AppHandle<R>) {
+    for pipeline in Pipeline::ALL {
+        let handle = app.clone();
+        app.listen_any(pipeline.event(Stage::Started), move |_| {
+            BUSY.fetch_add(1, Ordering::SeqCst);
+            set_last_error(None);
+            apply_state(&handle);
+        });
+        for stage in [Stage::Succeeded, Stage::Failed] {
+            let handle = app.clone();
+            app.listen_any(pipeline.event(stage), move |event| {
+                let _ = BUSY.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+                if stage == Stage::Failed {
+                    if let Some(error) = failure(event.payload()) {
+                        set_last_error(Some(error));
+                    }
+                }
+                apply_state(&handle);
+            });
+        }
+    }
+}
+
+/// Rebuild the submenus when models are added or removed, or results come in
+fn listen_submenus<R: Runtime>(app: &AppHandle<R>) {
+    let events = [
+        MODELS_CHANGED_EVENT.to_string(),
+        Pipeline::Download.event(Stage::Succeeded),
+        RECENT_RESULTS_CHANGED_EVENT.to_string(),
+    ];
+    for event in events {
+        let handle = app.clone();
+        app.listen_any(event, move |_| {
+            if let Err(e) = refresh(&handle) {
+                tracing::warn!("Tray menu not updated: {}", e);
+            }
+        });
+    }
+}
+
+fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    // The error has been seen
+    if state() == TrayState::Error {
+        set_last_error(None);
+        apply_state(app);
+    }
+}
+
+/// Tray menu with labels in the current locale
+fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let capture_i = MenuItem::with_id(app, "capture", t("tray-capture"), true, None::<&str>)?;
+    let show_i = MenuItem::with_id(app, "show", t("tray-show"), true, None::<&str>)?;
+    let pause_i = CheckMenuItem::with_id(
+        app,
+        "pause-live-ocr",
+        t("tray-pause-live-ocr"),
+        crate::live_ocr::is_running(),
+        crate::live_ocr::is_paused(),
+        None::<&str>,
+    )?;
+    let hover_i = CheckMenuItem::with_id(
+        app,
+        "hover-ocr",
+        t("tray-hover-ocr"),
+        true,
+        crate::hover_ocr::is_running(),
+        None::<&str>,
+    )?;
+    let languages = crate::tray_languages::build_submenu(app)?;
+    let recent = crate::recent_results::build_submenu(app)?;
     let separator = PredefinedMenuItem::separator(app)?;
-    let quit_i = MenuItem::with_id(app, "quit", "❌ Quit Screen Inu", true, None::<&str>)?;
+    let quit_i = MenuItem::with_id(app, "quit", t("tray-quit"), true, None::<&str>)?;
+
+    Menu::with_items(app, &[&capture_i, &show_i, &pause_i, &hover_i, &languages, &recent, &separator, &quit_i])
+}
 
-    let menu = Menu::with_items(app, &[&capture_i, &show_i, &separator, &quit_i])?;
+/// Rebuild the menu and tooltip after a locale, live OCR or language change
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(build_menu(app)?))?;
+        tray.set_tooltip(Some(tooltip(state())))?;
+    }
+    Ok(())
+}
+
+pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
 
-    let _tray = TrayIconBuilder::with_id("tray")
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
-        .tooltip("Screen Inu - OCR Tool 🐕")
+        .tooltip(t("tray-tooltip"))
         .show_menu_on_left_click(false)
         .on_menu_event(|app, event| match event.id.as_ref() {
             "capture" => {
@@ -24,33 +228,60 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                     let _ = window.emit("tray-capture", ());
                 }
             }
-            "show" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
+            "show" => show_main_window(app),
+            "pause-live-ocr" => crate::live_ocr::set_paused(app, !crate::live_ocr::is_paused()),
+            "hover-ocr" => {
+                if let Err(e) = crate::hover_ocr::set_enabled(app, !crate::hover_ocr::is_running()) {
+                    tracing::warn!("Hover OCR: {}", e);
                 }
             }
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            id => {
+                let _ = crate::tray_languages::handle_menu_event(app, id)
+                    || crate::recent_results::handle_menu_event(app, id);
+            }
         })
         .on_tray_icon_event(|tray, event| match event {
             TrayIconEvent::Click {
                 button: MouseButton::Left,
                 ..
-            } => {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
+            } => show_main_window(tray.app_handle()),
             _ => {}
         })
         .icon(app.default_window_icon().unwrap().clone())
         .build(app)?;
 
+    listen_pipelines(app);
+    listen_submenus(app);
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure() {
+        let failed = r#"{"opId":null,"source":"perform_ocr","durationMs":5,"errorCode":"ocr_failed","error":"OCR failed: x"}"#;
+        assert_eq!(failure(failed).as_deref(), Some("OCR failed: x"));
+        let cancelled = r#"{"errorCode":"cancelled","error":"Operation cancelled"}"#;
+        assert_eq!(failure(cancelled), None);
+        assert_eq!(failure("not json"), None);
+    }
+
+    #[test]
+    fn test_draw_badge() {
+        let (width, height) = (32, 32);
+        let mut rgba = vec![0u8; (width * height * 4) as usize];
+        draw_badge(&mut rgba, width, height, ERROR_BADGE);
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * width + x) * 4) as usize;
+            [rgba[i], rgba[i + 1], rgba[i + 2], rgba[i + 3]]
+        };
+        assert_eq!(pixel(width - 4, height - 4), ERROR_BADGE);
+        assert_eq!(pixel(2, 2), [0, 0, 0, 0]);
+        assert_eq!(pixel(width - 1, 0), [0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,179 @@
+// Tray language switcher
+// A "Languages" submenu lists the installed OCR languages and offline
+// translation pairs, with the active ones checked, so switching from
+// chi_tra→en to jpn→en doesn't need the main window. The frontend reports its
+// selection with `set_active_languages`; picking an entry in the tray updates
+// it and emits `tray://languages-changed` for the UI to follow. The tray
+// rebuilds the submenu when models are downloaded, deleted or imported.
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use tauri::{
+    menu::{CheckMenuItem, MenuItem, PredefinedMenuItem, Submenu},
+    AppHandle, Emitter, Runtime,
+};
+
+use crate::error::AppResult;
+use crate::i18n::t;
+
+/// Emitted with the new `ActiveLanguages` after a pick in the tray
+pub const LANGUAGES_CHANGED_EVENT: &str = "tray://languages-changed";
+
+/// Menu id prefixes of the entries
+const OCR_ID_PREFIX: &str = "ocr-lang:";
+const TRANSLATION_ID_PREFIX: &str = "translation-pair:";
+
+static ACTIVE: Lazy<Mutex<ActiveLanguages>> = Lazy::new(|| Mutex::new(ActiveLanguages::default()));
+
+/// The UI's current language selection
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ActiveLanguages {
+    /// Tesseract languages joined with '+', e.g. "eng+chi_tra"
+    pub ocr_langs: String,
+    /// Translation source; None when detected automatically
+    pub source_lang: Option<String>,
+    pub target_lang: Option<String>,
+}
+
+impl ActiveLanguages {
+    fn has_ocr_lang(&self, code: &str) -> bool {
+        self.ocr_langs.split('+').any(|lang| lang == code)
+    }
+
+    /// A pair is active if its target is, and its source too unless the
+    /// source is detected
+    fn has_pair(&self, source_lang: &str, target_lang: &str) -> bool {
+        self.target_lang.as_deref() == Some(target_lang)
+            && self.source_lang.as_deref().is_none_or(|source| source == source_lang)
+    }
+}
+
+/// Add or remove `code` in a '+'-joined language list, keeping at least one
+fn toggle_lang(langs: &str, code: &str) -> String {
+    let mut list: Vec<&str> = langs.split('+').filter(|lang| !lang.is_empty()).collect();
+    match list.iter().position(|lang| *lang == code) {
+        Some(index) if list.len() > 1 => {
+            list.remove(index);
+        }
+        Some(_) => {}
+        None => list.push(code),
+    }
+    list.join("+")
+}
+
+fn active() -> ActiveLanguages {
+    ACTIVE.lock().map(|active| active.clone()).unwrap_or_default()
+}
+
+/// The "Languages" submenu for the current selection and installed models
+pub fn build_submenu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Submenu<R>> {
+    let active = active();
+    let submenu = Submenu::with_id(app, "languages", t("tray-languages"), true)?;
+
+    submenu.append(&MenuItem::with_id(app, "ocr-header", t("tray-ocr-languages"), false, None::<&str>)?)?;
+    let ocr_models = crate::model_manager::list_installed_models().unwrap_or_default();
+    if ocr_models.is_empty() {
+        submenu.append(&MenuItem::with_id(app, "ocr-none", t("tray-no-models"), false, None::<&str>)?)?;
+    }
+    for model in ocr_models {
+        let id = format!("{}{}", OCR_ID_PREFIX, model.code);
+        let label = format!("{} ({})", model.name, model.code);
+        let checked = active.has_ocr_lang(&model.code);
+        submenu.append(&CheckMenuItem::with_id(app, id, label, true, checked, None::<&str>)?)?;
+    }
+
+    submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    submenu.append(&MenuItem::with_id(app, "translation-header", t("tray-translation-pairs"), false, None::<&str>)?)?;
+    let pairs: Vec<_> = crate::translator::list_translation_models()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|model| model.installed)
+        .collect();
+    if pairs.is_empty() {
+        submenu.append(&MenuItem::with_id(app, "translation-none", t("tray-no-models"), false, None::<&str>)?)?;
+    }
+    for pair in pairs {
+        let id = format!("{}{}-{}", TRANSLATION_ID_PREFIX, pair.source_lang, pair.target_lang);
+        let label = format!("{} → {}", pair.source_lang, pair.target_lang);
+        let checked = active.has_pair(&pair.source_lang, &pair.target_lang);
+        submenu.append(&CheckMenuItem::with_id(app, id, label, true, checked, None::<&str>)?)?;
+    }
+
+    Ok(submenu)
+}
+
+/// Apply a pick from the submenu. Returns false for other menu ids.
+pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, id: &str) -> bool {
+    let updated = {
+        let Ok(mut active) = ACTIVE.lock() else {
+            return false;
+        };
+        if let Some(code) = id.strip_prefix(OCR_ID_PREFIX) {
+            active.ocr_langs = toggle_lang(&active.ocr_langs, code);
+        } else if let Some(pair) = id.strip_prefix(TRANSLATION_ID_PREFIX) {
+            let Some((source, target)) = pair.split_once('-') else {
+                return false;
+            };
+            active.source_lang = Some(source.to_string());
+            active.target_lang = Some(target.to_string());
+        } else {
+            return false;
+        }
+        active.clone()
+    };
+    let _ = app.emit(LANGUAGES_CHANGED_EVENT, updated);
+    if let Err(e) = crate::tray::refresh(app) {
+        tracing::warn!("Tray menu not updated: {}", e);
+    }
+    true
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Tell the tray which languages the UI has selected
+#[tauri::command]
+pub fn set_active_languages(app: AppHandle, languages: ActiveLanguages) -> AppResult<()> {
+    {
+        let mut active = ACTIVE.lock()?;
+        if *active == languages {
+            return Ok(());
+        }
+        *active = languages;
+    }
+    if let Err(e) = crate::tray::refresh(&app) {
+        tracing::warn!("Tray menu not updated: {}", e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_lang() {
+        assert_eq!(toggle_lang("eng+chi_tra", "jpn"), "eng+chi_tra+jpn");
+        assert_eq!(toggle_lang("eng+chi_tra", "chi_tra"), "eng");
+        assert_eq!(toggle_lang("eng", "eng"), "eng");
+        assert_eq!(toggle_lang("", "jpn"), "jpn");
+    }
+
+    #[test]
+    fn test_active_pair() {
+        let mut active = ActiveLanguages {
+            ocr_langs: "jpn".to_string(),
+            source_lang: None,
+            target_lang: Some("en".to_string()),
+        };
+        assert!(active.has_pair("ja", "en"));
+        assert!(!active.has_pair("en", "ja"));
+        active.source_lang = Some("zh".to_string());
+        assert!(!active.has_pair("ja", "en"));
+        assert!(active.has_pair("zh", "en"));
+        assert!(active.has_ocr_lang("jpn") && !active.has_ocr_lang("jp"));
+    }
+}
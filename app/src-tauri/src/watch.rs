@@ -0,0 +1,225 @@
+// Region watches
+// OCRs a screen region periodically and alerts when its text matches a regex
+// ("Build failed", a price) or, without a pattern, whenever the text changes.
+// Alerts are a native notification plus a `watch://matched` event. Watch
+// definitions persist in the backend config.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use tauri::{AppHandle, Emitter};
+
+use crate::capture::{CaptureBackend, XcapBackend};
+use crate::error::{AppError, AppResult};
+use crate::frame_diff::Region;
+use crate::ocr::{self, OcrOptions};
+
+/// Emitted with a `WatchMatch` when a watch fires
+pub const WATCH_MATCHED_EVENT: &str = "watch://matched";
+
+/// Shortest allowed polling interval
+const MIN_INTERVAL_SECS: u64 = 2;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// A watched region
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionWatch {
+    pub id: String,
+    pub region: Region,
+    /// Regex to look for; alert on any text change when None
+    pub pattern: Option<String>,
+    pub interval_secs: u64,
+    pub langs: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Watch settings
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WatchConfig {
+    pub watches: Vec<RegionWatch>,
+}
+
+/// Payload of `watch://matched`
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchMatch {
+    pub id: String,
+    /// Full OCR text of the region
+    pub text: String,
+    /// The matching part (the whole text for change watches)
+    pub matched: String,
+}
+
+/// What the last check saw
+#[derive(Debug, Default)]
+struct WatchState {
+    last_run: Option<Instant>,
+    last_text: Option<String>,
+    /// Match that was already alerted, so a steady state alerts only once
+    last_match: Option<String>,
+}
+
+fn compile(pattern: &str) -> AppResult<Regex> {
+    Regex::new(pattern).map_err(|e| AppError::InvalidInput(format!("Invalid pattern: {}", e)))
+}
+
+/// Decide whether `text` should alert, updating the state. Returns the match.
+fn evaluate(pattern: Option<&Regex>, state: &mut WatchState, text: &str) -> Option<String> {
+    let text = text.trim();
+    let previous = state.last_text.replace(text.to_string());
+    match pattern {
+        Some(regex) => {
+            let current = regex.find(text).map(|m| m.as_str().to_string());
+            let changed = current != state.last_match;
+            state.last_match = current.clone();
+            current.filter(|_| changed)
+        }
+        // The first read is the baseline, not a change
+        None => previous.filter(|p| p != text).map(|_| text.to_string()),
+    }
+}
+
+fn ocr_region(watch: &RegionWatch) -> AppResult<String> {
+    crate::permissions::ensure_capture_permission()?;
+    let r = watch.region;
+    let image = XcapBackend::new(0).capture_region(r.x, r.y, r.width, r.height)?;
+
+    let mut bytes = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Capture(e.to_string()))?;
+    ocr::run_ocr(&bytes, &OcrOptions { langs: watch.langs.clone(), ..Default::default() }.with_reocr_config())
+}
+
+fn alert(app: &AppHandle, watch: &RegionWatch, text: String, matched: String) {
+    let title = match &watch.pattern {
+        Some(pattern) => crate::i18n::t_args("notification-watch-found", &[("pattern", pattern)]),
+        None => crate::i18n::t("notification-watch-changed"),
+    };
+    if let Err(e) = crate::notifications::show(app, &title, &matched) {
+        tracing::warn!("{}", e);
+    }
+    let _ = app.emit(WATCH_MATCHED_EVENT, WatchMatch { id: watch.id.clone(), text, matched });
+}
+
+/// Start the watch thread (once)
+pub fn init(app: &AppHandle) {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut states: HashMap<String, WatchState> = HashMap::new();
+        loop {
+            std::thread::sleep(Duration::from_secs(1));
+            let watches = crate::config::get().map(|c| c.watch.watches).unwrap_or_default();
+            states.retain(|id, _| watches.iter().any(|w| &w.id == id && w.enabled));
+
+            for watch in watches.iter().filter(|w| w.enabled) {
+                let state = states.entry(watch.id.clone()).or_default();
+                let interval = Duration::from_secs(watch.interval_secs.max(MIN_INTERVAL_SECS));
+                if state.last_run.is_some_and(|t| t.elapsed() < interval) {
+                    continue;
+                }
+                state.last_run = Some(Instant::now());
+
+                let pattern = match watch.pattern.as_deref().map(compile).transpose() {
+                    Ok(pattern) => pattern,
+                    Err(e) => {
+                        tracing::warn!("Watch {}: {}", watch.id, e);
+                        continue;
+                    }
+                };
+                match ocr_region(watch) {
+                    Ok(text) => {
+                        if let Some(matched) = evaluate(pattern.as_ref(), state, &text) {
+                            alert(&app, watch, text.trim().to_string(), matched);
+                        }
+                    }
+                    Err(e) => tracing::warn!("Watch {} failed: {}", watch.id, e),
+                }
+            }
+        }
+    });
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+/// Start watching a region. Returns the watch id.
+#[tauri::command]
+pub fn watch_region(
+    rect: Region,
+    pattern: Option<String>,
+    interval: Option<u64>,
+    langs: Option<String>,
+) -> AppResult<String> {
+    let pattern = pattern.filter(|p| !p.is_empty());
+    if let Some(pattern) = &pattern {
+        compile(pattern)?;
+    }
+    let id = format!("{:x}", chrono::Local::now().timestamp_millis());
+    let watch = RegionWatch {
+        id: id.clone(),
+        region: rect,
+        pattern,
+        interval_secs: interval.unwrap_or(30).max(MIN_INTERVAL_SECS),
+        langs,
+        enabled: true,
+    };
+    crate::config::update(|c| c.watch.watches.push(watch))?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn list_region_watches() -> AppResult<Vec<RegionWatch>> {
+    Ok(crate::config::get()?.watch.watches)
+}
+
+#[tauri::command]
+pub fn remove_region_watch(id: String) -> AppResult<()> {
+    crate::config::update(|c| c.watch.watches.retain(|w| w.id != id))
+}
+
+#[tauri::command]
+pub fn set_region_watch_enabled(id: String, enabled: bool) -> AppResult<()> {
+    crate::config::update(|c| {
+        for watch in c.watch.watches.iter_mut().filter(|w| w.id == id) {
+            watch.enabled = enabled;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_pattern_alerts_once_per_match() {
+        let regex = compile(r"Build (failed|passed)").unwrap();
+        let mut state = WatchState::default();
+        assert_eq!(evaluate(Some(&regex), &mut state, "Building..."), None);
+        assert_eq!(evaluate(Some(&regex), &mut state, "Build failed"), Some("Build failed".to_string()));
+        assert_eq!(evaluate(Some(&regex), &mut state, "Build failed "), None);
+        assert_eq!(evaluate(Some(&regex), &mut state, "Build passed"), Some("Build passed".to_string()));
+    }
+
+    #[test]
+    fn test_evaluate_change() {
+        let mut state = WatchState::default();
+        assert_eq!(evaluate(None, &mut state, "$10"), None);
+        assert_eq!(evaluate(None, &mut state, "$10"), None);
+        assert_eq!(evaluate(None, &mut state, "$9"), Some("$9".to_string()));
+    }
+}
@@ -0,0 +1,349 @@
+// History webhooks
+// Every new history item is POSTed to a configured URL, so automations
+// (n8n, Zapier, a Notion or Obsidian bridge) can pick captures up as they
+// happen. The body is the item as JSON, or the configured template rendered
+// with the item (`{"content": {text|json}}` for a JSON API). With a secret the
+// body is signed: X-ScreenInu-Signature is "sha256=" and the hex HMAC-SHA256
+// of the body, keyed with the secret. The URL and the secret are kept in the
+// OS keychain, never in the config file.
+
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::error::{AppError, AppResult};
+use crate::history::HistoryItem;
+use crate::templates::TemplateContext;
+
+const USER_AGENT: &str = concat!("ScreenInu/", env!("CARGO_PKG_VERSION"));
+
+pub const ITEM_ADDED_EVENT: &str = "history.item_added";
+
+/// Outbound webhook settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub enabled: bool,
+    // The URL and the HMAC key for the signature header (unsigned when
+    // unset) are accepted from the frontend and from configs written before
+    // they moved to the keychain; `move_secrets_to_keychain` moves them there
+    // and they are never written back or returned
+    #[serde(skip_serializing)]
+    pub url: Option<String>,
+    #[serde(skip_serializing)]
+    pub secret: Option<String>,
+    /// Output template for the body; the item as JSON when unset
+    pub template: Option<String>,
+    pub timeout_secs: u64,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            secret: None,
+            template: None,
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// Webhook settings kept in the keychain
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookSecret {
+    Url,
+    Secret,
+}
+
+impl WebhookSecret {
+    const ALL: [WebhookSecret; 2] = [WebhookSecret::Url, WebhookSecret::Secret];
+
+    fn keyring_user(self) -> &'static str {
+        match self {
+            WebhookSecret::Url => "history-webhook-url",
+            WebhookSecret::Secret => "history-webhook-secret",
+        }
+    }
+
+    /// The plaintext field of an older config or of unsaved test settings
+    fn field(self, config: &mut WebhookConfig) -> &mut Option<String> {
+        match self {
+            WebhookSecret::Url => &mut config.url,
+            WebhookSecret::Secret => &mut config.secret,
+        }
+    }
+}
+
+/// Store a setting in the keychain; an empty value removes it
+fn store_secret(secret: WebhookSecret, value: &str) -> AppResult<()> {
+    crate::secrets::store(secret.keyring_user(), value.trim())
+}
+
+fn load_secret(secret: WebhookSecret) -> AppResult<Option<String>> {
+    crate::secrets::load(secret.keyring_user())
+}
+
+fn validate_url(url: &str) -> AppResult<()> {
+    if url.trim().is_empty() || url.starts_with("https://") || url.starts_with("http://") {
+        Ok(())
+    } else {
+        // The URL itself may embed a token, so it is not echoed back
+        Err(AppError::InvalidInput("Webhook URL is not an HTTP URL".to_string()))
+    }
+}
+
+/// Hand plaintext settings found in `config` to `store` and clear them.
+/// Returns whether any were found.
+fn take_secrets(
+    config: &mut WebhookConfig,
+    mut store: impl FnMut(WebhookSecret, &str) -> AppResult<()>,
+) -> AppResult<bool> {
+    let mut found = false;
+    for secret in WebhookSecret::ALL {
+        if let Some(value) = secret.field(config).take() {
+            store(secret, &value)?;
+            found = true;
+        }
+    }
+    Ok(found)
+}
+
+/// Move the plaintext URL and secret in `config` to the keychain
+pub fn move_secrets_to_keychain(config: &mut WebhookConfig) -> AppResult<()> {
+    if let Some(url) = &config.url {
+        validate_url(url)?;
+    }
+    take_secrets(config, store_secret).map(|_| ())
+}
+
+/// Move settings left in the config file by older versions to the keychain
+pub fn init() {
+    let result = crate::config::get().and_then(|config| {
+        let mut webhooks = config.webhooks;
+        if take_secrets(&mut webhooks, store_secret)? {
+            crate::config::update(|c| c.webhooks = webhooks)?;
+            tracing::info!("Moved the history webhook URL and secret to the keychain");
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        tracing::warn!("History webhook settings not moved to the keychain: {}", e);
+    }
+}
+
+/// `secret` from `config` when given there (unsaved test settings), else
+/// from the keychain
+fn resolve(config: &WebhookConfig, secret: WebhookSecret) -> AppResult<Option<String>> {
+    let value = match secret {
+        WebhookSecret::Url => config.url.clone(),
+        WebhookSecret::Secret => config.secret.clone(),
+    };
+    match value {
+        Some(value) => Ok(Some(value)),
+        None => load_secret(secret),
+    }
+}
+
+/// Request body and its content type
+fn body(config: &WebhookConfig, item: &HistoryItem) -> AppResult<(String, &'static str)> {
+    let Some(template) = config.template.as_deref().filter(|t| !t.trim().is_empty()) else {
+        let body = serde_json::json!({ "event": ITEM_ADDED_EVENT, "item": item });
+        return Ok((body.to_string(), "application/json"));
+    };
+    let context = TemplateContext {
+        lang: Some(item.lang.clone()),
+        app: item.source_app.clone(),
+        timestamp: Some(item.timestamp),
+        ..Default::default()
+    };
+    let body = crate::templates::render(template, &item.text, &context)?;
+    let content_type = if serde_json::from_str::<serde_json::Value>(&body).is_ok() {
+        "application/json"
+    } else {
+        "text/plain; charset=utf-8"
+    };
+    Ok((body, content_type))
+}
+
+/// Value of the signature header for `body`
+fn signature(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body.as_bytes());
+    let hex: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// POST `item` to the configured URL
+pub async fn send(config: &WebhookConfig, item: &HistoryItem) -> AppResult<()> {
+    let url = resolve(config, WebhookSecret::Url)?
+        .filter(|u| !u.trim().is_empty())
+        .ok_or_else(|| AppError::InvalidInput("Webhook URL is not configured".to_string()))?;
+    let secret = resolve(config, WebhookSecret::Secret)?;
+    let (body, content_type) = body(config, item)?;
+
+    let mut request = reqwest::Client::new()
+        .post(&url)
+        .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+        .header("User-Agent", USER_AGENT)
+        .header("Content-Type", content_type)
+        .header("X-ScreenInu-Event", ITEM_ADDED_EVENT);
+    if let Some(secret) = secret.as_deref().filter(|s| !s.is_empty()) {
+        request = request.header("X-ScreenInu-Signature", signature(secret, &body));
+    }
+    let response = request
+        .body(body)
+        .send()
+        .await
+        // The URL may embed a token
+        .map_err(|e| AppError::Other(format!("Webhook request failed: {}", e.without_url())))?;
+    if !response.status().is_success() {
+        return Err(AppError::Other(format!("Webhook returned {}", response.status())));
+    }
+    Ok(())
+}
+
+/// Send `item` in the background when webhooks are enabled
+pub fn notify_item_added(item: HistoryItem) {
+    let config = match crate::config::get() {
+        Ok(config) => config.webhooks,
+        Err(e) => {
+            tracing::warn!("Webhook skipped: {}", e);
+            return;
+        }
+    };
+    if !config.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = send(&config, &item).await {
+            tracing::warn!("History webhook failed: {}", e);
+        }
+    });
+}
+
+// ========================================
+// Tauri Commands
+// ========================================
+
+#[tauri::command]
+pub fn get_webhook_config() -> AppResult<WebhookConfig> {
+    Ok(crate::config::get()?.webhooks)
+}
+
+#[tauri::command]
+pub fn set_webhook_config(mut config: WebhookConfig) -> AppResult<()> {
+    move_secrets_to_keychain(&mut config)?;
+    crate::config::update(|c| c.webhooks = config)
+}
+
+/// Store the webhook URL or signing secret in the keychain; `None` removes it
+#[tauri::command]
+pub fn set_webhook_secret(secret: WebhookSecret, value: Option<String>) -> AppResult<()> {
+    let value = value.unwrap_or_default();
+    if secret == WebhookSecret::Url {
+        validate_url(&value)?;
+    }
+    store_secret(secret, &value)
+}
+
+/// Whether the webhook URL or signing secret is stored
+#[tauri::command]
+pub fn has_webhook_secret(secret: WebhookSecret) -> AppResult<bool> {
+    Ok(load_secret(secret)?.is_some())
+}
+
+/// Send a sample item with the given settings, before saving them. A URL or
+/// secret left out of `config` is taken from the keychain.
+#[tauri::command]
+#[tracing::instrument(skip(config), err)]
+pub async fn test_webhook(config: WebhookConfig) -> AppResult<()> {
+    let item = HistoryItem {
+        id: "test".to_string(),
+        text: "Hello from Screen Inu".to_string(),
+        lang: "eng".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        source_app: None,
+        summary: None,
+        tags: Vec::new(),
+    };
+    send(&config, &item).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> HistoryItem {
+        HistoryItem {
+            id: "a1".to_string(),
+            text: "Say \"hi\"".to_string(),
+            lang: "eng".to_string(),
+            timestamp: 0,
+            source_app: Some("Safari".to_string()),
+            summary: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_body() {
+        let mut config = WebhookConfig::default();
+        let (json, content_type) = body(&config, &item()).unwrap();
+        assert_eq!(content_type, "application/json");
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["event"], ITEM_ADDED_EVENT);
+        assert_eq!(json["item"]["sourceApp"], "Safari");
+
+        config.template = Some("{{\"content\": {text|json}, \"app\": {app|json}}}".to_string());
+        assert_eq!(
+            body(&config, &item()).unwrap(),
+            ("{\"content\": \"Say \\\"hi\\\"\", \"app\": \"Safari\"}".to_string(), "application/json")
+        );
+        config.template = Some("{text} ({app})".to_string());
+        assert_eq!(body(&config, &item()).unwrap().1, "text/plain; charset=utf-8");
+    }
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            signature("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_secrets_stay_out_of_the_config() {
+        let json = r#"{"enabled":true,"url":"https://hooks.example/T0/token","secret":"hmac-key","timeoutSecs":5}"#;
+        let mut config: WebhookConfig = serde_json::from_str(json).unwrap();
+        let saved = serde_json::to_string(&config).unwrap();
+        assert!(!saved.contains("token") && !saved.contains("hmac-key"));
+
+        let mut stored = Vec::new();
+        assert!(take_secrets(&mut config, |secret, value| {
+            stored.push((secret, value.to_string()));
+            Ok(())
+        })
+        .unwrap());
+        assert_eq!(
+            stored,
+            vec![
+                (WebhookSecret::Url, "https://hooks.example/T0/token".to_string()),
+                (WebhookSecret::Secret, "hmac-key".to_string()),
+            ]
+        );
+        assert!(config.url.is_none() && config.secret.is_none());
+        assert!(config.enabled && config.timeout_secs == 5);
+        assert!(!take_secrets(&mut config, |_, _| unreachable!()).unwrap());
+    }
+
+    #[test]
+    fn test_validate_url() {
+        assert!(validate_url("https://hooks.example/x").is_ok());
+        assert!(validate_url("").is_ok());
+        assert!(validate_url("ftp://hooks.example/x").is_err());
+    }
+}
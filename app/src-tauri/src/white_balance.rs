@@ -0,0 +1,142 @@
+// White balance normalization
+// Captures taken with Night Shift / f.lux / Windows night light active have a
+// warm color cast that skews grayscale conversion and binarization. The cast
+// is estimated from the brightest pixels (page backgrounds and UI chrome are
+// white on screen, or the text is, in dark mode) and divided out per channel.
+// Each capture comes from one monitor, so monitors with different night-light
+// settings are each corrected on their own.
+
+use image::{Rgba, RgbaImage};
+
+use crate::error::{AppError, AppResult};
+
+/// Share of the brightest pixels treated as "should be white"
+const HIGHLIGHT_SHARE: f64 = 0.05;
+
+/// Highlights darker than this (mean of channels) carry no usable white point
+const MIN_HIGHLIGHT_LEVEL: f32 = 100.0;
+
+/// Channel ratios closer to 1 than this are left alone
+const MIN_CAST: f32 = 1.08;
+
+/// Channels are never boosted more than this
+const MAX_GAIN: f32 = 2.5;
+
+/// White balance settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WhiteBalanceConfig {
+    /// Correct color casts before OCR when one is detected
+    pub enabled: bool,
+}
+
+impl Default for WhiteBalanceConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Per-channel (r, g, b) gains that neutralize the image's color cast, or
+/// None when it has no noticeable cast
+pub fn detect_cast(image: &RgbaImage) -> Option<[f32; 3]> {
+    // Brightness histogram to find the highlight cutoff without sorting pixels
+    let mut histogram = [0u64; 766];
+    image
+        .pixels()
+        .for_each(|p| histogram[p[0] as usize + p[1] as usize + p[2] as usize] += 1);
+    let wanted = ((image.width() as u64 * image.height() as u64) as f64 * HIGHLIGHT_SHARE).ceil() as u64;
+    let mut seen = 0;
+    let cutoff = (0..histogram.len()).rev().find(|&level| {
+        seen += histogram[level];
+        seen >= wanted.max(1)
+    })?;
+
+    let (mut sums, mut count) = ([0u64; 3], 0u64);
+    for p in image.pixels().filter(|p| p[0] as usize + p[1] as usize + p[2] as usize >= cutoff) {
+        (0..3).for_each(|c| sums[c] += p[c] as u64);
+        count += 1;
+    }
+    let means = sums.map(|s| s as f32 / count.max(1) as f32);
+    let brightest = means.iter().cloned().fold(0.0, f32::max);
+    let dimmest = means.iter().cloned().fold(f32::MAX, f32::min);
+    let level = means.iter().sum::<f32>() / 3.0;
+    if level < MIN_HIGHLIGHT_LEVEL || brightest < dimmest * MIN_CAST {
+        return None;
+    }
+    Some(means.map(|m| (brightest / m.max(1.0)).min(MAX_GAIN)))
+}
+
+/// Scale each channel by its gain
+pub fn apply_gains(image: &mut RgbaImage, gains: [f32; 3]) {
+    for pixel in image.pixels_mut() {
+        let Rgba([r, g, b, a]) = *pixel;
+        let scale = |value: u8, gain: f32| (value as f32 * gain).round().min(255.0) as u8;
+        *pixel = Rgba([scale(r, gains[0]), scale(g, gains[1]), scale(b, gains[2]), a]);
+    }
+}
+
+/// Neutralize the color cast of an encoded image. Returns None when the
+/// image has no cast, so the caller can keep using the original bytes.
+pub fn normalize(image_bytes: &[u8]) -> AppResult<Option<Vec<u8>>> {
+    let mut image = crate::image_decode::decode_image_bytes(image_bytes)?.to_rgba8();
+    let Some(gains) = detect_cast(&image) else {
+        return Ok(None);
+    };
+    apply_gains(&mut image, gains);
+
+    let mut bytes = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| AppError::Ocr(format!("Failed to encode image: {}", e)))?;
+    Ok(Some(bytes))
+}
+
+/// `normalize` when enabled in settings; undecodable images are passed on
+/// unchanged for the OCR engine to report
+pub fn normalize_if_enabled(image_bytes: &[u8]) -> Option<Vec<u8>> {
+    let enabled = crate::config::get().map_or(true, |c| c.white_balance.enabled);
+    if !enabled {
+        return None;
+    }
+    normalize(image_bytes).ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// White page with dark text under a night-light tint
+    fn tinted_page() -> RgbaImage {
+        RgbaImage::from_fn(20, 20, |x, _| {
+            if x % 5 == 0 {
+                Rgba([40, 30, 20, 255])
+            } else {
+                Rgba([255, 220, 170, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn test_detects_warm_cast() {
+        let gains = detect_cast(&tinted_page()).unwrap();
+        assert_eq!(gains[0], 1.0);
+        assert!(gains[2] > gains[1] && gains[1] > 1.0);
+    }
+
+    #[test]
+    fn test_apply_gains_neutralizes_background() {
+        let mut image = tinted_page();
+        let gains = detect_cast(&image).unwrap();
+        apply_gains(&mut image, gains);
+        assert_eq!(*image.get_pixel(1, 0), Rgba([255, 255, 255, 255]));
+        assert!(detect_cast(&image).is_none());
+    }
+
+    #[test]
+    fn test_neutral_and_dark_images_untouched() {
+        let gray = RgbaImage::from_pixel(8, 8, Rgba([240, 242, 238, 255]));
+        assert!(detect_cast(&gray).is_none());
+        let dark = RgbaImage::from_pixel(8, 8, Rgba([60, 40, 20, 255]));
+        assert!(detect_cast(&dark).is_none());
+    }
+}
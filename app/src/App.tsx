@@ -1,5 +1,5 @@
 import { useEffect, useState, useRef } from "react";
-import { invoke } from "@tauri-apps/api/core";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
 import { register } from "@tauri-apps/plugin-global-shortcut";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { SnippingOverlay } from "./components/SnippingOverlay";
@@ -23,13 +23,36 @@ import {
   Languages,
   Layers,
   Volume2,
-  VolumeX
+  VolumeX,
+  Link,
+  Mail,
+  Phone,
+  Calendar,
+  MapPin,
+  IdCard,
+  ScanLine,
+  FileDown,
+  NotebookPen
 } from "lucide-react";
+import { errorMessage } from "./utils/errors";
+import { cancelOperation, isCancelled, newOperationId } from "./utils/operations";
 import { notifyOcrComplete } from "./utils/notification";
-import { addToHistoryAsync, getHistoryAsync, clearHistoryAsync, HistoryItem } from "./utils/history";
+import { addToHistoryAsync, getHistoryAsync, clearHistoryAsync, summarizeHistoryItemAsync, syncHistoryFolderAsync, HistoryItem } from "./utils/history";
 import { soundManager } from "./utils/SoundManager";
 import { translateText, COMMON_TARGET_LANGUAGES } from "./utils/translate";
+import { onTrayLanguagesChanged } from "./utils/events";
+import { checkShortcut, describeConflict, onShortcutConflicts, onShortcutTriggered } from "./utils/shortcuts";
 import { getSettings, setTranslationEngine as setTranslationEnginePref } from "./utils/settings";
+import { restoreLastSession, saveSession, SelectionRect } from "./utils/session";
+import { applyTemplate } from "./utils/templates";
+import { Entity, extractEntities, isOpenable, openDetectedUrl } from "./utils/entities";
+import { createEventFromText } from "./utils/calendar";
+import { Contact, exportVcard, performBusinessCardOcr } from "./utils/businessCard";
+import { scanDocument } from "./utils/documentScan";
+import { saveSearchablePdf } from "./utils/searchablePdf";
+import { appendToNote } from "./utils/notes";
+import { emailResult } from "./utils/email";
+import { Conversion, convertValues } from "./utils/conversion";
 import "./App.css";
 
 function App() {
@@ -37,6 +60,10 @@ function App() {
 
   // --- State ---
   const [screenshot, setScreenshot] = useState<string | null>(null);
+  // Capture being worked on and the area selected on it, saved with the result
+  const [captureId, setCaptureId] = useState<string | null>(null);
+  const [selection, setSelection] = useState<SelectionRect | null>(null);
+  const [sessionRestored, setSessionRestored] = useState(false);
   const [ocrResult, setOcrResult] = useState<string>("");
   const [isCopied, setIsCopied] = useState(false);
   const [selectedLang, setSelectedLang] = useState("eng+chi_tra");
@@ -44,6 +71,9 @@ function App() {
   const [soundEnabled, setSoundEnabled] = useState(true);
   const [directSnip, setDirectSnip] = useState(false);
   const [silentMode, setSilentMode] = useState(false);
+  const [businessCardMode, setBusinessCardMode] = useState(false);
+  // Captures and dropped files are photos of paper: straighten before OCR
+  const [documentMode, setDocumentMode] = useState(false);
   const [ocrEngine, setOcrEngine] = useState("auto");
   const [availableEngines, setAvailableEngines] = useState<string[]>(["auto", "tesseract"]);
 
@@ -55,7 +85,12 @@ function App() {
   // Refs to access current values in callbacks (avoid stale closures)
   const directSnipRef = useRef(directSnip);
   const silentModeRef = useRef(silentMode);
+  const businessCardModeRef = useRef(businessCardMode);
+  const documentModeRef = useRef(documentMode);
+  const runFileOcrRef = useRef<(paths: string[]) => void>(() => { });
   const [isLoading, setIsLoading] = useState(false);
+  // Running OCR, cancelled with Escape (e.g. after selecting the whole screen by mistake)
+  const ocrOpRef = useRef<string | null>(null);
   const [showHistory, setShowHistory] = useState(false);
   const [showSettings, setShowSettings] = useState(false);
   const [showBatchMode, setShowBatchMode] = useState(false);
@@ -63,10 +98,19 @@ function App() {
 
   // Translation state
   const [translatedText, setTranslatedText] = useState("");
+  const [entities, setEntities] = useState<Entity[]>([]);
+  const [conversions, setConversions] = useState<Conversion[]>([]);
+  // Fields read in business card mode
+  const [contact, setContact] = useState<Contact | null>(null);
+  // Image the result was read from, for PDF export
+  const [ocrImage, setOcrImage] = useState<string | null>(null);
+  // History item of the current result
+  const [resultHistoryId, setResultHistoryId] = useState<string | null>(null);
   const [isTranslating, setIsTranslating] = useState(false);
   const [translateEnabled, setTranslateEnabled] = useState(true);
   const [autoTranslate, setAutoTranslate] = useState(false);
   const [targetLang, setTargetLang] = useState('zh'); // Default to Chinese
+  const [translationSource, setTranslationSource] = useState<string | null>(null); // null = auto-detect
   const [translationEngine, setTranslationEngine] = useState<'online' | 'offline'>('online');
 
 
@@ -100,6 +144,15 @@ function App() {
       silentModeRef.current = enabled;
     }
 
+    if (localStorage.getItem('businessCardMode') === 'true') {
+      setBusinessCardMode(true);
+      businessCardModeRef.current = true;
+    }
+    if (localStorage.getItem('documentMode') === 'true') {
+      setDocumentMode(true);
+      documentModeRef.current = true;
+    }
+
     // Load OCR Engine preference
     const savedOcrEngine = localStorage.getItem('ocrEngine');
     if (savedOcrEngine) setOcrEngine(savedOcrEngine);
@@ -123,8 +176,28 @@ function App() {
       }
     }).catch(e => console.error("Failed to get OCR engines:", e));
 
-    // Load History (async)
+    // Load History (async), then pick up other devices' captures
     getHistoryAsync().then(items => setHistoryItems(items));
+    syncHistoryFolderAsync()
+      .then(async changed => { if (changed) setHistoryItems(await getHistoryAsync()); })
+      .catch(e => console.error("Folder sync failed:", e));
+
+    // Bring back the work in progress from before a crash or restart
+    restoreLastSession().then(session => {
+      if (!session) return;
+      if (session.capture) {
+        setCaptureId(session.capture.id);
+        setSelection(session.selection);
+      }
+      if (session.ocrText) {
+        setOcrResult(session.ocrText);
+        setTranslatedText(session.translation ?? "");
+      } else if (session.capture) {
+        // Captured but not cropped yet: back to the snipping overlay
+        setScreenshot(convertFileSrc(session.capture.id, "screeninu-capture"));
+      }
+    }).catch(e => console.error("Failed to restore the last session:", e))
+      .finally(() => setSessionRestored(true));
 
     let shortcutRegistered = false;
     let currentRegisteredShortcut = "";
@@ -162,8 +235,86 @@ function App() {
     };
     const unlistenPromise = setupTrayListener();
 
+    // Scheduled captures run in the backend, also while hidden in the tray
+    const setupSchedulerListener = async () => {
+      const { listen } = await import("@tauri-apps/api/event");
+      return listen<{ text: string | null; lang: string; error: string | null }>(
+        "scheduler://captured",
+        async (event) => {
+          const { text, lang, error } = event.payload;
+          if (error) {
+            console.error("Scheduled capture failed:", error);
+          } else if (text) {
+            await addToHistoryAsync(text, lang);
+          }
+        },
+      );
+    };
+    const unlistenSchedulerPromise = setupSchedulerListener();
+
+    // "Open history" clicked on a result notification
+    const setupNotificationListener = async () => {
+      const { listen } = await import("@tauri-apps/api/event");
+      return listen<{ action: string }>("notification://action", async (event) => {
+        if (event.payload.action === "openHistory") {
+          setHistoryItems(await getHistoryAsync());
+          setShowHistory(true);
+        }
+      });
+    };
+    const unlistenNotificationPromise = setupNotificationListener();
+
+    // Rewind hotkey: snip from the frame buffered a few seconds ago
+    const setupRewindListener = async () => {
+      const { listen } = await import("@tauri-apps/api/event");
+      return listen<{ id: string }>("capture-buffer://rewind", async (event) => {
+        setOcrResult("");
+        setCaptureId(event.payload.id);
+        setSelection(null);
+        setScreenshot(convertFileSrc(event.payload.id, "screeninu-capture"));
+        const window = getCurrentWebviewWindow();
+        await window.setFullscreen(true);
+        await window.show();
+        await window.setFocus();
+      });
+    };
+    const unlistenRewindPromise = setupRewindListener();
+
+    // Image files dropped on the window
+    const setupDropListener = async () => {
+      const { getCurrentWebview } = await import("@tauri-apps/api/webview");
+      return getCurrentWebview().onDragDropEvent((event) => {
+        if (event.payload.type === "drop" && event.payload.paths.length > 0) {
+          runFileOcrRef.current(event.payload.paths);
+        }
+      });
+    };
+    const unlistenDropPromise = setupDropListener();
+
+    // Chords and double presses bound in the backend
+    const unlistenShortcutPromise = onShortcutTriggered((binding) => {
+      if (binding.action === "capture") {
+        captureScreen();
+      } else if (binding.action === "hover-ocr") {
+        invoke<boolean>("is_hover_ocr_running")
+          .then((running) => invoke("set_hover_ocr", { enabled: !running }))
+          .catch((err) => console.error("Failed to toggle hover OCR:", err));
+      } else {
+        console.warn(`Unhandled shortcut action: ${binding.action}`);
+      }
+    });
+    const unlistenConflictsPromise = onShortcutConflicts((conflicts) => {
+      conflicts.forEach((conflict) => console.warn(`Shortcut not registered: ${describeConflict(conflict)}`));
+    });
+
     return () => {
       unlistenPromise.then(unlisten => unlisten());
+      unlistenSchedulerPromise.then(unlisten => unlisten());
+      unlistenNotificationPromise.then(unlisten => unlisten());
+      unlistenRewindPromise.then(unlisten => unlisten());
+      unlistenDropPromise.then(unlisten => unlisten());
+      unlistenShortcutPromise.then(unlisten => unlisten());
+      unlistenConflictsPromise.then(unlisten => unlisten());
       // Cleanup shortcut on unmount
       if (shortcutRegistered && currentRegisteredShortcut) {
         import("@tauri-apps/plugin-global-shortcut").then(({ unregister }) => {
@@ -197,6 +348,18 @@ function App() {
     localStorage.setItem('directSnip', String(enabled));
   };
 
+  const handleSetBusinessCardMode = (enabled: boolean) => {
+    setBusinessCardMode(enabled);
+    businessCardModeRef.current = enabled;
+    localStorage.setItem('businessCardMode', String(enabled));
+  };
+
+  const handleSetDocumentMode = (enabled: boolean) => {
+    setDocumentMode(enabled);
+    documentModeRef.current = enabled;
+    localStorage.setItem('documentMode', String(enabled));
+  };
+
   const handleSetSilentMode = (enabled: boolean) => {
     setSilentMode(enabled);
     silentModeRef.current = enabled; // Keep ref in sync
@@ -206,6 +369,11 @@ function App() {
   const handleSetCustomShortcut = async (newShortcut: string) => {
     if (!newShortcut || newShortcut === customShortcut) return;
 
+    const conflicts = await checkShortcut("capture", newShortcut).catch(() => []);
+    if (conflicts.length > 0) {
+      throw new Error(describeConflict(conflicts[0]));
+    }
+
     try {
       const { unregister } = await import("@tauri-apps/plugin-global-shortcut");
       // Unregister old shortcut
@@ -236,6 +404,29 @@ function App() {
     localStorage.setItem('ocrEngine', engine);
   };
 
+  // Keep the tray's language submenu in sync, and follow picks made there
+  useEffect(() => {
+    invoke("set_active_languages", {
+      languages: { ocrLangs: selectedLang, sourceLang: translationSource, targetLang },
+    }).catch((err) => console.warn("Failed to sync tray languages:", err));
+  }, [selectedLang, targetLang, translationSource]);
+
+  useEffect(() => {
+    const unlistenPromise = onTrayLanguagesChanged((languages) => {
+      if (languages.ocrLangs) setSelectedLang(languages.ocrLangs);
+      if (languages.targetLang) {
+        setTargetLang(languages.targetLang);
+        setTranslationSource(languages.sourceLang);
+        // Pairs in the tray are installed offline models
+        setTranslationEngine('offline');
+        setTranslationEnginePref('offline').catch(() => {});
+      }
+    });
+    return () => {
+      unlistenPromise.then(unlisten => unlisten());
+    };
+  }, []);
+
   const handleTranslate = async () => {
     if (!ocrResult.trim() || isTranslating) return;
 
@@ -244,10 +435,13 @@ function App() {
     try {
       const result = await translateText({
         text: ocrResult,
+        sourceLang: translationSource ?? undefined,
         targetLang: targetLang,
         offlineMode: translationEngine === 'offline'
       });
       setTranslatedText(result.translatedText);
+      invoke("add_recent_result", { kind: "translation", text: result.translatedText })
+        .catch(e => console.error("Failed to add recent result:", e));
       soundManager.playSuccess();
     } catch (error) {
       console.error("Translation failed:", error);
@@ -293,8 +487,11 @@ function App() {
       // Freeze Mode: Hide -> Capture Full -> Show Preview
       await window.hide();
       await new Promise(resolve => setTimeout(resolve, 150)); // Reduced delay
-      const base64: string = await invoke("capture_full_screen");
-      setScreenshot(`data:image/png;base64,${base64}`);
+      // Served over the capture protocol instead of a base64 string
+      const capture: { id: string } = await invoke("capture_full_screen_url");
+      setCaptureId(capture.id);
+      setSelection(null);
+      setScreenshot(convertFileSrc(capture.id, "screeninu-capture"));
       await window.setFullscreen(true);
       await window.show();
       await window.setFocus();
@@ -326,25 +523,41 @@ function App() {
       // ... rest of runOcr
       const qrResult: string | null = await invoke("scan_qr", { base64Image: base64 });
       let text: string;
+      setContact(null);
+      setOcrImage(base64);
 
       if (qrResult) {
         text = `[QR Code]\n${qrResult}`;
+      } else if (businessCardModeRef.current) {
+        const card = await performBusinessCardOcr(base64, selectedLang, ocrEngine);
+        text = card.text;
+        setContact(card.contact);
+      } else if (documentModeRef.current) {
+        text = (await scanDocument(base64, selectedLang, ocrEngine)).text;
       } else {
         // Use the selected OCR engine from settings
         // When "auto": CJK languages → Windows OCR (on Windows), other → Tesseract
-        text = await invoke("perform_ocr", { base64Image: base64, langs: selectedLang, engine: ocrEngine });
+        const opId = newOperationId();
+        ocrOpRef.current = opId;
+        text = await invoke("perform_ocr", { base64Image: base64, options: { langs: selectedLang, engine: ocrEngine }, opId });
       }
 
       setOcrResult(text || "__EMPTY__");
 
       if (text && text.trim()) {
         soundManager.playBark(); // 🐕 WOOF!
-        await addToHistoryAsync(text, qrResult ? "QR" : selectedLang);
+        const historyId = await addToHistoryAsync(text, qrResult ? "QR" : selectedLang);
+        setResultHistoryId(historyId ?? null);
         setHistoryItems(await getHistoryAsync()); // Refresh history view
+        if (historyId && !qrResult) {
+          summarizeHistoryItemAsync(historyId, text)
+            .then(async () => setHistoryItems(await getHistoryAsync()))
+            .catch((e) => console.error("Summarization failed:", e));
+        }
         notifyOcrComplete(text.length);
 
         if (autoCopy) {
-          const textToCopy = qrResult || text;
+          const textToCopy = qrResult || await applyTemplate(text, { lang: selectedLang });
           navigator.clipboard.writeText(textToCopy);
           setIsCopied(true);
           soundManager.playSuccess(); // ✨ DING!
@@ -360,18 +573,96 @@ function App() {
         soundManager.playError();
       }
     } catch (e) {
+      if (isCancelled(e)) {
+        setOcrResult("");
+        return;
+      }
       console.error("OCR Failed:", e);
       soundManager.playError();
-      setOcrResult("Error: " + e);
+      setOcrResult("Error: " + errorMessage(e));
     } finally {
+      ocrOpRef.current = null;
       setIsLoading(false);
     }
   }
 
-  const handleCopy = () => {
+  // Keep the work in progress on disk, once the last session was restored
+  useEffect(() => {
+    if (!sessionRestored) return;
+    const timer = setTimeout(() => {
+      saveSession({ captureId, selection, ocrText: ocrResult, translation: translatedText || null })
+        .catch(e => console.error("Failed to save the session:", e));
+    }, 500);
+    return () => clearTimeout(timer);
+  }, [sessionRestored, captureId, selection, ocrResult, translatedText]);
+
+  // Links, emails, dates, ... in the result
+  useEffect(() => {
+    if (!ocrResult || ocrResult === "__EMPTY__" || ocrResult.startsWith("Error:")) {
+      setEntities([]);
+      setConversions([]);
+      setContact(null);
+      setOcrImage(null);
+      setResultHistoryId(null);
+      return;
+    }
+    extractEntities(ocrResult)
+      .then(setEntities)
+      .catch(e => console.error("Entity extraction failed:", e));
+    convertValues(ocrResult)
+      .then(result => setConversions(result.conversions))
+      .catch(e => console.error("Conversion failed:", e));
+  }, [ocrResult]);
+
+  // Escape stops a running OCR
+  useEffect(() => {
+    if (!isLoading) return;
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === 'Escape' && ocrOpRef.current) {
+        cancelOperation(ocrOpRef.current).catch((err) => console.error("Failed to cancel OCR:", err));
+      }
+    };
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, [isLoading]);
+
+  async function runFileOcr(paths: string[]) {
+    // The batch panel handles its own drops
+    if (showBatchMode) return;
+    setIsLoading(true);
+    try {
+      const results = await invoke<{ path: string; text: string; error: string | null }[]>(
+        "perform_file_ocr",
+        { paths, langs: selectedLang, engine: ocrEngine, document: documentModeRef.current },
+      );
+      const failed = results.filter(r => r.error);
+      failed.forEach(r => console.error(`OCR of ${r.path} failed:`, r.error));
+      const text = results.map(r => r.text).filter(t => t.trim()).join("\n\n");
+      setOcrResult(text || (failed.length > 0 ? "Error: " + failed[0].error : "__EMPTY__"));
+
+      if (text.trim()) {
+        soundManager.playBark();
+        await addToHistoryAsync(text, selectedLang);
+        setHistoryItems(await getHistoryAsync());
+        notifyOcrComplete(text.length);
+      } else {
+        soundManager.playError();
+      }
+    } catch (e) {
+      console.error("File OCR Failed:", e);
+      soundManager.playError();
+      setOcrResult("Error: " + errorMessage(e));
+    } finally {
+      setIsLoading(false);
+    }
+  }
+  runFileOcrRef.current = runFileOcr;
+
+  const handleCopy = async () => {
+    // QR payloads are copied as they are
     const text = ocrResult.startsWith("[QR Code]")
       ? ocrResult.replace("[QR Code]\n", "")
-      : ocrResult;
+      : await applyTemplate(ocrResult, { lang: selectedLang, translation: translatedText || undefined });
 
     navigator.clipboard.writeText(text);
     setIsCopied(true);
@@ -500,6 +791,26 @@ function App() {
                 <Layers size={18} />
                 {t('batch_mode')}
               </button>
+
+              {/* Business Card Mode Toggle */}
+              <button
+                onClick={() => handleSetBusinessCardMode(!businessCardMode)}
+                className={`flex items-center gap-2 px-4 py-2 border-2 rounded-lg font-bold transition-all hover:scale-105 ${businessCardMode ? "bg-amber-400 text-zinc-900 border-amber-400" : "bg-zinc-800 text-amber-400 border-zinc-700 hover:border-amber-400"}`}
+                aria-pressed={businessCardMode}
+              >
+                <IdCard size={18} />
+                {t('business_card_mode')}
+              </button>
+
+              {/* Document Mode Toggle */}
+              <button
+                onClick={() => handleSetDocumentMode(!documentMode)}
+                className={`flex items-center gap-2 px-4 py-2 border-2 rounded-lg font-bold transition-all hover:scale-105 ${documentMode ? "bg-amber-400 text-zinc-900 border-amber-400" : "bg-zinc-800 text-amber-400 border-zinc-700 hover:border-amber-400"}`}
+                aria-pressed={documentMode}
+              >
+                <ScanLine size={18} />
+                {t('document_mode')}
+              </button>
             </div>
           ) : (
             // Result / Loading State
@@ -540,6 +851,44 @@ function App() {
                       <button onClick={handleSearch} className="p-1.5 bg-white border-2 border-[#0a0a0a] hover:bg-[#0a0a0a] hover:text-[#00ff88] transition-colors shadow-[2px_2px_0px_#0a0a0a] hover:shadow-none hover:translate-x-[2px] hover:translate-y-[2px] focus:outline-none focus-visible:ring-2 focus-visible:ring-[#0a0a0a] focus-visible:ring-offset-2" title={t('status.search')} aria-label={t('status.search')}>
                         <Search size={16} strokeWidth={3} />
                       </button>
+                      {ocrImage && (
+                        <button
+                          onClick={() => saveSearchablePdf([{ base64Image: ocrImage }], selectedLang, ocrEngine).catch(e => console.error("PDF export failed:", e))}
+                          className="p-1.5 bg-white border-2 border-[#0a0a0a] hover:bg-[#0a0a0a] hover:text-[#00ff88] transition-colors shadow-[2px_2px_0px_#0a0a0a] hover:shadow-none hover:translate-x-[2px] hover:translate-y-[2px] focus:outline-none focus-visible:ring-2 focus-visible:ring-[#0a0a0a] focus-visible:ring-offset-2"
+                          title={t('status.export_pdf')}
+                          aria-label={t('status.export_pdf')}
+                        >
+                          <FileDown size={16} strokeWidth={3} />
+                        </button>
+                      )}
+                      <button
+                        onClick={() => appendToNote({
+                          text: ocrResult,
+                          translation: translatedText || undefined,
+                          lang: selectedLang,
+                          captureId: captureId ?? undefined,
+                          base64Image: captureId ? undefined : ocrImage ?? undefined,
+                        }).catch(e => console.error("Send to notes failed:", e))}
+                        className="p-1.5 bg-white border-2 border-[#0a0a0a] hover:bg-[#0a0a0a] hover:text-[#00ff88] transition-colors shadow-[2px_2px_0px_#0a0a0a] hover:shadow-none hover:translate-x-[2px] hover:translate-y-[2px] focus:outline-none focus-visible:ring-2 focus-visible:ring-[#0a0a0a] focus-visible:ring-offset-2"
+                        title={t('status.send_to_notes')}
+                        aria-label={t('status.send_to_notes')}
+                      >
+                        <NotebookPen size={16} strokeWidth={3} />
+                      </button>
+                      {resultHistoryId && (
+                        <button
+                          onClick={() => emailResult(
+                            resultHistoryId,
+                            ocrResult,
+                            captureId ? { captureId } : ocrImage ? { base64Image: ocrImage } : {},
+                          ).catch(e => console.error("Email failed:", e))}
+                          className="p-1.5 bg-white border-2 border-[#0a0a0a] hover:bg-[#0a0a0a] hover:text-[#00ff88] transition-colors shadow-[2px_2px_0px_#0a0a0a] hover:shadow-none hover:translate-x-[2px] hover:translate-y-[2px] focus:outline-none focus-visible:ring-2 focus-visible:ring-[#0a0a0a] focus-visible:ring-offset-2"
+                          title={t('status.email_result')}
+                          aria-label={t('status.email_result')}
+                        >
+                          <Mail size={16} strokeWidth={3} />
+                        </button>
+                      )}
                       {translateEnabled && (
                         <button
                           onClick={handleTranslate}
@@ -551,7 +900,7 @@ function App() {
                           <Languages size={16} strokeWidth={3} />
                         </button>
                       )}
-                      <button onClick={() => { setOcrResult(""); setTranslatedText(""); }} className="p-1.5 bg-white border-2 border-[#0a0a0a] hover:bg-[#ff6b35] hover:text-white transition-colors shadow-[2px_2px_0px_#0a0a0a] hover:shadow-none hover:translate-x-[2px] hover:translate-y-[2px] focus:outline-none focus-visible:ring-2 focus-visible:ring-[#0a0a0a] focus-visible:ring-offset-2" title={t('status.clear')} aria-label={t('status.clear')}>
+                      <button onClick={() => { setOcrResult(""); setTranslatedText(""); setCaptureId(null); setSelection(null); }} className="p-1.5 bg-white border-2 border-[#0a0a0a] hover:bg-[#ff6b35] hover:text-white transition-colors shadow-[2px_2px_0px_#0a0a0a] hover:shadow-none hover:translate-x-[2px] hover:translate-y-[2px] focus:outline-none focus-visible:ring-2 focus-visible:ring-[#0a0a0a] focus-visible:ring-offset-2" title={t('status.clear')} aria-label={t('status.clear')}>
                         <X size={16} strokeWidth={3} />
                       </button>
                     </div>
@@ -567,6 +916,53 @@ function App() {
                       ocrResult
                     )}
 
+                    {/* Detected entities */}
+                    {entities.length > 0 && (
+                      <div className="mt-4 flex flex-wrap gap-2">
+                        {entities.map((entity, index) => {
+                          const Icon = { url: Link, email: Mail, phone: Phone, date: Calendar, address: MapPin }[entity.kind];
+                          return (
+                            <button
+                              key={`${entity.start}-${entity.kind}`}
+                              onClick={() =>
+                                (entity.kind === "date" ? createEventFromText(ocrResult).then(() => undefined) : openDetectedUrl(index))
+                                  .catch(e => console.error("Failed to open entity:", e))
+                              }
+                              disabled={!isOpenable(entity) && entity.kind !== "date"}
+                              className="flex items-center gap-1 px-2 py-0.5 border-2 border-[#0a0a0a] bg-white text-[10px] font-bold enabled:hover:bg-[#00ff88] disabled:cursor-default transition-colors"
+                              title={entity.value}
+                            >
+                              <Icon size={12} strokeWidth={3} />
+                              <span className="max-w-[180px] truncate">{entity.text}</span>
+                            </button>
+                          );
+                        })}
+                      </div>
+                    )}
+
+                    {/* Business card: save as a contact */}
+                    {contact && (
+                      <button
+                        onClick={() => exportVcard(contact).catch(e => console.error("Failed to export vCard:", e))}
+                        className="mt-2 flex items-center gap-1 px-2 py-0.5 border-2 border-[#0a0a0a] bg-white text-[10px] font-bold hover:bg-[#00ff88] transition-colors"
+                        title={[contact.name, contact.company].filter(Boolean).join(" · ")}
+                      >
+                        <IdCard size={12} strokeWidth={3} />
+                        {t('save_contact')}
+                      </button>
+                    )}
+
+                    {/* Converted prices and measurements */}
+                    {conversions.length > 0 && (
+                      <div className="mt-2 flex flex-wrap gap-x-4 text-[10px] text-[#0a0a0a]/60">
+                        {conversions.map((conversion) => (
+                          <span key={conversion.start}>
+                            {conversion.text} <span className="font-bold text-[#0a0a0a]">{conversion.display}</span>
+                          </span>
+                        ))}
+                      </div>
+                    )}
+
                     {/* Translation Result */}
                     {translatedText && (
                       <div className="mt-4 pt-4 border-t-2 border-dashed border-[#0a0a0a]/20">
@@ -698,6 +1094,7 @@ function App() {
               onSelect={(item) => {
                 setOcrResult(item.text);
                 setTranslatedText("");
+                setResultHistoryId(item.id);
                 setShowHistory(false);
                 soundManager.playSuccess();
               }}
@@ -717,10 +1114,12 @@ function App() {
             onClose={async () => {
               await restoreWindow();
               setScreenshot(null);
+              setCaptureId(null);
             }}
-            onCrop={async (croppedImage: string) => {
+            onCrop={async (croppedImage: string, cropSelection: SelectionRect) => {
               await restoreWindow();
               setScreenshot(null);
+              setSelection(cropSelection);
               runOcr(croppedImage);
             }}
           />
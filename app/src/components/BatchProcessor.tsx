@@ -90,8 +90,7 @@ export default function BatchProcessor({ ocrLang, ocrEngine, onClose }: BatchPro
             // Call batch OCR
             const batchResults = await invoke<BatchOcrResult[]>("perform_batch_ocr", {
                 images: base64Images,
-                langs: ocrLang,
-                engine: ocrEngine === "auto" ? null : ocrEngine,
+                options: { langs: ocrLang, engine: ocrEngine === "auto" ? null : ocrEngine },
             });
 
             setResults(batchResults.sort((a, b) => a.index - b.index));
@@ -1,8 +1,9 @@
-import { useEffect } from "react";
+import { useEffect, useState } from "react";
 import { motion } from "framer-motion";
-import { Bone, Dog, Trash2, X } from "lucide-react";
-import { HistoryItem } from "../utils/history";
+import { Bone, Dog, GitMerge, Trash2, X } from "lucide-react";
+import { getConflictsAsync, HistoryItem } from "../utils/history";
 import { soundManager } from "../utils/SoundManager";
+import { applyTemplate } from "../utils/templates";
 import { useTranslation } from "react-i18next";
 
 interface HistoryDrawerProps {
@@ -27,6 +28,15 @@ export const HistoryDrawer = ({
     onCopyItem,
 }: HistoryDrawerProps) => {
     const { t } = useTranslation();
+    const [conflictIds, setConflictIds] = useState<Set<string>>(new Set());
+
+    // Items edited on two devices at once
+    useEffect(() => {
+        if (!isOpen) return;
+        getConflictsAsync()
+            .then(conflicts => setConflictIds(new Set(conflicts.map(c => c.id))))
+            .catch(() => setConflictIds(new Set()));
+    }, [isOpen, historyItems]);
 
     // Close on Escape key
     useEffect(() => {
@@ -43,8 +53,13 @@ export const HistoryDrawer = ({
 
     if (!isOpen) return null;
 
-    const handleCopyItem = (e: React.MouseEvent, text: string) => {
+    const handleCopyItem = async (e: React.MouseEvent, item: HistoryItem) => {
         e.stopPropagation();
+        const text = await applyTemplate(item.text, {
+            lang: item.lang,
+            app: item.sourceApp,
+            timestamp: item.timestamp,
+        });
         navigator.clipboard.writeText(text);
         soundManager.playSuccess();
         onCopyItem(text);
@@ -130,9 +145,17 @@ export const HistoryDrawer = ({
                                             <span className="text-[9px] font-mono opacity-50">
                                                 {new Date(item.timestamp).toLocaleTimeString()}
                                             </span>
+                                            {conflictIds.has(item.id) && (
+                                                <span
+                                                    className="text-[9px] font-black uppercase bg-[#ff6b35] text-[#0a0a0a] px-1 border border-[#0a0a0a] flex items-center gap-0.5"
+                                                    title={t('history.conflict')}
+                                                >
+                                                    <GitMerge size={9} /> {t('history.conflict')}
+                                                </span>
+                                            )}
                                         </div>
                                         <button
-                                            onClick={(e) => handleCopyItem(e, item.text)}
+                                            onClick={(e) => handleCopyItem(e, item)}
                                             className="text-[#0a0a0a] hover:text-[#ff6b35] transition-colors p-1 -mr-1 -mt-1 rounded focus:outline-none focus:ring-1 focus:ring-[#ff6b35]"
                                             title={t('common.copy') || "Copy"}
                                         >
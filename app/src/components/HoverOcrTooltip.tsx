@@ -0,0 +1,33 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface HoverText {
+    text: string;
+    x: number;
+    y: number;
+}
+
+/**
+ * Tooltip window of hover OCR: the line under the mouse cursor. The backend
+ * moves, shows and hides the window.
+ */
+export default function HoverOcrTooltip() {
+    const [text, setText] = useState("");
+
+    useEffect(() => {
+        invoke<HoverText | null>("get_hover_text")
+            .then((latest) => latest && setText(latest.text))
+            .catch(() => { });
+        const unlistenPromise = listen<HoverText>("hover-ocr://text", (event) => setText(event.payload.text));
+        return () => {
+            unlistenPromise.then(unlisten => unlisten());
+        };
+    }, []);
+
+    return (
+        <div className="w-screen h-screen overflow-hidden bg-black/85 text-white px-3 py-2 text-sm select-none">
+            <p className="line-clamp-3 whitespace-pre-wrap">{text}</p>
+        </div>
+    );
+}
@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useTranslation } from 'react-i18next';
+import { errorMessage } from '../utils/errors';
 import { motion, AnimatePresence } from 'framer-motion';
 import { Download, Trash2, Check, Loader2, X, Globe, Search } from 'lucide-react';
 
@@ -51,7 +52,7 @@ export const ModelManager = ({ isOpen, onClose }: ModelManagerProps) => {
             const result = await invoke<ModelInfo[]>('list_ocr_models');
             setModels(result);
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         } finally {
             setLoading(false);
         }
@@ -64,7 +65,7 @@ export const ModelManager = ({ isOpen, onClose }: ModelManagerProps) => {
             await invoke('download_ocr_model', { lang });
             await loadModels(); // Refresh list
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         } finally {
             setDownloadingLang(null);
         }
@@ -77,7 +78,7 @@ export const ModelManager = ({ isOpen, onClose }: ModelManagerProps) => {
             await invoke('delete_ocr_model', { lang });
             await loadModels(); // Refresh list
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         } finally {
             setDeletingLang(null);
         }
@@ -0,0 +1,73 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+
+interface PinState {
+    label: string;
+    image: string;
+    width: number;
+    height: number;
+    zoom: number;
+    opacity: number;
+}
+
+interface PinWindowProps {
+    label: string;
+}
+
+/**
+ * A capture pinned on top of other windows. Drag to move, scroll to zoom,
+ * Ctrl+scroll to change opacity, double-click or Escape to close.
+ */
+export default function PinWindow({ label }: PinWindowProps) {
+    const [pin, setPin] = useState<PinState | null>(null);
+
+    useEffect(() => {
+        invoke<PinState>("get_pin", { label })
+            .then(setPin)
+            .catch((e) => {
+                console.error("Failed to load pin:", e);
+                getCurrentWindow().close();
+            });
+        const unlistenPromise = listen<PinState>("pin://updated", (event) => setPin(event.payload));
+        return () => {
+            unlistenPromise.then(unlisten => unlisten());
+        };
+    }, [label]);
+
+    useEffect(() => {
+        const handleKeyDown = (e: KeyboardEvent) => {
+            if (e.key === "Escape") invoke("close_pin", { label });
+        };
+        window.addEventListener("keydown", handleKeyDown);
+        return () => window.removeEventListener("keydown", handleKeyDown);
+    }, [label]);
+
+    if (!pin) return null;
+
+    const handleWheel = (e: React.WheelEvent) => {
+        const step = e.deltaY < 0 ? 0.1 : -0.1;
+        if (e.ctrlKey) {
+            invoke("set_pin_opacity", { label, opacity: pin.opacity + step });
+        } else {
+            invoke("set_pin_zoom", { label, zoom: pin.zoom + step });
+        }
+    };
+
+    return (
+        <div
+            data-tauri-drag-region
+            className="w-screen h-screen overflow-hidden cursor-move"
+            style={{ opacity: pin.opacity }}
+            onWheel={handleWheel}
+            onDoubleClick={() => invoke("close_pin", { label })}
+        >
+            <img
+                src={`data:image/png;base64,${pin.image}`}
+                className="w-full h-full pointer-events-none select-none"
+                alt=""
+            />
+        </div>
+    );
+}
@@ -0,0 +1,45 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface PushToOcrUpdate {
+    text: string;
+    translation: string | null;
+    error: string | null;
+}
+
+/**
+ * Result window shown while the push-to-OCR hotkey is held: the translation
+ * of the text under the cursor, or the text itself without a translation
+ * pair. The backend closes it on release.
+ */
+export default function PushToOcrWindow() {
+    const [update, setUpdate] = useState<PushToOcrUpdate | null>(null);
+
+    useEffect(() => {
+        invoke<PushToOcrUpdate | null>("get_push_to_ocr_update")
+            .then((latest) => latest && setUpdate(latest))
+            .catch(() => { });
+        const unlistenPromise = listen<PushToOcrUpdate>("push-to-ocr://updated", (event) => setUpdate(event.payload));
+        return () => {
+            unlistenPromise.then(unlisten => unlisten());
+        };
+    }, []);
+
+    return (
+        <div className="w-screen h-screen overflow-auto bg-black/85 text-white p-3 text-sm select-none">
+            {!update ? (
+                <p className="opacity-60">…</p>
+            ) : update.error ? (
+                <p className="text-red-400">{update.error}</p>
+            ) : (
+                <>
+                    <p className="whitespace-pre-wrap text-base">{update.translation ?? update.text}</p>
+                    {update.translation && (
+                        <p className="whitespace-pre-wrap mt-2 opacity-60">{update.text}</p>
+                    )}
+                </>
+            )}
+        </div>
+    );
+}
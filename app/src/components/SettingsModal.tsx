@@ -132,8 +132,8 @@ export const SettingsModal = ({
             setShortcutError(null);
             await setCustomShortcut(newShortcut);
             setIsRecordingShortcut(false);
-        } catch {
-            setShortcutError('Failed to register shortcut. It may be in use.');
+        } catch (err) {
+            setShortcutError(err instanceof Error ? err.message : 'Failed to register shortcut. It may be in use.');
         }
     };
 
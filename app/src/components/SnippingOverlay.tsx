@@ -2,10 +2,12 @@ import { useRef, useState, useEffect } from 'react';
 import { X, Crop, AlertCircle } from 'lucide-react';
 import { motion, AnimatePresence } from 'framer-motion';
 import { useTranslation } from 'react-i18next';
+import type { SelectionRect } from '../utils/session';
 
 interface Props {
     image: string;
-    onCrop: (croppedImage: string) => void;
+    /** The cropped PNG data URL, and the selection in image pixels */
+    onCrop: (croppedImage: string, selection: SelectionRect) => void;
     onClose: () => void;
 }
 
@@ -21,6 +23,8 @@ export function SnippingOverlay({ image, onCrop, onClose }: Props) {
     // Initial load of image
     useEffect(() => {
         const img = new Image();
+        // Captures come from the capture protocol; keep the canvas readable
+        img.crossOrigin = 'anonymous';
         img.src = image;
         img.onload = () => {
             setImgObj(img);
@@ -140,7 +144,12 @@ export function SnippingOverlay({ image, onCrop, onClose }: Props) {
                 const ctx = outputCanvas.getContext('2d');
                 if (ctx) {
                     ctx.drawImage(imgObj, x, y, w, h, 0, 0, w, h);
-                    onCrop(outputCanvas.toDataURL('image/png'));
+                    onCrop(outputCanvas.toDataURL('image/png'), {
+                        x: Math.round(x),
+                        y: Math.round(y),
+                        width: Math.round(w),
+                        height: Math.round(h),
+                    });
                 }
             } else if (w > 2 || h > 2) {
                 setShowToast(true);
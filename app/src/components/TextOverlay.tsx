@@ -0,0 +1,111 @@
+import { useState, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+
+interface BoundingBox {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+interface OcrWord {
+    text: string;
+    bbox: BoundingBox;
+}
+
+interface OcrLine {
+    text: string;
+    bbox: BoundingBox;
+    // Words are in logical order; RTL lines need dir="rtl" for selection
+    words: OcrWord[];
+    textDirection: 'ltr' | 'rtl';
+}
+
+interface OverlayPayload {
+    image: string;
+    result: { text: string; lines: OcrLine[] };
+}
+
+interface TextOverlayProps {
+    label: string;
+}
+
+/**
+ * Frozen screenshot with an invisible, selectable text layer positioned
+ * over the recognized words. Lines are block elements so copied text keeps
+ * its line breaks.
+ */
+export default function TextOverlay({ label }: TextOverlayProps) {
+    const [payload, setPayload] = useState<OverlayPayload | null>(null);
+    const [scale, setScale] = useState(1);
+    const imgRef = useRef<HTMLImageElement>(null);
+
+    useEffect(() => {
+        invoke<OverlayPayload>("get_overlay_payload", { label })
+            .then(setPayload)
+            .catch((e) => {
+                console.error("Failed to load overlay:", e);
+                getCurrentWindow().close();
+            });
+    }, [label]);
+
+    // Close on Escape
+    useEffect(() => {
+        const handleKeyDown = (e: KeyboardEvent) => {
+            if (e.key === "Escape") getCurrentWindow().close();
+        };
+        window.addEventListener("keydown", handleKeyDown);
+        return () => window.removeEventListener("keydown", handleKeyDown);
+    }, []);
+
+    // Image pixels to CSS pixels
+    useEffect(() => {
+        const updateScale = () => {
+            const img = imgRef.current;
+            if (img && img.naturalWidth > 0) setScale(img.clientWidth / img.naturalWidth);
+        };
+        window.addEventListener("resize", updateScale);
+        return () => window.removeEventListener("resize", updateScale);
+    }, []);
+
+    if (!payload) return null;
+
+    const px = (value: number) => `${value * scale}px`;
+
+    return (
+        <div className="relative w-screen h-screen overflow-hidden select-none cursor-text">
+            <img
+                ref={imgRef}
+                src={`data:image/png;base64,${payload.image}`}
+                className="absolute inset-0 w-full h-full pointer-events-none"
+                onLoad={(e) => setScale(e.currentTarget.clientWidth / e.currentTarget.naturalWidth)}
+                alt=""
+            />
+            {payload.result.lines.map((line, i) => (
+                <div
+                    key={i}
+                    dir={line.textDirection}
+                    className="absolute whitespace-pre text-transparent select-text selection:bg-blue-500/40"
+                    style={{ left: px(line.bbox.x), top: px(line.bbox.y), width: px(line.bbox.width), height: px(line.bbox.height) }}
+                >
+                    {line.words.map((word, j) => (
+                        <span
+                            key={j}
+                            className="absolute overflow-hidden leading-none"
+                            style={{
+                                left: px(word.bbox.x - line.bbox.x),
+                                top: px(word.bbox.y - line.bbox.y),
+                                width: px(word.bbox.width),
+                                height: px(word.bbox.height),
+                                fontSize: px(word.bbox.height * 0.9),
+                            }}
+                        >
+                            {j < line.words.length - 1 ? `${word.text} ` : word.text}
+                        </span>
+                    ))}
+                </div>
+            ))}
+        </div>
+    );
+}
@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useTranslation } from 'react-i18next';
+import { errorMessage } from '../utils/errors';
 import { motion, AnimatePresence } from 'framer-motion';
 import { Download, Trash2, Check, Loader2, X, Search, Languages, ArrowRight } from 'lucide-react';
 
@@ -53,7 +54,7 @@ export const TranslationModelManager = ({ isOpen, onClose }: TranslationModelMan
             const result = await invoke<TranslationModelInfo[]>('list_translation_models');
             setModels(result);
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         } finally {
             setLoading(false);
         }
@@ -66,7 +67,7 @@ export const TranslationModelManager = ({ isOpen, onClose }: TranslationModelMan
             await invoke('download_translation_model', { modelName });
             await loadModels(); // Refresh list
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         } finally {
             setDownloadingModel(null);
         }
@@ -79,7 +80,7 @@ export const TranslationModelManager = ({ isOpen, onClose }: TranslationModelMan
             await invoke('delete_translation_model', { modelName });
             await loadModels(); // Refresh list
         } catch (e) {
-            setError(String(e));
+            setError(errorMessage(e));
         } finally {
             setDeletingModel(null);
         }
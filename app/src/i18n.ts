@@ -1,6 +1,7 @@
 import i18n from 'i18next';
 import { initReactI18next } from 'react-i18next';
 import LanguageDetector from 'i18next-browser-languagedetector';
+import { invoke } from '@tauri-apps/api/core';
 
 import en from './locales/en.json';
 import zhTW from './locales/zh-TW.json';
@@ -8,6 +9,11 @@ import zhCN from './locales/zh-CN.json';
 import ja from './locales/ja.json';
 import ko from './locales/ko.json';
 
+// Backend errors, notifications and the tray follow the UI language
+i18n.on('languageChanged', (lng) => {
+    invoke('set_locale', { locale: lng }).catch(() => {});
+});
+
 i18n
     // detect user language
     // learn more: https://github.com/i18next/i18next-browser-languagedetector
@@ -1,11 +1,34 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import TextOverlay from "./components/TextOverlay";
+import PinWindow from "./components/PinWindow";
+import PushToOcrWindow from "./components/PushToOcrWindow";
+import HoverOcrTooltip from "./components/HoverOcrTooltip";
 import "./index.css";
 import "./i18n"; // Initialize i18n
 
+// Copy-text-in-place overlay windows load the same page with ?overlay=<label>,
+// pinned captures with ?pin=<label>, the push-to-OCR result with ?push-to-ocr
+// and the hover OCR tooltip with ?hover-ocr
+const params = new URLSearchParams(window.location.search);
+const overlayLabel = params.get("overlay");
+const pinLabel = params.get("pin");
+const pushToOcr = params.has("push-to-ocr");
+const hoverOcr = params.has("hover-ocr");
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {overlayLabel ? (
+      <TextOverlay label={overlayLabel} />
+    ) : pinLabel ? (
+      <PinWindow label={pinLabel} />
+    ) : pushToOcr ? (
+      <PushToOcrWindow />
+    ) : hoverOcr ? (
+      <HoverOcrTooltip />
+    ) : (
+      <App />
+    )}
   </React.StrictMode>,
 );
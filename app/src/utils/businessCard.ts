@@ -0,0 +1,37 @@
+/**
+ * Business card mode
+ * OCR of a card mapped to name, company, title, phones, emails and address,
+ * and exported as a vCard that the system contacts app can import.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type PhoneKind = 'work' | 'cell' | 'fax';
+
+export interface Contact {
+    name: string | null;
+    company: string | null;
+    /** Job title */
+    title: string | null;
+    phones: { kind: PhoneKind; number: string }[];
+    emails: string[];
+    urls: string[];
+    address: string | null;
+    /** Lines no field claimed */
+    note: string | null;
+}
+
+export interface BusinessCard {
+    text: string;
+    contact: Contact;
+    vcard: string;
+}
+
+export async function performBusinessCardOcr(base64Image: string, langs: string, engine: string): Promise<BusinessCard> {
+    return invoke<BusinessCard>('perform_business_card_ocr', { base64Image, langs, engine });
+}
+
+/** Write the contact as a .vcf file and open it; returns the file path */
+export async function exportVcard(contact: Contact, open = true): Promise<string> {
+    return invoke<string>('export_vcard', { contact, open });
+}
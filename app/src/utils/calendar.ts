@@ -0,0 +1,21 @@
+/**
+ * Calendar events from captured text
+ * `createEventFromText` reads a date, time, title and location out of the
+ * text and writes an .ics file, opened in the system calendar by default.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface CalendarEvent {
+    title: string;
+    /** YYYY-MM-DDTHH:MM, or YYYY-MM-DD when all-day */
+    start: string;
+    end: string;
+    allDay: boolean;
+    location: string | null;
+    icsPath: string | null;
+}
+
+export async function createEventFromText(text: string, open = true): Promise<CalendarEvent> {
+    return invoke<CalendarEvent>('create_event_from_text', { text, open });
+}
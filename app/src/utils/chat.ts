@@ -0,0 +1,17 @@
+/**
+ * Chat forwarding
+ * Posts text, and optionally the capture, to a Slack, Discord or Telegram
+ * target configured in the backend. Hotkey bindings can also forward
+ * automatically through a `forward` post-OCR action.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ChatImage {
+    captureId?: string;
+    base64Image?: string;
+}
+
+export async function forwardToChat(targetId: string, text: string, image: ChatImage = {}): Promise<void> {
+    return invoke('forward_to_chat', { targetId, text, ...image });
+}
@@ -0,0 +1,35 @@
+/**
+ * Notion and OneNote export
+ * Pushes a history item to the Notion database or OneNote section set in the
+ * backend config. API tokens are kept in the OS keychain by the backend.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { HistoryItem } from './history';
+
+export type NotesProvider = 'notion' | 'onenote';
+
+export interface NotesExport {
+    item: HistoryItem;
+    translation?: string;
+    captureId?: string;
+    base64Image?: string;
+}
+
+export interface NotesExportResult {
+    provider: NotesProvider;
+    url: string | null;
+}
+
+/** Store an API token in the keychain; null removes it */
+export async function setNotesToken(provider: NotesProvider, token: string | null): Promise<void> {
+    return invoke('set_notes_token', { provider, token });
+}
+
+export async function hasNotesToken(provider: NotesProvider): Promise<boolean> {
+    return invoke<boolean>('has_notes_token', { provider });
+}
+
+export async function exportToNotes(provider: NotesProvider, exportItem: NotesExport): Promise<NotesExportResult> {
+    return invoke<NotesExportResult>('export_to_notes', { provider, export: exportItem });
+}
@@ -0,0 +1,41 @@
+/**
+ * History collections
+ * Named groups of history items (a project, a book, a game). The backend
+ * keeps them beside the history file so collections made on different
+ * devices merge.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface Collection {
+    id: string;
+    name: string;
+    itemIds: string[];
+}
+
+export async function getCollections(): Promise<Collection[]> {
+    return invoke<Collection[]>('sync_get_collections');
+}
+
+export async function createCollection(name: string): Promise<Collection> {
+    return invoke<Collection>('sync_create_collection', { name });
+}
+
+export async function renameCollection(id: string, name: string): Promise<Collection> {
+    return invoke<Collection>('sync_rename_collection', { id, name });
+}
+
+/**
+ * Delete a collection; its items stay in history
+ */
+export async function deleteCollection(id: string): Promise<void> {
+    return invoke('sync_delete_collection', { id });
+}
+
+export async function addToCollection(collectionId: string, itemIds: string[]): Promise<Collection> {
+    return invoke<Collection>('sync_assign_to_collection', { collectionId, itemIds });
+}
+
+export async function removeFromCollection(collectionId: string, itemIds: string[]): Promise<Collection> {
+    return invoke<Collection>('sync_assign_to_collection', { collectionId, itemIds, remove: true });
+}
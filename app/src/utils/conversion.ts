@@ -0,0 +1,32 @@
+/**
+ * Currency and unit conversion of values in OCR text
+ * Prices go to the configured currency, measurements to the configured unit
+ * system. `ratesSource` says whether the rates were live, cached or the
+ * built-in approximations.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface Conversion {
+    kind: 'currency' | 'unit';
+    text: string;
+    start: number;
+    end: number;
+    value: number;
+    unit: string;
+    converted: number;
+    convertedUnit: string;
+    /** e.g. "≈ 3.11 mi" */
+    display: string;
+}
+
+export interface ConversionResult {
+    conversions: Conversion[];
+    /** The text with each conversion in parentheses after its value */
+    annotated: string;
+    ratesSource: 'live' | 'cached' | 'builtin' | null;
+}
+
+export async function convertValues(text: string): Promise<ConversionResult> {
+    return invoke<ConversionResult>('convert_values', { text });
+}
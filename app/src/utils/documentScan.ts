@@ -0,0 +1,18 @@
+/**
+ * Document scanning
+ * Photos of paper are straightened (page edges found, perspective undone) and
+ * evenly lit before OCR. `image` is the corrected page as a PNG data URL.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface DocumentScan {
+    text: string;
+    image: string;
+    /** Page corners in the photo: top-left, top-right, bottom-right, bottom-left */
+    corners: [number, number][] | null;
+}
+
+export async function scanDocument(base64Image: string, langs: string, engine: string): Promise<DocumentScan> {
+    return invoke<DocumentScan>('scan_document', { base64Image, langs, engine });
+}
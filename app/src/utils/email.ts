@@ -0,0 +1,27 @@
+/**
+ * Email capture results
+ * Mails a history item with its screenshot over the configured SMTP server,
+ * or hands it to the default mail app.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type EmailMethod = 'mailto' | 'smtp';
+
+export interface EmailAttachment {
+    captureId?: string;
+    base64Image?: string;
+}
+
+/**
+ * `text` stands in for the item when history isn't file-based; without a
+ * recipient the configured default is used
+ */
+export async function emailResult(
+    itemId: string,
+    text: string,
+    attachment: EmailAttachment = {},
+    recipient?: string,
+): Promise<EmailMethod> {
+    return invoke<EmailMethod>('email_result', { itemId, recipient, text, ...attachment });
+}
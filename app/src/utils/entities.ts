@@ -0,0 +1,32 @@
+/**
+ * Entities detected in OCR text
+ * URLs, emails, phone numbers, dates and addresses, with UTF-16 offsets into
+ * the text. `openDetectedUrl` opens an entity of the last extraction by index.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export type EntityKind = 'url' | 'email' | 'phone' | 'date' | 'address';
+
+export interface Entity {
+    kind: EntityKind;
+    /** As it appears in the text */
+    text: string;
+    /** Normalized: URL with scheme, phone digits, YYYY-MM-DD date */
+    value: string;
+    start: number;
+    end: number;
+}
+
+export async function extractEntities(text: string): Promise<Entity[]> {
+    return invoke<Entity[]>('extract_entities', { text });
+}
+
+/** Kinds `openDetectedUrl` can open */
+export function isOpenable(entity: Entity): boolean {
+    return entity.kind === 'url' || entity.kind === 'email' || entity.kind === 'phone';
+}
+
+export async function openDetectedUrl(index: number): Promise<void> {
+    return invoke('open_detected_url', { index });
+}
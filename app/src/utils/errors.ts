@@ -0,0 +1,45 @@
+/**
+ * Error helpers for Screen Inu
+ * Rust commands reject with a structured `{ code, message }` object
+ */
+
+export type AppErrorCode =
+    | 'model_missing'
+    | 'tesseract_not_found'
+    | 'permission_denied'
+    | 'capture_failed'
+    | 'ocr_failed'
+    | 'translation_failed'
+    | 'summarization_failed'
+    | 'tts_failed'
+    | 'download_failed'
+    | 'invalid_input'
+    | 'unsupported'
+    | 'cancelled'
+    | 'io_error'
+    | 'unknown';
+
+export interface AppError {
+    code: AppErrorCode;
+    message: string;
+}
+
+/**
+ * Check whether a rejected value is a structured backend error
+ */
+export function isAppError(error: unknown): error is AppError {
+    return typeof error === 'object'
+        && error !== null
+        && 'code' in error
+        && 'message' in error;
+}
+
+/**
+ * Get a displayable message from any rejected value
+ */
+export function errorMessage(error: unknown): string {
+    if (isAppError(error)) {
+        return error.message;
+    }
+    return String(error);
+}
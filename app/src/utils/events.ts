@@ -0,0 +1,62 @@
+/**
+ * Pipeline lifecycle events
+ * The backend emits `<pipeline>://started`, then `<pipeline>://succeeded` or
+ * `<pipeline>://failed` for capture, OCR, translation and model downloads, and
+ * `sync://item-added` for history items it adds. Picks in the tray's language
+ * submenu arrive on `tray://languages-changed`.
+ */
+
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { AppErrorCode } from './errors';
+import type { HistoryItem } from './history';
+
+export type Pipeline = 'capture' | 'ocr' | 'translate' | 'download';
+export type PipelineStage = 'started' | 'succeeded' | 'failed';
+
+export interface PipelineEvent {
+    /** Operation id (or OCR job id) when the caller gave one */
+    opId: string | null;
+    /** Command or background task that ran the stage */
+    source: string;
+    /** Set once finished */
+    durationMs: number | null;
+    errorCode: AppErrorCode | null;
+    error: string | null;
+}
+
+/** Language selection shared with the tray's language submenu */
+export interface ActiveLanguages {
+    /** Tesseract languages joined with '+', e.g. "eng+chi_tra" */
+    ocrLangs: string;
+    /** Translation source; null when detected automatically */
+    sourceLang: string | null;
+    targetLang: string | null;
+}
+
+export const SYNC_ITEM_ADDED_EVENT = 'sync://item-added';
+export const TRAY_LANGUAGES_CHANGED_EVENT = 'tray://languages-changed';
+
+/**
+ * Listen to one stage of a pipeline, e.g. `onPipelineEvent('ocr', 'failed', ...)`
+ */
+export function onPipelineEvent(
+    pipeline: Pipeline,
+    stage: PipelineStage,
+    handler: (event: PipelineEvent) => void,
+): Promise<UnlistenFn> {
+    return listen<PipelineEvent>(`${pipeline}://${stage}`, (event) => handler(event.payload));
+}
+
+/**
+ * Listen for history items added by the backend (e.g. imports)
+ */
+export function onHistoryItemAdded(handler: (item: HistoryItem) => void): Promise<UnlistenFn> {
+    return listen<HistoryItem>(SYNC_ITEM_ADDED_EVENT, (event) => handler(event.payload));
+}
+
+/**
+ * Listen for language picks in the tray menu
+ */
+export function onTrayLanguagesChanged(handler: (languages: ActiveLanguages) => void): Promise<UnlistenFn> {
+    return listen<ActiveLanguages>(TRAY_LANGUAGES_CHANGED_EVENT, (event) => handler(event.payload));
+}
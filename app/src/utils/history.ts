@@ -1,7 +1,9 @@
-import { save, open } from '@tauri-apps/plugin-dialog';
+import { open } from '@tauri-apps/plugin-dialog';
 import { writeTextFile, readTextFile, exists, mkdir } from '@tauri-apps/plugin-fs';
 import { join, appDataDir } from '@tauri-apps/api/path';
+import { invoke } from '@tauri-apps/api/core';
 import { getDataDirectory } from './settings';
+import { saveDialog } from './sandbox';
 
 /**
  * OCR History utility for Screen Inu
@@ -11,12 +13,31 @@ import { getDataDirectory } from './settings';
 const HISTORY_KEY = 'ocr_history';
 const HISTORY_FILE = 'ocr_history.json';
 const MAX_HISTORY_ITEMS = 20;
+const ENCRYPTED_PREFIX = 'SCREENINU-ENC1:';
 
 export interface HistoryItem {
     id: string;
     text: string;
     lang: string;
     timestamp: number;
+    sourceApp?: string;
+    summary?: string; // Short digest of long captures
+    tags?: string[];
+}
+
+export interface CountEntry {
+    key: string;
+    count: number;
+}
+
+export interface HistoryStats {
+    totalCaptures: number;
+    totalCharacters: number;
+    perDay: CountEntry[];
+    languages: CountEntry[];
+    sourceApps: CountEntry[];
+    firstCapture: number | null;
+    lastCapture: number | null;
 }
 
 // ========================================
@@ -49,6 +70,22 @@ async function ensureDataDirectory(): Promise<void> {
     }
 }
 
+/**
+ * Encrypt history content when encryption is enabled in the backend
+ */
+async function encryptIfEnabled(content: string): Promise<string> {
+    const enabled = await invoke<boolean>('sync_encryption_enabled').catch(() => false);
+    return enabled ? invoke<string>('sync_encrypt_history', { content }) : content;
+}
+
+/**
+ * Decrypt history content written with encryption enabled
+ */
+async function decryptIfNeeded(content: string, passphrase?: string): Promise<string> {
+    if (!content.startsWith(ENCRYPTED_PREFIX)) return content;
+    return invoke<string>('sync_decrypt_history', { content, passphrase });
+}
+
 // ========================================
 // Async API (File-based or localStorage)
 // ========================================
@@ -70,7 +107,7 @@ export async function getHistoryAsync(): Promise<HistoryItem[]> {
                 return [];
             }
 
-            const content = await readTextFile(filePath);
+            const content = await decryptIfNeeded(await readTextFile(filePath));
             return JSON.parse(content) as HistoryItem[];
         } else {
             // localStorage fallback
@@ -83,6 +120,15 @@ export async function getHistoryAsync(): Promise<HistoryItem[]> {
     }
 }
 
+/**
+ * Load history before modifying it. Unlike getHistoryAsync, read errors
+ * (e.g. locked encrypted history) propagate so the file is not overwritten.
+ */
+async function loadHistoryForUpdate(): Promise<HistoryItem[]> {
+    const customDir = await getDataDirectory();
+    return customDir ? readFileHistoryStrict() : getHistoryAsync();
+}
+
 /**
  * Save history to storage (internal)
  */
@@ -93,7 +139,8 @@ async function saveHistoryAsync(history: HistoryItem[]): Promise<void> {
         // File-based storage
         await ensureDataDirectory();
         const filePath = await getHistoryFilePath();
-        await writeTextFile(filePath, JSON.stringify(history, null, 2));
+        const content = await encryptIfEnabled(JSON.stringify(history, null, 2));
+        await writeTextFile(filePath, content);
     } else {
         // localStorage fallback
         localStorage.setItem(HISTORY_KEY, JSON.stringify(history));
@@ -103,10 +150,10 @@ async function saveHistoryAsync(history: HistoryItem[]): Promise<void> {
 /**
  * Add a new history item (async version)
  */
-export async function addToHistoryAsync(text: string, lang: string): Promise<void> {
-    if (!text || !text.trim()) return;
+export async function addToHistoryAsync(text: string, lang: string): Promise<string | undefined> {
+    if (!text || !text.trim()) return undefined;
 
-    const history = await getHistoryAsync();
+    const history = await loadHistoryForUpdate();
     const newItem: HistoryItem = {
         id: Date.now().toString(36) + Math.random().toString(36).slice(2),
         text: text.trim(),
@@ -117,6 +164,98 @@ export async function addToHistoryAsync(text: string, lang: string): Promise<voi
     // Add to beginning, limit to max items
     const updated = [newItem, ...history].slice(0, MAX_HISTORY_ITEMS);
     await saveHistoryAsync(updated);
+    // Fire the history webhook, if one is configured
+    invoke('sync_item_added', { item: newItem }).catch(console.error);
+    await syncHistoryFolderAsync().catch(console.error);
+    return newItem.id;
+}
+
+/**
+ * Exchange history with other devices through the shared sync folder, if
+ * one is configured. Returns whether history changed.
+ */
+export async function syncHistoryFolderAsync(): Promise<boolean> {
+    const history = await loadHistoryForUpdate();
+    const merged = await invoke<HistoryItem[] | null>('sync_history_folder', { items: history });
+    if (!merged || JSON.stringify(merged) === JSON.stringify(history)) return false;
+    await saveHistoryAsync(merged.slice(0, MAX_HISTORY_ITEMS));
+    return true;
+}
+
+/**
+ * Summarize a long capture in the background and store the digest with its
+ * history item (no-op unless auto-summarize is enabled in the backend config)
+ */
+export async function summarizeHistoryItemAsync(id: string, text: string): Promise<void> {
+    const summary = await invoke<string | null>('summarize_for_history', { text });
+    if (!summary) return;
+
+    const history = await loadHistoryForUpdate();
+    const updated = history.map(item => (item.id === id ? { ...item, summary } : item));
+    await saveHistoryAsync(updated);
+}
+
+/** A version of an edited item's text; version 0 is the recognized original */
+export interface ItemVersion {
+    version: number;
+    text: string;
+    timestamp: number;
+    device: string | null;
+}
+
+/**
+ * Correct a history item's text. Earlier versions are kept by the backend
+ * and edits from other devices merge in; the stored item gets the merged text.
+ */
+export async function editHistoryItemAsync(id: string, newText: string): Promise<ItemVersion | undefined> {
+    const history = await loadHistoryForUpdate();
+    const item = history.find(i => i.id === id);
+    if (!item) return undefined;
+
+    const version = await invoke<ItemVersion>('sync_edit_item', { id, newText, original: item.text });
+    const updated = history.map(i => (i.id === id ? { ...i, text: version.text } : i));
+    await saveHistoryAsync(updated);
+    return version;
+}
+
+/**
+ * Versions of a history item's text, oldest first; empty if never edited
+ */
+export async function getItemHistoryAsync(id: string): Promise<ItemVersion[]> {
+    return invoke<ItemVersion[]>('sync_get_item_history', { id });
+}
+
+/** An item edited on several devices at once */
+export interface ItemConflict {
+    id: string;
+    devices: string[];
+    timestamp: number;
+}
+
+/**
+ * Items edited concurrently on different devices and not edited since
+ */
+export async function getConflictsAsync(): Promise<ItemConflict[]> {
+    return invoke<ItemConflict[]>('sync_get_conflicts');
+}
+
+/**
+ * Merge several history items (e.g. captures of one document's pages) into
+ * one, keeping the earliest timestamp and every tag
+ */
+export async function mergeHistoryItemsAsync(ids: string[]): Promise<void> {
+    const history = await loadHistoryForUpdate();
+    const updated = await invoke<HistoryItem[]>('sync_merge_items', { items: history, ids });
+    await saveHistoryAsync(updated);
+}
+
+/**
+ * Split a history item at the given positions in its text
+ */
+export async function splitHistoryItemAsync(id: string, offsets: number[]): Promise<void> {
+    const history = await loadHistoryForUpdate();
+    const updated = await invoke<HistoryItem[]>('sync_split_item', { items: history, id, offsets });
+    await saveHistoryAsync(updated);
 }
 
 /**
@@ -136,7 +275,7 @@ export async function clearHistoryAsync(): Promise<void> {
  * Delete a specific history item (async version)
  */
 export async function deleteHistoryItemAsync(id: string): Promise<void> {
-    const history = await getHistoryAsync();
+    const history = await loadHistoryForUpdate();
     const updated = history.filter(item => item.id !== id);
     await saveHistoryAsync(updated);
 }
@@ -208,7 +347,7 @@ export async function exportHistory(): Promise<boolean> {
         const history = await getHistoryAsync();
         if (history.length === 0) return false;
 
-        const filePath = await save({
+        const filePath = await saveDialog({
             filters: [{
                 name: 'JSON',
                 extensions: ['json']
@@ -248,7 +387,7 @@ export async function importHistory(): Promise<boolean> {
             throw new Error('Invalid history format');
         }
 
-        const currentHistory = await getHistoryAsync();
+        const currentHistory = await loadHistoryForUpdate();
 
         // Merge history, avoiding duplicates by ID
         const currentIds = new Set(currentHistory.map(item => item.id));
@@ -268,6 +407,120 @@ export async function importHistory(): Promise<boolean> {
     }
 }
 
+// ========================================
+// Encryption
+// ========================================
+
+/**
+ * Read file-based history, throwing instead of falling back to an empty list
+ * so a locked file is never overwritten
+ */
+async function readFileHistoryStrict(passphrase?: string): Promise<HistoryItem[]> {
+    const filePath = await getHistoryFilePath();
+    if (!(await exists(filePath))) return [];
+    const content = await decryptIfNeeded(await readTextFile(filePath), passphrase);
+    return JSON.parse(content) as HistoryItem[];
+}
+
+/**
+ * Enable encryption at rest for file-based history and re-save it encrypted
+ */
+export async function enableHistoryEncryption(passphrase: string): Promise<void> {
+    if ((await getDataDirectory()) === null) {
+        throw new Error('History in app storage cannot be encrypted. Choose a data directory first.');
+    }
+    const history = await readFileHistoryStrict();
+    await invoke('sync_enable_encryption', { passphrase });
+    await saveHistoryAsync(history);
+}
+
+/**
+ * Disable encryption and re-save history as plain JSON
+ */
+export async function disableHistoryEncryption(): Promise<void> {
+    const fileBased = (await getDataDirectory()) !== null;
+    const history = fileBased ? await readFileHistoryStrict() : [];
+    await invoke('sync_disable_encryption');
+    if (fileBased) await saveHistoryAsync(history);
+}
+
+/**
+ * Unlock encrypted history with its passphrase (e.g. on a new machine)
+ */
+export async function unlockHistory(passphrase: string): Promise<HistoryItem[]> {
+    return readFileHistoryStrict(passphrase);
+}
+
+// ========================================
+// Backup/Restore (file-based history only)
+// ========================================
+
+/**
+ * Back up the history file to a user-selected location
+ */
+export async function backupHistory(): Promise<boolean> {
+    const filePath = await saveDialog({
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+        defaultPath: 'screen_inu_history_backup.json'
+    });
+    if (!filePath) return false;
+
+    await invoke('sync_backup', { path: filePath });
+    return true;
+}
+
+/**
+ * Restore the history file from a backup
+ * @returns Number of restored items, or null if cancelled
+ */
+export async function restoreHistory(): Promise<number | null> {
+    const filePath = await open({
+        multiple: false,
+        filters: [{ name: 'JSON', extensions: ['json'] }]
+    });
+    if (!filePath || Array.isArray(filePath)) return null;
+
+    return invoke<number>('sync_restore', { path: filePath });
+}
+
+// ========================================
+// Statistics
+// ========================================
+
+/**
+ * Get history statistics (per-day counts, languages, source apps) for charts
+ */
+export async function getHistoryStats(): Promise<HistoryStats> {
+    const items = await getHistoryAsync();
+    return invoke<HistoryStats>('sync_get_stats', {
+        items,
+        tzOffsetMinutes: -new Date().getTimezoneOffset(),
+    });
+}
+
+/**
+ * Supported external history sources
+ */
+export type ExternalHistoryFormat = 'textShot' | 'powerToys' | 'textFolder' | 'csv';
+
+/**
+ * Import history from another OCR tool, skipping duplicates
+ * @returns Number of imported items
+ */
+export async function importExternalHistory(format: ExternalHistoryFormat, path: string): Promise<number> {
+    const currentHistory = await loadHistoryForUpdate();
+    const newItems = await invoke<HistoryItem[]>('sync_import_external', {
+        format,
+        path,
+        existing: currentHistory,
+    });
+    if (newItems.length === 0) return 0;
+
+    const updated = [...newItems, ...currentHistory].slice(0, MAX_HISTORY_ITEMS);
+    await saveHistoryAsync(updated);
+    return newItems.length;
+}
+
 // ========================================
 // Migration Utilities
 // ========================================
@@ -293,6 +546,11 @@ export async function migrateToFileStorage(): Promise<boolean> {
  */
 export async function migrateToLocalStorage(): Promise<boolean> {
     try {
+        // localStorage can't hold encrypted history
+        if (await invoke<boolean>('sync_encryption_enabled').catch(() => false)) {
+            console.error('Disable history encryption before moving history to app storage');
+            return false;
+        }
         const fileHistory = await getHistoryAsync();
         localStorage.setItem(HISTORY_KEY, JSON.stringify(fileHistory));
         return true;
@@ -0,0 +1,63 @@
+/**
+ * Phone companion
+ * Images shared to the app from other apps, and OCR by touch: a dragged
+ * region or a tap, in coordinates relative to the displayed image (0-1).
+ * On iOS and Android recognition runs on Vision / ML Kit; on desktop the
+ * regular engines are used.
+ */
+
+import { addPluginListener, invoke, PluginListener } from '@tauri-apps/api/core';
+
+export interface SharedImage {
+    captureId: string; // Served on the capture:// scheme
+    mimeType: string;
+}
+
+export interface RelativeRect {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+export interface OcrLine {
+    text: string;
+    confidence: number;
+    bbox: { x: number; y: number; width: number; height: number };
+}
+
+export interface OcrResult {
+    text: string;
+    confidence: number;
+    lines: OcrLine[];
+    engine: string;
+}
+
+/**
+ * Images shared to the app since the last call
+ */
+export async function takeSharedImages(): Promise<SharedImage[]> {
+    return invoke<SharedImage[]>('mobile_take_shared_images');
+}
+
+/**
+ * Call `handler` when images are shared to the running app (Android)
+ */
+export async function onImagesShared(handler: () => void): Promise<PluginListener> {
+    return addPluginListener('mobile-ocr', 'shared', handler);
+}
+
+export async function ocrCapture(captureId: string, lang: string): Promise<OcrResult> {
+    return invoke<OcrResult>('mobile_ocr_capture', { captureId, lang });
+}
+
+export async function ocrRegion(captureId: string, rect: RelativeRect, lang: string): Promise<OcrResult> {
+    return invoke<OcrResult>('mobile_ocr_region', { captureId, rect, lang });
+}
+
+/**
+ * The line of text under a tap at (x, y), or null
+ */
+export async function textAt(captureId: string, x: number, y: number, lang: string): Promise<OcrLine | null> {
+    return invoke<OcrLine | null>('mobile_text_at', { captureId, x, y, lang });
+}
@@ -0,0 +1,21 @@
+/**
+ * Send to notes
+ * Appends a capture (text, translation and screenshot) to a note in the
+ * Obsidian or Logseq vault set in the backend config.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface NoteEntry {
+    text: string;
+    translation?: string;
+    lang?: string;
+    sourceApp?: string;
+    captureId?: string;
+    base64Image?: string;
+}
+
+/** Returns the path of the note appended to */
+export async function appendToNote(entry: NoteEntry, vaultPath?: string, noteTemplate?: string): Promise<string> {
+    return invoke<string>('append_to_note', { entry, vaultPath, noteTemplate });
+}
@@ -0,0 +1,30 @@
+/**
+ * Cancellable backend operations
+ * Long-running commands (OCR, offline translation, model downloads) accept an
+ * `opId`; passing the same id to `cancelOperation` stops them, and they reject
+ * with the `cancelled` error code.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { isAppError } from './errors';
+
+/**
+ * Create a unique operation id
+ */
+export function newOperationId(): string {
+    return Date.now().toString(36) + Math.random().toString(36).slice(2);
+}
+
+/**
+ * Cancel a running operation. Resolves false if it already finished.
+ */
+export async function cancelOperation(opId: string): Promise<boolean> {
+    return invoke<boolean>('cancel_operation', { opId });
+}
+
+/**
+ * Check whether a rejected value comes from a cancelled operation
+ */
+export function isCancelled(error: unknown): boolean {
+    return isAppError(error) && error.code === 'cancelled';
+}
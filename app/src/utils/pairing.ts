@@ -0,0 +1,43 @@
+/**
+ * Device pairing
+ * One device shows a QR code, the other scans it (or pastes its text) and
+ * both keep each other as paired peers for LAN sync. A code works once and
+ * expires after five minutes; `sync://paired` fires on the device showing it.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export const PAIRED_EVENT = 'sync://paired';
+
+export interface PairingCode {
+    code: string;
+    svg: string; // QR code image
+    expiresAt: number;
+}
+
+export interface PairedPeer {
+    device: string;
+    name: string;
+    host: string;
+    pairedAt: number;
+}
+
+export async function generatePairingCode(): Promise<PairingCode> {
+    return invoke<PairingCode>('sync_generate_pairing_code');
+}
+
+export async function pairWithCode(code: string): Promise<PairedPeer> {
+    return invoke<PairedPeer>('sync_pair_with_code', { code });
+}
+
+export async function getPairedPeers(): Promise<PairedPeer[]> {
+    return invoke<PairedPeer[]>('get_paired_peers');
+}
+
+export async function setDeviceName(name: string | null): Promise<void> {
+    return invoke('set_device_name', { name });
+}
+
+export async function unpairDevice(device: string): Promise<void> {
+    return invoke('sync_unpair', { device });
+}
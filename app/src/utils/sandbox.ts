@@ -0,0 +1,44 @@
+/**
+ * Flatpak / Snap sandbox
+ * Inside a sandbox the dialog plugin's GTK file chooser only sees the
+ * sandbox, so "Save as" goes through the FileChooser portal, which hands
+ * back a path the app may write.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { save, SaveDialogOptions } from '@tauri-apps/plugin-dialog';
+
+export interface SandboxInfo {
+    kind: 'flatpak' | 'snap' | null;
+    appId: string | null; // Flatpak app id or snap name
+}
+
+let sandboxInfo: Promise<SandboxInfo> | null = null;
+
+/**
+ * Which sandbox the app runs in (cached)
+ */
+export function getSandboxInfo(): Promise<SandboxInfo> {
+    if (!sandboxInfo) {
+        sandboxInfo = invoke<SandboxInfo>('get_sandbox_info')
+            .catch(() => ({ kind: null, appId: null }));
+    }
+    return sandboxInfo;
+}
+
+/**
+ * Ask where to save, through the portal when sandboxed
+ * @returns The chosen path, or null if cancelled
+ */
+export async function saveDialog(options: SaveDialogOptions = {}): Promise<string | null> {
+    const { kind } = await getSandboxInfo();
+    if (!kind) return save(options);
+
+    // The portal suggests a file name; folders stay the user's choice
+    const defaultName = options.defaultPath?.split(/[\\/]/).pop();
+    return invoke<string | null>('portal_save_file', {
+        title: options.title,
+        defaultName,
+        filters: options.filters,
+    });
+}
@@ -0,0 +1,35 @@
+/**
+ * Save targets
+ * Saves a capture and its text to the local folder and the SFTP/SMB targets
+ * configured in the backend. Failed network saves are queued and retried.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface SaveReport {
+    localPath: string | null;
+    saved: string[];
+    queued: string[];
+}
+
+export interface QueuedSave {
+    id: string;
+    targetId: string;
+    files: string[];
+    queuedAt: number;
+    attempts: number;
+    lastError: string;
+}
+
+export async function saveCapture(image: { captureId?: string; base64Image?: string }, text?: string): Promise<SaveReport> {
+    return invoke<SaveReport>('save_capture', { ...image, text });
+}
+
+export async function getSaveQueue(): Promise<QueuedSave[]> {
+    return invoke<QueuedSave[]>('get_save_queue');
+}
+
+/** Returns how many saves are still waiting */
+export async function retrySaveQueue(): Promise<number> {
+    return invoke<number>('retry_save_queue');
+}
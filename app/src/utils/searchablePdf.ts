@@ -0,0 +1,23 @@
+/**
+ * Searchable PDF export
+ * Captures become PDF pages with the OCR text as an invisible layer over the
+ * image, so the PDF can be searched and copied from.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { saveDialog } from './sandbox';
+
+export interface PdfPage {
+    captureId?: string;
+    base64Image?: string;
+}
+
+export async function exportSearchablePdf(pages: PdfPage[], path: string, langs: string, engine: string): Promise<string> {
+    return invoke<string>('export_searchable_pdf', { pages, path, langs, engine });
+}
+
+/** Ask where to save, then export; null when the dialog was cancelled */
+export async function saveSearchablePdf(pages: PdfPage[], langs: string, engine: string): Promise<string | null> {
+    const path = await saveDialog({ defaultPath: 'capture.pdf', filters: [{ name: 'PDF', extensions: ['pdf'] }] });
+    return path ? exportSearchablePdf(pages, path, langs, engine) : null;
+}
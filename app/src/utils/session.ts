@@ -0,0 +1,43 @@
+/**
+ * Session restore
+ * The capture being worked on, its selection, OCR result and translation are
+ * saved by the backend as they change, and brought back after a crash or
+ * restart. Saving an empty state forgets the session.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface SelectionRect {
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+export interface SessionState {
+    /** Capture protocol id of the capture being worked on */
+    captureId: string | null;
+    selection: SelectionRect | null;
+    ocrText: string;
+    translation: string | null;
+}
+
+export interface RestoredSession {
+    /** The capture, served over the capture protocol again */
+    capture: { id: string; mimeType: string; width: number; height: number } | null;
+    selection: SelectionRect | null;
+    ocrText: string;
+    translation: string | null;
+    savedAt: string;
+}
+
+export async function saveSession(state: SessionState): Promise<void> {
+    return invoke('save_session', { state });
+}
+
+/**
+ * The session saved before the last exit, if any
+ */
+export async function restoreLastSession(): Promise<RestoredSession | null> {
+    return invoke<RestoredSession | null>('restore_last_session');
+}
@@ -1,7 +1,17 @@
+import { invoke } from '@tauri-apps/api/core';
 import { load, Store } from '@tauri-apps/plugin-store';
 
 const SETTINGS_FILE = 'settings.json';
 
+/**
+ * Portable mode state (a `portable.flag` next to the executable)
+ */
+export interface PortableInfo {
+    portable: boolean;
+    dataDir: string | null;  // data folder beside the executable
+    settingsPath: string;
+}
+
 export interface AppSettings {
     dataDirectory: string | null;  // null = use localStorage (default)
     translationEngine: 'online' | 'offline'; // default: 'online'
@@ -11,17 +21,32 @@ export interface AppSettings {
 type StoreDefaults = Record<keyof AppSettings, unknown>;
 
 let storeInstance: Store | null = null;
+let portableInfo: Promise<PortableInfo> | null = null;
 const DEFAULT_SETTINGS: StoreDefaults = {
     dataDirectory: null,
     translationEngine: 'online'
 };
 
+/**
+ * Get portable mode state, queried once per session
+ */
+export function getPortableInfo(): Promise<PortableInfo> {
+    if (!portableInfo) {
+        portableInfo = invoke<PortableInfo>('get_portable_info').catch((error) => {
+            console.error('Failed to get portable mode state:', error);
+            return { portable: false, dataDir: null, settingsPath: SETTINGS_FILE };
+        });
+    }
+    return portableInfo;
+}
+
 /**
  * Get or create the store instance
  */
 async function getStore(): Promise<Store> {
     if (!storeInstance) {
-        storeInstance = await load(SETTINGS_FILE, { 
+        const { settingsPath } = await getPortableInfo();
+        storeInstance = await load(settingsPath, { 
             autoSave: true, 
             defaults: DEFAULT_SETTINGS
         });
@@ -37,8 +62,10 @@ export async function getSettings(): Promise<AppSettings> {
         const store = await getStore();
         const dataDirectory = await store.get<string | null>('dataDirectory');
         const translationEngine = await store.get<'online' | 'offline'>('translationEngine');
+        // Portable installs keep history in files beside the executable
+        const { dataDir: portableDir } = await getPortableInfo();
         return {
-            dataDirectory: dataDirectory ?? null,
+            dataDirectory: dataDirectory ?? portableDir,
             translationEngine: translationEngine ?? 'online',
         };
     } catch (error) {
@@ -0,0 +1,53 @@
+/**
+ * Backend shortcut bindings
+ * Bindings can be chords ("Ctrl+Shift+S R") or double presses of a modifier
+ * ("Shift Shift"). The backend emits `shortcuts://triggered` with the binding
+ * that fired, and reports bindings it couldn't register as conflicts.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface ShortcutBinding {
+    action: string;
+    trigger: string;
+}
+
+export interface ShortcutConflict {
+    action: string;
+    trigger: string;
+    kind: 'invalid' | 'app' | 'system' | 'unsupported';
+    /** The other action, app feature or system shortcut, or the error */
+    with: string;
+}
+
+/**
+ * Conflicts `trigger` would have as the shortcut of `action`
+ */
+export async function checkShortcut(action: string, trigger: string): Promise<ShortcutConflict[]> {
+    return invoke<ShortcutConflict[]>('check_shortcut', { action, trigger });
+}
+
+/**
+ * Human-readable reason for a conflict
+ */
+export function describeConflict(conflict: ShortcutConflict): string {
+    switch (conflict.kind) {
+        case 'invalid':
+            return conflict.with;
+        case 'app':
+            return `${conflict.trigger} is already used by ${conflict.with}`;
+        case 'system':
+            return `${conflict.trigger} is a system shortcut (${conflict.with})`;
+        case 'unsupported':
+            return `${conflict.trigger} isn't supported here: ${conflict.with}`;
+    }
+}
+
+export function onShortcutTriggered(handler: (binding: ShortcutBinding) => void): Promise<UnlistenFn> {
+    return listen<ShortcutBinding>('shortcuts://triggered', (event) => handler(event.payload));
+}
+
+export function onShortcutConflicts(handler: (conflicts: ShortcutConflict[]) => void): Promise<UnlistenFn> {
+    return listen<ShortcutConflict[]>('shortcuts://conflicts', (event) => handler(event.payload));
+}
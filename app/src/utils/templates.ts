@@ -0,0 +1,51 @@
+/**
+ * Output templates
+ * Copied and exported results go through the active template, e.g.
+ * "{text}\n\n— captured {date} from {app}". Placeholders: text, translation,
+ * lang, app, date, time; `{text|quote}` and `{text|json}` filters.
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+export interface OutputTemplate {
+    id: string;
+    name: string;
+    template: string;
+    builtin: boolean;
+}
+
+export interface TemplateContext {
+    translation?: string;
+    lang?: string;
+    app?: string;
+    /** Unix epoch milliseconds of the capture */
+    timestamp?: number;
+}
+
+export async function listTemplates(): Promise<{ active: string | null; templates: OutputTemplate[] }> {
+    return invoke('list_templates');
+}
+
+export async function setActiveTemplate(id: string | null): Promise<void> {
+    return invoke('set_active_template', { id });
+}
+
+export async function saveTemplate(template: Omit<OutputTemplate, 'builtin'>): Promise<void> {
+    return invoke('save_template', { template });
+}
+
+export async function deleteTemplate(id: string): Promise<void> {
+    return invoke('delete_template', { id });
+}
+
+/**
+ * `text` as it should be copied; the text as is if the template fails
+ */
+export async function applyTemplate(text: string, context: TemplateContext = {}): Promise<string> {
+    try {
+        return await invoke<string>('apply_template', { text, context });
+    } catch (e) {
+        console.error('Output template failed:', e);
+        return text;
+    }
+}
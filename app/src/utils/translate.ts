@@ -11,12 +11,26 @@ export interface TranslateOptions {
     targetLang: string; // 'zh', 'en', 'ja', etc.
     apiUrl?: string; // Custom LibreTranslate server URL (online mode only)
     offlineMode?: boolean; // Use offline translation if available
+    opId?: string; // Lets an offline translation be cancelled with cancelOperation
+    align?: boolean; // Offline only: translate sentence by sentence and return `segments`
+}
+
+export interface AlignedSegment {
+    source: string;
+    translation: string;
+    // Character (code point) offsets into the source and translated text
+    sourceStart: number;
+    sourceEnd: number;
+    targetStart: number;
+    targetEnd: number;
 }
 
 export interface TranslateResult {
     translatedText: string;
     detectedSourceLang?: string;
     mode?: 'online' | 'offline';
+    // Sentence alignment (offline mode with `align` only)
+    segments?: AlignedSegment[];
 }
 
 export interface TranslationModelInfo {
@@ -53,17 +67,26 @@ const LANG_MAP: Record<string, string> = {
 /**
  * Translate text using offline ONNX model
  */
-async function translateOffline(text: string, sourceLang: string, targetLang: string): Promise<TranslateResult> {
+async function translateOffline(
+    text: string,
+    sourceLang: string,
+    targetLang: string,
+    opId?: string,
+    align?: boolean,
+): Promise<TranslateResult> {
     try {
-        const translatedText = await invoke<string>('translate_offline', {
+        const result = await invoke<{ text: string; segments: AlignedSegment[] }>('translate_offline', {
             text,
             sourceLang: LANG_MAP[sourceLang] || sourceLang,
             targetLang: LANG_MAP[targetLang] || targetLang,
+            opId,
+            align,
         });
 
         return {
-            translatedText,
+            translatedText: result.text,
             mode: 'offline',
+            segments: result.segments,
         };
     } catch (error) {
         console.error('Offline translation failed:', error);
@@ -71,6 +94,38 @@ async function translateOffline(text: string, sourceLang: string, targetLang: st
     }
 }
 
+/**
+ * Post-edit a machine translation with the configured LLM (opt-in in settings).
+ * `onToken` receives the refined text as it streams in.
+ */
+export async function refineTranslation(
+    text: string,
+    translation: string,
+    onToken?: (token: string) => void,
+    provider?: string,
+    targetLang?: string,
+): Promise<string> {
+    const { listen } = await import('@tauri-apps/api/event');
+    const requestId = Date.now().toString(36) + Math.random().toString(36).slice(2);
+    const unlisten = await listen<{ requestId: string | null; token: string }>('llm://token', (event) => {
+        if (event.payload.requestId === requestId) onToken?.(event.payload.token);
+    });
+    try {
+        return await invoke<string>('refine_translation', { text, translation, provider, targetLang, requestId });
+    } finally {
+        unlisten();
+    }
+}
+
+/** Store an LLM provider's API key in the keychain; null removes it */
+export async function setLlmApiKey(providerId: string, apiKey: string | null): Promise<void> {
+    return invoke('set_llm_api_key', { providerId, apiKey });
+}
+
+export async function hasLlmApiKey(providerId: string): Promise<boolean> {
+    return invoke<boolean>('has_llm_api_key', { providerId });
+}
+
 /**
  * Translate text using LibreTranslate API (online)
  */
@@ -115,6 +170,8 @@ export async function translateText(options: TranslateOptions): Promise<Translat
         targetLang,
         apiUrl = DEFAULT_API_URL,
         offlineMode = false,
+        opId,
+        align,
     } = options;
 
     if (!text.trim()) {
@@ -124,7 +181,7 @@ export async function translateText(options: TranslateOptions): Promise<Translat
     // Try offline first if requested
     if (offlineMode) {
         try {
-            return await translateOffline(text, sourceLang, targetLang);
+            return await translateOffline(text, sourceLang, targetLang, opId, align);
         } catch (error) {
             console.warn('Offline translation failed, falling back to online:', error);
             // Fall through to online